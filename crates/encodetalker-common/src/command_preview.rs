@@ -1,37 +1,119 @@
-use crate::{AudioMode, EncoderType, EncodingConfig, VideoContentType};
+use crate::{
+    source_audio_input_args, source_input_args, AudioMode, DeinterlaceConfig, DeinterlaceMode,
+    EncoderType, EncodingConfig, VideoMode, EVEN_CROP_FILTER, VAAPI_UPLOAD_FILTER,
+};
 use std::fmt::Write as _;
 use std::path::Path;
 
 /// Générer une preview de la commande ffmpeg demux (TOUS les paramètres)
 #[must_use]
-pub fn build_ffmpeg_demux_preview(input: &Path, is_interlaced: Option<bool>) -> String {
-    let mut cmd = format!("ffmpeg -nostats -loglevel error -i {}", input.display());
+pub fn build_ffmpeg_demux_preview(
+    input: &Path,
+    is_interlaced: Option<bool>,
+    input_args: &[String],
+    deinterlace: DeinterlaceConfig,
+    crop_to_even: bool,
+) -> String {
+    let mut cmd = "ffmpeg -nostats -loglevel error".to_string();
+    for arg in input_args {
+        let _ = write!(cmd, " {arg}");
+    }
+    let _ = write!(cmd, " -i {}", input.display());
+    cmd.push_str(&preview_filters(
+        is_interlaced,
+        deinterlace,
+        crop_to_even,
+        None,
+    ));
+
+    cmd.push_str(" -f yuv4mpegpipe -pix_fmt yuv420p10le -strict -1 -");
+    cmd
+}
 
-    // Ajouter filtre yadif si interlacé
+/// Option `-vf` du décodage : désentrelacement si la source est entrelacée (ou forcé),
+/// suivi du recadrage aux dimensions paires puis de `extra`
+fn preview_filters(
+    is_interlaced: Option<bool>,
+    deinterlace: DeinterlaceConfig,
+    crop_to_even: bool,
+    extra: Option<&str>,
+) -> String {
+    let filter = deinterlace.filter_arg();
+    let rest: Vec<&str> = [crop_to_even.then_some(EVEN_CROP_FILTER), extra]
+        .into_iter()
+        .flatten()
+        .collect();
+    let rest = rest.join(",");
+    let sep = if rest.is_empty() { "" } else { "," };
     match is_interlaced {
-        Some(true) => cmd.push_str(" -vf yadif"),
-        Some(false) => {}
-        None => cmd.push_str(" [-vf yadif?]"), // Détection en cours
+        Some(detected) if deinterlace.applies(detected) => format!(" -vf {filter}{sep}{rest}"),
+        None if deinterlace.mode == DeinterlaceMode::Force => format!(" -vf {filter}{sep}{rest}"),
+        // Détection en cours
+        None if deinterlace.mode == DeinterlaceMode::Auto => {
+            if rest.is_empty() {
+                format!(" [-vf {filter}?]")
+            } else {
+                format!(" -vf [{filter},?]{rest}")
+            }
+        }
+        _ if !rest.is_empty() => format!(" -vf {rest}"),
+        _ => String::new(),
     }
+}
 
-    cmd.push_str(" -f yuv4mpegpipe -pix_fmt yuv420p10le -strict -1 -");
+/// Générer la preview d'un encodage matériel : décodage et encodage dans un seul ffmpeg
+///
+/// `vaapi_device` est le périphérique configuré, utilisé par les encodages VA-API.
+#[must_use]
+pub fn build_hardware_encode_preview(
+    input: &Path,
+    is_interlaced: Option<bool>,
+    config: &EncodingConfig,
+    vaapi_device: &Path,
+    output_ivf: &str,
+) -> String {
+    let mut cmd = "ffmpeg -nostats -loglevel error -progress pipe:2".to_string();
+    if config.encoder == EncoderType::Vaapi {
+        let _ = write!(cmd, " -vaapi_device {}", vaapi_device.display());
+    }
+    for arg in source_input_args(input, config) {
+        let _ = write!(cmd, " {arg}");
+    }
+    let _ = write!(cmd, " -i {} -an -sn -dn", input.display());
+    let upload = (config.encoder == EncoderType::Vaapi).then_some(VAAPI_UPLOAD_FILTER);
+    cmd.push_str(&preview_filters(
+        is_interlaced,
+        config.deinterlace,
+        config.crop_to_even,
+        upload,
+    ));
+    for arg in config.hardware_video_args(None) {
+        let _ = write!(cmd, " {arg}");
+    }
+    let _ = write!(cmd, " -f ivf -y {output_ivf}");
     cmd
 }
 
-/// Générer preview de la commande encodeur
+/// Générer preview de la commande encodeur (encodeurs matériels :
+/// [`build_hardware_encode_preview`], sans pipe)
 #[must_use]
 pub fn build_encoder_preview(config: &EncodingConfig, output_ivf: &str) -> String {
     match config.encoder {
         EncoderType::SvtAv1 => build_svt_av1_preview(config, output_ivf),
         EncoderType::Aom => build_aom_preview(config, output_ivf),
+        EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => {
+            config.hardware_video_args(None).join(" ")
+        }
     }
 }
 
 /// Générer preview de la commande SVT-AV1
 fn build_svt_av1_preview(config: &EncodingConfig, output: &str) -> String {
+    let params = &config.encoder_params;
     let mut cmd = format!(
-        "SvtAv1EncApp -i stdin --crf {} --preset {}",
-        config.encoder_params.crf, config.encoder_params.preset
+        "SvtAv1EncApp -i stdin {} --preset {}",
+        params.encoding_mode.svt_av1_args(params.crf).join(" "),
+        params.preset
     );
 
     if let Some(threads) = config.encoder_params.threads {
@@ -39,29 +121,27 @@ fn build_svt_av1_preview(config: &EncodingConfig, output: &str) -> String {
     }
 
     cmd.push_str(" --progress 2");
+    let _ = write!(
+        cmd,
+        " --tune {}",
+        config
+            .encoder_params
+            .tune
+            .arg_or_default(EncoderType::SvtAv1)
+    );
 
     // Paramètres spécifiques au type de contenu
-    match config.encoder_params.content_type {
-        VideoContentType::GrainedFilm => {
-            let _ = write!(
-                cmd,
-                " --enable-cdef 0 --enable-restoration 0 --enable-tf 0 --spy-rd 1 \
-                 --noise-norm-strength 3 --qm-min 10 --tune 0 --qp-scale-compress-strength 3 \
-                 --scm 0 --psy-rd 4.0 --hbd-mds 1"
-            );
-        }
-        VideoContentType::Anime => {
-            let _ = write!(cmd, " --qm-min 8 --noise-norm-strength 4 --enable-dlf 2");
-        }
-        _ => {
-            let _ = write!(cmd, " --qm-min 8 --noise-norm-strength 1 --enable-dlf 2");
-        }
+    for (option, value) in config.encoder_params.content_type.svt_av1_args() {
+        let _ = write!(cmd, " {option} {value}");
     }
 
-    // Extra params
+    // Extra params, puis keyframes (intervalle en secondes, converti au probe)
     for param in &config.encoder_params.extra_params {
         let _ = write!(cmd, " {param}");
     }
+    for arg in config.keyframes.encoder_args(EncoderType::SvtAv1, None) {
+        let _ = write!(cmd, " {arg}");
+    }
 
     let _ = write!(cmd, " -b {output}");
     cmd
@@ -69,29 +149,49 @@ fn build_svt_av1_preview(config: &EncodingConfig, output: &str) -> String {
 
 /// Générer preview de la commande aomenc
 fn build_aom_preview(config: &EncodingConfig, output: &str) -> String {
+    let params = &config.encoder_params;
     let mut cmd = format!(
-        "aomenc --cq-level={} --cpu-used={} --end-usage=q --passes=2",
-        config.encoder_params.crf, config.encoder_params.preset
+        "aomenc {} --cpu-used={} --passes=2",
+        params.encoding_mode.aom_args(params.crf).join(" "),
+        params.preset
     );
 
     if let Some(threads) = config.encoder_params.threads {
         let _ = write!(cmd, " --threads={threads}");
     }
 
-    // Extra params
+    if let Some(tune) = config.encoder_params.aom_tune_arg() {
+        let _ = write!(cmd, " {tune}");
+    }
+    for arg in config.encoder_params.aom.args() {
+        let _ = write!(cmd, " {arg}");
+    }
+
+    // Extra params, puis keyframes
     for param in &config.encoder_params.extra_params {
         let _ = write!(cmd, " {param}");
     }
+    for arg in config.keyframes.encoder_args(EncoderType::Aom, None) {
+        let _ = write!(cmd, " {arg}");
+    }
 
     let _ = write!(cmd, " --ivf -o {output} -");
     cmd
 }
 
-/// Générer preview de la commande d'encodage audio
+/// Générer preview de la commande d'encodage audio (None sans audio)
 #[must_use]
-pub fn build_audio_preview(input: &Path, config: &EncodingConfig, output_audio: &str) -> String {
-    let input_display = input.display();
-    match &config.audio_mode {
+pub fn build_audio_preview(
+    input: &Path,
+    config: &EncodingConfig,
+    output_audio: &str,
+) -> Option<String> {
+    let trim: String = source_audio_input_args(input, config)
+        .iter()
+        .map(|arg| format!("{arg} "))
+        .collect();
+    let input_display = format!("{trim}{}", input.display());
+    let cmd = match &config.audio_mode {
         AudioMode::Opus { bitrate } => {
             format!("ffmpeg -i {input_display} -vn -c:a libopus -b:a {bitrate}k -map 0:a {output_audio}")
         }
@@ -101,43 +201,79 @@ pub fn build_audio_preview(input: &Path, config: &EncodingConfig, output_audio:
         AudioMode::Custom { codec, bitrate } => {
             format!("ffmpeg -i {input_display} -vn -c:a {codec} -b:a {bitrate}k {output_audio}")
         }
-    }
+        AudioMode::None => return None,
+    };
+    Some(cmd)
 }
 
-/// Générer preview du muxing final
+/// Générer preview du muxing final (sans fichier audio : vidéo seule)
 #[must_use]
-pub fn build_muxing_preview(video_ivf: &str, audio_file: &str, output: &Path) -> String {
-    format!(
-        "ffmpeg -y -i {video_ivf} -i {audio_file} -map 0:v:0 -map 1:a:0 -c:v copy -c:a copy {}",
-        output.display()
-    )
+pub fn build_muxing_preview(video_ivf: &str, audio_file: Option<&str>, output: &Path) -> String {
+    match audio_file {
+        Some(audio_file) => format!(
+            "ffmpeg -y -i {video_ivf} -i {audio_file} -map 0:v:0 -map 1:a -c:v copy -c:a copy {}",
+            output.display()
+        ),
+        None => format!(
+            "ffmpeg -y -i {video_ivf} -map 0:v:0 -c:v copy {}",
+            output.display()
+        ),
+    }
 }
 
 /// Générer une preview multi-lignes complète du pipeline
+///
+/// `vaapi_device` est le périphérique VA-API configuré (`encoder.hardware.vaapi_device`).
 #[must_use]
 pub fn build_full_pipeline_preview(
     input: &Path,
     output: &Path,
     config: &EncodingConfig,
     is_interlaced: Option<bool>,
+    vaapi_device: &Path,
 ) -> Vec<String> {
     let mut lines = Vec::new();
 
-    // Étape 1: Demux + Encode (combinés avec pipe)
-    let demux_cmd = build_ffmpeg_demux_preview(input, is_interlaced);
-    let encoder_cmd = build_encoder_preview(config, "video.ivf");
-    lines.push(format!("{demux_cmd} | {encoder_cmd}"));
+    // Étape 1: Demux + Encode (combinés avec pipe), sauf si la vidéo est copiée
+    let video_file = match config.video_mode {
+        VideoMode::Encode if config.encoder.is_hardware() => {
+            lines.push(build_hardware_encode_preview(
+                input,
+                is_interlaced,
+                config,
+                vaapi_device,
+                "video.ivf",
+            ));
+            "video.ivf".to_string()
+        }
+        VideoMode::Encode => {
+            let demux_cmd = build_ffmpeg_demux_preview(
+                input,
+                is_interlaced,
+                &source_input_args(input, config),
+                config.deinterlace,
+                config.crop_to_even,
+            );
+            let encoder_cmd = build_encoder_preview(config, "video.ivf");
+            lines.push(format!("{demux_cmd} | {encoder_cmd}"));
+            "video.ivf".to_string()
+        }
+        VideoMode::Copy => input.display().to_string(),
+    };
 
     // Étape 2: Encodage audio
     let audio_ext = match config.audio_mode {
         AudioMode::Opus { .. } => "audio.opus",
         AudioMode::Copy => "audio.copy",
         AudioMode::Custom { ref codec, .. } => &format!("audio.{}", codec.to_lowercase()),
+        AudioMode::None => "",
     };
-    lines.push(build_audio_preview(input, config, audio_ext));
+    let audio_cmd = build_audio_preview(input, config, audio_ext);
+    let audio_file = audio_cmd.is_some().then_some(audio_ext);
+    lines.extend(audio_cmd);
 
     // Étape 3: Muxing
-    lines.push(build_muxing_preview("video.ivf", audio_ext, output));
+    lines.push(build_muxing_preview(&video_file, audio_file, output));
 
     lines
 }
@@ -10,14 +10,20 @@ pub fn binary_name(name: &str) -> String {
 }
 
 /// Helper pour obtenir le chemin IPC par défaut (socket Unix ou Named Pipe)
-fn get_default_ipc_path(data_dir: &Path) -> PathBuf {
+///
+/// Sur Unix, le socket va dans `$XDG_RUNTIME_DIR/encodetalker/` quand `runtime_dir` est
+/// connu (tmpfs vidé à la déconnexion), sinon dans `data_dir`.
+fn get_default_ipc_path(data_dir: &Path, runtime_dir: Option<&Path>) -> PathBuf {
     #[cfg(unix)]
     {
-        data_dir.join("daemon.sock")
+        runtime_dir.map_or_else(
+            || data_dir.join("daemon.sock"),
+            |dir| dir.join("encodetalker").join("daemon.sock"),
+        )
     }
     #[cfg(windows)]
     {
-        let _ = data_dir; // Utilisé seulement sur Unix
+        let _ = (data_dir, runtime_dir); // Utilisés seulement sur Unix
         PathBuf::from(r"\\.\pipe\encodetalker")
     }
 }
@@ -33,10 +39,16 @@ pub struct AppPaths {
     pub config_file: PathBuf,
     /// Fichier de persistance de l'état
     pub state_file: PathBuf,
+    /// Fichier d'analytique des jobs terminés (JSONL, ajout seul)
+    pub analytics_file: PathBuf,
+    /// Échantillons de progression des jobs en cours (JSONL, `[progress_log]`)
+    pub progress_file: PathBuf,
     /// Socket Unix pour IPC
     pub socket_path: PathBuf,
     /// Fichier de log du daemon
     pub log_file: PathBuf,
+    /// Fichier PID/verrou du daemon en cours (coordonne les redémarrages)
+    pub pid_file: PathBuf,
     /// Répertoire des dépendances compilées
     pub deps_dir: PathBuf,
     /// Répertoire bin des dépendances
@@ -125,20 +137,36 @@ impl AppPaths {
             data_dir.join("deps")
         };
 
-        // 4. Déterminer socket_path (custom, dérivé de data_dir, ou défaut IPC)
+        // 4. Déterminer socket_path (custom, runtime XDG, dérivé de data_dir, ou défaut IPC)
         let socket_path = if let Some(ref custom) = config.socket_path {
             PathsConfig::expand_path(custom)
                 .context("Impossible d'expanser socket_path personnalisé")?
         } else {
-            // Dérivé de data_dir ou chemin par défaut selon l'OS
-            get_default_ipc_path(&data_dir)
+            // Un data_dir personnalisé garde son socket : deux instances restent séparées
+            let runtime_dir = if config.data_dir.is_none() {
+                dirs::runtime_dir()
+            } else {
+                None
+            };
+            get_default_ipc_path(&data_dir, runtime_dir.as_deref())
         };
 
-        // 5. Construire tous les chemins
+        // 5. Déterminer log_file (custom ou dérivé de data_dir)
+        let log_file = if let Some(ref custom) = config.log_file {
+            PathsConfig::expand_path(custom)
+                .context("Impossible d'expanser log_file personnalisé")?
+        } else {
+            data_dir.join("daemon.log")
+        };
+
+        // 6. Construire tous les chemins
         Ok(Self {
             config_file,
             state_file: data_dir.join("state.json"),
-            log_file: data_dir.join("daemon.log"),
+            analytics_file: data_dir.join("analytics.jsonl"),
+            progress_file: data_dir.join("progress.jsonl"),
+            log_file,
+            pid_file: data_dir.join("daemon.pid"),
             deps_bin_dir: deps_dir.join("bin"),
             deps_src_dir: deps_dir.join("src"),
             data_dir,
@@ -164,6 +192,11 @@ impl AppPaths {
             .context("Impossible de créer le répertoire bin des dépendances")?;
         std::fs::create_dir_all(&self.deps_src_dir)
             .context("Impossible de créer le répertoire src des dépendances")?;
+        #[cfg(unix)]
+        if let Some(socket_dir) = self.socket_path.parent() {
+            std::fs::create_dir_all(socket_dir)
+                .context("Impossible de créer le répertoire du socket")?;
+        }
         Ok(())
     }
 
@@ -234,6 +267,21 @@ mod tests {
         assert!(paths.socket_path.to_string_lossy().contains("encodetalker"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_default_ipc_path_prefers_runtime_dir() {
+        let data_dir = Path::new("/home/user/.local/share/encodetalker");
+        assert_eq!(
+            get_default_ipc_path(data_dir, Some(Path::new("/run/user/1000"))),
+            PathBuf::from("/run/user/1000/encodetalker/daemon.sock")
+        );
+        // Sans $XDG_RUNTIME_DIR : socket dans data_dir, comme avant
+        assert_eq!(
+            get_default_ipc_path(data_dir, None),
+            data_dir.join("daemon.sock")
+        );
+    }
+
     #[test]
     fn test_from_config_none_is_same_as_new() {
         let paths1 = AppPaths::new().unwrap();
@@ -250,6 +298,7 @@ mod tests {
             data_dir: Some("/tmp/custom_data".to_string()),
             deps_dir: None,
             socket_path: None,
+            log_file: None,
         };
 
         let paths = AppPaths::from_config(Some(config)).unwrap();
@@ -282,6 +331,7 @@ mod tests {
             data_dir: Some("/data".to_string()),
             deps_dir: Some("/deps".to_string()),
             socket_path: Some("/tmp/custom.sock".to_string()),
+            log_file: None,
         };
 
         let paths = AppPaths::from_config(Some(config)).unwrap();
@@ -300,6 +350,7 @@ mod tests {
             data_dir: None,
             deps_dir: Some("/mnt/ssd/deps".to_string()),
             socket_path: None,
+            log_file: None,
         };
 
         let paths = AppPaths::from_config(Some(config)).unwrap();
@@ -322,6 +373,7 @@ mod tests {
             data_dir: Some("~/test_encodetalker".to_string()),
             deps_dir: None,
             socket_path: None,
+            log_file: None,
         };
 
         let paths = AppPaths::from_config(Some(config)).unwrap();
@@ -337,6 +389,7 @@ mod tests {
             socket_path: Some("$TEST_DIR/encodetalker.sock".to_string()),
             data_dir: None,
             deps_dir: None,
+            log_file: None,
         };
 
         let paths = AppPaths::from_config(Some(config)).unwrap();
@@ -353,10 +406,15 @@ mod tests {
             data_dir: Some("/custom".to_string()),
             deps_dir: Some("/custom/deps".to_string()),
             socket_path: Some("/custom/socket".to_string()),
+            log_file: Some("/var/log/encodetalker/daemon.log".to_string()),
         };
 
         let paths = AppPaths::from_config(Some(config)).unwrap();
         assert!(paths.config_dir.ends_with("config"));
         assert!(paths.config_file.ends_with("config.toml"));
+        assert_eq!(
+            paths.log_file,
+            PathBuf::from("/var/log/encodetalker/daemon.log")
+        );
     }
 }
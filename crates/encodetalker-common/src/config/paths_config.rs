@@ -20,10 +20,16 @@ pub struct PathsConfig {
     pub deps_dir: Option<String>,
 
     /// Socket Unix pour communication daemon<->TUI
-    /// Défaut: `<data_dir>/daemon.sock`
+    /// Défaut: `$XDG_RUNTIME_DIR/encodetalker/daemon.sock`, sinon `<data_dir>/daemon.sock`
+    /// (toujours `<data_dir>/daemon.sock` si `data_dir` est personnalisé)
     /// Cas d'usage: multi-utilisateurs avec /tmp/encodetalker-$USER.sock
     #[serde(default)]
     pub socket_path: Option<String>,
+
+    /// Fichier de log du daemon (les fichiers archivés sont suffixés .1, .2, ...)
+    /// Défaut: `<data_dir>/daemon.log`
+    #[serde(default)]
+    pub log_file: Option<String>,
 }
 
 impl PathsConfig {
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+/// En dessous de cet espace libre, la destination est signalée (10 Go)
+pub const LOW_FREE_SPACE_BYTES: u64 = 10_000_000_000;
+
+/// Espace disponible (octets) sur le système de fichiers qui contiendra `path`
+///
+/// Le fichier de sortie n'existe pas encore : on interroge son premier ancêtre existant.
+/// Retourne None si aucun ancêtre n'est accessible ou hors Unix.
+#[must_use]
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|dir| dir.exists())?;
+    statvfs_available(existing)
+}
+
+#[cfg(unix)]
+fn statvfs_available(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: chemin terminé par NUL, `stats` n'est lu que si l'appel réussit
+    if unsafe { libc::statvfs(c_path.as_ptr(), stats.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // SAFETY: statvfs a rempli la structure
+    let stats = unsafe { stats.assume_init() };
+    // Blocs disponibles pour un utilisateur non privilégié
+    #[allow(clippy::unnecessary_cast)]
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn statvfs_available(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_available_space_uses_existing_ancestor() {
+        let tmp = tempfile::tempdir().unwrap();
+        let missing = tmp.path().join("absent/sous-dossier/film.mkv");
+        assert!(available_space(&missing).is_some());
+    }
+}
@@ -1,11 +1,15 @@
 pub mod command_preview;
 pub mod config;
+pub mod disk;
 pub mod ipc;
 pub mod protocol;
 pub mod types;
+pub mod units;
 
 pub use command_preview::*;
 pub use config::*;
+pub use disk::*;
 pub use ipc::{IpcListener, IpcStream};
 pub use protocol::*;
 pub use types::*;
+pub use units::*;
@@ -1,4 +1,7 @@
-use super::super::types::{EncodingConfig, EncodingJob, EncodingStats};
+use super::super::types::{
+    BenchmarkReport, CleanupResult, CleanupTarget, EncoderCapabilities, EncoderType,
+    EncodingConfig, EncodingJob, EncodingStats, FailureCategory, PresetBenchmark,
+};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
@@ -29,6 +32,93 @@ pub struct DepsStatusInfo {
     pub completed_count: usize,
     /// Nombre total de dépendances
     pub total_count: usize,
+    /// Reprise d'une compilation interrompue
+    #[serde(default)]
+    pub resumed: bool,
+    /// Dépendances compilées, dans l'ordre (vide = toutes)
+    #[serde(default)]
+    pub deps: Vec<String>,
+    /// Échec de la compilation, ou raison pour laquelle elle n'a pas pu démarrer
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// État général du daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonStatusInfo {
+    /// Nouveaux jobs acceptés (faux pendant l'arrêt ou le drain)
+    pub accepting_jobs: bool,
+}
+
+/// Catégorie d'un point de contrôle de l'environnement de compilation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuildEnvCheckKind {
+    /// Outil en ligne de commande (compilateur, assembleur...)
+    Tool,
+    /// Bibliothèque détectée via pkg-config
+    Library,
+}
+
+/// Résultat d'un point de contrôle de l'environnement de compilation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildEnvCheck {
+    pub name: String,
+    pub kind: BuildEnvCheckKind,
+    pub found: bool,
+    /// Absence bloquante pour la compilation
+    pub required: bool,
+    /// Chemin, version ou raison de l'échec
+    pub detail: String,
+}
+
+/// Rapport de diagnostic de l'environnement de compilation des dépendances
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildEnvReport {
+    pub checks: Vec<BuildEnvCheck>,
+}
+
+impl BuildEnvReport {
+    /// Tous les éléments requis sont présents ?
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.checks.iter().all(|c| c.found || !c.required)
+    }
+}
+
+/// État complet de la file : jobs en attente, en cours et historique
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    pub queue: Vec<EncodingJob>,
+    pub active: Vec<EncodingJob>,
+    pub history: Vec<EncodingJob>,
+}
+
+/// Job d'un lot ajouté par `AddBatchJobs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchJobSpec {
+    pub input_path: PathBuf,
+    pub output_path: PathBuf,
+    pub config: EncodingConfig,
+}
+
+/// Métadonnées d'une vidéo probée (`RequestPayload::ProbeVideos`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VideoSummary {
+    pub duration_secs: Option<f64>,
+    pub size_bytes: u64,
+    /// Hauteur du stream vidéo (None = inconnue)
+    pub height: Option<u32>,
+    /// Canaux de la première piste audio (None = pas d'audio ou inconnu)
+    pub audio_channels: Option<u32>,
+}
+
+/// Preset d'un autre outil converti en configuration d'encodage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedPreset {
+    pub name: String,
+    pub config: EncodingConfig,
+    /// Réglages ignorés ou approchés, à signaler à l'utilisateur
+    pub warnings: Vec<String>,
 }
 
 /// Requête du client vers le daemon
@@ -57,12 +147,47 @@ pub enum RequestPayload {
     AddJob {
         input_path: PathBuf,
         output_path: PathBuf,
-        config: EncodingConfig,
+        config: Box<EncodingConfig>,
+    },
+    /// Ajouter plusieurs jobs sous un même identifiant de lot
+    ///
+    /// Chaque job est validé séparément : ceux qui sont refusés n'empêchent pas l'ajout
+    /// des autres. Le lot peut ensuite être annulé d'un coup (`CancelBatch`).
+    AddBatchJobs { jobs: Vec<BatchJobSpec> },
+    /// Ajouter un job dont la configuration est lue dans un fichier TOML ou JSON
+    ///
+    /// Le fichier est lu par le daemon, puis validé avant la mise en queue.
+    AddJobFromFile {
+        input_path: PathBuf,
+        /// None = sortie nommée par le daemon (`output_template`, `output_dir`)
+        output_path: Option<PathBuf>,
+        config_path: PathBuf,
+    },
+    /// Convertir les presets d'un export JSON de Handbrake (fichier lu par le daemon)
+    ///
+    /// Seuls l'encodeur, la qualité, l'audio, les langues et le désentrelacement sont
+    /// repris ; les autres réglages sont signalés dans les avertissements de chaque preset.
+    ImportHandbrakePresets { path: PathBuf },
+    /// Ajouter un extrait de test : les `duration_secs` premières secondes encodées vers
+    /// `<sortie>.preview.<ext>`, avant les encodages en attente
+    AddPreviewJob {
+        input_path: PathBuf,
+        output_path: PathBuf,
+        config: Box<EncodingConfig>,
+        duration_secs: u32,
     },
+    /// Ajouter un job de vérification d'un fichier existant (codec, intégrité, streams)
+    ///
+    /// Le rapport est joint au job, visible dans l'historique comme un encodage.
+    VerifyFile { path: PathBuf },
     /// Annuler un job (queued ou running)
     CancelJob { job_id: Uuid },
+    /// Annuler les jobs d'un lot encore en attente ou en cours
+    CancelBatch { batch_id: Uuid },
     /// Retry un job failed
     RetryJob { job_id: Uuid },
+    /// Déplacer un job en attente à `position` dans la queue (0 = prochain, bornée à la fin)
+    MoveJob { job_id: Uuid, position: usize },
     /// Obtenir la liste des jobs en queue
     ListQueue,
     /// Obtenir la liste des jobs actifs (running)
@@ -73,18 +198,57 @@ pub enum RequestPayload {
     GetJob { job_id: Uuid },
     /// Obtenir les stats actuelles d'un job running
     GetStats { job_id: Uuid },
-    /// Supprimer un job spécifique de l'historique
-    RemoveFromHistory { job_id: Uuid },
-    /// Clear l'historique
-    ClearHistory,
+    /// Supprimer un job spécifique de l'historique (et ses fichiers de sortie si demandé)
+    RemoveFromHistory { job_id: Uuid, delete_outputs: bool },
+    /// Clear l'historique (et les fichiers de sortie si demandé)
+    ClearHistory { delete_outputs: bool },
     /// Shutdown graceful du daemon
     Shutdown,
     /// Ping (healthcheck)
     Ping,
     /// Obtenir l'état de compilation des dépendances
     GetDepsStatus,
+    /// Obtenir l'état général du daemon (jobs acceptés ou non)
+    GetDaemonStatus,
     /// Probe une vidéo pour récupérer ses métadonnées (durée, etc.)
     ProbeVideo { path: PathBuf },
+    /// Prober plusieurs vidéos en parallèle (nombre de probes simultanés borné par le daemon)
+    ProbeVideos { paths: Vec<PathBuf> },
+    /// Extraire une frame d'une vidéo (miniature PNG de l'explorateur)
+    GetThumbnail { path: PathBuf, timestamp: f64 },
+    /// Obtenir les versions et options supportées par les encodeurs installés
+    GetEncoderCapabilities,
+    /// Changer la niceness des processus d'un job en cours
+    SetJobPriority { job_id: Uuid, nice: i32 },
+    /// Vérifier l'environnement de compilation des dépendances (outils, bibliothèques)
+    CheckBuildEnv,
+    /// Mesurer la vitesse des presets d'un encodeur sur un extrait de la source
+    ///
+    /// Le benchmark tourne en arrière-plan : les résultats arrivent par événements.
+    Benchmark {
+        encoder: EncoderType,
+        input_path: PathBuf,
+        /// Vitesse visée, en multiple du temps réel (1.0 = temps réel)
+        target_speed: f64,
+    },
+    /// Recevoir (ou non) la sortie des compilateurs sur cette connexion
+    ///
+    /// Désactivé par défaut : seule la vue de chargement qui l'affiche s'y abonne.
+    SetCompilationLogStreaming { enabled: bool },
+    /// Libérer de l'espace : sources des dépendances, fichiers temporaires orphelins,
+    /// anciens logs, cache des miniatures
+    ///
+    /// Avec `dry_run`, rien n'est supprimé : la réponse donne l'espace libérable.
+    Cleanup {
+        targets: Vec<CleanupTarget>,
+        dry_run: bool,
+    },
+    /// Suivre l'état complet de la file sur cette connexion
+    ///
+    /// La réponse contient l'état initial ; chaque changement (ajout, démarrage, fin,
+    /// retrait de l'historique...) est ensuite suivi d'un événement `StateChanged`.
+    /// La progression des jobs n'en déclenche pas.
+    WatchState,
 }
 
 /// Réponse du daemon vers le client
@@ -125,6 +289,14 @@ pub enum ResponsePayload {
     Error { message: String },
     /// ID d'un job créé
     JobId { job_id: Uuid },
+    /// Lot ajouté : jobs créés, et fichiers refusés avec la raison
+    BatchAdded {
+        batch_id: Uuid,
+        job_ids: Vec<Uuid>,
+        errors: Vec<(PathBuf, String)>,
+    },
+    /// Nombre de jobs annulés par `CancelBatch`
+    BatchCancelled { cancelled: usize },
     /// Un job unique
     Job { job: Box<EncodingJob> },
     /// Liste de jobs
@@ -135,12 +307,36 @@ pub enum ResponsePayload {
     Pong,
     /// État de compilation des dépendances
     DepsStatus { status: DepsStatusInfo },
-    /// Informations sur une vidéo (durée, taille)
+    /// État général du daemon
+    DaemonStatus { status: DaemonStatusInfo },
+    /// Informations sur une vidéo (durée, taille, hauteur)
     VideoInfo {
         path: PathBuf,
         duration_secs: Option<f64>,
         size_bytes: u64,
+        /// Hauteur du stream vidéo (None = inconnue)
+        #[serde(default)]
+        height: Option<u32>,
+        /// Canaux de la première piste audio (None = pas d'audio ou inconnu)
+        #[serde(default)]
+        audio_channels: Option<u32>,
+    },
+    /// Métadonnées de plusieurs vidéos, dans l'ordre de la requête (erreur par fichier)
+    VideoInfos {
+        results: Vec<(PathBuf, Result<VideoSummary, String>)>,
     },
+    /// Miniature PNG d'une vidéo
+    Thumbnail { path: PathBuf, png: Vec<u8> },
+    /// Capacités des encodeurs installés
+    EncoderCapabilities { capabilities: EncoderCapabilities },
+    /// Diagnostic de l'environnement de compilation
+    BuildEnvReport { report: BuildEnvReport },
+    /// État complet de la file
+    State { snapshot: QueueSnapshot },
+    /// Résultat de `Cleanup`, une entrée par cible demandée
+    CleanupReport { results: Vec<CleanupResult> },
+    /// Presets importés, dans l'ordre du fichier
+    ImportedPresets { presets: Vec<ImportedPreset> },
 }
 
 /// Événement push du daemon vers les clients (broadcast)
@@ -173,19 +369,43 @@ pub enum EventPayload {
     /// Job démarré
     JobStarted { job_id: Uuid },
     /// Progression d'un job
-    JobProgress { job_id: Uuid, stats: EncodingStats },
+    JobProgress {
+        job_id: Uuid,
+        stats: Box<EncodingStats>,
+    },
     /// Job terminé avec succès
     JobCompleted { job_id: Uuid },
     /// Job échoué
-    JobFailed { job_id: Uuid, error: String },
+    JobFailed {
+        job_id: Uuid,
+        error: String,
+        category: FailureCategory,
+    },
     /// Job annulé
     JobCancelled { job_id: Uuid },
+    /// Job en attente déplacé dans la queue
+    JobMoved { job_id: Uuid, position: usize },
+    /// Job retiré de l'historique
+    HistoryRemoved { job_id: Uuid },
+    /// Historique vidé
+    HistoryCleared,
+    /// Nouvel état de la file (seulement aux clients ayant envoyé `WatchState`)
+    StateChanged { snapshot: QueueSnapshot },
     /// Daemon en cours de shutdown
     DaemonShutdown,
+    /// Le daemon n'accepte plus de nouveaux jobs (arrêt ou drain en cours, jobs actifs
+    /// poursuivis selon `shutdown_mode`)
+    StoppedAcceptingJobs,
     /// Compilation des dépendances démarrée
     DepsCompilationStarted {
         /// Nombre total de dépendances à compiler
         total_deps: usize,
+        /// Reprise d'une compilation interrompue
+        #[serde(default)]
+        resumed: bool,
+        /// Dépendances compilées, dans l'ordre (vide = toutes)
+        #[serde(default)]
+        deps: Vec<String>,
     },
     /// Progression de compilation d'une dépendance
     DepsCompilationProgress {
@@ -216,6 +436,36 @@ pub enum EventPayload {
         /// Message d'erreur
         error: String,
     },
+    /// Lignes de sortie des compilateurs (envoyées par lots, seulement aux clients abonnés)
+    DepsCompilationLog {
+        lines: Vec<String>,
+        /// Lignes écartées depuis le lot précédent (sortie trop abondante)
+        skipped: usize,
+    },
+    /// Un preset du benchmark a été mesuré
+    BenchmarkProgress { result: PresetBenchmark },
+    /// Benchmark terminé
+    BenchmarkCompleted { report: BenchmarkReport },
+    /// Benchmark échoué
+    BenchmarkFailed { error: String },
+}
+
+impl EventPayload {
+    /// L'événement modifie-t-il le contenu de la file (hors progression) ?
+    #[must_use]
+    pub fn changes_queue_state(&self) -> bool {
+        matches!(
+            self,
+            Self::JobAdded { .. }
+                | Self::JobStarted { .. }
+                | Self::JobCompleted { .. }
+                | Self::JobFailed { .. }
+                | Self::JobCancelled { .. }
+                | Self::JobMoved { .. }
+                | Self::HistoryRemoved { .. }
+                | Self::HistoryCleared
+        )
+    }
 }
 
 /// Message IPC (peut être Request, Response ou Event)
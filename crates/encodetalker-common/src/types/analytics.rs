@@ -0,0 +1,182 @@
+use super::EncoderType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Enregistrement analytique d'un job terminé (une ligne du fichier JSONL)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnalyticsRecord {
+    pub job_id: Uuid,
+    pub finished_at: DateTime<Utc>,
+    pub encoder: EncoderType,
+    pub preset: u32,
+    pub crf: u32,
+    /// Durée de l'encodage vidéo (hors audio, muxage et VMAF)
+    pub encode_secs: f64,
+    /// Vitesse moyenne (frames source / durée d'encodage)
+    pub avg_fps: f64,
+    /// Nombre de frames de la source
+    pub total_frames: Option<u64>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Vitesse moyenne agrégée pour un couple encodeur/preset
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedSummary {
+    pub encoder: EncoderType,
+    pub preset: u32,
+    /// Nombre de jobs pris en compte
+    pub jobs: usize,
+    pub avg_fps: f64,
+    pub avg_encode_secs: f64,
+}
+
+/// Poids du job le plus récent dans la vitesse historique (moyenne mobile exponentielle)
+///
+/// Les anciens jobs comptent de moins en moins : un changement de machine ou de réglages
+/// se reflète après quelques encodages.
+pub const SPEED_HISTORY_WEIGHT: f64 = 0.3;
+
+/// Vitesse historique par encodeur/preset, pour amorcer l'ETA des nouveaux jobs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpeedHistory {
+    averages: HashMap<(EncoderType, u32), f64>,
+}
+
+impl SpeedHistory {
+    /// Reconstituer l'historique depuis les enregistrements (dans l'ordre de fin des jobs)
+    #[must_use]
+    pub fn from_records(records: &[AnalyticsRecord]) -> Self {
+        let mut sorted: Vec<&AnalyticsRecord> = records.iter().collect();
+        sorted.sort_by_key(|r| r.finished_at);
+        let mut history = Self::default();
+        for record in sorted {
+            history.record(record.encoder, record.preset, record.avg_fps);
+        }
+        history
+    }
+
+    /// Intégrer la vitesse d'un job terminé (ignorée si nulle)
+    pub fn record(&mut self, encoder: EncoderType, preset: u32, fps: f64) {
+        if fps <= 0.0 || !fps.is_finite() {
+            return;
+        }
+        self.averages
+            .entry((encoder, preset))
+            .and_modify(|avg| *avg += (fps - *avg) * SPEED_HISTORY_WEIGHT)
+            .or_insert(fps);
+    }
+
+    /// Vitesse attendue (frames source par seconde, toutes passes confondues)
+    #[must_use]
+    pub fn expected_fps(&self, encoder: EncoderType, preset: u32) -> Option<f64> {
+        self.averages.get(&(encoder, preset)).copied()
+    }
+}
+
+/// Parser un fichier JSONL d'analytique (les lignes invalides sont ignorées)
+#[must_use]
+pub fn parse_analytics_jsonl(content: &str) -> Vec<AnalyticsRecord> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Agréger la vitesse moyenne par encodeur et preset (trié par encodeur puis preset)
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // Safe: nombre de jobs bien inférieur à 2^52
+pub fn summarize_speed(records: &[AnalyticsRecord]) -> Vec<SpeedSummary> {
+    let mut groups: HashMap<(EncoderType, u32), (usize, f64, f64)> = HashMap::new();
+    for record in records {
+        let entry = groups
+            .entry((record.encoder, record.preset))
+            .or_insert((0, 0.0, 0.0));
+        entry.0 += 1;
+        entry.1 += record.avg_fps;
+        entry.2 += record.encode_secs;
+    }
+
+    let mut summaries: Vec<SpeedSummary> = groups
+        .into_iter()
+        .map(
+            |((encoder, preset), (jobs, fps_sum, secs_sum))| SpeedSummary {
+                encoder,
+                preset,
+                jobs,
+                avg_fps: fps_sum / jobs as f64,
+                avg_encode_secs: secs_sum / jobs as f64,
+            },
+        )
+        .collect();
+
+    summaries.sort_by(|a, b| {
+        a.encoder
+            .to_string()
+            .cmp(&b.encoder.to_string())
+            .then(a.preset.cmp(&b.preset))
+    });
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(encoder: EncoderType, preset: u32, avg_fps: f64) -> AnalyticsRecord {
+        AnalyticsRecord {
+            job_id: Uuid::new_v4(),
+            finished_at: Utc::now(),
+            encoder,
+            preset,
+            crf: 30,
+            encode_secs: 100.0,
+            avg_fps,
+            total_frames: Some(2400),
+            width: 1920,
+            height: 1080,
+        }
+    }
+
+    #[test]
+    fn test_summarize_speed_groups_by_encoder_and_preset() {
+        let records = vec![
+            record(EncoderType::SvtAv1, 6, 10.0),
+            record(EncoderType::SvtAv1, 6, 20.0),
+            record(EncoderType::SvtAv1, 8, 40.0),
+            record(EncoderType::Aom, 4, 2.0),
+        ];
+
+        let summaries = summarize_speed(&records);
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries[0].encoder, EncoderType::SvtAv1);
+        assert_eq!(summaries[0].preset, 6);
+        assert_eq!(summaries[0].jobs, 2);
+        assert!((summaries[0].avg_fps - 15.0).abs() < f64::EPSILON);
+        assert_eq!(summaries[2].encoder, EncoderType::Aom);
+    }
+
+    #[test]
+    fn test_speed_history_decays_old_samples() {
+        let mut old = record(EncoderType::SvtAv1, 6, 10.0);
+        old.finished_at = Utc::now() - chrono::Duration::days(30);
+        let recent = record(EncoderType::SvtAv1, 6, 20.0);
+        let failed = record(EncoderType::SvtAv1, 6, 0.0);
+
+        // Ordre du fichier indifférent : le plus récent pèse SPEED_HISTORY_WEIGHT
+        let history = SpeedHistory::from_records(&[recent, old, failed]);
+        let expected = history.expected_fps(EncoderType::SvtAv1, 6).unwrap();
+        assert!((expected - 13.0).abs() < 1e-9);
+        assert_eq!(history.expected_fps(EncoderType::SvtAv1, 8), None);
+    }
+
+    #[test]
+    fn test_parse_analytics_jsonl_skips_invalid_lines() {
+        let line = serde_json::to_string(&record(EncoderType::SvtAv1, 6, 10.0)).unwrap();
+        let content = format!("{line}\nnot json\n\n{line}\n");
+        assert_eq!(parse_analytics_jsonl(&content).len(), 2);
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// Débit Opus initial selon le nombre de canaux de la source
+/// (`[encoding] audio_bitrate_per_channel` et `max_audio_bitrate`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioBitratePolicy {
+    /// Débit par canal (kbps)
+    pub per_channel: u32,
+    /// Plafond du débit total (kbps)
+    pub max: u32,
+}
+
+impl AudioBitratePolicy {
+    /// Débit proposé pour une piste de `channels` canaux (au moins un canal compté)
+    #[must_use]
+    pub fn bitrate_for(&self, channels: u32) -> u32 {
+        self.per_channel
+            .saturating_mul(channels.max(1))
+            .min(self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitrate_scales_with_channels_and_is_capped() {
+        let policy = AudioBitratePolicy {
+            per_channel: 64,
+            max: 384,
+        };
+        assert_eq!(policy.bitrate_for(1), 64);
+        assert_eq!(policy.bitrate_for(2), 128);
+        assert_eq!(policy.bitrate_for(6), 384);
+        // 7.1 : plafonné
+        assert_eq!(policy.bitrate_for(8), 384);
+        assert_eq!(policy.bitrate_for(0), 64);
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use super::{EncoderType, TrimRange};
+
+/// Durée de l'extrait encodé pour chaque preset (secondes)
+pub const BENCHMARK_SAMPLE_SECS: f64 = 10.0;
+
+/// Vitesse mesurée pour un preset
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PresetBenchmark {
+    /// Preset testé (`--preset` SVT-AV1, `--cpu-used` aomenc)
+    pub preset: u32,
+    /// Frames encodées par seconde (toutes passes confondues)
+    pub fps: f64,
+}
+
+/// Résultat d'un benchmark des presets d'un encodeur
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub encoder: EncoderType,
+    /// Framerate de la source (référence du temps réel)
+    pub source_fps: f64,
+    /// Vitesse visée, en multiple du temps réel (1.0 = temps réel)
+    pub target_speed: f64,
+    /// Presets testés, du plus rapide au plus lent
+    pub results: Vec<PresetBenchmark>,
+}
+
+impl BenchmarkReport {
+    /// Vitesse d'un résultat en multiple du temps réel
+    #[must_use]
+    pub fn speed(&self, result: &PresetBenchmark) -> f64 {
+        if self.source_fps > 0.0 {
+            result.fps / self.source_fps
+        } else {
+            0.0
+        }
+    }
+
+    /// Preset recommandé : le plus lent (meilleure compression) qui tient la vitesse visée
+    #[must_use]
+    pub fn recommended(&self) -> Option<u32> {
+        self.results
+            .iter()
+            .filter(|r| self.speed(r) >= self.target_speed)
+            .min_by_key(|r| r.preset)
+            .map(|r| r.preset)
+    }
+}
+
+/// Extrait de la source encodé par le benchmark
+///
+/// Pris à 10 % de la durée pour éviter les génériques et écrans noirs du début, en
+/// restant dans la source ; une source plus courte que l'extrait est encodée en entier.
+#[must_use]
+pub fn benchmark_sample(duration_secs: Option<f64>) -> TrimRange {
+    match duration_secs {
+        Some(total) if total > BENCHMARK_SAMPLE_SECS => {
+            let start = (total * 0.1).min(total - BENCHMARK_SAMPLE_SECS);
+            TrimRange {
+                start: Some(start),
+                end: Some(start + BENCHMARK_SAMPLE_SECS),
+            }
+        }
+        Some(_) => TrimRange::default(),
+        None => TrimRange {
+            start: None,
+            end: Some(BENCHMARK_SAMPLE_SECS),
+        },
+    }
+}
+
+/// Presets testés par le benchmark, du plus rapide au plus lent
+#[must_use]
+pub fn benchmark_presets(encoder: EncoderType) -> &'static [u32] {
+    match encoder {
+        EncoderType::SvtAv1 => &[12, 10, 8, 6, 4],
+        EncoderType::Aom => &[8, 6, 4],
+        EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => &[6, 4, 2, 0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommended_preset_meets_target() {
+        let report = BenchmarkReport {
+            encoder: EncoderType::SvtAv1,
+            source_fps: 24.0,
+            target_speed: 1.0,
+            results: vec![
+                PresetBenchmark {
+                    preset: 12,
+                    fps: 180.0,
+                },
+                PresetBenchmark {
+                    preset: 10,
+                    fps: 60.0,
+                },
+                PresetBenchmark {
+                    preset: 8,
+                    fps: 25.0,
+                },
+                PresetBenchmark {
+                    preset: 6,
+                    fps: 9.0,
+                },
+            ],
+        };
+        assert_eq!(report.recommended(), Some(8));
+
+        let slow = BenchmarkReport {
+            target_speed: 10.0,
+            ..report
+        };
+        assert_eq!(slow.recommended(), None);
+    }
+
+    #[test]
+    fn test_benchmark_sample_stays_in_source() {
+        let sample = benchmark_sample(Some(1200.0));
+        assert_eq!(sample.start, Some(120.0));
+        assert_eq!(sample.end, Some(130.0));
+
+        // Source à peine plus longue que l'extrait : on recule le début
+        let sample = benchmark_sample(Some(10.5));
+        assert_eq!(sample.start, Some(0.5));
+        assert_eq!(sample.end, Some(10.5));
+
+        assert!(benchmark_sample(Some(4.0)).is_full());
+        assert_eq!(benchmark_sample(None).end, Some(BENCHMARK_SAMPLE_SECS));
+    }
+}
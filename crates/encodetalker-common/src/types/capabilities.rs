@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+
+use super::EncoderType;
+
+/// Numéro de version d'un encodeur (`major.minor.patch`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EncoderVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl EncoderVersion {
+    #[must_use]
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Extraire le premier numéro de version d'un texte (`SVT-AV1-PSY v2.3.0-B` → 2.3.0)
+    ///
+    /// Au moins `major.minor` est requis ; le patch manquant vaut 0.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        text.split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | ','))
+            .find_map(|token| {
+                let token = token.strip_prefix(['v', 'V']).unwrap_or(token);
+                let end = token
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(token.len());
+                let mut parts = token[..end].split('.').map(str::parse::<u32>);
+                let major = parts.next()?.ok()?;
+                let minor = parts.next()?.ok()?;
+                let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+                Some(Self::new(major, minor, patch))
+            })
+    }
+}
+
+impl std::fmt::Display for EncoderVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Informations sur un binaire d'encodage (version et options supportées)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EncoderBinaryInfo {
+    /// Version rapportée par le binaire (première ligne utile)
+    pub version: Option<String>,
+    /// Options longues supportées (ex: `--film-grain`), triées
+    pub options: Vec<String>,
+    /// Version numérique extraite de `version`
+    #[serde(default)]
+    pub parsed_version: Option<EncoderVersion>,
+    /// Options émises par le pipeline que cette version est trop ancienne pour connaître
+    #[serde(default)]
+    pub disabled_options: Vec<String>,
+}
+
+impl EncoderBinaryInfo {
+    /// Vérifier si une option est supportée par le binaire
+    #[must_use]
+    pub fn supports(&self, option: &str) -> bool {
+        self.options
+            .binary_search_by(|o| o.as_str().cmp(option))
+            .is_ok()
+            && !self.is_disabled(option)
+    }
+
+    /// L'option a-t-elle été désactivée parce que la version installée est trop ancienne ?
+    #[must_use]
+    pub fn is_disabled(&self, option: &str) -> bool {
+        self.disabled_options.iter().any(|o| o == option)
+    }
+
+    /// Le pipeline peut-il passer l'option ?
+    ///
+    /// Contrairement à [`Self::supports`], une liste d'options vide (sortie `--help`
+    /// illisible) ne bloque rien : seules les options désactivées par version sont refusées.
+    #[must_use]
+    pub fn accepts(&self, option: &str) -> bool {
+        if self.options.is_empty() {
+            !self.is_disabled(option)
+        } else {
+            self.supports(option)
+        }
+    }
+}
+
+/// Variante de SVT-AV1 (fork PSY ou version officielle)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SvtAv1Variant {
+    /// Fork SVT-AV1-PSY (réglages psychovisuels supplémentaires)
+    #[default]
+    Psy,
+    /// SVT-AV1 officiel (AOMedia)
+    Mainline,
+}
+
+impl std::fmt::Display for SvtAv1Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Psy => write!(f, "SVT-AV1-PSY"),
+            Self::Mainline => write!(f, "SVT-AV1"),
+        }
+    }
+}
+
+/// Capacités des encodeurs et de ffmpeg installés
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EncoderCapabilities {
+    /// `SvtAv1EncApp` (None si absent ou non exécutable)
+    pub svt_av1: Option<EncoderBinaryInfo>,
+    /// aomenc (None si absent ou non exécutable)
+    pub aom: Option<EncoderBinaryInfo>,
+    /// Version de ffmpeg
+    pub ffmpeg_version: Option<String>,
+    /// Encodeurs disponibles dans ffmpeg (ex: `libopus`, `aac`), triés
+    pub ffmpeg_encoders: Vec<String>,
+    /// Filtres disponibles dans ffmpeg (ex: `zscale`, `libvmaf`), triés
+    #[serde(default)]
+    pub ffmpeg_filters: Vec<String>,
+}
+
+impl EncoderCapabilities {
+    /// Binaire de l'encodeur demandé (None pour un encodeur matériel, intégré à ffmpeg)
+    #[must_use]
+    pub fn encoder(&self, encoder: EncoderType) -> Option<&EncoderBinaryInfo> {
+        match encoder {
+            EncoderType::SvtAv1 => self.svt_av1.as_ref(),
+            EncoderType::Aom => self.aom.as_ref(),
+            EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => None,
+        }
+    }
+
+    /// Vérifier si un encodeur vidéo est utilisable
+    ///
+    /// Un encodeur matériel l'est si ffmpeg le liste dans `-encoders` ; la présence
+    /// du GPU n'est connue qu'au lancement de l'encodage.
+    #[must_use]
+    pub fn has_encoder(&self, encoder: EncoderType) -> bool {
+        match encoder.ffmpeg_encoder() {
+            Some(name) => self.ffmpeg_has_encoder(name),
+            None => self.encoder(encoder).is_some(),
+        }
+    }
+
+    /// Encodeurs matériels détectés dans ffmpeg
+    #[must_use]
+    pub fn hardware_encoders(&self) -> Vec<EncoderType> {
+        EncoderType::HARDWARE
+            .into_iter()
+            .filter(|encoder| self.has_encoder(*encoder))
+            .collect()
+    }
+
+    /// Vérifier si un encodeur vidéo supporte une option donnée
+    #[must_use]
+    pub fn encoder_supports(&self, encoder: EncoderType, option: &str) -> bool {
+        self.encoder(encoder)
+            .is_some_and(|info| info.supports(option))
+    }
+
+    /// Variante de SVT-AV1 installée, détectée par ses options propres au fork PSY
+    #[must_use]
+    pub fn svt_av1_variant(&self) -> Option<SvtAv1Variant> {
+        self.svt_av1.as_ref().map(|info| {
+            if info.supports("--noise-norm-strength") || info.supports("--psy-rd") {
+                SvtAv1Variant::Psy
+            } else {
+                SvtAv1Variant::Mainline
+            }
+        })
+    }
+
+    /// Vérifier si ffmpeg dispose d'un encodeur (audio ou vidéo)
+    #[must_use]
+    pub fn ffmpeg_has_encoder(&self, name: &str) -> bool {
+        self.ffmpeg_encoders
+            .binary_search_by(|e| e.as_str().cmp(name))
+            .is_ok()
+    }
+
+    /// Vérifier si ffmpeg dispose d'un filtre
+    #[must_use]
+    pub fn ffmpeg_has_filter(&self, name: &str) -> bool {
+        self.ffmpeg_filters
+            .binary_search_by(|f| f.as_str().cmp(name))
+            .is_ok()
+    }
+
+    /// Filtres de `names` absents de ffmpeg (aucun si la liste des filtres est illisible)
+    #[must_use]
+    pub fn missing_ffmpeg_filters<'a>(&self, names: &[&'a str]) -> Vec<&'a str> {
+        if self.ffmpeg_filters.is_empty() {
+            return Vec::new();
+        }
+        names
+            .iter()
+            .copied()
+            .filter(|name| !self.ffmpeg_has_filter(name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_lookup() {
+        let caps = EncoderCapabilities {
+            svt_av1: Some(EncoderBinaryInfo {
+                version: Some("SVT-AV1 v2.3.0".to_string()),
+                options: vec!["--crf".to_string(), "--film-grain".to_string()],
+                ..Default::default()
+            }),
+            aom: None,
+            ffmpeg_version: None,
+            ffmpeg_encoders: vec![
+                "aac".to_string(),
+                "av1_nvenc".to_string(),
+                "libopus".to_string(),
+            ],
+            ffmpeg_filters: vec!["libvmaf".to_string(), "tonemap".to_string()],
+        };
+
+        assert!(caps.encoder_supports(EncoderType::SvtAv1, "--film-grain"));
+        assert!(!caps.encoder_supports(EncoderType::SvtAv1, "--enable-hdr"));
+        assert!(!caps.has_encoder(EncoderType::Aom));
+        assert!(caps.ffmpeg_has_encoder("libopus"));
+        assert!(!caps.ffmpeg_has_encoder("libfdk_aac"));
+        assert!(caps.has_encoder(EncoderType::Nvenc));
+        assert!(!caps.has_encoder(EncoderType::Vaapi));
+        assert_eq!(caps.hardware_encoders(), [EncoderType::Nvenc]);
+        assert!(!caps.encoder_supports(EncoderType::Nvenc, "--tune"));
+        assert!(caps.ffmpeg_has_filter("tonemap"));
+        assert_eq!(
+            caps.missing_ffmpeg_filters(&["zscale", "tonemap"]),
+            ["zscale"]
+        );
+        assert!(EncoderCapabilities::default()
+            .missing_ffmpeg_filters(&["zscale"])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_svt_av1_variant_detection() {
+        let mut caps = EncoderCapabilities::default();
+        assert_eq!(caps.svt_av1_variant(), None);
+
+        caps.svt_av1 = Some(EncoderBinaryInfo {
+            version: Some("SVT-AV1 v2.3.0".to_string()),
+            options: vec!["--crf".to_string(), "--preset".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(caps.svt_av1_variant(), Some(SvtAv1Variant::Mainline));
+
+        caps.svt_av1 = Some(EncoderBinaryInfo {
+            version: Some("SVT-AV1-PSY v2.3.0-B".to_string()),
+            options: vec!["--crf".to_string(), "--noise-norm-strength".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(caps.svt_av1_variant(), Some(SvtAv1Variant::Psy));
+    }
+
+    #[test]
+    fn test_encoder_version_parse() {
+        assert_eq!(
+            EncoderVersion::parse("SVT-AV1-PSY v2.3.0-B (release)"),
+            Some(EncoderVersion::new(2, 3, 0))
+        );
+        assert_eq!(
+            EncoderVersion::parse("SVT-AV1 v0.9"),
+            Some(EncoderVersion::new(0, 9, 0))
+        );
+        assert_eq!(EncoderVersion::parse("SVT-AV1 (git)"), None);
+        assert!(EncoderVersion::new(1, 10, 0) > EncoderVersion::new(1, 9, 3));
+    }
+
+    #[test]
+    fn test_disabled_options() {
+        let mut info = EncoderBinaryInfo {
+            options: vec!["--progress".to_string(), "--tune".to_string()],
+            disabled_options: vec!["--tune".to_string()],
+            ..Default::default()
+        };
+        assert!(info.supports("--progress"));
+        assert!(!info.supports("--tune"));
+
+        // Sans liste d'options, seules les options désactivées sont refusées
+        info.options.clear();
+        assert!(info.accepts("--progress"));
+        assert!(!info.accepts("--tune"));
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+
+use super::EncodingConfig;
+
+/// Extension des listes de concaténation (demuxer concat de ffmpeg)
+pub const CONCAT_LIST_EXTENSION: &str = "ffconcat";
+
+/// La source est-elle une liste de concaténation ?
+#[must_use]
+pub fn is_concat_list(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(CONCAT_LIST_EXTENSION))
+}
+
+/// Options d'entrée ffmpeg pour une source : demuxer concat si besoin, puis celles du job
+///
+/// `-safe 0` autorise les chemins absolus écrits par [`format_concat_list`].
+#[must_use]
+pub fn source_input_args(input: &Path, config: &EncodingConfig) -> Vec<String> {
+    let mut args = concat_demuxer_args(input);
+    args.extend(config.ffmpeg_input_args());
+    args
+}
+
+/// Options d'entrée ffmpeg pour lire l'audio d'une source
+/// ([`EncodingConfig::ffmpeg_audio_input_args`])
+#[must_use]
+pub fn source_audio_input_args(input: &Path, config: &EncodingConfig) -> Vec<String> {
+    let mut args = concat_demuxer_args(input);
+    args.extend(config.ffmpeg_audio_input_args());
+    args
+}
+
+fn concat_demuxer_args(input: &Path) -> Vec<String> {
+    if is_concat_list(input) {
+        ["-f", "concat", "-safe", "0"].map(String::from).to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Chemin de la liste joignant des fichiers dont le premier est `first` : `<nom>.joined.ffconcat`
+#[must_use]
+pub fn concat_list_path(dir: &Path, first: &Path) -> PathBuf {
+    let stem = first
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    dir.join(format!("{stem}.joined.{CONCAT_LIST_EXTENSION}"))
+}
+
+/// Écrire une liste de concaténation (une directive `file` par source, dans l'ordre)
+///
+/// # Errors
+///
+/// Retourne une erreur si un chemin n'est pas en UTF-8 (non représentable dans la liste).
+pub fn format_concat_list(files: &[PathBuf]) -> Result<String, String> {
+    let mut content = String::from("ffconcat version 1.0\n");
+    for file in files {
+        let path = file
+            .to_str()
+            .ok_or_else(|| format!("Chemin non UTF-8: {}", file.display()))?;
+        // Entre apostrophes, seule l'apostrophe doit être échappée : '\''
+        content.push_str("file '");
+        content.push_str(&path.replace('\'', "'\\''"));
+        content.push_str("'\n");
+    }
+    Ok(content)
+}
+
+/// Lire les fichiers d'une liste de concaténation
+///
+/// Les chemins relatifs sont résolus depuis `base` (répertoire de la liste), comme le
+/// fait ffmpeg ; les autres directives (`duration`, `inpoint`...) sont ignorées.
+#[must_use]
+pub fn parse_concat_list(content: &str, base: &Path) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.strip_prefix("file"))
+        .filter(|rest| rest.starts_with([' ', '\t']))
+        .map(|rest| {
+            let path = PathBuf::from(unquote(rest.trim()));
+            if path.is_absolute() {
+                path
+            } else {
+                base.join(path)
+            }
+        })
+        .collect()
+}
+
+/// Retirer les apostrophes et les échappements `\` d'un token ffconcat
+fn unquote(token: &str) -> String {
+    let mut out = String::with_capacity(token.len());
+    let mut quoted = false;
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => quoted = !quoted,
+            '\\' if !quoted => {
+                if let Some(next) = chars.next() {
+                    out.push(next);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat_list_roundtrip() {
+        let files = vec![
+            PathBuf::from("/videos/Épisode 1 - l'arrivée.mkv"),
+            PathBuf::from("/videos/Épisode 1 [partie 2].mkv"),
+        ];
+        let content = format_concat_list(&files).unwrap();
+        assert!(content.starts_with("ffconcat version 1.0\n"));
+        assert!(content.contains("file '/videos/Épisode 1 - l'\\''arrivée.mkv'"));
+        assert_eq!(parse_concat_list(&content, Path::new("/ailleurs")), files);
+
+        // Chemins relatifs au répertoire de la liste, directives ignorées
+        let content = "file part1.mkv\nduration 10\nfile 'sous dossier/part2.mkv'\n";
+        assert_eq!(
+            parse_concat_list(content, Path::new("/videos")),
+            [
+                PathBuf::from("/videos/part1.mkv"),
+                PathBuf::from("/videos/sous dossier/part2.mkv")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_input_args_for_concat_list() {
+        let list = concat_list_path(Path::new("/videos"), Path::new("/videos/ep1.mkv"));
+        assert_eq!(list, PathBuf::from("/videos/ep1.joined.ffconcat"));
+        assert!(is_concat_list(&list));
+
+        let config = EncodingConfig {
+            input_params: vec!["-hwaccel".to_string(), "auto".to_string()],
+            ..EncodingConfig::default()
+        };
+        assert_eq!(
+            source_input_args(&list, &config),
+            ["-f", "concat", "-safe", "0", "-hwaccel", "auto"]
+        );
+        assert_eq!(
+            source_input_args(Path::new("/videos/ep1.mkv"), &config),
+            ["-hwaccel", "auto"]
+        );
+    }
+}
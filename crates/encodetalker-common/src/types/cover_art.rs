@@ -0,0 +1,105 @@
+use super::TrimRange;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Pochette intégrée à la sortie (bibliothèques de serveurs multimédia)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverArtMode {
+    /// Aucune pochette
+    #[default]
+    Off,
+    /// Copier les pochettes de la source (streams `attached_pic`)
+    Copy,
+    /// Extraire une frame de la source comme pochette
+    Poster,
+    /// Copier les pochettes de la source, ou extraire une frame si elle n'en a pas
+    Auto,
+}
+
+impl fmt::Display for CoverArtMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Off => write!(f, "Aucune"),
+            Self::Copy => write!(f, "Copie"),
+            Self::Poster => write!(f, "Frame extraite"),
+            Self::Auto => write!(f, "Auto"),
+        }
+    }
+}
+
+/// Réglages de la pochette d'un job
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoverArtConfig {
+    pub mode: CoverArtMode,
+    /// Position de la frame extraite, en pourcentage de la partie encodée
+    pub poster_position: f64,
+}
+
+impl Default for CoverArtConfig {
+    fn default() -> Self {
+        Self {
+            mode: CoverArtMode::Off,
+            poster_position: 10.0,
+        }
+    }
+}
+
+impl CoverArtConfig {
+    /// Vérifier la position de la frame extraite
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si la position sort de 0-100 %.
+    pub fn validate(self) -> Result<(), String> {
+        if (0.0..=100.0).contains(&self.poster_position) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Position de la pochette {}% hors plage (0-100%)",
+                self.poster_position
+            ))
+        }
+    }
+
+    /// Position (secondes dans la source) de la frame extraite, `duration` étant la
+    /// durée de la source
+    #[must_use]
+    pub fn poster_timestamp(self, trim: &TrimRange, duration: Option<f64>) -> f64 {
+        let start = trim.start.unwrap_or(0.0);
+        let end = trim.end.or(duration).unwrap_or(start);
+        start + (end - start).max(0.0) * self.poster_position / 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poster_timestamp() {
+        let config = CoverArtConfig {
+            mode: CoverArtMode::Poster,
+            poster_position: 25.0,
+        };
+        assert!((config.poster_timestamp(&TrimRange::default(), Some(400.0)) - 100.0).abs() < 1e-9);
+        let trim = TrimRange {
+            start: Some(100.0),
+            end: Some(300.0),
+        };
+        assert!((config.poster_timestamp(&trim, Some(400.0)) - 150.0).abs() < 1e-9);
+        assert!(config.poster_timestamp(&TrimRange::default(), None).abs() < 1e-9);
+
+        assert!(config.validate().is_ok());
+        let outside = CoverArtConfig {
+            poster_position: 120.0,
+            ..config
+        };
+        assert!(outside.validate().is_err());
+
+        let parsed: CoverArtConfig = serde_json::from_str(r#"{"mode": "auto"}"#).unwrap();
+        assert_eq!(parsed.mode, CoverArtMode::Auto);
+        assert!((parsed.poster_position - 10.0).abs() < 1e-9);
+    }
+}
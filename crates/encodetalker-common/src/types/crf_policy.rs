@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// CRF par défaut des sources jusqu'à une hauteur donnée (`[[encoding.crf_by_resolution]]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolutionCrf {
+    /// Hauteur maximale (pixels) couverte par cette règle
+    pub max_height: u32,
+    pub crf: u32,
+}
+
+/// CRF proposé pour une source de hauteur `height`
+///
+/// La règle retenue est celle du plus petit `max_height` couvrant la source ; None si
+/// aucune règle ne la couvre (le CRF de l'encodeur s'applique alors).
+#[must_use]
+pub fn crf_for_height(rules: &[ResolutionCrf], height: u32) -> Option<u32> {
+    rules
+        .iter()
+        .filter(|rule| height <= rule.max_height)
+        .min_by_key(|rule| rule.max_height)
+        .map(|rule| rule.crf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crf_for_height_picks_smallest_covering_rule() {
+        // Ordre quelconque dans la configuration
+        let rules = [
+            ResolutionCrf {
+                max_height: 1080,
+                crf: 30,
+            },
+            ResolutionCrf {
+                max_height: 576,
+                crf: 32,
+            },
+            ResolutionCrf {
+                max_height: 2160,
+                crf: 28,
+            },
+        ];
+        assert_eq!(crf_for_height(&rules, 480), Some(32));
+        assert_eq!(crf_for_height(&rules, 576), Some(32));
+        assert_eq!(crf_for_height(&rules, 720), Some(30));
+        assert_eq!(crf_for_height(&rules, 2160), Some(28));
+        assert_eq!(crf_for_height(&rules, 4320), None);
+        assert_eq!(crf_for_height(&[], 1080), None);
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Quand désentrelacer la source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DeinterlaceMode {
+    /// Seulement si l'entrelacement est détecté (`field_order` ou filtre `idet`)
+    #[default]
+    Auto,
+    /// Toujours, même si la source paraît progressive
+    Force,
+    /// Jamais
+    Off,
+}
+
+/// Filtre ffmpeg de désentrelacement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Deinterlacer {
+    #[default]
+    Yadif,
+    /// Plus lent que yadif, meilleur sur les mouvements
+    Bwdif,
+}
+
+/// Réglages de désentrelacement d'un job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DeinterlaceConfig {
+    pub mode: DeinterlaceMode,
+    pub filter: Deinterlacer,
+    /// Une frame par trame (double le framerate et le nombre de frames)
+    pub double_rate: bool,
+}
+
+impl DeinterlaceConfig {
+    /// Le filtre doit-il être appliqué, selon la détection sur la source ?
+    #[must_use]
+    pub fn applies(self, detected: bool) -> bool {
+        match self.mode {
+            DeinterlaceMode::Auto => detected,
+            DeinterlaceMode::Force => true,
+            DeinterlaceMode::Off => false,
+        }
+    }
+
+    /// Filtre ffmpeg (`-vf`) correspondant
+    #[must_use]
+    pub fn filter_arg(self) -> String {
+        let name = match self.filter {
+            Deinterlacer::Yadif => "yadif",
+            Deinterlacer::Bwdif => "bwdif",
+        };
+        let mode = if self.double_rate {
+            "send_field"
+        } else {
+            "send_frame"
+        };
+        format!("{name}=mode={mode}")
+    }
+
+    /// Facteur appliqué au framerate et au nombre de frames par le filtre
+    #[must_use]
+    pub fn rate_factor(self) -> u32 {
+        if self.double_rate {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+impl fmt::Display for DeinterlaceConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let filter = match self.filter {
+            Deinterlacer::Yadif => "yadif",
+            Deinterlacer::Bwdif => "bwdif",
+        };
+        let rate = if self.double_rate {
+            ", double framerate"
+        } else {
+            ""
+        };
+        match self.mode {
+            DeinterlaceMode::Auto => write!(f, "Auto ({filter}{rate})"),
+            DeinterlaceMode::Force => write!(f, "Forcé ({filter}{rate})"),
+            DeinterlaceMode::Off => write!(f, "Désactivé"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deinterlace_filter_and_mode() {
+        let auto = DeinterlaceConfig::default();
+        assert!(auto.applies(true));
+        assert!(!auto.applies(false));
+        assert_eq!(auto.filter_arg(), "yadif=mode=send_frame");
+        assert_eq!(auto.rate_factor(), 1);
+
+        let bwdif = DeinterlaceConfig {
+            mode: DeinterlaceMode::Force,
+            filter: Deinterlacer::Bwdif,
+            double_rate: true,
+        };
+        assert!(bwdif.applies(false));
+        assert_eq!(bwdif.filter_arg(), "bwdif=mode=send_field");
+        assert_eq!(bwdif.rate_factor(), 2);
+
+        let off = DeinterlaceConfig {
+            mode: DeinterlaceMode::Off,
+            ..auto
+        };
+        assert!(!off.applies(true));
+    }
+}
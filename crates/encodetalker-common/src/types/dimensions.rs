@@ -0,0 +1,72 @@
+/// Plus petite dimension acceptée par les encodeurs AV1
+pub const MIN_AV1_DIMENSION: u32 = 16;
+
+/// Plus grande dimension acceptée par les encodeurs AV1
+pub const MAX_AV1_DIMENSION: u32 = 65_536;
+
+/// Filtre ffmpeg ramenant largeur et hauteur au pair inférieur (sans effet si déjà paires)
+pub const EVEN_CROP_FILTER: &str = "crop=trunc(iw/2)*2:trunc(ih/2)*2";
+
+/// Dimensions envoyées à l'encodeur, après le recadrage au pair éventuel
+#[must_use]
+pub fn output_dimensions(width: u32, height: u32, crop_to_even: bool) -> (u32, u32) {
+    if crop_to_even {
+        (width & !1, height & !1)
+    } else {
+        (width, height)
+    }
+}
+
+/// Problème des dimensions de sortie d'une source (None = encodable telle quelle)
+///
+/// L'AV1 en 4:2:0 impose une largeur et une hauteur paires ; des dimensions impaires
+/// font échouer l'encodeur ou décalent la chroma d'une ligne.
+#[must_use]
+pub fn dimensions_warning(width: u32, height: u32, crop_to_even: bool) -> Option<String> {
+    let (w, h) = output_dimensions(width, height, crop_to_even);
+    if w < MIN_AV1_DIMENSION || h < MIN_AV1_DIMENSION {
+        return Some(format!(
+            "Dimensions {w}x{h} trop petites (minimum {MIN_AV1_DIMENSION}x{MIN_AV1_DIMENSION})"
+        ));
+    }
+    if w > MAX_AV1_DIMENSION || h > MAX_AV1_DIMENSION {
+        return Some(format!(
+            "Dimensions {w}x{h} trop grandes (maximum {MAX_AV1_DIMENSION}x{MAX_AV1_DIMENSION})"
+        ));
+    }
+    if w % 2 == 1 || h % 2 == 1 {
+        let (even_w, even_h) = output_dimensions(w, h, true);
+        return Some(format!(
+            "Dimensions impaires {w}x{h} : l'AV1 demande des dimensions paires (recadrage possible en {even_w}x{even_h})"
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimensions_warning() {
+        assert_eq!(dimensions_warning(1920, 1080, false), None);
+        assert_eq!(
+            dimensions_warning(1919, 817, false).as_deref(),
+            Some("Dimensions impaires 1919x817 : l'AV1 demande des dimensions paires (recadrage possible en 1918x816)")
+        );
+        // Recadrage au pair : plus d'avertissement, dimensions paires inchangées
+        assert_eq!(dimensions_warning(1919, 817, true), None);
+        assert_eq!(output_dimensions(1919, 817, true), (1918, 816));
+        assert_eq!(output_dimensions(1920, 1080, true), (1920, 1080));
+
+        assert!(dimensions_warning(8, 720, false)
+            .unwrap()
+            .contains("trop petites"));
+        assert!(dimensions_warning(15, 720, true)
+            .unwrap()
+            .contains("trop petites"));
+        assert!(dimensions_warning(70_000, 720, false)
+            .unwrap()
+            .contains("trop grandes"));
+    }
+}
@@ -1,46 +1,447 @@
-use super::{EncodingStats, JobStatus};
+use super::{
+    select_tracks_by_language, validate_language_codes, CoverArtConfig, DeinterlaceConfig,
+    EncodingPhase, EncodingStats, FailureCategory, JobStatus, KeyframeConfig, SubtitlePolicy,
+    TonemapConfig, TrimRange, VerifyReport, VfrMode,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Configuration d'encodage pour un job
+///
+/// Les champs absents prennent leur valeur par défaut, ce qui permet de ne décrire
+/// que les réglages modifiés dans un fichier de configuration de job.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EncodingConfig {
     /// Encodeur à utiliser (svt-av1, aom)
     pub encoder: EncoderType,
+    /// Traitement de la piste vidéo (ré-encodage ou copie)
+    #[serde(default)]
+    pub video_mode: VideoMode,
     /// Mode audio
     pub audio_mode: AudioMode,
     /// Streams audio à inclure (None = tous)
     pub audio_streams: Option<Vec<usize>>,
     /// Streams de sous-titres à inclure (None = tous)
     pub subtitle_streams: Option<Vec<usize>>,
+    /// Langues des pistes audio à garder, résolues sur chaque source (vide = toutes)
+    ///
+    /// Ignoré si `audio_streams` est défini ; toutes les pistes sont gardées si aucune
+    /// ne correspond.
+    #[serde(default)]
+    pub audio_languages: Vec<String>,
+    /// Langues des pistes de sous-titres à garder (mêmes règles que `audio_languages`)
+    #[serde(default)]
+    pub subtitle_languages: Vec<String>,
     /// Paramètres spécifiques à l'encodeur
     pub encoder_params: EncoderParams,
     /// Activer le calcul VMAF après encodage (comparaison frame par frame)
     pub enable_vmaf: bool,
+    /// Mesurer aussi PSNR et SSIM, dans la même passe que le VMAF (lancée même si
+    /// `enable_vmaf` est faux)
+    #[serde(default)]
+    pub quality_metrics: bool,
+    /// Extrait à encoder (par défaut : tout le fichier)
+    #[serde(default)]
+    pub trim: TrimRange,
+    /// Découpage de la sortie en plusieurs fichiers
+    #[serde(default)]
+    pub split: SplitMode,
+    /// Désentrelacement de la source (auto par défaut)
+    #[serde(default)]
+    pub deinterlace: DeinterlaceConfig,
+    /// Recadrer d'une ligne ou d'une colonne les sources aux dimensions impaires
+    #[serde(default)]
+    pub crop_to_even: bool,
+    /// Traitement des sources à framerate variable
+    #[serde(default)]
+    pub vfr_mode: VfrMode,
+    /// Conversion HDR → SDR des sources HDR (désactivée par défaut)
+    #[serde(default)]
+    pub tonemap: TonemapConfig,
+    /// Sous-titres que le conteneur de sortie n'accepte pas en copie
+    #[serde(default)]
+    pub incompatible_subtitles: SubtitlePolicy,
+    /// Intervalle de keyframes (seek dans les sorties destinées au streaming)
+    #[serde(default)]
+    pub keyframes: KeyframeConfig,
+    /// Pochette intégrée à la sortie (copiée de la source ou frame extraite)
+    #[serde(default)]
+    pub cover_art: CoverArtConfig,
+    /// Options ffmpeg passées telles quelles avant chaque `-i` lisant la source
+    /// (ex. `-probesize 100M`, `-itsoffset 0.2`)
+    #[serde(default)]
+    pub input_params: Vec<String>,
+    /// Options ffmpeg ajoutées aux précédentes pour la seule lecture de l'audio
+    /// (ex. `-itsoffset 0.2` pour décaler le son sans décaler l'image)
+    #[serde(default)]
+    pub audio_input_params: Vec<String>,
+    /// Comptage précis des frames pour ce job (None = `encoding.precise_frame_count` du daemon)
+    #[serde(default)]
+    pub precise_frame_count: Option<bool>,
+    /// Variables d'environnement des processus lancés pour le job (ffmpeg, encodeur),
+    /// prioritaires sur celles héritées du daemon (ex. `SVT_LOG = "1"`)
+    #[serde(default)]
+    pub env: HashMap<String, String>,
 }
 
 impl Default for EncodingConfig {
     fn default() -> Self {
         Self {
             encoder: EncoderType::SvtAv1,
+            video_mode: VideoMode::default(),
             audio_mode: AudioMode::Opus { bitrate: 128 },
             audio_streams: None,
             subtitle_streams: None,
+            audio_languages: Vec::new(),
+            subtitle_languages: Vec::new(),
             encoder_params: EncoderParams::default(),
             enable_vmaf: true,
+            quality_metrics: false,
+            trim: TrimRange::default(),
+            split: SplitMode::default(),
+            deinterlace: DeinterlaceConfig::default(),
+            crop_to_even: false,
+            vfr_mode: VfrMode::default(),
+            tonemap: TonemapConfig::default(),
+            incompatible_subtitles: SubtitlePolicy::default(),
+            keyframes: KeyframeConfig::default(),
+            cover_art: CoverArtConfig::default(),
+            input_params: Vec::new(),
+            audio_input_params: Vec::new(),
+            precise_frame_count: None,
+            env: HashMap::new(),
         }
     }
 }
 
+impl EncodingConfig {
+    /// CRF maximal, commun à tous les encodeurs ([`EncoderType::crf_range`])
+    pub const MAX_CRF: u32 = 63;
+
+    /// Vérifier la cohérence de la configuration avant mise en queue
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message décrivant le premier réglage invalide.
+    pub fn validate(&self) -> Result<(), String> {
+        self.trim.validate()?;
+        if self
+            .input_params
+            .iter()
+            .chain(&self.audio_input_params)
+            .any(|param| param == "-i")
+        {
+            return Err("Les options d'entrée ne peuvent pas ajouter d'input (-i)".to_string());
+        }
+        validate_language_codes(&self.audio_languages)?;
+        validate_language_codes(&self.subtitle_languages)?;
+        self.validate_env()?;
+        if let SplitMode::Interval { secs: 0 } = self.split {
+            return Err("L'intervalle de découpage doit être non nul".to_string());
+        }
+        if self.video_mode == VideoMode::Copy {
+            return Ok(());
+        }
+
+        let params = &self.encoder_params;
+        let crf_range = self.encoder.crf_range();
+        if !crf_range.contains(&params.crf) {
+            return Err(format!(
+                "CRF {} hors plage pour {} ({}-{})",
+                params.crf,
+                self.encoder,
+                crf_range.start(),
+                crf_range.end()
+            ));
+        }
+        let max_preset = self.encoder.max_preset();
+        if params.preset > max_preset {
+            return Err(format!(
+                "Preset {} hors plage pour {} (0-{max_preset})",
+                params.preset, self.encoder
+            ));
+        }
+        params.encoding_mode.validate()?;
+        self.keyframes.validate()?;
+        self.cover_art.validate()?;
+        self.tonemap.validate()?;
+        if params.threads == Some(0) {
+            return Err("Le nombre de threads doit être non nul".to_string());
+        }
+        self.validate_extra_params()?;
+        if !params.tune.is_supported_by(self.encoder) {
+            return Err(format!(
+                "Tune {} non supporté par {}",
+                params.tune, self.encoder
+            ));
+        }
+        Ok(())
+    }
+
+    /// Vérifier les variables d'environnement : noms `[A-Za-z_][A-Za-z0-9_]*`, valeurs
+    /// sans caractère nul
+    fn validate_env(&self) -> Result<(), String> {
+        for (key, value) in &self.env {
+            let valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_key {
+                return Err(format!(
+                    "Nom de variable d'environnement \"{key}\" invalide"
+                ));
+            }
+            if value.contains('\0') {
+                return Err(format!(
+                    "La variable d'environnement {key} contient un caractère nul"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Traitement vidéo produit, pour l'affichage : `SVT-AV1`, `libaom` ou `Copie vidéo`
+    #[must_use]
+    pub fn video_label(&self) -> &'static str {
+        match (self.video_mode, self.encoder) {
+            (VideoMode::Copy, _) => "Copie vidéo",
+            (_, EncoderType::SvtAv1) => "SVT-AV1",
+            (_, EncoderType::Aom) => "libaom",
+            (_, EncoderType::Nvenc) => "NVENC",
+            (_, EncoderType::Qsv) => "QSV",
+            (_, EncoderType::Vaapi) => "VA-API",
+        }
+    }
+
+    /// Codec audio produit, pour l'affichage : `Opus 128k`, `Copie`...
+    #[must_use]
+    pub fn audio_label(&self) -> String {
+        match &self.audio_mode {
+            AudioMode::Opus { bitrate } => format!("Opus {bitrate}k"),
+            AudioMode::Copy => "Copie".to_string(),
+            AudioMode::None => "Sans audio".to_string(),
+            AudioMode::Custom { codec, bitrate } => format!("{codec} {bitrate}k"),
+        }
+    }
+
+    /// Étapes d'un encodage avec cette configuration, dans l'ordre du pipeline
+    ///
+    /// L'audio est encodé pendant la vidéo : son étape ne couvre que l'attente éventuelle
+    /// de sa fin. Le VMAF est sans objet quand la vidéo est copiée.
+    #[must_use]
+    pub fn phase_plan(&self) -> Vec<EncodingPhase> {
+        let copy_video = self.video_mode == VideoMode::Copy;
+        let mut plan = Vec::new();
+        if !copy_video {
+            plan.push(EncodingPhase::Video);
+        }
+        if self.audio_mode.has_audio() {
+            plan.push(EncodingPhase::Audio);
+        }
+        plan.push(EncodingPhase::Muxing);
+        if (self.enable_vmaf || self.quality_metrics) && !copy_video {
+            plan.push(EncodingPhase::Vmaf);
+        }
+        plan
+    }
+
+    /// Comptage précis des frames au probe : réglage du job, sinon `default` (celui du daemon)
+    #[must_use]
+    pub fn precise_frame_count_or(&self, default: bool) -> bool {
+        self.precise_frame_count.unwrap_or(default)
+    }
+
+    /// Pistes audio à garder (index relatifs), `languages` étant la langue de chaque
+    /// piste audio de la source (None = toutes)
+    #[must_use]
+    pub fn resolve_audio_streams(&self, languages: &[Option<String>]) -> Option<Vec<usize>> {
+        self.audio_streams
+            .clone()
+            .or_else(|| select_tracks_by_language(&self.audio_languages, languages))
+    }
+
+    /// Pistes de sous-titres à garder (index relatifs, None = toutes)
+    #[must_use]
+    pub fn resolve_subtitle_streams(&self, languages: &[Option<String>]) -> Option<Vec<usize>> {
+        self.subtitle_streams
+            .clone()
+            .or_else(|| select_tracks_by_language(&self.subtitle_languages, languages))
+    }
+
+    /// Vérifier les paramètres extra de l'encodeur
+    ///
+    /// Le premier doit être une option (`-x` ou `--xxx`), et aucune ne doit remplacer
+    /// celles du pipeline ([`EncoderType::reserved_options`]), y compris sous la forme
+    /// `--option=valeur`.
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message désignant le premier paramètre refusé.
+    pub fn validate_extra_params(&self) -> Result<(), String> {
+        let params = &self.encoder_params.extra_params;
+        if let Some(first) = params.first().filter(|first| !first.starts_with('-')) {
+            return Err(format!(
+                "Paramètre extra '{first}' : une option (-x ou --xxx) est attendue"
+            ));
+        }
+        let reserved = self.encoder.reserved_options();
+        for param in params {
+            let option = param.split('=').next().unwrap_or(param);
+            if reserved.contains(&option) {
+                return Err(format!(
+                    "Option {option} gérée par EncodeTalker, non modifiable dans les paramètres extra de {}",
+                    self.encoder
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Options d'entrée ffmpeg pour la source : options personnalisées puis découpe
+    #[must_use]
+    pub fn ffmpeg_input_args(&self) -> Vec<String> {
+        let mut args = self.input_params.clone();
+        args.extend(self.trim.ffmpeg_input_args());
+        args
+    }
+
+    /// Options d'entrée ffmpeg pour l'audio de la source : celles de la vidéo, précédées
+    /// des options propres à l'audio
+    #[must_use]
+    pub fn ffmpeg_audio_input_args(&self) -> Vec<String> {
+        let mut args = self.audio_input_params.clone();
+        args.extend(self.ffmpeg_input_args());
+        args
+    }
+
+    /// Options vidéo ffmpeg d'un encodeur matériel : codec, débit, vitesse, format 10 bits,
+    /// paramètres extra puis intervalle de keyframes au framerate `fps` (vide pour SVT-AV1
+    /// et libaom)
+    ///
+    /// Avec VA-API, le format est imposé par le filtre d'envoi vers le GPU
+    /// ([`VAAPI_UPLOAD_FILTER`]).
+    #[must_use]
+    pub fn hardware_video_args(&self, fps: Option<f64>) -> Vec<String> {
+        let Some(codec) = self.encoder.ffmpeg_encoder() else {
+            return Vec::new();
+        };
+        let params = &self.encoder_params;
+        let mut args = vec!["-c:v".to_string(), codec.to_string()];
+        args.extend(params.encoding_mode.hardware_args(self.encoder, params.crf));
+        args.extend(self.encoder.hardware_preset_args(params.preset));
+        if self.encoder != EncoderType::Vaapi {
+            args.extend(["-pix_fmt".to_string(), "p010le".to_string()]);
+        }
+        args.extend(params.extra_params.iter().cloned());
+        args.extend(self.keyframes.encoder_args(self.encoder, fps));
+        args
+    }
+}
+
+/// Filtre ffmpeg envoyant les frames au GPU pour `av1_vaapi` (10 bits)
+pub const VAAPI_UPLOAD_FILTER: &str = "format=p010,hwupload";
+
+/// Périphérique DRM par défaut des encodages VA-API (`encoder.hardware.vaapi_device`)
+pub const DEFAULT_VAAPI_DEVICE: &str = "/dev/dri/renderD128";
+
 /// Type d'encodeur vidéo
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EncoderType {
     /// SVT-AV1 (recommandé, rapide)
     SvtAv1,
     /// libaom AV1 (plus lent, meilleure qualité)
     Aom,
+    /// NVIDIA NVENC (`av1_nvenc` de ffmpeg, GPU RTX 40 et plus)
+    Nvenc,
+    /// Intel Quick Sync (`av1_qsv` de ffmpeg, GPU Arc et iGPU récents)
+    Qsv,
+    /// VA-API (`av1_vaapi` de ffmpeg, GPU AMD et Intel sous Linux)
+    Vaapi,
+}
+
+impl EncoderType {
+    /// Encodeurs matériels, dans l'ordre du dialogue
+    pub const HARDWARE: [Self; 3] = [Self::Nvenc, Self::Qsv, Self::Vaapi];
+
+    /// Encodeur ffmpeg d'un encodeur matériel (None pour SVT-AV1 et libaom, qui reçoivent
+    /// la vidéo décodée par un pipe)
+    #[must_use]
+    pub fn ffmpeg_encoder(self) -> Option<&'static str> {
+        match self {
+            Self::SvtAv1 | Self::Aom => None,
+            Self::Nvenc => Some("av1_nvenc"),
+            Self::Qsv => Some("av1_qsv"),
+            Self::Vaapi => Some("av1_vaapi"),
+        }
+    }
+
+    /// Encodeur matériel, piloté directement par ffmpeg ?
+    #[must_use]
+    pub fn is_hardware(self) -> bool {
+        self.ffmpeg_encoder().is_some()
+    }
+
+    /// Preset le plus rapide (SVT-AV1 `--preset`, aomenc `--cpu-used`)
+    ///
+    /// Les encodeurs matériels ont 7 niveaux, ramenés à la même échelle (0 = le plus lent).
+    #[must_use]
+    pub fn max_preset(self) -> u32 {
+        match self {
+            Self::SvtAv1 => 13,
+            Self::Aom => 8,
+            Self::Nvenc | Self::Qsv | Self::Vaapi => 6,
+        }
+    }
+
+    /// Plage du CRF (dialogue et validation)
+    ///
+    /// SVT-AV1 (`--crf`) et aomenc (`--cq-level`) acceptent 0-63 ; les encodeurs matériels
+    /// reçoivent la même échelle, convertie par [`EncodingMode::hardware_quality`].
+    #[must_use]
+    pub fn crf_range(self) -> RangeInclusive<u32> {
+        match self {
+            Self::SvtAv1 | Self::Aom | Self::Nvenc | Self::Qsv | Self::Vaapi => {
+                0..=EncodingConfig::MAX_CRF
+            }
+        }
+    }
+
+    /// Options ffmpeg de vitesse d'un encodeur matériel (vide pour les autres)
+    ///
+    /// NVENC : `-preset p7` (lent) à `p1` ; QSV : `-preset 1` (veryslow) à `7` (veryfast) ;
+    /// VA-API : `-compression_level 1` à `7`.
+    #[must_use]
+    pub fn hardware_preset_args(self, preset: u32) -> Vec<String> {
+        let level = preset.min(self.max_preset());
+        match self {
+            Self::SvtAv1 | Self::Aom => Vec::new(),
+            Self::Nvenc => vec!["-preset".to_string(), format!("p{}", 7 - level)],
+            Self::Qsv => vec!["-preset".to_string(), (level + 1).to_string()],
+            Self::Vaapi => vec!["-compression_level".to_string(), (level + 1).to_string()],
+        }
+    }
+
+    /// Options gérées par le pipeline (entrée, sortie, progression, passes), interdites
+    /// dans les paramètres extra
+    #[must_use]
+    pub fn reserved_options(self) -> &'static [&'static str] {
+        match self {
+            Self::SvtAv1 => &["-i", "-b", "--input", "--output", "--progress"],
+            Self::Aom => &["-o", "--output", "--passes", "--pass", "--fpf"],
+            Self::Nvenc | Self::Qsv | Self::Vaapi => &[
+                "-i",
+                "-c:v",
+                "-vcodec",
+                "-f",
+                "-progress",
+                "-pix_fmt",
+                "-vf",
+            ],
+        }
+    }
 }
 
 impl std::fmt::Display for EncoderType {
@@ -48,6 +449,41 @@ impl std::fmt::Display for EncoderType {
         match self {
             EncoderType::SvtAv1 => write!(f, "SVT-AV1"),
             EncoderType::Aom => write!(f, "libaom AV1"),
+            EncoderType::Nvenc => write!(f, "NVENC AV1 (GPU)"),
+            EncoderType::Qsv => write!(f, "Quick Sync AV1 (GPU)"),
+            EncoderType::Vaapi => write!(f, "VA-API AV1 (GPU)"),
+        }
+    }
+}
+
+/// Mode de traitement de la piste vidéo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum VideoMode {
+    /// Ré-encoder en AV1 avec l'encodeur choisi
+    #[default]
+    Encode,
+    /// Copier le stream vidéo source tel quel (seul l'audio est traité)
+    Copy,
+}
+
+/// Découpage de la sortie en segments encodés séparément
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SplitMode {
+    /// Un seul fichier de sortie
+    #[default]
+    None,
+    /// Un fichier par chapitre de la source
+    Chapters,
+    /// Un fichier par tranche de durée fixe (en secondes)
+    Interval { secs: u32 },
+}
+
+impl std::fmt::Display for SplitMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "Aucun"),
+            Self::Chapters => write!(f, "Par chapitre"),
+            Self::Interval { secs } => write!(f, "Toutes les {} min", secs / 60),
         }
     }
 }
@@ -61,6 +497,16 @@ pub enum AudioMode {
     Copy,
     /// Custom (pour usage futur)
     Custom { codec: String, bitrate: u32 },
+    /// Pas d'audio : sortie vidéo seule (sous-titres conservés)
+    None,
+}
+
+impl AudioMode {
+    /// La sortie contient-elle de l'audio ?
+    #[must_use]
+    pub fn has_audio(&self) -> bool {
+        !matches!(self, Self::None)
+    }
 }
 
 impl Default for AudioMode {
@@ -79,10 +525,48 @@ pub enum VideoContentType {
     Anime,
     /// Live Action / Vidéo réelle (tune 0, variance-boost 2)
     LiveAction,
-    /// Film granuleux / Contenu avec grain filmique (préservation du grain)
+    /// Film granuleux / Contenu avec grain filmique (préservation du grain, à combiner
+    /// avec le tune subjectif)
     GrainedFilm,
 }
 
+impl VideoContentType {
+    /// Options SVT-AV1 propres au type de contenu (option, valeur)
+    ///
+    /// Plusieurs options n'existent que dans SVT-AV1-PSY : le pipeline ignore celles
+    /// que le binaire installé ne supporte pas.
+    #[must_use]
+    pub fn svt_av1_args(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            // Film granuleux : préservation du grain filmique
+            Self::GrainedFilm => &[
+                ("--enable-cdef", "0"),
+                ("--enable-restoration", "0"),
+                ("--enable-tf", "0"),
+                ("--spy-rd", "1"),
+                ("--noise-norm-strength", "3"),
+                ("--qm-min", "10"),
+                ("--qp-scale-compress-strength", "3"),
+                ("--scm", "0"),
+                ("--psy-rd", "4.0"),
+                ("--hbd-mds", "1"),
+            ],
+            // Anime : réduction de bruit agressive
+            Self::Anime => &[
+                ("--qm-min", "8"),
+                ("--noise-norm-strength", "4"),
+                ("--enable-dlf", "2"),
+            ],
+            // Default et LiveAction : paramètres standards
+            Self::Default | Self::LiveAction => &[
+                ("--qm-min", "8"),
+                ("--noise-norm-strength", "1"),
+                ("--enable-dlf", "2"),
+            ],
+        }
+    }
+}
+
 impl std::fmt::Display for VideoContentType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -96,6 +580,7 @@ impl std::fmt::Display for VideoContentType {
 
 /// Paramètres spécifiques aux encodeurs
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EncoderParams {
     /// CRF (Constant Rate Factor) - qualité (0-63, plus bas = meilleure qualité)
     pub crf: u32,
@@ -107,6 +592,15 @@ pub struct EncoderParams {
     pub extra_params: Vec<String>,
     /// Type de contenu vidéo (anime, live-action, ou par défaut)
     pub content_type: VideoContentType,
+    /// Métrique d'optimisation (`--tune`), à valider contre l'encodeur
+    #[serde(default)]
+    pub tune: Tune,
+    /// Réglages propres à libaom (ignorés par SVT-AV1)
+    #[serde(default)]
+    pub aom: AomParams,
+    /// Contrôle de débit (CRF seul, CRF plafonné ou débit moyen)
+    #[serde(default)]
+    pub encoding_mode: EncodingMode,
 }
 
 impl Default for EncoderParams {
@@ -117,10 +611,384 @@ impl Default for EncoderParams {
             threads: None, // Auto par défaut
             extra_params: vec![],
             content_type: VideoContentType::default(),
+            tune: Tune::default(),
+            aom: AomParams::default(),
+            encoding_mode: EncodingMode::default(),
         }
     }
 }
 
+impl EncoderParams {
+    /// Monter ou descendre le CRF d'un cran, sans sortir de la plage de l'encodeur
+    pub fn step_crf(&mut self, encoder: EncoderType, increment: bool) {
+        let range = encoder.crf_range();
+        let crf = if increment {
+            self.crf.saturating_add(1)
+        } else {
+            self.crf.saturating_sub(1)
+        };
+        self.crf = crf.clamp(*range.start(), *range.end());
+    }
+
+    /// `--tune=<métrique>` d'aomenc (None si les paramètres extra fixent déjà `--tune`,
+    /// pour ne pas le passer deux fois)
+    #[must_use]
+    pub fn aom_tune_arg(&self) -> Option<String> {
+        let overridden = self
+            .extra_params
+            .iter()
+            .any(|param| param == "--tune" || param.starts_with("--tune="));
+        (!overridden).then(|| format!("--tune={}", self.tune.arg_or_default(EncoderType::Aom)))
+    }
+}
+
+/// Contrôle de débit de l'encodeur (débits en kbps)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum EncodingMode {
+    /// Qualité constante (CRF), sans limite de débit
+    #[default]
+    Crf,
+    /// CRF avec un débit maximal, pour le streaming
+    ConstrainedQuality { max_bitrate: u32 },
+    /// Débit moyen visé (VBR), avec un plafond optionnel
+    AverageBitrate {
+        bitrate: u32,
+        #[serde(default)]
+        max_bitrate: Option<u32>,
+    },
+}
+
+impl EncodingMode {
+    /// Vérifier les débits du mode
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si un débit est nul ou si le plafond n'est pas au-dessus
+    /// du débit moyen.
+    pub fn validate(self) -> Result<(), String> {
+        match self {
+            Self::Crf => Ok(()),
+            Self::ConstrainedQuality { max_bitrate: 0 } => {
+                Err("Le débit maximal doit être non nul".to_string())
+            }
+            Self::AverageBitrate { bitrate: 0, .. } => {
+                Err("Le débit moyen doit être non nul".to_string())
+            }
+            Self::AverageBitrate {
+                bitrate,
+                max_bitrate: Some(max),
+            } if max <= bitrate => Err(format!(
+                "Le débit maximal ({max} kbps) doit dépasser le débit moyen ({bitrate} kbps)"
+            )),
+            Self::ConstrainedQuality { .. } | Self::AverageBitrate { .. } => Ok(()),
+        }
+    }
+
+    /// Plafond en pourcentage du débit moyen (`--maxsection-pct`)
+    fn max_section_pct(bitrate: u32, max_bitrate: u32) -> u64 {
+        u64::from(max_bitrate) * 100 / u64::from(bitrate.max(1))
+    }
+
+    /// Options de contrôle de débit de `SvtAv1EncApp`
+    #[must_use]
+    pub fn svt_av1_args(self, crf: u32) -> Vec<String> {
+        match self {
+            Self::Crf => vec!["--crf".to_string(), crf.to_string()],
+            // `--mbr` plafonne le débit en mode CRF
+            Self::ConstrainedQuality { max_bitrate } => vec![
+                "--crf".to_string(),
+                crf.to_string(),
+                "--mbr".to_string(),
+                max_bitrate.to_string(),
+            ],
+            Self::AverageBitrate {
+                bitrate,
+                max_bitrate,
+            } => {
+                let mut args = vec![
+                    "--rc".to_string(),
+                    "1".to_string(),
+                    "--tbr".to_string(),
+                    bitrate.to_string(),
+                ];
+                if let Some(max) = max_bitrate {
+                    args.push("--maxsection-pct".to_string());
+                    args.push(Self::max_section_pct(bitrate, max).to_string());
+                }
+                args
+            }
+        }
+    }
+
+    /// Options de contrôle de débit d'aomenc
+    #[must_use]
+    pub fn aom_args(self, crf: u32) -> Vec<String> {
+        match self {
+            Self::Crf => vec!["--end-usage=q".to_string(), format!("--cq-level={crf}")],
+            // En mode cq, `--target-bitrate` est le plafond de débit
+            Self::ConstrainedQuality { max_bitrate } => vec![
+                "--end-usage=cq".to_string(),
+                format!("--cq-level={crf}"),
+                format!("--target-bitrate={max_bitrate}"),
+            ],
+            Self::AverageBitrate {
+                bitrate,
+                max_bitrate,
+            } => {
+                let mut args = vec![
+                    "--end-usage=vbr".to_string(),
+                    format!("--target-bitrate={bitrate}"),
+                ];
+                if let Some(max) = max_bitrate {
+                    args.push(format!(
+                        "--maxsection-pct={}",
+                        Self::max_section_pct(bitrate, max)
+                    ));
+                }
+                args
+            }
+        }
+    }
+
+    /// Quantificateur d'un encodeur matériel pour un CRF (0-63)
+    ///
+    /// NVENC (`-cq`) et QSV (`-global_quality`) attendent 1-51, VA-API (`-qp`) l'index
+    /// AV1 0-255, dont le CRF de SVT-AV1 est le quart.
+    #[must_use]
+    pub fn hardware_quality(encoder: EncoderType, crf: u32) -> u32 {
+        let crf = crf.min(EncodingConfig::MAX_CRF);
+        match encoder {
+            EncoderType::Vaapi => (crf * 4).min(255),
+            _ => (crf * 51 / EncodingConfig::MAX_CRF).max(1),
+        }
+    }
+
+    /// Options ffmpeg de contrôle de débit d'un encodeur matériel (vide pour les autres)
+    #[must_use]
+    pub fn hardware_args(self, encoder: EncoderType, crf: u32) -> Vec<String> {
+        let quality = Self::hardware_quality(encoder, crf).to_string();
+        let mut args: Vec<String> = match (encoder, self) {
+            (EncoderType::SvtAv1 | EncoderType::Aom, _) => return Vec::new(),
+            (EncoderType::Nvenc, Self::Crf | Self::ConstrainedQuality { .. }) => {
+                vec![
+                    "-rc".into(),
+                    "vbr".into(),
+                    "-cq".into(),
+                    quality,
+                    "-b:v".into(),
+                    "0".into(),
+                ]
+            }
+            (EncoderType::Qsv, Self::Crf | Self::ConstrainedQuality { .. }) => {
+                vec!["-global_quality".into(), quality]
+            }
+            (EncoderType::Vaapi, Self::Crf) => {
+                vec!["-rc_mode".into(), "CQP".into(), "-qp".into(), quality]
+            }
+            (EncoderType::Vaapi, Self::ConstrainedQuality { .. }) => {
+                vec![
+                    "-rc_mode".into(),
+                    "QVBR".into(),
+                    "-global_quality".into(),
+                    quality,
+                ]
+            }
+            (_, Self::AverageBitrate { bitrate, .. }) => {
+                let mut args = Vec::new();
+                match encoder {
+                    EncoderType::Nvenc => args.extend(["-rc".to_string(), "vbr".to_string()]),
+                    EncoderType::Vaapi => args.extend(["-rc_mode".to_string(), "VBR".to_string()]),
+                    _ => {}
+                }
+                args.extend(["-b:v".to_string(), format!("{bitrate}k")]);
+                args
+            }
+        };
+        let max_bitrate = match self {
+            Self::Crf => None,
+            Self::ConstrainedQuality { max_bitrate } => Some(max_bitrate),
+            Self::AverageBitrate { max_bitrate, .. } => max_bitrate,
+        };
+        if let Some(max) = max_bitrate {
+            args.extend([
+                "-maxrate".to_string(),
+                format!("{max}k"),
+                "-bufsize".to_string(),
+                format!("{}k", u64::from(max) * 2),
+            ]);
+        }
+        args
+    }
+}
+
+impl std::fmt::Display for EncodingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Crf => write!(f, "CRF (sans plafond)"),
+            Self::ConstrainedQuality { max_bitrate } => {
+                write!(f, "CRF plafonné à {max_bitrate} kbps")
+            }
+            Self::AverageBitrate {
+                bitrate,
+                max_bitrate: None,
+            } => write!(f, "Débit moyen {bitrate} kbps"),
+            Self::AverageBitrate {
+                bitrate,
+                max_bitrate: Some(max),
+            } => write!(f, "Débit moyen {bitrate} kbps (max {max} kbps)"),
+        }
+    }
+}
+
+/// Métrique d'optimisation de l'encodeur (`--tune`)
+///
+/// Les tunes VMAF de libaom nécessitent un build avec `CONFIG_TUNE_VMAF`
+/// (absent du build par défaut) : passer par les paramètres extra.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Tune {
+    /// Qualité visuelle subjective (SVT-AV1 `--tune 0`)
+    Subjective,
+    /// PSNR (SVT-AV1 `--tune 1`, défaut d'aomenc)
+    Psnr,
+    /// SSIM (SVT-AV1 `--tune 2`)
+    Ssim,
+    /// SSIM subjectif (SVT-AV1-PSY `--tune 3`)
+    #[default]
+    SubjectiveSsim,
+}
+
+impl Tune {
+    /// Tunes supportés par un encodeur, dans l'ordre du dialogue
+    #[must_use]
+    pub fn options_for(encoder: EncoderType) -> &'static [Tune] {
+        match encoder {
+            EncoderType::SvtAv1 => &[
+                Self::Subjective,
+                Self::Psnr,
+                Self::Ssim,
+                Self::SubjectiveSsim,
+            ],
+            EncoderType::Aom => &[Self::Psnr, Self::Ssim],
+            EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => &[],
+        }
+    }
+
+    /// Tune utilisé par défaut pour un encodeur
+    #[must_use]
+    pub fn default_for(encoder: EncoderType) -> Self {
+        match encoder {
+            EncoderType::SvtAv1 => Self::SubjectiveSsim,
+            EncoderType::Aom | EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => {
+                Self::Psnr
+            }
+        }
+    }
+
+    /// Le tune est-il supporté par l'encodeur ? (toujours vrai pour un encodeur matériel,
+    /// qui l'ignore)
+    #[must_use]
+    pub fn is_supported_by(self, encoder: EncoderType) -> bool {
+        encoder.is_hardware() || Self::options_for(encoder).contains(&self)
+    }
+
+    /// Valeur passée à `--tune` (None si non supporté par l'encodeur)
+    #[must_use]
+    pub fn encoder_arg(self, encoder: EncoderType) -> Option<&'static str> {
+        match (encoder, self) {
+            (EncoderType::SvtAv1, Self::Subjective) => Some("0"),
+            (EncoderType::SvtAv1, Self::Psnr) => Some("1"),
+            (EncoderType::SvtAv1, Self::Ssim) => Some("2"),
+            (EncoderType::SvtAv1, Self::SubjectiveSsim) => Some("3"),
+            (EncoderType::Aom, Self::Psnr) => Some("psnr"),
+            (EncoderType::Aom, Self::Ssim) => Some("ssim"),
+            (EncoderType::Aom | EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi, _) => {
+                None
+            }
+        }
+    }
+
+    /// Valeur passée à `--tune`, avec repli sur le défaut de l'encodeur si non supporté
+    #[must_use]
+    pub fn arg_or_default(self, encoder: EncoderType) -> &'static str {
+        self.encoder_arg(encoder)
+            .or_else(|| Self::default_for(encoder).encoder_arg(encoder))
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for Tune {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Subjective => write!(f, "Subjectif"),
+            Self::Psnr => write!(f, "PSNR"),
+            Self::Ssim => write!(f, "SSIM"),
+            Self::SubjectiveSsim => write!(f, "SSIM subjectif"),
+        }
+    }
+}
+
+/// Réglages qualité de libaom (None = valeur par défaut d'aomenc)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct AomParams {
+    /// Nombre max de frames pour le filtrage temporel ARNR (0-15)
+    #[serde(default)]
+    pub arnr_maxframes: Option<u32>,
+    /// Delta de quantification sur la chroma
+    #[serde(default)]
+    pub enable_chroma_deltaq: bool,
+    /// Nombre de frames d'anticipation (0-35)
+    #[serde(default)]
+    pub lag_in_frames: Option<u32>,
+}
+
+impl AomParams {
+    /// Arguments aomenc correspondants
+    #[must_use]
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(frames) = self.arnr_maxframes {
+            args.push(format!("--arnr-maxframes={frames}"));
+        }
+        if self.enable_chroma_deltaq {
+            args.push("--enable-chroma-deltaq=1".to_string());
+        }
+        if let Some(lag) = self.lag_in_frames {
+            args.push(format!("--lag-in-frames={lag}"));
+        }
+        args
+    }
+}
+
+/// Nature d'un job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum JobKind {
+    /// Encodage de la source vers la sortie
+    #[default]
+    Encode,
+    /// Vérification d'un fichier existant (probe et décodage complet, sans encodage)
+    Verify,
+    /// Extrait de test : encodage des premières secondes vers `<sortie>.preview.<ext>`,
+    /// sans analytique ni action sur la source
+    Preview,
+}
+
+impl JobKind {
+    /// Le job encode-t-il la source (encodage complet ou extrait de test) ?
+    #[must_use]
+    pub fn encodes(self) -> bool {
+        matches!(self, Self::Encode | Self::Preview)
+    }
+}
+
+/// Sortie d'un extrait de test : `film.av1.mkv` devient `film.av1.preview.mkv`
+#[must_use]
+pub fn preview_output_path(output: &Path) -> PathBuf {
+    let extension = output
+        .extension()
+        .map_or_else(|| "mkv".into(), |ext| ext.to_string_lossy());
+    output.with_extension(format!("preview.{extension}"))
+}
+
 /// Job d'encodage complet
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncodingJob {
@@ -132,12 +1000,40 @@ pub struct EncodingJob {
     pub output_path: PathBuf,
     /// Configuration d'encodage
     pub config: EncodingConfig,
+    /// Encodage ou vérification
+    #[serde(default)]
+    pub kind: JobKind,
+    /// Lot d'origine (jobs ajoutés ensemble par `AddBatchJobs`, None = ajout isolé)
+    #[serde(default)]
+    pub batch_id: Option<Uuid>,
     /// Status actuel
     pub status: JobStatus,
     /// Statistiques d'encodage (Some si Running)
     pub stats: Option<EncodingStats>,
     /// Message d'erreur (Some si Failed)
     pub error_message: Option<String>,
+    /// Catégorie d'échec (Some si Failed)
+    #[serde(default)]
+    pub failure_category: Option<FailureCategory>,
+    /// Étape de repli utilisée après un crash de l'encodeur (1 = première étape)
+    #[serde(default)]
+    pub fallback_level: Option<u32>,
+    /// Nouveaux essais du job entier après un échec (politique `[retry]` du daemon)
+    #[serde(default)]
+    pub retries: u32,
+    /// Niceness appliquée aux processus du job (None = priorité par défaut)
+    #[serde(default)]
+    pub nice: Option<i32>,
+    /// Threads attribués par le budget global au démarrage (None = pas de budget,
+    /// ou threads imposés par la configuration du job)
+    #[serde(default)]
+    pub allocated_threads: Option<u32>,
+    /// Résultat d'un job de vérification (Some une fois le fichier analysé)
+    #[serde(default)]
+    pub verify_report: Option<VerifyReport>,
+    /// Fichiers écrits par un encodage réussi (un par segment), seuls supprimés avec le job
+    #[serde(default)]
+    pub outputs: Vec<PathBuf>,
     /// Date de création du job
     pub created_at: DateTime<Utc>,
     /// Date de début d'exécution (Some si Running ou terminé)
@@ -155,15 +1051,67 @@ impl EncodingJob {
             input_path,
             output_path,
             config,
+            kind: JobKind::Encode,
+            batch_id: None,
             status: JobStatus::Queued,
             stats: None,
             error_message: None,
+            failure_category: None,
+            fallback_level: None,
+            retries: 0,
+            nice: None,
+            allocated_threads: None,
+            verify_report: None,
+            outputs: Vec::new(),
             created_at: Utc::now(),
             started_at: None,
             finished_at: None,
         }
     }
 
+    /// Créer un job de vérification d'un fichier déjà encodé
+    ///
+    /// Le fichier est à la fois l'entrée et la « sortie » du job : rien n'est écrit.
+    #[must_use]
+    pub fn new_verify(path: PathBuf) -> Self {
+        Self {
+            kind: JobKind::Verify,
+            ..Self::new(path.clone(), path, EncodingConfig::default())
+        }
+    }
+
+    /// Créer un extrait de test : les `duration_secs` premières secondes de la plage à
+    /// encoder (toute la source sans découpe), en un seul fichier à côté de la sortie
+    #[must_use]
+    pub fn new_preview(
+        input_path: PathBuf,
+        output_path: &Path,
+        mut config: EncodingConfig,
+        duration_secs: u32,
+    ) -> Self {
+        let start = config.trim.start.unwrap_or(0.0);
+        let end = start + f64::from(duration_secs.max(1));
+        config.trim = TrimRange {
+            start: config.trim.start,
+            end: Some(config.trim.end.map_or(end, |trim_end| trim_end.min(end))),
+        };
+        config.split = SplitMode::None;
+        Self {
+            kind: JobKind::Preview,
+            ..Self::new(input_path, preview_output_path(output_path), config)
+        }
+    }
+
+    /// Threads de l'encodeur : ceux imposés par le job, sinon sa part du budget global
+    /// (None = choix de l'encodeur)
+    #[must_use]
+    pub fn effective_threads(&self) -> Option<u32> {
+        self.config
+            .encoder_params
+            .threads
+            .or(self.allocated_threads)
+    }
+
     /// Marquer le job comme démarré
     pub fn mark_started(&mut self) {
         self.status = JobStatus::Running;
@@ -179,8 +1127,14 @@ impl EncodingJob {
 
     /// Marquer le job comme échoué
     pub fn mark_failed(&mut self, error: String) {
+        self.mark_failed_with(error, FailureCategory::Other);
+    }
+
+    /// Marquer le job comme échoué avec une catégorie d'erreur
+    pub fn mark_failed_with(&mut self, error: String, category: FailureCategory) {
         self.status = JobStatus::Failed;
         self.error_message = Some(error);
+        self.failure_category = Some(category);
         self.finished_at = Some(Utc::now());
     }
 
@@ -197,3 +1151,390 @@ impl EncodingJob {
         Some(finished - started)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_encoding_config() {
+        let mut config = EncodingConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.encoder_params.preset = 12;
+        assert!(config.validate().is_ok());
+        config.encoder = EncoderType::Aom;
+        assert!(config.validate().is_err());
+
+        // En copie vidéo, les réglages de l'encodeur sont ignorés
+        config.video_mode = VideoMode::Copy;
+        assert!(config.validate().is_ok());
+
+        config.trim = TrimRange {
+            start: Some(20.0),
+            end: Some(10.0),
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_preview_job_trims_and_renames() {
+        let output = Path::new("/films/film.av1.mkv");
+        assert_eq!(
+            preview_output_path(output),
+            PathBuf::from("/films/film.av1.preview.mkv")
+        );
+        assert_eq!(
+            preview_output_path(Path::new("/films/film")),
+            PathBuf::from("/films/film.preview.mkv")
+        );
+
+        let mut config = EncodingConfig {
+            split: SplitMode::Interval { secs: 600 },
+            ..EncodingConfig::default()
+        };
+        let job = EncodingJob::new_preview(PathBuf::from("/src.mkv"), output, config.clone(), 30);
+        assert_eq!(job.kind, JobKind::Preview);
+        assert!(job.kind.encodes());
+        assert_eq!(job.config.trim.start, None);
+        assert_eq!(job.config.trim.end, Some(30.0));
+        assert_eq!(job.config.split, SplitMode::None);
+
+        // Découpe déjà configurée : l'extrait part de son début sans dépasser sa fin
+        config.trim = TrimRange {
+            start: Some(60.0),
+            end: Some(75.0),
+        };
+        let job = EncodingJob::new_preview(PathBuf::from("/src.mkv"), output, config, 30);
+        assert_eq!(job.config.trim.start, Some(60.0));
+        assert_eq!(job.config.trim.end, Some(75.0));
+    }
+
+    #[test]
+    fn test_precise_frame_count_override() {
+        let mut config = EncodingConfig::default();
+        assert!(!config.precise_frame_count_or(false));
+        assert!(config.precise_frame_count_or(true));
+
+        config.precise_frame_count = Some(true);
+        assert!(config.precise_frame_count_or(false));
+        config.precise_frame_count = Some(false);
+        assert!(!config.precise_frame_count_or(true));
+    }
+
+    #[test]
+    fn test_output_labels() {
+        let mut config = EncodingConfig::default();
+        assert_eq!(config.video_label(), "SVT-AV1");
+        assert_eq!(config.audio_label(), "Opus 128k");
+
+        config.encoder = EncoderType::Aom;
+        config.audio_mode = AudioMode::Copy;
+        assert_eq!(config.video_label(), "libaom");
+        assert_eq!(config.audio_label(), "Copie");
+
+        config.video_mode = VideoMode::Copy;
+        config.audio_mode = AudioMode::Custom {
+            codec: "aac".to_string(),
+            bitrate: 192,
+        };
+        assert_eq!(config.video_label(), "Copie vidéo");
+        assert_eq!(config.audio_label(), "aac 192k");
+    }
+
+    #[test]
+    fn test_validate_extra_params() {
+        let mut config = EncodingConfig::default();
+        let extra = |params: &[&str]| params.iter().map(ToString::to_string).collect();
+
+        config.encoder_params.extra_params = extra(&["--enable-qm", "1", "--film-grain=8"]);
+        assert!(config.validate().is_ok());
+
+        config.encoder_params.extra_params = extra(&["1", "--enable-qm"]);
+        assert!(config.validate().unwrap_err().contains("'1'"));
+
+        // Sortie du pipeline (SVT-AV1) : refusée, même en --option=valeur
+        config.encoder_params.extra_params = extra(&["--tile-rows", "1", "-b", "out.ivf"]);
+        assert!(config.validate().unwrap_err().contains("-b"));
+        config.encoder_params.extra_params = extra(&["--output=out.ivf"]);
+        assert!(config.validate().is_err());
+
+        // Options réservées propres à chaque encodeur
+        config.encoder = EncoderType::Aom;
+        config.encoder_params.preset = 4;
+        config.encoder_params.tune = Tune::default_for(EncoderType::Aom);
+        config.encoder_params.extra_params = extra(&["-b", "10"]);
+        assert!(config.validate().is_ok());
+        config.encoder_params.extra_params = extra(&["--passes=1"]);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_input_params_precede_trim() {
+        let mut config = EncodingConfig {
+            input_params: vec!["-probesize".to_string(), "100M".to_string()],
+            trim: TrimRange {
+                start: Some(5.0),
+                end: None,
+            },
+            ..EncodingConfig::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.ffmpeg_input_args(),
+            ["-probesize", "100M", "-ss", "5.000"]
+        );
+
+        // Les options audio ne concernent que la lecture de l'audio
+        config.audio_input_params = vec!["-itsoffset".to_string(), "0.2".to_string()];
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.ffmpeg_input_args(),
+            ["-probesize", "100M", "-ss", "5.000"]
+        );
+        assert_eq!(
+            config.ffmpeg_audio_input_args(),
+            ["-itsoffset", "0.2", "-probesize", "100M", "-ss", "5.000"]
+        );
+
+        config.audio_input_params.push("-i".to_string());
+        assert!(config.validate().is_err());
+        config.audio_input_params.clear();
+
+        config
+            .input_params
+            .extend(["-i".to_string(), "autre.mkv".to_string()]);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_env_validation() {
+        let mut config: EncodingConfig =
+            serde_json::from_str(r#"{"env": {"SVT_LOG": "1", "_LD_HINT": "a=b"}}"#).unwrap();
+        assert_eq!(config.env["SVT_LOG"], "1");
+        assert!(config.validate().is_ok());
+        assert!(EncodingConfig::default().env.is_empty());
+
+        for key in ["", "1ABC", "MY-VAR", "A=B", "ÉTÉ"] {
+            config.env = HashMap::from([(key.to_string(), "1".to_string())]);
+            assert!(config.validate().is_err(), "{key}");
+        }
+        config.env = HashMap::from([("SVT_LOG".to_string(), "1\0".to_string())]);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_encoding_mode_args_and_validation() {
+        let capped = EncodingMode::ConstrainedQuality { max_bitrate: 6000 };
+        assert_eq!(capped.svt_av1_args(30), ["--crf", "30", "--mbr", "6000"]);
+        assert_eq!(
+            capped.aom_args(30),
+            ["--end-usage=cq", "--cq-level=30", "--target-bitrate=6000"]
+        );
+
+        let abr = EncodingMode::AverageBitrate {
+            bitrate: 4000,
+            max_bitrate: Some(6000),
+        };
+        assert!(abr.validate().is_ok());
+        assert_eq!(
+            abr.svt_av1_args(30),
+            ["--rc", "1", "--tbr", "4000", "--maxsection-pct", "150"]
+        );
+
+        let below = EncodingMode::AverageBitrate {
+            bitrate: 4000,
+            max_bitrate: Some(3000),
+        };
+        assert!(below.validate().is_err());
+        assert!(EncodingMode::ConstrainedQuality { max_bitrate: 0 }
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_step_crf_clamps_to_encoder_range() {
+        for encoder in [EncoderType::SvtAv1, EncoderType::Aom]
+            .into_iter()
+            .chain(EncoderType::HARDWARE)
+        {
+            let range = encoder.crf_range();
+            let mut params = EncoderParams {
+                crf: *range.end() - 1,
+                ..EncoderParams::default()
+            };
+            params.step_crf(encoder, true);
+            assert_eq!(params.crf, 63, "{encoder}");
+            params.step_crf(encoder, true);
+            assert_eq!(params.crf, 63, "{encoder}");
+
+            params.crf = 1;
+            params.step_crf(encoder, false);
+            params.step_crf(encoder, false);
+            assert_eq!(params.crf, *range.start(), "{encoder}");
+
+            // Valeur hors plage (fichier de job) : ramenée à la borne
+            params.crf = 80;
+            params.step_crf(encoder, false);
+            assert_eq!(params.crf, 63, "{encoder}");
+        }
+
+        let mut config = EncodingConfig::default();
+        config.encoder_params.crf = 63;
+        assert!(config.validate().is_ok());
+        config.encoder_params.crf = 64;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_aom_tune_passed_once() {
+        let mut params = EncoderParams {
+            tune: Tune::Ssim,
+            ..EncoderParams::default()
+        };
+        assert_eq!(params.aom_tune_arg().as_deref(), Some("--tune=ssim"));
+        // Tune propre à SVT-AV1 : défaut d'aomenc
+        params.tune = Tune::SubjectiveSsim;
+        assert_eq!(params.aom_tune_arg().as_deref(), Some("--tune=psnr"));
+
+        // `--tune` des paramètres extra : le tune du job n'est pas ajouté en plus
+        params.extra_params = vec!["--tune=vmaf_with_preprocessing".to_string()];
+        assert_eq!(params.aom_tune_arg(), None);
+        params.extra_params = vec!["--tune".to_string(), "ssim".to_string()];
+        assert_eq!(params.aom_tune_arg(), None);
+        params.extra_params = vec!["--tune-content=screen".to_string()];
+        assert!(params.aom_tune_arg().is_some());
+
+        let mut config = EncodingConfig {
+            encoder: EncoderType::Aom,
+            ..EncodingConfig::default()
+        };
+        config.encoder_params.extra_params = vec!["--tune=ssim".to_string()];
+        let preview = crate::build_encoder_preview(&config, "out.ivf");
+        assert_eq!(preview.matches("--tune").count(), 1, "{preview}");
+    }
+
+    #[test]
+    fn test_hardware_video_args() {
+        let mut config = EncodingConfig {
+            encoder: EncoderType::Nvenc,
+            ..EncodingConfig::default()
+        };
+        config.encoder_params.crf = 63;
+        config.encoder_params.preset = 0;
+        assert_eq!(
+            config.hardware_video_args(None),
+            [
+                "-c:v",
+                "av1_nvenc",
+                "-rc",
+                "vbr",
+                "-cq",
+                "51",
+                "-b:v",
+                "0",
+                "-preset",
+                "p7",
+                "-pix_fmt",
+                "p010le"
+            ]
+        );
+        // Un tune propre au logiciel ne bloque pas un job matériel
+        assert!(config.validate().is_ok());
+
+        config.encoder = EncoderType::Vaapi;
+        config.encoder_params.crf = 30;
+        config.encoder_params.preset = 6;
+        config.encoder_params.encoding_mode =
+            EncodingMode::ConstrainedQuality { max_bitrate: 6000 };
+        assert_eq!(
+            config.hardware_video_args(None),
+            [
+                "-c:v",
+                "av1_vaapi",
+                "-rc_mode",
+                "QVBR",
+                "-global_quality",
+                "120",
+                "-maxrate",
+                "6000k",
+                "-bufsize",
+                "12000k",
+                "-compression_level",
+                "7"
+            ]
+        );
+        // La preview montre le périphérique configuré
+        let preview = crate::build_hardware_encode_preview(
+            Path::new("in.mkv"),
+            None,
+            &config,
+            Path::new("/dev/dri/renderD129"),
+            "video.ivf",
+        );
+        assert!(
+            preview.contains("-vaapi_device /dev/dri/renderD129"),
+            "{preview}"
+        );
+
+        config.encoder = EncoderType::Qsv;
+        config.encoder_params.encoding_mode = EncodingMode::AverageBitrate {
+            bitrate: 4000,
+            max_bitrate: None,
+        };
+        assert_eq!(
+            config.hardware_video_args(None),
+            ["-c:v", "av1_qsv", "-b:v", "4000k", "-preset", "7", "-pix_fmt", "p010le"]
+        );
+        config.encoder_params.preset = 7;
+        assert!(config.validate().is_err());
+
+        config.encoder = EncoderType::SvtAv1;
+        assert!(config.hardware_video_args(None).is_empty());
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: EncodingConfig =
+            serde_json::from_str(r#"{"encoder": "Aom", "encoder_params": {"crf": 24}}"#).unwrap();
+        assert_eq!(config.encoder, EncoderType::Aom);
+        assert_eq!(config.encoder_params.crf, 24);
+        assert_eq!(
+            config.encoder_params.preset,
+            EncoderParams::default().preset
+        );
+        assert!(config.enable_vmaf);
+    }
+
+    #[test]
+    fn test_track_selection_by_language() {
+        let mut config = EncodingConfig {
+            audio_languages: vec!["jpn".to_string()],
+            ..EncodingConfig::default()
+        };
+        let languages = [Some("eng".to_string()), Some("jpn".to_string())];
+        assert_eq!(config.resolve_audio_streams(&languages), Some(vec![1]));
+        assert_eq!(config.resolve_subtitle_streams(&languages), None);
+        // Une sélection par index reste prioritaire
+        config.audio_streams = Some(vec![0]);
+        assert_eq!(config.resolve_audio_streams(&languages), Some(vec![0]));
+
+        config.subtitle_languages = vec!["english".to_string()];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_phase_plan() {
+        use EncodingPhase::{Audio, Muxing, Video, Vmaf};
+        let config = EncodingConfig::default();
+        assert_eq!(config.phase_plan(), [Video, Audio, Muxing, Vmaf]);
+
+        // Copie vidéo sans audio : rien d'autre que le muxage
+        let copy = EncodingConfig {
+            video_mode: VideoMode::Copy,
+            audio_mode: AudioMode::None,
+            ..EncodingConfig::default()
+        };
+        assert_eq!(copy.phase_plan(), [Muxing]);
+    }
+}
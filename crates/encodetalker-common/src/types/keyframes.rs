@@ -0,0 +1,150 @@
+use super::EncoderType;
+use serde::{Deserialize, Serialize};
+
+/// Intervalle de keyframes minimal accepté (secondes)
+pub const MIN_KEYFRAME_INTERVAL_SECS: f64 = 0.5;
+
+/// Intervalle de keyframes maximal accepté (secondes) : au-delà, le seek devient lent
+pub const MAX_KEYFRAME_INTERVAL_SECS: f64 = 60.0;
+
+/// Placement des keyframes, pour un seek rapide (streaming)
+///
+/// L'intervalle est donné en secondes et converti en frames avec le framerate de la
+/// source : `--keyint` pour SVT-AV1, `--kf-max-dist` pour aomenc, `-g` pour les encodeurs
+/// matériels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct KeyframeConfig {
+    /// Intervalle maximal entre deux keyframes (None = choix de l'encodeur)
+    pub interval_secs: Option<f64>,
+    /// Ajouter une keyframe à chaque changement de scène (sinon intervalle fixe)
+    pub scene_cuts: bool,
+}
+
+impl KeyframeConfig {
+    /// Vérifier que l'intervalle est utilisable
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si l'intervalle sort de
+    /// [`MIN_KEYFRAME_INTERVAL_SECS`]-[`MAX_KEYFRAME_INTERVAL_SECS`].
+    pub fn validate(self) -> Result<(), String> {
+        match self.interval_secs {
+            Some(secs)
+                if !(MIN_KEYFRAME_INTERVAL_SECS..=MAX_KEYFRAME_INTERVAL_SECS).contains(&secs) =>
+            {
+                Err(format!(
+                    "Intervalle de keyframes {secs}s hors plage ({MIN_KEYFRAME_INTERVAL_SECS}-{MAX_KEYFRAME_INTERVAL_SECS}s)"
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Intervalle en frames au framerate `fps` (au moins 1, None sans intervalle)
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn interval_frames(self, fps: f64) -> Option<u32> {
+        let secs = self.interval_secs?;
+        // Safe: intervalle validé (60 s max), framerate de quelques centaines au plus
+        Some((secs * fps).round().max(1.0) as u32)
+    }
+
+    /// Options de l'encodeur, `fps` étant le framerate sondé de la source
+    ///
+    /// Sans framerate (preview avant le probe), l'intervalle est affiché en secondes.
+    #[must_use]
+    pub fn encoder_args(self, encoder: EncoderType, fps: Option<f64>) -> Vec<String> {
+        let interval = self.interval_secs.map(|secs| {
+            fps.filter(|&fps| fps > 0.0)
+                .and_then(|fps| self.interval_frames(fps))
+                .map_or_else(|| format!("[{secs}s]"), |frames| frames.to_string())
+        });
+        let mut args = Vec::new();
+        match encoder {
+            EncoderType::SvtAv1 => {
+                if let Some(interval) = interval {
+                    args.extend(["--keyint".to_string(), interval]);
+                }
+                if self.scene_cuts {
+                    args.extend(["--scd".to_string(), "1".to_string()]);
+                }
+            }
+            EncoderType::Aom => {
+                if let Some(interval) = interval {
+                    // Intervalle fixe : distance minimale égale à la maximale
+                    if !self.scene_cuts {
+                        args.push(format!("--kf-min-dist={interval}"));
+                    }
+                    args.push(format!("--kf-max-dist={interval}"));
+                }
+            }
+            EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => {
+                if let Some(interval) = interval {
+                    args.extend(["-g".to_string(), interval]);
+                }
+            }
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyframe_args() {
+        let fixed = KeyframeConfig {
+            interval_secs: Some(2.0),
+            scene_cuts: false,
+        };
+        // 2 s à 23.976 fps : 48 frames
+        assert_eq!(fixed.interval_frames(24000.0 / 1001.0), Some(48));
+        assert_eq!(
+            fixed.encoder_args(EncoderType::SvtAv1, Some(25.0)),
+            ["--keyint", "50"]
+        );
+        assert_eq!(
+            fixed.encoder_args(EncoderType::Aom, Some(25.0)),
+            ["--kf-min-dist=50", "--kf-max-dist=50"]
+        );
+        assert_eq!(
+            fixed.encoder_args(EncoderType::Nvenc, Some(25.0)),
+            ["-g", "50"]
+        );
+        assert_eq!(
+            fixed.encoder_args(EncoderType::SvtAv1, None),
+            ["--keyint", "[2s]"]
+        );
+
+        let scene = KeyframeConfig {
+            scene_cuts: true,
+            ..fixed
+        };
+        assert_eq!(
+            scene.encoder_args(EncoderType::SvtAv1, Some(25.0)),
+            ["--keyint", "50", "--scd", "1"]
+        );
+        assert_eq!(
+            scene.encoder_args(EncoderType::Aom, Some(25.0)),
+            ["--kf-max-dist=50"]
+        );
+        assert!(KeyframeConfig::default()
+            .encoder_args(EncoderType::Aom, Some(25.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_keyframe_interval_validation() {
+        let config = |secs| KeyframeConfig {
+            interval_secs: Some(secs),
+            scene_cuts: false,
+        };
+        assert!(KeyframeConfig::default().validate().is_ok());
+        assert!(config(10.0).validate().is_ok());
+        assert!(config(0.1).validate().is_err());
+        assert!(config(120.0).validate().is_err());
+        assert!(config(f64::NAN).validate().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+/// Pistes à garder parmi celles d'une source, d'après leurs codes de langue
+///
+/// `languages` donne la langue de chaque piste du type concerné, dans l'ordre de la source
+/// (tag `language`). Le résultat est la liste des index relatifs (`0:a:N`) des pistes dont
+/// la langue est demandée, sans tenir compte de la casse ; None (tout garder) si aucune
+/// langue n'est demandée ou si aucune piste ne correspond.
+#[must_use]
+pub fn select_tracks_by_language(
+    wanted: &[String],
+    languages: &[Option<String>],
+) -> Option<Vec<usize>> {
+    if wanted.is_empty() {
+        return None;
+    }
+    let selected: Vec<usize> = languages
+        .iter()
+        .enumerate()
+        .filter(|(_, language)| {
+            language
+                .as_deref()
+                .is_some_and(|language| wanted.iter().any(|w| w.eq_ignore_ascii_case(language)))
+        })
+        .map(|(index, _)| index)
+        .collect();
+    (!selected.is_empty()).then_some(selected)
+}
+
+/// Vérifier des codes de langue (2 ou 3 lettres, ISO 639 : `en`, `eng`, `jpn`...)
+///
+/// # Errors
+///
+/// Retourne un message désignant le premier code invalide.
+pub fn validate_language_codes(codes: &[String]) -> Result<(), String> {
+    match codes.iter().find(|code| {
+        !(2..=3).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_alphabetic())
+    }) {
+        Some(code) => Err(format!(
+            "Code de langue '{code}' invalide (2 ou 3 lettres, ex: eng)"
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Lire une saisie `audio / sous-titres` : `eng,jpn / eng` (côté vide = toutes les pistes)
+#[must_use]
+pub fn parse_language_selection(text: &str) -> (Vec<String>, Vec<String>) {
+    let list = |part: &str| {
+        part.split([',', ' '])
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (audio, subtitles) = text.split_once('/').unwrap_or((text, ""));
+    (list(audio), list(subtitles))
+}
+
+/// Saisie `audio / sous-titres` correspondant à une sélection (inverse de [`parse_language_selection`])
+#[must_use]
+pub fn format_language_selection(audio: &[String], subtitles: &[String]) -> String {
+    match (audio.is_empty(), subtitles.is_empty()) {
+        (_, true) => audio.join(","),
+        _ => format!("{} / {}", audio.join(","), subtitles.join(",")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_tracks_by_language() {
+        let wanted = vec!["eng".to_string(), "JPN".to_string()];
+        let tracks = [
+            Some("fre".to_string()),
+            Some("jpn".to_string()),
+            None,
+            Some("ENG".to_string()),
+        ];
+        assert_eq!(
+            select_tracks_by_language(&wanted, &tracks),
+            Some(vec![1, 3])
+        );
+        // Aucune correspondance ou aucun filtre : toutes les pistes
+        assert_eq!(
+            select_tracks_by_language(&wanted, &[Some("ger".to_string()), None]),
+            None
+        );
+        assert_eq!(select_tracks_by_language(&[], &tracks), None);
+    }
+
+    #[test]
+    fn test_language_selection_roundtrip() {
+        let (audio, subtitles) = parse_language_selection("eng, JPN / fre");
+        assert_eq!(audio, ["eng", "jpn"]);
+        assert_eq!(subtitles, ["fre"]);
+        assert_eq!(
+            format_language_selection(&audio, &subtitles),
+            "eng,jpn / fre"
+        );
+
+        let (audio, subtitles) = parse_language_selection(" / eng");
+        assert!(audio.is_empty());
+        assert_eq!(format_language_selection(&audio, &subtitles), " / eng");
+        assert_eq!(parse_language_selection(""), (Vec::new(), Vec::new()));
+
+        assert!(validate_language_codes(&audio).is_ok());
+        assert!(validate_language_codes(&["en".to_string(), "jpn".to_string()]).is_ok());
+        assert!(validate_language_codes(&["english".to_string()]).is_err());
+        assert!(validate_language_codes(&["e1".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Ce que la maintenance (`RequestPayload::Cleanup`) peut supprimer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CleanupTarget {
+    /// Sources des dépendances (`deps/src`), inutiles une fois les binaires compilés
+    Sources,
+    /// Fichiers temporaires d'encodages interrompus (crash, arrêt forcé)
+    Temp,
+    /// Archives du log (`daemon.log.N`), le log courant est conservé
+    Logs,
+    /// Cache mémoire des miniatures de l'explorateur
+    Thumbnails,
+}
+
+impl CleanupTarget {
+    /// Toutes les cibles, dans l'ordre de la vue de maintenance
+    pub const ALL: [Self; 4] = [Self::Sources, Self::Temp, Self::Logs, Self::Thumbnails];
+}
+
+impl fmt::Display for CleanupTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sources => write!(f, "Sources des dépendances"),
+            Self::Temp => write!(f, "Fichiers temporaires"),
+            Self::Logs => write!(f, "Anciens logs"),
+            Self::Thumbnails => write!(f, "Cache des miniatures"),
+        }
+    }
+}
+
+/// Résultat du nettoyage d'une cible (ou estimation, sans suppression)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanupResult {
+    pub target: CleanupTarget,
+    /// Octets libérés (ou libérables)
+    pub bytes: u64,
+    /// Fichiers supprimés (ou supprimables)
+    pub files: usize,
+    /// Raison pour laquelle la cible n'a pas été nettoyée (ex. compilation en cours)
+    pub skipped: Option<String>,
+}
+
+impl CleanupResult {
+    #[must_use]
+    pub fn skipped(target: CleanupTarget, reason: impl Into<String>) -> Self {
+        Self {
+            target,
+            bytes: 0,
+            files: 0,
+            skipped: Some(reason.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleanup_targets() {
+        assert_eq!(CleanupTarget::ALL.len(), 4);
+        assert_eq!(CleanupTarget::Logs.to_string(), "Anciens logs");
+        let result = CleanupResult::skipped(CleanupTarget::Sources, "compilation en cours");
+        assert_eq!(result.bytes, 0);
+        assert_eq!(result.skipped.as_deref(), Some("compilation en cours"));
+    }
+}
@@ -1,7 +1,43 @@
+pub mod analytics;
+pub mod audio_bitrate;
+pub mod benchmark;
+pub mod capabilities;
+pub mod concat;
+pub mod cover_art;
+pub mod crf_policy;
+pub mod deinterlace;
+pub mod dimensions;
 pub mod job;
+pub mod keyframes;
+pub mod languages;
+pub mod maintenance;
+pub mod output_paths;
 pub mod stats;
 pub mod status;
+pub mod subtitles;
+pub mod tonemap;
+pub mod trim;
+pub mod verify;
+pub mod vfr;
 
+pub use analytics::*;
+pub use audio_bitrate::*;
+pub use benchmark::*;
+pub use capabilities::*;
+pub use concat::*;
+pub use cover_art::*;
+pub use crf_policy::*;
+pub use deinterlace::*;
+pub use dimensions::*;
 pub use job::*;
+pub use keyframes::*;
+pub use languages::*;
+pub use maintenance::*;
+pub use output_paths::*;
 pub use stats::*;
 pub use status::*;
+pub use subtitles::*;
+pub use tonemap::*;
+pub use trim::*;
+pub use verify::*;
+pub use vfr::*;
@@ -0,0 +1,538 @@
+use super::{EncoderType, EncodingConfig, VideoMode};
+use crate::config::PathsConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Modèle par défaut du nom des sorties : `film.mkv` devient `film.av1.mkv`
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{stem}{suffix}.mkv";
+
+/// Variables reconnues dans le modèle du nom des sorties
+pub const OUTPUT_TEMPLATE_PLACEHOLDERS: &[&str] =
+    &["{stem}", "{suffix}", "{encoder}", "{crf}", "{preset}"];
+
+/// Traitement d'une sortie qui existe déjà ou qu'un job en attente produira
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CollisionPolicy {
+    /// Écraser le fichier existant
+    #[default]
+    Overwrite,
+    /// Numéroter la sortie (`-2`, `-3`... avant l'extension)
+    Rename,
+    /// Refuser le job
+    Fail,
+}
+
+impl fmt::Display for CollisionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overwrite => write!(f, "Écraser"),
+            Self::Rename => write!(f, "Renommer"),
+            Self::Fail => write!(f, "Refuser"),
+        }
+    }
+}
+
+/// Nommage des sorties : chemin par défaut, saisie de l'utilisateur et collisions
+///
+/// Partagé par le dialogue, les batchs et le daemon (`--enqueue`, ajout des jobs), pour
+/// qu'une même source reçoive partout le même nom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputNamer {
+    /// Modèle du nom (voir [`OUTPUT_TEMPLATE_PLACEHOLDERS`])
+    template: String,
+    /// Valeur de `{suffix}`
+    suffix: String,
+    /// Répertoire des sorties (None = celui de la source)
+    output_dir: Option<PathBuf>,
+    collision: CollisionPolicy,
+}
+
+impl Default for OutputNamer {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_OUTPUT_TEMPLATE.to_string(),
+            suffix: ".av1".to_string(),
+            output_dir: None,
+            collision: CollisionPolicy::default(),
+        }
+    }
+}
+
+/// Nom d'un encodeur dans les noms de fichiers (`copy` si la vidéo est copiée)
+fn encoder_slug(config: &EncodingConfig) -> &'static str {
+    if config.video_mode == VideoMode::Copy {
+        return "copy";
+    }
+    match config.encoder {
+        EncoderType::SvtAv1 => "svt-av1",
+        EncoderType::Aom => "aom",
+        EncoderType::Nvenc => "nvenc",
+        EncoderType::Qsv => "qsv",
+        EncoderType::Vaapi => "vaapi",
+    }
+}
+
+impl OutputNamer {
+    /// Créer un nommage après vérification du modèle
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si le modèle est invalide (voir [`Self::validate_template`]).
+    pub fn new(
+        template: &str,
+        suffix: &str,
+        output_dir: Option<PathBuf>,
+        collision: CollisionPolicy,
+    ) -> Result<Self, String> {
+        let template = template.trim();
+        Self::validate_template(template)?;
+        Ok(Self {
+            template: template.to_string(),
+            suffix: suffix.to_string(),
+            output_dir,
+            collision,
+        })
+    }
+
+    /// Vérifier un modèle de nom
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si le modèle ne contient pas `{stem}` (deux sources
+    /// recevraient le même nom), contient un répertoire, une variable inconnue ou n'a
+    /// pas d'extension.
+    pub fn validate_template(template: &str) -> Result<(), String> {
+        if template.contains(['/', '\\']) {
+            return Err(format!(
+                "Modèle de sortie \"{template}\" : pas de répertoire (voir output_dir)"
+            ));
+        }
+        if !template.contains("{stem}") {
+            return Err(format!(
+                "Modèle de sortie \"{template}\" : {{stem}} est obligatoire"
+            ));
+        }
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end + 1)
+                .ok_or_else(|| format!("Modèle de sortie \"{template}\" : accolade non fermée"))?;
+            let placeholder = &rest[start..end];
+            if !OUTPUT_TEMPLATE_PLACEHOLDERS.contains(&placeholder) {
+                return Err(format!(
+                    "Modèle de sortie \"{template}\" : variable {placeholder} inconnue ({})",
+                    OUTPUT_TEMPLATE_PLACEHOLDERS.join(", ")
+                ));
+            }
+            rest = &rest[end..];
+        }
+        if Path::new(template).extension().is_none() {
+            return Err(format!(
+                "Modèle de sortie \"{template}\" : extension manquante (ex. .mkv)"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Utiliser un autre répertoire de sortie (None = celui de la source)
+    #[must_use]
+    pub fn with_output_dir(mut self, output_dir: Option<PathBuf>) -> Self {
+        self.output_dir = output_dir;
+        self
+    }
+
+    /// Utiliser une autre politique de collision
+    #[must_use]
+    pub fn with_collision(mut self, collision: CollisionPolicy) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    #[must_use]
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    #[must_use]
+    pub fn output_dir(&self) -> Option<&Path> {
+        self.output_dir.as_deref()
+    }
+
+    #[must_use]
+    pub fn collision(&self) -> CollisionPolicy {
+        self.collision
+    }
+
+    /// Nom de fichier de la sortie d'une source encodée avec `config`
+    #[must_use]
+    pub fn file_name(&self, input: &Path, config: &EncodingConfig) -> String {
+        let stem = input
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.template
+            .replace("{stem}", &stem)
+            .replace("{suffix}", &self.suffix)
+            .replace("{encoder}", encoder_slug(config))
+            .replace("{crf}", &config.encoder_params.crf.to_string())
+            .replace("{preset}", &config.encoder_params.preset.to_string())
+    }
+
+    /// Chemin de sortie par défaut : dans `output_dir` si défini, sinon à côté de la source
+    #[must_use]
+    pub fn default_path(&self, input: &Path, config: &EncodingConfig) -> PathBuf {
+        let file_name = self.file_name(input, config);
+        match &self.output_dir {
+            Some(dir) => dir.join(file_name),
+            None => input.with_file_name(file_name),
+        }
+    }
+
+    /// Chemin de sortie saisi, rendu absolu
+    ///
+    /// Un chemin relatif est résolu par rapport à `output_dir` ou au répertoire de la
+    /// source ; `~` et les variables d'environnement sont expansés.
+    #[must_use]
+    pub fn resolve_entered(&self, text: &str, input: &Path) -> PathBuf {
+        resolve_output_path(text, input, self.output_dir.as_deref())
+    }
+
+    /// Sorties par défaut d'un batch, `(source, configuration)` par job
+    ///
+    /// Deux sources de même nom ne s'écrasent jamais l'une l'autre, quelle que soit la
+    /// politique : les doublons sont numérotés. Retourne aussi les renommages effectués
+    /// (ancien chemin, nouveau chemin), dans l'ordre du batch.
+    pub fn batch_paths<'a>(
+        &self,
+        jobs: impl IntoIterator<Item = (&'a Path, &'a EncodingConfig)>,
+    ) -> (Vec<PathBuf>, Vec<(PathBuf, PathBuf)>) {
+        let mut outputs: Vec<PathBuf> = jobs
+            .into_iter()
+            .map(|(input, config)| self.default_path(input, config))
+            .collect();
+        let renamed = disambiguate_output_paths(&mut outputs);
+        (outputs, renamed)
+    }
+
+    /// Appliquer la politique de collision au chemin d'un nouveau job
+    ///
+    /// `taken` indique les sorties des jobs en attente ou en cours, `exists` les fichiers
+    /// présents sur le disque (injectés pour les tests).
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si la politique est [`CollisionPolicy::Fail`] et que la sortie
+    /// est déjà prise.
+    pub fn resolve_collision(
+        &self,
+        path: &Path,
+        taken: impl Fn(&Path) -> bool,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Result<PathBuf, String> {
+        let collides = |candidate: &Path| taken(candidate) || exists(candidate);
+        match self.collision {
+            CollisionPolicy::Overwrite => Ok(path.to_path_buf()),
+            CollisionPolicy::Fail if taken(path) => Err(format!(
+                "{} est déjà la sortie d'un job en attente ou en cours",
+                path.display()
+            )),
+            CollisionPolicy::Fail if exists(path) => Err(format!("{} existe déjà", path.display())),
+            CollisionPolicy::Rename if collides(path) => Ok((2..)
+                .map(|n| numbered_path(path, n))
+                .find(|candidate| !collides(candidate))
+                .unwrap_or_else(|| path.to_path_buf())),
+            CollisionPolicy::Fail | CollisionPolicy::Rename => Ok(path.to_path_buf()),
+        }
+    }
+}
+
+/// Chemin de sortie saisi, rendu absolu
+///
+/// `~` et les variables d'environnement sont expansés. Un chemin relatif (ex. un simple
+/// nom de fichier) est résolu par rapport à `output_dir` s'il est défini, sinon par
+/// rapport au répertoire de la source, jamais par rapport au répertoire courant du TUI.
+#[must_use]
+fn resolve_output_path(text: &str, input: &Path, output_dir: Option<&Path>) -> PathBuf {
+    let text = text.trim();
+    let path = PathsConfig::expand_path(text).unwrap_or_else(|_| PathBuf::from(text));
+    let resolved = if path.is_absolute() {
+        path
+    } else {
+        output_dir
+            .or_else(|| input.parent())
+            .unwrap_or(Path::new(""))
+            .join(path)
+    };
+    // Source elle-même relative : compléter avec le répertoire courant
+    std::path::absolute(&resolved).unwrap_or(resolved)
+}
+
+/// `<nom>-<n>.<ext>` : variante numérotée d'un chemin de sortie
+fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{stem}-{n}.{}", ext.to_string_lossy()),
+        None => format!("{stem}-{n}"),
+    };
+    path.with_file_name(file_name)
+}
+
+/// Renommer les sorties en double d'un batch (ex. deux `film.mkv` de dossiers différents
+/// envoyés dans le même répertoire de sortie)
+///
+/// La première occurrence garde son nom, les suivantes reçoivent `-2`, `-3`... avant
+/// l'extension, sans reprendre un chemin déjà présent dans le batch. Retourne les
+/// renommages effectués (ancien chemin, nouveau chemin), dans l'ordre du batch.
+fn disambiguate_output_paths(outputs: &mut [PathBuf]) -> Vec<(PathBuf, PathBuf)> {
+    let mut taken: HashSet<PathBuf> = outputs.iter().cloned().collect();
+    let mut seen = HashSet::new();
+    let mut renamed = Vec::new();
+    for output in outputs.iter_mut() {
+        if seen.insert(output.clone()) {
+            continue;
+        }
+        let unique = (2..)
+            .map(|n| numbered_path(output, n))
+            .find(|candidate| !taken.contains(candidate))
+            .unwrap_or_else(|| output.clone());
+        taken.insert(unique.clone());
+        seen.insert(unique.clone());
+        renamed.push((std::mem::replace(output, unique.clone()), unique));
+    }
+    renamed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_output_path() {
+        let input = Path::new("/videos/films/film.mkv");
+        assert_eq!(
+            resolve_output_path("sortie.mkv", input, None),
+            PathBuf::from("/videos/films/sortie.mkv")
+        );
+        assert_eq!(
+            resolve_output_path(" av1/sortie.mkv ", input, Some(Path::new("/encoded"))),
+            PathBuf::from("/encoded/av1/sortie.mkv")
+        );
+        assert_eq!(
+            resolve_output_path("/tmp/sortie.mkv", input, Some(Path::new("/encoded"))),
+            PathBuf::from("/tmp/sortie.mkv")
+        );
+
+        // Source relative : le résultat reste absolu
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            resolve_output_path("sortie.mkv", Path::new("films/film.mkv"), None),
+            cwd.join("films/sortie.mkv")
+        );
+        assert_eq!(
+            resolve_output_path("sortie.mkv", Path::new("film.mkv"), None),
+            cwd.join("sortie.mkv")
+        );
+    }
+
+    #[test]
+    fn test_duplicate_outputs_get_numbered() {
+        let out = |name: &str| PathBuf::from(format!("/encoded/{name}"));
+        let mut outputs = vec![
+            out("film.av1.mkv"),
+            out("film.av1.mkv"),
+            out("autre.av1.mkv"),
+            // Déjà pris par un autre fichier du batch : sauté
+            out("film.av1-2.mkv"),
+            out("film.av1.mkv"),
+        ];
+        let renamed = disambiguate_output_paths(&mut outputs);
+        assert_eq!(
+            outputs,
+            [
+                out("film.av1.mkv"),
+                out("film.av1-3.mkv"),
+                out("autre.av1.mkv"),
+                out("film.av1-2.mkv"),
+                out("film.av1-4.mkv"),
+            ]
+        );
+        assert_eq!(
+            renamed,
+            [
+                (out("film.av1.mkv"), out("film.av1-3.mkv")),
+                (out("film.av1.mkv"), out("film.av1-4.mkv")),
+            ]
+        );
+
+        let mut distinct = vec![out("a.mkv"), out("b.mkv")];
+        assert!(disambiguate_output_paths(&mut distinct).is_empty());
+    }
+
+    #[test]
+    fn test_default_namer_matches_previous_names() {
+        let namer = OutputNamer::default();
+        let config = EncodingConfig::default();
+        assert_eq!(
+            namer.default_path(Path::new("/videos/film.mp4"), &config),
+            PathBuf::from("/videos/film.av1.mkv")
+        );
+        assert_eq!(
+            namer
+                .clone()
+                .with_output_dir(Some(PathBuf::from("/encoded")))
+                .default_path(Path::new("/videos/film.mp4"), &config),
+            PathBuf::from("/encoded/film.av1.mkv")
+        );
+        // Source sans extension ni répertoire
+        assert_eq!(namer.file_name(Path::new("film"), &config), "film.av1.mkv");
+    }
+
+    #[test]
+    fn test_template_placeholders() {
+        let namer = OutputNamer::new(
+            " {stem}.{encoder}-crf{crf}-p{preset}{suffix}.mp4 ",
+            "",
+            None,
+            CollisionPolicy::Overwrite,
+        )
+        .unwrap();
+        let mut config = EncodingConfig {
+            encoder: EncoderType::Aom,
+            ..EncodingConfig::default()
+        };
+        config.encoder_params.crf = 28;
+        config.encoder_params.preset = 4;
+        assert_eq!(
+            namer.file_name(Path::new("/videos/film.mkv"), &config),
+            "film.aom-crf28-p4.mp4"
+        );
+
+        config.encoder = EncoderType::Nvenc;
+        assert!(namer
+            .file_name(Path::new("film.mkv"), &config)
+            .starts_with("film.nvenc-"));
+        config.video_mode = VideoMode::Copy;
+        assert!(namer
+            .file_name(Path::new("film.mkv"), &config)
+            .starts_with("film.copy-"));
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(OutputNamer::validate_template(DEFAULT_OUTPUT_TEMPLATE).is_ok());
+        assert!(OutputNamer::validate_template("{stem}-{encoder}.webm").is_ok());
+        // Toutes les sources auraient le même nom
+        assert!(OutputNamer::validate_template("sortie.mkv").is_err());
+        assert!(OutputNamer::validate_template("av1/{stem}.mkv").is_err());
+        assert!(OutputNamer::validate_template("{stem}\\x.mkv").is_err());
+        assert!(OutputNamer::validate_template("{stem}-{date}.mkv").is_err());
+        assert!(OutputNamer::validate_template("{stem.mkv").is_err());
+        assert!(OutputNamer::validate_template("{stem}{suffix}").is_err());
+        assert!(OutputNamer::new("{name}.mkv", ".av1", None, CollisionPolicy::Rename).is_err());
+    }
+
+    #[test]
+    fn test_resolve_entered_uses_output_dir() {
+        let namer = OutputNamer::default().with_output_dir(Some(PathBuf::from("/encoded")));
+        let input = Path::new("/videos/film.mkv");
+        assert_eq!(
+            namer.resolve_entered("sortie.mkv", input),
+            PathBuf::from("/encoded/sortie.mkv")
+        );
+        assert_eq!(
+            OutputNamer::default().resolve_entered("sortie.mkv", input),
+            PathBuf::from("/videos/sortie.mkv")
+        );
+    }
+
+    #[test]
+    fn test_batch_paths() {
+        let namer = OutputNamer::default().with_output_dir(Some(PathBuf::from("/encoded")));
+        let low = EncodingConfig::default();
+        let inputs = [
+            PathBuf::from("/a/film.mkv"),
+            PathBuf::from("/b/film.mkv"),
+            PathBuf::from("/b/autre.mkv"),
+        ];
+        let (outputs, renamed) = namer.batch_paths(inputs.iter().map(|i| (i.as_path(), &low)));
+        assert_eq!(
+            outputs,
+            [
+                PathBuf::from("/encoded/film.av1.mkv"),
+                PathBuf::from("/encoded/film.av1-2.mkv"),
+                PathBuf::from("/encoded/autre.av1.mkv"),
+            ]
+        );
+        assert_eq!(renamed.len(), 1);
+
+        // Le CRF propre à chaque source distingue les noms : aucun renommage
+        let crf = OutputNamer::new("{stem}-{crf}.mkv", "", None, CollisionPolicy::Fail).unwrap();
+        let mut high = EncodingConfig::default();
+        high.encoder_params.crf = low.encoder_params.crf + 4;
+        let (outputs, renamed) =
+            crf.batch_paths([(inputs[0].as_path(), &low), (inputs[0].as_path(), &high)]);
+        assert_ne!(outputs[0], outputs[1]);
+        assert!(renamed.is_empty());
+    }
+
+    #[test]
+    fn test_collision_policies() {
+        let path = Path::new("/encoded/film.av1.mkv");
+        let on_disk: HashSet<PathBuf> = [
+            PathBuf::from("/encoded/film.av1.mkv"),
+            PathBuf::from("/encoded/film.av1-2.mkv"),
+        ]
+        .into();
+        let queued: HashSet<PathBuf> = [PathBuf::from("/encoded/film.av1-3.mkv")].into();
+        let taken = |p: &Path| queued.contains(p);
+        let exists = |p: &Path| on_disk.contains(p);
+        let free = |_: &Path| false;
+        let namer = |collision| OutputNamer::default().with_collision(collision);
+
+        let overwrite = namer(CollisionPolicy::Overwrite);
+        assert_eq!(
+            overwrite.resolve_collision(path, taken, exists).unwrap(),
+            path
+        );
+
+        let rename = namer(CollisionPolicy::Rename);
+        assert_eq!(
+            rename.resolve_collision(path, taken, exists).unwrap(),
+            PathBuf::from("/encoded/film.av1-4.mkv")
+        );
+        assert_eq!(rename.resolve_collision(path, free, free).unwrap(), path);
+        assert_eq!(
+            rename
+                .resolve_collision(Path::new("/encoded/film.av1-3.mkv"), taken, free)
+                .unwrap(),
+            PathBuf::from("/encoded/film.av1-3-2.mkv")
+        );
+
+        let fail = namer(CollisionPolicy::Fail);
+        let err = fail.resolve_collision(path, free, exists).unwrap_err();
+        assert!(err.contains("existe déjà"));
+        let err = fail
+            .resolve_collision(Path::new("/encoded/film.av1-3.mkv"), taken, exists)
+            .unwrap_err();
+        assert!(err.contains("job en attente"));
+        assert_eq!(fail.resolve_collision(path, free, free).unwrap(), path);
+    }
+
+    #[test]
+    fn test_collision_policy_serde() {
+        let parsed: CollisionPolicy = serde_json::from_str(r#""rename""#).unwrap();
+        assert_eq!(parsed, CollisionPolicy::Rename);
+        assert_eq!(
+            serde_json::to_string(&CollisionPolicy::Overwrite).unwrap(),
+            r#""overwrite""#
+        );
+        assert!(serde_json::from_str::<CollisionPolicy>(r#""skip""#).is_err());
+    }
+}
@@ -1,8 +1,53 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
 use std::time::Duration;
 
+/// Étape d'un job, pour l'avancement global ([`EncodingStats::calculate_overall`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingPhase {
+    /// Encodage vidéo (toutes passes)
+    Video,
+    /// Attente de l'encodage audio, mené en parallèle de la vidéo
+    Audio,
+    /// Muxage final
+    Muxing,
+    /// Calcul VMAF (et PSNR/SSIM)
+    Vmaf,
+    /// Décodage complet d'un job de vérification
+    Verify,
+}
+
+impl EncodingPhase {
+    /// Poids approximatif de l'étape dans la durée d'un job
+    #[must_use]
+    pub fn weight(self) -> f64 {
+        match self {
+            Self::Video | Self::Verify => 100.0,
+            Self::Audio | Self::Muxing => 5.0,
+            Self::Vmaf => 40.0,
+        }
+    }
+}
+
+impl fmt::Display for EncodingPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Video => write!(f, "Vidéo"),
+            Self::Audio => write!(f, "Audio"),
+            Self::Muxing => write!(f, "Muxage"),
+            Self::Vmaf => write!(f, "VMAF"),
+            Self::Verify => write!(f, "Vérification"),
+        }
+    }
+}
+
 /// Statistiques d'encodage en temps réel
+///
+/// `progress_percent`, `remaining_frames` et `eta` sont calculés par le daemon
+/// ([`EncodingStats::update`]) à chaque mise à jour : les clients les affichent tels quels
+/// au lieu de refaire le calcul à partir des frames.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncodingStats {
     /// Frame actuelle
@@ -17,9 +62,12 @@ pub struct EncodingStats {
     pub time_encoded: Duration,
     /// Durée totale de la vidéo (None si inconnue)
     pub total_duration: Option<Duration>,
-    /// Progression en pourcentage (0.0 - 100.0)
+    /// Progression de la passe en cours en pourcentage (0.0 - 100.0, 0 si total inconnu)
     pub progress_percent: f64,
-    /// ETA (temps restant estimé)
+    /// Frames restant à traiter, passes suivantes comprises (None si total inconnu)
+    #[serde(default)]
+    pub remaining_frames: Option<u64>,
+    /// ETA (temps restant estimé, None si total ou vitesse inconnus)
     pub eta: Option<Duration>,
     /// Passe actuelle (1 pour SVT-AV1, 1 ou 2 pour aomenc)
     pub current_pass: u32,
@@ -35,8 +83,34 @@ pub struct EncodingStats {
     pub is_calculating_vmaf: bool,
     /// Chemin vers le fichier JSON contenant les scores VMAF par frame
     pub vmaf_json_path: Option<PathBuf>,
+    /// PSNR moyen de la luma en dB (Some si `quality_metrics`)
+    #[serde(default)]
+    pub psnr: Option<f64>,
+    /// SSIM moyen (0-1, Some si `quality_metrics`)
+    #[serde(default)]
+    pub ssim: Option<f64>,
+    /// Segment en cours (1-based) si la sortie est découpée
+    #[serde(default)]
+    pub segment_index: Option<u32>,
+    /// Nombre total de segments si la sortie est découpée
+    #[serde(default)]
+    pub segment_count: Option<u32>,
+    /// Vitesse historique de l'encodeur/preset (frames par seconde, toutes passes),
+    /// utilisée pour l'ETA tant que la vitesse mesurée n'est pas fiable
+    #[serde(default)]
+    pub expected_fps: Option<f64>,
+    /// Étape en cours (None = daemon antérieur, étape déduite de `is_calculating_vmaf`)
+    #[serde(default)]
+    pub phase: Option<EncodingPhase>,
+    /// Avancement global du job en pourcentage, toutes étapes et segments compris
+    /// (None si les étapes du job sont inconnues)
+    #[serde(default)]
+    pub overall_percent: Option<f64>,
 }
 
+/// Frames encodées avant que l'ETA ne repose entièrement sur la vitesse mesurée
+pub const ETA_PRIMING_FRAMES: u64 = 600;
+
 impl Default for EncodingStats {
     fn default() -> Self {
         Self {
@@ -47,6 +121,7 @@ impl Default for EncodingStats {
             time_encoded: Duration::from_secs(0),
             total_duration: None,
             progress_percent: 0.0,
+            remaining_frames: None,
             eta: None,
             current_pass: 1,
             total_passes: 1,
@@ -55,42 +130,316 @@ impl Default for EncodingStats {
             vmaf_max: None,
             is_calculating_vmaf: false,
             vmaf_json_path: None,
+            psnr: None,
+            ssim: None,
+            segment_index: None,
+            segment_count: None,
+            expected_fps: None,
+            phase: None,
+            overall_percent: None,
         }
     }
 }
 
 impl EncodingStats {
-    /// Calculer la progression en pourcentage
+    /// Calculer la progression de la passe en cours en pourcentage
+    ///
+    /// Un total de frames nul est traité comme inconnu (repli sur la durée), et la
+    /// progression vaut 0 si les deux sont inconnus ; le résultat reste dans 0-100 même si
+    /// l'encodeur dépasse le total estimé.
     #[allow(clippy::cast_precision_loss)]
     pub fn calculate_progress(&mut self) {
-        if let Some(total) = self.total_frames {
-            if total > 0 {
-                self.progress_percent = (self.frame as f64 / total as f64) * 100.0;
-            }
-        } else if let (Some(total_dur), _) = (self.total_duration, self.time_encoded) {
-            let total_secs = total_dur.as_secs_f64();
-            let encoded_secs = self.time_encoded.as_secs_f64();
-            if total_secs > 0.0 {
-                self.progress_percent = (encoded_secs / total_secs) * 100.0;
-            }
-        }
+        let percent = if let Some(total) = self.total_frames.filter(|&total| total > 0) {
+            self.frame as f64 / total as f64 * 100.0
+        } else if let Some(total_dur) = self.total_duration.filter(|d| !d.is_zero()) {
+            self.time_encoded.as_secs_f64() / total_dur.as_secs_f64() * 100.0
+        } else {
+            0.0
+        };
+        self.progress_percent = percent.clamp(0.0, 100.0);
     }
 
-    /// Calculer l'ETA basé sur le FPS actuel
+    /// Calculer les frames restantes et l'ETA
+    ///
+    /// Les frames restantes comptent les passes suivantes (aomenc). L'ETA les divise par la
+    /// vitesse mesurée ; avec une vitesse historique, il part de celle-ci puis bascule
+    /// progressivement vers la vitesse mesurée sur les [`ETA_PRIMING_FRAMES`] premières frames.
     #[allow(clippy::cast_precision_loss)]
     pub fn calculate_eta(&mut self) {
-        if let Some(total) = self.total_frames {
-            let remaining = total.saturating_sub(self.frame);
-            if self.fps > 0.0 {
-                let seconds_remaining = remaining as f64 / self.fps;
-                self.eta = Some(Duration::from_secs_f64(seconds_remaining));
+        let Some(total) = self.total_frames.filter(|&total| total > 0) else {
+            self.remaining_frames = None;
+            self.eta = None;
+            return;
+        };
+        let later_passes = u64::from(self.total_passes.saturating_sub(self.current_pass));
+        let remaining = total
+            .saturating_sub(self.frame)
+            .saturating_add(later_passes.saturating_mul(total));
+        self.remaining_frames = Some(remaining);
+
+        let live = (self.fps > 0.0).then(|| remaining as f64 / self.fps);
+        // La vitesse historique couvre tout le job (frames source / durée de toutes les
+        // passes) : chaque passe va `total_passes` fois plus vite
+        let passes = f64::from(self.total_passes.max(1));
+        let primed = self
+            .expected_fps
+            .filter(|&fps| fps > 0.0)
+            .map(|fps| remaining as f64 / (fps * passes));
+        let seconds_remaining = match (live, primed) {
+            (Some(live), Some(primed)) => {
+                let weight = (self.frame as f64 / ETA_PRIMING_FRAMES as f64).min(1.0);
+                live * weight + primed * (1.0 - weight)
             }
+            (Some(seconds), None) | (None, Some(seconds)) => seconds,
+            (None, None) => {
+                self.eta = None;
+                return;
+            }
+        };
+        // Vitesse infime ou aberrante : pas d'ETA plutôt qu'une durée hors limites
+        self.eta = Duration::try_from_secs_f64(seconds_remaining).ok();
+    }
+
+    /// Amorcer l'ETA avec la vitesse historique de l'encodeur/preset
+    ///
+    /// Sans effet pendant le calcul VMAF, dont la vitesse n'a rien à voir.
+    pub fn prime_eta(&mut self, expected_fps: Option<f64>) {
+        if self.is_calculating_vmaf || expected_fps.is_none() {
+            return;
         }
+        self.expected_fps = expected_fps;
+        self.update();
     }
 
-    /// Mettre à jour les statistiques et recalculer progress/eta
+    /// Recalculer `progress_percent`, `remaining_frames` et `eta` après une mise à jour
+    /// des frames, de la vitesse ou de la passe
     pub fn update(&mut self) {
         self.calculate_progress();
         self.calculate_eta();
     }
+
+    /// Progression de l'étape en cours (0-100), passes de la vidéo comprises
+    #[must_use]
+    pub fn phase_percent(&self) -> f64 {
+        if self.phase.is_none_or(|phase| phase == EncodingPhase::Video) && self.total_passes > 1 {
+            let done = f64::from(self.current_pass.clamp(1, self.total_passes) - 1);
+            (done * 100.0 + self.progress_percent) / f64::from(self.total_passes)
+        } else {
+            self.progress_percent
+        }
+    }
+
+    /// Calculer `overall_percent` d'après les étapes prévues du job, dans l'ordre
+    ///
+    /// Les étapes précédant l'étape en cours comptent comme terminées, même sautées
+    /// (audio déjà prêt à la fin de la vidéo). Sans étape en cours ou hors du plan,
+    /// l'avancement global reste inconnu.
+    pub fn calculate_overall(&mut self, plan: &[EncodingPhase]) {
+        self.overall_percent = self.phase.and_then(|phase| {
+            let position = plan.iter().position(|&p| p == phase)?;
+            let total: f64 = plan.iter().map(|p| p.weight()).sum();
+            let done: f64 = plan[..position].iter().map(|p| p.weight()).sum();
+            let current = phase.weight() * self.phase_percent() / 100.0;
+            Some(((done + current) / total * 100.0).clamp(0.0, 100.0))
+        });
+    }
+
+    /// Annoter les stats avec le segment en cours, l'avancement global couvrant
+    /// alors tous les segments
+    #[allow(clippy::cast_precision_loss)]
+    pub fn set_segment(&mut self, index: u32, count: u32) {
+        self.segment_index = Some(index);
+        self.segment_count = Some(count);
+        if let Some(percent) = self.overall_percent.filter(|_| count > 0) {
+            let done = f64::from(index.clamp(1, count) - 1);
+            self.overall_percent = Some((done * 100.0 + percent) / f64::from(count));
+        }
+    }
+
+    /// Résumé de l'avancement, ex. `Vidéo 80% • global 60%` (None sans étape connue)
+    #[must_use]
+    pub fn progress_summary(&self) -> Option<String> {
+        let phase = self.phase?;
+        let mut summary = format!("{phase} {:.0}%", self.phase_percent());
+        if let Some(overall) = self.overall_percent {
+            summary.push_str(&format!(" • global {overall:.0}%"));
+        }
+        Some(summary)
+    }
+
+    /// Marquer l'étape terminée : 100 %, plus rien à traiter
+    pub fn mark_finished(&mut self) {
+        if let Some(total) = self.total_frames {
+            self.frame = total;
+        }
+        self.progress_percent = 100.0;
+        self.remaining_frames = Some(0);
+        self.eta = Some(Duration::ZERO);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eta_primed_then_measured() {
+        let mut stats = EncodingStats {
+            total_frames: Some(2400),
+            ..EncodingStats::default()
+        };
+        // Aucune frame encodée : l'ETA vient de la vitesse historique
+        stats.prime_eta(Some(24.0));
+        assert_eq!(stats.eta, Some(Duration::from_secs(100)));
+
+        // Au-delà de la période d'amorçage : vitesse mesurée seule
+        stats.frame = 1200;
+        stats.fps = 12.0;
+        stats.calculate_eta();
+        assert_eq!(stats.eta, Some(Duration::from_secs(100)));
+
+        // Deux passes (aomenc) : la vitesse historique couvre déjà les deux passes
+        let mut two_pass = EncodingStats {
+            total_frames: Some(2400),
+            total_passes: 2,
+            ..EncodingStats::default()
+        };
+        two_pass.prime_eta(Some(24.0));
+        assert_eq!(two_pass.remaining_frames, Some(4800));
+        assert_eq!(two_pass.eta, Some(Duration::from_secs(100)));
+
+        // Seconde passe entamée : il reste la moitié d'une passe, soit un quart du job
+        two_pass.current_pass = 2;
+        two_pass.calculate_eta();
+        assert_eq!(two_pass.eta, Some(Duration::from_secs(50)));
+    }
+
+    #[test]
+    fn test_update_populates_progress_fields() {
+        let mut stats = EncodingStats {
+            frame: 250,
+            total_frames: Some(1000),
+            fps: 25.0,
+            ..EncodingStats::default()
+        };
+        stats.update();
+        assert!((stats.progress_percent - 25.0).abs() < f64::EPSILON);
+        assert_eq!(stats.remaining_frames, Some(750));
+        assert_eq!(stats.eta, Some(Duration::from_secs(30)));
+
+        // Première passe d'aomenc : la seconde reste à faire
+        stats.total_passes = 2;
+        stats.update();
+        assert_eq!(stats.remaining_frames, Some(1750));
+        assert_eq!(stats.eta, Some(Duration::from_secs(70)));
+        stats.current_pass = 2;
+        stats.frame = 500;
+        stats.fps = 50.0;
+        stats.update();
+        assert!((stats.progress_percent - 50.0).abs() < f64::EPSILON);
+        assert_eq!(stats.remaining_frames, Some(500));
+        assert_eq!(stats.eta, Some(Duration::from_secs(10)));
+
+        // Total devenu inconnu : aucune valeur périmée ne subsiste
+        stats.total_frames = None;
+        stats.update();
+        assert!(stats.progress_percent.abs() < f64::EPSILON);
+        assert_eq!(stats.remaining_frames, None);
+        assert_eq!(stats.eta, None);
+
+        stats.total_frames = Some(1000);
+        stats.mark_finished();
+        assert_eq!(stats.frame, 1000);
+        assert!((stats.progress_percent - 100.0).abs() < f64::EPSILON);
+        assert_eq!(stats.remaining_frames, Some(0));
+        assert_eq!(stats.eta, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_overall_progress_across_phases() {
+        let plan = [
+            EncodingPhase::Video,
+            EncodingPhase::Audio,
+            EncodingPhase::Muxing,
+            EncodingPhase::Vmaf,
+        ];
+        // Deux passes : la moitié de la seconde vaut 75 % de la vidéo
+        let mut stats = EncodingStats {
+            phase: Some(EncodingPhase::Video),
+            progress_percent: 50.0,
+            current_pass: 2,
+            total_passes: 2,
+            ..EncodingStats::default()
+        };
+        stats.calculate_overall(&plan);
+        assert!((stats.phase_percent() - 75.0).abs() < 1e-9);
+        assert!((stats.overall_percent.unwrap() - 50.0).abs() < 1e-9);
+        assert_eq!(
+            stats.progress_summary().as_deref(),
+            Some("Vidéo 75% • global 50%")
+        );
+
+        // Audio déjà prêt : le muxage suit directement la vidéo
+        let mut muxing = EncodingStats {
+            phase: Some(EncodingPhase::Muxing),
+            ..EncodingStats::default()
+        };
+        muxing.calculate_overall(&plan);
+        assert!((muxing.overall_percent.unwrap() - 105.0 / 150.0 * 100.0).abs() < 1e-9);
+
+        // Deuxième segment sur quatre, à moitié du sien
+        let mut segment = EncodingStats {
+            overall_percent: Some(50.0),
+            ..EncodingStats::default()
+        };
+        segment.set_segment(2, 4);
+        assert!((segment.overall_percent.unwrap() - 37.5).abs() < 1e-9);
+
+        // Étape hors plan ou inconnue (daemon antérieur) : pas d'avancement global
+        stats.phase = Some(EncodingPhase::Verify);
+        stats.calculate_overall(&plan);
+        assert_eq!(stats.overall_percent, None);
+        let legacy: EncodingStats =
+            serde_json::from_str(&serde_json::to_string(&EncodingStats::default()).unwrap())
+                .unwrap();
+        assert_eq!(legacy.phase, None);
+        assert_eq!(legacy.progress_summary(), None);
+    }
+
+    #[test]
+    fn test_zero_totals_do_not_break_progress() {
+        // Total de frames nul : repli sur la durée, pas d'ETA
+        let mut stats = EncodingStats {
+            frame: 12,
+            total_frames: Some(0),
+            fps: 24.0,
+            time_encoded: Duration::from_secs(5),
+            total_duration: Some(Duration::from_secs(10)),
+            ..EncodingStats::default()
+        };
+        stats.calculate_progress();
+        stats.calculate_eta();
+        assert!((stats.progress_percent - 50.0).abs() < f64::EPSILON);
+        assert_eq!(stats.eta, None);
+        stats.prime_eta(Some(24.0));
+        assert_eq!(stats.eta, None);
+
+        // Durée nulle également : la progression reste à zéro
+        stats.total_duration = Some(Duration::ZERO);
+        stats.progress_percent = 0.0;
+        stats.calculate_progress();
+        assert!(stats.progress_percent.abs() < f64::EPSILON);
+
+        // Total estimé dépassé : plafonné à 100 %
+        stats.total_frames = Some(10);
+        stats.calculate_progress();
+        assert!((stats.progress_percent - 100.0).abs() < f64::EPSILON);
+
+        // Vitesse infime : ETA hors limites ignoré
+        stats.total_frames = Some(u64::MAX);
+        stats.fps = f64::MIN_POSITIVE;
+        stats.eta = Some(Duration::from_secs(1));
+        stats.calculate_eta();
+        assert_eq!(stats.eta, None);
+    }
 }
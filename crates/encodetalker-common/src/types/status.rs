@@ -41,3 +41,59 @@ impl std::fmt::Display for JobStatus {
         }
     }
 }
+
+/// Catégorie d'échec d'un job (permet au client d'agir selon le type d'erreur)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureCategory {
+    /// Fichier source introuvable ou illisible
+    InputMissing,
+    /// Échec de l'analyse ffprobe
+    ProbeFailed,
+    /// L'encodeur (ou le décodage ffmpeg) a planté
+    EncoderFailed,
+    /// Échec de l'encodage/copie audio
+    AudioFailed,
+    /// Échec du muxage final
+    MuxFailed,
+    /// Espace disque insuffisant
+    DiskFull,
+    /// Aucune progression pendant le délai du watchdog : job arrêté
+    Stalled,
+    /// Annulé par l'utilisateur
+    Cancelled,
+    /// Erreur non classée
+    Other,
+    /// Fichier vérifié invalide (codec autre qu'AV1, erreurs de décodage)
+    VerifyFailed,
+}
+
+impl FailureCategory {
+    /// Erreur potentiellement transitoire (un nouvel essai peut réussir)
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            FailureCategory::EncoderFailed
+                | FailureCategory::AudioFailed
+                | FailureCategory::Stalled
+                | FailureCategory::Other
+        )
+    }
+}
+
+impl std::fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureCategory::InputMissing => write!(f, "Source introuvable"),
+            FailureCategory::ProbeFailed => write!(f, "Analyse échouée"),
+            FailureCategory::EncoderFailed => write!(f, "Encodeur planté"),
+            FailureCategory::AudioFailed => write!(f, "Audio échoué"),
+            FailureCategory::MuxFailed => write!(f, "Muxage échoué"),
+            FailureCategory::DiskFull => write!(f, "Disque plein"),
+            FailureCategory::Stalled => write!(f, "Bloqué"),
+            FailureCategory::Cancelled => write!(f, "Annulé"),
+            FailureCategory::Other => write!(f, "Erreur"),
+            FailureCategory::VerifyFailed => write!(f, "Fichier invalide"),
+        }
+    }
+}
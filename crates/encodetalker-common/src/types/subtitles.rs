@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Traitement des pistes de sous-titres que le conteneur de sortie n'accepte pas en copie
+/// (ex. SRT ou PGS vers MP4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitlePolicy {
+    /// Convertir les sous-titres texte au format du conteneur (mov_text, WebVTT) ;
+    /// les sous-titres image, inconvertibles, sont retirés avec un avertissement
+    #[default]
+    Convert,
+    /// Retirer les pistes incompatibles avec un avertissement
+    Drop,
+    /// Faire échouer le job avant l'encodage
+    Fail,
+}
+
+impl fmt::Display for SubtitlePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Convert => write!(f, "Conversion"),
+            Self::Drop => write!(f, "Retrait"),
+            Self::Fail => write!(f, "Échec"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtitle_policy_serde() {
+        assert_eq!(SubtitlePolicy::default(), SubtitlePolicy::Convert);
+        let policy: SubtitlePolicy = serde_json::from_str("\"drop\"").unwrap();
+        assert_eq!(policy, SubtitlePolicy::Drop);
+        assert_eq!(
+            serde_json::to_string(&SubtitlePolicy::Fail).unwrap(),
+            "\"fail\""
+        );
+    }
+}
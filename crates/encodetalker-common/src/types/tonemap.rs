@@ -0,0 +1,210 @@
+use super::EncoderType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Fonctions de transfert HDR signalées par ffprobe (`color_transfer`) : PQ (HDR10,
+/// HDR10+, Dolby Vision) et HLG
+pub const HDR_TRANSFERS: &[&str] = &["smpte2084", "arib-std-b67"];
+
+/// Filtres ffmpeg de la chaîne de tone mapping (`zscale` nécessite libzimg)
+pub const TONEMAP_FILTERS: &[&str] = &["zscale", "tonemap"];
+
+/// Luminance de crête SDR minimale acceptée (nits)
+pub const MIN_TONEMAP_PEAK_NITS: f64 = 50.0;
+
+/// Luminance de crête SDR maximale acceptée (nits)
+pub const MAX_TONEMAP_PEAK_NITS: f64 = 1000.0;
+
+/// La fonction de transfert de la source est-elle HDR ?
+#[must_use]
+pub fn is_hdr_transfer(transfer: &str) -> bool {
+    HDR_TRANSFERS.contains(&transfer)
+}
+
+/// Courbe de compression des hautes lumières (option `tonemap` du filtre ffmpeg)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TonemapMethod {
+    /// Courbe filmique, préserve les détails des hautes lumières
+    #[default]
+    Hable,
+    /// Proche de l'original jusqu'aux hautes lumières, puis compression douce
+    Mobius,
+    /// Compression simple, image plus terne
+    Reinhard,
+    /// Écrêtage des valeurs hors plage SDR
+    Clip,
+}
+
+impl TonemapMethod {
+    /// Nom de la courbe pour le filtre `tonemap`
+    #[must_use]
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            Self::Hable => "hable",
+            Self::Mobius => "mobius",
+            Self::Reinhard => "reinhard",
+            Self::Clip => "clip",
+        }
+    }
+}
+
+impl fmt::Display for TonemapMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hable => write!(f, "Hable"),
+            Self::Mobius => write!(f, "Möbius"),
+            Self::Reinhard => write!(f, "Reinhard"),
+            Self::Clip => write!(f, "Écrêtage"),
+        }
+    }
+}
+
+/// Conversion HDR → SDR des sources HDR, pour les écrans SDR
+///
+/// La chaîne `zscale`/`tonemap` est insérée au décodage et la sortie est signalée en
+/// BT.709 : les métadonnées HDR de la source ne sont pas reprises.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TonemapConfig {
+    /// Convertir les sources HDR (les sources SDR ne sont jamais modifiées)
+    pub enabled: bool,
+    pub method: TonemapMethod,
+    /// Luminance de crête de l'affichage SDR visé, en nits (`npl` de `zscale`)
+    pub peak_nits: f64,
+}
+
+impl Default for TonemapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: TonemapMethod::default(),
+            peak_nits: 100.0,
+        }
+    }
+}
+
+impl TonemapConfig {
+    /// Vérifier la luminance de crête
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si la luminance sort de
+    /// [`MIN_TONEMAP_PEAK_NITS`]-[`MAX_TONEMAP_PEAK_NITS`].
+    pub fn validate(self) -> Result<(), String> {
+        if (MIN_TONEMAP_PEAK_NITS..=MAX_TONEMAP_PEAK_NITS).contains(&self.peak_nits) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Luminance de crête {} nits hors plage ({MIN_TONEMAP_PEAK_NITS}-{MAX_TONEMAP_PEAK_NITS} nits)",
+                self.peak_nits
+            ))
+        }
+    }
+
+    /// Le tone mapping s'applique-t-il à une source de fonction de transfert `transfer` ?
+    #[must_use]
+    pub fn applies(self, transfer: Option<&str>) -> bool {
+        self.enabled && transfer.is_some_and(is_hdr_transfer)
+    }
+
+    /// Chaîne de filtres ffmpeg : linéarisation, passage en BT.709, compression des
+    /// hautes lumières puis retour en YUV 10 bits
+    #[must_use]
+    pub fn filter_chain(self) -> String {
+        format!(
+            "zscale=t=linear:npl={},format=gbrpf32le,zscale=p=bt709,tonemap=tonemap={}:desat=0,zscale=t=bt709:m=bt709:r=tv,format=yuv420p10le",
+            self.peak_nits,
+            self.method.ffmpeg_name()
+        )
+    }
+}
+
+/// Options signalant une sortie SDR BT.709 (primaires, transfert, matrice) à l'encodeur
+#[must_use]
+pub fn bt709_color_args(encoder: EncoderType) -> Vec<String> {
+    let args: &[&str] = match encoder {
+        EncoderType::SvtAv1 => &[
+            "--color-primaries",
+            "1",
+            "--transfer-characteristics",
+            "1",
+            "--matrix-coefficients",
+            "1",
+        ],
+        EncoderType::Aom => &[
+            "--color-primaries=bt709",
+            "--transfer-characteristics=bt709",
+            "--matrix-coefficients=bt709",
+        ],
+        EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => &[
+            "-color_primaries",
+            "bt709",
+            "-color_trc",
+            "bt709",
+            "-colorspace",
+            "bt709",
+        ],
+    };
+    args.iter().map(ToString::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tonemap_config() {
+        let config = TonemapConfig {
+            enabled: true,
+            ..TonemapConfig::default()
+        };
+        assert!(config.applies(Some("smpte2084")));
+        assert!(config.applies(Some("arib-std-b67")));
+        assert!(!config.applies(Some("bt709")));
+        assert!(!config.applies(None));
+        assert!(!TonemapConfig::default().applies(Some("smpte2084")));
+
+        let chain = TonemapConfig {
+            method: TonemapMethod::Mobius,
+            peak_nits: 203.0,
+            ..config
+        }
+        .filter_chain();
+        assert!(chain.starts_with("zscale=t=linear:npl=203,"));
+        assert!(chain.contains("tonemap=tonemap=mobius:"));
+        assert!(chain.ends_with(",format=yuv420p10le"));
+
+        assert!(config.validate().is_ok());
+        let dim = TonemapConfig {
+            peak_nits: 10.0,
+            ..config
+        };
+        assert!(dim.validate().is_err());
+
+        let parsed: TonemapConfig =
+            serde_json::from_str(r#"{"enabled": true, "method": "reinhard"}"#).unwrap();
+        assert_eq!(parsed.method, TonemapMethod::Reinhard);
+        assert!((parsed.peak_nits - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bt709_color_args() {
+        assert_eq!(bt709_color_args(EncoderType::SvtAv1).len(), 6);
+        assert_eq!(
+            bt709_color_args(EncoderType::Aom)[0],
+            "--color-primaries=bt709"
+        );
+        assert_eq!(
+            bt709_color_args(EncoderType::Vaapi),
+            [
+                "-color_primaries",
+                "bt709",
+                "-color_trc",
+                "bt709",
+                "-colorspace",
+                "bt709"
+            ]
+        );
+    }
+}
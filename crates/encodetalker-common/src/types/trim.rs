@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Plage de temps à encoder (en secondes depuis le début de la source)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct TrimRange {
+    /// Début de l'extrait (None = début du fichier)
+    pub start: Option<f64>,
+    /// Fin de l'extrait (None = fin du fichier)
+    pub end: Option<f64>,
+}
+
+impl TrimRange {
+    /// Aucune découpe configurée ?
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    /// Vérifier la cohérence de la plage
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si une borne est négative ou si la fin précède le début.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start.is_some_and(|s| s < 0.0) || self.end.is_some_and(|e| e < 0.0) {
+            return Err("Les bornes de découpe doivent être positives".to_string());
+        }
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            if end <= start {
+                return Err(format!(
+                    "La fin ({}) doit être après le début ({})",
+                    format_timestamp(end),
+                    format_timestamp(start)
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Options d'entrée ffmpeg (à placer avant `-i`)
+    ///
+    /// `-ss` en option d'entrée est précis à la frame lorsqu'on décode ; `-t` est
+    /// utilisé plutôt que `-to` pour rester relatif au point de départ.
+    #[must_use]
+    pub fn ffmpeg_input_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(start) = self.start.filter(|s| *s > 0.0) {
+            args.push("-ss".to_string());
+            args.push(format!("{start:.3}"));
+        }
+        if let Some(end) = self.end {
+            args.push("-t".to_string());
+            args.push(format!("{:.3}", end - self.start.unwrap_or(0.0)));
+        }
+        args
+    }
+
+    /// Durée de l'extrait, bornée par la durée de la source si connue
+    #[must_use]
+    pub fn trimmed_duration(&self, source: Option<Duration>) -> Option<Duration> {
+        let source_secs = source.map(|d| d.as_secs_f64());
+        let end = match (self.end, source_secs) {
+            (Some(end), Some(total)) => end.min(total),
+            (Some(end), None) => end,
+            (None, Some(total)) => total,
+            (None, None) => return None,
+        };
+        let start = self.start.unwrap_or(0.0);
+        Some(Duration::from_secs_f64((end - start).max(0.0)))
+    }
+}
+
+/// Parser un timestamp `SS`, `MM:SS` ou `HH:MM:SS` (secondes décimales acceptées)
+#[must_use]
+pub fn parse_timestamp(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let parts: Vec<&str> = text.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let mut total = 0.0;
+    for (i, part) in parts.iter().enumerate() {
+        let value: f64 = part.parse().ok()?;
+        // Seules les secondes (dernier champ) peuvent être décimales
+        if value < 0.0 || (i + 1 < parts.len() && value.fract() != 0.0) {
+            return None;
+        }
+        total = total * 60.0 + value;
+    }
+    Some(total)
+}
+
+/// Formater des secondes en `HH:MM:SS` (avec millisecondes si non entières)
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Safe: secondes positives
+pub fn format_timestamp(secs: f64) -> String {
+    // Arrondi sur le total : 59.9996 donne 00:01:00, pas 00:00:59.1000
+    let total_millis = (secs.max(0.0) * 1000.0).round() as u64;
+    let whole = total_millis / 1000;
+    let millis = total_millis % 1000;
+    let base = format!(
+        "{:02}:{:02}:{:02}",
+        whole / 3600,
+        (whole % 3600) / 60,
+        whole % 60
+    );
+    if millis > 0 {
+        format!("{base}.{millis:03}")
+    } else {
+        base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_timestamp() {
+        assert_eq!(parse_timestamp("90"), Some(90.0));
+        assert_eq!(parse_timestamp("1:30"), Some(90.0));
+        assert_eq!(parse_timestamp("01:02:03.5"), Some(3723.5));
+        assert_eq!(parse_timestamp("1.5:00"), None);
+        assert_eq!(parse_timestamp("abc"), None);
+        assert_eq!(parse_timestamp(""), None);
+
+        assert_eq!(format_timestamp(3723.5), "01:02:03.500");
+        assert_eq!(format_timestamp(90.0), "00:01:30");
+        assert_eq!(format_timestamp(59.9996), "00:01:00");
+        assert_eq!(format_timestamp(3599.9999), "01:00:00");
+        assert_eq!(format_timestamp(0.0004), "00:00:00");
+        assert_eq!(format_timestamp(-1.0), "00:00:00");
+    }
+
+    #[test]
+    fn test_trim_range_args_and_duration() {
+        let trim = TrimRange {
+            start: Some(60.0),
+            end: Some(90.0),
+        };
+        assert!(trim.validate().is_ok());
+        assert_eq!(
+            trim.ffmpeg_input_args(),
+            vec!["-ss", "60.000", "-t", "30.000"]
+        );
+        assert_eq!(
+            trim.trimmed_duration(Some(Duration::from_secs(600))),
+            Some(Duration::from_secs(30))
+        );
+
+        // Fin au-delà de la source : bornée à la durée réelle
+        let trim = TrimRange {
+            start: Some(550.0),
+            end: Some(700.0),
+        };
+        assert_eq!(
+            trim.trimmed_duration(Some(Duration::from_secs(600))),
+            Some(Duration::from_secs(50))
+        );
+
+        let reversed = TrimRange {
+            start: Some(90.0),
+            end: Some(60.0),
+        };
+        assert!(reversed.validate().is_err());
+        assert!(TrimRange::default().ffmpeg_input_args().is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+/// Nombre maximal d'erreurs de décodage conservées dans un rapport
+pub const MAX_VERIFY_ERRORS: usize = 20;
+
+/// Écart toléré entre la durée décodée et la durée annoncée par le conteneur (secondes)
+pub const VERIFY_DURATION_TOLERANCE_SECS: f64 = 1.0;
+
+/// Résumé d'un stream du fichier vérifié
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamSummary {
+    /// Type du stream (`video`, `audio`, `subtitle`)
+    pub kind: String,
+    /// Codec (nom ffprobe)
+    pub codec: String,
+    pub language: Option<String>,
+}
+
+/// Rapport d'un job de vérification (`JobKind::Verify`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Codec du stream vidéo principal
+    pub video_codec: String,
+    pub width: u32,
+    pub height: u32,
+    pub duration_secs: Option<f64>,
+    /// Framerate moyen du stream vidéo (0 = inconnu, rapports antérieurs)
+    #[serde(default)]
+    pub fps: f64,
+    /// Frames vidéo décodées (None = décodage interrompu avant la première)
+    pub decoded_frames: Option<u64>,
+    pub streams: Vec<StreamSummary>,
+    /// Erreurs signalées pendant le décodage (les premières seulement)
+    pub decode_errors: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Le stream vidéo est-il en AV1 ?
+    #[must_use]
+    pub fn is_av1(&self) -> bool {
+        self.video_codec.eq_ignore_ascii_case("av1")
+    }
+
+    /// Durée couverte par les frames décodées (None = framerate ou décodage inconnu)
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Safe: nombre de frames bien inférieur à 2^52
+    pub fn decoded_secs(&self) -> Option<f64> {
+        let frames = self.decoded_frames?;
+        (self.fps > 0.0).then(|| frames as f64 / self.fps)
+    }
+
+    /// Problèmes relevés : codec autre qu'AV1, durée décodée différente de celle du
+    /// conteneur (fichier tronqué), erreurs de décodage (vide = fichier valide)
+    #[must_use]
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.is_av1() {
+            problems.push(format!("codec vidéo {} au lieu d'AV1", self.video_codec));
+        }
+        if let (Some(decoded), Some(expected)) = (self.decoded_secs(), self.duration_secs) {
+            if (decoded - expected).abs() > VERIFY_DURATION_TOLERANCE_SECS {
+                problems.push(format!("{decoded:.1}s décodées au lieu de {expected:.1}s"));
+            }
+        }
+        if let Some(first) = self.decode_errors.first() {
+            problems.push(format!(
+                "{} erreur(s) de décodage, dont: {first}",
+                self.decode_errors.len()
+            ));
+        }
+        problems
+    }
+
+    /// Résumé sur une ligne : `av1 1920x1080, 1:32:10, 132600 frames décodées, 2 audio (eng, jpn)`
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!(
+            "{} {}x{}",
+            self.video_codec, self.width, self.height
+        )];
+        if let Some(secs) = self.duration_secs {
+            // Safe: durée positive, bien inférieure à 2^53 secondes
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let secs = secs.round() as u64;
+            parts.push(format!(
+                "{}:{:02}:{:02}",
+                secs / 3600,
+                secs % 3600 / 60,
+                secs % 60
+            ));
+        }
+        if let Some(frames) = self.decoded_frames {
+            parts.push(format!("{frames} frames décodées"));
+        }
+        for (kind, label) in [("audio", "audio"), ("subtitle", "sous-titres")] {
+            let streams: Vec<&StreamSummary> =
+                self.streams.iter().filter(|s| s.kind == kind).collect();
+            if streams.is_empty() {
+                continue;
+            }
+            let languages: Vec<&str> = streams
+                .iter()
+                .filter_map(|s| s.language.as_deref())
+                .collect();
+            if languages.is_empty() {
+                parts.push(format!("{} {label}", streams.len()));
+            } else {
+                parts.push(format!(
+                    "{} {label} ({})",
+                    streams.len(),
+                    languages.join(", ")
+                ));
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(kind: &str, codec: &str, language: Option<&str>) -> StreamSummary {
+        StreamSummary {
+            kind: kind.to_string(),
+            codec: codec.to_string(),
+            language: language.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_report_summary_and_problems() {
+        let mut report = VerifyReport {
+            video_codec: "av1".to_string(),
+            width: 1920,
+            height: 1080,
+            duration_secs: Some(5530.4),
+            fps: 23.976,
+            decoded_frames: Some(132_600),
+            streams: vec![
+                stream("video", "av1", None),
+                stream("audio", "opus", Some("eng")),
+                stream("audio", "opus", Some("jpn")),
+                stream("subtitle", "ass", None),
+            ],
+            decode_errors: Vec::new(),
+        };
+        assert_eq!(
+            report.summary(),
+            "av1 1920x1080, 1:32:10, 132600 frames décodées, 2 audio (eng, jpn), 1 sous-titres"
+        );
+        assert!(report.problems().is_empty());
+
+        // Framerate inconnu : durée non vérifiée
+        report.decoded_frames = Some(1000);
+        report.fps = 0.0;
+        assert!(report.problems().is_empty());
+        report.fps = 25.0;
+        assert_eq!(report.problems(), ["40.0s décodées au lieu de 5530.4s"]);
+        report.decoded_frames = Some(138_260);
+        assert!(report.problems().is_empty());
+
+        report.video_codec = "h264".to_string();
+        report
+            .decode_errors
+            .push("Invalid NAL unit size".to_string());
+        assert_eq!(
+            report.problems(),
+            [
+                "codec vidéo h264 au lieu d'AV1",
+                "1 erreur(s) de décodage, dont: Invalid NAL unit size"
+            ]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Traitement des sources à framerate variable (VFR, ex. vidéos de téléphone)
+///
+/// Le pipe `yuv4mpegpipe` vers l'encodeur ne transporte pas de timestamps : les frames
+/// y sont rejouées à cadence fixe, ce qui désynchronise l'audio si rien n'est fait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VfrMode {
+    /// Normaliser au framerate moyen en dupliquant ou supprimant des frames (synchro exacte)
+    #[default]
+    Cfr,
+    /// Garder chaque frame une seule fois, rejouée au framerate moyen
+    /// (durée totale conservée, cadence locale lissée)
+    Passthrough,
+}
+
+impl VfrMode {
+    /// Options de sortie ffmpeg du décodage d'une source VFR de framerate moyen `fps`
+    #[must_use]
+    pub fn ffmpeg_output_args(self, fps: f64) -> Vec<String> {
+        let vsync = match self {
+            Self::Cfr => "cfr",
+            Self::Passthrough => "passthrough",
+        };
+        vec![
+            "-vsync".to_string(),
+            vsync.to_string(),
+            "-r".to_string(),
+            format!("{fps:.3}"),
+        ]
+    }
+}
+
+impl fmt::Display for VfrMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cfr => write!(f, "Framerate constant"),
+            Self::Passthrough => write!(f, "Passthrough"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vfr_mode_args() {
+        assert_eq!(
+            VfrMode::Cfr.ffmpeg_output_args(29.87),
+            ["-vsync", "cfr", "-r", "29.870"]
+        );
+        assert_eq!(
+            VfrMode::Passthrough.ffmpeg_output_args(30.0),
+            ["-vsync", "passthrough", "-r", "30.000"]
+        );
+        let mode: VfrMode = serde_json::from_str("\"passthrough\"").unwrap();
+        assert_eq!(mode, VfrMode::Passthrough);
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+/// Unités des tailles affichées
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnits {
+    /// Puissances de 1000 : Mo, Go, To (comme les fabricants de disques)
+    #[default]
+    Decimal,
+    /// Puissances de 1024 : Mio, Gio, Tio (comme `ls -h` ou `du -h`)
+    Binary,
+}
+
+/// Unité des débits affichés
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BitrateUnit {
+    /// kbps sous 1000 kbps, Mbps au-delà
+    #[default]
+    Auto,
+    Kbps,
+    Mbps,
+}
+
+/// Unités d'affichage du TUI (section `[ui.units]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct UnitPreferences {
+    pub size: SizeUnits,
+    pub bitrate: BitrateUnit,
+}
+
+impl UnitPreferences {
+    /// Taille lisible dans l'unité choisie
+    #[must_use]
+    pub fn size(self, bytes: u64) -> String {
+        format_bytes(bytes, self.size)
+    }
+
+    /// Débit lisible dans l'unité choisie
+    #[must_use]
+    pub fn bitrate(self, kbps: f64) -> String {
+        format_bitrate(kbps, self.bitrate)
+    }
+}
+
+/// Taille lisible : `850 Mo`, `12,4 Go`, `1,8 To` (ou `Mio`, `Gio`, `Tio`)
+///
+/// Les dixièmes sont tronqués, jamais arrondis vers le haut.
+#[must_use]
+pub fn format_bytes(bytes: u64, units: SizeUnits) -> String {
+    let (base, labels) = match units {
+        SizeUnits::Decimal => (1000_u64, ["Mo", "Go", "To"]),
+        SizeUnits::Binary => (1024, ["Mio", "Gio", "Tio"]),
+    };
+    let mega = base.pow(2);
+    for (unit, label) in [(base.pow(4), labels[2]), (base.pow(3), labels[1])] {
+        if bytes >= unit {
+            let tenths = u128::from(bytes) * 10 / u128::from(unit);
+            return format!("{},{} {label}", tenths / 10, tenths % 10);
+        }
+    }
+    format!("{} {}", bytes / mega, labels[0])
+}
+
+/// Taille lisible avec unités décimales : `850 Mo`, `12,4 Go`, `1,8 To`
+#[must_use]
+pub fn format_size(bytes: u64) -> String {
+    format_bytes(bytes, SizeUnits::Decimal)
+}
+
+/// Débit lisible : `850 kbps` ou `4,25 Mbps`
+#[must_use]
+pub fn format_bitrate(kbps: f64, unit: BitrateUnit) -> String {
+    let mbps = match unit {
+        BitrateUnit::Auto => kbps >= 1000.0,
+        BitrateUnit::Kbps => false,
+        BitrateUnit::Mbps => true,
+    };
+    if mbps {
+        format!("{:.2} Mbps", kbps / 1000.0).replace('.', ",")
+    } else {
+        format!("{kbps:.0} kbps")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(850_000_000), "850 Mo");
+        assert_eq!(format_size(12_450_000_000), "12,4 Go");
+        assert_eq!(format_size(1_800_000_000_000), "1,8 To");
+        assert_eq!(format_size(400_000), "0 Mo");
+    }
+
+    #[test]
+    fn test_binary_sizes() {
+        assert_eq!(format_bytes(850_000_000, SizeUnits::Binary), "810 Mio");
+        assert_eq!(format_bytes(12_450_000_000, SizeUnits::Binary), "11,5 Gio");
+        assert_eq!(format_bytes(1 << 40, SizeUnits::Binary), "1,0 Tio");
+        assert_eq!(format_bytes(u64::MAX, SizeUnits::Decimal), "18446744,0 To");
+    }
+
+    #[test]
+    fn test_format_bitrate() {
+        assert_eq!(format_bitrate(850.4, BitrateUnit::Auto), "850 kbps");
+        assert_eq!(format_bitrate(4254.0, BitrateUnit::Auto), "4,25 Mbps");
+        assert_eq!(format_bitrate(4254.0, BitrateUnit::Kbps), "4254 kbps");
+        assert_eq!(format_bitrate(850.0, BitrateUnit::Mbps), "0,85 Mbps");
+
+        let units: UnitPreferences = serde_json::from_str(r#"{"size": "binary"}"#).unwrap();
+        assert_eq!(units.size, SizeUnits::Binary);
+        assert_eq!(units.bitrate, BitrateUnit::Auto);
+    }
+}
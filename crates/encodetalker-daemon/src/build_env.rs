@@ -0,0 +1,208 @@
+use encodetalker_common::protocol::messages::{BuildEnvCheck, BuildEnvCheckKind, BuildEnvReport};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Outils système requis par `INSTALL_DEPENDENCIES.sh` (alternatives acceptées)
+///
+/// `CMake`, Meson et Ninja sont téléchargés par le script et ne sont pas vérifiés.
+const REQUIRED_TOOLS: &[(&str, &[&str])] = &[
+    ("Compilateur C", &["cc", "gcc", "clang"]),
+    ("Compilateur C++", &["c++", "g++", "clang++"]),
+    ("make", &["make"]),
+    ("git", &["git"]),
+    ("pkg-config", &["pkg-config", "pkgconf"]),
+    ("nasm", &["nasm"]),
+    ("yasm", &["yasm"]),
+    ("python3 (Meson)", &["python3"]),
+    ("curl ou wget", &["curl", "wget"]),
+    ("tar", &["tar"]),
+    ("xz (sources FFmpeg)", &["xz"]),
+];
+
+/// Bibliothèques activées dans le `./configure` de FFmpeg (module pkg-config)
+///
+/// Elles sont compilées par le script si absentes : leur absence n'est pas bloquante.
+const FFMPEG_LIBS: &[(&str, &str)] = &[
+    ("libopus", "opus"),
+    ("libvpx", "vpx"),
+    ("libdav1d", "dav1d"),
+    ("libvmaf", "libvmaf"),
+];
+
+/// Chercher un exécutable dans une liste de répertoires (format `PATH`, pas forcément UTF-8)
+#[must_use]
+pub fn find_in_path(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Vérifier les outils système nécessaires à la compilation des dépendances
+#[must_use]
+pub fn check_system_deps(path_var: &OsStr) -> Vec<BuildEnvCheck> {
+    REQUIRED_TOOLS
+        .iter()
+        .map(|(label, candidates)| {
+            let found = candidates
+                .iter()
+                .find_map(|name| find_in_path(name, path_var));
+            BuildEnvCheck {
+                name: (*label).to_string(),
+                kind: BuildEnvCheckKind::Tool,
+                found: found.is_some(),
+                required: true,
+                detail: found.map_or_else(
+                    || format!("introuvable (cherché: {})", candidates.join(", ")),
+                    |path| path.display().to_string(),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Commande d'installation des outils de compilation d'après `/etc/os-release`
+///
+/// La distribution est reconnue par `ID` puis par `ID_LIKE` ; None si elle est inconnue.
+#[must_use]
+pub fn linux_install_command(os_release: &str) -> Option<&'static str> {
+    let field = |key: &str| {
+        os_release
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(|value| value.trim_matches('"').to_lowercase())
+            .unwrap_or_default()
+    };
+    let ids = format!("{} {}", field("ID"), field("ID_LIKE"));
+    ids.split_whitespace().find_map(|id| match id {
+        "arch" | "manjaro" | "endeavouros" => {
+            Some("sudo pacman -S --needed base-devel git nasm yasm pkgconf python curl xz")
+        }
+        "debian" | "ubuntu" => {
+            Some("sudo apt install build-essential git nasm yasm pkg-config python3 curl xz-utils")
+        }
+        "fedora" | "rhel" | "centos" => {
+            Some("sudo dnf install gcc gcc-c++ make git nasm yasm pkgconf python3 curl xz")
+        }
+        "opensuse" | "suse" => {
+            Some("sudo zypper install gcc gcc-c++ make git nasm yasm pkg-config python3 curl xz")
+        }
+        _ => None,
+    })
+}
+
+/// Instructions d'installation des outils de compilation pour la plateforme courante
+#[must_use]
+pub fn install_instructions() -> String {
+    if cfg!(target_os = "macos") {
+        return "xcode-select --install && brew install git nasm yasm pkg-config python3 xz"
+            .to_string();
+    }
+    let os_release = std::fs::read_to_string("/etc/os-release").unwrap_or_default();
+    linux_install_command(&os_release).map_or_else(
+        || {
+            "Installer un compilateur C/C++, make, git, pkg-config, nasm, yasm, python3, \
+             curl (ou wget), tar et xz avec le gestionnaire de paquets du système"
+                .to_string()
+        },
+        str::to_string,
+    )
+}
+
+/// Message d'erreur listant les outils requis manquants (None = tous présents)
+#[must_use]
+pub fn missing_tools_message(checks: &[BuildEnvCheck]) -> Option<String> {
+    let missing: Vec<&str> = checks
+        .iter()
+        .filter(|check| check.required && !check.found)
+        .map(|check| check.name.as_str())
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Outils de compilation manquants : {}\n\nLes installer puis redémarrer le daemon :\n  {}",
+        missing.join(", "),
+        install_instructions()
+    ))
+}
+
+/// Vérifier une bibliothèque via pkg-config (système et `<deps_dir>/lib/pkgconfig`)
+async fn check_pkg_config_lib(label: &str, module: &str, pkg_config_path: &str) -> BuildEnvCheck {
+    let version = Command::new("pkg-config")
+        .arg("--modversion")
+        .arg(module)
+        .env("PKG_CONFIG_PATH", pkg_config_path)
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    BuildEnvCheck {
+        name: label.to_string(),
+        kind: BuildEnvCheckKind::Library,
+        found: version.is_some(),
+        required: false,
+        detail: version.map_or_else(
+            || "absente, sera compilée par le script".to_string(),
+            |v| format!("version {v}"),
+        ),
+    }
+}
+
+/// Diagnostic complet de l'environnement de compilation des dépendances
+pub async fn check_build_env(deps_dir: &Path) -> BuildEnvReport {
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let mut checks = check_system_deps(&path_var);
+
+    let mut pkg_config_path = deps_dir.join("lib/pkgconfig").display().to_string();
+    if let Ok(existing) = std::env::var("PKG_CONFIG_PATH") {
+        pkg_config_path = format!("{pkg_config_path}:{existing}");
+    }
+    for (label, module) in FFMPEG_LIBS {
+        checks.push(check_pkg_config_lib(label, module, &pkg_config_path).await);
+    }
+
+    BuildEnvReport { checks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_system_deps_reports_missing_tools() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("git"), "").unwrap();
+
+        let path_var = std::env::join_paths([Path::new("/nonexistent"), &dir]).unwrap();
+        let checks = check_system_deps(&path_var);
+        let git = checks.iter().find(|c| c.name == "git").unwrap();
+        assert!(git.found);
+        let nasm = checks.iter().find(|c| c.name == "nasm").unwrap();
+        assert!(!nasm.found);
+        assert!(nasm.required);
+
+        let message = missing_tools_message(&checks).unwrap();
+        assert!(message.contains("nasm") && !message.contains("git,"));
+        assert!(missing_tools_message(&[]).is_none());
+    }
+
+    #[test]
+    fn test_linux_install_command_from_os_release() {
+        let ubuntu = "NAME=\"Ubuntu\"\nID=ubuntu\nID_LIKE=debian\n";
+        assert!(linux_install_command(ubuntu)
+            .unwrap()
+            .starts_with("sudo apt"));
+        // Dérivée reconnue par ID_LIKE
+        let cachyos = "ID=cachyos\nID_LIKE=\"arch\"\n";
+        assert!(linux_install_command(cachyos)
+            .unwrap()
+            .starts_with("sudo pacman"));
+        assert!(linux_install_command("ID=nixos\n").is_none());
+        assert!(linux_install_command("").is_none());
+    }
+}
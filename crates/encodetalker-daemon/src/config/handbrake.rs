@@ -0,0 +1,462 @@
+use anyhow::{Context, Result};
+use encodetalker_common::protocol::messages::ImportedPreset;
+use encodetalker_common::{
+    validate_language_codes, AudioMode, DeinterlaceMode, Deinterlacer, EncoderType, EncodingConfig,
+    EncodingMode, Tune,
+};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Champs Handbrake repris dans la configuration (les autres sont signalés)
+const MAPPED_FIELDS: &[&str] = &[
+    "VideoEncoder",
+    "VideoQualityType",
+    "VideoQualitySlider",
+    "VideoAvgBitrate",
+    "VideoPreset",
+    "VideoTune",
+    "AudioList",
+    "AudioLanguageList",
+    "SubtitleLanguageList",
+    "PictureWidth",
+    "PictureHeight",
+    "PictureDeinterlaceFilter",
+    "PictureDeinterlacePreset",
+];
+
+/// Champs descriptifs d'un preset, sans effet sur l'encodage
+const DESCRIPTIVE_FIELDS: &[&str] = &[
+    "PresetName",
+    "PresetDescription",
+    "PresetDisabled",
+    "Type",
+    "Folder",
+    "Default",
+    "ChildrenArray",
+    "UsesPictureFilters",
+];
+
+/// Charger les presets d'un export JSON de Handbrake
+///
+/// # Errors
+///
+/// Retourne une erreur si le fichier est illisible ou si son contenu n'est pas un export
+/// de presets Handbrake (voir [`parse_handbrake_presets`]).
+pub async fn load_handbrake_presets(path: &Path) -> Result<Vec<ImportedPreset>> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Impossible de lire {}", path.display()))?;
+    parse_handbrake_presets(&content).with_context(|| format!("Import de {}", path.display()))
+}
+
+/// Convertir les presets d'un export JSON de Handbrake en configurations d'encodage
+///
+/// Sont repris : l'encodeur AV1, la qualité ou le débit moyen, le preset et le tune, la
+/// première piste de `AudioList`, les langues audio et sous-titres et le désentrelacement.
+/// Les autres réglages, sans équivalent, sont ignorés avec un avertissement par preset.
+/// Les dossiers (`ChildrenArray`) sont parcourus.
+///
+/// # Errors
+///
+/// Retourne une erreur si le JSON est invalide, s'il ne contient aucun preset ou si une
+/// configuration obtenue est invalide.
+pub fn parse_handbrake_presets(content: &str) -> Result<Vec<ImportedPreset>> {
+    let value: Value = serde_json::from_str(content).context("JSON invalide")?;
+    let mut presets = Vec::new();
+    match value.get("PresetList") {
+        Some(list) => collect_presets(list, &mut presets),
+        None => collect_presets(&value, &mut presets),
+    }
+    if presets.is_empty() {
+        anyhow::bail!("Aucun preset Handbrake trouvé (PresetList vide ou absente)");
+    }
+
+    presets
+        .into_iter()
+        .enumerate()
+        .map(|(index, preset)| {
+            let imported = import_preset(preset, index);
+            imported
+                .config
+                .validate()
+                .map_err(|e| anyhow::anyhow!("Preset « {} » : {e}", imported.name))?;
+            Ok(imported)
+        })
+        .collect()
+}
+
+/// Presets d'une liste ou d'un objet, dossiers compris
+fn collect_presets<'a>(value: &'a Value, presets: &mut Vec<&'a Map<String, Value>>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_presets(item, presets);
+            }
+        }
+        Value::Object(object) => match object.get("ChildrenArray") {
+            Some(children) => collect_presets(children, presets),
+            None if object.contains_key("PresetName") => presets.push(object),
+            None => {}
+        },
+        _ => {}
+    }
+}
+
+/// Valeur sans effet (désactivée, vide ou automatique) : pas besoin de la signaler
+fn is_unset(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(enabled) => !enabled,
+        Value::Number(n) => n.as_f64() == Some(0.0),
+        Value::String(s) => matches!(s.as_str(), "" | "off" | "none" | "auto"),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(object) => object.is_empty(),
+    }
+}
+
+/// Convertir un preset ; `index` nomme les presets sans `PresetName`
+fn import_preset(preset: &Map<String, Value>, index: usize) -> ImportedPreset {
+    let str_field = |key: &str| preset.get(key).and_then(Value::as_str).unwrap_or("");
+    let num_field = |key: &str| preset.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+    let name = match str_field("PresetName") {
+        "" => format!("Handbrake {}", index + 1),
+        name => name.to_string(),
+    };
+    let mut config = EncodingConfig::default();
+    let mut warnings = Vec::new();
+
+    // Encodeur : les variantes 10 bits sont équivalentes (la sortie est toujours en 10 bits)
+    let encoder_name = str_field("VideoEncoder");
+    let encoder = match encoder_name.trim_end_matches("_10bit") {
+        "" | "svt_av1" => Some(EncoderType::SvtAv1),
+        "nvenc_av1" => Some(EncoderType::Nvenc),
+        "qsv_av1" => Some(EncoderType::Qsv),
+        _ => None,
+    };
+    config.encoder = encoder.unwrap_or(EncoderType::SvtAv1);
+    config.encoder_params.tune = Tune::default_for(config.encoder);
+    if encoder.is_none() {
+        warnings.push(format!(
+            "Encodeur {encoder_name} non AV1 : SVT-AV1 avec sa qualité et son preset par défaut"
+        ));
+    }
+
+    if encoder.is_some() {
+        import_quality(preset, &mut config, &mut warnings);
+        import_preset_speed(str_field("VideoPreset"), &mut config, &mut warnings);
+        match (str_field("VideoTune"), config.encoder) {
+            ("", _) => {}
+            ("psnr", EncoderType::SvtAv1) => config.encoder_params.tune = Tune::Psnr,
+            ("ssim", EncoderType::SvtAv1) => config.encoder_params.tune = Tune::Ssim,
+            (tune, _) => warnings.push(format!("Tune {tune} sans équivalent, ignoré")),
+        }
+    }
+
+    import_audio(preset.get("AudioList"), &mut config, &mut warnings);
+    config.audio_languages = import_languages(preset, "AudioLanguageList", &mut warnings);
+    config.subtitle_languages = import_languages(preset, "SubtitleLanguageList", &mut warnings);
+
+    let (width, height) = (num_field("PictureWidth"), num_field("PictureHeight"));
+    if width > 0.0 || height > 0.0 {
+        warnings.push(format!(
+            "Dimensions {width}x{height} ignorées : la résolution de la source est conservée"
+        ));
+    }
+
+    match str_field("PictureDeinterlaceFilter") {
+        "" => {}
+        "off" => config.deinterlace.mode = DeinterlaceMode::Off,
+        "yadif" => config.deinterlace.filter = Deinterlacer::Yadif,
+        "bwdif" => config.deinterlace.filter = Deinterlacer::Bwdif,
+        filter => warnings.push(format!(
+            "Désentrelacement {filter} remplacé par yadif (si la source est entrelacée)"
+        )),
+    }
+    config.deinterlace.double_rate = str_field("PictureDeinterlacePreset") == "bob";
+
+    let mut ignored: Vec<&str> = preset
+        .iter()
+        .filter(|(key, value)| {
+            !MAPPED_FIELDS.contains(&key.as_str())
+                && !DESCRIPTIVE_FIELDS.contains(&key.as_str())
+                && !is_unset(value)
+        })
+        .map(|(key, _)| key.as_str())
+        .collect();
+    ignored.sort_unstable();
+    if !ignored.is_empty() {
+        warnings.push(format!(
+            "Champs sans équivalent ignorés : {}",
+            ignored.join(", ")
+        ));
+    }
+
+    ImportedPreset {
+        name,
+        config,
+        warnings,
+    }
+}
+
+/// Qualité constante (`VideoQualitySlider`) ou débit moyen (`VideoAvgBitrate`)
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn import_quality(
+    preset: &Map<String, Value>,
+    config: &mut EncodingConfig,
+    warnings: &mut Vec<String>,
+) {
+    let num_field = |key: &str| preset.get(key).and_then(Value::as_f64);
+    match preset.get("VideoQualityType").and_then(Value::as_u64) {
+        // Safe: valeurs bornées avant la conversion
+        Some(1) => match num_field("VideoAvgBitrate").filter(|&kbps| kbps >= 1.0) {
+            Some(kbps) => {
+                config.encoder_params.encoding_mode = EncodingMode::AverageBitrate {
+                    bitrate: kbps.min(f64::from(u32::MAX)) as u32,
+                    max_bitrate: None,
+                };
+            }
+            None => warnings.push("Débit moyen absent : qualité constante par défaut".to_string()),
+        },
+        Some(2) | None => {
+            if let Some(rf) = num_field("VideoQualitySlider") {
+                let crf = rf.round().clamp(0.0, f64::from(EncodingConfig::MAX_CRF)) as u32;
+                if (rf - f64::from(crf)).abs() >= 0.5 {
+                    warnings.push(format!("Qualité {rf} ramenée au CRF {crf}"));
+                }
+                config.encoder_params.crf = crf;
+            }
+        }
+        Some(kind) => warnings.push(format!(
+            "Mode de qualité {kind} (taille visée) sans équivalent : qualité constante par défaut"
+        )),
+    }
+}
+
+/// Preset de vitesse : numéro SVT-AV1 (`0`-`13`) ou `p1`-`p7` de NVENC
+fn import_preset_speed(preset: &str, config: &mut EncodingConfig, warnings: &mut Vec<String>) {
+    if preset.is_empty() {
+        return;
+    }
+    let max = config.encoder.max_preset();
+    let speed = match config.encoder {
+        EncoderType::SvtAv1 => preset.parse::<u32>().ok(),
+        // Preset 0 (le plus lent) du dialogue = p7
+        EncoderType::Nvenc => preset
+            .strip_prefix('p')
+            .and_then(|n| n.parse::<u32>().ok())
+            .filter(|n| (1..=7).contains(n))
+            .map(|n| 7 - n),
+        _ => None,
+    };
+    match speed {
+        Some(speed) if speed <= max => config.encoder_params.preset = speed,
+        Some(speed) => {
+            warnings.push(format!("Preset {speed} ramené à {max}"));
+            config.encoder_params.preset = max;
+        }
+        None => warnings.push(format!(
+            "Preset {preset} sans équivalent : preset {} par défaut",
+            config.encoder_params.preset
+        )),
+    }
+}
+
+/// Première piste de `AudioList` : Opus, copie ou conversion en Opus
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn import_audio(list: Option<&Value>, config: &mut EncodingConfig, warnings: &mut Vec<String>) {
+    let Some(tracks) = list.and_then(Value::as_array) else {
+        return;
+    };
+    let Some(track) = tracks.first() else {
+        config.audio_mode = AudioMode::None;
+        return;
+    };
+    if tracks.len() > 1 {
+        warnings.push(format!(
+            "{} pistes dans AudioList : seul le réglage de la première est repris",
+            tracks.len()
+        ));
+    }
+    // Safe: débit borné avant la conversion
+    let bitrate = track
+        .get("AudioBitrate")
+        .and_then(Value::as_f64)
+        .filter(|&kbps| kbps >= 1.0)
+        .map_or(128, |kbps| kbps.min(f64::from(u32::MAX)) as u32);
+    match track
+        .get("AudioEncoder")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+    {
+        "opus" => config.audio_mode = AudioMode::Opus { bitrate },
+        codec if codec == "copy" || codec.starts_with("copy:") => {
+            config.audio_mode = AudioMode::Copy;
+        }
+        "none" => config.audio_mode = AudioMode::None,
+        codec => {
+            warnings.push(format!("Audio {codec} converti en Opus {bitrate}k"));
+            config.audio_mode = AudioMode::Opus { bitrate };
+        }
+    }
+}
+
+/// Codes d'une liste de langues (`any` et `und` = toutes les pistes)
+fn import_languages(
+    preset: &Map<String, Value>,
+    key: &str,
+    warnings: &mut Vec<String>,
+) -> Vec<String> {
+    let codes: Vec<String> = preset
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_lowercase)
+        .filter(|code| code != "any" && code != "und")
+        .collect();
+    match validate_language_codes(&codes) {
+        Ok(()) => codes,
+        Err(e) => {
+            warnings.push(format!("{key} ignorée : {e}"));
+            Vec::new()
+        }
+    }
+}
+
+/// Nom de fichier d'un profil importé : `<nom en minuscules>.toml`, sans espaces
+#[must_use]
+pub fn profile_file_name(name: &str) -> String {
+    let slug: Vec<String> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if slug.is_empty() {
+        "handbrake.toml".to_string()
+    } else {
+        format!("{}.toml", slug.join("-"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{job_config_toml, parse_job_config};
+
+    const EXPORT: &str = r#"{
+        "PresetList": [
+            {
+                "ChildrenArray": [
+                    {
+                        "PresetName": "AV1 Anime 1080p",
+                        "Type": 1,
+                        "FileFormat": "av_mkv",
+                        "ChapterMarkers": true,
+                        "VideoEncoder": "svt_av1_10bit",
+                        "VideoQualityType": 2,
+                        "VideoQualitySlider": 28.0,
+                        "VideoPreset": "4",
+                        "VideoTune": "psnr",
+                        "VideoMultiPass": false,
+                        "PictureWidth": 1920,
+                        "PictureHeight": 1080,
+                        "PictureDeinterlaceFilter": "bwdif",
+                        "AudioLanguageList": ["jpn", "any"],
+                        "SubtitleLanguageList": ["eng"],
+                        "AudioList": [
+                            {"AudioEncoder": "opus", "AudioBitrate": 160, "AudioMixdown": "stereo"},
+                            {"AudioEncoder": "copy:ac3"}
+                        ]
+                    }
+                ],
+                "Folder": true,
+                "PresetName": "Mes presets"
+            },
+            {
+                "PresetName": "Streaming x264",
+                "VideoEncoder": "x264",
+                "VideoQualityType": 1,
+                "VideoAvgBitrate": 6000,
+                "AudioList": [{"AudioEncoder": "av_aac", "AudioBitrate": 192}]
+            }
+        ],
+        "VersionMajor": 47
+    }"#;
+
+    #[test]
+    fn test_import_handbrake_presets() {
+        let presets = parse_handbrake_presets(EXPORT).unwrap();
+        assert_eq!(presets.len(), 2);
+
+        let anime = &presets[0];
+        assert_eq!(anime.name, "AV1 Anime 1080p");
+        let config = &anime.config;
+        assert_eq!(config.encoder, EncoderType::SvtAv1);
+        assert_eq!(config.encoder_params.crf, 28);
+        assert_eq!(config.encoder_params.preset, 4);
+        assert_eq!(config.encoder_params.tune, Tune::Psnr);
+        assert!(matches!(
+            config.audio_mode,
+            AudioMode::Opus { bitrate: 160 }
+        ));
+        assert_eq!(config.audio_languages, ["jpn"]);
+        assert_eq!(config.subtitle_languages, ["eng"]);
+        assert_eq!(config.deinterlace.filter, Deinterlacer::Bwdif);
+        let warnings = anime.warnings.join("\n");
+        assert!(warnings.contains("1920x1080"));
+        assert!(warnings.contains("2 pistes"));
+        // Seuls les champs actifs sont signalés
+        assert!(warnings.contains("ChapterMarkers, FileFormat"));
+        assert!(!warnings.contains("VideoMultiPass"));
+
+        // Encodeur non AV1 : seul l'audio est repris
+        let x264 = &presets[1];
+        assert_eq!(x264.config.encoder, EncoderType::SvtAv1);
+        assert_eq!(x264.config.encoder_params.encoding_mode, EncodingMode::Crf);
+        assert!(matches!(
+            x264.config.audio_mode,
+            AudioMode::Opus { bitrate: 192 }
+        ));
+        assert!(x264.warnings.iter().any(|w| w.contains("x264")));
+        assert!(x264.warnings.iter().any(|w| w.contains("av_aac")));
+
+        assert!(parse_handbrake_presets(r#"{"PresetList": []}"#).is_err());
+        assert!(parse_handbrake_presets("{").is_err());
+    }
+
+    #[test]
+    fn test_import_hardware_preset_and_profile_file() {
+        let preset = r#"{
+            "PresetName": "NVENC rapide",
+            "VideoEncoder": "nvenc_av1",
+            "VideoQualityType": 1,
+            "VideoAvgBitrate": 8000,
+            "VideoPreset": "p7",
+            "AudioList": []
+        }"#;
+        let imported = parse_handbrake_presets(preset).unwrap().remove(0);
+        assert_eq!(imported.config.encoder, EncoderType::Nvenc);
+        assert_eq!(imported.config.encoder_params.preset, 0);
+        assert_eq!(
+            imported.config.encoder_params.encoding_mode,
+            EncodingMode::AverageBitrate {
+                bitrate: 8000,
+                max_bitrate: None
+            }
+        );
+        assert!(matches!(imported.config.audio_mode, AudioMode::None));
+        assert!(imported.warnings.is_empty());
+
+        // Le profil écrit se relit comme un fichier de job
+        let name = profile_file_name(&imported.name);
+        assert_eq!(name, "nvenc-rapide.toml");
+        let toml = job_config_toml(&imported.config).unwrap();
+        let reread = parse_job_config(&toml, Path::new(&name)).unwrap();
+        assert_eq!(
+            serde_json::to_value(&reread).unwrap(),
+            serde_json::to_value(&imported.config).unwrap()
+        );
+        assert_eq!(profile_file_name("  !! "), "handbrake.toml");
+    }
+}
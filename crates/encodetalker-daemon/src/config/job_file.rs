@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use encodetalker_common::{EncodingConfig, Tune};
+use std::path::Path;
+
+/// Charger une configuration d'encodage depuis un fichier TOML ou JSON
+///
+/// Le format est déduit de l'extension (`.json`, sinon TOML). Les champs absents
+/// prennent leur valeur par défaut (le tune, celle de l'encodeur choisi) ; la configuration est validée avant d'être retournée.
+///
+/// # Errors
+///
+/// Retourne une erreur si le fichier est illisible, mal formé ou si la configuration
+/// est invalide.
+pub async fn load_job_config(path: &Path) -> Result<EncodingConfig> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Impossible de lire {}", path.display()))?;
+    parse_job_config(&content, path)
+}
+
+/// Analyser le contenu d'un fichier de configuration de job (format selon `path`)
+///
+/// # Errors
+///
+/// Retourne une erreur si le contenu est mal formé ou si la configuration est invalide.
+pub fn parse_job_config(content: &str, path: &Path) -> Result<EncodingConfig> {
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let (mut config, has_tune): (EncodingConfig, bool) = if is_json {
+        let value: serde_json::Value = serde_json::from_str(content)
+            .with_context(|| format!("JSON invalide dans {}", path.display()))?;
+        let has_tune = value.pointer("/encoder_params/tune").is_some();
+        let config = serde_json::from_value(value)
+            .with_context(|| format!("Configuration invalide dans {}", path.display()))?;
+        (config, has_tune)
+    } else {
+        let value: toml::Table = toml::from_str(content)
+            .with_context(|| format!("TOML invalide dans {}", path.display()))?;
+        let has_tune = value
+            .get("encoder_params")
+            .and_then(|params| params.get("tune"))
+            .is_some();
+        let config = value
+            .try_into()
+            .with_context(|| format!("Configuration invalide dans {}", path.display()))?;
+        (config, has_tune)
+    };
+    // Tune omis : celui par défaut de l'encodeur choisi (le défaut global est propre à SVT-AV1)
+    if !has_tune {
+        config.encoder_params.tune = Tune::default_for(config.encoder);
+    }
+
+    config
+        .validate()
+        .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+    Ok(config)
+}
+
+/// Sérialiser une configuration au format des fichiers de job (TOML)
+///
+/// # Errors
+///
+/// Retourne une erreur si la configuration n'est pas représentable en TOML.
+pub fn job_config_toml(config: &EncodingConfig) -> Result<String> {
+    toml::to_string_pretty(config).context("Configuration non sérialisable en TOML")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encodetalker_common::{AudioMode, EncoderType};
+
+    #[test]
+    fn test_parse_toml_and_json_job_config() {
+        let toml = r#"
+encoder = "Aom"
+enable_vmaf = false
+audio_mode = "Copy"
+
+[encoder_params]
+crf = 28
+preset = 4
+"#;
+        let config = parse_job_config(toml, Path::new("anime.toml")).unwrap();
+        assert_eq!(config.encoder, EncoderType::Aom);
+        assert_eq!(config.encoder_params.crf, 28);
+        assert!(!config.enable_vmaf);
+        assert!(matches!(config.audio_mode, AudioMode::Copy));
+        assert_eq!(
+            config.encoder_params.tune,
+            Tune::default_for(EncoderType::Aom)
+        );
+
+        let json = r#"{"audio_mode": {"Opus": {"bitrate": 96}}}"#;
+        let config = parse_job_config(json, Path::new("film.JSON")).unwrap();
+        assert_eq!(config.encoder, EncoderType::SvtAv1);
+        assert!(matches!(config.audio_mode, AudioMode::Opus { bitrate: 96 }));
+    }
+
+    #[test]
+    fn test_parse_job_config_rejects_invalid() {
+        let err =
+            parse_job_config("[encoder_params]\npreset = 20", Path::new("job.toml")).unwrap_err();
+        assert!(err.to_string().contains("Preset 20"));
+        assert!(parse_job_config("{", Path::new("job.json")).is_err());
+    }
+}
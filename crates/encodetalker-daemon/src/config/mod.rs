@@ -1,3 +1,7 @@
+pub mod handbrake;
+pub mod job_file;
 pub mod settings;
 
+pub use handbrake::*;
+pub use job_file::*;
 pub use settings::*;
@@ -1,7 +1,13 @@
 use anyhow::Result;
-use encodetalker_common::PathsConfig;
+use encodetalker_common::{
+    AomParams, AudioBitratePolicy, AudioMode, CollisionPolicy, CoverArtConfig, DeinterlaceConfig,
+    EncoderParams, EncoderType, EncodingConfig, FailureCategory, KeyframeConfig, OutputNamer,
+    PathsConfig, ResolutionCrf, SubtitlePolicy, SvtAv1Variant, TonemapConfig, Tune,
+    UnitPreferences, VfrMode, DEFAULT_VAAPI_DEVICE,
+};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 
 /// Configuration du daemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +19,170 @@ pub struct DaemonConfig {
     pub encoding: EncodingSettings,
     pub encoder: EncoderSettings,
     pub ui: UiSettings,
+    /// Échelle de repli après un crash de l'encodeur (optionnel)
+    #[serde(default)]
+    pub fallback: FallbackSettings,
+    /// Nouvel essai du job entier selon la catégorie d'échec ou le code de sortie (optionnel)
+    #[serde(default)]
+    pub retry: RetrySettings,
+    /// Choix des dépendances compilées par `INSTALL_DEPENDENCIES.sh` (optionnel)
+    #[serde(default)]
+    pub deps: DepsSettings,
+    /// Sort de la source après un encodage réussi (optionnel, conservée par défaut)
+    #[serde(default)]
+    pub source_cleanup: SourceCleanupSettings,
+    /// Mode et groupe des fichiers produits (optionnel, Unix seulement)
+    #[serde(default)]
+    pub output_permissions: OutputPermissionsSettings,
+    /// Rapport JSON écrit à côté de chaque sortie (optionnel, désactivé par défaut)
+    #[serde(default)]
+    pub sidecar_report: SidecarReportSettings,
+    /// Heures de démarrage des jobs (optionnel, toute heure par défaut)
+    #[serde(default)]
+    pub schedule: ScheduleSettings,
+    /// Journal local de la progression des jobs en cours (optionnel, désactivé par défaut)
+    #[serde(default)]
+    pub progress_log: ProgressLogSettings,
+}
+
+/// Section `[progress_log]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressLogSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Intervalle minimal entre deux échantillons d'un même job (s, au moins 1)
+    #[serde(default = "default_progress_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_progress_interval_secs() -> u64 {
+    10
+}
+
+impl Default for ProgressLogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_progress_interval_secs(),
+        }
+    }
+}
+
+/// Section `[schedule]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleSettings {
+    /// Expressions `OnCalendar` de systemd : un job démarre pendant toute minute décrite
+    /// par l'une d'elles (vide = toute heure)
+    #[serde(default)]
+    pub calendar: Vec<String>,
+}
+
+/// Action appliquée à la source d'un job terminé avec succès
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceAction {
+    /// Ne pas toucher à la source
+    #[default]
+    Keep,
+    /// Déplacer la source dans `move_dir`
+    Move,
+    /// Supprimer définitivement la source
+    Delete,
+    /// Envoyer la source dans la corbeille (spécification freedesktop)
+    Trash,
+}
+
+/// Section `[source_cleanup]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceCleanupSettings {
+    #[serde(default)]
+    pub action: SourceAction,
+    /// Répertoire de destination de l'action `move` (support de ~ et des variables)
+    #[serde(default)]
+    pub move_dir: Option<String>,
+    /// Vérifier la sortie (lisible, durée attendue) avant de toucher à la source
+    #[serde(default = "default_verify_output")]
+    pub verify_output: bool,
+}
+
+fn default_verify_output() -> bool {
+    true
+}
+
+impl Default for SourceCleanupSettings {
+    fn default() -> Self {
+        Self {
+            action: SourceAction::default(),
+            move_dir: None,
+            verify_output: default_verify_output(),
+        }
+    }
+}
+
+/// Section `[output_permissions]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputPermissionsSettings {
+    /// Mode octal des sorties, ex. "664" (None = umask du daemon)
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Groupe propriétaire des sorties, par nom ou gid (None = groupe du daemon)
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// Section `[sidecar_report]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidecarReportSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Nom du rapport : `{name}` = nom de la sortie, `{stem}` = nom sans extension
+    #[serde(default = "default_sidecar_file_name")]
+    pub file_name: String,
+    /// Répertoire des rapports (None = celui de la sortie ; support de ~ et des variables)
+    #[serde(default)]
+    pub dir: Option<String>,
+}
+
+fn default_sidecar_file_name() -> String {
+    "{stem}.json".to_string()
+}
+
+impl Default for SidecarReportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file_name: default_sidecar_file_name(),
+            dir: None,
+        }
+    }
+}
+
+/// Dépendances compilées (lues aussi par le script d'installation)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepsSettings {
+    /// Fork SVT-AV1-PSY ou SVT-AV1 officiel
+    #[serde(default)]
+    pub svt_av1_variant: SvtAv1Variant,
+    /// Tag ou commit à compiler (None = dernière révision)
+    #[serde(default)]
+    pub svt_av1_ref: Option<String>,
+    /// Profondeur des clones Git des sources (0 = historique complet)
+    #[serde(default = "default_git_clone_depth")]
+    pub git_clone_depth: u32,
+}
+
+fn default_git_clone_depth() -> u32 {
+    1
+}
+
+impl Default for DepsSettings {
+    fn default() -> Self {
+        Self {
+            svt_av1_variant: SvtAv1Variant::default(),
+            svt_av1_ref: None,
+            git_clone_depth: default_git_clone_depth(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,22 +192,205 @@ pub struct DaemonSettings {
     /// Gardé pour rétrocompatibilité mais ignoré par le daemon
     #[serde(default = "default_socket_path")]
     pub socket_path: String,
+    /// Niveau de log (syntaxe `EnvFilter`, ex: "info" ou "`encodetalker_daemon=debug`")
     pub log_level: String,
+    /// Taille maximale du fichier de log avant rotation (Mo, 0 = pas de rotation)
+    #[serde(default = "default_log_max_size_mb")]
+    pub log_max_size_mb: u64,
+    /// Nombre de fichiers de log archivés conservés (daemon.log.1, .2, ...)
+    #[serde(default = "default_log_max_files")]
+    pub log_max_files: usize,
+    /// Sort des jobs actifs à l'arrêt du daemon
+    #[serde(default)]
+    pub shutdown_mode: ShutdownMode,
+    /// Délai sans progression (secondes) avant d'arrêter un job bloqué (0 = désactivé)
+    #[serde(default)]
+    pub stall_timeout_secs: u64,
+    /// Threads répartis entre tous les jobs actifs (0 = chaque job utilise tous les cœurs)
+    #[serde(default)]
+    pub total_threads: u32,
+    /// Encodages audio simultanés, pré-encodages compris (0 est traité comme 1)
+    #[serde(default = "default_max_audio_encodes")]
+    pub max_audio_encodes: usize,
+    /// Jobs en attente dont l'audio est pré-encodé pendant qu'un job tourne (0 = désactivé)
+    #[serde(default)]
+    pub audio_prefetch_jobs: usize,
+    /// Plafond de threads des ffmpeg audio et de muxage, réduit à la part du job
+    /// (0 = pas de limite)
+    #[serde(default = "default_audio_threads")]
+    pub audio_threads: u32,
+    /// Arrêter le daemon après ce délai sans job actif ni en attente (secondes, 0 = jamais)
+    #[serde(default)]
+    pub idle_shutdown_secs: u64,
+}
+
+impl DaemonSettings {
+    /// Délai du watchdog des jobs bloqués (None = désactivé)
+    #[must_use]
+    pub fn stall_timeout(&self) -> Option<std::time::Duration> {
+        (self.stall_timeout_secs > 0)
+            .then(|| std::time::Duration::from_secs(self.stall_timeout_secs))
+    }
+
+    /// Délai d'inactivité avant arrêt du daemon (None = désactivé)
+    #[must_use]
+    pub fn idle_shutdown(&self) -> Option<std::time::Duration> {
+        (self.idle_shutdown_secs > 0)
+            .then(|| std::time::Duration::from_secs(self.idle_shutdown_secs))
+    }
+
+    /// Budget global de threads (None = désactivé)
+    #[must_use]
+    pub fn thread_budget(&self) -> Option<u32> {
+        (self.total_threads > 0).then_some(self.total_threads)
+    }
+}
+
+/// Sort des jobs actifs à l'arrêt du daemon (hors drain, qui attend toujours)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShutdownMode {
+    /// Attendre jusqu'à 30 s, puis annuler et remettre en queue les jobs restants
+    #[default]
+    Wait,
+    /// Annuler tout de suite et remettre les jobs en tête de queue pour le prochain démarrage
+    CancelAndRequeue,
+    /// Annuler tout de suite et classer les jobs comme annulés, sorties partielles supprimées
+    CancelAndDiscard,
 }
 
 fn default_socket_path() -> String {
     "~/.local/share/encodetalker/daemon.sock".to_string()
 }
 
+fn default_log_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_max_files() -> usize {
+    5
+}
+
+fn default_max_audio_encodes() -> usize {
+    2
+}
+
+fn default_audio_threads() -> u32 {
+    2
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncodingSettings {
     pub default_encoder: String,
     pub default_audio_mode: String,
+    /// Débit Opus (kbps) quand le nombre de canaux de la source est inconnu
     pub default_audio_bitrate: u32,
+    /// Débit Opus initial par canal de la source (kbps, 0 = toujours `default_audio_bitrate`)
+    #[serde(default = "default_audio_bitrate_per_channel")]
+    pub audio_bitrate_per_channel: u32,
+    /// Plafond du débit calculé par canal (kbps)
+    #[serde(default = "default_max_audio_bitrate")]
+    pub max_audio_bitrate: u32,
+    /// Valeur de `{suffix}` dans `output_template`
     pub output_suffix: String,
+    /// Modèle du nom des sorties ({stem}, {suffix}, {encoder}, {crf}, {preset})
+    #[serde(default = "default_output_template")]
+    pub output_template: String,
+    /// Sortie déjà existante ou prise par un job en attente ("overwrite", "rename" ou "fail")
+    #[serde(default)]
+    pub on_collision: CollisionPolicy,
+    /// Répertoire de sortie par défaut (None = à côté du fichier source)
+    /// Support de ~ et des variables d'environnement
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    /// Créer le répertoire de sortie d'un job s'il n'existe pas (sinon l'ajout est refusé)
+    #[serde(default = "default_create_output_dirs")]
+    pub create_output_dirs: bool,
+    /// Durée des extraits de test lancés depuis le dialogue (secondes)
+    #[serde(default = "default_preview_duration_secs")]
+    pub preview_duration_secs: u32,
+    /// Répertoire des fichiers temporaires des encodages (None = à côté de la sortie)
+    /// Support de ~ et des variables d'environnement
+    #[serde(default)]
+    pub temp_dir: Option<String>,
     /// Activer le comptage précis des frames (lent mais exact)
     #[serde(default)]
     pub precise_frame_count: bool,
+    /// Délai maximal du comptage précis (secondes), avant repli sur l'estimation
+    #[serde(default = "default_precise_frame_count_timeout_secs")]
+    pub precise_frame_count_timeout_secs: u64,
+    /// Intervalle minimal (ms) entre deux mises à jour de progression envoyées aux clients
+    #[serde(default = "default_stats_update_interval_ms")]
+    pub stats_update_interval_ms: u64,
+    /// Désentrelacement par défaut des nouveaux jobs (section `[encoding.deinterlace]`)
+    #[serde(default)]
+    pub deinterlace: DeinterlaceConfig,
+    /// Intervalle de keyframes par défaut des nouveaux jobs (section `[encoding.keyframes]`)
+    #[serde(default)]
+    pub keyframes: KeyframeConfig,
+    /// Pochette par défaut des nouveaux jobs (section `[encoding.cover_art]`)
+    #[serde(default)]
+    pub cover_art: CoverArtConfig,
+    /// Traitement par défaut des sources à framerate variable ("cfr" ou "passthrough")
+    #[serde(default)]
+    pub vfr_mode: VfrMode,
+    /// Conversion HDR → SDR par défaut des nouveaux jobs (section `[encoding.tonemap]`)
+    #[serde(default)]
+    pub tonemap: TonemapConfig,
+    /// Sous-titres incompatibles avec le conteneur de sortie ("convert", "drop" ou "fail")
+    #[serde(default)]
+    pub incompatible_subtitles: SubtitlePolicy,
+    /// Recadrer par défaut au pair inférieur les sources aux dimensions impaires
+    #[serde(default)]
+    pub crop_to_even: bool,
+    /// CRF initial du dialogue selon la hauteur de la source (vide = CRF de l'encodeur)
+    #[serde(default)]
+    pub crf_by_resolution: Vec<ResolutionCrf>,
+    /// Langues des pistes audio gardées par défaut (ex. ["eng", "jpn"], vide = toutes)
+    #[serde(default)]
+    pub audio_languages: Vec<String>,
+    /// Langues des pistes de sous-titres gardées par défaut (vide = toutes)
+    #[serde(default)]
+    pub subtitle_languages: Vec<String>,
+}
+
+fn default_output_template() -> String {
+    encodetalker_common::DEFAULT_OUTPUT_TEMPLATE.to_string()
+}
+
+fn default_precise_frame_count_timeout_secs() -> u64 {
+    300
+}
+
+fn default_stats_update_interval_ms() -> u64 {
+    250
+}
+
+fn default_audio_bitrate_per_channel() -> u32 {
+    64
+}
+
+fn default_max_audio_bitrate() -> u32 {
+    384
+}
+
+impl EncodingSettings {
+    /// Débit Opus selon les canaux de la source (None = débit fixe `default_audio_bitrate`)
+    #[must_use]
+    pub fn audio_bitrate_policy(&self) -> Option<AudioBitratePolicy> {
+        (self.audio_bitrate_per_channel > 0).then_some(AudioBitratePolicy {
+            per_channel: self.audio_bitrate_per_channel,
+            max: self.max_audio_bitrate.max(self.audio_bitrate_per_channel),
+        })
+    }
+}
+
+fn default_preview_duration_secs() -> u32 {
+    30
+}
+
+fn default_create_output_dirs() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +398,108 @@ pub struct EncoderSettings {
     #[serde(rename = "svt-av1")]
     pub svt_av1: SvtAv1Settings,
     pub aom: AomSettings,
+    /// Binaires imposés (section `[encoder.binaries]`)
+    #[serde(default)]
+    pub binaries: EncoderBinaries,
+    /// Encodeurs matériels de ffmpeg (section `[encoder.hardware]`, optionnelle)
+    #[serde(default)]
+    pub hardware: HardwareSettings,
+}
+
+/// Réglages des encodeurs matériels (NVENC, Quick Sync, VA-API)
+///
+/// Preset et CRF suivent l'échelle du dialogue (preset 0-6, 0 = le plus lent ; CRF 0-63),
+/// convertie vers les options de chaque encodeur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareSettings {
+    #[serde(default = "default_hardware_preset")]
+    pub preset: u32,
+    #[serde(default = "default_hardware_crf")]
+    pub crf: u32,
+    /// Paramètres extra passés à ffmpeg après le codec
+    #[serde(default)]
+    pub params: Vec<String>,
+    /// Périphérique DRM utilisé par VA-API
+    #[serde(default = "default_vaapi_device")]
+    pub vaapi_device: String,
+}
+
+fn default_hardware_preset() -> u32 {
+    3
+}
+
+fn default_hardware_crf() -> u32 {
+    30
+}
+
+fn default_vaapi_device() -> String {
+    DEFAULT_VAAPI_DEVICE.to_string()
+}
+
+impl Default for HardwareSettings {
+    fn default() -> Self {
+        Self {
+            preset: default_hardware_preset(),
+            crf: default_hardware_crf(),
+            params: Vec::new(),
+            vaapi_device: default_vaapi_device(),
+        }
+    }
+}
+
+/// Chemins explicites des encodeurs, consultés avant le répertoire de dépendances
+///
+/// Utile quand un binaire a un autre nom (paquet de distribution) ou pour une
+/// compilation personnelle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncoderBinaries {
+    /// Chemin de `SvtAv1EncApp` (None = `deps_dir/bin`)
+    #[serde(default, rename = "svt-av1")]
+    pub svt_av1: Option<String>,
+    /// Chemin d'`aomenc` (None = `deps_dir/bin`)
+    #[serde(default)]
+    pub aom: Option<String>,
+}
+
+impl EncoderBinaries {
+    /// Binaire à lancer pour un encodeur : le chemin imposé, sinon `default`
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le chemin imposé ne peut pas être expansé, n'existe pas,
+    /// n'est pas un fichier ou n'est pas exécutable.
+    pub fn resolve(&self, encoder: EncoderType, default: PathBuf) -> Result<PathBuf> {
+        let configured = match encoder {
+            EncoderType::SvtAv1 => self.svt_av1.as_deref(),
+            EncoderType::Aom => self.aom.as_deref(),
+            // Intégrés à ffmpeg : pas de binaire propre
+            EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => None,
+        };
+        let Some(configured) = configured else {
+            return Ok(default);
+        };
+        let path = PathsConfig::expand_path(configured)?;
+        ensure_executable(&path).map_err(|e| {
+            anyhow::anyhow!("encoder.binaries: {encoder:?} {}: {e}", path.display())
+        })?;
+        Ok(path)
+    }
+}
+
+/// Vérifier qu'un chemin désigne un fichier exécutable
+fn ensure_executable(path: &Path) -> Result<(), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("introuvable ({e})"))?;
+    if !metadata.is_file() {
+        return Err("n'est pas un fichier".to_string());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err("n'est pas exécutable".to_string());
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,12 +514,317 @@ pub struct AomSettings {
     #[serde(rename = "cpu-used")]
     pub cpu_used: u32,
     pub crf: u32,
+    /// Frames max du filtrage temporel ARNR (0-15, None = défaut d'aomenc)
+    #[serde(default, rename = "arnr-maxframes")]
+    pub arnr_maxframes: Option<u32>,
+    /// Delta de quantification sur la chroma
+    #[serde(default, rename = "enable-chroma-deltaq")]
+    pub enable_chroma_deltaq: bool,
+    /// Frames d'anticipation (0-35, None = défaut d'aomenc)
+    #[serde(default, rename = "lag-in-frames")]
+    pub lag_in_frames: Option<u32>,
+    /// Paramètres extra passés à aomenc
+    #[serde(default)]
+    pub params: Vec<String>,
+}
+
+impl EncoderSettings {
+    /// Paramètres par défaut des nouveaux jobs pour un encodeur
+    #[must_use]
+    pub fn params_for(&self, encoder: EncoderType) -> EncoderParams {
+        let (preset, crf, extra_params) = match encoder {
+            EncoderType::SvtAv1 => (
+                self.svt_av1.preset,
+                self.svt_av1.crf,
+                self.svt_av1.params.clone(),
+            ),
+            EncoderType::Aom => (self.aom.cpu_used, self.aom.crf, self.aom.params.clone()),
+            EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => (
+                self.hardware.preset,
+                self.hardware.crf,
+                self.hardware.params.clone(),
+            ),
+        };
+        EncoderParams {
+            crf,
+            preset,
+            extra_params,
+            tune: Tune::default_for(encoder),
+            aom: self.aom.params(),
+            ..EncoderParams::default()
+        }
+    }
+}
+
+impl AomSettings {
+    /// Réglages libaom appliqués par défaut aux nouveaux jobs
+    #[must_use]
+    pub fn params(&self) -> AomParams {
+        AomParams {
+            arnr_maxframes: self.arnr_maxframes.map(|n| n.min(15)),
+            enable_chroma_deltaq: self.enable_chroma_deltaq,
+            lag_in_frames: self.lag_in_frames.map(|n| n.min(35)),
+        }
+    }
+}
+
+/// Échelle de repli : étapes essayées dans l'ordre quand l'encodeur plante
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FallbackSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_fallback_steps")]
+    pub steps: Vec<FallbackStep>,
+}
+
+/// Une étape de repli (chaque étape n'est tentée qu'une fois)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FallbackStep {
+    /// Preset à utiliser à la place de celui du job
+    #[serde(default)]
+    pub preset: Option<u32>,
+    /// Paramètres extra de remplacement (None = conserver ceux du job)
+    #[serde(default)]
+    pub extra_params: Option<Vec<String>>,
+}
+
+fn default_fallback_steps() -> Vec<FallbackStep> {
+    vec![FallbackStep {
+        preset: Some(8),
+        extra_params: Some(Vec::new()),
+    }]
+}
+
+impl Default for FallbackSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            steps: default_fallback_steps(),
+        }
+    }
+}
+
+impl FallbackSettings {
+    /// Étapes actives (vide si désactivé)
+    #[must_use]
+    pub fn active_steps(&self) -> Vec<FallbackStep> {
+        if self.enabled {
+            self.steps.clone()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Relance du job entier après un échec (section `[retry]`), après l'échelle de repli
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Nouveaux essais au plus par job
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Attente avant chaque nouvel essai (secondes)
+    #[serde(default = "default_retry_delay_secs")]
+    pub delay_secs: u64,
+    /// Règles consultées dans l'ordre, la première qui correspond décide (aucune = échec)
+    #[serde(default = "default_retry_rules")]
+    pub rules: Vec<RetryRule>,
+}
+
+/// Décision d'une règle de `[retry]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryAction {
+    /// Relancer le job avec sa configuration d'origine
+    Retry,
+    /// Classer le job en échec
+    Fail,
+}
+
+/// Règle de `[retry]` : une catégorie d'échec et/ou des codes de sortie
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryRule {
+    /// Catégorie d'échec (None = toutes)
+    #[serde(default)]
+    pub category: Option<FailureCategory>,
+    /// Codes de sortie de l'encodeur ou de ffmpeg, 128 + n pour un processus tué par le
+    /// signal n (vide = tous, y compris les échecs sans code)
+    #[serde(default)]
+    pub exit_codes: Vec<i32>,
+    pub action: RetryAction,
+}
+
+impl RetryRule {
+    fn matches(&self, category: FailureCategory, exit_code: Option<i32>) -> bool {
+        self.category.is_none_or(|c| c == category)
+            && (self.exit_codes.is_empty()
+                || exit_code.is_some_and(|code| self.exit_codes.contains(&code)))
+    }
+}
+
+fn default_max_retries() -> u32 {
+    1
+}
+
+fn default_retry_delay_secs() -> u64 {
+    30
+}
+
+/// Échecs souvent passagers : encodeur tué de l'extérieur (SIGKILL du manque de mémoire,
+/// SIGTERM), job bloqué, audio ; les autres (segfault, source, disque plein...) échouent
+fn default_retry_rules() -> Vec<RetryRule> {
+    let rule = |category, exit_codes: &[i32], action| RetryRule {
+        category: Some(category),
+        exit_codes: exit_codes.to_vec(),
+        action,
+    };
+    vec![
+        rule(
+            FailureCategory::EncoderFailed,
+            &[137, 143],
+            RetryAction::Retry,
+        ),
+        rule(FailureCategory::Stalled, &[], RetryAction::Retry),
+        rule(FailureCategory::AudioFailed, &[], RetryAction::Retry),
+    ]
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_retries: default_max_retries(),
+            delay_secs: default_retry_delay_secs(),
+            rules: default_retry_rules(),
+        }
+    }
+}
+
+impl RetrySettings {
+    /// Politique active (None si désactivée ou sans nouvel essai)
+    #[must_use]
+    pub fn active_policy(&self) -> Option<Self> {
+        (self.enabled && self.max_retries > 0).then(|| self.clone())
+    }
+
+    /// Décision pour un échec : première règle qui correspond, sinon échec
+    ///
+    /// Une annulation n'est jamais relancée.
+    #[must_use]
+    pub fn action(&self, category: FailureCategory, exit_code: Option<i32>) -> RetryAction {
+        if category == FailureCategory::Cancelled {
+            return RetryAction::Fail;
+        }
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(category, exit_code))
+            .map_or(RetryAction::Fail, |rule| rule.action)
+    }
+
+    /// Attente avant un nouvel essai
+    #[must_use]
+    pub fn delay(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.delay_secs)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UiSettings {
     pub file_extensions: Vec<String>,
+    /// Intervalle de rafraîchissement du TUI (ms)
     pub refresh_interval_ms: u64,
+    /// Attente maximale (s) du démarrage du daemon lancé par le TUI
+    #[serde(default = "default_daemon_start_timeout_secs")]
+    pub daemon_start_timeout_secs: u64,
+    /// Unités des tailles et débits affichés (section `[ui.units]`)
+    #[serde(default)]
+    pub units: UnitPreferences,
+    /// Binaire du daemon, essayé s'il n'est ni à côté du TUI ni dans le `PATH`
+    /// Support de ~ et des variables d'environnement
+    #[serde(default)]
+    pub daemon_bin: Option<String>,
+    /// Pistes audio et sous-titres listées dans le dialogue d'encodage avant défilement
+    #[serde(default = "default_track_list_rows")]
+    pub track_list_rows: usize,
+    /// Répertoires épinglés de l'explorateur (raccourcis `1` à `9`), écrits par le TUI
+    #[serde(default)]
+    pub pinned_dirs: Vec<PathBuf>,
+}
+
+fn default_daemon_start_timeout_secs() -> u64 {
+    300
+}
+
+fn default_track_list_rows() -> usize {
+    5
+}
+
+impl UiSettings {
+    /// Intervalle minimal de rafraîchissement (évite de saturer le CPU et l'IPC)
+    pub const MIN_REFRESH_INTERVAL_MS: u64 = 50;
+    /// Intervalle utilisé si la valeur configurée est nulle
+    pub const DEFAULT_REFRESH_INTERVAL_MS: u64 = 500;
+
+    /// Intervalle de rafraîchissement effectif du TUI
+    #[must_use]
+    pub fn tick_rate(&self) -> std::time::Duration {
+        let ms = match self.refresh_interval_ms {
+            0 => Self::DEFAULT_REFRESH_INTERVAL_MS,
+            ms => ms.max(Self::MIN_REFRESH_INTERVAL_MS),
+        };
+        std::time::Duration::from_millis(ms)
+    }
+
+    /// Attente maximale du démarrage du daemon (au moins 5 s)
+    #[must_use]
+    pub fn daemon_start_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.daemon_start_timeout_secs.max(5))
+    }
+
+    /// Trouver le binaire du daemon : à côté du TUI (`tui_dir`), puis dans les répertoires
+    /// de `path_var` (format `PATH`), puis `ui.daemon_bin`
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur listant les emplacements essayés si aucun n'est un exécutable,
+    /// y compris un `ui.daemon_bin` qui ne peut pas être développé.
+    pub fn find_daemon_binary(&self, tui_dir: Option<&Path>, path_var: &OsStr) -> Result<PathBuf> {
+        let name = format!("encodetalker-daemon{}", std::env::consts::EXE_SUFFIX);
+        let mut candidates: Vec<PathBuf> = tui_dir.map(|dir| dir.join(&name)).into_iter().collect();
+        candidates.extend(
+            std::env::split_paths(path_var)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(|dir| dir.join(&name)),
+        );
+        let mut expand_error = None;
+        if let Some(configured) = self.daemon_bin.as_deref() {
+            match PathsConfig::expand_path(configured) {
+                Ok(path) => candidates.push(path),
+                Err(e) => expand_error = Some((PathBuf::from(configured), e.to_string())),
+            }
+        }
+
+        let mut tried = Vec::new();
+        for candidate in candidates {
+            if tried.iter().any(|(path, _)| *path == candidate) {
+                continue;
+            }
+            match ensure_executable(&candidate) {
+                Ok(()) => return Ok(candidate),
+                Err(reason) => tried.push((candidate, reason)),
+            }
+        }
+        tried.extend(expand_error);
+        let tried: Vec<String> = tried
+            .iter()
+            .map(|(path, reason)| format!("  {} : {reason}", path.display()))
+            .collect();
+        anyhow::bail!(
+            "Binaire {name} introuvable. Emplacements essayés :\n{}\nIndiquez son chemin avec ui.daemon_bin dans config.toml",
+            tried.join("\n")
+        )
+    }
 }
 
 impl Default for DaemonConfig {
@@ -75,29 +835,59 @@ impl Default for DaemonConfig {
                 max_concurrent_jobs: 1,
                 socket_path: "~/.local/share/encodetalker/daemon.sock".to_string(),
                 log_level: "info".to_string(),
+                log_max_size_mb: default_log_max_size_mb(),
+                log_max_files: default_log_max_files(),
+                shutdown_mode: ShutdownMode::default(),
+                stall_timeout_secs: 0,
+                total_threads: 0,
+                max_audio_encodes: default_max_audio_encodes(),
+                audio_prefetch_jobs: 0,
+                audio_threads: default_audio_threads(),
+                idle_shutdown_secs: 0,
             },
             encoding: EncodingSettings {
                 default_encoder: "svt-av1".to_string(),
                 default_audio_mode: "opus".to_string(),
                 default_audio_bitrate: 128,
+                audio_bitrate_per_channel: default_audio_bitrate_per_channel(),
+                max_audio_bitrate: default_max_audio_bitrate(),
                 output_suffix: ".av1".to_string(),
+                output_template: default_output_template(),
+                on_collision: CollisionPolicy::default(),
+                output_dir: None,
+                create_output_dirs: true,
+                preview_duration_secs: default_preview_duration_secs(),
+                temp_dir: None,
                 precise_frame_count: false,
+                precise_frame_count_timeout_secs: default_precise_frame_count_timeout_secs(),
+                stats_update_interval_ms: default_stats_update_interval_ms(),
+                deinterlace: DeinterlaceConfig::default(),
+                keyframes: KeyframeConfig::default(),
+                cover_art: CoverArtConfig::default(),
+                vfr_mode: VfrMode::default(),
+                tonemap: TonemapConfig::default(),
+                incompatible_subtitles: SubtitlePolicy::default(),
+                crop_to_even: false,
+                crf_by_resolution: Vec::new(),
+                audio_languages: Vec::new(),
+                subtitle_languages: Vec::new(),
             },
             encoder: EncoderSettings {
                 svt_av1: SvtAv1Settings {
                     preset: 6,
                     crf: 30,
-                    params: vec![
-                        "--keyint".to_string(),
-                        "240".to_string(),
-                        "--tune".to_string(),
-                        "3".to_string(),
-                    ],
+                    params: vec!["--keyint".to_string(), "240".to_string()],
                 },
                 aom: AomSettings {
                     cpu_used: 4,
                     crf: 30,
+                    arnr_maxframes: None,
+                    enable_chroma_deltaq: false,
+                    lag_in_frames: None,
+                    params: Vec::new(),
                 },
+                binaries: EncoderBinaries::default(),
+                hardware: HardwareSettings::default(),
             },
             ui: UiSettings {
                 file_extensions: vec![
@@ -108,7 +898,20 @@ impl Default for DaemonConfig {
                     ".webm".to_string(),
                 ],
                 refresh_interval_ms: 500,
+                daemon_start_timeout_secs: default_daemon_start_timeout_secs(),
+                daemon_bin: None,
+                units: UnitPreferences::default(),
+                track_list_rows: default_track_list_rows(),
+                pinned_dirs: Vec::new(),
             },
+            fallback: FallbackSettings::default(),
+            retry: RetrySettings::default(),
+            deps: DepsSettings::default(),
+            source_cleanup: SourceCleanupSettings::default(),
+            output_permissions: OutputPermissionsSettings::default(),
+            sidecar_report: SidecarReportSettings::default(),
+            schedule: ScheduleSettings::default(),
+            progress_log: ProgressLogSettings::default(),
         }
     }
 }
@@ -125,6 +928,65 @@ impl DaemonConfig {
         Ok(config)
     }
 
+    /// Configuration initiale du dialogue d'encodage (sections [encoding] et [encoder])
+    ///
+    /// Les valeurs inconnues de `default_encoder` / `default_audio_mode` retombent
+    /// sur SVT-AV1 et Opus.
+    #[must_use]
+    pub fn default_encoding_config(&self) -> EncodingConfig {
+        let encoder = match self.encoding.default_encoder.as_str() {
+            "aom" | "libaom" | "aomenc" => EncoderType::Aom,
+            "nvenc" | "av1_nvenc" => EncoderType::Nvenc,
+            "qsv" | "av1_qsv" => EncoderType::Qsv,
+            "vaapi" | "av1_vaapi" => EncoderType::Vaapi,
+            _ => EncoderType::SvtAv1,
+        };
+        let audio_mode = match self.encoding.default_audio_mode.as_str() {
+            "copy" => AudioMode::Copy,
+            "none" => AudioMode::None,
+            _ => AudioMode::Opus {
+                bitrate: self.encoding.default_audio_bitrate,
+            },
+        };
+        EncodingConfig {
+            encoder,
+            audio_mode,
+            encoder_params: self.encoder.params_for(encoder),
+            deinterlace: self.encoding.deinterlace,
+            keyframes: self.encoding.keyframes,
+            cover_art: self.encoding.cover_art,
+            vfr_mode: self.encoding.vfr_mode,
+            tonemap: self.encoding.tonemap,
+            incompatible_subtitles: self.encoding.incompatible_subtitles,
+            crop_to_even: self.encoding.crop_to_even,
+            audio_languages: self.encoding.audio_languages.clone(),
+            subtitle_languages: self.encoding.subtitle_languages.clone(),
+            ..EncodingConfig::default()
+        }
+    }
+
+    /// Nommage des sorties (`output_template`, `output_dir`, `on_collision`)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le modèle est invalide ou si `output_dir` ne peut pas être
+    /// expansé.
+    pub fn output_namer(&self) -> Result<OutputNamer> {
+        let output_dir = self
+            .encoding
+            .output_dir
+            .as_deref()
+            .map(PathsConfig::expand_path)
+            .transpose()?;
+        OutputNamer::new(
+            &self.encoding.output_template,
+            &self.encoding.output_suffix,
+            output_dir,
+            self.encoding.on_collision,
+        )
+        .map_err(anyhow::Error::msg)
+    }
+
     /// Charger la configuration avec fallback sur défaut
     #[must_use]
     pub fn load_or_default(path: &PathBuf) -> Self {
@@ -137,3 +999,271 @@ impl DaemonConfig {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_default_encoding_config_uses_encoder_settings() {
+        let mut config = DaemonConfig::default();
+        config.encoder.svt_av1.preset = 4;
+        config.encoder.svt_av1.crf = 26;
+        config.encoder.aom.cpu_used = 3;
+        config.encoder.aom.params = vec!["--enable-fwd-kf=1".to_string()];
+        config.encoding.default_audio_mode = "copy".to_string();
+        config.encoding.audio_languages = vec!["eng".to_string(), "jpn".to_string()];
+
+        let encoding = config.default_encoding_config();
+        assert_eq!(encoding.audio_languages, ["eng", "jpn"]);
+        assert!(encoding.subtitle_languages.is_empty());
+        assert_eq!(encoding.encoder, EncoderType::SvtAv1);
+        assert_eq!(encoding.encoder_params.preset, 4);
+        assert_eq!(encoding.encoder_params.crf, 26);
+        assert_eq!(
+            encoding.encoder_params.extra_params,
+            vec!["--keyint", "240"]
+        );
+        assert!(matches!(encoding.audio_mode, AudioMode::Copy));
+
+        config.encoding.default_encoder = "aom".to_string();
+        let encoding = config.default_encoding_config();
+        assert_eq!(encoding.encoder, EncoderType::Aom);
+        assert_eq!(encoding.encoder_params.preset, 3);
+        assert_eq!(
+            encoding.encoder_params.extra_params,
+            vec!["--enable-fwd-kf=1"]
+        );
+        // aomenc ne connaît pas `--tune subjective-ssim` (défaut de SVT-AV1)
+        assert_eq!(encoding.encoder_params.tune, Tune::Psnr);
+        assert!(encoding
+            .encoder_params
+            .tune
+            .is_supported_by(EncoderType::Aom));
+
+        config.encoding.default_encoder = "nvenc".to_string();
+        config.encoder.hardware.preset = 5;
+        let encoding = config.default_encoding_config();
+        assert_eq!(encoding.encoder, EncoderType::Nvenc);
+        assert_eq!(encoding.encoder_params.preset, 5);
+        assert!(encoding.encoder_params.extra_params.is_empty());
+    }
+
+    #[test]
+    fn test_crf_by_resolution_table() {
+        let mut encoding: EncodingSettings = toml::from_str(
+            r#"
+default_encoder = "svt-av1"
+default_audio_mode = "opus"
+default_audio_bitrate = 128
+output_suffix = ".av1"
+
+[[crf_by_resolution]]
+max_height = 576
+crf = 32
+
+[[crf_by_resolution]]
+max_height = 1080
+crf = 30
+"#,
+        )
+        .unwrap();
+        assert_eq!(encoding.crf_by_resolution.len(), 2);
+        assert_eq!(
+            encodetalker_common::crf_for_height(&encoding.crf_by_resolution, 720),
+            Some(30)
+        );
+        // Débit audio par canal : actif par défaut, désactivé par 0
+        assert_eq!(
+            encoding.audio_bitrate_policy().map(|p| p.bitrate_for(6)),
+            Some(384)
+        );
+        encoding.audio_bitrate_per_channel = 0;
+        assert!(encoding.audio_bitrate_policy().is_none());
+
+        assert!(DaemonConfig::default()
+            .encoding
+            .crf_by_resolution
+            .is_empty());
+    }
+
+    #[test]
+    fn test_output_namer() {
+        let mut config = DaemonConfig::default();
+        let namer = config.output_namer().unwrap();
+        assert_eq!(namer, OutputNamer::default());
+
+        config.encoding.output_template = "{stem}.{encoder}{suffix}.mkv".to_string();
+        config.encoding.output_suffix = "-crf30".to_string();
+        config.encoding.output_dir = Some("/encoded".to_string());
+        config.encoding.on_collision = CollisionPolicy::Rename;
+        let namer = config.output_namer().unwrap();
+        assert_eq!(namer.collision(), CollisionPolicy::Rename);
+        assert_eq!(
+            namer.default_path(Path::new("/videos/film.mkv"), &EncodingConfig::default()),
+            PathBuf::from("/encoded/film.svt-av1-crf30.mkv")
+        );
+
+        config.encoding.output_template = "film.mkv".to_string();
+        assert!(config.output_namer().is_err());
+    }
+
+    #[test]
+    fn test_retry_rules() {
+        let retry = RetrySettings::default();
+        let encoder = FailureCategory::EncoderFailed;
+        assert_eq!(retry.action(encoder, Some(137)), RetryAction::Retry);
+        assert_eq!(retry.action(encoder, Some(139)), RetryAction::Fail);
+        assert_eq!(retry.action(encoder, None), RetryAction::Fail);
+        assert_eq!(
+            retry.action(FailureCategory::Stalled, None),
+            RetryAction::Retry
+        );
+        assert_eq!(
+            retry.action(FailureCategory::DiskFull, None),
+            RetryAction::Fail
+        );
+        assert!(retry.active_policy().is_none());
+
+        // Première règle qui correspond ; une annulation n'est jamais relancée
+        let retry: RetrySettings = toml::from_str(
+            r#"
+            enabled = true
+            rules = [
+                { category = "EncoderFailed", exit_codes = [139], action = "fail" },
+                { action = "retry" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(retry.max_retries, 1);
+        assert_eq!(retry.action(encoder, Some(139)), RetryAction::Fail);
+        assert_eq!(retry.action(encoder, Some(1)), RetryAction::Retry);
+        assert_eq!(
+            retry.action(FailureCategory::MuxFailed, None),
+            RetryAction::Retry
+        );
+        assert_eq!(
+            retry.action(FailureCategory::Cancelled, None),
+            RetryAction::Fail
+        );
+        assert!(retry.active_policy().is_some());
+    }
+
+    #[test]
+    fn test_shutdown_mode_parses_kebab_case() {
+        let settings: DaemonSettings = toml::from_str(
+            "max_concurrent_jobs = 1\nlog_level = \"info\"\nshutdown_mode = \"cancel-and-discard\"",
+        )
+        .unwrap();
+        assert_eq!(settings.shutdown_mode, ShutdownMode::CancelAndDiscard);
+
+        let settings: DaemonSettings =
+            toml::from_str("max_concurrent_jobs = 1\nlog_level = \"info\"").unwrap();
+        assert_eq!(settings.shutdown_mode, ShutdownMode::Wait);
+    }
+
+    #[test]
+    fn test_ui_tick_rate_is_clamped() {
+        let mut ui = DaemonConfig::default().ui;
+        assert_eq!(ui.tick_rate(), Duration::from_millis(500));
+        ui.refresh_interval_ms = 5;
+        assert_eq!(ui.tick_rate(), Duration::from_millis(50));
+        ui.refresh_interval_ms = 0;
+        assert_eq!(ui.tick_rate(), Duration::from_millis(500));
+        ui.refresh_interval_ms = 1000;
+        assert_eq!(ui.tick_rate(), Duration::from_secs(1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_daemon_binary() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+
+        let dir = tmp.path().to_path_buf();
+        let (tui_dir, bin_dir, opt_dir) = (dir.join("tui"), dir.join("bin"), dir.join("opt"));
+        for sub in [&tui_dir, &bin_dir, &opt_dir] {
+            std::fs::create_dir_all(sub).unwrap();
+        }
+        let install = |sub: &Path| {
+            let binary = sub.join("encodetalker-daemon");
+            std::fs::write(&binary, b"#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+            binary
+        };
+        let mut ui = DaemonConfig::default().ui;
+        let path_var = std::env::join_paths([dir.join("absent"), bin_dir.clone()]).unwrap();
+
+        // Rien nulle part : l'erreur liste chaque emplacement essayé, ui.daemon_bin
+        // non développable compris
+        ui.daemon_bin = Some("$ENCODETALKER_TEST_UNSET_VAR/encodetalker-daemon".to_string());
+        let err = ui
+            .find_daemon_binary(Some(&tui_dir), &path_var)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&*tui_dir.to_string_lossy()));
+        assert!(err.contains(&*bin_dir.to_string_lossy()));
+        assert!(err.contains("$ENCODETALKER_TEST_UNSET_VAR/encodetalker-daemon"));
+        assert!(err.contains("ui.daemon_bin"));
+
+        // ui.daemon_bin en dernier recours
+        let configured = install(&opt_dir);
+        ui.daemon_bin = Some(configured.display().to_string());
+        assert_eq!(
+            ui.find_daemon_binary(Some(&tui_dir), &path_var).unwrap(),
+            configured
+        );
+        // Le PATH passe avant, et le répertoire du TUI avant tout
+        let in_path = install(&bin_dir);
+        assert_eq!(
+            ui.find_daemon_binary(Some(&tui_dir), &path_var).unwrap(),
+            in_path
+        );
+        let beside = install(&tui_dir);
+        assert_eq!(
+            ui.find_daemon_binary(Some(&tui_dir), &path_var).unwrap(),
+            beside
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encoder_binary_override_is_validated() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+
+        let dir = tmp.path().to_path_buf();
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("SvtAv1EncApp-custom");
+        std::fs::write(&binary, b"#!/bin/sh\n").unwrap();
+        let default = PathBuf::from("/deps/bin/aomenc");
+
+        let mut binaries: EncoderBinaries =
+            toml::from_str(&format!("svt-av1 = \"{}\"", binary.display())).unwrap();
+        assert_eq!(
+            binaries.resolve(EncoderType::Aom, default.clone()).unwrap(),
+            default
+        );
+        // Fichier présent mais non exécutable
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(binaries
+            .resolve(EncoderType::SvtAv1, default.clone())
+            .is_err());
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(
+            binaries
+                .resolve(EncoderType::SvtAv1, default.clone())
+                .unwrap(),
+            binary
+        );
+
+        binaries.aom = Some(dir.join("absent").display().to_string());
+        assert!(binaries.resolve(EncoderType::Aom, default.clone()).is_err());
+        binaries.aom = Some(dir.display().to_string());
+        assert!(binaries.resolve(EncoderType::Aom, default).is_err());
+    }
+}
@@ -1,5 +1,13 @@
+use crate::queue::QueueEvent;
+use anyhow::{Context, Result};
 use encodetalker_common::protocol::messages::{DepsCompilationStep, DepsStatusInfo};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
 
 /// État de compilation des dépendances
 #[derive(Debug, Clone, Default)]
@@ -16,6 +24,12 @@ struct DepsCompilationState {
     completed_count: usize,
     /// Nombre total de dépendances
     total_count: usize,
+    /// Reprise d'une compilation interrompue (daemon ou script tué)
+    resumed: bool,
+    /// Dépendances compilées, dans l'ordre (vide = toutes)
+    deps: Vec<String>,
+    /// Dernière erreur (compilation échouée ou impossible)
+    error: Option<String>,
 }
 
 /// Tracker de compilation des dépendances (thread-safe)
@@ -48,6 +62,9 @@ impl DepsCompilationTracker {
             current_step: state.current_step.clone(),
             completed_count: state.completed_count,
             total_count: state.total_count,
+            resumed: state.resumed,
+            deps: state.deps.clone(),
+            error: state.error.clone(),
         }
     }
 
@@ -77,6 +94,21 @@ impl DepsCompilationTracker {
         state.total_count = total_deps;
         state.current_dep = None;
         state.current_step = None;
+        state.resumed = false;
+        state.deps.clear();
+        state.error = None;
+    }
+
+    /// Démarrer la reprise d'une compilation interrompue pour les dépendances `deps`
+    ///
+    /// # Panics
+    ///
+    /// Peut paniquer si le lock est empoisonné (thread panic pendant le write).
+    pub fn resume_compilation(&self, deps: Vec<String>) {
+        self.start_compilation(deps.len());
+        let mut state = self.state.write().unwrap();
+        state.resumed = true;
+        state.deps = deps;
     }
 
     /// Définir la dépendance et l'étape courante
@@ -124,6 +156,15 @@ impl DepsCompilationTracker {
         state.current_dep = None;
         state.current_step = None;
     }
+
+    /// Enregistrer l'erreur qui bloque les dépendances, renvoyée aux clients qui se connectent
+    ///
+    /// # Panics
+    ///
+    /// Peut paniquer si le lock est empoisonné (thread panic pendant le write).
+    pub fn set_error(&self, error: String) {
+        self.state.write().unwrap().error = Some(error);
+    }
 }
 
 impl Default for DepsCompilationTracker {
@@ -131,3 +172,431 @@ impl Default for DepsCompilationTracker {
         Self::new()
     }
 }
+
+/// Marqueur écrit dans le répertoire des dépendances pendant `INSTALL_DEPENDENCIES.sh`
+///
+/// Supprimé à la fin d'une installation réussie : s'il reste, la compilation a été
+/// interrompue (script ou machine arrêtés) ou a échoué.
+pub const BUILD_MARKER_FILE: &str = ".build-in-progress";
+
+/// Dépendance compilée par `INSTALL_DEPENDENCIES.sh`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltDependency {
+    /// Nom affiché
+    pub name: &'static str,
+    /// Option du script compilant cette dépendance seule
+    pub script_flag: &'static str,
+    /// Binaires installés par la compilation
+    pub binaries: Vec<PathBuf>,
+}
+
+/// Dépendances attendues dans `bin_dir`, dans l'ordre de compilation du script
+#[must_use]
+pub fn built_dependencies(bin_dir: &Path, exe_suffix: &str) -> Vec<BuiltDependency> {
+    let bin = |name: &str| bin_dir.join(format!("{name}{exe_suffix}"));
+    vec![
+        BuiltDependency {
+            name: "FFmpeg",
+            script_flag: "--ffmpeg",
+            binaries: vec![bin("ffmpeg"), bin("ffprobe")],
+        },
+        BuiltDependency {
+            name: "SVT-AV1",
+            script_flag: "--svt-av1",
+            binaries: vec![bin("SvtAv1EncApp")],
+        },
+        BuiltDependency {
+            name: "libaom",
+            script_flag: "--aomenc",
+            binaries: vec![bin("aomenc")],
+        },
+    ]
+}
+
+/// Binaire utilisable : fichier non vide (et exécutable sous Unix)
+///
+/// Un fichier vide ou non exécutable est le reste d'une installation interrompue.
+fn binary_usable(path: &Path) -> bool {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    let executable = {
+        use std::os::unix::fs::PermissionsExt;
+        meta.permissions().mode() & 0o111 != 0
+    };
+    #[cfg(not(unix))]
+    let executable = true;
+    meta.is_file() && meta.len() > 0 && executable
+}
+
+/// État des dépendances sur disque au démarrage du daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepsOnDisk {
+    /// Tous les binaires sont présents
+    Ready,
+    /// Compilation interrompue : ces dépendances manquent encore et sont à reprendre
+    Interrupted { missing: Vec<BuiltDependency> },
+    /// Dépendances jamais installées (pas de compilation en cours)
+    Missing { missing: Vec<BuiltDependency> },
+}
+
+/// Comparer le marqueur de compilation aux binaires réellement présents
+///
+/// Un marqueur resté alors que tous les binaires sont là (script arrêté juste après la
+/// dernière installation) est supprimé.
+#[must_use]
+pub fn reconcile_deps_on_disk(deps_dir: &Path, deps: &[BuiltDependency]) -> DepsOnDisk {
+    let marker = deps_dir.join(BUILD_MARKER_FILE);
+    let missing: Vec<BuiltDependency> = deps
+        .iter()
+        .filter(|dep| !dep.binaries.iter().all(|bin| binary_usable(bin)))
+        .cloned()
+        .collect();
+
+    if missing.is_empty() {
+        if marker.exists() {
+            info!(
+                "Marqueur de compilation périmé supprimé: {}",
+                marker.display()
+            );
+            let _ = std::fs::remove_file(&marker);
+        }
+        DepsOnDisk::Ready
+    } else if marker.exists() {
+        DepsOnDisk::Interrupted { missing }
+    } else {
+        DepsOnDisk::Missing { missing }
+    }
+}
+
+/// Reprendre une compilation interrompue : relancer le script pour chaque dépendance manquante
+///
+/// La progression passe par le tracker et les événements de compilation, comme une
+/// première compilation ; la sortie du script est relayée aux clients abonnés.
+///
+/// # Errors
+///
+/// Retourne une erreur si le script échoue ou si un binaire manque encore après sa
+/// compilation (l'échec est aussi signalé aux clients).
+pub async fn resume_interrupted_build(
+    script: &Path,
+    missing: Vec<BuiltDependency>,
+    tracker: &DepsCompilationTracker,
+    event_tx: &mpsc::UnboundedSender<QueueEvent>,
+) -> Result<()> {
+    let total_deps = missing.len();
+    tracker.resume_compilation(missing.iter().map(|dep| dep.name.to_string()).collect());
+    let _ = event_tx.send(QueueEvent::DepsCompilationStarted {
+        total_deps,
+        resumed: true,
+        deps: missing.iter().map(|dep| dep.name.to_string()).collect(),
+    });
+
+    let (lines_tx, lines_rx) = mpsc::unbounded_channel();
+    let forwarder = spawn_compilation_log_forwarder(lines_rx, event_tx.clone());
+    let result = async {
+        for (dep_index, dep) in missing.iter().enumerate() {
+            let progress = |step: DepsCompilationStep| {
+                tracker.set_current(dep.name.to_string(), step.clone());
+                let _ = event_tx.send(QueueEvent::DepsCompilationProgress {
+                    dep_name: dep.name.to_string(),
+                    dep_index,
+                    total_deps,
+                    step,
+                });
+            };
+
+            progress(DepsCompilationStep::Building);
+            info!("Reprise de la compilation: {}", dep.name);
+            run_install_script(script, dep.script_flag, &lines_tx)
+                .await
+                .with_context(|| format!("Compilation de {} échouée", dep.name))
+                .map_err(|e| (dep.name, e))?;
+
+            progress(DepsCompilationStep::Verifying);
+            if let Some(bin) = dep.binaries.iter().find(|bin| !binary_usable(bin)) {
+                return Err((
+                    dep.name,
+                    anyhow::anyhow!("Binaire absent après compilation: {}", bin.display()),
+                ));
+            }
+            tracker.complete_dep();
+            let _ = event_tx.send(QueueEvent::DepsCompilationItemCompleted {
+                dep_name: dep.name.to_string(),
+                dep_index,
+                total_deps,
+            });
+        }
+        Ok::<(), (&str, anyhow::Error)>(())
+    }
+    .await;
+    drop(lines_tx);
+    let _ = forwarder.await;
+
+    match result {
+        Ok(()) => {
+            tracker.finish_compilation();
+            let _ = event_tx.send(QueueEvent::DepsCompilationCompleted);
+            info!("Compilation interrompue reprise avec succès");
+            Ok(())
+        }
+        Err((dep_name, e)) => {
+            warn!("Échec de la reprise de compilation ({dep_name}): {e:#}");
+            tracker.fail_compilation();
+            tracker.set_error(format!("{dep_name}: {e:#}"));
+            let _ = event_tx.send(QueueEvent::DepsCompilationFailed {
+                dep_name: dep_name.to_string(),
+                error: format!("{e:#}"),
+            });
+            Err(e)
+        }
+    }
+}
+
+/// Lancer `INSTALL_DEPENDENCIES.sh <option>` en relayant sa sortie
+async fn run_install_script(
+    script: &Path,
+    flag: &str,
+    lines_tx: &mpsc::UnboundedSender<String>,
+) -> Result<()> {
+    let mut child = tokio::process::Command::new("bash")
+        .arg(script)
+        .arg("--skip-check")
+        .arg(flag)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Impossible de lancer {}", script.display()))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .map(|out| tokio::spawn(forward_output_lines(out, lines_tx.clone())));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|err| tokio::spawn(forward_output_lines(err, lines_tx.clone())));
+    let status = child.wait().await?;
+    for task in [stdout, stderr].into_iter().flatten() {
+        let _ = task.await;
+    }
+    if !status.success() {
+        anyhow::bail!("{} {flag} a échoué ({status})", script.display());
+    }
+    Ok(())
+}
+
+/// Intervalle minimal entre deux lots de sortie de compilation envoyés aux clients
+pub const COMPILATION_LOG_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+/// Lignes gardées par lot : au-delà, les plus anciennes sont écartées
+pub const COMPILATION_LOG_MAX_LINES: usize = 40;
+
+/// Lot de lignes de sortie de compilation en attente d'envoi
+#[derive(Debug, Default)]
+pub struct CompilationLogBatch {
+    lines: VecDeque<String>,
+    skipped: usize,
+}
+
+impl CompilationLogBatch {
+    /// Ajouter une ligne (écarte la plus ancienne si le lot est plein)
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == COMPILATION_LOG_MAX_LINES {
+            self.lines.pop_front();
+            self.skipped += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Vider le lot : `None` s'il n'y a rien à envoyer
+    pub fn take(&mut self) -> Option<(Vec<String>, usize)> {
+        if self.lines.is_empty() {
+            return None;
+        }
+        let skipped = std::mem::take(&mut self.skipped);
+        Some((self.lines.drain(..).collect(), skipped))
+    }
+}
+
+/// Relayer la sortie des compilateurs vers les clients, par lots limités en débit
+///
+/// Les lignes reçues sur `lines_rx` sont regroupées et envoyées au plus toutes les
+/// [`COMPILATION_LOG_FLUSH_INTERVAL`] ; la tâche se termine quand tous les émetteurs
+/// sont fermés, après un dernier envoi.
+pub fn spawn_compilation_log_forwarder(
+    mut lines_rx: mpsc::UnboundedReceiver<String>,
+    event_tx: mpsc::UnboundedSender<QueueEvent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut batch = CompilationLogBatch::default();
+        let mut interval = tokio::time::interval(COMPILATION_LOG_FLUSH_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                line = lines_rx.recv() => match line {
+                    Some(line) => batch.push(line),
+                    None => break,
+                },
+                _ = interval.tick() => {
+                    if let Some((lines, skipped)) = batch.take() {
+                        let _ = event_tx.send(QueueEvent::DepsCompilationLog { lines, skipped });
+                    }
+                }
+            }
+        }
+        if let Some((lines, skipped)) = batch.take() {
+            let _ = event_tx.send(QueueEvent::DepsCompilationLog { lines, skipped });
+        }
+    })
+}
+
+/// Lire la sortie d'un processus de compilation (stdout ou stderr) ligne par ligne
+pub async fn forward_output_lines(
+    output: impl AsyncRead + Unpin,
+    lines_tx: mpsc::UnboundedSender<String>,
+) {
+    let mut lines = BufReader::new(output).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if lines_tx.send(line).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Installer un faux binaire (exécutable, non vide)
+    fn install(path: &Path) {
+        std::fs::write(path, b"#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_reconcile_partial_binaries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let deps_dir = tmp.path().to_path_buf();
+        let bin_dir = deps_dir.join("bin");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        let deps = built_dependencies(&bin_dir, "");
+        let names = |missing: &[BuiltDependency]| -> Vec<&str> {
+            missing.iter().map(|dep| dep.name).collect()
+        };
+
+        // Jamais compilé : rien à reprendre
+        let DepsOnDisk::Missing { missing } = reconcile_deps_on_disk(&deps_dir, &deps) else {
+            panic!("dépendances manquantes attendues");
+        };
+        assert_eq!(names(&missing), ["FFmpeg", "SVT-AV1", "libaom"]);
+
+        // Script tué pendant SVT-AV1 : ffprobe installé mais ffmpeg vide, aomenc non exécutable
+        std::fs::write(deps_dir.join(BUILD_MARKER_FILE), "--all").unwrap();
+        install(&bin_dir.join("ffprobe"));
+        std::fs::write(bin_dir.join("ffmpeg"), b"").unwrap();
+        std::fs::write(bin_dir.join("aomenc"), b"\x7fELF").unwrap();
+        let DepsOnDisk::Interrupted { missing } = reconcile_deps_on_disk(&deps_dir, &deps) else {
+            panic!("compilation interrompue attendue");
+        };
+        #[cfg(unix)]
+        assert_eq!(names(&missing), ["FFmpeg", "SVT-AV1", "libaom"]);
+
+        // FFmpeg terminé : seules les suivantes sont reprises
+        install(&bin_dir.join("ffmpeg"));
+        let DepsOnDisk::Interrupted { missing } = reconcile_deps_on_disk(&deps_dir, &deps) else {
+            panic!("compilation interrompue attendue");
+        };
+        #[cfg(unix)]
+        assert_eq!(names(&missing), ["SVT-AV1", "libaom"]);
+        assert_eq!(missing[0].script_flag, "--svt-av1");
+
+        // Tout est là : prêt, et le marqueur périmé disparaît
+        install(&bin_dir.join("SvtAv1EncApp"));
+        install(&bin_dir.join("aomenc"));
+        assert_eq!(reconcile_deps_on_disk(&deps_dir, &deps), DepsOnDisk::Ready);
+        assert!(!deps_dir.join(BUILD_MARKER_FILE).exists());
+    }
+
+    #[test]
+    fn test_resumed_compilation_status() {
+        let tracker = DepsCompilationTracker::new();
+        tracker.resume_compilation(vec!["libaom".to_string()]);
+        let status = tracker.get_status();
+        assert!(status.compiling && status.resumed && !status.all_present);
+        assert_eq!(
+            (status.total_count, status.deps),
+            (1, vec!["libaom".to_string()])
+        );
+
+        tracker.fail_compilation();
+        let status = tracker.get_status();
+        assert!(!status.compiling && !status.all_present);
+
+        tracker.set_error("nasm introuvable".to_string());
+        assert_eq!(
+            tracker.get_status().error.as_deref(),
+            Some("nasm introuvable")
+        );
+        tracker.resume_compilation(vec!["libaom".to_string()]);
+        assert!(tracker.get_status().error.is_none());
+    }
+
+    #[test]
+    fn test_compilation_log_batch_keeps_latest_lines() {
+        let mut batch = CompilationLogBatch::default();
+        assert!(batch.take().is_none());
+
+        for i in 0..COMPILATION_LOG_MAX_LINES + 5 {
+            batch.push(format!("ligne {i}"));
+        }
+        let (lines, skipped) = batch.take().unwrap();
+        assert_eq!(lines.len(), COMPILATION_LOG_MAX_LINES);
+        assert_eq!(skipped, 5);
+        assert_eq!(lines[0], "ligne 5");
+
+        batch.push("suite".to_string());
+        assert_eq!(batch.take(), Some((vec!["suite".to_string()], 0)));
+    }
+
+    #[tokio::test]
+    async fn test_install_script_output_reaches_clients() {
+        let tmp = tempfile::tempdir().unwrap();
+        let script = tmp.path().join("INSTALL_DEPENDENCIES.sh");
+        std::fs::write(
+            &script,
+            "echo \"cc $2\"\necho 'warning: unused' >&2\n[ \"$2\" != --fail ]\n",
+        )
+        .unwrap();
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (lines_tx, lines_rx) = mpsc::unbounded_channel();
+        let forwarder = spawn_compilation_log_forwarder(lines_rx, event_tx);
+        run_install_script(&script, "--aom", &lines_tx)
+            .await
+            .unwrap();
+        assert!(run_install_script(&script, "--fail", &lines_tx)
+            .await
+            .is_err());
+        drop(lines_tx);
+        forwarder.await.unwrap();
+
+        // stdout et stderr relayés, y compris ceux d'une étape en échec
+        let mut lines = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let QueueEvent::DepsCompilationLog { lines: batch, .. } = event {
+                lines.extend(batch);
+            }
+        }
+        for expected in ["cc --aom", "cc --fail", "warning: unused"] {
+            assert!(lines.iter().any(|line| line == expected), "{lines:?}");
+        }
+        assert_eq!(lines.len(), 4);
+    }
+}
@@ -0,0 +1,219 @@
+use super::TempWorkspace;
+use anyhow::Result;
+use encodetalker_common::{source_audio_input_args, EncodingJob};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Clé des réglages dont dépend la piste audio d'un job
+///
+/// Un pré-encodage n'est réutilisé que si la clé n'a pas changé entre-temps (job relancé
+/// avec d'autres réglages, segment d'un job découpé...).
+#[must_use]
+pub fn audio_prefetch_key(job: &EncodingJob) -> String {
+    format!(
+        "{}|{:?}|{:?}|{:?}|{:?}",
+        job.input_path.display(),
+        source_audio_input_args(&job.input_path, &job.config),
+        job.config.audio_mode,
+        job.config.audio_streams,
+        job.config.audio_languages,
+    )
+}
+
+/// Audio pré-encodé (ou en cours) d'un job en attente
+struct PrefetchEntry {
+    key: String,
+    path: PathBuf,
+    /// Supprime le fichier si l'entrée est abandonnée
+    workspace: TempWorkspace,
+    task: PrefetchTask,
+}
+
+/// Tâche de pré-encodage, arrêtée si elle est abandonnée (ffmpeg est alors tué)
+struct PrefetchTask(JoinHandle<Result<()>>);
+
+impl Drop for PrefetchTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Pistes audio des jobs en attente, encodées pendant qu'une vidéo s'encode
+#[derive(Default)]
+pub struct AudioPrefetch {
+    entries: Mutex<HashMap<Uuid, PrefetchEntry>>,
+}
+
+impl AudioPrefetch {
+    /// Un pré-encodage à jour existe-t-il déjà pour ce job ?
+    pub async fn contains(&self, job: &EncodingJob) -> bool {
+        self.entries
+            .lock()
+            .await
+            .get(&job.id)
+            .is_some_and(|entry| entry.key == audio_prefetch_key(job))
+    }
+
+    /// Enregistrer un pré-encodage lancé (remplace un éventuel pré-encodage périmé)
+    pub async fn insert(
+        &self,
+        job: &EncodingJob,
+        workspace: TempWorkspace,
+        path: PathBuf,
+        task: JoinHandle<Result<()>>,
+    ) {
+        let entry = PrefetchEntry {
+            key: audio_prefetch_key(job),
+            path,
+            workspace,
+            task: PrefetchTask(task),
+        };
+        let previous = self.entries.lock().await.insert(job.id, entry);
+        if let Some(previous) = previous {
+            discard(previous).await;
+        }
+    }
+
+    /// Récupérer le pré-encodage d'un job qui démarre (None = rien de réutilisable)
+    ///
+    /// Un pré-encodage dont les réglages ont changé est abandonné.
+    pub async fn take(&self, job: &EncodingJob) -> Option<PrefetchedAudio> {
+        let entry = self.entries.lock().await.remove(&job.id)?;
+        if entry.key != audio_prefetch_key(job) {
+            discard(entry).await;
+            return None;
+        }
+        Some(PrefetchedAudio {
+            job_id: job.id,
+            entry,
+        })
+    }
+
+    /// Abandonner les pré-encodages des jobs qui ne sont plus en attente ni actifs
+    pub async fn retain(&self, keep: &HashSet<Uuid>) {
+        let stale: Vec<PrefetchEntry> = {
+            let mut entries = self.entries.lock().await;
+            let ids: Vec<Uuid> = entries
+                .keys()
+                .filter(|id| !keep.contains(id))
+                .copied()
+                .collect();
+            ids.iter().filter_map(|id| entries.remove(id)).collect()
+        };
+        for entry in stale {
+            discard(entry).await;
+        }
+    }
+
+    /// Fichiers des pré-encodages en cours ou prêts
+    pub async fn paths(&self) -> HashSet<PathBuf> {
+        self.entries
+            .lock()
+            .await
+            .values()
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+}
+
+/// Pré-encodage repris par son job, peut-être encore en cours
+pub struct PrefetchedAudio {
+    job_id: Uuid,
+    entry: PrefetchEntry,
+}
+
+impl PrefetchedAudio {
+    /// Attendre la fin du pré-encodage
+    ///
+    /// Retourne le fichier audio et son workspace (qui le supprimera), ou None si le
+    /// pré-encodage a échoué : l'audio est alors encodé normalement.
+    pub async fn finish(self) -> Option<(TempWorkspace, PathBuf)> {
+        let PrefetchEntry {
+            path,
+            mut workspace,
+            mut task,
+            ..
+        } = self.entry;
+        let failure = match (&mut task.0).await {
+            Ok(Ok(())) => {
+                info!("Job {} : audio pré-encodé réutilisé", self.job_id);
+                return Some((workspace, path));
+            }
+            Ok(Err(e)) => format!("{e:#}"),
+            Err(e) => e.to_string(),
+        };
+        warn!(
+            "Job {} : pré-encodage audio échoué, nouvel essai: {failure}",
+            self.job_id
+        );
+        workspace.cleanup().await;
+        None
+    }
+}
+
+/// Arrêter le pré-encodage puis supprimer son fichier
+async fn discard(entry: PrefetchEntry) {
+    let mut task = entry.task;
+    task.0.abort();
+    let _ = (&mut task.0).await;
+    let mut workspace = entry.workspace;
+    workspace.cleanup().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encodetalker_common::{AudioMode, EncodingConfig};
+    use std::path::Path;
+
+    /// Simuler un pré-encodage (écrit un fichier dans `dir`)
+    async fn start(prefetch: &AudioPrefetch, dir: &Path, job: &EncodingJob) -> PathBuf {
+        let mut workspace = TempWorkspace::new(dir);
+        let path = workspace.allocate("opus");
+        let task_path = path.clone();
+        let task = tokio::spawn(async move {
+            tokio::fs::write(&task_path, b"opus").await?;
+            Ok(())
+        });
+        prefetch.insert(job, workspace, path.clone(), task).await;
+        path
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_reused_only_with_same_settings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let mut job = EncodingJob::new(
+            dir.join("film.mkv"),
+            dir.join("film.av1.mkv"),
+            EncodingConfig::default(),
+        );
+        let prefetch = AudioPrefetch::default();
+
+        let path = start(&prefetch, &dir, &job).await;
+        assert!(prefetch.contains(&job).await);
+        let prefetched = prefetch.take(&job).await.unwrap();
+        let (mut workspace, taken) = prefetched.finish().await.unwrap();
+        assert_eq!(taken, path);
+        assert!(path.exists());
+        workspace.cleanup().await;
+
+        // Réglages audio modifiés depuis le pré-encodage : fichier abandonné
+        let path = start(&prefetch, &dir, &job).await;
+        job.config.audio_mode = AudioMode::Copy;
+        assert!(!prefetch.contains(&job).await);
+        assert!(prefetch.take(&job).await.is_none());
+        assert!(!path.exists());
+
+        // Job retiré de la queue
+        let path = start(&prefetch, &dir, &job).await;
+        prefetch.retain(&HashSet::new()).await;
+        assert!(!prefetch.contains(&job).await);
+        assert!(!path.exists());
+    }
+}
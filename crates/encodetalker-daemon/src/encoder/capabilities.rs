@@ -0,0 +1,274 @@
+use encodetalker_common::{EncoderBinaryInfo, EncoderCapabilities, EncoderVersion};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::RwLock;
+
+static OPTION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s,\[])(--[A-Za-z0-9][A-Za-z0-9-]*)").unwrap());
+
+/// Version minimale de `SvtAv1EncApp` pour les options toujours émises par le pipeline
+pub const SVT_AV1_MIN_VERSIONS: &[(&str, EncoderVersion)] = &[
+    ("--tune", EncoderVersion::new(0, 9, 0)),
+    ("--progress", EncoderVersion::new(1, 2, 0)),
+];
+
+/// Délai maximal pour interroger un binaire (`--help`, `-encoders`, ...)
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exécuter un binaire et récupérer stdout + stderr concaténés
+///
+/// Le code de sortie est ignoré : certains encodeurs retournent un code non nul
+/// pour `--help`. Retourne None si le binaire ne peut pas être lancé.
+async fn run_capture(bin: &Path, args: &[&str]) -> Option<String> {
+    let output = tokio::time::timeout(PROBE_TIMEOUT, Command::new(bin).args(args).output())
+        .await
+        .ok()?
+        .ok()?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push('\n');
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Some(text)
+}
+
+/// Extraire les options longues (`--xxx`) d'une sortie `--help`
+#[must_use]
+pub fn parse_help_options(help: &str) -> Vec<String> {
+    let mut options: Vec<String> = OPTION_REGEX
+        .captures_iter(help)
+        .map(|caps| caps[1].to_string())
+        .collect();
+    options.sort();
+    options.dedup();
+    options
+}
+
+/// Extraire les noms d'encodeurs de la sortie `ffmpeg -encoders`
+///
+/// Format : ` V....D libsvtav1            SVT-AV1(...)`, après la ligne ` ------`
+#[must_use]
+pub fn parse_ffmpeg_encoders(output: &str) -> Vec<String> {
+    let mut encoders: Vec<String> = output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("------"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let flags = parts.next()?;
+            // Les flags font 6 caractères, le premier est le type (V, A, S)
+            if flags.len() != 6 || !flags.starts_with(['V', 'A', 'S']) {
+                return None;
+            }
+            parts.next().map(ToString::to_string)
+        })
+        .collect();
+    encoders.sort();
+    encoders.dedup();
+    encoders
+}
+
+/// Extraire les noms de filtres de la sortie `ffmpeg -filters`
+///
+/// Format : ` TSC zscale            V->V       Apply resizing...`, après la légende
+#[must_use]
+pub fn parse_ffmpeg_filters(output: &str) -> Vec<String> {
+    let mut filters: Vec<String> = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _flags = parts.next()?;
+            let name = parts.next()?;
+            parts
+                .next()
+                .filter(|io| io.contains("->"))
+                .map(|_| name.to_string())
+        })
+        .collect();
+    filters.sort();
+    filters.dedup();
+    filters
+}
+
+/// Première ligne non vide d'une sortie
+fn first_line(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Options de la table trop récentes pour la version détectée (version inconnue = aucune)
+#[must_use]
+pub fn options_too_new(
+    version: Option<EncoderVersion>,
+    min_versions: &[(&str, EncoderVersion)],
+) -> Vec<String> {
+    let Some(version) = version else {
+        return Vec::new();
+    };
+    min_versions
+        .iter()
+        .filter(|(_, min)| version < *min)
+        .map(|(option, _)| (*option).to_string())
+        .collect()
+}
+
+/// Interroger `SvtAv1EncApp`
+async fn probe_svt_av1(bin: &Path) -> Option<EncoderBinaryInfo> {
+    let help = run_capture(bin, &["--help"]).await?;
+    let version = run_capture(bin, &["--version"])
+        .await
+        .and_then(|v| first_line(&v));
+    let parsed_version = version.as_deref().and_then(EncoderVersion::parse);
+    Some(EncoderBinaryInfo {
+        version,
+        options: parse_help_options(&help),
+        parsed_version,
+        disabled_options: options_too_new(parsed_version, SVT_AV1_MIN_VERSIONS),
+    })
+}
+
+/// Interroger aomenc (la version figure dans la liste des codecs de `--help`)
+async fn probe_aom(bin: &Path) -> Option<EncoderBinaryInfo> {
+    let help = run_capture(bin, &["--help"]).await?;
+    let version = help
+        .lines()
+        .find(|l| l.contains("AOMedia Project AV1 Encoder"))
+        .map(|l| l.trim().to_string());
+    Some(EncoderBinaryInfo {
+        parsed_version: version.as_deref().and_then(EncoderVersion::parse),
+        version,
+        options: parse_help_options(&help),
+        disabled_options: Vec::new(),
+    })
+}
+
+/// Interroger toutes les capacités (encodeurs + ffmpeg)
+pub async fn probe_capabilities(
+    ffmpeg_bin: &Path,
+    svt_av1_bin: &Path,
+    aom_bin: &Path,
+) -> EncoderCapabilities {
+    let (svt_av1, aom, ffmpeg_version, ffmpeg_encoders, ffmpeg_filters) = tokio::join!(
+        probe_svt_av1(svt_av1_bin),
+        probe_aom(aom_bin),
+        run_capture(ffmpeg_bin, &["-hide_banner", "-version"]),
+        run_capture(ffmpeg_bin, &["-hide_banner", "-encoders"]),
+        run_capture(ffmpeg_bin, &["-hide_banner", "-filters"]),
+    );
+
+    EncoderCapabilities {
+        svt_av1,
+        aom,
+        ffmpeg_version: ffmpeg_version.and_then(|v| first_line(&v)),
+        ffmpeg_encoders: ffmpeg_encoders
+            .map(|out| parse_ffmpeg_encoders(&out))
+            .unwrap_or_default(),
+        ffmpeg_filters: ffmpeg_filters
+            .map(|out| parse_ffmpeg_filters(&out))
+            .unwrap_or_default(),
+    }
+}
+
+/// Cache des capacités des encodeurs
+///
+/// Le résultat n'est mis en cache que si tous les binaires existent, pour ne pas
+/// figer un état incomplet pendant la compilation des dépendances.
+pub struct CapabilitiesCache {
+    ffmpeg_bin: PathBuf,
+    svt_av1_bin: PathBuf,
+    aom_bin: PathBuf,
+    cached: RwLock<Option<EncoderCapabilities>>,
+}
+
+impl CapabilitiesCache {
+    #[must_use]
+    pub fn new(ffmpeg_bin: PathBuf, svt_av1_bin: PathBuf, aom_bin: PathBuf) -> Self {
+        Self {
+            ffmpeg_bin,
+            svt_av1_bin,
+            aom_bin,
+            cached: RwLock::new(None),
+        }
+    }
+
+    /// Obtenir les capacités (depuis le cache si disponible)
+    pub async fn get(&self) -> EncoderCapabilities {
+        if let Some(caps) = self.cached.read().await.as_ref() {
+            return caps.clone();
+        }
+
+        let caps = probe_capabilities(&self.ffmpeg_bin, &self.svt_av1_bin, &self.aom_bin).await;
+
+        let complete =
+            self.ffmpeg_bin.exists() && self.svt_av1_bin.exists() && self.aom_bin.exists();
+        if complete {
+            *self.cached.write().await = Some(caps.clone());
+        }
+
+        caps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_help_options() {
+        let help = "Usage: SvtAv1EncApp <options> -b dst_filename -i src_filename\n\
+                    \n\
+                    --crf                         Constant rate factor [0-63]\n\
+                    -q, --qp                      Initial QP\n\
+                    --film-grain                  Film grain denoising [0-50]\n\
+                    --crf                         duplicate\n";
+        let options = parse_help_options(help);
+        assert_eq!(options, vec!["--crf", "--film-grain", "--qp"]);
+    }
+
+    #[test]
+    fn test_options_too_new() {
+        assert_eq!(
+            options_too_new(Some(EncoderVersion::new(1, 1, 0)), SVT_AV1_MIN_VERSIONS),
+            ["--progress"]
+        );
+        assert_eq!(
+            options_too_new(Some(EncoderVersion::new(0, 8, 7)), SVT_AV1_MIN_VERSIONS),
+            ["--tune", "--progress"]
+        );
+        assert!(
+            options_too_new(Some(EncoderVersion::new(2, 3, 0)), SVT_AV1_MIN_VERSIONS).is_empty()
+        );
+        assert!(options_too_new(None, SVT_AV1_MIN_VERSIONS).is_empty());
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_encoders() {
+        let output = "Encoders:\n \
+                      V..... = Video\n \
+                      A..... = Audio\n \
+                      ------\n \
+                      V....D libsvtav1            SVT-AV1(Scalable Video Technology for AV1) encoder (codec av1)\n \
+                      V....D av1_nvenc            NVIDIA NVENC av1 encoder (codec av1)\n \
+                      A....D libopus              libopus Opus (codec opus)\n \
+                      A....D aac                  AAC (Advanced Audio Coding)\n";
+        let encoders = parse_ffmpeg_encoders(output);
+        assert_eq!(encoders, vec!["aac", "av1_nvenc", "libopus", "libsvtav1"]);
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_filters() {
+        let output = "Filters:\n  \
+                      T.. = Timeline support\n  \
+                      A = Audio input/output\n  \
+                      | = Source or sink filter\n \
+                      TSC zscale            V->V       Apply resizing, colorspace and bit depth conversion.\n \
+                      ..C tonemap           V->V       Conversion to/from different dynamic ranges.\n \
+                      ... abuffer           |->A       Buffer audio frames.\n";
+        let filters = parse_ffmpeg_filters(output);
+        assert_eq!(filters, vec!["abuffer", "tonemap", "zscale"]);
+    }
+}
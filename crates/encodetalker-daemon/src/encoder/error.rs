@@ -0,0 +1,180 @@
+use encodetalker_common::FailureCategory;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Erreur structurée du pipeline d'encodage
+///
+/// Les messages restent lisibles pour l'affichage ; la catégorie permet au client
+/// de distinguer les échecs (source manquante, crash encodeur, annulation...).
+#[derive(Debug, Error)]
+pub enum EncodeError {
+    #[error("Fichier source introuvable: {}", .0.display())]
+    InputMissing(PathBuf),
+
+    #[error("Échec du probe vidéo: {0:#}")]
+    ProbeFailed(anyhow::Error),
+
+    /// `code` suit la convention du shell : 128 + n pour un processus tué par le signal n
+    #[error("L'encodeur a échoué avec le code {code:?}")]
+    EncoderFailed { code: Option<i32> },
+
+    #[error("ffmpeg a échoué avec le code {code:?}")]
+    DecoderFailed { code: Option<i32> },
+
+    #[error("Le conteneur {container} ne supporte pas le codec vidéo {codec} en copie")]
+    UnsupportedCopyCodec { codec: String, container: String },
+
+    #[error("Le conteneur {container} ne supporte pas les sous-titres {codec} en copie (incompatible_subtitles = \"fail\")")]
+    IncompatibleSubtitles { codec: String, container: String },
+
+    #[error("Encodage audio échoué: {0}")]
+    AudioFailed(String),
+
+    #[error("Muxage ffmpeg échoué: {0}")]
+    MuxFailed(String),
+
+    #[error("Espace disque insuffisant")]
+    DiskFull,
+
+    #[error("Encodage annulé")]
+    Cancelled,
+
+    #[error("Aucune progression depuis {secs} s, job arrêté par le watchdog")]
+    Stalled { secs: u64 },
+
+    #[error("Fichier invalide: {0}")]
+    VerifyFailed(String),
+
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl EncodeError {
+    /// Catégorie d'échec transmise au client
+    #[must_use]
+    pub fn category(&self) -> FailureCategory {
+        match self {
+            EncodeError::InputMissing(_) => FailureCategory::InputMissing,
+            EncodeError::ProbeFailed(_) => FailureCategory::ProbeFailed,
+            EncodeError::EncoderFailed { .. } | EncodeError::DecoderFailed { .. } => {
+                FailureCategory::EncoderFailed
+            }
+            EncodeError::UnsupportedCopyCodec { .. }
+            | EncodeError::IncompatibleSubtitles { .. } => FailureCategory::MuxFailed,
+            EncodeError::AudioFailed(_) => FailureCategory::AudioFailed,
+            EncodeError::MuxFailed(_) => FailureCategory::MuxFailed,
+            EncodeError::DiskFull => FailureCategory::DiskFull,
+            EncodeError::Cancelled => FailureCategory::Cancelled,
+            EncodeError::Stalled { .. } => FailureCategory::Stalled,
+            EncodeError::VerifyFailed(_) => FailureCategory::VerifyFailed,
+            EncodeError::Other(_) => FailureCategory::Other,
+        }
+    }
+
+    /// Code de sortie de l'encodeur ou de ffmpeg en échec (None = autre erreur)
+    #[must_use]
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            EncodeError::EncoderFailed { code } | EncodeError::DecoderFailed { code } => *code,
+            _ => None,
+        }
+    }
+
+    /// Convertir une erreur anyhow en erreur structurée
+    ///
+    /// Récupère une `EncodeError` encapsulée si présente, et détecte un disque plein
+    /// dans la chaîne d'erreurs (erreur IO ou message ffmpeg).
+    #[must_use]
+    pub fn from_anyhow(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<EncodeError>() {
+            Ok(encode_err) => {
+                return match encode_err {
+                    EncodeError::AudioFailed(ref msg) | EncodeError::MuxFailed(ref msg)
+                        if is_disk_full_message(msg) =>
+                    {
+                        EncodeError::DiskFull
+                    }
+                    other => other,
+                };
+            }
+            Err(err) => err,
+        };
+
+        let disk_full = err.chain().any(|cause| {
+            cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|io| io.kind() == std::io::ErrorKind::StorageFull)
+                || is_disk_full_message(&cause.to_string())
+        });
+
+        if disk_full {
+            EncodeError::DiskFull
+        } else {
+            EncodeError::Other(err)
+        }
+    }
+}
+
+/// Code de sortie d'un processus à la manière du shell : 128 + n s'il a été tué par le
+/// signal n (139 pour un segfault, 137 pour SIGKILL)
+#[must_use]
+pub fn exit_code(status: std::process::ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return Some(128 + signal);
+        }
+    }
+    status.code()
+}
+
+/// Message d'erreur système indiquant un disque plein (ENOSPC)
+fn is_disk_full_message(msg: &str) -> bool {
+    msg.contains("No space left on device")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_anyhow_keeps_structured_error() {
+        let err = anyhow::Error::from(EncodeError::EncoderFailed { code: Some(139) });
+        let err = EncodeError::from_anyhow(err.context("Passe 1"));
+        assert!(matches!(
+            err,
+            EncodeError::EncoderFailed { code: Some(139) }
+        ));
+        assert_eq!(err.category(), FailureCategory::EncoderFailed);
+        assert_eq!(err.exit_code(), Some(139));
+        assert_eq!(EncodeError::DiskFull.exit_code(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_of_signaled_process() {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(
+            exit_code(std::process::ExitStatus::from_raw(1 << 8)),
+            Some(1)
+        );
+        // Tué par SIGSEGV (11)
+        assert_eq!(exit_code(std::process::ExitStatus::from_raw(11)), Some(139));
+    }
+
+    #[test]
+    fn test_from_anyhow_detects_disk_full() {
+        let io = std::io::Error::from(std::io::ErrorKind::StorageFull);
+        let err = EncodeError::from_anyhow(anyhow::Error::from(io));
+        assert_eq!(err.category(), FailureCategory::DiskFull);
+
+        let err = EncodeError::from_anyhow(
+            EncodeError::MuxFailed("out.mkv: No space left on device".to_string()).into(),
+        );
+        assert_eq!(err.category(), FailureCategory::DiskFull);
+
+        let err = EncodeError::from_anyhow(anyhow::anyhow!("autre chose"));
+        assert_eq!(err.category(), FailureCategory::Other);
+    }
+}
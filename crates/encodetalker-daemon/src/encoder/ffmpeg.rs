@@ -1,6 +1,12 @@
 use anyhow::{Context, Result};
+use encodetalker_common::{
+    bt709_color_args, is_concat_list, is_hdr_transfer, output_dimensions, parse_concat_list,
+    DeinterlaceConfig, EncoderType, TonemapConfig, TrimRange, VfrMode, EVEN_CROP_FILTER,
+};
+use futures::StreamExt;
 use serde::Deserialize;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Estimer le nombre de frames à partir de la durée et du fps
@@ -14,22 +20,117 @@ fn estimate_frame_count(duration_secs: f64, fps: f64) -> u64 {
 }
 
 /// Informations sur le fichier vidéo source
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct VideoInfo {
     pub duration: Option<Duration>,
     pub total_frames: Option<u64>,
     pub width: u32,
     pub height: u32,
     pub fps: f64,
+    /// Entrelacement détecté sur la source
     pub is_interlaced: bool,
+    /// Filtre de désentrelacement appliqué au décodage (None = aucun)
+    pub deinterlace_filter: Option<String>,
+    /// Recadrage aux dimensions paires appliqué après le désentrelacement (None = aucun)
+    pub crop_filter: Option<String>,
+    /// Framerate variable détecté sur la source (`fps` est alors le framerate moyen)
+    pub is_vfr: bool,
+    /// Traitement VFR appliqué au décodage (None = source à framerate constant)
+    pub vfr_mode: Option<VfrMode>,
+    /// Fonction de transfert du stream vidéo principal (`color_transfer` de ffprobe :
+    /// bt709, smpte2084 pour le HDR10, arib-std-b67 pour le HLG...)
+    pub color_transfer: Option<String>,
+    /// Conversion HDR → SDR appliquée au décodage, après le recadrage (None = aucune)
+    pub tonemap_filter: Option<String>,
+    /// Codec du stream vidéo principal (nom ffprobe : h264, hevc, av1...)
+    pub video_codec: String,
     pub audio_streams: Vec<AudioStreamInfo>,
     pub subtitle_streams: Vec<SubtitleStreamInfo>,
+    /// Pochettes de la source (vide si aucune)
+    pub attached_pics: Vec<AttachedPicInfo>,
+    /// Chapitres de la source (vide si aucun)
+    pub chapters: Vec<ChapterInfo>,
+}
+
+impl VideoInfo {
+    /// Options de sortie du décodage fixant la cadence des frames envoyées à l'encodeur
+    #[must_use]
+    pub fn decode_rate_args(&self) -> Vec<String> {
+        self.vfr_mode
+            .map(|mode| mode.ffmpeg_output_args(self.fps))
+            .unwrap_or_default()
+    }
+
+    /// Chaîne de filtres du décodage (désentrelacement, recadrage puis tone mapping,
+    /// None = aucun)
+    #[must_use]
+    pub fn decode_filter(&self) -> Option<String> {
+        let filters: Vec<&str> = [
+            self.deinterlace_filter.as_deref(),
+            self.crop_filter.as_deref(),
+            self.tonemap_filter.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+
+    /// Source HDR (transfert PQ ou HLG) ?
+    #[must_use]
+    pub fn is_hdr(&self) -> bool {
+        self.color_transfer.as_deref().is_some_and(is_hdr_transfer)
+    }
+
+    /// Options de l'encodeur décrivant les couleurs de la sortie : BT.709 après un tone
+    /// mapping, rien sinon (les métadonnées HDR de la source ne sont jamais reprises)
+    #[must_use]
+    pub fn output_color_args(&self, encoder: EncoderType) -> Vec<String> {
+        if self.tonemap_filter.is_some() {
+            bt709_color_args(encoder)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Filtres appliqués à la référence VMAF pour qu'elle corresponde frame à frame
+    /// à la vidéo encodée (désentrelacement, recadrage, tone mapping, normalisation VFR)
+    #[must_use]
+    pub fn reference_filter(&self) -> Option<String> {
+        let fps = (self.vfr_mode == Some(VfrMode::Cfr)).then(|| format!("fps={:.3}", self.fps));
+        match (self.decode_filter(), fps) {
+            (Some(filter), Some(fps)) => Some(format!("{filter},{fps}")),
+            (filter, None) => filter,
+            (None, fps) => fps,
+        }
+    }
+}
+
+/// Pochette de la source : image attachée, que ffprobe présente comme un stream vidéo
+#[derive(Debug, Clone)]
+pub struct AttachedPicInfo {
+    /// Index absolu du stream dans la source
+    pub index: usize,
+    pub codec: String,
+    /// Nom de la pièce jointe (Matroska), repris dans la sortie
+    pub filename: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChapterInfo {
+    /// Début en secondes
+    pub start: f64,
+    /// Fin en secondes
+    pub end: f64,
+    pub title: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct AudioStreamInfo {
     pub index: usize,
     pub codec: String,
+    /// Nombre de canaux (None = non indiqué par ffprobe)
+    pub channels: Option<u32>,
     pub language: Option<String>,
     pub title: Option<String>,
 }
@@ -47,6 +148,15 @@ pub struct SubtitleStreamInfo {
 struct FFProbeOutput {
     format: FFProbeFormat,
     streams: Vec<FFProbeStream>,
+    #[serde(default)]
+    chapters: Vec<FFProbeChapter>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FFProbeChapter {
+    start_time: String,
+    end_time: String,
+    tags: Option<FFProbeTags>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,17 +172,36 @@ struct FFProbeStream {
     width: Option<u32>,
     height: Option<u32>,
     r_frame_rate: Option<String>,
+    avg_frame_rate: Option<String>,
     nb_frames: Option<String>,
     field_order: Option<String>,
+    #[serde(default)]
+    color_transfer: Option<String>,
+    #[serde(default)]
+    channels: Option<u32>,
     tags: Option<FFProbeTags>,
+    #[serde(default)]
+    disposition: Option<FFProbeDisposition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FFProbeDisposition {
+    /// Pochette (image attachée à un fichier audio), pas une piste vidéo
+    #[serde(default)]
+    attached_pic: u8,
 }
 
 #[derive(Debug, Deserialize)]
 struct FFProbeTags {
     language: Option<String>,
     title: Option<String>,
+    #[serde(default)]
+    filename: Option<String>,
 }
 
+/// Délai par défaut du comptage précis des frames
+pub const DEFAULT_PRECISE_COUNT_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// Déterminer le nombre total de frames (3 stratégies)
 async fn determine_total_frames(
     ffmpeg_bin: &Path,
@@ -80,7 +209,8 @@ async fn determine_total_frames(
     metadata_frames: Option<u64>,
     duration: Option<Duration>,
     fps: f64,
-    precise_count: bool,
+    precise_count: Option<Duration>,
+    env: &HashMap<String, String>,
 ) -> Option<u64> {
     match metadata_frames {
         Some(frames) => {
@@ -88,9 +218,9 @@ async fn determine_total_frames(
             Some(frames)
         }
         None => {
-            if precise_count {
+            if let Some(timeout) = precise_count {
                 // Niveau 2: Comptage précis via ffmpeg (lent mais exact)
-                match count_frames_precisely(ffmpeg_bin, input).await {
+                match count_frames_precisely(ffmpeg_bin, input, timeout, env).await {
                     Ok(frames) => {
                         tracing::info!("Total frames: {frames} (source: comptage précis ffmpeg)");
                         Some(frames)
@@ -119,7 +249,15 @@ fn estimate_frames_from_duration(duration: Option<Duration>, fps: f64) -> Option
 
 /// Compter précisément les frames via ffmpeg -c copy -f null
 /// ATTENTION: LENT (lit tout le fichier vidéo)
-async fn count_frames_precisely(ffmpeg_bin: &Path, input: &Path) -> Result<u64> {
+///
+/// Le processus est tué si le comptage dépasse `timeout` ou si la future est abandonnée
+/// (job annulé pendant le probe).
+async fn count_frames_precisely(
+    ffmpeg_bin: &Path,
+    input: &Path,
+    timeout: Duration,
+    env: &HashMap<String, String>,
+) -> Result<u64> {
     use regex::Regex;
     use tokio::io::{AsyncBufReadExt, BufReader};
     use tokio::process::Command;
@@ -127,6 +265,7 @@ async fn count_frames_precisely(ffmpeg_bin: &Path, input: &Path) -> Result<u64>
     tracing::info!("Comptage précis des frames (peut prendre du temps)...");
 
     let mut child = Command::new(ffmpeg_bin)
+        .envs(env)
         .arg("-i")
         .arg(input)
         .arg("-map")
@@ -138,6 +277,7 @@ async fn count_frames_precisely(ffmpeg_bin: &Path, input: &Path) -> Result<u64>
         .arg("-")
         .stdout(std::process::Stdio::null())
         .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
         .spawn()
         .context("Échec du comptage de frames")?;
 
@@ -148,7 +288,6 @@ async fn count_frames_precisely(ffmpeg_bin: &Path, input: &Path) -> Result<u64>
     let frame_regex = Regex::new(r"frame=\s*(\d+)").unwrap();
     let mut last_frame = 0u64;
 
-    // NOUVEAU: Timeout global de 5 minutes
     let count_task = async {
         while let Ok(Some(line)) = lines.next_line().await {
             if let Some(caps) = frame_regex.captures(&line) {
@@ -160,8 +299,11 @@ async fn count_frames_precisely(ffmpeg_bin: &Path, input: &Path) -> Result<u64>
         last_frame
     };
 
-    let Ok(last_frame) = tokio::time::timeout(Duration::from_secs(300), count_task).await else {
-        tracing::warn!("Timeout comptage frames (5 min), arrêt du processus");
+    let Ok(last_frame) = tokio::time::timeout(timeout, count_task).await else {
+        tracing::warn!(
+            "Timeout comptage frames ({}s), arrêt du processus",
+            timeout.as_secs()
+        );
         let _ = child.kill().await;
         anyhow::bail!("Timeout comptage précis des frames");
     };
@@ -174,6 +316,16 @@ async fn count_frames_precisely(ffmpeg_bin: &Path, input: &Path) -> Result<u64>
 
 /// Prober un fichier vidéo avec ffprobe
 ///
+/// Sans `nb_frames` dans les métadonnées, le nombre de frames est compté par ffmpeg en
+/// `precise_count` au plus (None = estimation depuis la durée). Les processus lancés sont
+/// tués si la future est abandonnée.
+///
+/// Sans `field_order` exploitable, l'entrelacement n'est analysé avec le filtre `idet`
+/// que si `detect_interlacing` est vrai (désentrelacement automatique) ; sinon la source
+/// est considérée progressive.
+///
+/// `env` complète l'environnement de ffprobe et ffmpeg (variables du job).
+///
 /// # Errors
 ///
 /// Retourne une erreur si:
@@ -181,19 +333,184 @@ async fn count_frames_precisely(ffmpeg_bin: &Path, input: &Path) -> Result<u64>
 /// - Le parsing JSON échoue
 /// - Aucun stream vidéo n'est trouvé
 /// - Les métadonnées essentielles (largeur/hauteur) sont manquantes
+/// - La source n'a aucune frame, une durée nulle ou est une image fixe
+/// - Les fichiers d'une liste de concaténation n'ont pas le même format
+pub async fn probe_video(
+    ffprobe_bin: &Path,
+    ffmpeg_bin: &Path,
+    input: &Path,
+    precise_count: Option<Duration>,
+    detect_interlacing: bool,
+    env: &HashMap<String, String>,
+) -> Result<VideoInfo> {
+    if is_concat_list(input) {
+        probe_concat_list(
+            ffprobe_bin,
+            ffmpeg_bin,
+            input,
+            precise_count,
+            detect_interlacing,
+            env,
+        )
+        .await
+    } else {
+        probe_file(
+            ffprobe_bin,
+            ffmpeg_bin,
+            input,
+            precise_count,
+            detect_interlacing,
+            env,
+        )
+        .await
+    }
+}
+
+/// Probes simultanés au plus d'une requête `ProbeVideos`, qui restent ainsi bornés même
+/// pour un gros lot
+pub const MAX_PARALLEL_PROBES: usize = 4;
+
+/// Prober plusieurs fichiers en parallèle, `concurrency` à la fois, sans comptage précis
 ///
-/// # Panics
+/// Un échec n'interrompt pas les autres probes : chaque fichier a son résultat, dans
+/// l'ordre de `inputs`.
+pub async fn probe_videos(
+    ffprobe_bin: &Path,
+    ffmpeg_bin: &Path,
+    inputs: Vec<PathBuf>,
+    concurrency: usize,
+) -> Vec<(PathBuf, Result<VideoInfo>)> {
+    futures::stream::iter(inputs)
+        .map(|input| async move {
+            let result = probe_video(
+                ffprobe_bin,
+                ffmpeg_bin,
+                &input,
+                None,
+                false,
+                &HashMap::new(),
+            )
+            .await;
+            (input, result)
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Prober chaque fichier d'une liste de concaténation et combiner leurs informations
+async fn probe_concat_list(
+    ffprobe_bin: &Path,
+    ffmpeg_bin: &Path,
+    list: &Path,
+    precise_count: Option<Duration>,
+    detect_interlacing: bool,
+    env: &HashMap<String, String>,
+) -> Result<VideoInfo> {
+    let content = tokio::fs::read_to_string(list)
+        .await
+        .with_context(|| format!("Impossible de lire la liste {}", list.display()))?;
+    let files = parse_concat_list(&content, list.parent().unwrap_or_else(|| Path::new(".")));
+
+    let mut parts = Vec::with_capacity(files.len());
+    for file in &files {
+        if is_concat_list(file) {
+            anyhow::bail!(
+                "Listes de concaténation imbriquées non supportées: {}",
+                file.display()
+            );
+        }
+        // L'entrelacement vient du premier fichier (voir `combine_concat_parts`)
+        let detect = detect_interlacing && parts.is_empty();
+        let info = probe_file(ffprobe_bin, ffmpeg_bin, file, precise_count, detect, env)
+            .await
+            .with_context(|| format!("Échec du probe de {}", file.display()))?;
+        parts.push((file.clone(), info));
+    }
+    combine_concat_parts(parts)
+}
+
+/// Combiner les informations des fichiers joints (durée, frames et chapitres cumulés)
 ///
-/// Peut paniquer si le chemin d'entrée contient des caractères invalides (conversion `to_str().unwrap()`).
-pub async fn probe_video(
+/// Le demuxer concat exige des fichiers de même format : codec, résolution et nombre de
+/// pistes audio sont vérifiés. Les pistes et l'entrelacement viennent du premier fichier ;
+/// des framerates différents rendent la source variable.
+fn combine_concat_parts(parts: Vec<(PathBuf, VideoInfo)>) -> Result<VideoInfo> {
+    let mut parts = parts.into_iter();
+    let (_, mut combined) = parts.next().context("Liste de concaténation vide")?;
+    let mut elapsed = combined.duration;
+
+    for (file, part) in parts {
+        let name = file.display();
+        if part.video_codec != combined.video_codec {
+            anyhow::bail!(
+                "{name}: codec {} différent de {} (fichiers à joindre incompatibles)",
+                part.video_codec,
+                combined.video_codec
+            );
+        }
+        if (part.width, part.height) != (combined.width, combined.height) {
+            anyhow::bail!(
+                "{name}: résolution {}x{} différente de {}x{} (fichiers à joindre incompatibles)",
+                part.width,
+                part.height,
+                combined.width,
+                combined.height
+            );
+        }
+        if part.audio_streams.len() != combined.audio_streams.len() {
+            anyhow::bail!(
+                "{name}: {} pistes audio au lieu de {} (fichiers à joindre incompatibles)",
+                part.audio_streams.len(),
+                combined.audio_streams.len()
+            );
+        }
+
+        if let Some(offset) = elapsed {
+            let offset = offset.as_secs_f64();
+            combined
+                .chapters
+                .extend(part.chapters.into_iter().map(|chapter| ChapterInfo {
+                    start: chapter.start + offset,
+                    end: chapter.end + offset,
+                    ..chapter
+                }));
+        }
+        combined.is_vfr |= part.is_vfr || (part.fps - combined.fps).abs() > f64::EPSILON;
+        combined.total_frames = combined
+            .total_frames
+            .zip(part.total_frames)
+            .map(|(a, b)| a + b);
+        elapsed = elapsed.zip(part.duration).map(|(a, b)| a + b);
+    }
+
+    combined.duration = elapsed;
+    // Framerate moyen de l'ensemble quand les fichiers diffèrent
+    if combined.is_vfr {
+        if let (Some(frames), Some(duration)) = (combined.total_frames, combined.duration) {
+            if duration.as_secs_f64() > 0.0 {
+                #[allow(clippy::cast_precision_loss)] // Safe: < 2^53 frames
+                let fps = frames as f64 / duration.as_secs_f64();
+                combined.fps = fps;
+            }
+        }
+    }
+    Ok(combined)
+}
+
+/// Prober un seul fichier vidéo avec ffprobe
+async fn probe_file(
     ffprobe_bin: &Path,
     ffmpeg_bin: &Path,
     input: &Path,
-    precise_count: bool,
+    precise_count: Option<Duration>,
+    detect_interlacing: bool,
+    env: &HashMap<String, String>,
 ) -> Result<VideoInfo> {
     use tokio::process::Command;
 
     let output = Command::new(ffprobe_bin)
+        .envs(env)
         .args([
             "-v",
             "quiet",
@@ -201,8 +518,10 @@ pub async fn probe_video(
             "json",
             "-show_format",
             "-show_streams",
-            input.to_str().unwrap(),
+            "-show_chapters",
         ])
+        .arg(input)
+        .kill_on_drop(true)
         .output()
         .await
         .context("Échec de l'exécution de ffprobe")?;
@@ -223,35 +542,60 @@ pub async fn probe_video(
         .and_then(|d| d.parse::<f64>().ok())
         .map(Duration::from_secs_f64);
 
-    // Trouver le stream vidéo principal
+    // Trouver le stream vidéo principal (les pochettes ne comptent pas)
     let video_stream = probe
         .streams
         .iter()
-        .find(|s| s.codec_type == "video")
+        .find(|s| {
+            s.codec_type == "video" && s.disposition.as_ref().is_none_or(|d| d.attached_pic == 0)
+        })
         .context("Aucun stream vidéo trouvé")?;
 
     let width = video_stream.width.context("Largeur manquante")?;
     let height = video_stream.height.context("Hauteur manquante")?;
 
-    // Détecter l'entrelacement via field_order
-    let is_interlaced = video_stream
-        .field_order
-        .as_ref()
-        .is_some_and(|fo| matches!(fo.as_str(), "tt" | "bb" | "tb" | "bt"));
+    // Détecter l'entrelacement via field_order, puis idet si le conteneur ne le précise pas
+    let is_interlaced = match video_stream.field_order.as_deref() {
+        Some("tt" | "bb" | "tb" | "bt") => true,
+        Some("progressive") => false,
+        _ if detect_interlacing => detect_interlacing_idet(ffmpeg_bin, input, env).await,
+        _ => false,
+    };
 
     if is_interlaced {
         tracing::info!(
-            "Vidéo entrelacée détectée (field_order: {:?}), yadif sera appliqué",
+            "Vidéo entrelacée détectée (field_order: {:?})",
             video_stream.field_order
         );
     }
 
-    // Parser FPS (format: "24000/1001" ou "24")
-    let fps = video_stream
+    // Fonction de transfert : seules les valeurs connues sont gardées
+    let color_transfer = video_stream
+        .color_transfer
+        .clone()
+        .filter(|t| !t.is_empty() && t != "unknown");
+    if color_transfer.as_deref().is_some_and(is_hdr_transfer) {
+        tracing::info!("Source HDR détectée (color_transfer: {color_transfer:?})");
+    }
+
+    // Parser FPS (format: "24000/1001" ou "24") ; pour une source VFR, le framerate
+    // moyen reflète la durée réelle, contrairement à `r_frame_rate`
+    let base_fps = video_stream
         .r_frame_rate
-        .as_ref()
-        .and_then(|r| parse_frame_rate(r))
-        .unwrap_or(30.0);
+        .as_deref()
+        .and_then(parse_frame_rate);
+    let avg_fps = video_stream
+        .avg_frame_rate
+        .as_deref()
+        .and_then(parse_frame_rate);
+    let is_vfr = is_variable_frame_rate(base_fps, avg_fps);
+    let fps = if is_vfr { avg_fps } else { base_fps }.unwrap_or(30.0);
+    if is_vfr {
+        tracing::info!(
+            "Framerate variable détecté (r_frame_rate: {:?}, moyen: {fps:.3})",
+            video_stream.r_frame_rate
+        );
+    }
 
     // Parser total frames avec fallback sur estimation
     let total_frames_from_metadata = video_stream
@@ -267,6 +611,7 @@ pub async fn probe_video(
         duration,
         fps,
         precise_count,
+        env,
     )
     .await;
 
@@ -278,6 +623,7 @@ pub async fn probe_video(
         .map(|s| AudioStreamInfo {
             index: s.index as usize,
             codec: s.codec_name.clone(),
+            channels: s.channels,
             language: s.tags.as_ref().and_then(|t| t.language.clone()),
             title: s.tags.as_ref().and_then(|t| t.title.clone()),
         })
@@ -296,16 +642,405 @@ pub async fn probe_video(
         })
         .collect();
 
-    Ok(VideoInfo {
+    // Extraire les pochettes
+    let attached_pics = probe
+        .streams
+        .iter()
+        .filter(|s| {
+            s.codec_type == "video" && s.disposition.as_ref().is_some_and(|d| d.attached_pic != 0)
+        })
+        .map(|s| AttachedPicInfo {
+            index: s.index as usize,
+            codec: s.codec_name.clone(),
+            filename: s.tags.as_ref().and_then(|t| t.filename.clone()),
+        })
+        .collect();
+
+    // Extraire les chapitres (ignorés si les timestamps sont illisibles)
+    let chapters = probe
+        .chapters
+        .iter()
+        .filter_map(|c| {
+            Some(ChapterInfo {
+                start: c.start_time.parse().ok()?,
+                end: c.end_time.parse().ok()?,
+                title: c.tags.as_ref().and_then(|t| t.title.clone()),
+            })
+        })
+        .collect();
+
+    let info = VideoInfo {
         duration,
         total_frames,
         width,
         height,
         fps,
         is_interlaced,
+        deinterlace_filter: None,
+        crop_filter: None,
+        is_vfr,
+        vfr_mode: None,
+        color_transfer,
+        tonemap_filter: None,
+        video_codec: video_stream.codec_name.clone(),
         audio_streams,
         subtitle_streams,
-    })
+        attached_pics,
+        chapters,
+    };
+    ensure_encodable(&info)?;
+    Ok(info)
+}
+
+/// Codecs d'images fixes (PNG, JPEG...) que ffprobe présente comme un stream vidéo
+const STILL_IMAGE_CODECS: &[&str] = &["png", "mjpeg", "bmp", "tiff", "webp", "jpeg2000", "jpegxl"];
+
+/// Refuser les sources sans vidéo à encoder : aucune frame, durée nulle ou image fixe
+///
+/// Sans frames, la progression et l'ETA n'ont pas de sens et l'encodeur produirait une
+/// sortie vide. Le MJPEG n'est une image fixe que s'il ne compte qu'une frame.
+fn ensure_encodable(info: &VideoInfo) -> Result<()> {
+    if info.total_frames == Some(0) {
+        anyhow::bail!("La source ne contient aucune frame vidéo (fichier vide ou tronqué)");
+    }
+    let still_codec = STILL_IMAGE_CODECS.contains(&info.video_codec.as_str());
+    let single_frame = info.total_frames.is_none_or(|frames| frames <= 1);
+    if info.total_frames == Some(1) || (still_codec && single_frame) {
+        anyhow::bail!(
+            "La source est une image fixe ({}) : une vidéo est attendue",
+            info.video_codec
+        );
+    }
+    if info.duration.is_some_and(|d| d.is_zero()) {
+        anyhow::bail!("La source a une durée nulle");
+    }
+    Ok(())
+}
+
+/// Frames analysées par le filtre `idet`
+const IDET_FRAMES: u32 = 300;
+
+/// Détecter l'entrelacement en analysant les premières frames avec le filtre `idet`
+///
+/// Utilisé quand ffprobe ne donne pas de `field_order` exploitable et que le job
+/// désentrelace en mode automatique. En cas d'échec,
+/// la source est considérée progressive.
+async fn detect_interlacing_idet(
+    ffmpeg_bin: &Path,
+    input: &Path,
+    env: &HashMap<String, String>,
+) -> bool {
+    let output = tokio::process::Command::new(ffmpeg_bin)
+        .envs(env)
+        .arg("-nostats")
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(input)
+        .args(["-map", "0:v:0", "-vf", "idet", "-an", "-f", "null"])
+        .arg("-frames:v")
+        .arg(IDET_FRAMES.to_string())
+        .arg("-")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    match tokio::time::timeout(Duration::from_secs(60), output).await {
+        Ok(Ok(output)) => {
+            let detected = parse_idet_output(&String::from_utf8_lossy(&output.stderr));
+            tracing::debug!("Détection idet: {detected:?}");
+            detected.unwrap_or(false)
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("Détection idet impossible: {e}");
+            false
+        }
+        Err(_) => {
+            tracing::warn!("Timeout de la détection idet (60s)");
+            false
+        }
+    }
+}
+
+/// Lire le bilan `idet` (« Multi frame detection ») : entrelacé si les frames TFF/BFF
+/// l'emportent sur les progressives
+fn parse_idet_output(stderr: &str) -> Option<bool> {
+    let line = stderr
+        .lines()
+        .rev()
+        .find(|l| l.contains("Multi frame detection:"))?;
+    let count = |key: &str| -> Option<u64> {
+        line.split(key)
+            .nth(1)?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    };
+    let interlaced = count("TFF:")? + count("BFF:")?;
+    Some(interlaced > count("Progressive:")?)
+}
+
+/// Choisir le filtre de désentrelacement du job et ajuster framerate et nombre de frames
+///
+/// À appeler après [`apply_trim`], qui recalcule le nombre de frames depuis le framerate.
+pub fn apply_deinterlace(info: &mut VideoInfo, config: DeinterlaceConfig) {
+    if !config.applies(info.is_interlaced) {
+        info.deinterlace_filter = None;
+        return;
+    }
+    let filter = config.filter_arg();
+    let factor = config.rate_factor();
+    if factor > 1 {
+        info.fps *= f64::from(factor);
+        info.total_frames = info.total_frames.map(|n| n * u64::from(factor));
+    }
+    tracing::info!(
+        "Désentrelacement: {filter} ({:.2} fps, {:?} frames)",
+        info.fps,
+        info.total_frames
+    );
+    info.deinterlace_filter = Some(filter);
+}
+
+/// Recadrer au pair inférieur une source aux dimensions impaires (`crop_to_even`)
+///
+/// Sans recadrage, des dimensions impaires sont seulement signalées : l'encodeur
+/// les refusera probablement.
+pub fn apply_even_crop(info: &mut VideoInfo, crop_to_even: bool) {
+    info.crop_filter = None;
+    let (width, height) = output_dimensions(info.width, info.height, true);
+    if (width, height) == (info.width, info.height) {
+        return;
+    }
+    if !crop_to_even {
+        tracing::warn!(
+            "Dimensions impaires {}x{} : l'encodage AV1 risque d'échouer (crop_to_even désactivé)",
+            info.width,
+            info.height
+        );
+        return;
+    }
+    tracing::info!(
+        "Recadrage aux dimensions paires: {}x{} -> {width}x{height}",
+        info.width,
+        info.height
+    );
+    info.width = width;
+    info.height = height;
+    info.crop_filter = Some(EVEN_CROP_FILTER.to_string());
+}
+
+/// Choisir la conversion HDR → SDR du job (sans effet sur une source SDR)
+pub fn apply_tonemap(info: &mut VideoInfo, config: TonemapConfig) {
+    info.tonemap_filter = config
+        .applies(info.color_transfer.as_deref())
+        .then(|| config.filter_chain());
+    if info.tonemap_filter.is_some() {
+        tracing::info!(
+            "Tone mapping HDR → SDR: {} à {} nits",
+            config.method,
+            config.peak_nits
+        );
+    } else if info.is_hdr() {
+        tracing::info!("Source HDR sans tone mapping (tonemap désactivé)");
+    }
+}
+
+/// Écart relatif entre `r_frame_rate` et le framerate moyen au-delà duquel la source est VFR
+const VFR_TOLERANCE: f64 = 0.002;
+
+/// La source est-elle à framerate variable ?
+///
+/// `r_frame_rate` est la plus petite cadence représentant tous les timestamps : il ne
+/// correspond au framerate moyen que si les frames sont régulièrement espacées.
+fn is_variable_frame_rate(base_fps: Option<f64>, avg_fps: Option<f64>) -> bool {
+    match (base_fps, avg_fps) {
+        (Some(base), Some(avg)) if avg > 0.0 => (base - avg).abs() / avg > VFR_TOLERANCE,
+        _ => false,
+    }
+}
+
+/// Choisir le traitement VFR du job (sans effet sur une source à framerate constant)
+pub fn apply_vfr(info: &mut VideoInfo, mode: VfrMode) {
+    info.vfr_mode = info.is_vfr.then_some(mode);
+    if info.is_vfr {
+        tracing::info!("Source VFR: {mode} à {:.3} fps", info.fps);
+    }
+}
+
+/// Sortie JSON de ffprobe limitée aux durées
+#[derive(Debug, Deserialize)]
+struct FFProbeDurations {
+    format: FFProbeFormat,
+    #[serde(default)]
+    streams: Vec<FFProbeStreamDuration>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FFProbeStreamDuration {
+    duration: Option<String>,
+    tags: Option<FFProbeDurationTags>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FFProbeDurationTags {
+    /// Durée par stream écrite par le muxer Matroska (`HH:MM:SS.nnnnnnnnn`)
+    #[serde(rename = "DURATION")]
+    duration: Option<String>,
+}
+
+/// Durées d'un fichier : conteneur puis chaque stream (en secondes)
+///
+/// # Errors
+///
+/// Retourne une erreur si ffprobe échoue ou si sa sortie est illisible.
+pub async fn probe_durations(
+    ffprobe_bin: &Path,
+    file: &Path,
+    env: &HashMap<String, String>,
+) -> Result<Vec<f64>> {
+    let output = tokio::process::Command::new(ffprobe_bin)
+        .envs(env)
+        .args([
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_entries",
+            "format=duration:stream=duration:stream_tags=DURATION",
+        ])
+        .arg(file)
+        .output()
+        .await
+        .context("Échec de l'exécution de ffprobe")?;
+    if !output.status.success() {
+        anyhow::bail!("ffprobe a échoué sur {}", file.display());
+    }
+    parse_durations(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_durations(json: &str) -> Result<Vec<f64>> {
+    let probe: FFProbeDurations =
+        serde_json::from_str(json).context("Échec du parsing de la sortie ffprobe")?;
+    let format = probe.format.duration.and_then(|d| d.parse::<f64>().ok());
+    let streams = probe.streams.into_iter().filter_map(|s| {
+        s.duration
+            .and_then(|d| d.parse::<f64>().ok())
+            .or_else(|| s.tags?.duration.as_deref().and_then(parse_timestamp))
+    });
+    Ok(format.into_iter().chain(streams).collect())
+}
+
+/// Parser un timestamp `HH:MM:SS.fraction`
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut parts = value.rsplitn(3, ':');
+    let secs: f64 = parts.next()?.parse().ok()?;
+    let mins: f64 = parts.next()?.parse().ok()?;
+    let hours: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + mins * 60.0 + secs)
+}
+
+/// Restreindre durée et nombre de frames à l'extrait demandé (progression, VMAF)
+pub fn apply_trim(info: &mut VideoInfo, trim: &TrimRange) {
+    if trim.is_full() {
+        return;
+    }
+    info.duration = trim.trimmed_duration(info.duration);
+    info.total_frames = info
+        .duration
+        .map(|d| estimate_frame_count(d.as_secs_f64(), info.fps));
+    tracing::info!(
+        "Découpe appliquée: durée {:?}, {:?} frames",
+        info.duration,
+        info.total_frames
+    );
+}
+
+/// Le conteneur de sortie accepte-t-il ce codec vidéo en copie directe ?
+///
+/// Le conteneur est déduit de l'extension. Matroska accepte tous les codecs ;
+/// une extension inconnue est laissée à l'appréciation de ffmpeg au muxage.
+#[must_use]
+pub fn container_supports_video_codec(output: &Path, codec: &str) -> bool {
+    let ext = output
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match ext.as_deref() {
+        Some("webm") => matches!(codec, "vp8" | "vp9" | "av1"),
+        Some("mp4" | "m4v") => matches!(
+            codec,
+            "h264" | "hevc" | "av1" | "vp9" | "mpeg4" | "mpeg2video" | "mjpeg"
+        ),
+        Some("mov") => matches!(
+            codec,
+            "h264" | "hevc" | "av1" | "vp9" | "mpeg4" | "mpeg2video" | "mjpeg" | "prores"
+        ),
+        _ => true,
+    }
+}
+
+/// Le conteneur de sortie accepte-t-il une pochette dans ce codec d'image ?
+///
+/// WebM n'a pas de pochette ; MP4/MOV n'acceptent que JPEG, PNG et BMP.
+#[must_use]
+pub fn container_supports_cover_art(output: &Path, codec: &str) -> bool {
+    let ext = output
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match ext.as_deref() {
+        Some("webm") => false,
+        Some("mp4" | "m4v" | "mov") => matches!(codec, "mjpeg" | "png" | "bmp"),
+        _ => true,
+    }
+}
+
+/// Traitement d'une piste de sous-titres au muxage, selon le conteneur de sortie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleSupport {
+    /// Copie directe
+    Copy,
+    /// Conversion nécessaire vers ce codec ffmpeg
+    Convert(&'static str),
+    /// Aucun format accepté par le conteneur (sous-titres image vers MP4 ou WebM)
+    Unsupported,
+}
+
+/// Sous-titres texte, convertibles d'un format à l'autre
+fn is_text_subtitle(codec: &str) -> bool {
+    matches!(
+        codec,
+        "subrip" | "srt" | "ass" | "ssa" | "webvtt" | "mov_text" | "text"
+    )
+}
+
+/// Le conteneur de sortie accepte-t-il ce codec de sous-titres en copie ?
+///
+/// Le conteneur est déduit de l'extension. MP4/MOV n'acceptent que mov_text et WebM
+/// que WebVTT ; Matroska accepte tout sauf mov_text, converti en SRT.
+#[must_use]
+pub fn subtitle_support(output: &Path, codec: &str) -> SubtitleSupport {
+    let ext = output
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+
+    let (native, target) = match ext.as_deref() {
+        Some("mp4" | "m4v" | "mov") => ("mov_text", "mov_text"),
+        Some("webm") => ("webvtt", "webvtt"),
+        _ if codec == "mov_text" => return SubtitleSupport::Convert("srt"),
+        _ => return SubtitleSupport::Copy,
+    };
+    if codec == native {
+        SubtitleSupport::Copy
+    } else if is_text_subtitle(codec) {
+        SubtitleSupport::Convert(target)
+    } else {
+        SubtitleSupport::Unsupported
+    }
 }
 
 /// Parser un frame rate (format "24000/1001" ou "24")
@@ -327,6 +1062,338 @@ fn parse_frame_rate(rate_str: &str) -> Option<f64> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_container_supports_video_codec() {
+        assert!(container_supports_video_codec(
+            Path::new("out.mkv"),
+            "mpeg2video"
+        ));
+        assert!(container_supports_video_codec(Path::new("out.MP4"), "hevc"));
+        assert!(!container_supports_video_codec(
+            Path::new("out.webm"),
+            "h264"
+        ));
+        assert!(!container_supports_video_codec(Path::new("out.mp4"), "vc1"));
+
+        assert!(container_supports_cover_art(Path::new("out.mkv"), "webp"));
+        assert!(container_supports_cover_art(Path::new("out.m4v"), "mjpeg"));
+        assert!(!container_supports_cover_art(Path::new("out.mp4"), "webp"));
+        assert!(!container_supports_cover_art(Path::new("out.webm"), "png"));
+    }
+
+    #[test]
+    fn test_subtitle_support() {
+        let mkv = Path::new("out.mkv");
+        let mp4 = Path::new("out.MP4");
+        let webm = Path::new("out.webm");
+        assert_eq!(
+            subtitle_support(mkv, "hdmv_pgs_subtitle"),
+            SubtitleSupport::Copy
+        );
+        assert_eq!(
+            subtitle_support(mkv, "mov_text"),
+            SubtitleSupport::Convert("srt")
+        );
+        assert_eq!(subtitle_support(mp4, "mov_text"), SubtitleSupport::Copy);
+        assert_eq!(
+            subtitle_support(mp4, "subrip"),
+            SubtitleSupport::Convert("mov_text")
+        );
+        assert_eq!(
+            subtitle_support(mp4, "dvd_subtitle"),
+            SubtitleSupport::Unsupported
+        );
+        assert_eq!(
+            subtitle_support(webm, "ass"),
+            SubtitleSupport::Convert("webvtt")
+        );
+        assert_eq!(
+            subtitle_support(webm, "hdmv_pgs_subtitle"),
+            SubtitleSupport::Unsupported
+        );
+    }
+
+    #[test]
+    fn test_parse_idet_output() {
+        let interlaced = "[Parsed_idet_0 @ 0x55] Repeated Fields: Neither:   300 Top:     0 Bottom:     0\n\
+            [Parsed_idet_0 @ 0x55] Single frame detection: TFF:   180 BFF:     0 Progressive:    60 Undetermined:    60\n\
+            [Parsed_idet_0 @ 0x55] Multi frame detection: TFF:   250 BFF:     0 Progressive:    30 Undetermined:    20\n";
+        assert_eq!(parse_idet_output(interlaced), Some(true));
+
+        let progressive = "[Parsed_idet_0 @ 0x55] Multi frame detection: TFF:     2 BFF:     1 Progressive:   290 Undetermined:     7";
+        assert_eq!(parse_idet_output(progressive), Some(false));
+        assert_eq!(parse_idet_output("Conversion failed!"), None);
+    }
+
+    #[test]
+    fn test_apply_deinterlace_doubles_rate() {
+        let mut info = VideoInfo {
+            duration: Some(Duration::from_secs(10)),
+            total_frames: Some(250),
+            width: 720,
+            height: 576,
+            fps: 25.0,
+            is_interlaced: true,
+            deinterlace_filter: None,
+            crop_filter: None,
+            is_vfr: false,
+            vfr_mode: None,
+            color_transfer: None,
+            tonemap_filter: None,
+            video_codec: "mpeg2video".to_string(),
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            attached_pics: Vec::new(),
+            chapters: Vec::new(),
+        };
+        let config = DeinterlaceConfig {
+            double_rate: true,
+            ..DeinterlaceConfig::default()
+        };
+        apply_deinterlace(&mut info, config);
+        assert_eq!(
+            info.deinterlace_filter.as_deref(),
+            Some("yadif=mode=send_field")
+        );
+        assert_eq!(info.total_frames, Some(500));
+        assert!((info.fps - 50.0).abs() < f64::EPSILON);
+
+        info.is_interlaced = false;
+        apply_deinterlace(&mut info, DeinterlaceConfig::default());
+        assert_eq!(info.deinterlace_filter, None);
+    }
+
+    #[test]
+    fn test_apply_even_crop_joins_decode_filter() {
+        let mut info = VideoInfo {
+            duration: Some(Duration::from_secs(10)),
+            total_frames: Some(250),
+            width: 1919,
+            height: 817,
+            fps: 25.0,
+            is_interlaced: true,
+            deinterlace_filter: Some("yadif".to_string()),
+            crop_filter: None,
+            is_vfr: false,
+            vfr_mode: None,
+            color_transfer: None,
+            tonemap_filter: None,
+            video_codec: "h264".to_string(),
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            attached_pics: Vec::new(),
+            chapters: Vec::new(),
+        };
+        apply_even_crop(&mut info, false);
+        assert_eq!((info.width, info.height), (1919, 817));
+        assert_eq!(info.decode_filter().as_deref(), Some("yadif"));
+
+        apply_even_crop(&mut info, true);
+        assert_eq!((info.width, info.height), (1918, 816));
+        assert_eq!(
+            info.decode_filter().as_deref(),
+            Some("yadif,crop=trunc(iw/2)*2:trunc(ih/2)*2")
+        );
+        assert_eq!(info.reference_filter(), info.decode_filter());
+
+        // Dimensions déjà paires : pas de filtre
+        apply_even_crop(&mut info, true);
+        assert_eq!(info.crop_filter, None);
+    }
+
+    #[test]
+    fn test_apply_tonemap_only_on_hdr() {
+        let mut info = VideoInfo {
+            crop_filter: Some(EVEN_CROP_FILTER.to_string()),
+            color_transfer: Some("bt709".to_string()),
+            ..VideoInfo::default()
+        };
+        let config = TonemapConfig {
+            enabled: true,
+            ..TonemapConfig::default()
+        };
+        apply_tonemap(&mut info, config);
+        assert_eq!(info.tonemap_filter, None);
+        assert!(info.output_color_args(EncoderType::SvtAv1).is_empty());
+
+        info.color_transfer = Some("smpte2084".to_string());
+        assert!(info.is_hdr());
+        apply_tonemap(&mut info, config);
+        let filter = info.decode_filter().unwrap();
+        assert!(filter.starts_with(&format!("{EVEN_CROP_FILTER},zscale=t=linear")));
+        assert_eq!(info.reference_filter().as_deref(), Some(filter.as_str()));
+        assert_eq!(
+            info.output_color_args(EncoderType::SvtAv1),
+            bt709_color_args(EncoderType::SvtAv1)
+        );
+
+        apply_tonemap(&mut info, TonemapConfig::default());
+        assert_eq!(info.tonemap_filter, None);
+    }
+
+    #[test]
+    fn test_vfr_detection_and_reference_filter() {
+        // Vidéo de téléphone : timestamps au 1/30 s mais 29.87 fps en moyenne
+        assert!(is_variable_frame_rate(Some(30.0), Some(29.87)));
+        assert!(!is_variable_frame_rate(
+            parse_frame_rate("24000/1001"),
+            parse_frame_rate("24000/1001")
+        ));
+        assert!(!is_variable_frame_rate(Some(25.0), None));
+
+        let mut info = VideoInfo {
+            duration: Some(Duration::from_secs(10)),
+            total_frames: Some(299),
+            width: 1920,
+            height: 1080,
+            fps: 29.87,
+            is_interlaced: false,
+            deinterlace_filter: None,
+            crop_filter: None,
+            is_vfr: true,
+            vfr_mode: None,
+            color_transfer: None,
+            tonemap_filter: None,
+            video_codec: "h264".to_string(),
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            attached_pics: Vec::new(),
+            chapters: Vec::new(),
+        };
+        apply_vfr(&mut info, VfrMode::Cfr);
+        assert_eq!(info.decode_rate_args(), ["-vsync", "cfr", "-r", "29.870"]);
+        assert_eq!(info.reference_filter().as_deref(), Some("fps=29.870"));
+
+        info.deinterlace_filter = Some("yadif=mode=send_frame".to_string());
+        apply_vfr(&mut info, VfrMode::Passthrough);
+        assert_eq!(
+            info.reference_filter().as_deref(),
+            Some("yadif=mode=send_frame")
+        );
+
+        info.is_vfr = false;
+        apply_vfr(&mut info, VfrMode::Cfr);
+        assert!(info.decode_rate_args().is_empty());
+    }
+
+    #[test]
+    fn test_parse_output_durations() {
+        let json = r#"{
+            "streams": [
+                {"tags": {"DURATION": "00:01:00.500000000"}},
+                {"duration": "59.980000"},
+                {}
+            ],
+            "format": {"duration": "60.500000"}
+        }"#;
+        assert_eq!(parse_durations(json).unwrap(), [60.5, 60.5, 59.98]);
+        assert_eq!(parse_timestamp("01:02:03.5"), Some(3723.5));
+        assert_eq!(parse_timestamp("bogus"), None);
+    }
+
+    #[test]
+    fn test_combine_concat_parts() {
+        let episode = |frames: u64, secs: u64| VideoInfo {
+            duration: Some(Duration::from_secs(secs)),
+            total_frames: Some(frames),
+            width: 1920,
+            height: 1080,
+            fps: 25.0,
+            is_interlaced: false,
+            deinterlace_filter: None,
+            crop_filter: None,
+            is_vfr: false,
+            vfr_mode: None,
+            color_transfer: None,
+            tonemap_filter: None,
+            video_codec: "h264".to_string(),
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            attached_pics: Vec::new(),
+            chapters: vec![ChapterInfo {
+                start: 0.0,
+                end: 5.0,
+                title: None,
+            }],
+        };
+
+        let combined = combine_concat_parts(vec![
+            (PathBuf::from("ep1a.mkv"), episode(250, 10)),
+            (PathBuf::from("ep1b.mkv"), episode(500, 20)),
+        ])
+        .unwrap();
+        assert_eq!(combined.duration, Some(Duration::from_secs(30)));
+        assert_eq!(combined.total_frames, Some(750));
+        assert!(!combined.is_vfr);
+        assert_eq!(combined.chapters.len(), 2);
+        assert!((combined.chapters[1].start - 10.0).abs() < f64::EPSILON);
+
+        // Framerates différents : framerate moyen de l'ensemble
+        let mut ntsc = episode(300, 10);
+        ntsc.fps = 30.0;
+        let combined = combine_concat_parts(vec![
+            (PathBuf::from("a.mkv"), episode(250, 10)),
+            (PathBuf::from("b.mkv"), ntsc),
+        ])
+        .unwrap();
+        assert!(combined.is_vfr);
+        assert!((combined.fps - 27.5).abs() < 1e-9);
+
+        let mut hevc = episode(250, 10);
+        hevc.video_codec = "hevc".to_string();
+        let err = combine_concat_parts(vec![
+            (PathBuf::from("a.mkv"), episode(250, 10)),
+            (PathBuf::from("b.mkv"), hevc),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("b.mkv: codec hevc"));
+
+        let mut sd = episode(250, 10);
+        sd.height = 576;
+        assert!(combine_concat_parts(vec![
+            (PathBuf::from("a.mkv"), episode(250, 10)),
+            (PathBuf::from("b.mkv"), sd),
+        ])
+        .is_err());
+        assert!(combine_concat_parts(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_degenerate_inputs_are_rejected() {
+        let source = |codec: &str, frames: Option<u64>, duration: Option<f64>| VideoInfo {
+            duration: duration.map(Duration::from_secs_f64),
+            total_frames: frames,
+            width: 1280,
+            height: 720,
+            fps: 25.0,
+            is_interlaced: false,
+            deinterlace_filter: None,
+            crop_filter: None,
+            is_vfr: false,
+            vfr_mode: None,
+            color_transfer: None,
+            tonemap_filter: None,
+            video_codec: codec.to_string(),
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            attached_pics: Vec::new(),
+            chapters: Vec::new(),
+        };
+
+        let err = ensure_encodable(&source("h264", Some(0), Some(10.0))).unwrap_err();
+        assert!(err.to_string().contains("aucune frame"));
+        // JPEG via le demuxer image2 (1 frame de 40 ms), PNG sans durée ni nombre de frames
+        let err = ensure_encodable(&source("mjpeg", Some(1), Some(0.04))).unwrap_err();
+        assert!(err.to_string().contains("image fixe (mjpeg)"));
+        assert!(ensure_encodable(&source("png", None, None)).is_err());
+        assert!(ensure_encodable(&source("h264", None, Some(0.0))).is_err());
+
+        // Vraies vidéos, y compris MJPEG et durée inconnue
+        assert!(ensure_encodable(&source("mjpeg", Some(750), Some(30.0))).is_ok());
+        assert!(ensure_encodable(&source("h264", Some(250), Some(10.0))).is_ok());
+        assert!(ensure_encodable(&source("hevc", None, None)).is_ok());
+    }
+
     #[test]
     fn test_parse_frame_rate() {
         assert_eq!(parse_frame_rate("24"), Some(24.0));
@@ -358,4 +1425,61 @@ mod tests {
         let estimated_decimal = estimate_frame_count(duration_decimal.as_secs_f64(), fps_25);
         assert_eq!(estimated_decimal, 3013);
     }
+
+    #[tokio::test]
+    async fn test_probe_videos_keeps_order_and_errors() {
+        let inputs: Vec<PathBuf> = (0..5)
+            .map(|i| PathBuf::from(format!("/nonexistent/film{i}.mkv")))
+            .collect();
+        let results = probe_videos(
+            Path::new("/nonexistent/ffprobe"),
+            Path::new("/nonexistent/ffmpeg"),
+            inputs.clone(),
+            2,
+        )
+        .await;
+        assert_eq!(results.len(), 5);
+        for ((path, result), input) in results.iter().zip(&inputs) {
+            assert_eq!(path, input);
+            assert!(result.is_err());
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_idet_only_when_requested() {
+        use crate::encoder::write_fake_bin;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        // Sans field_order : seul idet peut détecter l'entrelacement
+        let ffprobe = write_fake_bin(
+            dir,
+            "ffprobe",
+            r#"echo '{"format":{"duration":"60"},"streams":[{"index":0,"codec_type":"video","codec_name":"mpeg2video","width":720,"height":576,"r_frame_rate":"25/1","avg_frame_rate":"25/1","nb_frames":"1500"}]}'"#,
+        );
+        let ffmpeg = write_fake_bin(
+            dir,
+            "ffmpeg",
+            &format!(
+                "touch '{}'\necho 'Multi frame detection: TFF: 280 BFF: 0 Progressive: 20 Undetermined: 0' >&2",
+                dir.join("idet-ran").display()
+            ),
+        );
+        let input = dir.join("film.mpg");
+        std::fs::write(&input, b"").unwrap();
+        let env = HashMap::new();
+
+        let info = probe_video(&ffprobe, &ffmpeg, &input, None, false, &env)
+            .await
+            .unwrap();
+        assert!(!info.is_interlaced);
+        assert!(!dir.join("idet-ran").exists());
+
+        let info = probe_video(&ffprobe, &ffmpeg, &input, None, true, &env)
+            .await
+            .unwrap();
+        assert!(info.is_interlaced);
+        assert!(dir.join("idet-ran").exists());
+    }
 }
@@ -1,7 +1,32 @@
+pub mod audio_prefetch;
+pub mod capabilities;
+pub mod error;
 pub mod ffmpeg;
 pub mod parser;
 pub mod pipeline;
+pub mod process;
+pub mod split;
+pub mod thumbnail;
+pub mod workspace;
 
+pub use audio_prefetch::*;
+pub use capabilities::*;
+pub use error::*;
 pub use ffmpeg::*;
 pub use parser::*;
 pub use pipeline::*;
+pub use process::*;
+pub use split::*;
+pub use thumbnail::*;
+pub use workspace::*;
+
+/// Écrire un faux exécutable (script shell) dans `dir`, à passer au pipeline en test
+#[cfg(all(test, unix))]
+pub(crate) fn write_fake_bin(dir: &std::path::Path, name: &str, body: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
@@ -1,7 +1,11 @@
-use encodetalker_common::EncodingStats;
+use encodetalker_common::{EncodingPhase, EncodingStats};
 use regex::Regex;
+use std::io::{BufRead, BufReader, Read};
 use std::time::Duration;
 
+/// Longueur maximale (octets) d'une ligne de stderr conservée
+pub const MAX_STDERR_LINE: usize = 4096;
+
 static FRAME_REGEX: std::sync::LazyLock<Regex> =
     std::sync::LazyLock::new(|| Regex::new(r"frame=\s*(\d+)").unwrap());
 static FPS_REGEX: std::sync::LazyLock<Regex> =
@@ -31,12 +35,14 @@ impl StatsParser {
         let stats = EncodingStats {
             total_frames,
             total_duration,
+            phase: Some(EncodingPhase::Video),
             ..Default::default()
         };
         Self { stats }
     }
 
     /// Parser une ligne de sortie ffmpeg
+    #[allow(clippy::collapsible_match)] // Une branche par clé de -progress
     pub fn parse_line(&mut self, line: &str) {
         // Format -progress : key=value sur des lignes séparées
         if let Some((key, value)) = line.split_once('=') {
@@ -176,10 +182,64 @@ impl StatsParser {
         self.stats.frame = 0;
         self.stats.fps = 0.0;
         self.stats.bitrate = 0.0;
-        self.stats.progress_percent = 0.0;
-        self.stats.eta = None;
         self.stats.current_pass = pass;
+        self.stats.update();
+    }
+}
+
+/// Lire le stderr d'un processus ligne par ligne, `\r` comme `\n` terminant une ligne
+///
+/// Chaque ligne non vide est passée sans espaces de bord à `on_line`, qui retourne false
+/// pour arrêter la lecture. Les séquences UTF-8 invalides sont remplacées plutôt que de
+/// faire perdre la ligne. Au-delà de [`MAX_STDERR_LINE`] octets, la suite d'une ligne est
+/// ignorée jusqu'à la prochaine fin de ligne : une sortie binaire ne peut pas faire
+/// grossir la mémoire.
+///
+/// # Errors
+///
+/// Retourne l'erreur de lecture du flux.
+pub fn read_stderr_lines<R: Read>(
+    reader: R,
+    mut on_line: impl FnMut(&str) -> bool,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::with_capacity(256);
+    let mut truncated = false;
+    let mut flush = |line: &mut Vec<u8>, truncated: &mut bool| {
+        if std::mem::take(truncated) {
+            tracing::debug!("Ligne stderr tronquée à {MAX_STDERR_LINE} octets");
+        }
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim();
+        let keep_reading = text.is_empty() || on_line(text);
+        line.clear();
+        keep_reading
+    };
+
+    loop {
+        let chunk = match reader.fill_buf() {
+            Ok([]) => break,
+            Ok(chunk) => chunk,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let len = chunk.len();
+        for &byte in chunk {
+            if byte == b'\r' || byte == b'\n' {
+                if !flush(&mut line, &mut truncated) {
+                    return Ok(());
+                }
+            } else if line.len() < MAX_STDERR_LINE {
+                line.push(byte);
+            } else {
+                truncated = true;
+            }
+        }
+        reader.consume(len);
     }
+    // Dernière ligne sans fin de ligne
+    flush(&mut line, &mut truncated);
+    Ok(())
 }
 
 #[cfg(test)]
@@ -201,6 +261,42 @@ mod tests {
         assert_eq!(stats.frame, 123);
         assert_eq!(stats.fps, 25.3);
         assert_eq!(stats.bitrate, 1234.5);
+        assert!((stats.progress_percent - 12.3).abs() < 1e-9);
+        assert_eq!(stats.remaining_frames, Some(877));
+        assert!(stats.eta.is_some());
         // Note: out_time parsing nécessiterait un regex différent pour le format microseconde
     }
+
+    #[test]
+    fn test_read_stderr_lines_survives_malformed_output() {
+        let mut input = b"Encoding frame   10 1000.00 kbps 20.00 fps\r".to_vec();
+        // UTF-8 invalide au milieu d'une ligne de progression
+        input.extend_from_slice(b"\xff\xfe Encoding frame   20 1000.00 kbps 21.00 fps\r\n");
+        // Sortie binaire sans fin de ligne
+        input.extend(std::iter::repeat_n(b'x', 100_000));
+        input.extend_from_slice(b"\nEncoding frame   30 1000.00 kbps 22.00 fps");
+
+        let mut parser = StatsParser::new(Some(100), None);
+        let mut lines = Vec::new();
+        read_stderr_lines(input.as_slice(), |line| {
+            parser.parse_encoder_line(line);
+            lines.push(line.to_string());
+            true
+        })
+        .unwrap();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with('\u{fffd}'));
+        assert_eq!(lines[2].len(), MAX_STDERR_LINE);
+        assert_eq!(parser.get_stats().frame, 30);
+
+        // Arrêt demandé par le callback
+        let mut count = 0;
+        read_stderr_lines(input.as_slice(), |_| {
+            count += 1;
+            count < 2
+        })
+        .unwrap();
+        assert_eq!(count, 2);
+    }
 }
@@ -1,11 +1,28 @@
-use super::{probe_video, StatsParser, VideoInfo};
+use super::{
+    apply_deinterlace, apply_even_crop, apply_tonemap, apply_trim, apply_vfr,
+    container_supports_cover_art, container_supports_video_codec, ensure_dir_writable, exit_code,
+    extract_poster, isolate_process_group, job_temp_dir, move_atomically, plan_segments,
+    probe_durations, probe_video, read_stderr_lines, segment_output_path, subtitle_support,
+    AudioPrefetch, CapabilitiesCache, EncodeError, ProcessRegistry, StatsParser, SubtitleSupport,
+    TempWorkspace, VideoInfo, DEFAULT_PRECISE_COUNT_TIMEOUT,
+};
 use anyhow::{Context, Result};
-use encodetalker_common::{AudioMode, EncoderType, EncodingJob, EncodingStats};
-use std::io::{BufRead, BufReader};
+use encodetalker_common::{
+    benchmark_presets, benchmark_sample, source_audio_input_args, source_input_args, AudioMode,
+    BenchmarkReport, CoverArtMode, DeinterlaceConfig, DeinterlaceMode, EncoderBinaryInfo,
+    EncoderParams, EncoderType, EncodingConfig, EncodingJob, EncodingPhase, EncodingStats, JobKind,
+    PresetBenchmark, SplitMode, StreamSummary, SubtitlePolicy, VerifyReport, VfrMode, VideoMode,
+    DEFAULT_VAAPI_DEVICE, MAX_VERIFY_ERRORS, TONEMAP_FILTERS, VAAPI_UPLOAD_FILTER,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
 use tracing::info;
 
 /// Obtenir le nombre de threads disponibles, en u32 pour les encodeurs
@@ -17,6 +34,20 @@ fn get_available_threads() -> u32 {
     std::thread::available_parallelism().map_or(1, |n| n.get().min(u32::MAX as usize) as u32)
 }
 
+/// Threads des ffmpeg audio et de muxage : le plafond configuré, réduit à la part du job
+/// s'il en a une (None = pas de limite, choix de ffmpeg)
+fn tail_threads(limit: u32, job_threads: Option<u32>) -> Option<u32> {
+    if limit == 0 {
+        return None;
+    }
+    Some(job_threads.map_or(limit, |threads| limit.min(threads).max(1)))
+}
+
+/// Option `-threads` de ffmpeg (vide sans limite)
+fn thread_args(threads: Option<u32>) -> Vec<String> {
+    threads.map_or_else(Vec::new, |n| vec!["-threads".to_string(), n.to_string()])
+}
+
 // ============================================================================
 // Fonctions helper pour refactoring des fonctions too_many_lines
 // ============================================================================
@@ -30,12 +61,11 @@ where
     F: Fn(&str) + Send + 'static,
 {
     std::thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines().map_while(Result::ok) {
-            let line = line.trim();
-            if !line.is_empty() {
-                parse_fn(line);
-            }
+        if let Err(e) = read_stderr_lines(stderr, |line| {
+            parse_fn(line);
+            true
+        }) {
+            tracing::error!("Erreur lecture stderr: {e}");
         }
         tracing::debug!("Lecture stderr terminée");
     })
@@ -62,14 +92,14 @@ async fn wait_for_processes_with_cancellation(
             if let Ok(mut ffmpeg) = ffmpeg_arc.lock() {
                 let _ = ffmpeg.kill();
             }
-            anyhow::bail!("Encodage annulé");
+            return Err(EncodeError::Cancelled.into());
         }
         result = tokio::task::spawn_blocking(move || {
             tracing::debug!("Attente de la fin de l'encodeur...");
             let encoder_status = encoder_clone.lock().unwrap().wait()
                 .context("Échec d'attente de l'encodeur")?;
             if !encoder_status.success() {
-                anyhow::bail!("L'encodeur a échoué avec le code {:?}", encoder_status.code());
+                return Err(EncodeError::EncoderFailed { code: exit_code(encoder_status) }.into());
             }
             tracing::debug!("Encodeur terminé avec succès");
 
@@ -77,7 +107,7 @@ async fn wait_for_processes_with_cancellation(
             let ffmpeg_status = ffmpeg_clone.lock().unwrap().wait()
                 .context("Échec d'attente de ffmpeg")?;
             if !ffmpeg_status.success() {
-                anyhow::bail!("ffmpeg a échoué avec le code {:?}", ffmpeg_status.code());
+                return Err(EncodeError::DecoderFailed { code: exit_code(ffmpeg_status) }.into());
             }
             tracing::debug!("ffmpeg terminé avec succès");
 
@@ -104,7 +134,7 @@ async fn wait_for_process_with_cancellation(
             if let Ok(mut child) = child_arc.lock() {
                 let _ = child.kill();
             }
-            anyhow::bail!("Opération annulée");
+            return Err(EncodeError::Cancelled.into());
         }
         result = tokio::task::spawn_blocking(move || {
             let status = child_clone.lock().unwrap().wait()
@@ -121,24 +151,62 @@ async fn wait_for_process_with_cancellation(
     Ok(())
 }
 
+/// Attendre la vidéo en surveillant l'audio encodé en parallèle
+///
+/// La vidéo s'arrête sur `abort_tx`, par lequel l'annulation du job (`cancel_rx`) lui est
+/// relayée. Un échec de l'audio arrête aussitôt la vidéo et est retourné sans attendre
+/// la fin de l'encodage ; un audio réussi est gardé pour la suite (None = pas fini).
+async fn video_with_audio<T, U>(
+    video: impl std::future::Future<Output = Result<T, EncodeError>>,
+    audio: std::pin::Pin<&mut impl std::future::Future<Output = Result<U, EncodeError>>>,
+    cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    abort_tx: &mpsc::UnboundedSender<()>,
+) -> Result<(T, Option<U>), EncodeError> {
+    let mut video = std::pin::pin!(video);
+    let mut audio = audio;
+    let mut audio_done = None;
+    loop {
+        tokio::select! {
+            result = &mut video => return result.map(|video| (video, audio_done)),
+            Some(()) = cancel_rx.recv() => {
+                let _ = abort_tx.send(());
+            }
+            result = &mut audio, if audio_done.is_none() => match result {
+                Ok(audio) => audio_done = Some(audio),
+                Err(e) => {
+                    // Processus vidéo tués avant de rendre la main
+                    let _ = abort_tx.send(());
+                    let _ = video.await;
+                    return Err(e);
+                }
+            },
+        }
+    }
+}
+
 /// Construire la commande ffmpeg pour décoder la vidéo
 fn build_ffmpeg_decode_command(
     ffmpeg_bin: &Path,
     input: &Path,
     pix_fmt: &str,
-    is_interlaced: bool,
+    deinterlace: Option<&str>,
+    rate_args: &[String],
+    input_args: &[String],
 ) -> std::process::Command {
     let mut cmd = std::process::Command::new(ffmpeg_bin);
+    isolate_process_group(&mut cmd);
     cmd.arg("-nostats")
         .arg("-loglevel")
         .arg("error")
+        .args(input_args)
         .arg("-i")
         .arg(input);
 
-    if is_interlaced {
-        info!("Application du filtre yadif (désentrelacement)");
-        cmd.arg("-vf").arg("yadif");
+    if let Some(filter) = deinterlace {
+        info!("Application du filtre {filter} (désentrelacement)");
+        cmd.arg("-vf").arg(filter);
     }
+    cmd.args(rate_args);
 
     cmd.arg("-f")
         .arg("yuv4mpegpipe")
@@ -154,31 +222,445 @@ fn build_ffmpeg_decode_command(
     cmd
 }
 
-/// Construire la commande ffmpeg pour calculer le VMAF
+/// Construire la commande ffmpeg d'un encodage matériel : décodage et encodage AV1 dans
+/// le même processus, progression `-progress` sur stderr
+fn build_hardware_encode_command(
+    ffmpeg_bin: &Path,
+    job: &EncodingJob,
+    video_info: &VideoInfo,
+    vaapi_device: &Path,
+    output: &Path,
+) -> std::process::Command {
+    let mut cmd = std::process::Command::new(ffmpeg_bin);
+    isolate_process_group(&mut cmd);
+    cmd.envs(&job.config.env);
+    cmd.arg("-nostats")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-progress")
+        .arg("pipe:2");
+    if job.config.encoder == EncoderType::Vaapi {
+        cmd.arg("-vaapi_device").arg(vaapi_device);
+    }
+    cmd.args(source_input_args(&job.input_path, &job.config))
+        .arg("-i")
+        .arg(&job.input_path)
+        .args(["-an", "-sn", "-dn"]);
+
+    // Filtres du décodage (désentrelacement, recadrage), puis envoi vers le GPU (VA-API)
+    let filters: Vec<String> = video_info
+        .decode_filter()
+        .into_iter()
+        .chain((job.config.encoder == EncoderType::Vaapi).then(|| VAAPI_UPLOAD_FILTER.to_string()))
+        .collect();
+    if !filters.is_empty() {
+        cmd.arg("-vf").arg(filters.join(","));
+    }
+    cmd.args(video_info.decode_rate_args())
+        .args(job.config.hardware_video_args(Some(video_info.fps)))
+        .args(video_info.output_color_args(job.config.encoder))
+        .arg("-f")
+        .arg("ivf")
+        .arg("-y")
+        .arg(output)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    cmd
+}
+
+/// Piste de sous-titres retenue au muxage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MuxedSubtitle {
+    /// Index relatif parmi les sous-titres de la source
+    stream: usize,
+    /// Codec de sortie : `copy` ou format de conversion
+    codec: &'static str,
+}
+
+/// Pistes de sous-titres à muxer, d'après la sélection du job et ce qu'accepte le
+/// conteneur de sortie (`incompatible_subtitles`)
+///
+/// # Errors
+///
+/// Retourne [`EncodeError::IncompatibleSubtitles`] si une piste retenue n'est pas
+/// copiable et que la politique est `fail`.
+fn plan_subtitles(
+    job: &EncodingJob,
+    video_info: &VideoInfo,
+) -> Result<Vec<MuxedSubtitle>, EncodeError> {
+    let streams = &video_info.subtitle_streams;
+    let languages: Vec<Option<String>> = streams.iter().map(|s| s.language.clone()).collect();
+    let selected = job
+        .config
+        .resolve_subtitle_streams(&languages)
+        .unwrap_or_else(|| (0..streams.len()).collect());
+    let container = job
+        .output_path
+        .extension()
+        .map_or_else(String::new, |e| e.to_string_lossy().into_owned());
+    let policy = job.config.incompatible_subtitles;
+
+    let mut planned = Vec::new();
+    for stream in selected {
+        // Index absent de la source : laissé à ffmpeg, qui le signalera au muxage
+        let Some(codec) = streams.get(stream).map(|s| s.codec.as_str()) else {
+            planned.push(MuxedSubtitle {
+                stream,
+                codec: "copy",
+            });
+            continue;
+        };
+        let codec = match (subtitle_support(&job.output_path, codec), policy) {
+            (SubtitleSupport::Copy, _) => "copy",
+            (SubtitleSupport::Convert(target), SubtitlePolicy::Convert) => {
+                info!("Sous-titres {stream} ({codec}) convertis en {target} pour le conteneur {container}");
+                target
+            }
+            (_, SubtitlePolicy::Fail) => {
+                return Err(EncodeError::IncompatibleSubtitles {
+                    codec: codec.to_string(),
+                    container,
+                })
+            }
+            _ => {
+                tracing::warn!("Sous-titres {stream} ({codec}) retirés : non supportés par le conteneur {container}");
+                continue;
+            }
+        };
+        planned.push(MuxedSubtitle { stream, codec });
+    }
+    Ok(planned)
+}
+
+/// Pochette muxée comme stream vidéo `attached_pic` (pièce jointe en Matroska)
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MuxedCover {
+    /// Pochette de la source, copiée
+    Source {
+        /// Index absolu du stream dans la source
+        stream: usize,
+        codec: String,
+        filename: Option<String>,
+    },
+    /// Frame extraite de la source (PNG)
+    Poster(PathBuf),
+}
+
+impl MuxedCover {
+    /// Nom de fichier et type MIME de la pochette, `rank` étant sa position parmi les
+    /// pochettes de la sortie
+    fn attachment(&self, rank: usize) -> (String, String) {
+        let codec = match self {
+            Self::Source { codec, .. } => codec.as_str(),
+            Self::Poster(_) => "png",
+        };
+        let (extension, mimetype) = match codec {
+            "mjpeg" => ("jpg", "image/jpeg".to_string()),
+            other => (other, format!("image/{other}")),
+        };
+        let filename = match self {
+            Self::Source {
+                filename: Some(filename),
+                ..
+            } => filename.clone(),
+            _ if rank == 0 => format!("cover.{extension}"),
+            _ => format!("cover_{}.{extension}", rank + 1),
+        };
+        (filename, mimetype)
+    }
+}
+
+/// Pochettes de la source à copier selon `cover_art`, et s'il faut extraire une frame
+/// (mode `poster`, ou `auto` sans pochette copiable)
+///
+/// Les pochettes que le conteneur de sortie n'accepte pas sont retirées.
+fn plan_covers(job: &EncodingJob, video_info: &VideoInfo) -> (Vec<MuxedCover>, bool) {
+    let mode = job.config.cover_art.mode;
+    let mut covers = Vec::new();
+    if matches!(mode, CoverArtMode::Copy | CoverArtMode::Auto) {
+        for pic in &video_info.attached_pics {
+            if container_supports_cover_art(&job.output_path, &pic.codec) {
+                covers.push(MuxedCover::Source {
+                    stream: pic.index,
+                    codec: pic.codec.clone(),
+                    filename: pic.filename.clone(),
+                });
+            } else {
+                tracing::warn!(
+                    "Pochette {} ({}) retirée : non supportée par le conteneur de sortie",
+                    pic.index,
+                    pic.codec
+                );
+            }
+        }
+    }
+    let poster = match mode {
+        CoverArtMode::Off | CoverArtMode::Copy => false,
+        CoverArtMode::Poster => true,
+        CoverArtMode::Auto => covers.is_empty(),
+    } && container_supports_cover_art(&job.output_path, "png");
+    (covers, poster)
+}
+
+/// Construire la commande ffmpeg du muxage final vers `output` : vidéo et audio copiés
+/// (None = vidéo seule), sous-titres de la source copiés ou convertis, pochettes
+#[allow(clippy::too_many_arguments)] // Une entrée par piste muxée, plus la destination
+fn build_mux_command(
+    ffmpeg_bin: &Path,
+    job: &EncodingJob,
+    video_path: &Path,
+    audio_path: Option<&Path>,
+    subtitles: &[MuxedSubtitle],
+    covers: &[MuxedCover],
+    threads: Option<u32>,
+    output: &Path,
+) -> Command {
+    let mut cmd = Command::new(ffmpeg_bin);
+    // Tué si la tâche est interrompue (arrêt du daemon)
+    cmd.kill_on_drop(true);
+    cmd.envs(&job.config.env);
+    // Les inputs lus depuis la source reçoivent les mêmes options (découpe comprise) que la vidéo encodée
+    let input_args = source_input_args(&job.input_path, &job.config);
+    let has_subtitles = !subtitles.is_empty();
+
+    // Étape 1: Ajouter TOUS les inputs d'abord
+    cmd.arg("-y"); // Écraser sans demander
+    if video_path == job.input_path {
+        // Vidéo copiée : la découpe se cale sur les keyframes de la source
+        cmd.args(&input_args);
+    }
+    cmd.arg("-i").arg(video_path); // Input 0: Vidéo AV1 (ou source en copie)
+    if let Some(audio_path) = audio_path {
+        cmd.arg("-i").arg(audio_path); // Input 1: Audio
+    }
+
+    // Ajouter l'input source pour les sous-titres si nécessaire (après l'audio s'il y en a)
+    let subtitle_input = if audio_path.is_some() { 2 } else { 1 };
+    if has_subtitles {
+        cmd.args(&input_args).arg("-i").arg(&job.input_path);
+    }
+
+    // Pochettes : la source sans découpe (une pochette n'a pas de durée), puis les frames
+    // extraites, chacune dans son input
+    let mut next_input = subtitle_input + usize::from(has_subtitles);
+    let mut source_cover_input = None;
+    let mut cover_maps = Vec::new();
+    for cover in covers {
+        match cover {
+            MuxedCover::Source { stream, .. } => {
+                let input = *source_cover_input.get_or_insert_with(|| {
+                    cmd.args(source_input_args(
+                        &job.input_path,
+                        &EncodingConfig::default(),
+                    ))
+                    .arg("-i")
+                    .arg(&job.input_path);
+                    next_input += 1;
+                    next_input - 1
+                });
+                cover_maps.push(format!("{input}:{stream}"));
+            }
+            MuxedCover::Poster(path) => {
+                cmd.arg("-i").arg(path);
+                cover_maps.push(format!("{next_input}:v:0"));
+                next_input += 1;
+            }
+        }
+    }
+
+    // Étape 2: Ajouter TOUS les -map ensuite
+    cmd.arg("-map").arg("0:v:0"); // Vidéo du premier input
+    if audio_path.is_some() {
+        cmd.arg("-map").arg("1:a"); // Pistes audio retenues, toutes dans le deuxième input
+    }
+
+    for subtitle in subtitles {
+        cmd.arg("-map")
+            .arg(format!("{subtitle_input}:s:{}", subtitle.stream));
+    }
+    for map in &cover_maps {
+        cmd.arg("-map").arg(map);
+    }
+
+    // Étape 3: Options de codec (copie sans réencodage, sauf sous-titres à convertir)
+    cmd.arg("-c:v").arg("copy");
+    if audio_path.is_some() {
+        cmd.arg("-c:a").arg("copy");
+    }
+    for (i, subtitle) in subtitles.iter().enumerate() {
+        cmd.arg(format!("-c:s:{i}")).arg(subtitle.codec);
+    }
+    // Pochettes après la vidéo principale (v:0), copiées comme elle
+    for (rank, cover) in covers.iter().enumerate() {
+        let stream = rank + 1;
+        let (filename, mimetype) = cover.attachment(rank);
+        cmd.arg(format!("-disposition:v:{stream}"))
+            .arg("attached_pic")
+            .arg(format!("-metadata:s:v:{stream}"))
+            .arg(format!("filename={filename}"))
+            .arg(format!("-metadata:s:v:{stream}"))
+            .arg(format!("mimetype={mimetype}"));
+    }
+
+    cmd.args(thread_args(threads));
+
+    // Étape 4: Output (le conteneur suit l'extension de la sortie finale)
+    cmd.arg(output);
+    cmd
+}
+
+/// Écart de durée (secondes) entre sortie et source au-delà duquel une dérive est signalée
+const SYNC_DRIFT_WARN_SECS: f64 = 0.5;
+
+/// Écart maximal (secondes) entre la durée d'une sortie vérifiée et la durée attendue
+const OUTPUT_DURATION_TOLERANCE_SECS: f64 = 1.0;
+
+/// Vérifier la durée mesurée d'une sortie (la plus longue, conteneur ou stream)
+fn check_output_duration(expected: Option<f64>, durations: &[f64]) -> Result<(), String> {
+    let Some(measured) = durations.iter().copied().reduce(f64::max) else {
+        return Err("durée illisible".to_string());
+    };
+    if measured <= 0.0 {
+        return Err("durée nulle".to_string());
+    }
+    match expected {
+        Some(expected) if (measured - expected).abs() > OUTPUT_DURATION_TOLERANCE_SECS => Err(
+            format!("durée {measured:.2}s au lieu de {expected:.2}s attendues"),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Plus grand écart entre la durée attendue et les durées mesurées
+fn max_drift(expected: f64, durations: &[f64]) -> f64 {
+    durations
+        .iter()
+        .map(|d| (d - expected).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Streams d'un fichier (vidéo principale, audio, sous-titres)
+fn verify_stream_summaries(video_info: &VideoInfo) -> Vec<StreamSummary> {
+    let summary = |kind: &str, codec: &str, language: Option<&String>| StreamSummary {
+        kind: kind.to_string(),
+        codec: codec.to_string(),
+        language: language.cloned(),
+    };
+    std::iter::once(summary("video", &video_info.video_codec, None))
+        .chain(
+            video_info
+                .audio_streams
+                .iter()
+                .map(|s| summary("audio", &s.codec, s.language.as_ref())),
+        )
+        .chain(
+            video_info
+                .subtitle_streams
+                .iter()
+                .map(|s| summary("subtitle", &s.codec, s.language.as_ref())),
+        )
+        .collect()
+}
+
+/// Extension du fichier audio intermédiaire
+fn audio_extension(mode: &AudioMode) -> &'static str {
+    match mode {
+        AudioMode::Opus { .. } => "opus",
+        _ => "mka",
+    }
+}
+
+/// Options `-map` des pistes audio de la source (None = toutes)
+fn audio_map_args(streams: Option<&[usize]>) -> Vec<String> {
+    match streams {
+        Some(streams) => streams
+            .iter()
+            .flat_map(|index| ["-map".to_string(), format!("0:a:{index}")])
+            .collect(),
+        None => vec!["-map".to_string(), "0:a".to_string()],
+    }
+}
+
+/// Chemin du log VMAF par frame associé à une sortie (`<stem>_vmaf.json`)
+#[must_use]
+pub fn vmaf_log_path(output: &Path) -> PathBuf {
+    let mut name = output
+        .file_stem()
+        .map_or_else(|| OsString::from("output"), OsString::from);
+    name.push("_vmaf.json");
+    output.with_file_name(name)
+}
+
+/// Option d'encodeur de la forme `--flag=<chemin>`, sans passer par une chaîne UTF-8
+fn path_option(flag: &str, path: &Path) -> OsString {
+    let mut arg = OsString::from(flag);
+    arg.push("=");
+    arg.push(path);
+    arg
+}
+
+/// Échapper un chemin utilisé comme valeur d'option dans un filtre ffmpeg
+///
+/// Deux niveaux d'échappement : celui des options du filtre (`:` les sépare), puis celui
+/// du graphe de filtres (`,`, `;` et les crochets y sont significatifs).
+fn escape_filter_path(path: &Path) -> Result<String> {
+    fn escape(value: &str, special: &[char]) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if special.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    let path = path.to_str().with_context(|| {
+        format!(
+            "Chemin non UTF-8, inutilisable dans un filtre ffmpeg: {}",
+            path.display()
+        )
+    })?;
+    let option = escape(path, &['\\', '\'', ':']);
+    Ok(escape(&option, &['\\', '\'', '[', ']', ',', ';']))
+}
+
+/// Construire la commande ffmpeg pour calculer le VMAF d'un job (source vs sortie)
 fn build_vmaf_command(
     ffmpeg_bin: &Path,
-    input_ref: &Path,
-    input_distorted: &Path,
+    job: &EncodingJob,
     vmaf_log: &Path,
     threads: u32,
-    is_interlaced: bool,
-) -> std::process::Command {
-    let ref_filter = if is_interlaced {
-        "[0:v]yadif,setpts=PTS-STARTPTS[ref]"
-    } else {
-        "[0:v]setpts=PTS-STARTPTS[ref]"
+    reference_filter: Option<&str>,
+) -> Result<std::process::Command> {
+    // La référence passe par les mêmes filtres que la source encodée
+    let ref_filter = match reference_filter {
+        Some(filter) => format!("[0:v]{filter},setpts=PTS-STARTPTS[ref]"),
+        None => "[0:v]setpts=PTS-STARTPTS[ref]".to_string(),
     };
 
+    // PSNR et SSIM calculés par libvmaf dans la même passe (un seul décodage)
+    let features = if job.config.quality_metrics {
+        ":feature=name=psnr|name=float_ssim"
+    } else {
+        ""
+    };
     let vmaf_filter = format!(
-        "{ref_filter};[1:v]setpts=PTS-STARTPTS[dist];[dist][ref]libvmaf=n_threads={threads}:n_subsample=1:log_path={}:log_fmt=json",
-        vmaf_log.display()
+        "{ref_filter};[1:v]setpts=PTS-STARTPTS[dist];[dist][ref]libvmaf=n_threads={threads}:n_subsample=1:log_path={}:log_fmt=json{features}",
+        escape_filter_path(vmaf_log)?
     );
 
+    // La référence est découpée comme la source encodée
     let mut cmd = std::process::Command::new(ffmpeg_bin);
-    cmd.arg("-i")
-        .arg(input_ref)
+    isolate_process_group(&mut cmd);
+    cmd.envs(&job.config.env);
+    cmd.args(source_input_args(&job.input_path, &job.config))
+        .arg("-i")
+        .arg(&job.input_path)
         .arg("-i")
-        .arg(input_distorted)
+        .arg(&job.output_path)
         .arg("-lavfi")
         .arg(&vmaf_filter)
         .arg("-f")
@@ -188,7 +670,7 @@ fn build_vmaf_command(
         .stdout(Stdio::null())
         .stderr(Stdio::piped());
 
-    cmd
+    Ok(cmd)
 }
 
 /// Parser le fichier JSON VMAF pour extraire les scores
@@ -211,61 +693,77 @@ fn parse_vmaf_json_log(vmaf_json: &str) -> Result<(Option<f64>, Option<f64>, Opt
     Ok((vmaf_mean, vmaf_min, vmaf_max))
 }
 
-/// Spawner un thread pour parser stderr de ffmpeg VMAF (byte par byte pour \r)
+/// PSNR de la luma et SSIM moyens du JSON VMAF (None = métrique non calculée)
+fn parse_quality_metrics(vmaf_json: &str) -> (Option<f64>, Option<f64>) {
+    let Ok(data) = serde_json::from_str::<serde_json::Value>(vmaf_json) else {
+        return (None, None);
+    };
+    let mean = |metric: &str| {
+        data.get("pooled_metrics")
+            .and_then(|p| p.get(metric))
+            .and_then(|m| m.get("mean"))
+            .and_then(serde_json::Value::as_f64)
+    };
+    (mean("psnr_y"), mean("float_ssim"))
+}
+
+/// Numéro de frame d'une ligne de stats ffmpeg (`frame=  123 fps=...`)
+fn parse_ffmpeg_frame(line: &str) -> Option<u64> {
+    let (_, rest) = line.split_once("frame=")?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Spawner un thread lisant stderr de ffmpeg ligne par ligne, `\r` compris (stats)
+///
+/// `on_line` reçoit chaque ligne non vide et retourne false pour arrêter la lecture.
+fn spawn_ffmpeg_stderr_thread<F>(
+    stderr: std::process::ChildStderr,
+    mut on_line: F,
+) -> std::thread::JoinHandle<()>
+where
+    F: FnMut(&str) -> bool + Send + 'static,
+{
+    std::thread::spawn(move || {
+        if let Err(e) = read_stderr_lines(stderr, &mut on_line) {
+            tracing::error!("Erreur lecture stderr ffmpeg: {e}");
+        }
+        tracing::debug!("Lecture stderr ffmpeg terminée");
+    })
+}
+
+/// Signaler le passage à une étape sans progression mesurée (attente de l'audio, muxage)
+fn send_phase(
+    stats_tx: &mpsc::UnboundedSender<EncodingStats>,
+    phase: EncodingPhase,
+    video_info: &VideoInfo,
+) {
+    let _ = stats_tx.send(EncodingStats {
+        total_frames: video_info.total_frames,
+        total_duration: video_info.duration,
+        phase: Some(phase),
+        ..EncodingStats::default()
+    });
+}
+
+/// Spawner un thread pour parser stderr de ffmpeg VMAF
 fn spawn_vmaf_stderr_parser_thread(
     stderr: std::process::ChildStderr,
     total_frames: u64,
     stats_tx: mpsc::UnboundedSender<EncodingStats>,
 ) -> std::thread::JoinHandle<()> {
-    std::thread::spawn(move || {
-        use std::io::Read;
-        let mut reader = BufReader::new(stderr);
-        let mut buffer = Vec::new();
-        let mut byte = [0u8; 1];
-
-        loop {
-            match reader.read(&mut byte) {
-                Ok(0) => break,
-                Ok(_) => {
-                    if byte[0] == b'\r' || byte[0] == b'\n' {
-                        if !buffer.is_empty() {
-                            if let Ok(line) = String::from_utf8(buffer.clone()) {
-                                let line = line.trim();
-                                if let Some(frame_str) = line
-                                    .strip_prefix("frame=")
-                                    .or_else(|| line.find("frame=").map(|pos| &line[pos + 6..]))
-                                {
-                                    if let Some(frame_num) = frame_str
-                                        .split_whitespace()
-                                        .next()
-                                        .and_then(|s| s.parse::<u64>().ok())
-                                    {
-                                        let mut stats = EncodingStats {
-                                            frame: frame_num,
-                                            total_frames: Some(total_frames),
-                                            is_calculating_vmaf: true,
-                                            ..EncodingStats::default()
-                                        };
-                                        stats.calculate_progress();
-                                        if stats_tx.send(stats).is_err() {
-                                            break;
-                                        }
-                                    }
-                                }
-                            }
-                            buffer.clear();
-                        }
-                    } else {
-                        buffer.push(byte[0]);
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Erreur lecture stderr ffmpeg VMAF: {e}");
-                    break;
-                }
-            }
-        }
-        tracing::debug!("Lecture stderr ffmpeg VMAF terminée");
+    spawn_ffmpeg_stderr_thread(stderr, move |line| {
+        let Some(frame) = parse_ffmpeg_frame(line) else {
+            return true;
+        };
+        let mut stats = EncodingStats {
+            frame,
+            total_frames: Some(total_frames),
+            is_calculating_vmaf: true,
+            phase: Some(EncodingPhase::Vmaf),
+            ..EncodingStats::default()
+        };
+        stats.update();
+        stats_tx.send(stats).is_ok()
     })
 }
 
@@ -273,6 +771,53 @@ fn spawn_vmaf_stderr_parser_thread(
 // Pipeline d'encodage
 // ============================================================================
 
+/// Résultat d'un encodage réussi (utilisé pour l'analytique)
+#[derive(Debug, Clone, Default)]
+pub struct EncodeOutcome {
+    pub width: u32,
+    pub height: u32,
+    pub total_frames: Option<u64>,
+    /// Durée de l'encodage vidéo seul (toutes passes confondues)
+    pub video_encode_time: Duration,
+    /// Fichiers produits (un par segment) et durée attendue de chacun
+    pub outputs: Vec<(PathBuf, Option<Duration>)>,
+    /// Source telle que relevée au probe, avant découpe et filtres
+    pub source: SourceProbe,
+}
+
+/// Caractéristiques de la source d'un encodage (rapport JSON `[sidecar_report]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SourceProbe {
+    pub video_codec: String,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    pub duration_secs: Option<f64>,
+    pub total_frames: Option<u64>,
+    pub is_interlaced: bool,
+    pub is_vfr: bool,
+    pub streams: Vec<StreamSummary>,
+    pub chapters: usize,
+}
+
+impl SourceProbe {
+    #[must_use]
+    pub fn from_info(video_info: &VideoInfo) -> Self {
+        Self {
+            video_codec: video_info.video_codec.clone(),
+            width: video_info.width,
+            height: video_info.height,
+            fps: video_info.fps,
+            duration_secs: video_info.duration.map(|d| d.as_secs_f64()),
+            total_frames: video_info.total_frames,
+            is_interlaced: video_info.is_interlaced,
+            is_vfr: video_info.is_vfr,
+            streams: verify_stream_summaries(video_info),
+            chapters: video_info.chapters.len(),
+        }
+    }
+}
+
 /// Pipeline d'encodage complet
 pub struct EncodingPipeline {
     ffmpeg_bin: PathBuf,
@@ -280,6 +825,24 @@ pub struct EncodingPipeline {
     svt_av1_bin: PathBuf,
     aom_bin: PathBuf,
     precise_frame_count: bool,
+    /// Délai maximal du comptage précis, avant repli sur l'estimation
+    precise_count_timeout: Duration,
+    /// Intervalle minimal entre deux envois de stats (coalescence des lignes stderr)
+    stats_interval: Duration,
+    /// Processus enfants des jobs en cours
+    processes: ProcessRegistry,
+    /// Capacités des encodeurs, pour adapter les options au binaire installé
+    capabilities: Option<Arc<CapabilitiesCache>>,
+    /// Encodages audio simultanés (tous jobs et pré-encodages confondus)
+    audio_slots: Arc<Semaphore>,
+    /// Audio des jobs en attente, pré-encodé pendant les encodages vidéo
+    audio_prefetch: AudioPrefetch,
+    /// Répertoire des fichiers temporaires (None = à côté de la sortie)
+    temp_dir: Option<PathBuf>,
+    /// Plafond de threads des ffmpeg audio et de muxage (0 = pas de limite)
+    audio_threads: u32,
+    /// Périphérique DRM des encodages VA-API
+    vaapi_device: PathBuf,
 }
 
 impl EncodingPipeline {
@@ -290,6 +853,7 @@ impl EncodingPipeline {
         svt_av1_bin: PathBuf,
         aom_bin: PathBuf,
         precise_frame_count: bool,
+        stats_interval: Duration,
     ) -> Self {
         Self {
             ffmpeg_bin,
@@ -297,79 +861,625 @@ impl EncodingPipeline {
             svt_av1_bin,
             aom_bin,
             precise_frame_count,
+            precise_count_timeout: DEFAULT_PRECISE_COUNT_TIMEOUT,
+            stats_interval,
+            processes: ProcessRegistry::default(),
+            capabilities: None,
+            audio_slots: Arc::new(Semaphore::new(1)),
+            audio_prefetch: AudioPrefetch::default(),
+            temp_dir: None,
+            audio_threads: 0,
+            vaapi_device: PathBuf::from(DEFAULT_VAAPI_DEVICE),
         }
     }
 
-    /// Encoder un job complet
+    /// Délai maximal du comptage précis des frames, au-delà duquel le nombre est estimé
+    #[must_use]
+    pub fn with_precise_count_timeout(mut self, timeout: Duration) -> Self {
+        self.precise_count_timeout = timeout;
+        self
+    }
+
+    /// Périphérique DRM utilisé par les encodages VA-API (`-vaapi_device`)
+    #[must_use]
+    pub fn with_vaapi_device(mut self, device: PathBuf) -> Self {
+        self.vaapi_device = device;
+        self
+    }
+
+    /// Plafonner les threads des ffmpeg audio et de muxage (0 = pas de limite), pour
+    /// qu'ils ne prennent pas le CPU de l'encodage vidéo
+    #[must_use]
+    pub fn with_audio_threads(mut self, max: u32) -> Self {
+        self.audio_threads = max;
+        self
+    }
+
+    /// Threads des ffmpeg audio et de muxage d'un job
+    fn tail_threads(&self, job: &EncodingJob) -> Option<u32> {
+        tail_threads(self.audio_threads, job.effective_threads())
+    }
+
+    /// Écrire les fichiers temporaires des jobs dans `dir` plutôt qu'à côté de la sortie
+    #[must_use]
+    pub fn with_temp_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.temp_dir = dir;
+        self
+    }
+
+    /// Répertoire temporaire configuré (None = à côté de la sortie)
+    #[must_use]
+    pub fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
+    /// Nombre d'encodages audio simultanés (au moins 1)
+    #[must_use]
+    pub fn with_audio_concurrency(mut self, max: usize) -> Self {
+        self.audio_slots = Arc::new(Semaphore::new(max.max(1)));
+        self
+    }
+
+    /// Adapter les commandes aux options supportées par les encodeurs installés
+    #[must_use]
+    pub fn with_capabilities(mut self, capabilities: Arc<CapabilitiesCache>) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Registre des processus enfants des jobs en cours
+    #[must_use]
+    pub fn processes(&self) -> &ProcessRegistry {
+        &self.processes
+    }
+
+    /// Lancer une commande et attendre sa sortie, enregistrée le temps de son exécution
     ///
-    /// # Errors
+    /// Équivalent de [`Command::output`] : l'enfant reçoit la niceness du job et est tué
+    /// à l'arrêt du daemon comme l'encodeur vidéo.
+    async fn output_registered(
+        &self,
+        job: &EncodingJob,
+        cmd: &mut Command,
+    ) -> std::io::Result<std::process::Output> {
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let child = cmd.spawn()?;
+        let pid = child.id();
+        if let Some(pid) = pid {
+            self.processes.register(job.id, pid);
+        }
+        let output = child.wait_with_output().await;
+        if let Some(pid) = pid {
+            self.processes.unregister(job.id, pid);
+        }
+        output
+    }
+
+    /// Prober la source d'un job, interrompu dès que le job est annulé
     ///
-    /// Retourne une erreur si le probe, l'encodage vidéo, l'encodage audio ou le muxage échoue.
+    /// Abandonner le probe tue ffprobe et le comptage précis des frames, qui peut durer
+    /// plusieurs minutes sur un gros fichier.
+    async fn probe_cancellable(
+        &self,
+        input: &Path,
+        precise: bool,
+        detect_interlacing: bool,
+        env: &HashMap<String, String>,
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<VideoInfo, EncodeError> {
+        tokio::select! {
+            _ = cancel_rx.recv() => {
+                info!("Annulation demandée pendant le probe");
+                Err(EncodeError::Cancelled)
+            }
+            result = probe_video(
+                &self.ffprobe_bin,
+                &self.ffmpeg_bin,
+                input,
+                precise.then_some(self.precise_count_timeout),
+                detect_interlacing,
+                env,
+            ) => result.map_err(EncodeError::ProbeFailed),
+        }
+    }
+
+    /// Pré-encoder l'audio d'un job en attente, dans un emplacement du pool audio
     ///
-    /// # Panics
+    /// Sans effet si l'audio est déjà pré-encodé avec les mêmes réglages, ou pour les
+    /// jobs découpés (chaque segment a son propre extrait audio), les sorties sans audio
+    /// et les vérifications.
+    pub async fn prefetch_audio(self: &Arc<Self>, job: &EncodingJob) {
+        if job.kind != JobKind::Encode
+            || job.config.split != SplitMode::None
+            || !job.config.audio_mode.has_audio()
+            || self.audio_prefetch.contains(job).await
+        {
+            return;
+        }
+        let dir = job_temp_dir(self.temp_dir(), &job.output_path);
+        if !dir.is_dir() {
+            return;
+        }
+        let mut workspace = TempWorkspace::new(dir).with_prefix("encodetalker-audio-");
+        let path = workspace.allocate(audio_extension(&job.config.audio_mode));
+
+        let pipeline = self.clone();
+        let task_job = job.clone();
+        let task_path = path.clone();
+        let task = tokio::spawn(async move {
+            let video_info = probe_video(
+                &pipeline.ffprobe_bin,
+                &pipeline.ffmpeg_bin,
+                &task_job.input_path,
+                None,
+                false,
+                &task_job.config.env,
+            )
+            .await?;
+            pipeline
+                .encode_audio_limited(&task_job, &video_info, &task_path)
+                .await
+        });
+        info!("Job {} : pré-encodage de l'audio", job.id);
+        self.audio_prefetch.insert(job, workspace, path, task).await;
+    }
+
+    /// Abandonner l'audio pré-encodé des jobs absents de `keep` (annulés, retirés...)
+    pub async fn retain_audio_prefetch(&self, keep: &std::collections::HashSet<uuid::Uuid>) {
+        self.audio_prefetch.retain(keep).await;
+    }
+
+    /// Fichiers de l'audio pré-encodé des jobs en attente (à ne pas nettoyer)
+    pub async fn prefetched_audio_paths(&self) -> std::collections::HashSet<PathBuf> {
+        self.audio_prefetch.paths().await
+    }
+
+    /// Encoder un job complet (un fichier par segment si la sortie est découpée)
     ///
-    /// Peut paniquer si `job.output_path.parent()` retourne `None`.
+    /// # Errors
+    ///
+    /// Retourne une [`EncodeError`] si le probe, l'encodage vidéo, l'encodage audio ou le
+    /// muxage échoue, ou si le job est annulé.
     pub async fn encode_job(
         &self,
         job: &EncodingJob,
         stats_tx: mpsc::UnboundedSender<EncodingStats>,
-        mut cancel_rx: mpsc::UnboundedReceiver<()>,
-    ) -> Result<()> {
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<EncodeOutcome, EncodeError> {
         info!(
             "Début d'encodage: {} -> {}",
             job.input_path.display(),
             job.output_path.display()
         );
 
+        if !job.input_path.exists() {
+            return Err(EncodeError::InputMissing(job.input_path.clone()));
+        }
+        job.config
+            .trim
+            .validate()
+            .map_err(|e| EncodeError::Other(anyhow::anyhow!(e)))?;
+
         // 1. Probe du fichier source
-        let video_info = probe_video(
-            &self.ffprobe_bin,
-            &self.ffmpeg_bin,
-            &job.input_path,
-            self.precise_frame_count,
-        )
-        .await
-        .context("Échec du probe vidéo")?;
+        let video_info = self
+            .probe_cancellable(
+                &job.input_path,
+                job.config.precise_frame_count_or(self.precise_frame_count),
+                job.config.video_mode == VideoMode::Encode
+                    && job.config.deinterlace.mode == DeinterlaceMode::Auto,
+                &job.config.env,
+                cancel_rx,
+            )
+            .await?;
 
-        info!(
-            "Vidéo: {}x{} @ {:.2} fps, durée: {:?}",
-            video_info.width, video_info.height, video_info.fps, video_info.duration
+        if job.config.split == SplitMode::None {
+            return self
+                .encode_probed(job, video_info, stats_tx, cancel_rx)
+                .await;
+        }
+
+        self.encode_segments(job, &video_info, &stats_tx, cancel_rx)
+            .await
+    }
+
+    /// Mesurer la vitesse des presets d'un encodeur sur un court extrait de la source
+    ///
+    /// Seule la vidéo est encodée, vers un fichier temporaire supprimé après chaque mesure.
+    /// Les presets sont testés du plus rapide au plus lent et le benchmark s'arrête au
+    /// premier qui n'atteint pas `target_speed` : les suivants seraient encore plus lents.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la source est introuvable, si le probe échoue, si le nombre
+    /// de frames de l'extrait est inconnu, si un encodage échoue ou si le benchmark est
+    /// annulé.
+    pub async fn benchmark(
+        &self,
+        input: &Path,
+        encoder: EncoderType,
+        target_speed: f64,
+        progress_tx: mpsc::UnboundedSender<PresetBenchmark>,
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<BenchmarkReport> {
+        if !tokio::fs::try_exists(input).await.unwrap_or(false) {
+            anyhow::bail!("Fichier source introuvable: {}", input.display());
+        }
+        let mut video_info = self
+            .probe_cancellable(
+                input,
+                self.precise_frame_count,
+                true,
+                &HashMap::new(),
+                cancel_rx,
+            )
+            .await?;
+        let trim = benchmark_sample(video_info.duration.map(|d| d.as_secs_f64()));
+        apply_trim(&mut video_info, &trim);
+        apply_deinterlace(&mut video_info, DeinterlaceConfig::default());
+        apply_even_crop(&mut video_info, true);
+        apply_vfr(&mut video_info, VfrMode::default());
+        #[allow(clippy::cast_precision_loss)] // Safe: quelques centaines de frames
+        let frames = video_info
+            .total_frames
+            .filter(|&n| n > 0)
+            .context("Nombre de frames de l'extrait inconnu")? as f64;
+
+        let mut report = BenchmarkReport {
+            encoder,
+            source_fps: video_info.fps,
+            target_speed,
+            results: Vec::new(),
+        };
+        // Les stats ne servent pas ici, mais le récepteur doit rester vivant
+        // pour que le thread de lecture continue de drainer stderr
+        let (stats_tx, _stats_rx) = mpsc::unbounded_channel::<EncodingStats>();
+
+        // Même répertoire temporaire que les jobs ; sans sortie, le temporaire système
+        // remplace le répertoire de la sortie
+        if let Some(dir) = self.temp_dir() {
+            ensure_dir_writable(dir, true, "encoding.temp_dir").await?;
+        }
+        let dir = self
+            .temp_dir()
+            .map_or_else(std::env::temp_dir, Path::to_path_buf);
+        let mut workspace = TempWorkspace::new(dir).with_prefix("encodetalker-benchmark-");
+        for &preset in benchmark_presets(encoder) {
+            let config = EncodingConfig {
+                encoder,
+                encoder_params: EncoderParams {
+                    preset,
+                    ..EncoderParams::default()
+                },
+                enable_vmaf: false,
+                trim,
+                ..EncodingConfig::default()
+            };
+            let output = workspace.allocate("ivf");
+            let job = EncodingJob::new(input.to_path_buf(), output.clone(), config);
+
+            info!("Benchmark {encoder} preset {preset}");
+            let start = Instant::now();
+            let result = self
+                .encode_video(&job, &video_info, &output, stats_tx.clone(), cancel_rx)
+                .await;
+            let elapsed = start.elapsed().as_secs_f64();
+            workspace.cleanup().await;
+            result?;
+
+            let measure = PresetBenchmark {
+                preset,
+                fps: frames / elapsed.max(f64::EPSILON),
+            };
+            info!(
+                "Benchmark preset {preset}: {:.1} fps ({:.2}x)",
+                measure.fps,
+                report.speed(&measure)
+            );
+            let _ = progress_tx.send(measure);
+            report.results.push(measure);
+            if report.speed(&measure) < target_speed {
+                break;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Encoder chaque segment (chapitre ou tranche fixe) dans son propre fichier
+    async fn encode_segments(
+        &self,
+        job: &EncodingJob,
+        video_info: &VideoInfo,
+        stats_tx: &mpsc::UnboundedSender<EncodingStats>,
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<EncodeOutcome, EncodeError> {
+        let segments = plan_segments(
+            job.config.split,
+            &job.config.trim,
+            &video_info.chapters,
+            video_info.duration,
         );
+        if segments.is_empty() {
+            return Err(EncodeError::Other(anyhow::anyhow!(
+                "Découpage impossible ({}): aucun chapitre ou durée inconnue",
+                job.config.split
+            )));
+        }
+
+        let count = segments.len();
+        info!("Découpage en {count} segments ({})", job.config.split);
 
-        // 2. Préparer les chemins temporaires
-        let temp_dir = job.output_path.parent().unwrap();
-        let video_temp = temp_dir.join(format!("{}.ivf", uuid::Uuid::new_v4()));
-        let audio_ext = match &job.config.audio_mode {
-            AudioMode::Opus { .. } => "opus",
-            _ => "mka",
+        let mut total = EncodeOutcome {
+            width: video_info.width,
+            height: video_info.height,
+            total_frames: Some(0),
+            video_encode_time: Duration::ZERO,
+            outputs: Vec::new(),
+            source: SourceProbe::from_info(video_info),
         };
-        let audio_temp = temp_dir.join(format!("{}.{}", uuid::Uuid::new_v4(), audio_ext));
 
-        // 3. Encoder la vidéo
-        self.encode_video(
-            job,
-            &video_info,
-            &video_temp,
-            stats_tx.clone(),
-            &mut cancel_rx,
-        )
-        .await?;
+        for segment in &segments {
+            let mut segment_job = job.clone();
+            segment_job.output_path = segment_output_path(&job.output_path, segment);
+            segment_job.config.trim = segment.trim();
+            segment_job.config.split = SplitMode::None;
+            info!(
+                "Segment {}/{count}: {:.1}s → {:.1}s -> {}",
+                segment.index,
+                segment.start,
+                segment.end,
+                segment_job.output_path.display()
+            );
 
-        // 4. Encoder l'audio (en parallèle possible, mais pour simplifier on le fait après)
-        self.encode_audio(job, &audio_temp).await?;
+            // Annoter les stats avec le segment en cours
+            let (segment_tx, mut segment_rx) = mpsc::unbounded_channel::<EncodingStats>();
+            let forward_tx = stats_tx.clone();
+            #[allow(clippy::cast_possible_truncation)] // Safe: nombre de segments petit
+            let (index, count_u32) = (segment.index as u32, count as u32);
+            let forwarder = tokio::spawn(async move {
+                while let Some(mut stats) = segment_rx.recv().await {
+                    stats.set_segment(index, count_u32);
+                    if forward_tx.send(stats).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            let result = self
+                .encode_probed(&segment_job, video_info.clone(), segment_tx, cancel_rx)
+                .await;
+            let _ = forwarder.await;
+            let outcome = match result {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    // Pas de sortie partielle : les segments déjà écrits sont supprimés
+                    for (path, _) in &total.outputs {
+                        if let Err(e) = tokio::fs::remove_file(path).await {
+                            tracing::warn!("Impossible de supprimer {}: {e}", path.display());
+                        }
+                    }
+                    return Err(e);
+                }
+            };
+
+            total.video_encode_time += outcome.video_encode_time;
+            total.outputs.extend(outcome.outputs);
+            total.total_frames = total
+                .total_frames
+                .zip(outcome.total_frames)
+                .map(|(a, b)| a + b);
+        }
 
-        // 5. Muxer le tout
-        self.mux_final(job, &video_temp, &audio_temp, &video_info)
-            .await?;
+        info!("{count} segments encodés avec succès");
+        Ok(total)
+    }
+
+    /// Encoder un job dont la source a déjà été analysée
+    async fn encode_probed(
+        &self,
+        job: &EncodingJob,
+        video_info: VideoInfo,
+        stats_tx: mpsc::UnboundedSender<EncodingStats>,
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<EncodeOutcome, EncodeError> {
+        // Compléter les stats avec l'avancement global, d'après les étapes du job
+        let plan = job.config.phase_plan();
+        let (phase_tx, mut phase_rx) = mpsc::unbounded_channel::<EncodingStats>();
+        let forwarder = tokio::spawn(async move {
+            while let Some(mut stats) = phase_rx.recv().await {
+                stats.calculate_overall(&plan);
+                if stats_tx.send(stats).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = self
+            .encode_phases(job, video_info, phase_tx, cancel_rx)
+            .await;
+        let _ = forwarder.await;
+        result
+    }
+
+    /// Étapes d'un encodage : vidéo et audio en parallèle, muxage puis VMAF
+    async fn encode_phases(
+        &self,
+        job: &EncodingJob,
+        mut video_info: VideoInfo,
+        stats_tx: mpsc::UnboundedSender<EncodingStats>,
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<EncodeOutcome, EncodeError> {
+        let source = SourceProbe::from_info(&video_info);
+        // Position de la frame extraite, d'après la durée de la source (avant découpe)
+        let poster_at = job.config.cover_art.poster_timestamp(
+            &job.config.trim,
+            video_info.duration.map(|d| d.as_secs_f64()),
+        );
+        apply_trim(&mut video_info, &job.config.trim);
+        apply_deinterlace(&mut video_info, job.config.deinterlace);
+        apply_even_crop(&mut video_info, job.config.crop_to_even);
+        apply_vfr(&mut video_info, job.config.vfr_mode);
+        if job.config.video_mode == VideoMode::Encode {
+            apply_tonemap(&mut video_info, job.config.tonemap);
+        }
+        if video_info.tonemap_filter.is_some() {
+            self.ensure_tonemap_filters().await?;
+        }
 
-        // 6. Nettoyer les fichiers temporaires
-        let _ = tokio::fs::remove_file(&video_temp).await;
-        let _ = tokio::fs::remove_file(&audio_temp).await;
+        info!(
+            "Vidéo: {}x{} @ {:.2} fps, durée: {:?}",
+            video_info.width, video_info.height, video_info.fps, video_info.duration
+        );
+
+        // Sous-titres incompatibles avec le conteneur : échec avant tout encodage
+        let subtitles = plan_subtitles(job, &video_info)?;
+        let (mut covers, extract_cover) = plan_covers(job, &video_info);
 
-        // 7. Calculer VMAF si activé
-        if job.config.enable_vmaf {
+        // 2. Préparer les chemins temporaires (supprimés aussi si le job est abandonné)
+        if let Some(dir) = self.temp_dir() {
+            // Le répertoire a pu devenir inaccessible depuis l'ajout du job
+            ensure_dir_writable(dir, true, "encoding.temp_dir")
+                .await
+                .map_err(EncodeError::Other)?;
+        }
+        let mut workspace = TempWorkspace::new(job_temp_dir(self.temp_dir(), &job.output_path));
+        let video_temp = workspace.allocate("ivf");
+        // Stats de passe 1 d'aomenc
+        workspace.track(video_temp.with_extension("log"));
+        let audio_temp = job
+            .config
+            .audio_mode
+            .has_audio()
+            .then(|| workspace.allocate(audio_extension(&job.config.audio_mode)));
+        let poster_temp = extract_cover.then(|| workspace.allocate("png"));
+        // Muxage sous un nom temporaire, renommé en sortie finale une fois complet
+        let mux_temp = workspace.allocate(&job.output_path.extension().map_or_else(
+            || "mkv".to_string(),
+            |ext| ext.to_string_lossy().into_owned(),
+        ));
+        // Audio pré-encodé pendant que le job attendait (None = à encoder)
+        let prefetched = self.audio_prefetch.take(job).await;
+        let mut prefetch_workspace = None;
+
+        // 3-5. Encoder vidéo et audio en parallèle puis muxer ; les temporaires sont
+        // supprimés même en cas d'échec ou d'annulation
+        let copy_video = job.config.video_mode == VideoMode::Copy;
+        // Arrêt de la vidéo : annulation du job ou échec de l'audio
+        let (abort_tx, mut abort_rx) = mpsc::unbounded_channel();
+        let result = async {
+            // 3. Encoder la vidéo (ou la reprendre telle quelle depuis la source)
+            let video = async {
+                let video_start = Instant::now();
+                if copy_video {
+                    if !container_supports_video_codec(&job.output_path, &video_info.video_codec) {
+                        return Err(EncodeError::UnsupportedCopyCodec {
+                            codec: video_info.video_codec.clone(),
+                            container: job
+                                .output_path
+                                .extension()
+                                .map_or_else(String::new, |e| e.to_string_lossy().into_owned()),
+                        });
+                    }
+                    info!("Copie de la vidéo source ({})", video_info.video_codec);
+                } else {
+                    self.encode_video(
+                        job,
+                        &video_info,
+                        &video_temp,
+                        stats_tx.clone(),
+                        &mut abort_rx,
+                    )
+                    .await
+                    .map_err(EncodeError::from_anyhow)?;
+                }
+                Ok(video_start.elapsed())
+            };
+
+            // 4. Encoder l'audio pendant la vidéo (dans la limite du pool audio), ou
+            // attendre la fin de son pré-encodage
+            // (rien à faire pour une sortie sans audio)
+            let audio = async {
+                let Some(audio_temp) = &audio_temp else {
+                    return Ok(None);
+                };
+                if let Some(prefetched) = prefetched {
+                    if let Some((prefetch_ws, path)) = prefetched.finish().await {
+                        prefetch_workspace = Some(prefetch_ws);
+                        return Ok(Some(path));
+                    }
+                }
+                self.encode_audio_limited(job, &video_info, audio_temp)
+                    .await
+                    .map_err(EncodeError::from_anyhow)?;
+                Ok(Some(audio_temp.clone()))
+            };
+            let mut audio = std::pin::pin!(audio);
+            // Vidéo en échec ou annulée : l'audio en cours est abandonné (ffmpeg tué) ;
+            // audio en échec : la vidéo est arrêtée sans attendre sa fin
+            let (video_encode_time, audio_done) =
+                video_with_audio(video, audio.as_mut(), cancel_rx, &abort_tx).await?;
+            let audio_source = match audio_done {
+                Some(source) => source,
+                None => {
+                    if audio_temp.is_some() {
+                        send_phase(&stats_tx, EncodingPhase::Audio, &video_info);
+                    }
+                    audio.await?
+                }
+            };
+
+            // 5. Muxer le tout
+            send_phase(&stats_tx, EncodingPhase::Muxing, &video_info);
+            if let Some(poster) = &poster_temp {
+                // Pochette facultative : son échec ne fait pas échouer le job
+                let filter = video_info.decode_filter();
+                match extract_poster(
+                    &self.ffmpeg_bin,
+                    &job.input_path,
+                    poster_at,
+                    filter.as_deref(),
+                    poster,
+                    &job.config.env,
+                )
+                .await
+                {
+                    Ok(()) => covers.push(MuxedCover::Poster(poster.clone())),
+                    Err(e) => tracing::warn!("Pochette non extraite, sortie sans pochette: {e:#}"),
+                }
+            }
+            let video_source = if copy_video {
+                &job.input_path
+            } else {
+                &video_temp
+            };
+            self.mux_final(
+                job,
+                video_source,
+                audio_source.as_deref(),
+                &subtitles,
+                &covers,
+                &mux_temp,
+            )
+            .await
+            .map_err(EncodeError::from_anyhow)?;
+            self.check_av_sync(job, &video_info).await;
+            Ok(video_encode_time)
+        }
+        .await;
+
+        // 6. Nettoyer les fichiers temporaires (dont les stats de passe 1 d'aomenc)
+        workspace.cleanup().await;
+        if let Some(mut prefetch_workspace) = prefetch_workspace {
+            prefetch_workspace.cleanup().await;
+        }
+        let video_encode_time = result?;
+
+        // 7. Calculer VMAF si activé (sans objet quand la vidéo est copiée)
+        if (job.config.enable_vmaf || job.config.quality_metrics) && !copy_video {
             if let Err(e) = self
-                .calculate_vmaf(job, &video_info, stats_tx, &mut cancel_rx)
+                .calculate_vmaf(job, &video_info, stats_tx, cancel_rx)
                 .await
             {
                 // Ne pas faire échouer le job si le calcul VMAF échoue
@@ -381,7 +1491,34 @@ impl EncodingPipeline {
             "Encodage terminé avec succès: {}",
             job.output_path.display()
         );
-        Ok(())
+        Ok(EncodeOutcome {
+            width: video_info.width,
+            height: video_info.height,
+            total_frames: video_info.total_frames,
+            video_encode_time,
+            outputs: vec![(job.output_path.clone(), video_info.duration)],
+            source,
+        })
+    }
+
+    /// Vérifier que ffmpeg dispose des filtres du tone mapping
+    ///
+    /// Sans informations sur ffmpeg (liste des filtres illisible), rien n'est bloqué.
+    async fn ensure_tonemap_filters(&self) -> Result<(), EncodeError> {
+        let Some(capabilities) = &self.capabilities else {
+            return Ok(());
+        };
+        let missing = capabilities
+            .get()
+            .await
+            .missing_ffmpeg_filters(TONEMAP_FILTERS);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(EncodeError::Other(anyhow::anyhow!(
+            "Tone mapping impossible: filtre(s) {} absent(s) de ffmpeg (zscale nécessite libzimg, --enable-libzimg)",
+            missing.join(", ")
+        )))
     }
 
     /// Encoder la piste vidéo (gère automatiquement les 2 passes pour aomenc)
@@ -397,7 +1534,12 @@ impl EncodingPipeline {
 
         match job.config.encoder {
             EncoderType::SvtAv1 => {
-                let encoder_cmd = self.build_svt_av1_std_command(job, output_path);
+                let svt_info = match &self.capabilities {
+                    Some(capabilities) => capabilities.get().await.svt_av1,
+                    None => None,
+                };
+                let encoder_cmd =
+                    self.build_svt_av1_std_command(job, output_path, svt_info.as_ref(), video_info);
                 self.run_encode_pass(job, video_info, encoder_cmd, stats_tx, cancel_rx)
                     .await?;
             }
@@ -406,20 +1548,30 @@ impl EncodingPipeline {
 
                 // Passe 1 : génère les statistiques
                 info!("aomenc passe 1/2 : analyse");
-                let encoder_cmd =
-                    self.build_aom_std_command(job, Path::new("/dev/null"), 1, &fpf_path);
+                let encoder_cmd = self.build_aom_std_command(
+                    job,
+                    Path::new("/dev/null"),
+                    1,
+                    &fpf_path,
+                    video_info,
+                );
                 self.run_encode_pass(job, video_info, encoder_cmd, stats_tx.clone(), cancel_rx)
                     .await?;
 
                 // Passe 2 : encodage final
                 info!("aomenc passe 2/2 : encodage");
-                let encoder_cmd = self.build_aom_std_command(job, output_path, 2, &fpf_path);
+                let encoder_cmd =
+                    self.build_aom_std_command(job, output_path, 2, &fpf_path, video_info);
                 self.run_encode_pass(job, video_info, encoder_cmd, stats_tx, cancel_rx)
                     .await?;
 
                 // Nettoyer le fichier de stats
                 let _ = tokio::fs::remove_file(&fpf_path).await;
             }
+            EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => {
+                self.run_hardware_encode(job, video_info, output_path, stats_tx, cancel_rx)
+                    .await?;
+            }
         }
 
         info!("Encodage vidéo terminé avec succès");
@@ -440,8 +1592,11 @@ impl EncodingPipeline {
             &self.ffmpeg_bin,
             &job.input_path,
             "yuv420p10le",
-            video_info.is_interlaced,
+            video_info.decode_filter().as_deref(),
+            &video_info.decode_rate_args(),
+            &source_input_args(&job.input_path, &job.config),
         );
+        ffmpeg_cmd.envs(&job.config.env);
         let mut ffmpeg_child = ffmpeg_cmd.spawn().context("Échec du démarrage de ffmpeg")?;
 
         let ffmpeg_stdout = ffmpeg_child
@@ -469,40 +1624,33 @@ impl EncodingPipeline {
         // 3. Parser stderr de l'encodeur (byte par byte pour gérer \r)
         let parser = StatsParser::new(video_info.total_frames, video_info.duration);
         let stats_tx_clone = stats_tx.clone();
+        let stats_interval = self.stats_interval;
 
         let encoder_stderr_handle = std::thread::spawn(move || {
-            use std::io::Read;
-            let mut reader = BufReader::new(encoder_stderr);
             let mut parser = parser;
-            let mut buffer = Vec::new();
-            let mut byte = [0u8; 1];
-
-            loop {
-                match reader.read(&mut byte) {
-                    Ok(0) => break,
-                    Ok(_) => {
-                        if byte[0] == b'\r' || byte[0] == b'\n' {
-                            if !buffer.is_empty() {
-                                if let Ok(line) = String::from_utf8(buffer.clone()) {
-                                    let line = line.trim();
-                                    if !line.is_empty() {
-                                        parser.parse_encoder_line(line);
-                                        if stats_tx_clone.send(parser.clone_stats()).is_err() {
-                                            break;
-                                        }
-                                    }
-                                }
-                                buffer.clear();
-                            }
-                        } else {
-                            buffer.push(byte[0]);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("Erreur lecture stderr encodeur: {e}");
-                        break;
+            // Coalescer les mises à jour : au plus un envoi par intervalle
+            let mut last_sent: Option<Instant> = None;
+            let mut pending = false;
+
+            let read_result = read_stderr_lines(encoder_stderr, |line| {
+                parser.parse_encoder_line(line);
+                pending = true;
+                if last_sent.is_none_or(|t| t.elapsed() >= stats_interval) {
+                    if stats_tx_clone.send(parser.clone_stats()).is_err() {
+                        return false;
                     }
+                    last_sent = Some(Instant::now());
+                    pending = false;
                 }
+                true
+            });
+            if let Err(e) = read_result {
+                tracing::error!("Erreur lecture stderr encodeur: {e}");
+            }
+
+            // Toujours envoyer le dernier état à EOF
+            if pending {
+                let _ = stats_tx_clone.send(parser.clone_stats());
             }
             tracing::debug!("Lecture stderr encodeur terminée");
         });
@@ -513,7 +1661,16 @@ impl EncodingPipeline {
         });
 
         // 5. Attendre les processus avec annulation
-        wait_for_processes_with_cancellation(ffmpeg_child, encoder_child, cancel_rx).await?;
+        let pids = [ffmpeg_child.id(), encoder_child.id()];
+        for pid in pids {
+            self.processes.register(job.id, pid);
+        }
+        let wait_result =
+            wait_for_processes_with_cancellation(ffmpeg_child, encoder_child, cancel_rx).await;
+        for pid in pids {
+            self.processes.unregister(job.id, pid);
+        }
+        wait_result?;
 
         // 6. Joindre les threads stderr
         if let Err(e) = encoder_stderr_handle.join() {
@@ -526,54 +1683,125 @@ impl EncodingPipeline {
         Ok(())
     }
 
-    /// Construire la commande SVT-AV1 (`std::process`)
-    fn build_svt_av1_std_command(&self, job: &EncodingJob, output: &Path) -> std::process::Command {
+    /// Encoder la vidéo avec un encodeur matériel de ffmpeg (un seul processus, sans pipe)
+    async fn run_hardware_encode(
+        &self,
+        job: &EncodingJob,
+        video_info: &VideoInfo,
+        output_path: &Path,
+        stats_tx: mpsc::UnboundedSender<EncodingStats>,
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<()> {
+        let mut ffmpeg_cmd = build_hardware_encode_command(
+            &self.ffmpeg_bin,
+            job,
+            video_info,
+            &self.vaapi_device,
+            output_path,
+        );
+        let mut ffmpeg_child = ffmpeg_cmd
+            .spawn()
+            .context("Échec du démarrage de ffmpeg pour l'encodage matériel")?;
+        let ffmpeg_stderr = ffmpeg_child
+            .stderr
+            .take()
+            .context("Impossible de prendre stderr de ffmpeg")?;
+
+        // Progression (clé=valeur) et erreurs de ffmpeg (GPU absent, format refusé...)
+        let mut parser = StatsParser::new(video_info.total_frames, video_info.duration);
+        let stats_interval = self.stats_interval;
+        let mut last_sent: Option<Instant> = None;
+        let stderr_handle = spawn_ffmpeg_stderr_thread(ffmpeg_stderr, move |line| {
+            if !line.contains('=') {
+                tracing::error!("ffmpeg stderr: {line}");
+                return true;
+            }
+            parser.parse_line(line);
+            let end = line == "progress=end";
+            if line.starts_with("progress=")
+                && (end || last_sent.is_none_or(|t| t.elapsed() >= stats_interval))
+            {
+                if stats_tx.send(parser.clone_stats()).is_err() {
+                    return false;
+                }
+                last_sent = Some(Instant::now());
+            }
+            true
+        });
+
+        let pid = ffmpeg_child.id();
+        self.processes.register(job.id, pid);
+        let wait_result = wait_for_process_with_cancellation(ffmpeg_child, cancel_rx).await;
+        self.processes.unregister(job.id, pid);
+
+        if let Err(e) = stderr_handle.join() {
+            tracing::error!("Échec de jointure du thread stderr ffmpeg: {e:?}");
+        }
+        wait_result.with_context(|| {
+            format!(
+                "Encodage {} échoué (GPU ou pilote indisponible ?)",
+                job.config.encoder
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Construire la commande SVT-AV1 (`std::process`), le framerate de `video_info`
+    /// servant à convertir l'intervalle de keyframes en frames
+    ///
+    /// Sans informations sur le binaire (`svt_info` à None), toutes les options sont passées.
+    fn build_svt_av1_std_command(
+        &self,
+        job: &EncodingJob,
+        output: &Path,
+        svt_info: Option<&EncoderBinaryInfo>,
+        video_info: &VideoInfo,
+    ) -> std::process::Command {
         let mut cmd = std::process::Command::new(&self.svt_av1_bin);
+        isolate_process_group(&mut cmd);
+        cmd.envs(&job.config.env);
 
+        let params = &job.config.encoder_params;
         cmd.arg("-i")
             .arg("stdin")
-            .arg("--crf")
-            .arg(job.config.encoder_params.crf.to_string())
+            .args(params.encoding_mode.svt_av1_args(params.crf))
             .arg("--preset")
             .arg(job.config.encoder_params.preset.to_string());
 
-        // Ajouter threads si spécifié
-        if let Some(threads) = job.config.encoder_params.threads {
+        // Ajouter threads si spécifié (ou attribués par le budget global)
+        if let Some(threads) = job.effective_threads() {
             cmd.arg("--lp").arg(threads.to_string());
         }
 
-        cmd.arg("--progress")
-            .arg("2") // Activer la progression sur stderr
-            .arg("-b")
-            .arg(output);
-
-        // Paramètres spécifiques au type de contenu
-        match job.config.encoder_params.content_type {
-            encodetalker_common::VideoContentType::GrainedFilm => {
-                // Film granuleux : préservation du grain filmique
-                cmd.arg("--enable-cdef").arg("0");
-                cmd.arg("--enable-restoration").arg("0");
-                cmd.arg("--enable-tf").arg("0");
-                cmd.arg("--spy-rd").arg("1");
-                cmd.arg("--noise-norm-strength").arg("3");
-                cmd.arg("--qm-min").arg("10");
-                cmd.arg("--tune").arg("0");
-                cmd.arg("--qp-scale-compress-strength").arg("3");
-                cmd.arg("--scm").arg("0");
-                cmd.arg("--psy-rd").arg("4.0");
-                cmd.arg("--hbd-mds").arg("1");
-            }
-            encodetalker_common::VideoContentType::Anime => {
-                // Anime : réduction de bruit agressive
-                cmd.arg("--qm-min").arg("8");
-                cmd.arg("--noise-norm-strength").arg("4");
-                cmd.arg("--enable-dlf").arg("2");
-            }
-            _ => {
-                // Default et LiveAction : paramètres standards
-                cmd.arg("--qm-min").arg("8");
-                cmd.arg("--noise-norm-strength").arg("1");
-                cmd.arg("--enable-dlf").arg("2");
+        // Options refusées par une version trop ancienne : ignorées plutôt que de faire
+        // échouer l'encodeur (signalées au démarrage du daemon)
+        let accepts = |option: &str| svt_info.is_none_or(|info| info.accepts(option));
+
+        if accepts("--progress") {
+            cmd.arg("--progress").arg("2"); // Activer la progression sur stderr
+        } else {
+            tracing::debug!("--progress non supporté par SvtAv1EncApp, progression indisponible");
+        }
+        cmd.arg("-b").arg(output);
+
+        if accepts("--tune") {
+            cmd.arg("--tune").arg(
+                job.config
+                    .encoder_params
+                    .tune
+                    .arg_or_default(EncoderType::SvtAv1),
+            );
+        } else {
+            tracing::debug!("--tune non supporté par SvtAv1EncApp, ignoré");
+        }
+
+        // Paramètres spécifiques au type de contenu (ceux propres à SVT-AV1-PSY sont
+        // ignorés si le binaire installé ne les connaît pas)
+        for (option, value) in job.config.encoder_params.content_type.svt_av1_args() {
+            if accepts(option) {
+                cmd.arg(option).arg(value);
+            } else {
+                tracing::debug!("Option {option} non supportée par SvtAv1EncApp, ignorée");
             }
         }
 
@@ -582,6 +1810,23 @@ impl EncodingPipeline {
             cmd.arg(param);
         }
 
+        // Intervalle de keyframes configuré : après les paramètres extra, qu'il remplace
+        cmd.args(
+            job.config
+                .keyframes
+                .encoder_args(EncoderType::SvtAv1, Some(video_info.fps)),
+        );
+
+        // Couleurs de la sortie après un tone mapping (par paires option / valeur)
+        let color_args = video_info.output_color_args(EncoderType::SvtAv1);
+        for pair in color_args.chunks(2) {
+            if accepts(&pair[0]) {
+                cmd.args(pair);
+            } else {
+                tracing::debug!("Option {} non supportée par SvtAv1EncApp, ignorée", pair[0]);
+            }
+        }
+
         cmd
     }
 
@@ -592,24 +1837,29 @@ impl EncodingPipeline {
         output: &Path,
         pass: u32,
         fpf_path: &Path,
+        video_info: &VideoInfo,
     ) -> std::process::Command {
         let mut cmd = std::process::Command::new(&self.aom_bin);
+        isolate_process_group(&mut cmd);
+        cmd.envs(&job.config.env);
 
-        cmd.arg(format!("--cq-level={}", job.config.encoder_params.crf))
-            .arg(format!("--cpu-used={}", job.config.encoder_params.preset))
-            .arg("--end-usage=q")
+        let params = &job.config.encoder_params;
+        cmd.args(params.encoding_mode.aom_args(params.crf))
+            .arg(format!("--cpu-used={}", params.preset))
             .arg("--passes=2")
             .arg(format!("--pass={pass}"))
-            .arg(format!("--fpf={}", fpf_path.display()));
+            .arg(path_option("--fpf", fpf_path));
 
         // Ajouter threads (auto-detect si None)
         let threads = job
-            .config
-            .encoder_params
-            .threads
+            .effective_threads()
             .unwrap_or_else(get_available_threads);
         cmd.arg(format!("--threads={threads}"));
 
+        // Métrique d'optimisation et réglages qualité propres à libaom
+        cmd.args(job.config.encoder_params.aom_tune_arg());
+        cmd.args(job.config.encoder_params.aom.args());
+
         // --ivf seulement pour la passe 2 (passe 1 écrit dans /dev/null)
         if pass == 2 {
             cmd.arg("--ivf");
@@ -621,6 +1871,12 @@ impl EncodingPipeline {
         for param in &job.config.encoder_params.extra_params {
             cmd.arg(param);
         }
+        cmd.args(
+            job.config
+                .keyframes
+                .encoder_args(EncoderType::Aom, Some(video_info.fps)),
+        );
+        cmd.args(video_info.output_color_args(EncoderType::Aom));
 
         // Source stdin en dernier (argument positionnel)
         cmd.arg("-");
@@ -628,14 +1884,52 @@ impl EncodingPipeline {
         cmd
     }
 
+    /// Encoder l'audio après avoir obtenu un emplacement du pool audio
+    async fn encode_audio_limited(
+        &self,
+        job: &EncodingJob,
+        video_info: &VideoInfo,
+        output: &Path,
+    ) -> Result<()> {
+        let _permit = self
+            .audio_slots
+            .acquire()
+            .await
+            .context("Pool d'encodage audio fermé")?;
+        self.encode_audio(job, video_info, output).await
+    }
+
     /// Encoder l'audio
-    async fn encode_audio(&self, job: &EncodingJob, output: &Path) -> Result<()> {
+    async fn encode_audio(
+        &self,
+        job: &EncodingJob,
+        video_info: &VideoInfo,
+        output: &Path,
+    ) -> Result<()> {
         info!("Encodage audio: {:?}", job.config.audio_mode);
+        let input_args = source_audio_input_args(&job.input_path, &job.config);
+        let languages: Vec<Option<String>> = video_info
+            .audio_streams
+            .iter()
+            .map(|s| s.language.clone())
+            .collect();
+        let streams = job.config.resolve_audio_streams(&languages);
+        if streams.is_none() && !job.config.audio_languages.is_empty() {
+            info!(
+                "Aucune piste audio en {}, toutes les pistes sont gardées",
+                job.config.audio_languages.join("/")
+            );
+        }
 
         match &job.config.audio_mode {
+            AudioMode::None => {}
             AudioMode::Opus { bitrate } => {
                 let mut cmd = Command::new(&self.ffmpeg_bin);
-                cmd.arg("-i")
+                // Tué si la tâche est interrompue (arrêt du daemon)
+                cmd.kill_on_drop(true);
+                cmd.envs(&job.config.env);
+                cmd.args(&input_args)
+                    .arg("-i")
                     .arg(&job.input_path)
                     .arg("-vn") // Pas de vidéo
                     .arg("-c:a")
@@ -643,70 +1937,77 @@ impl EncodingPipeline {
                     .arg("-b:a")
                     .arg(format!("{bitrate}k"));
 
-                // Sélectionner les streams audio spécifiques si configuré
-                if let Some(streams) = &job.config.audio_streams {
-                    for stream_idx in streams {
-                        cmd.arg("-map").arg(format!("0:a:{stream_idx}"));
-                    }
-                } else {
-                    cmd.arg("-map").arg("0:a"); // Tous les streams audio
-                }
+                // Sélectionner les streams audio (par index ou par langue) si configuré
+                cmd.args(audio_map_args(streams.as_deref()));
+                cmd.args(thread_args(self.tail_threads(job)));
 
                 cmd.arg(output);
 
-                let output = cmd.output().await.context("Échec de l'encodage audio")?;
+                let output = self
+                    .output_registered(job, &mut cmd)
+                    .await
+                    .context("Échec de l'encodage audio")?;
 
                 if !output.status.success() {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    anyhow::bail!("Encodage audio échoué: {stderr}");
+                    return Err(EncodeError::AudioFailed(stderr.into_owned()).into());
                 }
             }
             AudioMode::Copy => {
                 // Copie directe sans ré-encodage
                 let mut cmd = Command::new(&self.ffmpeg_bin);
-                cmd.arg("-i")
+                cmd.kill_on_drop(true);
+                cmd.envs(&job.config.env);
+                cmd.args(&input_args)
+                    .arg("-i")
                     .arg(&job.input_path)
                     .arg("-vn")
                     .arg("-c:a")
                     .arg("copy");
 
-                if let Some(streams) = &job.config.audio_streams {
-                    for stream_idx in streams {
-                        cmd.arg("-map").arg(format!("0:a:{stream_idx}"));
-                    }
-                } else {
-                    cmd.arg("-map").arg("0:a");
-                }
+                cmd.args(audio_map_args(streams.as_deref()));
+                cmd.args(thread_args(self.tail_threads(job)));
 
                 cmd.arg(output);
 
-                let output = cmd.output().await.context("Échec de la copie audio")?;
+                let output = self
+                    .output_registered(job, &mut cmd)
+                    .await
+                    .context("Échec de la copie audio")?;
 
                 if !output.status.success() {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    anyhow::bail!("Copie audio échouée: {stderr}");
+                    return Err(EncodeError::AudioFailed(stderr.into_owned()).into());
                 }
             }
             AudioMode::Custom { codec, bitrate } => {
                 // Custom codec
                 let mut cmd = Command::new(&self.ffmpeg_bin);
-                cmd.arg("-i")
+                cmd.kill_on_drop(true);
+                cmd.envs(&job.config.env);
+                cmd.args(&input_args)
+                    .arg("-i")
                     .arg(&job.input_path)
                     .arg("-vn")
                     .arg("-c:a")
                     .arg(codec)
                     .arg("-b:a")
-                    .arg(format!("{bitrate}k"))
-                    .arg(output);
+                    .arg(format!("{bitrate}k"));
+                // Sans sélection, ffmpeg garde sa piste audio par défaut
+                if streams.is_some() {
+                    cmd.args(audio_map_args(streams.as_deref()));
+                }
+                cmd.args(thread_args(self.tail_threads(job)));
+                cmd.arg(output);
 
-                let output = cmd
-                    .output()
+                let output = self
+                    .output_registered(job, &mut cmd)
                     .await
                     .context("Échec de l'encodage audio custom")?;
 
                 if !output.status.success() {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    anyhow::bail!("Encodage audio custom échoué: {stderr}");
+                    return Err(EncodeError::AudioFailed(stderr.into_owned()).into());
                 }
             }
         }
@@ -714,66 +2015,216 @@ impl EncodingPipeline {
         Ok(())
     }
 
-    /// Muxer vidéo + audio + sous-titres dans un MKV final
+    /// Muxer vidéo + audio + sous-titres + pochettes dans `muxed`, puis le déplacer vers
+    /// la sortie du job
+    ///
+    /// La sortie n'apparaît qu'une fois complète : un crash ou un échec pendant le
+    /// muxage laisse seulement le fichier temporaire (et une sortie précédente intacte).
     async fn mux_final(
         &self,
         job: &EncodingJob,
         video_path: &Path,
-        audio_path: &Path,
-        video_info: &VideoInfo,
+        audio_path: Option<&Path>,
+        subtitles: &[MuxedSubtitle],
+        covers: &[MuxedCover],
+        muxed: &Path,
     ) -> Result<()> {
         info!("Muxage final avec ffmpeg");
 
-        let mut cmd = Command::new(&self.ffmpeg_bin);
-
-        // Étape 1: Ajouter TOUS les inputs d'abord
-        cmd.arg("-y") // Écraser sans demander
-            .arg("-i")
-            .arg(video_path) // Input 0: Vidéo AV1
-            .arg("-i")
-            .arg(audio_path); // Input 1: Audio
+        let mut cmd = build_mux_command(
+            &self.ffmpeg_bin,
+            job,
+            video_path,
+            audio_path,
+            subtitles,
+            covers,
+            self.tail_threads(job),
+            muxed,
+        );
+        let output = self
+            .output_registered(job, &mut cmd)
+            .await
+            .context("Échec du muxage")?;
 
-        // Ajouter l'input source pour les sous-titres si nécessaire
-        if !video_info.subtitle_streams.is_empty() {
-            cmd.arg("-i").arg(&job.input_path); // Input 2: Source pour sous-titres
+        if !output.status.success() {
+            let _ = tokio::fs::remove_file(muxed).await;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(EncodeError::MuxFailed(stderr.into_owned()).into());
         }
+        move_atomically(muxed, &job.output_path).await?;
 
-        // Étape 2: Ajouter TOUS les -map ensuite
-        cmd.arg("-map")
-            .arg("0:v:0") // Vidéo du premier input
-            .arg("-map")
-            .arg("1:a:0"); // Audio du deuxième input
-
-        if !video_info.subtitle_streams.is_empty() {
-            if let Some(streams) = &job.config.subtitle_streams {
-                for stream_idx in streams {
-                    cmd.arg("-map").arg(format!("2:s:{stream_idx}"));
-                }
-            } else {
-                // Par défaut, copier tous les sous-titres
-                cmd.arg("-map").arg("2:s?");
+        info!("Muxage réussi");
+        Ok(())
+    }
+
+    /// Vérifier les fichiers produits par un job : non vides, lisibles par ffprobe et
+    /// de la durée attendue (à une seconde près)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur désignant la première sortie invalide.
+    pub async fn verify_outputs(
+        &self,
+        outcome: &EncodeOutcome,
+        env: &HashMap<String, String>,
+    ) -> Result<()> {
+        if outcome.outputs.is_empty() {
+            anyhow::bail!("Aucune sortie produite");
+        }
+        for (output, expected) in &outcome.outputs {
+            let size = tokio::fs::metadata(output)
+                .await
+                .map(|meta| meta.len())
+                .with_context(|| format!("Sortie introuvable: {}", output.display()))?;
+            if size == 0 {
+                anyhow::bail!("Sortie vide: {}", output.display());
             }
+            let durations = probe_durations(&self.ffprobe_bin, output, env).await?;
+            check_output_duration(expected.map(|d| d.as_secs_f64()), &durations)
+                .map_err(|e| anyhow::anyhow!("Sortie invalide {}: {e}", output.display()))?;
         }
+        Ok(())
+    }
 
-        // Étape 3: Options de codec (copie sans réencodage)
-        cmd.arg("-c:v").arg("copy").arg("-c:a").arg("copy");
-
-        if !video_info.subtitle_streams.is_empty() {
-            cmd.arg("-c:s").arg("copy");
+    /// Vérifier un fichier existant : probe puis décodage complet de la vidéo et de l'audio
+    ///
+    /// La vidéo AV1 est décodée par dav1d. Le rapport est retourné même si le fichier est
+    /// invalide ; c'est au job de le juger (voir [`VerifyReport::problems`]).
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le fichier est introuvable, si le probe échoue, si ffmpeg ne
+    /// démarre pas ou si la vérification est annulée.
+    pub async fn verify_file(
+        &self,
+        job: &EncodingJob,
+        stats_tx: mpsc::UnboundedSender<EncodingStats>,
+        cancel_rx: &mut mpsc::UnboundedReceiver<()>,
+    ) -> Result<VerifyReport, EncodeError> {
+        let path = &job.input_path;
+        info!("Vérification de {}", path.display());
+        if !path.exists() {
+            return Err(EncodeError::InputMissing(path.clone()));
         }
+        let video_info = self
+            .probe_cancellable(
+                path,
+                job.config.precise_frame_count_or(self.precise_frame_count),
+                false,
+                &job.config.env,
+                cancel_rx,
+            )
+            .await?;
 
-        // Étape 4: Output MKV
-        cmd.arg(&job.output_path);
+        let mut report = VerifyReport {
+            video_codec: video_info.video_codec.clone(),
+            width: video_info.width,
+            height: video_info.height,
+            duration_secs: video_info.duration.map(|d| d.as_secs_f64()),
+            fps: video_info.fps,
+            streams: verify_stream_summaries(&video_info),
+            ..VerifyReport::default()
+        };
 
-        let output = cmd.output().await.context("Échec du muxage")?;
+        let mut cmd = std::process::Command::new(&self.ffmpeg_bin);
+        isolate_process_group(&mut cmd);
+        cmd.envs(&job.config.env);
+        cmd.args(["-nostdin", "-hide_banner", "-loglevel", "error", "-stats"]);
+        if report.is_av1() {
+            cmd.args(["-c:v", "libdav1d"]);
+        }
+        cmd.args(source_input_args(path, &job.config))
+            .arg("-i")
+            .arg(path)
+            .args(["-map", "0:v:0", "-map", "0:a?", "-f", "null", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        let mut child = cmd
+            .spawn()
+            .context("Échec du démarrage de ffmpeg pour la vérification")
+            .map_err(EncodeError::Other)?;
+        let stderr = child
+            .stderr
+            .take()
+            .context("Impossible de prendre stderr de ffmpeg")
+            .map_err(EncodeError::Other)?;
+
+        // Dernière frame décodée et erreurs affichées par ffmpeg
+        let findings = Arc::new(std::sync::Mutex::new((None, Vec::new())));
+        let thread_findings = findings.clone();
+        let total_frames = video_info.total_frames;
+        let stderr_handle = spawn_ffmpeg_stderr_thread(stderr, move |line| {
+            let Some(frame) = parse_ffmpeg_frame(line) else {
+                if let Ok(mut findings) = thread_findings.lock() {
+                    if findings.1.len() < MAX_VERIFY_ERRORS {
+                        findings.1.push(line.to_string());
+                    }
+                }
+                return true;
+            };
+            if let Ok(mut findings) = thread_findings.lock() {
+                findings.0 = Some(frame);
+            }
+            let mut stats = EncodingStats {
+                frame,
+                total_frames,
+                phase: Some(EncodingPhase::Verify),
+                ..EncodingStats::default()
+            };
+            stats.update();
+            stats.calculate_overall(&[EncodingPhase::Verify]);
+            let _ = stats_tx.send(stats);
+            true
+        });
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Muxage ffmpeg échoué: {stderr}");
+        let pid = child.id();
+        self.processes.register(job.id, pid);
+        let wait_result = wait_for_process_with_cancellation(child, cancel_rx).await;
+        self.processes.unregister(job.id, pid);
+        if let Err(e) = stderr_handle.join() {
+            tracing::error!("Échec de jointure du thread stderr de vérification: {e:?}");
         }
 
-        info!("Muxage réussi");
-        Ok(())
+        let (decoded_frames, decode_errors) = std::mem::take(
+            &mut *findings
+                .lock()
+                .map_err(|_| EncodeError::Other(anyhow::anyhow!("Verrou empoisonné")))?,
+        );
+        report.decoded_frames = decoded_frames;
+        report.decode_errors = decode_errors;
+        match wait_result.map_err(EncodeError::from_anyhow) {
+            Err(EncodeError::Cancelled) => return Err(EncodeError::Cancelled),
+            Err(e) if report.decode_errors.is_empty() => report.decode_errors.push(e.to_string()),
+            _ => {}
+        }
+
+        info!("Vérification de {} : {}", path.display(), report.summary());
+        Ok(report)
+    }
+
+    /// Comparer les durées de la sortie (conteneur et streams) à celle de la source
+    ///
+    /// Une dérive révèle une désynchronisation audio/vidéo, typiquement sur une source VFR ;
+    /// elle est signalée sans faire échouer le job.
+    async fn check_av_sync(&self, job: &EncodingJob, video_info: &VideoInfo) {
+        let Some(expected) = video_info.duration.map(|d| d.as_secs_f64()) else {
+            return;
+        };
+        match probe_durations(&self.ffprobe_bin, &job.output_path, &job.config.env).await {
+            Ok(durations) => {
+                let drift = max_drift(expected, &durations);
+                if drift > SYNC_DRIFT_WARN_SECS {
+                    tracing::warn!(
+                        "Dérive de synchro sur {}: {drift:.2}s par rapport à la source ({expected:.2}s, sortie: {durations:?})",
+                        job.output_path.display()
+                    );
+                } else {
+                    tracing::debug!("Synchro vérifiée: dérive {drift:.3}s");
+                }
+            }
+            Err(e) => tracing::warn!("Vérification de la synchro impossible: {e}"),
+        }
     }
 
     /// Calculer le score VMAF en comparant la source et le fichier encodé frame par frame
@@ -795,36 +2246,27 @@ impl EncodingPipeline {
             is_calculating_vmaf: true,
             total_frames: video_info.total_frames,
             total_duration: video_info.duration,
+            phase: Some(EncodingPhase::Vmaf),
             ..EncodingStats::default()
         };
         let _ = stats_tx.send(vmaf_stats.clone());
 
         // Préparer le fichier JSON pour le log VMAF
-        let vmaf_log = {
-            let stem = job
-                .output_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("output");
-            job.output_path.with_file_name(format!("{stem}_vmaf.json"))
-        };
+        let vmaf_log = vmaf_log_path(&job.output_path);
 
         // Déterminer le nombre de threads
         let threads = job
-            .config
-            .encoder_params
-            .threads
+            .effective_threads()
             .unwrap_or_else(get_available_threads);
 
         // Construire et spawner ffmpeg pour VMAF
         let mut ffmpeg_cmd = build_vmaf_command(
             &self.ffmpeg_bin,
-            &job.input_path,
-            &job.output_path,
+            job,
             &vmaf_log,
             threads,
-            video_info.is_interlaced,
-        );
+            video_info.reference_filter().as_deref(),
+        )?;
         let mut ffmpeg_child = ffmpeg_cmd
             .spawn()
             .context("Échec du démarrage de ffmpeg pour VMAF")?;
@@ -842,7 +2284,11 @@ impl EncodingPipeline {
         );
 
         // Attendre avec annulation
-        wait_for_process_with_cancellation(ffmpeg_child, cancel_rx).await?;
+        let pid = ffmpeg_child.id();
+        self.processes.register(job.id, pid);
+        let wait_result = wait_for_process_with_cancellation(ffmpeg_child, cancel_rx).await;
+        self.processes.unregister(job.id, pid);
+        wait_result?;
 
         // Joindre le thread stderr
         if let Err(e) = stderr_handle.join() {
@@ -865,6 +2311,18 @@ impl EncodingPipeline {
         } else {
             tracing::warn!("Impossible d'extraire le score VMAF du JSON");
         }
+        let (psnr, ssim) = if job.config.quality_metrics {
+            parse_quality_metrics(&vmaf_json)
+        } else {
+            (None, None)
+        };
+        match (psnr, ssim) {
+            (Some(psnr), Some(ssim)) => info!("PSNR: {psnr:.2} dB, SSIM: {ssim:.4}"),
+            _ if job.config.quality_metrics => {
+                tracing::warn!("PSNR/SSIM absents du JSON VMAF (libvmaf trop ancien ?)");
+            }
+            _ => {}
+        }
 
         // Envoyer les résultats finaux
         vmaf_stats.is_calculating_vmaf = false;
@@ -872,7 +2330,9 @@ impl EncodingPipeline {
         vmaf_stats.vmaf_min = vmaf_min;
         vmaf_stats.vmaf_max = vmaf_max;
         vmaf_stats.vmaf_json_path = Some(vmaf_log.clone());
-        vmaf_stats.progress_percent = 100.0;
+        vmaf_stats.psnr = psnr;
+        vmaf_stats.ssim = ssim;
+        vmaf_stats.mark_finished();
         let _ = stats_tx.send(vmaf_stats);
 
         info!(
@@ -882,3 +2342,550 @@ impl EncodingPipeline {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::{AttachedPicInfo, SubtitleStreamInfo};
+    use encodetalker_common::CoverArtConfig;
+    use std::ffi::OsStr;
+
+    /// Vidéo factice : ne se termine que sur un arrêt, comme un encodeur tué
+    async fn video_until_aborted(
+        abort_rx: &mut mpsc::UnboundedReceiver<()>,
+        events: &std::sync::Mutex<Vec<&'static str>>,
+    ) -> Result<Duration, EncodeError> {
+        abort_rx.recv().await;
+        events.lock().unwrap().push("vidéo arrêtée");
+        Err(EncodeError::Cancelled)
+    }
+
+    #[tokio::test]
+    async fn test_audio_failure_stops_video_first() {
+        let events = std::sync::Mutex::new(Vec::new());
+        let (_cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+        let (abort_tx, mut abort_rx) = mpsc::unbounded_channel();
+        let audio = async {
+            events.lock().unwrap().push("audio échoué");
+            Err::<PathBuf, _>(EncodeError::AudioFailed("libopus".to_string()))
+        };
+        let mut audio = std::pin::pin!(audio);
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            video_with_audio(
+                video_until_aborted(&mut abort_rx, &events),
+                audio.as_mut(),
+                &mut cancel_rx,
+                &abort_tx,
+            ),
+        )
+        .await
+        .expect("l'échec de l'audio doit arrêter la vidéo");
+        // L'erreur retournée est celle de l'audio, une fois la vidéo arrêtée
+        assert!(matches!(result, Err(EncodeError::AudioFailed(_))));
+        assert_eq!(*events.lock().unwrap(), ["audio échoué", "vidéo arrêtée"]);
+    }
+
+    #[tokio::test]
+    async fn test_video_with_audio_keeps_finished_audio_and_relays_cancel() {
+        // Audio fini avant la vidéo : gardé pour la suite
+        let (_cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+        let (abort_tx, _abort_rx) = mpsc::unbounded_channel();
+        let video = async {
+            tokio::task::yield_now().await;
+            Ok(Duration::from_secs(1))
+        };
+        let mut audio = std::pin::pin!(async { Ok(PathBuf::from("audio.opus")) });
+        let (video_time, audio_done) =
+            video_with_audio(video, audio.as_mut(), &mut cancel_rx, &abort_tx)
+                .await
+                .unwrap();
+        assert_eq!(video_time, Duration::from_secs(1));
+        assert_eq!(audio_done, Some(PathBuf::from("audio.opus")));
+
+        // Annulation du job pendant que l'audio tourne encore : relayée à la vidéo
+        let events = std::sync::Mutex::new(Vec::new());
+        let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+        let (abort_tx, mut abort_rx) = mpsc::unbounded_channel();
+        let mut audio = std::pin::pin!(std::future::pending::<Result<PathBuf, EncodeError>>());
+        cancel_tx.send(()).unwrap();
+        let result = video_with_audio(
+            video_until_aborted(&mut abort_rx, &events),
+            audio.as_mut(),
+            &mut cancel_rx,
+            &abort_tx,
+        )
+        .await;
+        assert!(matches!(result, Err(EncodeError::Cancelled)));
+    }
+
+    #[test]
+    fn test_tail_threads_follow_job_share() {
+        assert_eq!(tail_threads(0, Some(8)), None);
+        assert_eq!(tail_threads(2, None), Some(2));
+        assert_eq!(tail_threads(2, Some(8)), Some(2));
+        // Part du job plus petite que le plafond
+        assert_eq!(tail_threads(4, Some(1)), Some(1));
+        assert_eq!(tail_threads(4, Some(0)), Some(1));
+        assert_eq!(thread_args(Some(2)), ["-threads", "2"]);
+        assert!(thread_args(None).is_empty());
+    }
+
+    #[test]
+    fn test_escape_filter_path_special_characters() {
+        assert_eq!(
+            escape_filter_path(Path::new("/out/Mes films/été_vmaf.json")).unwrap(),
+            "/out/Mes films/été_vmaf.json"
+        );
+        // `'` et `:` échappés pour l'option, puis l'ensemble (antislashs compris) pour le graphe
+        assert_eq!(
+            escape_filter_path(Path::new("/out/l'été: [1],x;y.json")).unwrap(),
+            r"/out/l\\\'été\\: \[1\]\,x\;y.json"
+        );
+    }
+
+    #[test]
+    fn test_check_output_duration() {
+        assert!(check_output_duration(Some(60.0), &[60.4, 59.9]).is_ok());
+        assert!(check_output_duration(None, &[12.0]).is_ok());
+        // Sortie tronquée, vide ou illisible
+        assert!(check_output_duration(Some(60.0), &[30.0]).is_err());
+        assert!(check_output_duration(Some(60.0), &[0.0]).is_err());
+        assert!(check_output_duration(None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_quality_metrics() {
+        let json = r#"{"pooled_metrics": {
+            "vmaf": {"min": 80.1, "max": 99.0, "mean": 94.2},
+            "psnr_y": {"min": 35.0, "max": 50.0, "mean": 42.37},
+            "float_ssim": {"min": 0.95, "max": 1.0, "mean": 0.9871}
+        }}"#;
+        assert_eq!(parse_quality_metrics(json), (Some(42.37), Some(0.9871)));
+        let (mean, _, _) = parse_vmaf_json_log(json).unwrap();
+        assert_eq!(mean, Some(94.2));
+
+        // VMAF seul : pas de PSNR/SSIM
+        assert_eq!(
+            parse_quality_metrics(r#"{"pooled_metrics": {"vmaf": {"mean": 94.2}}}"#),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_max_drift() {
+        assert!(max_drift(60.0, &[]).abs() < f64::EPSILON);
+        let drift = max_drift(60.0, &[60.02, 59.1, 60.0]);
+        assert!((drift - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_path_arguments_keep_original_name() {
+        assert_eq!(
+            vmaf_log_path(Path::new("/out/L'été \"2\".mkv")),
+            PathBuf::from("/out/L'été \"2\"_vmaf.json")
+        );
+        assert_eq!(
+            path_option("--fpf", Path::new("/tmp/mon job/pass.log")),
+            OsString::from("--fpf=/tmp/mon job/pass.log")
+        );
+    }
+
+    #[test]
+    fn test_mux_command_without_audio() {
+        let mut config = EncodingConfig {
+            audio_mode: AudioMode::None,
+            ..EncodingConfig::default()
+        };
+        config.subtitle_languages = vec!["fre".to_string()];
+        let job = EncodingJob::new(
+            PathBuf::from("/films/film.mkv"),
+            PathBuf::from("/films/film.av1.mkv"),
+            config,
+        );
+        let video_info = VideoInfo {
+            subtitle_streams: vec![
+                SubtitleStreamInfo {
+                    index: 3,
+                    codec: "subrip".to_string(),
+                    language: Some("eng".to_string()),
+                    title: None,
+                },
+                SubtitleStreamInfo {
+                    index: 4,
+                    codec: "subrip".to_string(),
+                    language: Some("fre".to_string()),
+                    title: None,
+                },
+            ],
+            ..VideoInfo::default()
+        };
+        let subtitles = plan_subtitles(&job, &video_info).unwrap();
+        let args = |audio: Option<&Path>| -> Vec<String> {
+            build_mux_command(
+                Path::new("ffmpeg"),
+                &job,
+                Path::new("/tmp/video.ivf"),
+                audio,
+                &subtitles,
+                &[],
+                None,
+                &job.output_path,
+            )
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+        };
+
+        // Vidéo seule : pas d'input ni de -map audio, sous-titres en input 1
+        let video_only = args(None);
+        assert_eq!(video_only.iter().filter(|a| *a == "-i").count(), 2);
+        assert!(!video_only
+            .iter()
+            .any(|a| a == "-c:a" || a.starts_with("1:a")));
+        assert!(video_only.iter().any(|a| a == "1:s:1"));
+
+        let with_audio = args(Some(Path::new("/tmp/audio.opus")));
+        assert_eq!(with_audio.iter().filter(|a| *a == "-i").count(), 3);
+        assert!(with_audio.iter().any(|a| a == "1:a"));
+        assert!(with_audio.iter().any(|a| a == "2:s:1"));
+    }
+
+    #[test]
+    fn test_job_env_reaches_mux_command() {
+        let mut job = EncodingJob::new(
+            PathBuf::from("/films/film.mkv"),
+            PathBuf::from("/films/film.av1.mkv"),
+            EncodingConfig::default(),
+        );
+        job.config
+            .env
+            .insert("FFREPORT".to_string(), "level=32".to_string());
+        let cmd = build_mux_command(
+            Path::new("ffmpeg"),
+            &job,
+            Path::new("/tmp/video.ivf"),
+            Some(Path::new("/tmp/audio.opus")),
+            &[],
+            &[],
+            None,
+            &job.output_path,
+        );
+        let envs: Vec<_> = cmd.as_std().get_envs().collect();
+        assert_eq!(
+            envs,
+            [(OsStr::new("FFREPORT"), Some(OsStr::new("level=32")))]
+        );
+    }
+
+    #[test]
+    fn test_cover_art_mux() {
+        let pic = |index, codec: &str| AttachedPicInfo {
+            index,
+            codec: codec.to_string(),
+            filename: None,
+        };
+        let video_info = VideoInfo {
+            attached_pics: vec![pic(3, "mjpeg"), pic(4, "webp")],
+            ..VideoInfo::default()
+        };
+        let job = |output: &str, mode: CoverArtMode| {
+            let config = EncodingConfig {
+                cover_art: CoverArtConfig {
+                    mode,
+                    ..CoverArtConfig::default()
+                },
+                ..EncodingConfig::default()
+            };
+            EncodingJob::new(
+                PathBuf::from("/films/film.mkv"),
+                PathBuf::from(output),
+                config,
+            )
+        };
+
+        // MP4 : la pochette WebP est retirée ; auto n'extrait rien s'il reste une pochette
+        let (covers, poster) = plan_covers(&job("/out/film.mp4", CoverArtMode::Auto), &video_info);
+        assert_eq!(covers.len(), 1);
+        assert!(!poster);
+        let (covers, poster) = plan_covers(&job("/out/film.mkv", CoverArtMode::Copy), &video_info);
+        assert_eq!(covers.len(), 2);
+        assert!(!poster);
+        let (covers, poster) = plan_covers(
+            &job("/out/film.mkv", CoverArtMode::Auto),
+            &VideoInfo::default(),
+        );
+        assert!(covers.is_empty() && poster);
+        assert_eq!(
+            plan_covers(&job("/out/film.webm", CoverArtMode::Poster), &video_info),
+            (Vec::new(), false)
+        );
+
+        let mkv = job("/out/film.mkv", CoverArtMode::Auto);
+        let (mut covers, _) = plan_covers(&mkv, &video_info);
+        covers.push(MuxedCover::Poster(PathBuf::from("/tmp/poster.png")));
+        let args: Vec<String> = build_mux_command(
+            Path::new("ffmpeg"),
+            &mkv,
+            Path::new("/tmp/video.ivf"),
+            Some(Path::new("/tmp/audio.opus")),
+            &[],
+            &covers,
+            None,
+            &mkv.output_path,
+        )
+        .as_std()
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+        // Source en input 2 (une seule fois pour ses deux pochettes), frame extraite en 3
+        assert_eq!(args.iter().filter(|a| *a == "-i").count(), 4);
+        assert!(args.windows(2).any(|w| w == ["-map", "2:3"]));
+        assert!(args.windows(2).any(|w| w == ["-map", "2:4"]));
+        assert!(args.windows(2).any(|w| w == ["-map", "3:v:0"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-disposition:v:1", "attached_pic"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-metadata:s:v:1", "mimetype=image/jpeg"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-metadata:s:v:2", "filename=cover_2.webp"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-metadata:s:v:3", "filename=cover_3.png"]));
+    }
+
+    #[test]
+    fn test_incompatible_subtitles_policy() {
+        let subtitle = |codec: &str| SubtitleStreamInfo {
+            index: 2,
+            codec: codec.to_string(),
+            language: None,
+            title: None,
+        };
+        let video_info = VideoInfo {
+            subtitle_streams: vec![subtitle("subrip"), subtitle("hdmv_pgs_subtitle")],
+            ..VideoInfo::default()
+        };
+        let job = |output: &str, policy: SubtitlePolicy| {
+            let config = EncodingConfig {
+                incompatible_subtitles: policy,
+                ..EncodingConfig::default()
+            };
+            EncodingJob::new(
+                PathBuf::from("/films/film.mkv"),
+                PathBuf::from(output),
+                config,
+            )
+        };
+        let muxed = |stream, codec| MuxedSubtitle { stream, codec };
+
+        // Matroska : tout est copié, quelle que soit la politique
+        assert_eq!(
+            plan_subtitles(&job("/out/film.mkv", SubtitlePolicy::Fail), &video_info).unwrap(),
+            [muxed(0, "copy"), muxed(1, "copy")]
+        );
+        // MP4 : SRT converti en mov_text, PGS inconvertible retiré
+        let convert = job("/out/film.mp4", SubtitlePolicy::Convert);
+        let subtitles = plan_subtitles(&convert, &video_info).unwrap();
+        assert_eq!(subtitles, [muxed(0, "mov_text")]);
+        let args: Vec<String> = build_mux_command(
+            Path::new("ffmpeg"),
+            &convert,
+            Path::new("/tmp/video.ivf"),
+            None,
+            &subtitles,
+            &[],
+            None,
+            &convert.output_path,
+        )
+        .as_std()
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+        assert!(args.windows(2).any(|w| w == ["-map", "1:s:0"]));
+        assert!(args.windows(2).any(|w| w == ["-c:s:0", "mov_text"]));
+        assert!(!args.iter().any(|a| a == "1:s:1"));
+
+        assert!(
+            plan_subtitles(&job("/out/film.mp4", SubtitlePolicy::Drop), &video_info)
+                .unwrap()
+                .is_empty()
+        );
+        let err =
+            plan_subtitles(&job("/out/film.webm", SubtitlePolicy::Fail), &video_info).unwrap_err();
+        assert!(matches!(
+            err,
+            EncodeError::IncompatibleSubtitles { ref codec, ref container }
+                if codec == "subrip" && container == "webm"
+        ));
+    }
+
+    #[test]
+    fn test_hardware_encode_command() {
+        let mut config = EncodingConfig {
+            encoder: EncoderType::Vaapi,
+            ..EncodingConfig::default()
+        };
+        config.trim.start = Some(60.0);
+        let job = EncodingJob::new(
+            PathBuf::from("/films/film.mkv"),
+            PathBuf::from("/films/film.av1.mkv"),
+            config,
+        );
+        let video_info = VideoInfo {
+            crop_filter: Some("crop=1918:816".to_string()),
+            ..VideoInfo::default()
+        };
+        let cmd = build_hardware_encode_command(
+            Path::new("ffmpeg"),
+            &job,
+            &video_info,
+            Path::new("/dev/dri/renderD129"),
+            Path::new("/tmp/video.ivf"),
+        );
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let position = |arg: &str| args.iter().position(|a| a == arg).unwrap();
+
+        assert_eq!(args[position("-vaapi_device") + 1], "/dev/dri/renderD129");
+        assert!(position("-vaapi_device") < position("-i"));
+        assert!(position("-ss") < position("-i"));
+        assert_eq!(
+            args[position("-vf") + 1],
+            format!("crop=1918:816,{VAAPI_UPLOAD_FILTER}")
+        );
+        assert_eq!(args[position("-c:v") + 1], "av1_vaapi");
+        assert_eq!(args[position("-progress") + 1], "pipe:2");
+        assert_eq!(args.last().unwrap(), "/tmp/video.ivf");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_paths() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let output = Path::new(std::ffi::OsStr::from_bytes(b"/out/vid\xe9o.mkv"));
+        assert_eq!(
+            vmaf_log_path(output).as_os_str().as_bytes(),
+            b"/out/vid\xe9o_vmaf.json"
+        );
+        assert!(path_option("--fpf", output)
+            .as_bytes()
+            .ends_with(b"vid\xe9o.mkv"));
+        assert!(escape_filter_path(output).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancel_during_precise_frame_count() {
+        use crate::encoder::write_fake_bin;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        // Sans nb_frames : le comptage précis lance ffmpeg, qui ne se termine jamais
+        let ffprobe = write_fake_bin(
+            dir,
+            "ffprobe",
+            r#"echo '{"format":{"duration":"60"},"streams":[{"index":0,"codec_type":"video","codec_name":"h264","width":1920,"height":1080,"r_frame_rate":"25/1","avg_frame_rate":"25/1","field_order":"progressive"}]}'"#,
+        );
+        let ffmpeg = write_fake_bin(dir, "ffmpeg", "exec sleep 30");
+        let input = dir.join("film.mkv");
+        std::fs::write(&input, b"").unwrap();
+
+        // Délai dépassé : repli sur l'estimation durée × fps
+        let info = probe_video(
+            &ffprobe,
+            &ffmpeg,
+            &input,
+            Some(Duration::from_millis(200)),
+            false,
+            &HashMap::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(info.total_frames, Some(1500));
+
+        let pipeline = EncodingPipeline::new(
+            ffmpeg.clone(),
+            ffprobe,
+            PathBuf::from("SvtAv1EncApp"),
+            PathBuf::from("aomenc"),
+            true,
+            Duration::from_millis(250),
+        );
+        let job = EncodingJob::new(input, dir.join("film.av1.mkv"), EncodingConfig::default());
+        let (stats_tx, _stats_rx) = mpsc::unbounded_channel();
+        let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+        let cancel = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let _ = cancel_tx.send(());
+            cancel_tx
+        });
+
+        let started = Instant::now();
+        let result = pipeline.encode_job(&job, stats_tx, &mut cancel_rx).await;
+        assert!(matches!(result, Err(EncodeError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        drop(cancel.await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_mux_failure_keeps_previous_output() {
+        use crate::encoder::write_fake_bin;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        // ffmpeg interrompu en cours d'écriture : données partielles puis échec
+        let failing = write_fake_bin(
+            dir,
+            "ffmpeg-failing",
+            r#"for last; do :; done; printf partiel > "$last"; exit 1"#,
+        );
+        let working = write_fake_bin(
+            dir,
+            "ffmpeg-working",
+            r#"for last; do :; done; printf complet > "$last""#,
+        );
+        let pipeline = |ffmpeg: &Path| {
+            EncodingPipeline::new(
+                ffmpeg.to_path_buf(),
+                PathBuf::from("ffprobe"),
+                PathBuf::from("SvtAv1EncApp"),
+                PathBuf::from("aomenc"),
+                true,
+                Duration::from_millis(250),
+            )
+        };
+        let output = dir.join("film.av1.mkv");
+        std::fs::write(&output, b"ancien").unwrap();
+        let job = EncodingJob::new(
+            dir.join("film.mkv"),
+            output.clone(),
+            EncodingConfig::default(),
+        );
+        let video = dir.join("video.ivf");
+        let muxed = dir.join("temp").join("mux.mkv");
+        std::fs::create_dir_all(muxed.parent().unwrap()).unwrap();
+
+        let result = pipeline(&failing)
+            .mux_final(&job, &video, None, &[], &[], &muxed)
+            .await;
+        assert!(result.is_err());
+        assert_eq!(std::fs::read(&output).unwrap(), b"ancien");
+        assert!(!muxed.exists());
+
+        pipeline(&working)
+            .mux_final(&job, &video, None, &[], &[], &muxed)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), b"complet");
+        assert!(!muxed.exists());
+    }
+}
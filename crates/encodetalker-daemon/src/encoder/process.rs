@@ -0,0 +1,246 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Plage de niceness acceptée par `setpriority`
+pub const NICE_MIN: i32 = -20;
+pub const NICE_MAX: i32 = 19;
+
+#[derive(Debug, Default)]
+struct JobProcesses {
+    pids: Vec<u32>,
+    nice: Option<i32>,
+}
+
+/// Registre des processus enfants (ffmpeg, encodeurs) de chaque job actif
+///
+/// Permet d'appliquer une priorité à tous les enfants d'un job, y compris ceux
+/// lancés après le changement (passe 2 d'aomenc, VMAF...).
+#[derive(Debug, Clone, Default)]
+pub struct ProcessRegistry {
+    inner: Arc<Mutex<HashMap<Uuid, JobProcesses>>>,
+}
+
+impl ProcessRegistry {
+    /// Enregistrer un processus enfant d'un job (applique la niceness courante du job)
+    ///
+    /// # Panics
+    ///
+    /// Panique si le mutex interne est empoisonné.
+    pub fn register(&self, job_id: Uuid, pid: u32) {
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner.entry(job_id).or_default();
+        entry.pids.push(pid);
+        if let Some(nice) = entry.nice {
+            if let Err(e) = set_process_nice(pid, nice) {
+                tracing::warn!("Impossible d'appliquer nice {nice} au PID {pid}: {e}");
+            }
+        }
+    }
+
+    /// Retirer un processus terminé
+    ///
+    /// # Panics
+    ///
+    /// Panique si le mutex interne est empoisonné.
+    pub fn unregister(&self, job_id: Uuid, pid: u32) {
+        if let Some(entry) = self.inner.lock().unwrap().get_mut(&job_id) {
+            entry.pids.retain(|p| *p != pid);
+        }
+    }
+
+    /// Oublier un job terminé
+    ///
+    /// # Panics
+    ///
+    /// Panique si le mutex interne est empoisonné.
+    pub fn remove_job(&self, job_id: Uuid) {
+        self.inner.lock().unwrap().remove(&job_id);
+    }
+
+    /// PIDs actuellement enregistrés pour un job
+    ///
+    /// # Panics
+    ///
+    /// Panique si le mutex interne est empoisonné.
+    #[must_use]
+    pub fn pids(&self, job_id: Uuid) -> Vec<u32> {
+        self.inner
+            .lock()
+            .unwrap()
+            .get(&job_id)
+            .map(|e| e.pids.clone())
+            .unwrap_or_default()
+    }
+
+    /// Tuer tous les processus enregistrés, avec leur groupe (arrêt du daemon)
+    ///
+    /// Les tâches d'encodage attendent leurs enfants dans des threads bloquants : les
+    /// annuler ne tue pas ffmpeg ni l'encodeur. Retourne le nombre de processus visés.
+    ///
+    /// # Panics
+    ///
+    /// Panique si le mutex interne est empoisonné.
+    pub fn kill_all(&self) -> usize {
+        let inner = self.inner.lock().unwrap();
+        let pids: Vec<u32> = inner
+            .values()
+            .flat_map(|e| e.pids.iter().copied())
+            .collect();
+        for pid in &pids {
+            if let Err(e) = kill_process_tree(*pid) {
+                tracing::warn!("Impossible de tuer le PID {pid}: {e}");
+            }
+        }
+        pids.len()
+    }
+
+    /// Changer la niceness de tous les processus d'un job
+    ///
+    /// Un processus terminé mais pas encore retiré du registre est ignoré.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la valeur est hors plage ou si `setpriority` échoue
+    /// (baisser la niceness nécessite des privilèges).
+    ///
+    /// # Panics
+    ///
+    /// Panique si le mutex interne est empoisonné.
+    pub fn set_nice(&self, job_id: Uuid, nice: i32) -> Result<()> {
+        if !(NICE_MIN..=NICE_MAX).contains(&nice) {
+            anyhow::bail!("Niceness {nice} hors plage ({NICE_MIN} à {NICE_MAX})");
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner.entry(job_id).or_default();
+        for pid in &entry.pids {
+            set_process_nice(*pid, nice)?;
+        }
+        entry.nice = Some(nice);
+        Ok(())
+    }
+}
+
+/// Placer un processus enfant dans son propre groupe (Unix)
+///
+/// Le groupe peut alors être tué d'un coup avec ses sous-processus, et un Ctrl+C dans
+/// le terminal du daemon n'interrompt pas l'enfant avant l'arrêt ordonné.
+pub fn isolate_process_group(cmd: &mut std::process::Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Tuer un processus et ses descendants
+#[cfg(unix)]
+fn kill_process_tree(pid: u32) -> Result<()> {
+    let pid = i32::try_from(pid)?;
+    // SAFETY: appels système sans pointeur ; un PID négatif vise le groupe du processus
+    let ret = unsafe { libc::kill(-pid, libc::SIGKILL) };
+    if ret == 0 {
+        return Ok(());
+    }
+    // Processus hors de son propre groupe : le tuer seul
+    // SAFETY: idem
+    if unsafe { libc::kill(pid, libc::SIGKILL) } != 0 {
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::ESRCH) {
+            anyhow::bail!("kill({pid}) a échoué: {err}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn kill_process_tree(pid: u32) -> Result<()> {
+    let status = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("taskkill /PID {pid} a échoué");
+    }
+    Ok(())
+}
+
+/// Appliquer une niceness à un processus (sans effet s'il est déjà terminé)
+#[cfg(unix)]
+fn set_process_nice(pid: u32, nice: i32) -> Result<()> {
+    // SAFETY: appel système sans pointeur, PID fourni par std::process::Child::id
+    let ret = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid, nice) };
+    if ret != 0 {
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::ESRCH) {
+            return Ok(());
+        }
+        anyhow::bail!("setpriority({pid}, {nice}) a échoué: {err}");
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_process_nice(_pid: u32, _nice: i32) -> Result<()> {
+    anyhow::bail!("Changement de priorité non supporté sur cette plateforme")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_tracks_pids_and_nice() {
+        let registry = ProcessRegistry::default();
+
+        // Job sans processus : seule la plage est vérifiée
+        let idle_job = Uuid::new_v4();
+        assert!(registry.set_nice(idle_job, 42).is_err());
+        assert!(registry.set_nice(idle_job, 5).is_ok());
+
+        let job_id = Uuid::new_v4();
+        registry.register(job_id, 1234);
+        registry.register(job_id, 5678);
+        registry.unregister(job_id, 1234);
+        assert_eq!(registry.pids(job_id), vec![5678]);
+
+        registry.remove_job(job_id);
+        assert!(registry.pids(job_id).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_set_nice_skips_exited_process() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+
+        // Processus terminé et réclamé, encore enregistré : ignoré
+        let registry = ProcessRegistry::default();
+        let job_id = Uuid::new_v4();
+        registry.register(job_id, pid);
+        assert!(registry.set_nice(job_id, 10).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_all_reaps_long_running_child() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // Le shell lance lui-même un sous-processus, tué avec le groupe
+        let mut cmd = std::process::Command::new("sh");
+        cmd.arg("-c").arg("sleep 30 & wait");
+        isolate_process_group(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+
+        let registry = ProcessRegistry::default();
+        registry.register(Uuid::new_v4(), child.id());
+        assert_eq!(registry.kill_all(), 1);
+
+        let status = child.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+}
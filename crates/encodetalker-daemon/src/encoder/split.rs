@@ -0,0 +1,214 @@
+use super::ChapterInfo;
+use encodetalker_common::{SplitMode, TrimRange};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Segment de la source encodé dans son propre fichier
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Position du segment (1-based)
+    pub index: usize,
+    /// Début en secondes
+    pub start: f64,
+    /// Fin en secondes
+    pub end: f64,
+    /// Titre du chapitre (mode chapitres uniquement)
+    pub title: Option<String>,
+}
+
+impl Segment {
+    /// Plage de découpe correspondant au segment
+    #[must_use]
+    pub fn trim(&self) -> TrimRange {
+        TrimRange {
+            start: Some(self.start),
+            end: Some(self.end),
+        }
+    }
+}
+
+/// Calculer les segments à encoder, restreints à la plage de découpe du job
+///
+/// Retourne une liste vide si le mode ne découpe pas, si la source n'a pas de
+/// chapitres, ou si sa durée est inconnue en mode intervalle.
+#[must_use]
+pub fn plan_segments(
+    split: SplitMode,
+    trim: &TrimRange,
+    chapters: &[ChapterInfo],
+    duration: Option<Duration>,
+) -> Vec<Segment> {
+    let range_start = trim.start.unwrap_or(0.0);
+    let range_end = match (trim.end, duration) {
+        (Some(end), Some(total)) => end.min(total.as_secs_f64()),
+        (Some(end), None) => end,
+        (None, Some(total)) => total.as_secs_f64(),
+        (None, None) => f64::INFINITY,
+    };
+
+    let raw: Vec<(f64, f64, Option<String>)> = match split {
+        SplitMode::None => return Vec::new(),
+        SplitMode::Chapters => chapters
+            .iter()
+            .map(|c| (c.start, c.end, c.title.clone()))
+            .collect(),
+        SplitMode::Interval { secs } => {
+            if secs == 0 || !range_end.is_finite() {
+                return Vec::new();
+            }
+            let step = f64::from(secs);
+            let mut bounds = Vec::new();
+            let mut start = range_start;
+            while start < range_end {
+                bounds.push((start, (start + step).min(range_end), None));
+                start += step;
+            }
+            bounds
+        }
+    };
+
+    raw.into_iter()
+        .filter_map(|(start, end, title)| {
+            let start = start.max(range_start);
+            let end = end.min(range_end);
+            (end > start).then_some((start, end, title))
+        })
+        .enumerate()
+        .map(|(i, (start, end, title))| Segment {
+            index: i + 1,
+            start,
+            end,
+            title,
+        })
+        .collect()
+}
+
+/// Chemin de sortie d'un segment
+///
+/// Les jetons `{index}` (numéro sur 2 chiffres) et `{chapter}` (titre du chapitre)
+/// du nom de fichier sont remplacés ; sans jeton, le numéro est ajouté avant l'extension.
+#[must_use]
+pub fn segment_output_path(output: &Path, segment: &Segment) -> PathBuf {
+    let index = format!("{:02}", segment.index);
+    let file_name = output
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let name = if file_name.contains("{index}") || file_name.contains("{chapter}") {
+        let chapter = segment
+            .title
+            .as_deref()
+            .map_or_else(|| format!("Chapitre {index}"), sanitize_file_name);
+        OsString::from(
+            file_name
+                .replace("{index}", &index)
+                .replace("{chapter}", &chapter),
+        )
+    } else {
+        // Sans jeton, le nom d'origine est conservé tel quel (même non UTF-8)
+        let mut name = output.file_stem().map(OsString::from).unwrap_or_default();
+        name.push(format!("_{index}"));
+        if let Some(ext) = output.extension() {
+            name.push(".");
+            name.push(ext);
+        }
+        name
+    };
+
+    output.with_file_name(name)
+}
+
+/// Remplacer les caractères interdits dans un nom de fichier
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(start: f64, end: f64, title: &str) -> ChapterInfo {
+        ChapterInfo {
+            start,
+            end,
+            title: Some(title.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_plan_segments_by_chapters_respects_trim() {
+        let chapters = vec![
+            chapter(0.0, 60.0, "Intro"),
+            chapter(60.0, 600.0, "Épisode 1"),
+            chapter(600.0, 1200.0, "Épisode 2"),
+        ];
+        let trim = TrimRange {
+            start: Some(30.0),
+            end: Some(900.0),
+        };
+
+        let segments = plan_segments(
+            SplitMode::Chapters,
+            &trim,
+            &chapters,
+            Some(Duration::from_secs(1200)),
+        );
+        assert_eq!(segments.len(), 3);
+        assert!((segments[0].start - 30.0).abs() < f64::EPSILON);
+        assert!((segments[2].end - 900.0).abs() < f64::EPSILON);
+        assert_eq!(segments[1].title.as_deref(), Some("Épisode 1"));
+    }
+
+    #[test]
+    fn test_plan_segments_by_interval() {
+        let segments = plan_segments(
+            SplitMode::Interval { secs: 600 },
+            &TrimRange::default(),
+            &[],
+            Some(Duration::from_secs(1500)),
+        );
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[2].index, 3);
+        assert!((segments[2].end - 1500.0).abs() < f64::EPSILON);
+
+        // Durée inconnue : impossible de découper
+        let segments = plan_segments(
+            SplitMode::Interval { secs: 600 },
+            &TrimRange::default(),
+            &[],
+            None,
+        );
+        assert!(segments.is_empty());
+    }
+
+    #[test]
+    fn test_segment_output_path() {
+        let segment = Segment {
+            index: 2,
+            start: 0.0,
+            end: 10.0,
+            title: Some("Le retour: partie 2".to_string()),
+        };
+        assert_eq!(
+            segment_output_path(Path::new("/out/show.av1.mkv"), &segment),
+            PathBuf::from("/out/show.av1_02.mkv")
+        );
+        assert_eq!(
+            segment_output_path(Path::new("/out/show - {index} - {chapter}.mkv"), &segment),
+            PathBuf::from("/out/show - 02 - Le retour_ partie 2.mkv")
+        );
+        assert_eq!(
+            segment_output_path(Path::new("/out/Séries/L'été \"final\".mkv"), &segment),
+            PathBuf::from("/out/Séries/L'été \"final\"_02.mkv")
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use encodetalker_common::{source_input_args, EncodingConfig};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+
+/// Largeur des miniatures (la hauteur suit le ratio de la source)
+pub const THUMBNAIL_WIDTH: u32 = 320;
+
+/// Nombre de miniatures gardées en mémoire
+const THUMBNAIL_CACHE_CAPACITY: usize = 64;
+
+/// Durée maximale d'une extraction (source distante, seek lent)
+const THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Extraire une frame de `input` à `timestamp` secondes, en PNG
+///
+/// Le PNG (RGB 8 bits) n'est ni filtré ni compressé (`-pred none`, blocs zlib stockés) :
+/// un client peut relire les pixels sans décodeur zlib, pour les terminaux sixel.
+///
+/// # Errors
+///
+/// Retourne une erreur si ffmpeg échoue, dépasse le délai ou ne produit aucune frame.
+pub async fn extract_thumbnail(ffmpeg_bin: &Path, input: &Path, timestamp: f64) -> Result<Vec<u8>> {
+    let mut cmd = tokio::process::Command::new(ffmpeg_bin);
+    cmd.kill_on_drop(true)
+        .arg("-nostdin")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-ss")
+        .arg(format!("{:.3}", timestamp.max(0.0)))
+        .args(source_input_args(input, &EncodingConfig::default()))
+        .arg("-i")
+        .arg(input)
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-vf")
+        .arg(format!("scale={THUMBNAIL_WIDTH}:-2"))
+        .arg("-pix_fmt")
+        .arg("rgb24")
+        .arg("-c:v")
+        .arg("png")
+        .arg("-pred")
+        .arg("none")
+        .arg("-compression_level")
+        .arg("0")
+        .arg("-f")
+        .arg("image2pipe")
+        .arg("-");
+
+    let output = tokio::time::timeout(THUMBNAIL_TIMEOUT, cmd.output())
+        .await
+        .context("Délai d'extraction de la miniature dépassé")?
+        .context("Échec de l'exécution de ffmpeg")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("ffmpeg a échoué: {}", stderr.trim());
+    }
+    if output.stdout.is_empty() {
+        anyhow::bail!("Aucune frame à {timestamp:.1}s");
+    }
+    Ok(output.stdout)
+}
+
+/// Extraire en PNG pleine résolution la frame de `input` à `timestamp` secondes, comme
+/// pochette d'un job
+///
+/// `filter` est la chaîne de filtres du décodage (désentrelacement, recadrage) : la
+/// pochette a le cadrage de la vidéo encodée. `env` complète l'environnement de ffmpeg
+/// (variables du job).
+///
+/// # Errors
+///
+/// Retourne une erreur si ffmpeg échoue, dépasse le délai ou ne produit aucune frame.
+pub async fn extract_poster(
+    ffmpeg_bin: &Path,
+    input: &Path,
+    timestamp: f64,
+    filter: Option<&str>,
+    output: &Path,
+    env: &HashMap<String, String>,
+) -> Result<()> {
+    let mut cmd = tokio::process::Command::new(ffmpeg_bin);
+    cmd.kill_on_drop(true)
+        .envs(env)
+        .arg("-nostdin")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-y")
+        .arg("-ss")
+        .arg(format!("{:.3}", timestamp.max(0.0)))
+        .args(source_input_args(input, &EncodingConfig::default()))
+        .arg("-i")
+        .arg(input)
+        // Vidéo principale, pas une pochette existante
+        .arg("-map")
+        .arg("0:V:0")
+        .arg("-frames:v")
+        .arg("1");
+    if let Some(filter) = filter {
+        cmd.arg("-vf").arg(filter);
+    }
+    cmd.arg("-c:v").arg("png").arg(output);
+
+    let result = tokio::time::timeout(THUMBNAIL_TIMEOUT, cmd.output())
+        .await
+        .context("Délai d'extraction de la pochette dépassé")?
+        .context("Échec de l'exécution de ffmpeg")?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        anyhow::bail!("ffmpeg a échoué: {}", stderr.trim());
+    }
+    if tokio::fs::metadata(output).await.map_or(0, |m| m.len()) == 0 {
+        anyhow::bail!("Aucune frame à {timestamp:.1}s");
+    }
+    Ok(())
+}
+
+/// Clé de cache : la miniature est invalidée quand le fichier est modifié
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ThumbnailKey {
+    path: PathBuf,
+    modified: SystemTime,
+    /// Position en millisecondes (les f64 ne sont pas hashables)
+    timestamp_ms: u64,
+}
+
+impl ThumbnailKey {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Safe: position >= 0
+    fn new(path: &Path, modified: SystemTime, timestamp: f64) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            modified,
+            timestamp_ms: (timestamp.max(0.0) * 1000.0).round() as u64,
+        }
+    }
+}
+
+/// Miniatures déjà extraites, par chemin, date de modification et position
+#[derive(Debug, Default)]
+struct ThumbnailStore {
+    entries: HashMap<ThumbnailKey, Vec<u8>>,
+    /// Ordre d'insertion, pour évincer les plus anciennes
+    order: VecDeque<ThumbnailKey>,
+}
+
+impl ThumbnailStore {
+    fn get(&self, key: &ThumbnailKey) -> Option<Vec<u8>> {
+        self.entries.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: ThumbnailKey, png: Vec<u8>) {
+        // Une version plus ancienne du même fichier ne resservira pas
+        self.order
+            .retain(|k| k.path != key.path || k.modified == key.modified);
+        self.entries
+            .retain(|k, _| k.path != key.path || k.modified == key.modified);
+
+        if self.entries.insert(key.clone(), png).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > THUMBNAIL_CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    /// Taille totale des miniatures (octets) et nombre de miniatures
+    fn usage(&self) -> (u64, usize) {
+        let bytes = self.entries.values().map(|png| png.len() as u64).sum();
+        (bytes, self.entries.len())
+    }
+}
+
+/// Cache des miniatures de l'explorateur de fichiers
+pub struct ThumbnailCache {
+    ffmpeg_bin: PathBuf,
+    store: Mutex<ThumbnailStore>,
+}
+
+impl ThumbnailCache {
+    #[must_use]
+    pub fn new(ffmpeg_bin: PathBuf) -> Self {
+        Self {
+            ffmpeg_bin,
+            store: Mutex::new(ThumbnailStore::default()),
+        }
+    }
+
+    /// Mémoire occupée par le cache : octets et nombre de miniatures
+    pub async fn usage(&self) -> (u64, usize) {
+        self.store.lock().await.usage()
+    }
+
+    /// Vider le cache ; retourne ce qu'il occupait (voir [`ThumbnailCache::usage`])
+    pub async fn clear(&self) -> (u64, usize) {
+        let mut store = self.store.lock().await;
+        let usage = store.usage();
+        *store = ThumbnailStore::default();
+        usage
+    }
+
+    /// Obtenir la miniature de `path` à `timestamp` (depuis le cache si le fichier n'a pas changé)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le fichier est introuvable ou si l'extraction échoue.
+    pub async fn get(&self, path: &Path, timestamp: f64) -> Result<Vec<u8>> {
+        let modified = tokio::fs::metadata(path)
+            .await
+            .and_then(|meta| meta.modified())
+            .with_context(|| format!("Fichier introuvable: {}", path.display()))?;
+        let key = ThumbnailKey::new(path, modified, timestamp);
+        if let Some(png) = self.store.lock().await.get(&key) {
+            return Ok(png);
+        }
+
+        let png = extract_thumbnail(&self.ffmpeg_bin, path, timestamp).await?;
+        self.store.lock().await.insert(key, png.clone());
+        Ok(png)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_store_keys_on_mtime_and_evicts() {
+        let path = Path::new("/videos/film.mkv");
+        let before = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let after = before + Duration::from_secs(60);
+
+        let mut store = ThumbnailStore::default();
+        store.insert(ThumbnailKey::new(path, before, 12.0), vec![1]);
+        assert_eq!(
+            store.get(&ThumbnailKey::new(path, before, 12.0004)),
+            Some(vec![1])
+        );
+        assert_eq!(store.get(&ThumbnailKey::new(path, before, 30.0)), None);
+        // Fichier modifié : l'ancienne miniature ne sert plus et est retirée
+        assert_eq!(store.get(&ThumbnailKey::new(path, after, 12.0)), None);
+        store.insert(ThumbnailKey::new(path, after, 12.0), vec![2]);
+        assert_eq!(store.entries.len(), 1);
+
+        for i in 0..THUMBNAIL_CACHE_CAPACITY {
+            let other = PathBuf::from(format!("/videos/{i}.mkv"));
+            store.insert(ThumbnailKey::new(&other, before, 0.0), vec![0]);
+        }
+        assert_eq!(store.entries.len(), THUMBNAIL_CACHE_CAPACITY);
+        assert_eq!(store.order.len(), THUMBNAIL_CACHE_CAPACITY);
+        assert_eq!(store.get(&ThumbnailKey::new(path, after, 12.0)), None);
+    }
+}
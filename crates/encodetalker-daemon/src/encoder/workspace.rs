@@ -0,0 +1,323 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use uuid::Uuid;
+
+/// Répertoire d'une sortie (`.` pour une sortie sans répertoire)
+#[must_use]
+pub fn output_dir(output: &Path) -> PathBuf {
+    match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Répertoire des fichiers temporaires d'un job : `temp_dir` s'il est configuré,
+/// sinon le répertoire de la sortie
+#[must_use]
+pub fn job_temp_dir(temp_dir: Option<&Path>, output: &Path) -> PathBuf {
+    temp_dir.map_or_else(|| output_dir(output), Path::to_path_buf)
+}
+
+/// Vérifier qu'un répertoire où le daemon écrit (sortie d'un job, `encoding.temp_dir`)
+/// est accessible en écriture
+///
+/// Un répertoire manquant est créé si `create_missing` est vrai. L'écriture est testée
+/// en créant puis supprimant un fichier : les bits de permission ne suffisent pas
+/// (système de fichiers monté en lecture seule, ACL, partage réseau). `role` désigne le
+/// répertoire dans les messages (ex. `répertoire de sortie`).
+///
+/// # Errors
+///
+/// Retourne une erreur nommant le répertoire s'il manque (et ne doit pas être créé), ne
+/// peut pas être créé ou n'est pas accessible en écriture.
+pub async fn ensure_dir_writable(dir: &Path, create_missing: bool, role: &str) -> Result<()> {
+    match tokio::fs::metadata(dir).await {
+        Ok(meta) if !meta.is_dir() => {
+            anyhow::bail!("{} n'est pas un répertoire ({role})", dir.display());
+        }
+        Ok(_) => {}
+        Err(_) if create_missing => {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .with_context(|| format!("Impossible de créer {} ({role})", dir.display()))?;
+            info!("Répertoire créé: {} ({role})", dir.display());
+        }
+        Err(_) => {
+            anyhow::bail!("{} n'existe pas ({role})", dir.display());
+        }
+    }
+
+    let probe = dir.join(format!(".encodetalker-write-test-{}", Uuid::new_v4()));
+    tokio::fs::File::create(&probe)
+        .await
+        .with_context(|| format!("{} non accessible en écriture ({role})", dir.display()))?;
+    let _ = tokio::fs::remove_file(&probe).await;
+    Ok(())
+}
+
+/// Préfixe de la copie d'une sortie en cours de déplacement vers un autre système de
+/// fichiers, créée à côté de la destination
+pub const PARTIAL_OUTPUT_PREFIX: &str = ".encodetalker-partial-";
+
+/// Déplacer un fichier terminé vers sa destination sans jamais y exposer un fichier
+/// partiel : renommage atomique, ou copie à côté de la destination puis renommage quand
+/// la source est sur un autre système de fichiers
+///
+/// Un fichier existant à la destination n'est remplacé qu'une fois la copie complète.
+///
+/// # Errors
+///
+/// Retourne une erreur si le renommage ou la copie échoue ; la destination est alors
+/// inchangée et la copie partielle supprimée.
+pub async fn move_atomically(from: &Path, to: &Path) -> Result<()> {
+    match tokio::fs::rename(from, to).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            copy_then_rename(from, to).await?;
+            let _ = tokio::fs::remove_file(from).await;
+            Ok(())
+        }
+        Err(e) => Err(e).with_context(|| {
+            format!(
+                "Impossible de déplacer {} vers {}",
+                from.display(),
+                to.display()
+            )
+        }),
+    }
+}
+
+/// Copier `from` dans le répertoire de `to` sous un nom temporaire, puis le renommer
+async fn copy_then_rename(from: &Path, to: &Path) -> Result<()> {
+    let extension = to
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let partial = to.with_file_name(format!(
+        "{PARTIAL_OUTPUT_PREFIX}{}{extension}",
+        Uuid::new_v4()
+    ));
+    let result = async {
+        tokio::fs::copy(from, &partial)
+            .await
+            .with_context(|| format!("Échec de la copie vers {}", partial.display()))?;
+        // La copie doit être sur le disque avant de prendre la place de la destination
+        tokio::fs::File::open(&partial).await?.sync_all().await?;
+        tokio::fs::rename(&partial, to)
+            .await
+            .with_context(|| format!("Impossible de renommer {}", partial.display()))
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&partial).await;
+    }
+    result
+}
+
+/// Fichiers temporaires d'un encodage, alloués dans un répertoire donné
+///
+/// Chaque nom est unique (uuid, jamais un fichier existant ni un nom déjà alloué) même
+/// quand plusieurs jobs ou segments écrivent dans le même répertoire. Les fichiers suivis
+/// sont supprimés par [`TempWorkspace::cleanup`], et à défaut quand le workspace est
+/// détruit (job annulé, future abandonnée).
+#[derive(Debug)]
+pub struct TempWorkspace {
+    dir: PathBuf,
+    /// Préfixe des noms alloués (ex. `encodetalker-benchmark-`)
+    prefix: String,
+    files: HashSet<PathBuf>,
+}
+
+impl TempWorkspace {
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: String::new(),
+            files: HashSet::new(),
+        }
+    }
+
+    /// Préfixer les noms alloués, pour reconnaître les fichiers dans un répertoire partagé
+    #[must_use]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Réserver un nouveau chemin `<préfixe><uuid>.<extension>` (le fichier n'est pas créé)
+    pub fn allocate(&mut self, extension: &str) -> PathBuf {
+        loop {
+            let path = self
+                .dir
+                .join(format!("{}{}.{extension}", self.prefix, Uuid::new_v4()));
+            if !self.files.contains(&path) && !path.exists() {
+                self.files.insert(path.clone());
+                return path;
+            }
+        }
+    }
+
+    /// Suivre un fichier produit à partir d'un chemin alloué (ex. stats de passe 1 d'aomenc)
+    pub fn track(&mut self, path: impl Into<PathBuf>) {
+        self.files.insert(path.into());
+    }
+
+    /// Fichiers suivis
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(PathBuf::as_path)
+    }
+
+    /// Supprimer les fichiers suivis (ceux qui n'existent pas sont ignorés)
+    pub async fn cleanup(&mut self) {
+        for path in self.files.drain() {
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+    }
+}
+
+impl Drop for TempWorkspace {
+    fn drop(&mut self) {
+        for path in self.files.drain() {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_temp_dir() {
+        let output = Path::new("/films/film.av1.mkv");
+        assert_eq!(job_temp_dir(None, output), PathBuf::from("/films"));
+        assert_eq!(
+            job_temp_dir(Some(Path::new("/scratch")), output),
+            PathBuf::from("/scratch")
+        );
+        assert_eq!(
+            job_temp_dir(None, Path::new("film.mkv")),
+            PathBuf::from(".")
+        );
+        assert_eq!(output_dir(Path::new("film.mkv")), PathBuf::from("."));
+    }
+
+    #[tokio::test]
+    async fn test_temp_dir_writability() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let temp = dir.join("tmp");
+
+        // Manquant : refusé, ou créé à la demande sans laisser de fichier de test
+        let err = ensure_dir_writable(&temp, false, "encoding.temp_dir")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("n'existe pas"));
+        ensure_dir_writable(&temp, true, "encoding.temp_dir")
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_dir(&temp).unwrap().count(), 0);
+
+        // Un fichier à la place du répertoire : ni écriture ni création possibles
+        let file = dir.join("fichier");
+        std::fs::write(&file, b"").unwrap();
+        let err = ensure_dir_writable(&file, true, "encoding.temp_dir")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("encoding.temp_dir"));
+        assert!(
+            ensure_dir_writable(&file.join("sous-dossier"), true, "encoding.temp_dir")
+                .await
+                .is_err()
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let readonly = dir.join("readonly");
+            std::fs::create_dir(&readonly).unwrap();
+            std::fs::set_permissions(&readonly, std::fs::Permissions::from_mode(0o555)).unwrap();
+            let result = ensure_dir_writable(&readonly, true, "encoding.temp_dir").await;
+            // root ignore les permissions : on ne vérifie que si l'écriture est vraiment refusée
+            if std::fs::write(readonly.join("probe"), b"").is_err() {
+                let err = result.unwrap_err();
+                assert!(err.to_string().contains("non accessible en écriture"));
+                assert!(err.to_string().contains(&*readonly.to_string_lossy()));
+            }
+            std::fs::set_permissions(&readonly, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workspace_allocates_unique_names_and_cleans_up() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let mut workspace = TempWorkspace::new(&dir);
+        let video = workspace.allocate("ivf");
+        let audio = workspace.allocate("opus");
+        assert_ne!(video, audio);
+        assert_eq!(video.parent(), Some(dir.as_path()));
+        assert_eq!(audio.extension().unwrap(), "opus");
+        workspace.track(video.with_extension("log"));
+        assert_eq!(workspace.files().count(), 3);
+
+        for path in [&video, &audio] {
+            tokio::fs::write(path, b"temp").await.unwrap();
+        }
+        workspace.cleanup().await;
+        assert!(!video.exists() && !audio.exists());
+
+        // Workspace abandonné (annulation) : les fichiers sont supprimés quand même
+        let mut workspace = TempWorkspace::new(&dir).with_prefix("bench-");
+        let leftover = workspace.allocate("ivf");
+        assert!(leftover
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("bench-"));
+        tokio::fs::write(&leftover, b"temp").await.unwrap();
+        drop(workspace);
+        assert!(!leftover.exists());
+    }
+
+    #[tokio::test]
+    async fn test_move_atomically() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let output = dir.join("film.av1.mkv");
+        tokio::fs::write(&output, b"ancienne sortie").await.unwrap();
+
+        // Même système de fichiers : renommage, la sortie précédente est remplacée
+        let muxed = dir.join(format!("{}.mkv", Uuid::new_v4()));
+        tokio::fs::write(&muxed, b"nouvelle sortie").await.unwrap();
+        move_atomically(&muxed, &output).await.unwrap();
+        assert_eq!(tokio::fs::read(&output).await.unwrap(), b"nouvelle sortie");
+        assert!(!muxed.exists());
+
+        // Repli d'un autre système de fichiers : copie puis renommage, sans reste
+        let muxed = dir.join(format!("{}.mkv", Uuid::new_v4()));
+        tokio::fs::write(&muxed, b"copie").await.unwrap();
+        copy_then_rename(&muxed, &output).await.unwrap();
+        assert_eq!(tokio::fs::read(&output).await.unwrap(), b"copie");
+        let names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert!(names
+            .iter()
+            .all(|name| !name.starts_with(PARTIAL_OUTPUT_PREFIX)));
+
+        // Copie impossible (source disparue) : la destination est intacte
+        let missing = dir.join("absent.mkv");
+        assert!(copy_then_rename(&missing, &output).await.is_err());
+        assert!(move_atomically(&missing, &output).await.is_err());
+        assert_eq!(tokio::fs::read(&output).await.unwrap(), b"copie");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    }
+}
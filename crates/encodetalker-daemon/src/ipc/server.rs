@@ -1,13 +1,17 @@
 use crate::deps_tracker::DepsCompilationTracker;
-use crate::encoder::ffmpeg::probe_video;
+use crate::encoder::ffmpeg::{probe_video, probe_videos, MAX_PARALLEL_PROBES};
+use crate::encoder::{CapabilitiesCache, ThumbnailCache, VideoInfo};
+use crate::maintenance::Maintenance;
 use crate::queue::{QueueEvent, QueueManager};
 use anyhow::Result;
 use encodetalker_common::ipc::{IpcListener, IpcStream};
+use encodetalker_common::protocol::messages::DaemonStatusInfo;
 use encodetalker_common::{
     EncodingJob, Event, EventPayload, IpcMessage, Request, RequestPayload, Response,
-    ResponsePayload,
+    ResponsePayload, VideoSummary,
 };
 use futures::{SinkExt, StreamExt};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -15,6 +19,19 @@ use tokio_serde::{formats::Bincode, Framed as SerdeFramed};
 use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{error, info, warn};
 
+/// Métadonnées d'une vidéo probée renvoyées au client
+fn video_summary(path: &Path, video_info: &VideoInfo) -> VideoSummary {
+    VideoSummary {
+        duration_secs: video_info.duration.map(|d| d.as_secs_f64()),
+        size_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        height: (video_info.height > 0).then_some(video_info.height),
+        audio_channels: video_info
+            .audio_streams
+            .first()
+            .and_then(|stream| stream.channels),
+    }
+}
+
 /// Serveur IPC Unix socket
 pub struct IpcServer {
     socket_path: std::path::PathBuf,
@@ -22,6 +39,9 @@ pub struct IpcServer {
     deps_tracker: Arc<DepsCompilationTracker>,
     ffprobe_bin: std::path::PathBuf,
     ffmpeg_bin: std::path::PathBuf,
+    capabilities: Arc<CapabilitiesCache>,
+    thumbnails: Arc<ThumbnailCache>,
+    maintenance: Arc<Maintenance>,
 }
 
 impl IpcServer {
@@ -31,6 +51,8 @@ impl IpcServer {
         deps_tracker: Arc<DepsCompilationTracker>,
         ffprobe_bin: impl AsRef<Path>,
         ffmpeg_bin: impl AsRef<Path>,
+        capabilities: Arc<CapabilitiesCache>,
+        maintenance: Maintenance,
     ) -> Self {
         Self {
             socket_path: socket_path.as_ref().to_path_buf(),
@@ -38,6 +60,9 @@ impl IpcServer {
             deps_tracker,
             ffprobe_bin: ffprobe_bin.as_ref().to_path_buf(),
             ffmpeg_bin: ffmpeg_bin.as_ref().to_path_buf(),
+            capabilities,
+            thumbnails: Arc::new(ThumbnailCache::new(ffmpeg_bin.as_ref().to_path_buf())),
+            maintenance: Arc::new(maintenance),
         }
     }
 
@@ -93,15 +118,36 @@ impl IpcServer {
                     QueueEvent::JobCompleted(id) => {
                         Event::new(EventPayload::JobCompleted { job_id: id })
                     }
-                    QueueEvent::JobFailed(id, error) => {
-                        Event::new(EventPayload::JobFailed { job_id: id, error })
+                    QueueEvent::JobFailed(id, error, category) => {
+                        Event::new(EventPayload::JobFailed {
+                            job_id: id,
+                            error,
+                            category,
+                        })
                     }
                     QueueEvent::JobCancelled(id) => {
                         Event::new(EventPayload::JobCancelled { job_id: id })
                     }
-                    QueueEvent::DepsCompilationStarted { total_deps } => {
-                        Event::new(EventPayload::DepsCompilationStarted { total_deps })
+                    QueueEvent::JobMoved(id, position) => Event::new(EventPayload::JobMoved {
+                        job_id: id,
+                        position,
+                    }),
+                    QueueEvent::HistoryRemoved(id) => {
+                        Event::new(EventPayload::HistoryRemoved { job_id: id })
+                    }
+                    QueueEvent::HistoryCleared => Event::new(EventPayload::HistoryCleared),
+                    QueueEvent::StoppedAcceptingJobs => {
+                        Event::new(EventPayload::StoppedAcceptingJobs)
                     }
+                    QueueEvent::DepsCompilationStarted {
+                        total_deps,
+                        resumed,
+                        deps,
+                    } => Event::new(EventPayload::DepsCompilationStarted {
+                        total_deps,
+                        resumed,
+                        deps,
+                    }),
                     QueueEvent::DepsCompilationProgress {
                         dep_name,
                         dep_index,
@@ -128,6 +174,18 @@ impl IpcServer {
                     QueueEvent::DepsCompilationFailed { dep_name, error } => {
                         Event::new(EventPayload::DepsCompilationFailed { dep_name, error })
                     }
+                    QueueEvent::DepsCompilationLog { lines, skipped } => {
+                        Event::new(EventPayload::DepsCompilationLog { lines, skipped })
+                    }
+                    QueueEvent::BenchmarkProgress(result) => {
+                        Event::new(EventPayload::BenchmarkProgress { result })
+                    }
+                    QueueEvent::BenchmarkCompleted(report) => {
+                        Event::new(EventPayload::BenchmarkCompleted { report })
+                    }
+                    QueueEvent::BenchmarkFailed(error) => {
+                        Event::new(EventPayload::BenchmarkFailed { error })
+                    }
                 };
 
                 let _ = broadcast_tx_clone.send(ipc_event);
@@ -143,6 +201,9 @@ impl IpcServer {
                     let broadcast_rx = broadcast_tx.subscribe();
                     let ffprobe_bin = self.ffprobe_bin.clone();
                     let ffmpeg_bin = self.ffmpeg_bin.clone();
+                    let capabilities = self.capabilities.clone();
+                    let thumbnails = self.thumbnails.clone();
+                    let maintenance = self.maintenance.clone();
                     tokio::spawn(async move {
                         if let Err(e) = Self::handle_client(
                             stream,
@@ -150,6 +211,9 @@ impl IpcServer {
                             deps_tracker,
                             ffprobe_bin,
                             ffmpeg_bin,
+                            capabilities,
+                            thumbnails,
+                            maintenance,
                             broadcast_rx,
                         )
                         .await
@@ -166,12 +230,16 @@ impl IpcServer {
     }
 
     /// Gérer une connexion client
+    #[allow(clippy::too_many_arguments)] // Ressources partagées entre connexions
     async fn handle_client(
         stream: IpcStream,
         queue_manager: Arc<QueueManager>,
         deps_tracker: Arc<DepsCompilationTracker>,
         ffprobe_bin: std::path::PathBuf,
         ffmpeg_bin: std::path::PathBuf,
+        capabilities: Arc<CapabilitiesCache>,
+        thumbnails: Arc<ThumbnailCache>,
+        maintenance: Arc<Maintenance>,
         mut broadcast_rx: tokio::sync::broadcast::Receiver<Event>,
     ) -> Result<()> {
         info!("Nouveau client connecté");
@@ -185,17 +253,37 @@ impl IpcServer {
         // Split pour lecture et écriture
         let (mut writer, mut reader) = framed.split();
 
+        // Sortie des compilateurs : envoyée seulement si le client l'a demandée
+        let mut stream_compilation_logs = false;
+        // État complet de la file poussé à chaque changement (requête WatchState)
+        let mut watch_state = false;
+
         loop {
             tokio::select! {
                 // Recevoir des requêtes du client
                 msg = reader.next() => {
                     match msg {
                         Some(Ok(IpcMessage::Request(request))) => {
+                            if let RequestPayload::SetCompilationLogStreaming { enabled } = request.payload {
+                                stream_compilation_logs = enabled;
+                                writer.send(IpcMessage::Response(Response::ok(request.id))).await?;
+                                continue;
+                            }
+                            if let RequestPayload::WatchState = request.payload {
+                                watch_state = true;
+                                let snapshot = queue_manager.snapshot().await;
+                                let response = Response::new(request.id, ResponsePayload::State { snapshot });
+                                writer.send(IpcMessage::Response(response)).await?;
+                                continue;
+                            }
                             let response = Self::handle_request(
                                 &queue_manager,
                                 &deps_tracker,
                                 &ffprobe_bin,
                                 &ffmpeg_bin,
+                                &capabilities,
+                                &thumbnails,
+                                &maintenance,
                                 request,
                             )
                             .await;
@@ -219,10 +307,24 @@ impl IpcServer {
                 event = broadcast_rx.recv() => {
                     match event {
                         Ok(event) => {
+                            if !stream_compilation_logs
+                                && matches!(event.payload, EventPayload::DepsCompilationLog { .. })
+                            {
+                                continue;
+                            }
+                            let state_changed = watch_state && event.payload.changes_queue_state();
                             if let Err(e) = writer.send(IpcMessage::Event(event)).await {
                                 error!("Échec d'envoi d'événement: {}", e);
                                 break;
                             }
+                            if state_changed {
+                                let snapshot = queue_manager.snapshot().await;
+                                let event = Event::new(EventPayload::StateChanged { snapshot });
+                                if let Err(e) = writer.send(IpcMessage::Event(event)).await {
+                                    error!("Échec d'envoi de l'état: {}", e);
+                                    break;
+                                }
+                            }
                         }
                         Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
                             warn!("Client en retard sur les événements");
@@ -237,11 +339,15 @@ impl IpcServer {
     }
 
     /// Traiter une requête et retourner une réponse
+    #[allow(clippy::too_many_arguments)] // Ressources partagées entre connexions
     async fn handle_request(
         queue_manager: &Arc<QueueManager>,
         deps_tracker: &Arc<DepsCompilationTracker>,
         ffprobe_bin: &Path,
         ffmpeg_bin: &Path,
+        capabilities: &CapabilitiesCache,
+        thumbnails: &ThumbnailCache,
+        maintenance: &Maintenance,
         request: Request,
     ) -> Response {
         let request_id = request.id;
@@ -252,13 +358,92 @@ impl IpcServer {
                 output_path,
                 config,
             } => {
-                let job = EncodingJob::new(input_path, output_path, config);
+                let job = EncodingJob::new(input_path, output_path, *config);
                 match queue_manager.add_job(job.clone()).await {
                     Ok(job_id) => Response::new(request_id, ResponsePayload::JobId { job_id }),
                     Err(e) => Response::error(request_id, e.to_string()),
                 }
             }
 
+            RequestPayload::AddJobFromFile {
+                input_path,
+                output_path,
+                config_path,
+            } => match crate::config::load_job_config(&config_path).await {
+                Ok(config) => {
+                    let output_path = output_path.unwrap_or_else(|| {
+                        queue_manager
+                            .output_namer()
+                            .default_path(&input_path, &config)
+                    });
+                    let job = EncodingJob::new(input_path, output_path, config);
+                    match queue_manager.add_job(job).await {
+                        Ok(job_id) => Response::new(request_id, ResponsePayload::JobId { job_id }),
+                        Err(e) => Response::error(request_id, e.to_string()),
+                    }
+                }
+                Err(e) => Response::error(request_id, format!("{e:#}")),
+            },
+            RequestPayload::ImportHandbrakePresets { path } => {
+                match crate::config::load_handbrake_presets(&path).await {
+                    Ok(presets) => {
+                        Response::new(request_id, ResponsePayload::ImportedPresets { presets })
+                    }
+                    Err(e) => Response::error(request_id, format!("{e:#}")),
+                }
+            }
+            RequestPayload::AddBatchJobs { jobs } => {
+                let inputs: Vec<_> = jobs.iter().map(|spec| spec.input_path.clone()).collect();
+                let jobs = jobs
+                    .into_iter()
+                    .map(|spec| EncodingJob::new(spec.input_path, spec.output_path, spec.config))
+                    .collect();
+                let (batch_id, results) = queue_manager.add_batch(jobs).await;
+                let mut job_ids = Vec::new();
+                let mut errors = Vec::new();
+                for (input, result) in inputs.into_iter().zip(results) {
+                    match result {
+                        Ok(job_id) => job_ids.push(job_id),
+                        Err(e) => errors.push((input, e.to_string())),
+                    }
+                }
+                Response::new(
+                    request_id,
+                    ResponsePayload::BatchAdded {
+                        batch_id,
+                        job_ids,
+                        errors,
+                    },
+                )
+            }
+            RequestPayload::CancelBatch { batch_id } => {
+                match queue_manager.cancel_batch(batch_id).await {
+                    Ok(cancelled) => {
+                        Response::new(request_id, ResponsePayload::BatchCancelled { cancelled })
+                    }
+                    Err(e) => Response::error(request_id, e.to_string()),
+                }
+            }
+            RequestPayload::AddPreviewJob {
+                input_path,
+                output_path,
+                config,
+                duration_secs,
+            } => {
+                let job =
+                    EncodingJob::new_preview(input_path, &output_path, *config, duration_secs);
+                match queue_manager.add_job(job).await {
+                    Ok(job_id) => Response::new(request_id, ResponsePayload::JobId { job_id }),
+                    Err(e) => Response::error(request_id, e.to_string()),
+                }
+            }
+
+            RequestPayload::VerifyFile { path } => {
+                match queue_manager.add_job(EncodingJob::new_verify(path)).await {
+                    Ok(job_id) => Response::new(request_id, ResponsePayload::JobId { job_id }),
+                    Err(e) => Response::error(request_id, e.to_string()),
+                }
+            }
             RequestPayload::CancelJob { job_id } => match queue_manager.cancel_job(job_id).await {
                 Ok(()) => Response::ok(request_id),
                 Err(e) => Response::error(request_id, e.to_string()),
@@ -269,6 +454,13 @@ impl IpcServer {
                 Err(e) => Response::error(request_id, e.to_string()),
             },
 
+            RequestPayload::MoveJob { job_id, position } => {
+                match queue_manager.move_job(job_id, position).await {
+                    Ok(_) => Response::ok(request_id),
+                    Err(e) => Response::error(request_id, e.to_string()),
+                }
+            }
+
             RequestPayload::ListQueue => {
                 let jobs = queue_manager.get_queue().await;
                 Response::new(request_id, ResponsePayload::JobList { jobs })
@@ -297,21 +489,30 @@ impl IpcServer {
                 None => Response::error(request_id, format!("Job {job_id} non trouvé")),
             },
 
-            RequestPayload::RemoveFromHistory { job_id } => {
-                match queue_manager.remove_from_history(job_id).await {
+            RequestPayload::RemoveFromHistory {
+                job_id,
+                delete_outputs,
+            } => {
+                match queue_manager
+                    .remove_from_history(job_id, delete_outputs)
+                    .await
+                {
                     Ok(()) => Response::ok(request_id),
                     Err(e) => Response::error(request_id, e.to_string()),
                 }
             }
 
-            RequestPayload::ClearHistory => match queue_manager.clear_history().await {
-                Ok(()) => Response::ok(request_id),
-                Err(e) => Response::error(request_id, e.to_string()),
-            },
+            RequestPayload::ClearHistory { delete_outputs } => {
+                match queue_manager.clear_history(delete_outputs).await {
+                    Ok(()) => Response::ok(request_id),
+                    Err(e) => Response::error(request_id, e.to_string()),
+                }
+            }
 
             RequestPayload::Shutdown => {
                 info!("Shutdown demandé par un client");
-                // Note: le shutdown réel est géré par le main
+                // L'arrêt est fait par le main, après l'envoi de cette réponse
+                queue_manager.request_shutdown();
                 Response::ok(request_id)
             }
 
@@ -322,25 +523,113 @@ impl IpcServer {
                 Response::new(request_id, ResponsePayload::DepsStatus { status })
             }
 
+            RequestPayload::GetDaemonStatus => {
+                let status = DaemonStatusInfo {
+                    accepting_jobs: queue_manager.is_accepting_jobs().await,
+                };
+                Response::new(request_id, ResponsePayload::DaemonStatus { status })
+            }
+
             RequestPayload::ProbeVideo { path } => {
-                // Prober la vidéo pour récupérer durée et taille
-                match probe_video(ffprobe_bin, ffmpeg_bin, &path, false).await {
+                // Prober la vidéo pour récupérer durée, taille, hauteur et canaux audio
+                match probe_video(ffprobe_bin, ffmpeg_bin, &path, None, false, &HashMap::new())
+                    .await
+                {
                     Ok(video_info) => {
-                        let duration_secs = video_info.duration.map(|d| d.as_secs_f64());
-                        let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
-
+                        let summary = video_summary(&path, &video_info);
                         Response::new(
                             request_id,
                             ResponsePayload::VideoInfo {
                                 path,
-                                duration_secs,
-                                size_bytes,
+                                duration_secs: summary.duration_secs,
+                                size_bytes: summary.size_bytes,
+                                height: summary.height,
+                                audio_channels: summary.audio_channels,
                             },
                         )
                     }
                     Err(e) => Response::error(request_id, format!("Erreur probe vidéo: {e}")),
                 }
             }
+
+            RequestPayload::ProbeVideos { paths } => {
+                let results =
+                    probe_videos(ffprobe_bin, ffmpeg_bin, paths, MAX_PARALLEL_PROBES).await;
+                let results = results
+                    .into_iter()
+                    .map(|(path, result)| {
+                        let summary = result
+                            .map(|video_info| video_summary(&path, &video_info))
+                            .map_err(|e| format!("Erreur probe vidéo: {e}"));
+                        (path, summary)
+                    })
+                    .collect();
+                Response::new(request_id, ResponsePayload::VideoInfos { results })
+            }
+
+            RequestPayload::GetThumbnail { path, timestamp } => {
+                match thumbnails.get(&path, timestamp).await {
+                    Ok(png) => Response::new(request_id, ResponsePayload::Thumbnail { path, png }),
+                    Err(e) => Response::error(request_id, format!("Erreur miniature: {e}")),
+                }
+            }
+
+            RequestPayload::SetJobPriority { job_id, nice } => {
+                match queue_manager.set_job_priority(job_id, nice).await {
+                    Ok(()) => Response::ok(request_id),
+                    Err(e) => Response::error(request_id, e.to_string()),
+                }
+            }
+
+            RequestPayload::GetEncoderCapabilities => {
+                let capabilities = capabilities.get().await;
+                Response::new(
+                    request_id,
+                    ResponsePayload::EncoderCapabilities { capabilities },
+                )
+            }
+
+            RequestPayload::CheckBuildEnv => {
+                // Les binaires sont dans `<deps_dir>/bin`
+                let deps_dir = ffmpeg_bin
+                    .parent()
+                    .and_then(Path::parent)
+                    .unwrap_or_else(|| Path::new("."));
+                let report = crate::build_env::check_build_env(deps_dir).await;
+                Response::new(request_id, ResponsePayload::BuildEnvReport { report })
+            }
+
+            RequestPayload::Benchmark {
+                encoder,
+                input_path,
+                target_speed,
+            } => match queue_manager
+                .start_benchmark(input_path, encoder, target_speed)
+                .await
+            {
+                Ok(()) => Response::ok(request_id),
+                Err(e) => Response::error(request_id, e.to_string()),
+            },
+
+            RequestPayload::Cleanup { targets, dry_run } => {
+                let results = maintenance
+                    .cleanup(&targets, dry_run, queue_manager, deps_tracker, thumbnails)
+                    .await;
+                if !dry_run {
+                    let bytes: u64 = results.iter().map(|result| result.bytes).sum();
+                    info!("Nettoyage : {bytes} octets libérés");
+                }
+                Response::new(request_id, ResponsePayload::CleanupReport { results })
+            }
+
+            // Géré par la connexion (handle_client), qui porte l'abonnement
+            RequestPayload::SetCompilationLogStreaming { .. } => Response::ok(request_id),
+            RequestPayload::WatchState => Response::new(
+                request_id,
+                ResponsePayload::State {
+                    snapshot: queue_manager.snapshot().await,
+                },
+            ),
         }
     }
 }
@@ -1,11 +1,17 @@
+pub mod build_env;
 pub mod config;
 pub mod deps_tracker;
 pub mod encoder;
 pub mod ipc;
+pub mod lock;
+pub mod logging;
+pub mod maintenance;
 pub mod queue;
 
 pub use config::*;
 pub use deps_tracker::*;
 pub use encoder::*;
 pub use ipc::*;
+pub use lock::*;
+pub use maintenance::*;
 pub use queue::*;
@@ -0,0 +1,221 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// Intervalle de vérification pendant l'attente d'un daemon en drain
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Contenu du fichier PID : `<pid>` puis `draining` si le daemon est en drain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub draining: bool,
+}
+
+impl LockInfo {
+    /// Parser le contenu du fichier PID (None si illisible)
+    #[must_use]
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        let pid = lines.next()?.trim().parse().ok().filter(|pid| *pid > 0)?;
+        let draining = lines.any(|line| line.trim() == "draining");
+        Some(Self { pid, draining })
+    }
+
+    fn render(self) -> String {
+        if self.draining {
+            format!("{}\ndraining\n", self.pid)
+        } else {
+            format!("{}\n", self.pid)
+        }
+    }
+}
+
+/// Lire le fichier PID du daemon (None si absent ou illisible)
+#[must_use]
+pub fn read_lock(path: &Path) -> Option<LockInfo> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| LockInfo::parse(&content))
+}
+
+/// Vérifier qu'un processus existe encore
+#[cfg(unix)]
+#[must_use]
+pub fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: le signal 0 ne fait que tester l'existence du processus
+    let ret = unsafe { libc::kill(pid, 0) };
+    ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Vérifier qu'un processus existe encore (non supporté : fichier considéré obsolète)
+#[cfg(not(unix))]
+#[must_use]
+pub fn process_alive(_pid: u32) -> bool {
+    false
+}
+
+/// Envoyer le signal de drain (SIGUSR1) au daemon en cours
+///
+/// # Errors
+///
+/// Retourne une erreur si aucun daemon ne tourne ou si le signal ne peut pas être envoyé.
+pub fn send_drain_signal(path: &Path) -> Result<u32> {
+    let info = read_lock(path)
+        .filter(|info| process_alive(info.pid))
+        .context("Aucun daemon en cours d'exécution")?;
+
+    #[cfg(unix)]
+    {
+        let pid = libc::pid_t::try_from(info.pid).context("PID invalide")?;
+        // SAFETY: envoi d'un signal à un PID lu depuis notre propre fichier verrou
+        if unsafe { libc::kill(pid, libc::SIGUSR1) } != 0 {
+            return Err(std::io::Error::last_os_error())
+                .context("Échec d'envoi du signal de drain");
+        }
+        Ok(info.pid)
+    }
+
+    #[cfg(not(unix))]
+    anyhow::bail!("Le drain n'est supporté que sur Unix (PID {})", info.pid)
+}
+
+/// Verrou d'instance du daemon (fichier PID)
+///
+/// Un seul daemon peut le détenir. Un nouveau daemon lancé pendant le drain du
+/// précédent attend qu'il libère le verrou (et donc le socket) avant de démarrer.
+pub struct DaemonLock {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl DaemonLock {
+    /// Prendre le verrou, en attendant la fin d'un daemon en drain
+    ///
+    /// Un fichier laissé par un daemon mort est ignoré.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si un autre daemon actif (hors drain) détient le verrou, ou
+    /// si le fichier PID ne peut pas être écrit.
+    pub async fn acquire(path: &Path) -> Result<Self> {
+        let mut waiting = false;
+        while let Some(existing) = read_lock(path) {
+            if existing.pid == std::process::id() || !process_alive(existing.pid) {
+                break;
+            }
+            if !existing.draining {
+                anyhow::bail!(
+                    "Un daemon est déjà en cours d'exécution (PID {}). \
+                     Utiliser --drain pour le remplacer sans interrompre les encodages",
+                    existing.pid
+                );
+            }
+            if !waiting {
+                info!(
+                    "Daemon précédent en drain (PID {}), attente de sa fin...",
+                    existing.pid
+                );
+                waiting = true;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+
+        let lock = Self {
+            path: path.to_path_buf(),
+            pid: std::process::id(),
+        };
+        lock.write(false)?;
+        Ok(lock)
+    }
+
+    /// Signaler le drain dans le fichier PID (le prochain daemon attendra)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le fichier PID ne peut pas être écrit.
+    pub fn mark_draining(&self) -> Result<()> {
+        self.write(true)
+    }
+
+    fn write(&self, draining: bool) -> Result<()> {
+        let info = LockInfo {
+            pid: self.pid,
+            draining,
+        };
+        std::fs::write(&self.path, info.render()).context("Échec d'écriture du fichier PID")
+    }
+}
+
+impl Drop for DaemonLock {
+    fn drop(&mut self) {
+        // Ne supprimer que notre propre verrou
+        if read_lock(&self.path).is_some_and(|info| info.pid == self.pid) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_info_parse() {
+        assert_eq!(
+            LockInfo::parse("1234\n"),
+            Some(LockInfo {
+                pid: 1234,
+                draining: false
+            })
+        );
+        assert_eq!(
+            LockInfo::parse("1234\ndraining\n"),
+            Some(LockInfo {
+                pid: 1234,
+                draining: true
+            })
+        );
+        assert_eq!(LockInfo::parse("0\n"), None);
+        assert_eq!(LockInfo::parse("abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_replaces_stale_lock_and_releases_on_drop() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("daemon.pid");
+
+        // PID hors de la plage des processus possibles : verrou obsolète
+        std::fs::write(&path, "999999999\n").unwrap();
+        let lock = DaemonLock::acquire(&path).await.unwrap();
+        assert_eq!(read_lock(&path).unwrap().pid, std::process::id());
+
+        lock.mark_draining().unwrap();
+        assert!(read_lock(&path).unwrap().draining);
+
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_acquire_refuses_running_daemon() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("daemon.pid");
+
+        // Le processus parent (cargo test) est vivant et ne draine pas
+        let parent = std::os::unix::process::parent_id();
+        std::fs::write(&path, format!("{parent}\n")).unwrap();
+        assert!(DaemonLock::acquire(&path).await.is_err());
+        // Le verrou d'un autre processus n'est pas supprimé
+        assert!(path.exists());
+    }
+}
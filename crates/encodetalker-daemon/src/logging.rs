@@ -0,0 +1,226 @@
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+/// Fichier de log avec rotation par taille
+///
+/// Quand une écriture ferait dépasser `max_bytes`, `daemon.log` devient `daemon.log.1`,
+/// les archives existantes sont décalées et seules les `max_files` plus récentes sont gardées.
+pub struct RollingFileAppender {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    state: Mutex<RollingState>,
+}
+
+struct RollingState {
+    file: File,
+    size: u64,
+}
+
+impl RollingFileAppender {
+    /// Ouvrir (ou créer) le fichier de log en ajout
+    ///
+    /// `max_bytes` à 0 désactive la rotation.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le répertoire parent ou le fichier ne peut pas être créé.
+    pub fn new(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = open_append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            state: Mutex::new(RollingState { file, size }),
+        })
+    }
+
+    /// Chemin d'une archive (`daemon.log.<index>`)
+    #[must_use]
+    pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+        let mut name = OsString::from(path.as_os_str());
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    /// Archiver le fichier courant et en ouvrir un nouveau
+    fn rotate(&self, state: &mut RollingState) -> io::Result<()> {
+        state.file.flush()?;
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = Self::rotated_path(&self.path, index);
+                if from.exists() {
+                    std::fs::rename(&from, Self::rotated_path(&self.path, index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, Self::rotated_path(&self.path, 1))?;
+        }
+        state.file = open_append(&self.path)?;
+        state.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for &RollingFileAppender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let len = buf.len() as u64;
+        if self.max_bytes > 0 && state.size > 0 && state.size + len > self.max_bytes {
+            // Un échec de rotation ne doit pas faire perdre la ligne : on continue sur l'ancien fichier
+            if let Err(e) = self.rotate(&mut state) {
+                let _ = writeln!(state.file, "Échec de rotation du log: {e}");
+            }
+        }
+        state.file.write_all(buf)?;
+        state.size += len;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .file
+            .flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingFileAppender {
+    type Writer = &'a RollingFileAppender;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self
+    }
+}
+
+/// Vérifier un niveau de log (`info`, ou directives `cible=niveau` séparées par des virgules)
+///
+/// Plus strict qu'`EnvFilter`, qui accepte un mot quelconque comme nom de cible :
+/// une faute de frappe comme "inof" activerait alors tous les niveaux de cette cible.
+///
+/// # Errors
+///
+/// Retourne un message désignant la première directive invalide.
+pub fn validate_log_level(level: &str) -> Result<(), String> {
+    if level.trim().is_empty() {
+        return Err("niveau de log vide".to_string());
+    }
+    for directive in level.split(',').map(str::trim) {
+        let level_part = directive.rsplit_once('=').map_or(directive, |(_, l)| l);
+        if level_part.parse::<LevelFilter>().is_err() {
+            return Err(format!(
+                "directive '{directive}' invalide (niveaux: off, error, warn, info, debug, trace)"
+            ));
+        }
+    }
+    EnvFilter::try_new(level)
+        .map(drop)
+        .map_err(|e| format!("'{level}': {e}"))
+}
+
+/// Construire le filtre de log
+///
+/// Priorité : niveau passé en ligne de commande, puis `RUST_LOG`, puis la configuration.
+/// Un niveau invalide est ignoré ("info" en dernier recours) ; les avertissements sont
+/// retournés pour être loggés une fois le subscriber installé.
+#[must_use]
+pub fn build_filter(cli_level: Option<&str>, config_level: &str) -> (EnvFilter, Vec<String>) {
+    let mut warnings = Vec::new();
+
+    if let Some(level) = cli_level {
+        match validate_log_level(level) {
+            Ok(()) => return (EnvFilter::new(level), warnings),
+            Err(e) => warnings.push(format!("--log-level ignoré: {e}")),
+        }
+    }
+    match EnvFilter::try_from_default_env() {
+        Ok(filter) => return (filter, warnings),
+        Err(e) if std::env::var_os(EnvFilter::DEFAULT_ENV).is_some() => {
+            warnings.push(format!("RUST_LOG ignoré: {e}"));
+        }
+        Err(_) => {}
+    }
+    match validate_log_level(config_level) {
+        Ok(()) => (EnvFilter::new(config_level), warnings),
+        Err(e) => {
+            warnings.push(format!(
+                "log_level de la configuration ignoré, \"info\" utilisé: {e}"
+            ));
+            (EnvFilter::new("info"), warnings)
+        }
+    }
+}
+
+/// Initialiser le logging : sortie standard et, si fourni, fichier avec rotation
+pub fn init(filter: EnvFilter, file: Option<RollingFileAppender>) {
+    let file_layer = file.map(|appender| {
+        fmt::layer()
+            .with_ansi(false)
+            .with_target(false)
+            .with_thread_ids(true)
+            .with_writer(appender)
+    });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_target(false).with_thread_ids(true))
+        .with(file_layer)
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_log_level() {
+        assert!(validate_log_level("debug").is_ok());
+        assert!(validate_log_level("WARN").is_ok());
+        assert!(validate_log_level("info,encodetalker_daemon=trace").is_ok());
+        assert!(validate_log_level("inof").is_err());
+        assert!(validate_log_level("encodetalker_daemon=verbose").is_err());
+        assert!(validate_log_level("").is_err());
+    }
+
+    #[test]
+    fn test_rotation_keeps_max_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let path = dir.join("daemon.log");
+        let appender = RollingFileAppender::new(path.clone(), 10, 2).unwrap();
+
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            (&appender).write_all(line.as_bytes()).unwrap();
+        }
+        (&appender).flush().unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "dddddddd\n");
+        assert_eq!(
+            std::fs::read_to_string(RollingFileAppender::rotated_path(&path, 1)).unwrap(),
+            "cccccccc\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(RollingFileAppender::rotated_path(&path, 2)).unwrap(),
+            "bbbbbbbb\n"
+        );
+        assert!(!RollingFileAppender::rotated_path(&path, 3).exists());
+    }
+}
@@ -1,18 +1,28 @@
+use std::path::Path;
 use std::process::Command;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::signal;
 use tokio::sync::mpsc;
 // Ne pas importer Result de anyhow directement à cause de conflits potentiels
-use tracing::{error, info};
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing::{error, info, warn};
 
 use encodetalker_common::ipc::IpcListener;
-use encodetalker_common::AppPaths;
+use encodetalker_common::{AppPaths, EncoderType, OutputNamer, PathsConfig};
+use encodetalker_daemon::logging::{self, RollingFileAppender};
 use encodetalker_daemon::{
-    DaemonConfig, DepsCompilationTracker, EncodingPipeline, IpcServer, Persistence, QueueManager,
+    built_dependencies, reconcile_deps_on_disk, resume_interrupted_build, AnalyticsLog,
+    CapabilitiesCache, DaemonConfig, DaemonLock, DepsCompilationTracker, DepsOnDisk,
+    EncodingPipeline, IdleTimer, IpcServer, Maintenance, OutputPermissions, Persistence,
+    ProgressLog, QueueManager, Schedule, ShutdownMode, SidecarReport, SourceCleanup,
 };
 
+/// Délai de regroupement des modifications d'état avant sauvegarde
+const STATE_SAVE_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Intervalle de vérification de l'inactivité du daemon (`idle_shutdown_secs`)
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Chercher un fichier en remontant les dossiers parents depuis l'exécutable
 fn find_script_from_exe(relative_path: &str) -> Option<std::path::PathBuf> {
     let mut dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
@@ -27,11 +37,9 @@ fn find_script_from_exe(relative_path: &str) -> Option<std::path::PathBuf> {
     }
 }
 
-/// Vérifie que toutes les dépendances sont installées via le script shell
-fn check_dependencies_installed() -> anyhow::Result<()> {
-    // Chercher le script en remontant depuis l'exécutable
-    let script_name = "scripts/CHECK_INSTALLED_DEPENDENCIES.sh";
-    let script_path = find_script_from_exe(script_name)
+/// Chercher un script du projet depuis l'exécutable, puis depuis le répertoire courant
+fn find_script(script_name: &str) -> anyhow::Result<std::path::PathBuf> {
+    find_script_from_exe(script_name)
         .or_else(|| {
             // Fallback : chercher depuis le répertoire courant
             let candidate = std::env::current_dir().ok()?.join(script_name);
@@ -42,7 +50,13 @@ fn check_dependencies_installed() -> anyhow::Result<()> {
                 "Script {script_name} not found.\n\
                 Please ensure you are running the daemon from the project directory."
             )
-        })?;
+        })
+}
+
+/// Vérifie que toutes les dépendances sont installées via le script shell
+fn check_dependencies_installed(svt_av1_bin: &Path, aomenc_bin: &Path) -> anyhow::Result<()> {
+    // Chercher le script en remontant depuis l'exécutable
+    let script_path = find_script("scripts/CHECK_INSTALLED_DEPENDENCIES.sh")?;
 
     info!(
         "Vérification des dépendances avec le script: {}",
@@ -50,8 +64,11 @@ fn check_dependencies_installed() -> anyhow::Result<()> {
     );
 
     // Exécuter le script
+    // Encodeurs éventuellement imposés par [encoder.binaries]
     let output = Command::new("bash")
         .arg(&script_path)
+        .env("SVT_AV1_BIN", svt_av1_bin)
+        .env("AOMENC_BIN", aomenc_bin)
         .output()
         .map_err(|e| anyhow::anyhow!("Échec de l'exécution du script de vérification: {e}"))?;
 
@@ -91,20 +108,65 @@ fn check_dependencies_installed() -> anyhow::Result<()> {
     Ok(())
 }
 
-#[tokio::main]
-#[allow(clippy::too_many_lines)] // Setup complet : logging, config, deps, queue, IPC
-async fn main() -> anyhow::Result<()> {
-    // Initialiser le logging
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+/// Valeur d'une option de ligne de commande (`--nom valeur` ou `--nom=valeur`)
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Attendre le signal de drain (SIGUSR1)
+#[cfg(unix)]
+async fn drain_signal() {
+    match signal::unix::signal(signal::unix::SignalKind::user_defined1()) {
+        Ok(mut sig) => {
+            sig.recv().await;
+        }
+        Err(e) => {
+            error!("Impossible d'écouter SIGUSR1, drain désactivé: {e}");
+            std::future::pending::<()>().await;
+        }
+    }
+}
 
-    fmt()
-        .with_env_filter(filter)
-        .with_target(false)
-        .with_thread_ids(true)
-        .init();
+/// Attendre le signal de drain (non supporté hors Unix)
+#[cfg(not(unix))]
+async fn drain_signal() {
+    std::future::pending::<()>().await;
+}
 
-    info!("EncodeTalker Daemon v{}", env!("CARGO_PKG_VERSION"));
+/// Attendre que le daemon soit resté inactif pendant `timeout` : dépendances prêtes,
+/// aucun job actif ni en attente, aucun benchmark et aucun nouveau job (None = jamais)
+async fn idle_shutdown(
+    queue_manager: &QueueManager,
+    deps_tracker: &DepsCompilationTracker,
+    timeout: Option<Duration>,
+) {
+    let Some(timeout) = timeout else {
+        return std::future::pending().await;
+    };
+    let mut timer = IdleTimer::new(timeout);
+    let mut interval = tokio::time::interval(IDLE_POLL_INTERVAL.min(timeout));
+    loop {
+        interval.tick().await;
+        let deps = deps_tracker.get_status();
+        let busy = !deps.all_present || deps.compiling || queue_manager.has_work().await;
+        if timer.observe(busy, queue_manager.jobs_added(), Instant::now()) {
+            return;
+        }
+    }
+}
 
+#[tokio::main]
+#[allow(clippy::too_many_lines)] // Setup complet : logging, config, deps, queue, IPC
+async fn main() -> anyhow::Result<()> {
     // ÉTAPE 1: Créer AppPaths par défaut pour trouver config.toml
     let default_paths = AppPaths::new().map_err(|e| anyhow::anyhow!("{e}"))?;
     default_paths
@@ -113,10 +175,6 @@ async fn main() -> anyhow::Result<()> {
 
     // ÉTAPE 2: Charger config.toml (peut contenir [paths] personnalisés)
     let config = DaemonConfig::load_or_default(&default_paths.config_file);
-    info!(
-        "Configuration chargée depuis {:?}",
-        default_paths.config_file
-    );
 
     // ÉTAPE 3: Recréer AppPaths avec la config (chemins personnalisés si définis)
     let paths =
@@ -125,6 +183,64 @@ async fn main() -> anyhow::Result<()> {
         .ensure_dirs_exist()
         .map_err(|e| anyhow::anyhow!("{e}"))?;
 
+    // Diagnostic préalable à la compilation des dépendances : afficher et quitter
+    if std::env::args().any(|arg| arg == "--check-build-env") {
+        let report = encodetalker_daemon::build_env::check_build_env(&paths.deps_dir).await;
+        for check in &report.checks {
+            let icon = match (check.found, check.required) {
+                (true, _) => "✓",
+                (false, true) => "✗",
+                (false, false) => "!",
+            };
+            println!("{icon} {:<24} {}", check.name, check.detail);
+        }
+        if report.is_ready() {
+            println!("Environnement prêt pour ./INSTALL_DEPENDENCIES.sh");
+            return Ok(());
+        }
+        anyhow::bail!("Outils de compilation manquants");
+    }
+
+    // Demander au daemon en cours de se drainer puis quitter
+    if std::env::args().any(|arg| arg == "--drain") {
+        let pid = encodetalker_daemon::send_drain_signal(&paths.pid_file)?;
+        println!("Drain demandé au daemon (PID {pid}) : il termine ses jobs actifs puis s'arrête");
+        return Ok(());
+    }
+
+    // Initialiser le logging (niveau et fichier surchargeables par --log-level / --log-file)
+    let log_file = match arg_value("--log-file") {
+        Some(path) => PathsConfig::expand_path(&path)?,
+        None => paths.log_file.clone(),
+    };
+    let appender = RollingFileAppender::new(
+        log_file.clone(),
+        config.daemon.log_max_size_mb.saturating_mul(1024 * 1024),
+        config.daemon.log_max_files,
+    );
+    let (filter, filter_warnings) = logging::build_filter(
+        arg_value("--log-level").as_deref(),
+        &config.daemon.log_level,
+    );
+    let appender_error = appender.as_ref().err().map(ToString::to_string);
+    logging::init(filter, appender.ok());
+
+    info!("EncodeTalker Daemon v{}", env!("CARGO_PKG_VERSION"));
+    info!(
+        "Configuration chargée depuis {:?}",
+        default_paths.config_file
+    );
+    for warning in filter_warnings {
+        warn!("{warning}");
+    }
+    match appender_error {
+        Some(e) => warn!("Fichier de log {:?} inutilisable: {e}", log_file),
+        None => info!("Logs écrits dans {:?}", log_file),
+    }
+
+    // Verrou d'instance : attend la fin d'un daemon en drain avant de prendre le socket
+    let lock = DaemonLock::acquire(&paths.pid_file).await?;
+
     // ÉTAPE 4: Logger les chemins utilisés
     info!("Chemins utilisés:");
     info!("  - Données:      {:?}", paths.data_dir);
@@ -137,9 +253,6 @@ async fn main() -> anyhow::Result<()> {
     let listener = IpcListener::bind(&paths.socket_path)?;
     info!("Listener IPC créé et en écoute");
 
-    // Vérifier que les dépendances sont installées (exit si manquantes)
-    check_dependencies_installed()?;
-
     // Utiliser les binaires depuis le répertoire de dépendances
     let deps_bin = paths.deps_bin_dir.clone();
     #[cfg(unix)]
@@ -147,50 +260,258 @@ async fn main() -> anyhow::Result<()> {
     #[cfg(windows)]
     let exe_suffix = ".exe";
 
+    // Encodeurs : chemins imposés par [encoder.binaries], sinon ceux des dépendances
+    let svt_av1_bin = config.encoder.binaries.resolve(
+        EncoderType::SvtAv1,
+        deps_bin.join(format!("SvtAv1EncApp{exe_suffix}")),
+    )?;
+    let aomenc_bin = config.encoder.binaries.resolve(
+        EncoderType::Aom,
+        deps_bin.join(format!("aomenc{exe_suffix}")),
+    )?;
+    for (name, path) in [("svt-av1", &svt_av1_bin), ("aom", &aomenc_bin)] {
+        if !path.starts_with(&deps_bin) {
+            info!(
+                "Encodeur {name} imposé par la configuration: {}",
+                path.display()
+            );
+        }
+    }
+
+    // Compilation interrompue (marqueur du script resté) : la reprendre pour les binaires
+    // manquants ; sinon vérifier que les dépendances sont installées (exit si manquantes)
+    let mut missing_tools = None;
+    let resume =
+        match reconcile_deps_on_disk(&paths.deps_dir, &built_dependencies(&deps_bin, exe_suffix)) {
+            DepsOnDisk::Interrupted { missing } => {
+                match find_script("scripts/INSTALL_DEPENDENCIES.sh") {
+                    Ok(script) => {
+                        let names: Vec<&str> = missing.iter().map(|dep| dep.name).collect();
+                        // Sans outils de compilation, la reprise échouerait après un long
+                        // build : signaler tout de suite les paquets à installer
+                        let path_var = std::env::var_os("PATH").unwrap_or_default();
+                        let checks = encodetalker_daemon::build_env::check_system_deps(&path_var);
+                        if let Some(message) =
+                            encodetalker_daemon::build_env::missing_tools_message(&checks)
+                        {
+                            error!(
+                                "Reprise de la compilation impossible ({}): {message}",
+                                names.join(", ")
+                            );
+                            missing_tools = Some(message);
+                            None
+                        } else {
+                            warn!(
+                                "Compilation des dépendances interrompue, reprise: {}",
+                                names.join(", ")
+                            );
+                            Some((script, missing))
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Reprise de la compilation impossible: {e}");
+                        check_dependencies_installed(&svt_av1_bin, &aomenc_bin)?;
+                        None
+                    }
+                }
+            }
+            DepsOnDisk::Ready | DepsOnDisk::Missing { .. } => {
+                check_dependencies_installed(&svt_av1_bin, &aomenc_bin)?;
+                None
+            }
+        };
+
     let ffmpeg_bin = deps_bin.join(format!("ffmpeg{exe_suffix}"));
     let ffprobe_bin = deps_bin.join(format!("ffprobe{exe_suffix}"));
-    let svt_av1_bin = deps_bin.join(format!("SvtAv1EncApp{exe_suffix}"));
-    let aomenc_bin = deps_bin.join(format!("aomenc{exe_suffix}"));
+
+    // Cache des capacités des encodeurs (interrogé à la demande via IPC)
+    let capabilities = Arc::new(CapabilitiesCache::new(
+        ffmpeg_bin.clone(),
+        svt_av1_bin.clone(),
+        aomenc_bin.clone(),
+    ));
+
+    // Signaler une version SVT-AV1 trop ancienne pour certaines options, ou une variante
+    // installée qui ne correspond pas à la configuration
+    {
+        let capabilities = capabilities.clone();
+        let expected = config.deps.svt_av1_variant;
+        tokio::spawn(async move {
+            let caps = capabilities.get().await;
+            if let Some(info) = &caps.svt_av1 {
+                if !info.disabled_options.is_empty() {
+                    warn!(
+                        "{} trop ancien, options désactivées: {}",
+                        info.version.as_deref().unwrap_or("SvtAv1EncApp"),
+                        info.disabled_options.join(", ")
+                    );
+                }
+            }
+            if let Some(installed) = caps.svt_av1_variant() {
+                if installed == expected {
+                    info!("Encodeur SVT-AV1 installé: {installed}");
+                } else {
+                    warn!(
+                        "{installed} est installé mais la configuration demande {expected}: \
+                         relancer INSTALL_DEPENDENCIES.sh --svt-av1 pour recompiler"
+                    );
+                }
+            }
+            let hardware = caps.hardware_encoders();
+            if !hardware.is_empty() {
+                let names: Vec<String> = hardware.iter().map(ToString::to_string).collect();
+                info!(
+                    "Encodeurs matériels détectés dans ffmpeg: {}",
+                    names.join(", ")
+                );
+            }
+        });
+    }
 
     // Créer le pipeline d'encodage
+    let temp_dir = config
+        .encoding
+        .temp_dir
+        .as_deref()
+        .map(PathsConfig::expand_path)
+        .transpose()?;
     let pipeline = EncodingPipeline::new(
         ffmpeg_bin.clone(),
         ffprobe_bin.clone(),
         svt_av1_bin,
         aomenc_bin,
         config.encoding.precise_frame_count,
-    );
+        Duration::from_millis(config.encoding.stats_update_interval_ms),
+    )
+    .with_precise_count_timeout(Duration::from_secs(
+        config.encoding.precise_frame_count_timeout_secs,
+    ))
+    .with_capabilities(capabilities.clone())
+    .with_audio_concurrency(config.daemon.max_audio_encodes)
+    .with_audio_threads(config.daemon.audio_threads)
+    .with_temp_dir(temp_dir)
+    .with_vaapi_device(PathsConfig::expand_path(
+        &config.encoder.hardware.vaapi_device,
+    )?);
 
     // Créer la persistance
     let persistence = Persistence::new(paths.state_file.clone());
 
     // Channel pour les événements de la queue
     let (event_tx, event_rx) = mpsc::unbounded_channel();
+    let deps_event_tx = event_tx.clone();
+
+    // Sort des sources après succès (opt-in) : une config invalide les conserve
+    let source_cleanup = match SourceCleanup::from_settings(&config.source_cleanup) {
+        Ok(cleanup) => cleanup,
+        Err(e) => {
+            error!("{e}, sources conservées");
+            None
+        }
+    };
+    if source_cleanup.is_some() {
+        warn!(
+            "Sources des jobs réussis traitées automatiquement : {:?} (vérification de la sortie : {})",
+            config.source_cleanup.action, config.source_cleanup.verify_output
+        );
+    }
+
+    // Mode et groupe des sorties : une config invalide les laisse inchangés
+    let output_permissions = match OutputPermissions::from_settings(&config.output_permissions) {
+        Ok(permissions) => permissions,
+        Err(e) => {
+            error!("{e}, permissions des sorties inchangées");
+            None
+        }
+    };
+    if output_permissions.is_some() && cfg!(not(unix)) {
+        warn!("output_permissions ignoré sur cette plateforme");
+    }
+
+    // Rapport JSON des sorties : une config invalide le désactive
+    let sidecar_report = match SidecarReport::from_settings(&config.sidecar_report) {
+        Ok(report) => report,
+        Err(e) => {
+            error!("{e}, rapports désactivés");
+            None
+        }
+    };
+
+    // Heures de démarrage des jobs : une expression invalide empêche le démarrage, pour ne
+    // jamais encoder hors des heures voulues
+    let schedule = Schedule::from_settings(&config.schedule)?;
+
+    // Nommage des sorties : une config invalide retombe sur `{stem}.av1.mkv` à côté de la source
+    let output_namer = match config.output_namer() {
+        Ok(namer) => namer,
+        Err(e) => {
+            error!("{e:#}, nommage des sorties par défaut");
+            OutputNamer::default()
+        }
+    };
 
     // Créer le queue manager
-    let queue_manager = Arc::new(QueueManager::new(
-        config.daemon.max_concurrent_jobs,
-        pipeline,
-        persistence,
-        event_tx,
-    ));
+    let queue_manager = Arc::new(
+        QueueManager::new(
+            config.daemon.max_concurrent_jobs,
+            pipeline,
+            persistence,
+            AnalyticsLog::new(paths.analytics_file.clone()),
+            event_tx,
+            config.fallback.active_steps(),
+        )
+        .with_stall_timeout(config.daemon.stall_timeout())
+        .with_thread_budget(config.daemon.thread_budget())
+        .with_create_output_dirs(config.encoding.create_output_dirs)
+        .with_output_namer(output_namer)
+        .with_source_cleanup(source_cleanup)
+        .with_output_permissions(output_permissions)
+        .with_sidecar_report(sidecar_report)
+        .with_progress_log(ProgressLog::from_settings(
+            &config.progress_log,
+            paths.progress_file.clone(),
+        ))
+        .with_audio_prefetch(config.daemon.audio_prefetch_jobs)
+        .with_retry(config.retry.active_policy())
+        .with_schedule(schedule),
+    );
 
     // Charger l'état sauvegardé
     if let Err(e) = queue_manager.load_state().await {
         error!("Échec du chargement de l'état: {}", e);
     }
 
-    // Lancer la loop de démarrage de jobs
-    let queue_manager_starter = queue_manager.clone();
-    let job_starter_task = tokio::spawn(async move {
-        queue_manager_starter.run_job_starter().await;
-    });
-
     // Créer le tracker de compilation
     let deps_tracker = Arc::new(DepsCompilationTracker::new());
 
-    // Les dépendances sont toutes présentes (vérification faite plus haut)
-    deps_tracker.set_all_present();
+    // Lancer la loop de démarrage de jobs
+    let queue_manager_starter = queue_manager.clone();
+    let job_starter_task = if let Some(message) = missing_tools {
+        // Dépendances incomplètes : aucun job ne peut démarrer avant l'installation
+        // des outils et le redémarrage du daemon
+        deps_tracker.set_error(message);
+        tokio::spawn(async {})
+    } else if let Some((script, missing)) = resume {
+        // Les jobs ne démarrent qu'une fois les binaires manquants installés
+        let tracker = deps_tracker.clone();
+        tokio::spawn(async move {
+            if resume_interrupted_build(&script, missing, &tracker, &deps_event_tx)
+                .await
+                .is_ok()
+            {
+                queue_manager_starter.run_job_starter().await;
+            }
+        })
+    } else {
+        // Les dépendances sont toutes présentes (vérification faite plus haut)
+        deps_tracker.set_all_present();
+        tokio::spawn(async move {
+            queue_manager_starter.run_job_starter().await;
+        })
+    };
+
+    // Relancer le démarrage des jobs à l'ouverture des plages de `[schedule]`
+    let schedule_task = tokio::spawn(queue_manager.clone().run_schedule());
 
     // Créer le serveur IPC
     let ipc_server = IpcServer::new(
@@ -199,15 +520,17 @@ async fn main() -> anyhow::Result<()> {
         deps_tracker.clone(),
         &ffprobe_bin,
         &ffmpeg_bin,
+        capabilities,
+        Maintenance::new(paths.deps_src_dir.clone(), log_file.clone()),
     );
 
-    // Tâche d'auto-save périodique
+    // Tâche d'auto-save : sauvegarde après chaque changement, regroupée par anti-rebond
     let queue_manager_save = queue_manager.clone();
     let auto_save_task = tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(10));
         loop {
-            interval.tick().await;
-            if let Err(e) = queue_manager_save.save_state().await {
+            queue_manager_save.wait_state_changed().await;
+            tokio::time::sleep(STATE_SAVE_DEBOUNCE).await;
+            if let Err(e) = queue_manager_save.save_if_dirty().await {
                 error!("Échec de l'auto-save: {}", e);
             }
         }
@@ -222,24 +545,70 @@ async fn main() -> anyhow::Result<()> {
 
     info!("Daemon démarré, serveur IPC en cours d'exécution");
 
-    // Attendre le signal de shutdown
-    tokio::select! {
+    // Attendre le signal de shutdown (ou de drain)
+    let draining = tokio::select! {
         _ = signal::ctrl_c() => {
             info!("Signal SIGINT reçu, arrêt graceful...");
+            false
+        }
+        () = drain_signal() => {
+            info!("Signal de drain reçu: fin des jobs actifs, la queue est conservée");
+            if let Err(e) = lock.mark_draining() {
+                error!("Échec du marquage du drain: {}", e);
+            }
+            true
+        }
+        () = queue_manager.shutdown_requested() => {
+            info!("Arrêt demandé par un client, arrêt graceful...");
+            // Le client relance souvent un daemon aussitôt le socket fermé : il doit
+            // attendre la libération du verrou plutôt que refuser de démarrer
+            if let Err(e) = lock.mark_draining() {
+                error!("Échec du marquage de l'arrêt: {}", e);
+            }
+            false
+        }
+        () = idle_shutdown(&queue_manager, &deps_tracker, config.daemon.idle_shutdown()) => {
+            info!(
+                "Aucun job depuis {} s, arrêt du daemon inactif",
+                config.daemon.idle_shutdown_secs
+            );
+            // Un client peut relancer un daemon pendant l'arrêt : il attend le verrou
+            if let Err(e) = lock.mark_draining() {
+                error!("Échec du marquage de l'arrêt: {}", e);
+            }
+            false
         }
         _ = ipc_task => {
             info!("Serveur IPC terminé");
+            false
         }
-    }
+    };
 
-    // Arrêter d'accepter les nouveaux jobs
+    // Arrêter d'accepter les nouveaux jobs (et de démarrer ceux de la queue)
     queue_manager.stop_accepting_jobs().await;
 
-    // Attendre que les jobs actifs se terminent (timeout 30s)
-    info!("Attente de la fin des jobs actifs...");
-    queue_manager
-        .wait_active_jobs(Duration::from_secs(30))
-        .await;
+    // Jobs actifs : le drain attend sans limite, sinon selon `shutdown_mode`
+    let shutdown_mode = if draining {
+        ShutdownMode::Wait
+    } else {
+        config.daemon.shutdown_mode
+    };
+    if shutdown_mode == ShutdownMode::Wait {
+        info!("Attente de la fin des jobs actifs...");
+        let timeout = if draining {
+            Duration::MAX
+        } else {
+            Duration::from_secs(30)
+        };
+        queue_manager.wait_active_jobs(timeout).await;
+    }
+    // Jobs restants (timeout atteint ou mode d'annulation) : tuer leurs processus
+    queue_manager.cancel_active_jobs(shutdown_mode).await;
+    // Enfants ayant survécu à l'annulation : annuler les tâches ne les tuerait pas
+    let killed = queue_manager.kill_child_processes();
+    if killed > 0 {
+        warn!("{} processus enfants tués à l'arrêt", killed);
+    }
 
     // Sauvegarder l'état final
     info!("Sauvegarde de l'état final...");
@@ -258,6 +627,10 @@ async fn main() -> anyhow::Result<()> {
     // Arrêter les tâches
     auto_save_task.abort();
     job_starter_task.abort();
+    schedule_task.abort();
+
+    // Libérer le verrou en dernier : le daemon suivant peut alors prendre le socket
+    drop(lock);
 
     info!("Daemon arrêté proprement");
     anyhow::Ok(())
@@ -0,0 +1,394 @@
+use crate::deps_tracker::DepsCompilationTracker;
+use crate::encoder::ThumbnailCache;
+use crate::queue::QueueManager;
+use encodetalker_common::{CleanupResult, CleanupTarget};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+/// Âge minimal d'un fichier temporaire avant suppression (il n'est plus écrit)
+pub const STALE_TEMP_AGE: Duration = Duration::from_secs(3600);
+
+/// Préfixes des fichiers temporaires du daemon (voir `TempWorkspace::with_prefix`)
+const TEMP_PREFIXES: &[&str] = &[
+    "encodetalker-audio-",
+    "encodetalker-benchmark-",
+    ".encodetalker-write-test-",
+];
+
+/// Nom d'un fichier temporaire du daemon : `<préfixe><uuid>[.extension]`
+///
+/// Un `<uuid>[.extension]` sans préfixe n'est reconnu qu'avec `bare_uuid`, c'est-à-dire
+/// dans `encoding.temp_dir` : ailleurs (répertoire temporaire du système), un tel nom
+/// peut appartenir à un autre programme.
+#[must_use]
+pub fn is_temp_file_name(name: &str, bare_uuid: bool) -> bool {
+    let rest = match TEMP_PREFIXES
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+    {
+        Some(rest) => rest,
+        None if bare_uuid => name,
+        None => return false,
+    };
+    rest.split('.')
+        .next()
+        .is_some_and(|stem| Uuid::parse_str(stem).is_ok())
+}
+
+/// Répertoire parcouru par le nettoyage des fichiers temporaires
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TempDir {
+    path: PathBuf,
+    /// Noms `<uuid>` sans préfixe acceptés (`encoding.temp_dir` seulement)
+    bare_uuid: bool,
+}
+
+/// Répertoires temporaires du daemon : celui du système, puis `encoding.temp_dir`
+///
+/// Les répertoires de sortie ne sont jamais parcourus : leurs fichiers appartiennent
+/// à l'utilisateur.
+fn temp_dirs(configured: Option<PathBuf>) -> Vec<TempDir> {
+    let system = std::env::temp_dir();
+    let mut dirs = Vec::new();
+    if configured.as_ref() != Some(&system) {
+        dirs.push(TempDir {
+            path: system,
+            bare_uuid: false,
+        });
+    }
+    dirs.extend(configured.map(|path| TempDir {
+        path,
+        bare_uuid: true,
+    }));
+    dirs
+}
+
+/// Fichier ou répertoire à supprimer, avec sa taille
+struct Entry {
+    path: PathBuf,
+    bytes: u64,
+    files: usize,
+}
+
+/// Taille et nombre de fichiers d'une arborescence (liens symboliques non suivis)
+fn tree_size(path: &Path) -> (u64, usize) {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return (0, 0);
+    };
+    if !meta.is_dir() {
+        return (meta.len(), 1);
+    }
+    std::fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| tree_size(&entry.path()))
+        .fold((0, 0), |(bytes, files), (b, f)| (bytes + b, files + f))
+}
+
+/// Supprimer les entrées (ou seulement les compter avec `dry_run`)
+fn remove_entries(target: CleanupTarget, entries: Vec<Entry>, dry_run: bool) -> CleanupResult {
+    let mut result = CleanupResult {
+        target,
+        bytes: 0,
+        files: 0,
+        skipped: None,
+    };
+    let mut failures = Vec::new();
+    for entry in entries {
+        if !dry_run {
+            let removed = if entry.path.is_dir() {
+                std::fs::remove_dir_all(&entry.path)
+            } else {
+                std::fs::remove_file(&entry.path)
+            };
+            if let Err(e) = removed {
+                failures.push(format!("{}: {e}", entry.path.display()));
+                continue;
+            }
+        }
+        result.bytes += entry.bytes;
+        result.files += entry.files;
+    }
+    if let Some(first) = failures.first() {
+        result.skipped = Some(format!(
+            "{} suppression(s) impossible(s), dont {first}",
+            failures.len()
+        ));
+    }
+    result
+}
+
+/// Contenu de `deps/src` (le répertoire lui-même est gardé)
+fn source_entries(deps_src_dir: &Path) -> Vec<Entry> {
+    std::fs::read_dir(deps_src_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            let (bytes, files) = tree_size(&path);
+            Entry { path, bytes, files }
+        })
+        .collect()
+}
+
+/// Archives `<log>.N` du log ; le log courant reste ouvert par le daemon
+fn log_entries(log_file: &Path) -> Vec<Entry> {
+    let (Some(dir), Some(name)) = (log_file.parent(), log_file.file_name()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry.file_name().to_str().is_some_and(|name| {
+                name.strip_prefix(&prefix).is_some_and(|index| {
+                    !index.is_empty() && index.chars().all(|c| c.is_ascii_digit())
+                })
+            })
+        })
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok().filter(std::fs::Metadata::is_file)?;
+            Some(Entry {
+                path: entry.path(),
+                bytes: meta.len(),
+                files: 1,
+            })
+        })
+        .collect()
+}
+
+/// Fichiers temporaires orphelins des répertoires `dirs`, hors fichiers `protected`
+fn temp_entries(dirs: &[TempDir], protected: &HashSet<PathBuf>, now: SystemTime) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for dir in dirs {
+        for entry in std::fs::read_dir(&dir.path).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let stale = meta
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age >= STALE_TEMP_AGE);
+            if meta.is_file()
+                && stale
+                && !protected.contains(&path)
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| is_temp_file_name(name, dir.bare_uuid))
+            {
+                entries.push(Entry {
+                    path,
+                    bytes: meta.len(),
+                    files: 1,
+                });
+            }
+        }
+    }
+    entries
+}
+
+/// Nettoyage à la demande (`RequestPayload::Cleanup`) des fichiers accumulés par le daemon
+pub struct Maintenance {
+    deps_src_dir: PathBuf,
+    log_file: PathBuf,
+}
+
+impl Maintenance {
+    #[must_use]
+    pub fn new(deps_src_dir: PathBuf, log_file: PathBuf) -> Self {
+        Self {
+            deps_src_dir,
+            log_file,
+        }
+    }
+
+    /// Nettoyer les cibles demandées, dans l'ordre (ou estimer l'espace libérable)
+    ///
+    /// Une cible en cours d'utilisation est ignorée avec sa raison : les sources pendant
+    /// une compilation, les fichiers temporaires pendant un encodage ou un benchmark.
+    pub async fn cleanup(
+        &self,
+        targets: &[CleanupTarget],
+        dry_run: bool,
+        queue_manager: &QueueManager,
+        deps_tracker: &DepsCompilationTracker,
+        thumbnails: &ThumbnailCache,
+    ) -> Vec<CleanupResult> {
+        let mut results = Vec::new();
+        let mut seen = HashSet::new();
+        for &target in targets.iter().filter(|target| seen.insert(**target)) {
+            let result = match target {
+                CleanupTarget::Sources if deps_tracker.get_status().compiling => {
+                    CleanupResult::skipped(target, "compilation des dépendances en cours")
+                }
+                CleanupTarget::Sources => {
+                    let dir = self.deps_src_dir.clone();
+                    blocking(target, move || {
+                        remove_entries(target, source_entries(&dir), dry_run)
+                    })
+                    .await
+                }
+                CleanupTarget::Temp => match queue_manager.busy_reason().await {
+                    Some(reason) => CleanupResult::skipped(target, reason),
+                    None => {
+                        let dirs = temp_dirs(queue_manager.temp_dir());
+                        let protected = queue_manager.prefetched_audio_paths().await;
+                        blocking(target, move || {
+                            let entries = temp_entries(&dirs, &protected, SystemTime::now());
+                            remove_entries(target, entries, dry_run)
+                        })
+                        .await
+                    }
+                },
+                CleanupTarget::Logs => {
+                    let log_file = self.log_file.clone();
+                    blocking(target, move || {
+                        remove_entries(target, log_entries(&log_file), dry_run)
+                    })
+                    .await
+                }
+                CleanupTarget::Thumbnails => {
+                    let (bytes, files) = if dry_run {
+                        thumbnails.usage().await
+                    } else {
+                        thumbnails.clear().await
+                    };
+                    CleanupResult {
+                        target,
+                        bytes,
+                        files,
+                        skipped: None,
+                    }
+                }
+            };
+            results.push(result);
+        }
+        results
+    }
+}
+
+/// Exécuter un parcours du disque hors du runtime async
+async fn blocking(
+    target: CleanupTarget,
+    task: impl FnOnce() -> CleanupResult + Send + 'static,
+) -> CleanupResult {
+    tokio::task::spawn_blocking(task)
+        .await
+        .unwrap_or_else(|e| CleanupResult::skipped(target, format!("tâche interrompue: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_temp_file_name() {
+        let id = Uuid::new_v4();
+        assert!(is_temp_file_name(&format!("{id}.ivf"), true));
+        assert!(is_temp_file_name(&format!("{id}.log"), true));
+        assert!(is_temp_file_name(
+            &format!("encodetalker-audio-{id}.opus"),
+            true
+        ));
+        assert!(is_temp_file_name(
+            &format!(".encodetalker-write-test-{id}"),
+            true
+        ));
+        assert!(!is_temp_file_name("film.av1.mkv", true));
+        assert!(!is_temp_file_name("encodetalker-audio-film.opus", true));
+
+        // Hors de `encoding.temp_dir`, seuls les noms préfixés sont reconnus
+        assert!(!is_temp_file_name(&format!("{id}.ivf"), false));
+        assert!(is_temp_file_name(
+            &format!("encodetalker-audio-{id}.opus"),
+            false
+        ));
+        assert!(is_temp_file_name(
+            &format!("encodetalker-benchmark-{id}.ivf"),
+            false
+        ));
+    }
+
+    #[test]
+    fn test_temp_dirs_skip_output_dirs() {
+        let system = std::env::temp_dir();
+        assert_eq!(
+            temp_dirs(None),
+            [TempDir {
+                path: system.clone(),
+                bare_uuid: false,
+            }]
+        );
+        let configured = PathBuf::from("/var/tmp/encodetalker");
+        let dirs = temp_dirs(Some(configured.clone()));
+        assert_eq!(dirs.len(), 2);
+        assert!(!dirs[0].bare_uuid);
+        assert_eq!(dirs[1].path, configured);
+        assert!(dirs[1].bare_uuid);
+        // `temp_dir` pointant sur le répertoire du système : parcouru une seule fois
+        let dirs = temp_dirs(Some(system.clone()));
+        assert_eq!(dirs.len(), 1);
+        assert!(dirs[0].bare_uuid);
+    }
+
+    #[test]
+    fn test_cleanup_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let src = dir.join("src");
+        std::fs::create_dir_all(src.join("libaom/build")).unwrap();
+        std::fs::write(src.join("libaom/build/a.o"), [0; 100]).unwrap();
+        std::fs::write(src.join("svt.tar.gz"), [0; 50]).unwrap();
+        std::fs::write(dir.join("daemon.log"), [0; 10]).unwrap();
+        std::fs::write(dir.join("daemon.log.1"), [0; 20]).unwrap();
+        std::fs::write(dir.join("daemon.log.12"), [0; 30]).unwrap();
+        std::fs::write(dir.join("daemon.log.bak"), [0; 40]).unwrap();
+
+        // Estimation : rien n'est supprimé
+        let estimate = remove_entries(CleanupTarget::Sources, source_entries(&src), true);
+        assert_eq!((estimate.bytes, estimate.files), (150, 2));
+        assert!(src.join("svt.tar.gz").exists());
+        let removed = remove_entries(CleanupTarget::Sources, source_entries(&src), false);
+        assert_eq!((removed.bytes, removed.files), (150, 2));
+        assert!(src.exists());
+        assert_eq!(std::fs::read_dir(&src).unwrap().count(), 0);
+
+        let logs = remove_entries(
+            CleanupTarget::Logs,
+            log_entries(&dir.join("daemon.log")),
+            false,
+        );
+        assert_eq!((logs.bytes, logs.files), (50, 2));
+        assert!(dir.join("daemon.log").exists());
+        assert!(dir.join("daemon.log.bak").exists());
+
+        // Fichiers temporaires : anciens, au nom reconnu et non protégés seulement
+        let stale = dir.join(format!("{}.ivf", Uuid::new_v4()));
+        let prefetched = dir.join(format!("{}.opus", Uuid::new_v4()));
+        std::fs::write(&stale, [0; 7]).unwrap();
+        std::fs::write(&prefetched, [0; 9]).unwrap();
+        let protected = HashSet::from([prefetched.clone()]);
+        let mut dirs = [TempDir {
+            path: dir.clone(),
+            bare_uuid: true,
+        }];
+        assert!(temp_entries(&dirs, &protected, SystemTime::now()).is_empty());
+        let later = SystemTime::now() + STALE_TEMP_AGE;
+        let entries = temp_entries(&dirs, &protected, later);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, stale);
+
+        // Répertoire du système : un `<uuid>` sans préfixe n'est pas au daemon
+        dirs[0].bare_uuid = false;
+        assert!(temp_entries(&dirs, &protected, later).is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use encodetalker_common::{parse_analytics_jsonl, AnalyticsRecord};
+use std::path::PathBuf;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+
+/// Journal analytique des jobs terminés (JSONL, ajout seul)
+pub struct AnalyticsLog {
+    path: PathBuf,
+}
+
+impl AnalyticsLog {
+    #[must_use]
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Lire tous les enregistrements (fichier absent = aucun)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le fichier existe mais ne peut pas être lu.
+    pub async fn load(&self) -> Result<Vec<AnalyticsRecord>> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => Ok(parse_analytics_jsonl(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e).context("Échec de lecture du fichier d'analytique"),
+        }
+    }
+
+    /// Ajouter un enregistrement en fin de fichier
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la sérialisation échoue ou si le fichier ne peut pas être écrit.
+    pub async fn append(&self, record: &AnalyticsRecord) -> Result<()> {
+        let mut line =
+            serde_json::to_string(record).context("Échec de sérialisation de l'analytique")?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .context("Échec d'ouverture du fichier d'analytique")?;
+
+        file.write_all(line.as_bytes())
+            .await
+            .context("Échec d'écriture du fichier d'analytique")?;
+
+        Ok(())
+    }
+}
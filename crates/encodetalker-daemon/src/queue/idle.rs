@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+/// Détection d'un daemon inactif (`idle_shutdown_secs`)
+///
+/// Le délai court depuis la première observation sans travail (job actif ou en attente,
+/// benchmark, dépendances pas prêtes). Tout travail ou nouveau job le remet à zéro, même
+/// un job ajouté et terminé entre deux observations.
+#[derive(Debug, Clone)]
+pub struct IdleTimer {
+    timeout: Duration,
+    idle_since: Option<Instant>,
+    jobs_added: u64,
+}
+
+impl IdleTimer {
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            idle_since: None,
+            jobs_added: 0,
+        }
+    }
+
+    /// Prendre en compte l'état du daemon (`jobs_added` = jobs reçus depuis le démarrage)
+    ///
+    /// Retourne vrai une fois le délai écoulé sans travail ni nouveau job.
+    pub fn observe(&mut self, busy: bool, jobs_added: u64, now: Instant) -> bool {
+        if busy || jobs_added != self.jobs_added {
+            self.jobs_added = jobs_added;
+            self.idle_since = None;
+            return false;
+        }
+        let since = *self.idle_since.get_or_insert(now);
+        now.duration_since(since) >= self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_timer() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut timer = IdleTimer::new(Duration::from_secs(60));
+
+        assert!(!timer.observe(false, 0, at(0)));
+        assert!(!timer.observe(false, 0, at(59)));
+        assert!(timer.observe(false, 0, at(60)));
+
+        // Un job reçu entre deux observations relance le délai, même s'il est déjà terminé
+        assert!(!timer.observe(false, 1, at(61)));
+        assert!(!timer.observe(false, 1, at(100)));
+        assert!(!timer.observe(true, 1, at(130)));
+        assert!(!timer.observe(false, 1, at(140)));
+        assert!(!timer.observe(false, 1, at(199)));
+        assert!(timer.observe(false, 1, at(200)));
+    }
+}
@@ -1,10 +1,24 @@
-use super::{PersistedState, Persistence};
-use crate::encoder::EncodingPipeline;
+use super::{
+    AnalyticsLog, OutputPermissions, PersistedState, Persistence, ProgressLog, ProgressSample,
+    Schedule, SidecarReport, SourceCleanup, StallWatchdog, ThreadBudget,
+};
+use crate::config::{FallbackStep, RetryAction, RetrySettings, ShutdownMode};
+use crate::encoder::{
+    ensure_dir_writable, output_dir, vmaf_log_path, EncodeError, EncodeOutcome, EncodingPipeline,
+};
 use anyhow::Result;
+use chrono::Timelike;
 use encodetalker_common::protocol::messages::DepsCompilationStep;
-use encodetalker_common::{EncodingJob, EncodingStats, JobStatus};
-use std::collections::{HashMap, VecDeque};
+use encodetalker_common::{
+    AnalyticsRecord, BenchmarkReport, CollisionPolicy, EncoderType, EncodingJob, EncodingStats,
+    FailureCategory, JobKind, JobStatus, OutputNamer, PresetBenchmark, QueueSnapshot, SpeedHistory,
+    VerifyReport, VideoMode,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tracing::{error, info, warn};
 use uuid::Uuid;
@@ -14,13 +28,19 @@ use uuid::Uuid;
 pub enum QueueEvent {
     JobAdded(Uuid),
     JobStarted(Uuid),
-    JobProgress(Uuid, EncodingStats),
+    JobProgress(Uuid, Box<EncodingStats>),
     JobCompleted(Uuid),
-    JobFailed(Uuid, String),
+    JobFailed(Uuid, String, FailureCategory),
     JobCancelled(Uuid),
+    JobMoved(Uuid, usize),
+    HistoryRemoved(Uuid),
+    HistoryCleared,
+    StoppedAcceptingJobs,
     // Événements de compilation des dépendances
     DepsCompilationStarted {
         total_deps: usize,
+        resumed: bool,
+        deps: Vec<String>,
     },
     DepsCompilationProgress {
         dep_name: String,
@@ -38,6 +58,14 @@ pub enum QueueEvent {
         dep_name: String,
         error: String,
     },
+    DepsCompilationLog {
+        lines: Vec<String>,
+        skipped: usize,
+    },
+    // Événements du benchmark des presets
+    BenchmarkProgress(PresetBenchmark),
+    BenchmarkCompleted(BenchmarkReport),
+    BenchmarkFailed(String),
 }
 
 /// Contrôle d'un job en cours
@@ -67,6 +95,42 @@ pub struct QueueManager {
     accepting_jobs: Arc<RwLock<bool>>,
     /// Notify pour démarrage de jobs
     start_notify: Arc<tokio::sync::Notify>,
+    /// Arrêt du daemon demandé par un client (`RequestPayload::Shutdown`)
+    shutdown_notify: Arc<tokio::sync::Notify>,
+    /// Étapes de repli après un crash de l'encodeur (vide = désactivé)
+    fallback_steps: Arc<Vec<FallbackStep>>,
+    /// Journal analytique des jobs terminés
+    analytics: Arc<AnalyticsLog>,
+    /// Vitesse historique par encodeur/preset (amorce de l'ETA)
+    speed_history: Arc<RwLock<SpeedHistory>>,
+    /// Annulation du benchmark en cours (None = aucun benchmark)
+    benchmark_cancel: Arc<Mutex<Option<mpsc::UnboundedSender<()>>>>,
+    /// Mode d'arrêt en cours (None = annulations demandées par un client)
+    shutdown_mode: Arc<RwLock<Option<ShutdownMode>>>,
+    /// Délai sans progression avant d'arrêter un job (None = watchdog désactivé)
+    stall_timeout: Option<Duration>,
+    /// Créer le répertoire de sortie d'un job s'il n'existe pas encore
+    create_output_dirs: bool,
+    /// Nommage des sorties et politique de collision des nouveaux jobs
+    output_namer: Arc<OutputNamer>,
+    /// Budget global de threads des jobs actifs (None = chaque job décide seul)
+    thread_budget: Option<Arc<Mutex<ThreadBudget>>>,
+    /// Action sur les sources des jobs réussis (None = conservées)
+    source_cleanup: Option<Arc<SourceCleanup>>,
+    /// Mode et groupe appliqués aux sorties des jobs réussis (None = inchangés)
+    output_permissions: Option<OutputPermissions>,
+    /// Rapport JSON écrit à côté des sorties des jobs réussis (None = aucun)
+    sidecar_report: Option<Arc<SidecarReport>>,
+    /// Échantillons de progression des jobs en cours (None = désactivé)
+    progress_log: Option<Arc<ProgressLog>>,
+    /// Jobs en attente dont l'audio est pré-encodé pendant les encodages (0 = désactivé)
+    audio_prefetch_jobs: usize,
+    /// Relance du job entier après un échec (None = désactivée)
+    retry: Option<Arc<RetrySettings>>,
+    /// Heures de démarrage des jobs (None = toute heure)
+    schedule: Option<Arc<Schedule>>,
+    /// Jobs reçus depuis le démarrage (remet à zéro le délai d'inactivité)
+    jobs_added: Arc<AtomicU64>,
 }
 
 impl QueueManager {
@@ -75,7 +139,9 @@ impl QueueManager {
         max_concurrent: usize,
         pipeline: EncodingPipeline,
         persistence: Persistence,
+        analytics: AnalyticsLog,
         event_tx: mpsc::UnboundedSender<QueueEvent>,
+        fallback_steps: Vec<FallbackStep>,
     ) -> Self {
         Self {
             queue: Arc::new(RwLock::new(VecDeque::new())),
@@ -88,9 +154,112 @@ impl QueueManager {
             persistence: Arc::new(persistence),
             accepting_jobs: Arc::new(RwLock::new(true)),
             start_notify: Arc::new(tokio::sync::Notify::new()),
+            shutdown_notify: Arc::new(tokio::sync::Notify::new()),
+            fallback_steps: Arc::new(fallback_steps),
+            analytics: Arc::new(analytics),
+            speed_history: Arc::new(RwLock::new(SpeedHistory::default())),
+            benchmark_cancel: Arc::new(Mutex::new(None)),
+            shutdown_mode: Arc::new(RwLock::new(None)),
+            stall_timeout: None,
+            create_output_dirs: true,
+            output_namer: Arc::new(OutputNamer::default()),
+            thread_budget: None,
+            source_cleanup: None,
+            output_permissions: None,
+            sidecar_report: None,
+            progress_log: None,
+            audio_prefetch_jobs: 0,
+            retry: None,
+            schedule: None,
+            jobs_added: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Relancer le job entier après un échec, selon les règles de `[retry]`
+    #[must_use]
+    pub fn with_retry(mut self, retry: Option<RetrySettings>) -> Self {
+        self.retry = retry.map(Arc::new);
+        self
+    }
+
+    /// Ne démarrer les jobs que pendant les minutes décrites par `[schedule]`
+    #[must_use]
+    pub fn with_schedule(mut self, schedule: Option<Schedule>) -> Self {
+        self.schedule = schedule.map(Arc::new);
+        self
+    }
+
+    /// Pré-encoder l'audio des `jobs` prochains jobs en attente pendant qu'un job tourne
+    #[must_use]
+    pub fn with_audio_prefetch(mut self, jobs: usize) -> Self {
+        self.audio_prefetch_jobs = jobs;
+        self
+    }
+
+    /// Déplacer, supprimer ou mettre à la corbeille la source des jobs réussis
+    #[must_use]
+    pub fn with_source_cleanup(mut self, cleanup: Option<SourceCleanup>) -> Self {
+        self.source_cleanup = cleanup.map(Arc::new);
+        self
+    }
+
+    /// Appliquer un mode et un groupe aux fichiers produits par les jobs réussis
+    #[must_use]
+    pub fn with_output_permissions(mut self, permissions: Option<OutputPermissions>) -> Self {
+        self.output_permissions = permissions;
+        self
+    }
+
+    /// Écrire un rapport JSON (job terminé et probe de la source) à côté de chaque sortie
+    #[must_use]
+    pub fn with_sidecar_report(mut self, report: Option<SidecarReport>) -> Self {
+        self.sidecar_report = report.map(Arc::new);
+        self
+    }
+
+    /// Journaliser la progression des jobs en cours, un échantillon par intervalle et par job
+    #[must_use]
+    pub fn with_progress_log(mut self, log: Option<ProgressLog>) -> Self {
+        self.progress_log = log.map(Arc::new);
+        self
+    }
+
+    /// Répartir `total` threads entre les emplacements de jobs (None = pas de budget global)
+    #[must_use]
+    pub fn with_thread_budget(mut self, total: Option<u32>) -> Self {
+        let slots = self.max_concurrent;
+        self.thread_budget =
+            total.map(|total| Arc::new(Mutex::new(ThreadBudget::new(total, slots))));
+        self
+    }
+
+    /// Arrêter les jobs sans progression pendant `timeout` (catégorie `Stalled`)
+    #[must_use]
+    pub fn with_stall_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.stall_timeout = timeout;
+        self
+    }
+
+    /// Créer (ou non) les répertoires de sortie manquants à l'ajout d'un job
+    #[must_use]
+    pub fn with_create_output_dirs(mut self, create: bool) -> Self {
+        self.create_output_dirs = create;
+        self
+    }
+
+    /// Nommer les sorties des jobs soumis sans chemin et traiter les sorties déjà prises
+    #[must_use]
+    pub fn with_output_namer(mut self, namer: OutputNamer) -> Self {
+        self.output_namer = Arc::new(namer);
+        self
+    }
+
+    /// Nommage des sorties (`output_template`, `output_dir`, `on_collision`)
+    #[must_use]
+    pub fn output_namer(&self) -> &OutputNamer {
+        &self.output_namer
+    }
+
     /// Charger l'état depuis le disque
     ///
     /// # Errors
@@ -112,6 +281,12 @@ impl QueueManager {
         let mut history = self.history.write().await;
         *history = state.history;
 
+        // L'analytique n'est qu'une aide à l'ETA : un fichier illisible n'empêche pas le démarrage
+        match self.analytics.load().await {
+            Ok(records) => *self.speed_history.write().await = SpeedHistory::from_records(&records),
+            Err(e) => warn!("Vitesse historique indisponible: {e}"),
+        }
+
         Ok(())
     }
 
@@ -130,20 +305,89 @@ impl QueueManager {
         self.persistence.save(&state).await
     }
 
+    /// Sauvegarder l'état uniquement s'il a changé depuis la dernière sauvegarde
+    ///
+    /// Retourne `true` si une sauvegarde a eu lieu. En cas d'échec, l'état reste
+    /// marqué modifié pour une nouvelle tentative.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la sauvegarde échoue.
+    pub async fn save_if_dirty(&self) -> Result<bool> {
+        if !self.persistence.take_dirty() {
+            return Ok(false);
+        }
+        if let Err(e) = self.save_state().await {
+            self.persistence.mark_dirty();
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Attendre la prochaine modification de l'état (pour l'auto-save)
+    pub async fn wait_state_changed(&self) {
+        self.persistence.changed().await;
+    }
+
+    /// Demander l'arrêt du daemon (traité par la boucle principale, selon `shutdown_mode`)
+    pub fn request_shutdown(&self) {
+        self.shutdown_notify.notify_one();
+    }
+
+    /// Attendre qu'un client demande l'arrêt du daemon
+    pub async fn shutdown_requested(&self) {
+        self.shutdown_notify.notified().await;
+    }
+
     /// Ajouter un job à la queue
     ///
     /// # Errors
     ///
-    /// Retourne une erreur si le daemon n'accepte plus de nouveaux jobs.
+    /// Retourne une erreur si le daemon n'accepte plus de nouveaux jobs, si la
+    /// configuration est invalide ou si le répertoire de sortie ou le répertoire temporaire
+    /// n'est pas accessible en écriture, ou si la sortie est déjà prise et que
+    /// `on_collision` vaut `fail`.
     pub async fn add_job(&self, mut job: EncodingJob) -> Result<Uuid> {
         if !*self.accepting_jobs.read().await {
             anyhow::bail!("Le daemon n'accepte plus de nouveaux jobs");
         }
 
+        job.config.validate().map_err(anyhow::Error::msg)?;
+        // Une vérification n'écrit rien
+        if job.kind.encodes() {
+            ensure_dir_writable(
+                &output_dir(&job.output_path),
+                self.create_output_dirs,
+                "répertoire de sortie",
+            )
+            .await?;
+            if let Some(dir) = self.pipeline.temp_dir() {
+                ensure_dir_writable(dir, true, "encoding.temp_dir").await?;
+            }
+        }
+        // Un extrait de test remplace toujours le précédent
+        if job.kind == JobKind::Encode {
+            job.output_path = self.resolve_output_collision(&job.output_path).await?;
+        }
+
         job.status = JobStatus::Queued;
         let job_id = job.id;
+        self.jobs_added.fetch_add(1, Ordering::Relaxed);
 
-        self.queue.write().await.push_back(job);
+        {
+            let mut queue = self.queue.write().await;
+            if job.kind == JobKind::Preview {
+                // Extrait de test : passe devant les encodages, après les extraits déjà en attente
+                let pos = queue
+                    .iter()
+                    .position(|queued| queued.kind != JobKind::Preview)
+                    .unwrap_or(queue.len());
+                queue.insert(pos, job);
+            } else {
+                queue.push_back(job);
+            }
+        }
+        self.persistence.mark_dirty();
 
         info!("Job {} ajouté à la queue", job_id);
         let _ = self.event_tx.send(QueueEvent::JobAdded(job_id));
@@ -154,6 +398,96 @@ impl QueueManager {
         Ok(job_id)
     }
 
+    /// Appliquer `on_collision` à la sortie d'un nouveau job : fichier existant ou sortie
+    /// d'un job en attente ou en cours
+    async fn resolve_output_collision(&self, output: &Path) -> Result<PathBuf> {
+        if self.output_namer.collision() == CollisionPolicy::Overwrite {
+            return Ok(output.to_path_buf());
+        }
+        let mut taken: HashSet<PathBuf> = self
+            .active
+            .read()
+            .await
+            .values()
+            .map(|job| job.output_path.clone())
+            .collect();
+        taken.extend(
+            self.queue
+                .read()
+                .await
+                .iter()
+                .map(|job| job.output_path.clone()),
+        );
+        let resolved = self
+            .output_namer
+            .resolve_collision(output, |path| taken.contains(path), Path::exists)
+            .map_err(anyhow::Error::msg)?;
+        if resolved != output {
+            info!(
+                "Sortie {} déjà prise, job écrit vers {}",
+                output.display(),
+                resolved.display()
+            );
+        }
+        Ok(resolved)
+    }
+
+    /// Ajouter des jobs sous un même identifiant de lot
+    ///
+    /// Retourne l'identifiant du lot et le résultat de l'ajout de chaque job, dans l'ordre.
+    pub async fn add_batch(&self, jobs: Vec<EncodingJob>) -> (Uuid, Vec<Result<Uuid>>) {
+        let batch_id = Uuid::new_v4();
+        let mut results = Vec::with_capacity(jobs.len());
+        for mut job in jobs {
+            job.batch_id = Some(batch_id);
+            results.push(self.add_job(job).await);
+        }
+        info!("Lot {} : {} jobs soumis", batch_id, results.len());
+        (batch_id, results)
+    }
+
+    /// Annuler les jobs d'un lot en attente ou en cours
+    ///
+    /// Les jobs en attente sont retirés avant d'arrêter les jobs actifs, pour qu'aucun
+    /// ne démarre à la place d'un job annulé. Retourne le nombre de jobs annulés.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si aucun job du lot n'est en attente ni en cours.
+    pub async fn cancel_batch(&self, batch_id: Uuid) -> Result<usize> {
+        let in_batch = |job: &EncodingJob| job.batch_id == Some(batch_id);
+        let queued: Vec<Uuid> = self
+            .queue
+            .read()
+            .await
+            .iter()
+            .filter(|job| in_batch(job))
+            .map(|job| job.id)
+            .collect();
+        let active: Vec<Uuid> = self
+            .active
+            .read()
+            .await
+            .values()
+            .filter(|job| in_batch(job))
+            .map(|job| job.id)
+            .collect();
+
+        let mut cancelled = 0;
+        for job_id in queued.into_iter().chain(active) {
+            match self.cancel_job(job_id).await {
+                Ok(()) => cancelled += 1,
+                // Job terminé entre-temps
+                Err(e) => warn!("Lot {batch_id} : {e}"),
+            }
+        }
+        if cancelled == 0 {
+            anyhow::bail!("Aucun job en attente ou en cours pour le lot {batch_id}");
+        }
+        info!("Lot {} : {} jobs annulés", batch_id, cancelled);
+        Ok(cancelled)
+    }
+
     /// Annuler un job
     ///
     /// # Errors
@@ -182,6 +516,7 @@ impl QueueManager {
             job.mark_cancelled();
 
             self.history.write().await.push(job);
+            self.persistence.mark_dirty();
 
             info!("Job {} retiré de la queue", job_id);
             let _ = self.event_tx.send(QueueEvent::JobCancelled(job_id));
@@ -206,11 +541,15 @@ impl QueueManager {
             let mut job = history.remove(pos);
             job.status = JobStatus::Queued;
             job.error_message = None;
+            job.failure_category = None;
+            job.fallback_level = None;
+            job.retries = 0;
             job.stats = None;
             job.started_at = None;
             job.finished_at = None;
 
             self.queue.write().await.push_back(job);
+            self.persistence.mark_dirty();
 
             info!("Job {} remis en queue", job_id);
             let _ = self.event_tx.send(QueueEvent::JobAdded(job_id));
@@ -222,6 +561,31 @@ impl QueueManager {
         anyhow::bail!("Job {job_id} non trouvé ou non failed");
     }
 
+    /// Déplacer un job en attente à `position` dans la queue (bornée à la fin)
+    ///
+    /// Retourne la position effective du job.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le job n'est pas en attente.
+    pub async fn move_job(&self, job_id: Uuid, position: usize) -> Result<usize> {
+        let mut queue = self.queue.write().await;
+        let Some(from) = queue.iter().position(|j| j.id == job_id) else {
+            anyhow::bail!("Job {job_id} non trouvé dans la queue");
+        };
+        let job = queue.remove(from).unwrap();
+        let position = position.min(queue.len());
+        queue.insert(position, job);
+        drop(queue);
+
+        if position != from {
+            self.persistence.mark_dirty();
+            info!("Job {} déplacé en position {}", job_id, position + 1);
+            let _ = self.event_tx.send(QueueEvent::JobMoved(job_id, position));
+        }
+        Ok(position)
+    }
+
     /// Obtenir la queue
     pub async fn get_queue(&self) -> Vec<EncodingJob> {
         self.queue.read().await.iter().cloned().collect()
@@ -237,33 +601,93 @@ impl QueueManager {
         self.history.read().await.clone()
     }
 
+    /// Nombre de jobs reçus depuis le démarrage du daemon
+    #[must_use]
+    pub fn jobs_added(&self) -> u64 {
+        self.jobs_added.load(Ordering::Relaxed)
+    }
+
+    /// Le daemon a-t-il du travail : job en attente ou actif, ou benchmark en cours ?
+    pub async fn has_work(&self) -> bool {
+        !self.queue.read().await.is_empty() || self.busy_reason().await.is_some()
+    }
+
+    /// Raison d'attendre avant de nettoyer les fichiers temporaires (None = aucun en cours)
+    pub async fn busy_reason(&self) -> Option<String> {
+        let active = self.active.read().await.len();
+        if active > 0 {
+            Some(format!("{active} encodage(s) en cours"))
+        } else if self.benchmark_cancel.lock().await.is_some() {
+            Some("benchmark en cours".to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Répertoire temporaire configuré (`encoding.temp_dir`), s'il y en a un
+    #[must_use]
+    pub fn temp_dir(&self) -> Option<PathBuf> {
+        self.pipeline.temp_dir().map(Path::to_path_buf)
+    }
+
+    /// Fichiers de l'audio pré-encodé des jobs en attente
+    pub async fn prefetched_audio_paths(&self) -> HashSet<PathBuf> {
+        self.pipeline.prefetched_audio_paths().await
+    }
+
+    /// Obtenir l'état complet de la file
+    pub async fn snapshot(&self) -> QueueSnapshot {
+        QueueSnapshot {
+            queue: self.get_queue().await,
+            active: self.get_active().await,
+            history: self.get_history().await,
+        }
+    }
+
     /// Supprimer un job spécifique de l'historique
     ///
+    /// Si `delete_outputs` est vrai, les fichiers produits par le job (sortie, segments,
+    /// scores VMAF) sont aussi supprimés du disque.
+    ///
     /// # Errors
     ///
     /// Retourne une erreur si le job n'est pas trouvé dans l'historique.
-    pub async fn remove_from_history(&self, job_id: Uuid) -> Result<()> {
-        let mut history = self.history.write().await;
-        let initial_len = history.len();
-        history.retain(|job| job.id != job_id);
-        let removed = initial_len - history.len();
+    pub async fn remove_from_history(&self, job_id: Uuid, delete_outputs: bool) -> Result<()> {
+        let removed = {
+            let mut history = self.history.write().await;
+            let Some(pos) = history.iter().position(|job| job.id == job_id) else {
+                anyhow::bail!("Job {job_id} non trouvé dans l'historique")
+            };
+            history.remove(pos)
+        };
+        self.persistence.mark_dirty();
+        let _ = self.event_tx.send(QueueEvent::HistoryRemoved(job_id));
+        info!("Job {} supprimé de l'historique", job_id);
 
-        if removed > 0 {
-            info!("Job {} supprimé de l'historique", job_id);
-            Ok(())
-        } else {
-            anyhow::bail!("Job {job_id} non trouvé dans l'historique")
+        if delete_outputs {
+            delete_job_outputs(&removed).await;
         }
+        Ok(())
     }
 
     /// Clear l'historique
     ///
+    /// Si `delete_outputs` est vrai, les fichiers produits par chaque job sont supprimés.
+    ///
     /// # Errors
     ///
     /// Ne retourne jamais d'erreur actuellement, mais utilise `Result` pour cohérence avec l'API.
-    pub async fn clear_history(&self) -> Result<()> {
-        self.history.write().await.clear();
+    pub async fn clear_history(&self, delete_outputs: bool) -> Result<()> {
+        let removed = std::mem::take(&mut *self.history.write().await);
+        self.persistence.mark_dirty();
+        let _ = self.event_tx.send(QueueEvent::HistoryCleared);
         info!("Historique nettoyé");
+
+        if delete_outputs {
+            for job in &removed {
+                delete_job_outputs(job).await;
+            }
+        }
         Ok(())
     }
 
@@ -293,8 +717,12 @@ impl QueueManager {
             // Attendre une notification
             self.start_notify.notified().await;
 
-            // Essayer de démarrer des jobs
+            // Essayer de démarrer des jobs (aucun nouveau démarrage pendant l'arrêt ou le drain)
             loop {
+                if !*self.accepting_jobs.read().await {
+                    break;
+                }
+
                 let active_count = self.active.read().await.len();
 
                 if active_count >= self.max_concurrent {
@@ -303,7 +731,16 @@ impl QueueManager {
 
                 let job = {
                     let mut queue = self.queue.write().await;
-                    queue.pop_front()
+                    // Hors des heures de `[schedule]`, seuls les extraits de test démarrent
+                    if self.schedule_allows_start()
+                        || queue
+                            .front()
+                            .is_some_and(|job| job.kind == JobKind::Preview)
+                    {
+                        queue.pop_front()
+                    } else {
+                        None
+                    }
                 };
 
                 if let Some(job) = job {
@@ -312,6 +749,71 @@ impl QueueManager {
                     break;
                 }
             }
+
+            self.prefetch_queued_audio().await;
+        }
+    }
+
+    /// Un job peut-il démarrer maintenant selon `[schedule]` ?
+    fn schedule_allows_start(&self) -> bool {
+        self.schedule
+            .as_ref()
+            .is_none_or(|schedule| schedule.allows(chrono::Local::now().naive_local()))
+    }
+
+    /// Relancer le démarrage des jobs à chaque minute permise par `[schedule]` (à appeler
+    /// dans une tâche séparée ; ne fait rien sans `[schedule]`)
+    pub async fn run_schedule(self: Arc<Self>) {
+        let Some(schedule) = self.schedule.clone() else {
+            return;
+        };
+        let mut was_open = None;
+        loop {
+            let now = chrono::Local::now().naive_local();
+            let open = schedule.allows(now);
+            if was_open != Some(open) {
+                if open {
+                    info!("Plage de [schedule] ouverte : démarrage des jobs en attente");
+                } else {
+                    info!("Hors des plages de [schedule] : les jobs en attente patientent");
+                }
+                was_open = Some(open);
+            }
+            if open {
+                self.start_notify.notify_one();
+            }
+            // Réveil au début de la minute suivante
+            let wait = 60 - u64::from(now.second());
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+        }
+    }
+
+    /// Pré-encoder l'audio des prochains jobs en attente tant qu'un job est actif
+    ///
+    /// Les pré-encodages des jobs sortis de cette fenêtre (annulés, déplacés plus loin
+    /// dans la queue) sont abandonnés et leurs fichiers supprimés.
+    async fn prefetch_queued_audio(&self) {
+        let active: Vec<Uuid> = self.active.read().await.keys().copied().collect();
+        let upcoming: Vec<EncodingJob> = if active.is_empty() || !*self.accepting_jobs.read().await
+        {
+            Vec::new()
+        } else {
+            self.queue
+                .read()
+                .await
+                .iter()
+                .take(self.audio_prefetch_jobs)
+                .cloned()
+                .collect()
+        };
+
+        let keep: HashSet<Uuid> = active
+            .into_iter()
+            .chain(upcoming.iter().map(|job| job.id))
+            .collect();
+        self.pipeline.retain_audio_prefetch(&keep).await;
+        for job in &upcoming {
+            self.pipeline.prefetch_audio(job).await;
         }
     }
 
@@ -320,13 +822,26 @@ impl QueueManager {
         job.mark_started();
         let job_id = job.id;
 
+        // Part du budget de threads réservée à l'emplacement du job
+        job.allocated_threads = None;
+        if let Some(budget) = &self.thread_budget {
+            let job_threads = job.config.encoder_params.threads;
+            let threads = budget.lock().await.allocate(job_id, job_threads);
+            if job_threads.is_none() {
+                job.allocated_threads = Some(threads);
+            }
+            info!("Job {} : {} threads du budget global", job_id, threads);
+        }
+
         self.active.write().await.insert(job_id, job.clone());
+        self.persistence.mark_dirty();
 
         info!("Démarrage du job {}", job_id);
         let _ = self.event_tx.send(QueueEvent::JobStarted(job_id));
 
         // Créer les channels de contrôle
-        let (cancel_tx, cancel_rx) = mpsc::unbounded_channel::<()>();
+        let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel::<()>();
+        let watchdog_cancel_tx = cancel_tx.clone();
         let (stats_tx, mut stats_rx) = mpsc::unbounded_channel::<EncodingStats>();
 
         // Stocker le contrôle
@@ -342,65 +857,399 @@ impl QueueManager {
         let active_controls = self.active_controls.clone();
         let event_tx = self.event_tx.clone();
         let start_notify = self.start_notify.clone();
+        let fallback_steps = self.fallback_steps.clone();
+        let retry = self.retry.clone();
+        let analytics = self.analytics.clone();
+        let speed_history = self.speed_history.clone();
+        let persistence = self.persistence.clone();
+        let queue = self.queue.clone();
+        let shutdown_mode = self.shutdown_mode.clone();
+        let thread_budget = self.thread_budget.clone();
+        let source_cleanup = self.source_cleanup.clone();
+        let output_permissions = self.output_permissions;
+        let sidecar_report = self.sidecar_report.clone();
+        let progress_log = self.progress_log.clone();
+        let stall_timeout = self.stall_timeout;
+        let stalled = Arc::new(AtomicBool::new(false));
+        let watchdog_stalled = stalled.clone();
 
         // Lancer l'encodage dans une tâche
         tokio::spawn(async move {
-            // Task pour propager les stats
+            // Task pour propager les stats (et surveiller la progression si le watchdog est actif)
             let stats_job_id = job_id;
             let stats_event_tx = event_tx.clone();
             let stats_active = active.clone();
+            let expected_fps = match job.kind {
+                JobKind::Encode | JobKind::Preview => speed_history
+                    .read()
+                    .await
+                    .expected_fps(job.config.encoder, job.config.encoder_params.preset),
+                JobKind::Verify => None,
+            };
             let stats_handle = tokio::spawn(async move {
-                while let Some(stats) = stats_rx.recv().await {
+                let mut watchdog = stall_timeout.map(|t| StallWatchdog::new(t, Instant::now()));
+                let mut progress_throttle = progress_log.as_deref().map(ProgressLog::throttle);
+                loop {
+                    let deadline = watchdog.as_ref().and_then(StallWatchdog::deadline);
+                    let received = match deadline {
+                        Some(deadline) => {
+                            let deadline = tokio::time::Instant::from_std(deadline);
+                            if let Ok(received) =
+                                tokio::time::timeout_at(deadline, stats_rx.recv()).await
+                            {
+                                received
+                            } else {
+                                warn!(
+                                    "Job {} : aucune progression depuis {:?}, arrêt",
+                                    stats_job_id,
+                                    stall_timeout.unwrap_or_default()
+                                );
+                                watchdog_stalled.store(true, Ordering::SeqCst);
+                                let _ = watchdog_cancel_tx.send(());
+                                if let Some(watchdog) = &mut watchdog {
+                                    watchdog.reset(Instant::now());
+                                }
+                                continue;
+                            }
+                        }
+                        None => stats_rx.recv().await,
+                    };
+                    let Some(mut stats) = received else {
+                        break;
+                    };
+                    if let Some(watchdog) = &mut watchdog {
+                        watchdog.observe(&stats, Instant::now());
+                    }
+                    stats.prime_eta(expected_fps);
                     // Mettre à jour les stats dans le job actif
                     if let Some(job) = stats_active.write().await.get_mut(&stats_job_id) {
                         job.stats = Some(stats.clone());
                     }
-                    let _ = stats_event_tx.send(QueueEvent::JobProgress(stats_job_id, stats));
+                    // Échantillon du journal de progression, au plus un par intervalle
+                    if let (Some(log), Some(throttle)) =
+                        (progress_log.as_deref(), progress_throttle.as_mut())
+                    {
+                        if throttle.should_sample(Instant::now()) {
+                            let sample =
+                                ProgressSample::new(stats_job_id, &stats, chrono::Utc::now());
+                            if let Err(e) = log.append(&sample).await {
+                                warn!("Échantillon de progression non écrit: {e:#}");
+                            }
+                        }
+                    }
+                    let _ =
+                        stats_event_tx.send(QueueEvent::JobProgress(stats_job_id, Box::new(stats)));
                 }
             });
 
-            // Lancer le pipeline
-            let result = pipeline.encode_job(&job, stats_tx, cancel_rx).await;
+            // Lancer le pipeline, puis descendre l'échelle de repli si l'encodeur plante ;
+            // en dernier recours, relancer le job entier selon `[retry]`
+            let mut verify_report = None;
+            let original_config = job.config.clone();
+            let result = loop {
+                let mut result = match job.kind {
+                    JobKind::Encode | JobKind::Preview => {
+                        pipeline
+                            .encode_job(&job, stats_tx.clone(), &mut cancel_rx)
+                            .await
+                    }
+                    JobKind::Verify => verify_outcome(
+                        pipeline
+                            .verify_file(&job, stats_tx.clone(), &mut cancel_rx)
+                            .await,
+                        &mut verify_report,
+                    ),
+                };
+                result = stall_outcome(result, &stalled, stall_timeout);
+                for (level, step) in (1u32..).zip(fallback_steps.iter()) {
+                    if !job.kind.encodes()
+                        || !matches!(
+                            result,
+                            Err(EncodeError::EncoderFailed { .. } | EncodeError::Stalled { .. })
+                        )
+                    {
+                        break;
+                    }
+                    warn!(
+                        "Job {} : {}, nouvel essai avec l'étape de repli {}",
+                        job_id,
+                        result
+                            .as_ref()
+                            .err()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                        level
+                    );
+                    apply_fallback_step(&mut job, step);
+                    job.fallback_level = Some(level);
+                    if let Some(active_job) = active.write().await.get_mut(&job_id) {
+                        active_job.config = job.config.clone();
+                        active_job.fallback_level = job.fallback_level;
+                    }
+                    persistence.mark_dirty();
+                    result = pipeline
+                        .encode_job(&job, stats_tx.clone(), &mut cancel_rx)
+                        .await;
+                    result = stall_outcome(result, &stalled, stall_timeout);
+                }
+
+                let (Some(retry), Err(e)) = (&retry, &result) else {
+                    break result;
+                };
+                if job.retries >= retry.max_retries
+                    || retry.action(e.category(), e.exit_code()) == RetryAction::Fail
+                {
+                    break result;
+                }
+                job.retries += 1;
+                warn!(
+                    "Job {} : {e}, nouvel essai {}/{} dans {:?}",
+                    job_id,
+                    job.retries,
+                    retry.max_retries,
+                    retry.delay()
+                );
+                // Nouvel essai avec la configuration d'origine, échelle de repli comprise
+                job.config = original_config.clone();
+                job.fallback_level = None;
+                if let Some(active_job) = active.write().await.get_mut(&job_id) {
+                    active_job.config = job.config.clone();
+                    active_job.fallback_level = None;
+                    active_job.retries = job.retries;
+                }
+                persistence.mark_dirty();
+                let deadline = tokio::time::Instant::now() + retry.delay();
+                let cancelled = loop {
+                    tokio::select! {
+                        _ = cancel_rx.recv() => {
+                            // Le watchdog n'a rien à surveiller pendant l'attente : seule une
+                            // annulation demandée compte
+                            if !stalled.swap(false, Ordering::SeqCst) {
+                                break true;
+                            }
+                        }
+                        () = tokio::time::sleep_until(deadline) => break false,
+                    }
+                };
+                if cancelled {
+                    break Err(EncodeError::Cancelled);
+                }
+            };
+            drop(stats_tx);
 
             // Attendre que le receiver ait traité tous les messages (dont les stats VMAF)
             let _ = stats_handle.await;
 
             // Nettoyer le contrôle
             active_controls.lock().await.remove(&job_id);
+            pipeline.processes().remove_job(job_id);
 
-            // Retirer des actifs
+            // Retirer des actifs et rendre ses threads au budget
             let mut job = active.write().await.remove(&job_id).unwrap();
+            if let Some(budget) = &thread_budget {
+                budget.lock().await.release(job_id);
+            }
+            job.verify_report = verify_report;
 
             // Traiter le résultat
             match result {
-                Ok(()) => {
+                Ok(outcome) => {
+                    job.outputs = outcome
+                        .outputs
+                        .iter()
+                        .map(|(path, _)| path.clone())
+                        .collect();
                     job.mark_completed();
                     info!("Job {} terminé avec succès", job_id);
+                    if let Some(permissions) =
+                        output_permissions.as_ref().filter(|_| job.kind.encodes())
+                    {
+                        permissions.apply(&outcome).await;
+                    }
+                    if let Some(report) = sidecar_report.as_ref().filter(|_| job.kind.encodes()) {
+                        report.write(&job, &outcome).await;
+                    }
+                    if let Some(cleanup) = source_cleanup
+                        .as_ref()
+                        .filter(|_| job.kind == JobKind::Encode)
+                    {
+                        cleanup.apply(&job, &outcome, &pipeline).await;
+                    }
+                    // Les copies vidéo, les extraits de test et les vérifications ne mesurent pas
+                    // la vitesse d'un encodeur
+                    if job.kind == JobKind::Encode && job.config.video_mode == VideoMode::Encode {
+                        let record = analytics_record(&job, &outcome);
+                        speed_history.write().await.record(
+                            record.encoder,
+                            record.preset,
+                            record.avg_fps,
+                        );
+                        if let Err(e) = analytics.append(&record).await {
+                            warn!("Échec d'écriture de l'analytique du job {}: {}", job_id, e);
+                        }
+                    }
                     let _ = event_tx.send(QueueEvent::JobCompleted(job_id));
                 }
+                Err(EncodeError::Cancelled) => {
+                    let shutdown = *shutdown_mode.read().await;
+                    if shutdown.is_some_and(|mode| mode != ShutdownMode::CancelAndDiscard) {
+                        job.status = JobStatus::Queued;
+                        job.stats = None;
+                        job.started_at = None;
+                        job.allocated_threads = None;
+                        queue.write().await.push_front(job);
+                        persistence.mark_dirty();
+                        info!("Job {} interrompu par l'arrêt, remis en queue", job_id);
+                        return;
+                    }
+                    job.mark_cancelled();
+                    info!("Job {} annulé", job_id);
+                    let _ = event_tx.send(QueueEvent::JobCancelled(job_id));
+                }
                 Err(e) => {
                     let error_msg = e.to_string();
-                    job.mark_failed(error_msg.clone());
-                    error!("Job {} échoué: {}", job_id, error_msg);
-                    let _ = event_tx.send(QueueEvent::JobFailed(job_id, error_msg));
+                    let category = e.category();
+                    job.mark_failed_with(error_msg.clone(), category);
+                    error!("Job {} échoué ({}): {}", job_id, category, error_msg);
+                    let _ = event_tx.send(QueueEvent::JobFailed(job_id, error_msg, category));
                 }
             }
 
             // Ajouter à l'historique
             history.write().await.push(job);
+            persistence.mark_dirty();
 
             // Notifier pour démarrer le prochain job
             start_notify.notify_one();
         });
     }
 
+    /// Changer la niceness d'un job en cours (appliquée à tous ses processus enfants)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le job n'est pas actif, si la valeur est hors plage ou
+    /// si `setpriority` échoue.
+    pub async fn set_job_priority(&self, job_id: Uuid, nice: i32) -> Result<()> {
+        let mut active = self.active.write().await;
+        let Some(job) = active.get_mut(&job_id) else {
+            anyhow::bail!("Job {job_id} non actif");
+        };
+
+        self.pipeline.processes().set_nice(job_id, nice)?;
+        job.nice = Some(nice);
+        self.persistence.mark_dirty();
+        info!("Job {} : niceness fixée à {}", job_id, nice);
+        Ok(())
+    }
+
+    /// Lancer un benchmark des presets en arrière-plan
+    ///
+    /// Refusé pendant un encodage (les mesures seraient faussées par la charge) ou si un
+    /// benchmark tourne déjà. Les résultats sont publiés via les événements `Benchmark*`.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si des jobs sont en cours ou si un benchmark est déjà lancé.
+    /// Une source introuvable est signalée par `BenchmarkFailed`.
+    pub async fn start_benchmark(
+        &self,
+        input: PathBuf,
+        encoder: EncoderType,
+        target_speed: f64,
+    ) -> Result<()> {
+        if !self.active.read().await.is_empty() {
+            anyhow::bail!("Benchmark impossible pendant un encodage");
+        }
+        let mut running = self.benchmark_cancel.lock().await;
+        if running.is_some() {
+            anyhow::bail!("Un benchmark est déjà en cours");
+        }
+        let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+        *running = Some(cancel_tx);
+        drop(running);
+
+        info!("Benchmark {} sur {}", encoder, input.display());
+        let pipeline = self.pipeline.clone();
+        let event_tx = self.event_tx.clone();
+        let benchmark_cancel = self.benchmark_cancel.clone();
+        tokio::spawn(async move {
+            let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+            let progress_event_tx = event_tx.clone();
+            let forwarder = tokio::spawn(async move {
+                while let Some(result) = progress_rx.recv().await {
+                    let _ = progress_event_tx.send(QueueEvent::BenchmarkProgress(result));
+                }
+            });
+
+            let result = pipeline
+                .benchmark(&input, encoder, target_speed, progress_tx, &mut cancel_rx)
+                .await;
+            let _ = forwarder.await;
+            *benchmark_cancel.lock().await = None;
+
+            match result {
+                Ok(report) => {
+                    info!(
+                        "Benchmark terminé, preset recommandé: {:?}",
+                        report.recommended()
+                    );
+                    let _ = event_tx.send(QueueEvent::BenchmarkCompleted(report));
+                }
+                Err(e) => {
+                    warn!("Benchmark échoué: {e:#}");
+                    let _ = event_tx.send(QueueEvent::BenchmarkFailed(format!("{e:#}")));
+                }
+            }
+        });
+
+        Ok(())
+    }
+
     /// Arrêter d'accepter les nouveaux jobs
     pub async fn stop_accepting_jobs(&self) {
         *self.accepting_jobs.write().await = false;
         info!("Le daemon n'accepte plus de nouveaux jobs");
+        let _ = self.event_tx.send(QueueEvent::StoppedAcceptingJobs);
+    }
+
+    /// Nouveaux jobs encore acceptés ?
+    pub async fn is_accepting_jobs(&self) -> bool {
+        *self.accepting_jobs.read().await
+    }
+
+    /// Annuler les jobs actifs et le benchmark en cours pour l'arrêt du daemon
+    ///
+    /// Les processus enfants sont tués par le signal d'annulation. Avec
+    /// [`ShutdownMode::CancelAndDiscard`] les jobs passent dans l'historique, sinon ils
+    /// retournent en tête de queue. Les sorties partielles restent dans l'espace
+    /// temporaire du job, nettoyé à son abandon : la sortie finale n'est pas touchée.
+    pub async fn cancel_active_jobs(&self, mode: ShutdownMode) {
+        *self.shutdown_mode.write().await = Some(mode);
+
+        for (job_id, control) in self.active_controls.lock().await.iter() {
+            let _ = control.cancel_tx.send(());
+            info!("Arrêt : annulation du job {}", job_id);
+        }
+        if let Some(cancel_tx) = self.benchmark_cancel.lock().await.as_ref() {
+            let _ = cancel_tx.send(());
+        }
+
+        self.wait_active_jobs(std::time::Duration::from_secs(10))
+            .await;
+    }
+
+    /// Tuer les processus enfants encore en vie (dernier recours avant l'arrêt des tâches)
+    ///
+    /// Retourne le nombre de processus tués.
+    #[must_use]
+    pub fn kill_child_processes(&self) -> usize {
+        self.pipeline.processes().kill_all()
     }
 
     /// Attendre que tous les jobs actifs se terminent (avec timeout)
+    ///
+    /// `Duration::MAX` attend sans limite (drain).
     pub async fn wait_active_jobs(&self, timeout: std::time::Duration) {
         let start = std::time::Instant::now();
 
@@ -420,3 +1269,426 @@ impl QueueManager {
         }
     }
 }
+
+/// Fichiers produits par un job présents sur le disque (sortie ou segments, log VMAF)
+///
+/// Seuls les fichiers enregistrés sur le job sont retenus, jamais le contenu du répertoire
+/// de sortie. Le fichier source n'est jamais inclus, même si la sortie pointe dessus.
+async fn job_output_files(job: &EncodingJob) -> Vec<PathBuf> {
+    // Une vérification ne produit rien : son fichier n'est pas une sortie
+    if job.kind == JobKind::Verify {
+        return Vec::new();
+    }
+    let mut candidates = if job.outputs.is_empty() {
+        vec![job.output_path.clone()]
+    } else {
+        job.outputs.clone()
+    };
+
+    // Log VMAF par frame associé à chaque sortie
+    let vmaf_logs: Vec<PathBuf> = candidates
+        .iter()
+        .map(|output| vmaf_log_path(output))
+        .collect();
+    candidates.extend(vmaf_logs);
+
+    let mut outputs = Vec::new();
+    for path in candidates {
+        if path != job.input_path
+            && tokio::fs::metadata(&path)
+                .await
+                .is_ok_and(|metadata| metadata.is_file())
+        {
+            outputs.push(path);
+        }
+    }
+    outputs
+}
+
+/// Résultat d'un job de vérification : rapport conservé, fichier invalide = échec
+fn verify_outcome(
+    result: Result<VerifyReport, EncodeError>,
+    report: &mut Option<VerifyReport>,
+) -> Result<EncodeOutcome, EncodeError> {
+    let problems = report.insert(result?).problems();
+    if problems.is_empty() {
+        Ok(EncodeOutcome::default())
+    } else {
+        Err(EncodeError::VerifyFailed(problems.join(" ; ")))
+    }
+}
+
+/// Requalifier en blocage l'annulation déclenchée par le watchdog
+fn stall_outcome(
+    result: Result<EncodeOutcome, EncodeError>,
+    stalled: &AtomicBool,
+    stall_timeout: Option<Duration>,
+) -> Result<EncodeOutcome, EncodeError> {
+    let was_stalled = stalled.swap(false, Ordering::SeqCst);
+    match result {
+        Err(EncodeError::Cancelled) if was_stalled => Err(EncodeError::Stalled {
+            secs: stall_timeout.unwrap_or_default().as_secs(),
+        }),
+        other => other,
+    }
+}
+
+/// Supprimer les fichiers produits par un job (les échecs sont journalisés)
+async fn delete_job_outputs(job: &EncodingJob) {
+    for path in job_output_files(job).await {
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => info!("Fichier de sortie supprimé: {}", path.display()),
+            Err(e) => warn!("Impossible de supprimer {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Construire l'enregistrement analytique d'un job terminé
+#[allow(clippy::cast_precision_loss)] // Safe: nombre de frames bien inférieur à 2^52
+fn analytics_record(job: &EncodingJob, outcome: &EncodeOutcome) -> AnalyticsRecord {
+    let encode_secs = outcome.video_encode_time.as_secs_f64();
+    let avg_fps = match outcome.total_frames {
+        Some(frames) if encode_secs > 0.0 => frames as f64 / encode_secs,
+        _ => 0.0,
+    };
+
+    AnalyticsRecord {
+        job_id: job.id,
+        finished_at: job.finished_at.unwrap_or_else(chrono::Utc::now),
+        encoder: job.config.encoder,
+        preset: job.config.encoder_params.preset,
+        crf: job.config.encoder_params.crf,
+        encode_secs,
+        avg_fps,
+        total_frames: outcome.total_frames,
+        width: outcome.width,
+        height: outcome.height,
+    }
+}
+
+/// Appliquer une étape de repli à la configuration du job
+///
+/// Le preset est borné à celui de l'encodeur du job : l'échelle est commune à tous les
+/// encodeurs (8 pour aomenc est déjà le plus rapide, 6 pour les encodeurs matériels).
+fn apply_fallback_step(job: &mut EncodingJob, step: &FallbackStep) {
+    if let Some(preset) = step.preset {
+        job.config.encoder_params.preset = preset.min(job.config.encoder.max_preset());
+    }
+    if let Some(params) = &step.extra_params {
+        job.config.encoder_params.extra_params.clone_from(params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encodetalker_common::{EncodingConfig, SplitMode};
+
+    /// Gestionnaire sans boucle de démarrage : les jobs ajoutés restent en attente
+    fn test_manager(dir: &Path) -> (QueueManager, mpsc::UnboundedReceiver<QueueEvent>) {
+        test_manager_with(dir, PathBuf::from("ffmpeg"), PathBuf::from("ffprobe"))
+    }
+
+    fn test_manager_with(
+        dir: &Path,
+        ffmpeg: PathBuf,
+        ffprobe: PathBuf,
+    ) -> (QueueManager, mpsc::UnboundedReceiver<QueueEvent>) {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let pipeline = EncodingPipeline::new(
+            ffmpeg,
+            ffprobe,
+            PathBuf::from("SvtAv1EncApp"),
+            PathBuf::from("aomenc"),
+            false,
+            Duration::from_secs(1),
+        );
+        let manager = QueueManager::new(
+            1,
+            pipeline,
+            Persistence::new(dir.join("state.json")),
+            AnalyticsLog::new(dir.join("analytics.jsonl")),
+            event_tx,
+            Vec::new(),
+        );
+        (manager, event_rx)
+    }
+
+    fn job_in(dir: &Path, name: &str) -> EncodingJob {
+        EncodingJob::new(
+            dir.join(format!("{name}.mkv")),
+            dir.join(format!("{name}.av1.mkv")),
+            EncodingConfig::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_add_batch_tags_jobs_and_keeps_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (manager, _events) = test_manager(tmp.path());
+
+        let mut invalid = job_in(tmp.path(), "b");
+        invalid.config.split = SplitMode::Interval { secs: 0 };
+        let jobs = vec![job_in(tmp.path(), "a"), invalid, job_in(tmp.path(), "c")];
+        let ids: Vec<Uuid> = jobs.iter().map(|job| job.id).collect();
+
+        // Un job refusé n'empêche pas l'ajout des autres
+        let (batch_id, results) = manager.add_batch(jobs).await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &ids[0]);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &ids[2]);
+
+        let queue = manager.get_queue().await;
+        assert_eq!(
+            queue.iter().map(|job| job.id).collect::<Vec<_>>(),
+            [ids[0], ids[2]]
+        );
+        assert!(queue.iter().all(|job| job.batch_id == Some(batch_id)));
+
+        // Chaque lot a son identifiant
+        let (other_batch, _) = manager.add_batch(vec![job_in(tmp.path(), "d")]).await;
+        assert_ne!(other_batch, batch_id);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_batch_queued_and_active() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (manager, _events) = test_manager(tmp.path());
+
+        let (batch_id, _) = manager
+            .add_batch(vec![job_in(tmp.path(), "a"), job_in(tmp.path(), "b")])
+            .await;
+        let (other_batch, other) = manager.add_batch(vec![job_in(tmp.path(), "c")]).await;
+        let other_id = *other[0].as_ref().unwrap();
+
+        // Job du lot en cours d'encodage
+        let mut running = job_in(tmp.path(), "d");
+        running.batch_id = Some(batch_id);
+        running.status = JobStatus::Running;
+        let running_id = running.id;
+        let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+        manager.active.write().await.insert(running_id, running);
+        manager
+            .active_controls
+            .lock()
+            .await
+            .insert(running_id, ActiveJobControl { cancel_tx });
+
+        assert_eq!(manager.cancel_batch(batch_id).await.unwrap(), 3);
+
+        // Les jobs en attente passent dans l'historique, le job actif reçoit l'annulation
+        assert_eq!(
+            manager
+                .get_queue()
+                .await
+                .iter()
+                .map(|job| job.id)
+                .collect::<Vec<_>>(),
+            [other_id]
+        );
+        let history = manager.get_history().await;
+        assert_eq!(history.len(), 2);
+        assert!(history
+            .iter()
+            .all(|job| job.batch_id == Some(batch_id) && job.status == JobStatus::Cancelled));
+        assert!(cancel_rx.try_recv().is_ok());
+
+        // Job actif arrêté : plus rien à annuler dans le lot, l'autre lot est intact
+        manager.active.write().await.remove(&running_id);
+        assert!(manager.cancel_batch(batch_id).await.is_err());
+        assert!(manager.cancel_batch(Uuid::new_v4()).await.is_err());
+        assert_eq!(manager.cancel_batch(other_batch).await.unwrap(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shutdown_cancel_keeps_existing_output() {
+        use crate::encoder::write_fake_bin;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        // Analyse bloquée : le job est encore actif quand l'arrêt l'annule
+        let ffprobe = write_fake_bin(dir, "ffprobe", "exec sleep 30");
+        let ffmpeg = write_fake_bin(dir, "ffmpeg", "exit 1");
+        let (manager, _events) = test_manager_with(dir, ffmpeg, ffprobe);
+
+        let job = job_in(dir, "film");
+        std::fs::write(&job.input_path, b"source").unwrap();
+        std::fs::write(&job.output_path, b"encodage precedent").unwrap();
+        let job_id = job.id;
+
+        manager.start_job(job).await;
+        manager
+            .cancel_active_jobs(ShutdownMode::CancelAndRequeue)
+            .await;
+
+        // Le fichier déjà présent à l'emplacement de sortie n'appartient pas au job
+        assert_eq!(
+            std::fs::read(dir.join("film.av1.mkv")).unwrap(),
+            b"encodage precedent"
+        );
+        let queue = manager.get_queue().await;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].id, job_id);
+        assert_eq!(queue[0].status, JobStatus::Queued);
+    }
+
+    #[tokio::test]
+    async fn test_move_job_reorders_and_clamps() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (manager, mut events) = test_manager(tmp.path());
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c"] {
+            ids.push(manager.add_job(job_in(tmp.path(), name)).await.unwrap());
+        }
+        while events.try_recv().is_ok() {}
+        let order = || async {
+            manager
+                .get_queue()
+                .await
+                .iter()
+                .map(|job| job.id)
+                .collect::<Vec<_>>()
+        };
+
+        // Dernier job en tête
+        assert_eq!(manager.move_job(ids[2], 0).await.unwrap(), 0);
+        assert_eq!(order().await, [ids[2], ids[0], ids[1]]);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(QueueEvent::JobMoved(id, 0)) if id == ids[2]
+        ));
+
+        // Position hors de la queue : bornée à la fin
+        assert_eq!(manager.move_job(ids[2], 99).await.unwrap(), 2);
+        assert_eq!(order().await, [ids[0], ids[1], ids[2]]);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(QueueEvent::JobMoved(id, 2)) if id == ids[2]
+        ));
+
+        // Déjà en place : aucun événement
+        assert_eq!(manager.move_job(ids[2], 5).await.unwrap(), 2);
+        assert!(events.try_recv().is_err());
+
+        // Job absent de la queue
+        assert!(manager.move_job(Uuid::new_v4(), 0).await.is_err());
+        assert_eq!(order().await, [ids[0], ids[1], ids[2]]);
+    }
+
+    #[tokio::test]
+    async fn test_job_output_files_only_lists_recorded_outputs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let config = EncodingConfig {
+            split: SplitMode::Chapters,
+            ..EncodingConfig::default()
+        };
+        let mut job = EncodingJob::new(
+            dir.join("film.mkv"),
+            dir.join("{index} - {chapter}.mkv"),
+            config,
+        );
+        job.outputs = vec![dir.join("01 - Début.mkv"), dir.join("02 - Fin.mkv")];
+        for name in [
+            "film.mkv",
+            "01 - Début.mkv",
+            "02 - Fin.mkv",
+            "02 - Fin_vmaf.json",
+            // Fichiers de l'utilisateur au nom proche : jamais supprimés
+            "03 - Autre.mkv",
+            "vacances.mkv",
+        ] {
+            tokio::fs::write(dir.join(name), b"").await.unwrap();
+        }
+
+        let mut files = job_output_files(&job).await;
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                dir.join("01 - Début.mkv"),
+                dir.join("02 - Fin.mkv"),
+                dir.join("02 - Fin_vmaf.json"),
+            ]
+        );
+
+        // Sans sortie enregistrée (job interrompu) : la sortie nominale seule
+        job.outputs.clear();
+        job.output_path = dir.join("vacances.mkv");
+        assert_eq!(job_output_files(&job).await, [dir.join("vacances.mkv")]);
+        job.output_path = job.input_path.clone();
+        assert!(job_output_files(&job).await.is_empty());
+    }
+
+    #[test]
+    fn test_verify_outcome_keeps_report() {
+        let valid = VerifyReport {
+            video_codec: "av1".to_string(),
+            width: 1920,
+            height: 1080,
+            duration_secs: Some(60.0),
+            fps: 25.0,
+            decoded_frames: Some(1500),
+            streams: vec![encodetalker_common::StreamSummary {
+                kind: "video".to_string(),
+                codec: "av1".to_string(),
+                language: None,
+            }],
+            decode_errors: Vec::new(),
+        };
+
+        // Fichier valide : succès, rapport conservé
+        let mut report = None;
+        assert!(verify_outcome(Ok(valid.clone()), &mut report).is_ok());
+        assert_eq!(report.as_ref(), Some(&valid));
+
+        // Fichier tronqué : échec « Fichier invalide », rapport conservé quand même
+        let truncated = VerifyReport {
+            decoded_frames: Some(750),
+            ..valid
+        };
+        let mut report = None;
+        match verify_outcome(Ok(truncated.clone()), &mut report) {
+            Err(EncodeError::VerifyFailed(problems)) => {
+                assert!(
+                    problems.contains("30.0s décodées au lieu de 60.0s"),
+                    "{problems}"
+                );
+            }
+            other => panic!("VerifyFailed attendu, obtenu {other:?}"),
+        }
+        assert_eq!(report, Some(truncated));
+
+        // Pas de stream vidéo : le probe échoue, sans rapport
+        let mut report = None;
+        let result = verify_outcome(
+            Err(EncodeError::ProbeFailed(anyhow::anyhow!(
+                "Aucun stream vidéo trouvé"
+            ))),
+            &mut report,
+        );
+        assert!(matches!(result, Err(EncodeError::ProbeFailed(_))));
+        assert!(report.is_none());
+    }
+
+    #[test]
+    fn test_fallback_step_clamps_preset_to_encoder() {
+        let step = FallbackStep {
+            preset: Some(10),
+            extra_params: None,
+        };
+        let mut job = job_in(Path::new("/videos"), "film");
+        job.config.encoder_params.extra_params = vec!["--tile-columns=1".to_string()];
+        apply_fallback_step(&mut job, &step);
+        assert_eq!(job.config.encoder_params.preset, 10);
+        assert_eq!(job.config.encoder_params.extra_params, ["--tile-columns=1"]);
+
+        for encoder in [EncoderType::Aom, EncoderType::Nvenc] {
+            job.config.encoder = encoder;
+            apply_fallback_step(&mut job, &step);
+            assert_eq!(job.config.encoder_params.preset, encoder.max_preset());
+        }
+    }
+}
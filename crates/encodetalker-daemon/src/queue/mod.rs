@@ -1,5 +1,23 @@
+pub mod analytics;
+pub mod idle;
 pub mod manager;
+pub mod output_permissions;
 pub mod persist;
+pub mod progress_log;
+pub mod schedule;
+pub mod sidecar_report;
+pub mod source_cleanup;
+pub mod thread_budget;
+pub mod watchdog;
 
+pub use analytics::*;
+pub use idle::*;
 pub use manager::*;
+pub use output_permissions::*;
 pub use persist::*;
+pub use progress_log::*;
+pub use schedule::*;
+pub use sidecar_report::*;
+pub use source_cleanup::*;
+pub use thread_budget::*;
+pub use watchdog::*;
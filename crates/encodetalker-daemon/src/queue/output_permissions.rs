@@ -0,0 +1,166 @@
+use crate::config::OutputPermissionsSettings;
+use crate::encoder::EncodeOutcome;
+use anyhow::{Context, Result};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Permissions appliquées aux sorties d'un encodage réussi (`[output_permissions]`)
+///
+/// Sans effet hors Unix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputPermissions {
+    /// Mode des fichiers produits (ex. 0o664)
+    mode: Option<u32>,
+    /// Groupe propriétaire des fichiers produits
+    gid: Option<u32>,
+}
+
+impl OutputPermissions {
+    /// Lire la section de configuration (None = permissions laissées à ffmpeg et à l'umask)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le mode n'est pas un nombre octal valide ou si le groupe
+    /// n'existe pas.
+    pub fn from_settings(settings: &OutputPermissionsSettings) -> Result<Option<Self>> {
+        let mode = settings.mode.as_deref().map(parse_mode).transpose()?;
+        let gid = settings.group.as_deref().map(resolve_group).transpose()?;
+        if mode.is_none() && gid.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self { mode, gid }))
+    }
+
+    /// Appliquer mode et groupe aux fichiers produits par un job
+    ///
+    /// Un échec est seulement signalé : le job reste réussi.
+    pub async fn apply(&self, outcome: &EncodeOutcome) {
+        for (output, _) in &outcome.outputs {
+            match self.apply_to(output).await {
+                Ok(()) => info!("Permissions appliquées à {}", output.display()),
+                Err(e) => warn!("Permissions de {} non modifiées: {e:#}", output.display()),
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    async fn apply_to(&self, path: &Path) -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Some(gid) = self.gid {
+            let owned = path.to_path_buf();
+            tokio::task::spawn_blocking(move || std::os::unix::fs::chown(&owned, None, Some(gid)))
+                .await?
+                .with_context(|| format!("Échec du changement de groupe (gid {gid})"))?;
+        }
+        // Après chown, qui peut retirer les bits setuid/setgid
+        if let Some(mode) = self.mode {
+            tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+                .await
+                .with_context(|| format!("Échec du chmod {mode:o}"))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    #[allow(clippy::unused_async)]
+    async fn apply_to(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Mode octal : `664`, `0664` ou `0o664`
+fn parse_mode(text: &str) -> Result<u32> {
+    let digits = text.trim().trim_start_matches("0o");
+    let mode = u32::from_str_radix(digits, 8).with_context(|| {
+        format!("output_permissions: mode \"{text}\" invalide (octal, ex. 664)")
+    })?;
+    if mode > 0o7777 {
+        anyhow::bail!("output_permissions: mode \"{text}\" hors limites (maximum 7777)");
+    }
+    Ok(mode)
+}
+
+/// Groupe désigné par son nom ou son gid numérique
+fn resolve_group(group: &str) -> Result<u32> {
+    let group = group.trim();
+    if let Ok(gid) = group.parse() {
+        return Ok(gid);
+    }
+    lookup_group(group)
+        .with_context(|| format!("output_permissions: groupe \"{group}\" introuvable"))
+}
+
+#[cfg(unix)]
+fn lookup_group(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    // SAFETY: nom terminé par un nul ; l'entrée retournée est lue aussitôt,
+    // avant tout autre appel à getgr* (lecture unique au démarrage)
+    let entry = unsafe { libc::getgrnam(name.as_ptr()) };
+    if entry.is_null() {
+        return None;
+    }
+    // SAFETY: pointeur non nul vers l'entrée statique de getgrnam
+    Some(unsafe { (*entry).gr_gid })
+}
+
+#[cfg(not(unix))]
+fn lookup_group(_name: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_parse_mode_and_group() {
+        let mut settings = OutputPermissionsSettings::default();
+        assert_eq!(OutputPermissions::from_settings(&settings).unwrap(), None);
+
+        settings.mode = Some("0664".to_string());
+        settings.group = Some("1000".to_string());
+        assert_eq!(
+            OutputPermissions::from_settings(&settings).unwrap(),
+            Some(OutputPermissions {
+                mode: Some(0o664),
+                gid: Some(1000)
+            })
+        );
+        assert_eq!(parse_mode("0o2775").unwrap(), 0o2775);
+        assert!(parse_mode("rw-rw-r--").is_err());
+        assert!(parse_mode("17777").is_err());
+
+        settings.group = Some("groupe-qui-n-existe-pas".to_string());
+        assert!(OutputPermissions::from_settings(&settings).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_apply_sets_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+
+        let dir = tmp.path().to_path_buf();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let output = dir.join("film.av1.mkv");
+        tokio::fs::write(&output, b"sortie").await.unwrap();
+
+        let permissions = OutputPermissions {
+            mode: Some(0o640),
+            gid: None,
+        };
+        let outcome = EncodeOutcome {
+            outputs: vec![(output.clone(), None)],
+            ..EncodeOutcome::default()
+        };
+        permissions.apply(&outcome).await;
+        let mode = tokio::fs::metadata(&output)
+            .await
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o640);
+    }
+}
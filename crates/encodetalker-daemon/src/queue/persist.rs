@@ -2,10 +2,12 @@ use anyhow::{Context, Result};
 use encodetalker_common::EncodingJob;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
-use tracing::info;
+use tokio::sync::Notify;
+use tracing::{info, warn};
 
 /// État persisté du daemon
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -16,33 +18,77 @@ pub struct PersistedState {
 }
 
 /// Gestionnaire de persistance
+///
+/// Les modifications de l'état sont signalées via [`Persistence::mark_dirty`] ; la
+/// tâche d'auto-save ne sauvegarde qu'après un changement (avec anti-rebond).
 pub struct Persistence {
     state_file: PathBuf,
+    /// L'état a changé depuis la dernière sauvegarde
+    dirty: AtomicBool,
+    /// Réveil de la tâche d'auto-save
+    changed: Notify,
 }
 
 impl Persistence {
     #[must_use]
     pub fn new(state_file: PathBuf) -> Self {
-        Self { state_file }
+        Self {
+            state_file,
+            dirty: AtomicBool::new(false),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Copie de secours (état de la sauvegarde précédente)
+    fn backup_file(&self) -> PathBuf {
+        self.state_file.with_extension("json.bak")
+    }
+
+    /// Signaler que l'état a changé et doit être sauvegardé
+    pub fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Release);
+        self.changed.notify_one();
+    }
+
+    /// Consommer le flag de modification (true si une sauvegarde est nécessaire)
+    pub fn take_dirty(&self) -> bool {
+        self.dirty.swap(false, Ordering::AcqRel)
+    }
+
+    /// Attendre le prochain changement d'état
+    pub async fn changed(&self) {
+        self.changed.notified().await;
     }
 
     /// Charger l'état depuis le disque
     ///
+    /// Si le fichier principal est absent ou illisible (tronqué par un arrêt brutal),
+    /// la copie de secours `.bak` est utilisée.
+    ///
     /// # Errors
     ///
-    /// Retourne une erreur si le fichier ne peut pas être lu ou si le parsing JSON échoue.
+    /// Retourne une erreur si le fichier ne peut pas être lu ou si le parsing JSON échoue
+    /// et qu'aucune copie de secours valide n'existe.
     pub async fn load(&self) -> Result<PersistedState> {
+        let backup = self.backup_file();
+
         if !self.state_file.exists() {
+            if backup.exists() {
+                warn!("Fichier d'état absent, restauration depuis la copie de secours");
+                return read_state(&backup).await;
+            }
             info!("Fichier d'état non trouvé, démarrage avec état vide");
             return Ok(PersistedState::default());
         }
 
-        let content = tokio::fs::read_to_string(&self.state_file)
-            .await
-            .context("Échec de lecture du fichier d'état")?;
-
-        let state: PersistedState =
-            serde_json::from_str(&content).context("Échec du parsing de l'état")?;
+        let state = match read_state(&self.state_file).await {
+            Ok(state) => state,
+            Err(e) if backup.exists() => {
+                warn!("Fichier d'état corrompu ({e:#}), restauration depuis la copie de secours");
+                read_state(&backup).await?
+            }
+            Err(e) => return Err(e),
+        };
 
         info!(
             "État chargé: {} queued, {} active, {} history",
@@ -56,6 +102,9 @@ impl Persistence {
 
     /// Sauvegarder l'état sur le disque (écriture atomique)
     ///
+    /// L'état est écrit dans un fichier temporaire synchronisé, l'ancien fichier devient
+    /// la copie de secours, puis le temporaire est renommé.
+    ///
     /// # Errors
     ///
     /// Retourne une erreur si le fichier ne peut pas être créé, écrit ou si le rename atomique échoue.
@@ -80,6 +129,13 @@ impl Persistence {
 
         drop(file);
 
+        // Conserver la sauvegarde précédente
+        if self.state_file.exists() {
+            tokio::fs::rename(&self.state_file, self.backup_file())
+                .await
+                .context("Échec de la copie de secours")?;
+        }
+
         // Rename atomique
         tokio::fs::rename(&temp_file, &self.state_file)
             .await
@@ -88,3 +144,61 @@ impl Persistence {
         Ok(())
     }
 }
+
+/// Lire et parser un fichier d'état
+async fn read_state(path: &Path) -> Result<PersistedState> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .context("Échec de lecture du fichier d'état")?;
+
+    serde_json::from_str(&content).context("Échec du parsing de l'état")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encodetalker_common::EncodingConfig;
+
+    fn state_with_queue(len: usize) -> PersistedState {
+        let mut state = PersistedState::default();
+        for i in 0..len {
+            state.queue.push_back(EncodingJob::new(
+                PathBuf::from(format!("/videos/{i}.mkv")),
+                PathBuf::from(format!("/videos/{i}.av1.mkv")),
+                EncodingConfig::default(),
+            ));
+        }
+        state
+    }
+
+    #[tokio::test]
+    async fn test_load_falls_back_to_backup_after_truncated_write() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::create_dir_all(&dir).unwrap();
+        let persistence = Persistence::new(dir.join("state.json"));
+
+        persistence.save(&state_with_queue(1)).await.unwrap();
+        persistence.save(&state_with_queue(2)).await.unwrap();
+        assert_eq!(persistence.load().await.unwrap().queue.len(), 2);
+
+        // Arrêt brutal pendant une écriture non atomique : fichier principal tronqué
+        let content = std::fs::read_to_string(dir.join("state.json")).unwrap();
+        std::fs::write(dir.join("state.json"), &content[..content.len() / 2]).unwrap();
+        assert_eq!(persistence.load().await.unwrap().queue.len(), 1);
+
+        // Arrêt entre les deux renames : seul le .bak (et le .tmp) subsiste
+        std::fs::remove_file(dir.join("state.json")).unwrap();
+        std::fs::write(dir.join("state.tmp"), "{").unwrap();
+        assert_eq!(persistence.load().await.unwrap().queue.len(), 1);
+    }
+
+    #[test]
+    fn test_dirty_flag_is_consumed() {
+        let persistence = Persistence::new(PathBuf::from("/nonexistent/state.json"));
+        assert!(!persistence.take_dirty());
+        persistence.mark_dirty();
+        assert!(persistence.take_dirty());
+        assert!(!persistence.take_dirty());
+    }
+}
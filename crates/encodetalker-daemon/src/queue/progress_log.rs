@@ -0,0 +1,181 @@
+use crate::config::ProgressLogSettings;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use encodetalker_common::EncodingStats;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Échantillon de progression d'un job, une ligne du journal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgressSample {
+    pub timestamp: DateTime<Utc>,
+    pub job_id: Uuid,
+    /// Progression de la passe en cours (0-100)
+    pub percent: f64,
+    pub fps: f64,
+    /// Temps restant estimé en secondes (None si inconnu)
+    pub eta_secs: Option<f64>,
+    pub frame: u64,
+    pub total_frames: Option<u64>,
+    pub pass: u32,
+    pub total_passes: u32,
+}
+
+impl ProgressSample {
+    #[must_use]
+    pub fn new(job_id: Uuid, stats: &EncodingStats, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            job_id,
+            percent: stats.progress_percent,
+            fps: stats.fps,
+            eta_secs: stats.eta.map(|eta| eta.as_secs_f64()),
+            frame: stats.frame,
+            total_frames: stats.total_frames,
+            pass: stats.current_pass,
+            total_passes: stats.total_passes,
+        }
+    }
+}
+
+/// Journal local des échantillons de progression (`[progress_log]`, JSONL, ajout seul)
+///
+/// Le daemon n'expose ni métriques ni webhook : le fichier est seulement écrit dans le
+/// répertoire de données, à charge d'un outil externe de le lire. Chaque job est limité
+/// à un échantillon par intervalle, pour que le fichier ne grossisse pas à la cadence des
+/// stats de l'encodeur.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressLog {
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl ProgressLog {
+    /// Lire la section de configuration (None = journal désactivé)
+    #[must_use]
+    pub fn from_settings(settings: &ProgressLogSettings, path: PathBuf) -> Option<Self> {
+        settings.enabled.then(|| Self {
+            path,
+            interval: Duration::from_secs(settings.interval_secs.max(1)),
+        })
+    }
+
+    /// Limiteur de cadence pour un nouveau job
+    #[must_use]
+    pub fn throttle(&self) -> ProgressThrottle {
+        ProgressThrottle {
+            interval: self.interval,
+            last: None,
+        }
+    }
+
+    /// Ajouter un échantillon en fin de fichier
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la sérialisation échoue ou si le fichier ne peut pas être écrit.
+    pub async fn append(&self, sample: &ProgressSample) -> Result<()> {
+        let mut line =
+            serde_json::to_string(sample).context("Échec de sérialisation de la progression")?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .context("Échec d'ouverture du journal de progression")?;
+
+        file.write_all(line.as_bytes())
+            .await
+            .context("Échec d'écriture du journal de progression")?;
+
+        Ok(())
+    }
+}
+
+/// Cadence des échantillons d'un job : le premier passe, puis un par intervalle
+#[derive(Debug, Clone)]
+pub struct ProgressThrottle {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl ProgressThrottle {
+    /// Faut-il écrire l'échantillon reçu à `now` ?
+    pub fn should_sample(&mut self, now: Instant) -> bool {
+        let due = self
+            .last
+            .is_none_or(|last| now.duration_since(last) >= self.interval);
+        if due {
+            self.last = Some(now);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_and_throttle() {
+        let mut settings = ProgressLogSettings::default();
+        let path = PathBuf::from("/data/progress.jsonl");
+        assert_eq!(ProgressLog::from_settings(&settings, path.clone()), None);
+
+        settings.enabled = true;
+        settings.interval_secs = 0;
+        let log = ProgressLog::from_settings(&settings, path).unwrap();
+        assert_eq!(log.interval, Duration::from_secs(1));
+
+        let start = Instant::now();
+        let mut throttle = log.throttle();
+        assert!(throttle.should_sample(start));
+        assert!(!throttle.should_sample(start + Duration::from_millis(500)));
+        assert!(throttle.should_sample(start + Duration::from_secs(1)));
+        assert!(!throttle.should_sample(start + Duration::from_millis(1500)));
+    }
+
+    #[tokio::test]
+    async fn test_append_sample() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let settings = ProgressLogSettings {
+            enabled: true,
+            interval_secs: 10,
+        };
+        let log = ProgressLog::from_settings(&settings, dir.join("progress.jsonl")).unwrap();
+
+        let job_id = Uuid::new_v4();
+        let stats = EncodingStats {
+            frame: 1200,
+            total_frames: Some(4800),
+            fps: 48.0,
+            progress_percent: 25.0,
+            eta: Some(Duration::from_secs(75)),
+            ..EncodingStats::default()
+        };
+        let sample = ProgressSample::new(job_id, &stats, Utc::now());
+        log.append(&sample).await.unwrap();
+        log.append(&sample).await.unwrap();
+
+        let content = tokio::fs::read_to_string(dir.join("progress.jsonl"))
+            .await
+            .unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["job_id"], job_id.to_string());
+        assert_eq!(lines[0]["percent"], 25.0);
+        assert_eq!(lines[0]["fps"], 48.0);
+        assert_eq!(lines[0]["eta_secs"], 75.0);
+    }
+}
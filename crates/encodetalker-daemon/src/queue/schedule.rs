@@ -0,0 +1,310 @@
+use crate::config::ScheduleSettings;
+use anyhow::Result;
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+/// Valeurs d'un champ d'une expression calendaire : `*`, `5`, `1..5`, `*/15`, `0..30/10`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Field {
+    /// Plages (début, fin, pas), vide = toutes les valeurs
+    ranges: Vec<(u32, u32, u32)>,
+}
+
+impl Field {
+    const ANY: Self = Self { ranges: Vec::new() };
+
+    /// Lire une liste séparée par des virgules, bornée à `min..=max`
+    fn parse(text: &str, name: &str, min: u32, max: u32) -> Result<Self, String> {
+        if text == "*" {
+            return Ok(Self::ANY);
+        }
+        let number = |value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("{name} \"{value}\" invalide"))
+        };
+        let mut ranges = Vec::new();
+        for item in text.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, number(step)?),
+                None => (item, 1),
+            };
+            if step == 0 {
+                return Err(format!("{name} \"{item}\" : pas nul"));
+            }
+            let (start, end) = match range.split_once("..") {
+                Some((start, end)) => (number(start)?, number(end)?),
+                None if range == "*" => (min, max),
+                // `5/10` : de 5 jusqu'à la fin du champ, de 10 en 10
+                None if step > 1 => (number(range)?, max),
+                None => {
+                    let value = number(range)?;
+                    (value, value)
+                }
+            };
+            if start < min || end > max || start > end {
+                return Err(format!("{name} \"{item}\" hors plage ({min}-{max})"));
+            }
+            ranges.push((start, end, step));
+        }
+        Ok(Self { ranges })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.ranges.is_empty()
+            || self.ranges.iter().any(|&(start, end, step)| {
+                (start..=end).contains(&value) && (value - start).is_multiple_of(step)
+            })
+    }
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Jour de la semaine (0 = lundi), nom anglais complet ou abrégé à trois lettres
+fn parse_weekday(name: &str) -> Result<u32, String> {
+    let lower = name.to_ascii_lowercase();
+    (0u32..)
+        .zip(WEEKDAYS)
+        .find(|(_, day)| *day == lower || (lower.len() == 3 && day.starts_with(&lower)))
+        .map(|(index, _)| index)
+        .ok_or_else(|| format!("jour de semaine \"{name}\" inconnu"))
+}
+
+/// Expression calendaire au format `OnCalendar` de systemd, évaluée à la minute
+///
+/// `[jours] [AAAA-MM-JJ] [HH:MM[:SS]]` : les jours sont des noms anglais (`Mon..Fri`,
+/// `Sat,Sun`), chaque autre champ accepte `*`, des listes, des plages `..` et des pas
+/// `/`. Une date omise vaut `*-*-*`, une heure omise `00:00`. Les secondes sont lues mais
+/// ignorées : un job démarre pendant toute minute décrite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarExpr {
+    /// Jours autorisés (bit 0 = lundi)
+    weekdays: u8,
+    year: Field,
+    month: Field,
+    day: Field,
+    hour: Field,
+    minute: Field,
+}
+
+impl CalendarExpr {
+    /// Lire une expression
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message désignant la partie invalide de l'expression.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let expanded = match text.trim().to_ascii_lowercase().as_str() {
+            "minutely" => "*-*-* *:*".to_string(),
+            "hourly" => "*-*-* *:00".to_string(),
+            "daily" => "*-*-* 00:00".to_string(),
+            "weekly" => "Mon *-*-* 00:00".to_string(),
+            "monthly" => "*-*-01 00:00".to_string(),
+            "quarterly" => "*-01,04,07,10-01 00:00".to_string(),
+            "semiannually" => "*-01,07-01 00:00".to_string(),
+            "yearly" | "annually" => "*-01-01 00:00".to_string(),
+            _ => text.trim().to_string(),
+        };
+        let mut parts: Vec<&str> = expanded.split_whitespace().collect();
+        if parts.is_empty() {
+            return Err("expression vide".to_string());
+        }
+
+        let mut weekdays = 0x7f;
+        if parts[0].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            weekdays = 0;
+            for item in parts.remove(0).split(',') {
+                let (start, end) = match item.split_once("..") {
+                    Some((start, end)) => (parse_weekday(start)?, parse_weekday(end)?),
+                    None => {
+                        let day = parse_weekday(item)?;
+                        (day, day)
+                    }
+                };
+                if start > end {
+                    return Err(format!(
+                        "jours \"{item}\" dans le désordre (lundi en premier)"
+                    ));
+                }
+                for day in start..=end {
+                    weekdays |= 1 << day;
+                }
+            }
+        }
+
+        let (mut date, mut time) = (None, None);
+        for part in parts {
+            if part.contains(':') && time.is_none() {
+                time = Some(part);
+            } else if part.contains('-') && date.is_none() && time.is_none() {
+                date = Some(part);
+            } else {
+                return Err(format!("\"{part}\" n'est ni une date ni une heure"));
+            }
+        }
+
+        let date: Vec<&str> = date.unwrap_or("*-*-*").split('-').collect();
+        let (year, month, day) = match date.as_slice() {
+            [year, month, day] => (Field::parse(year, "année", 1970, 2199)?, *month, *day),
+            [month, day] => (Field::ANY, *month, *day),
+            _ => return Err(format!("date \"{}\" invalide (AAAA-MM-JJ)", date.join("-"))),
+        };
+        let month = Field::parse(month, "mois", 1, 12)?;
+        let day = Field::parse(day, "jour", 1, 31)?;
+
+        let time: Vec<&str> = time.unwrap_or("00:00").split(':').collect();
+        let (hour, minute) = match time.as_slice() {
+            [hour, minute] | [hour, minute, _] => (*hour, *minute),
+            _ => {
+                return Err(format!(
+                    "heure \"{}\" invalide (HH:MM[:SS])",
+                    time.join(":")
+                ))
+            }
+        };
+        if let [_, _, second] = time.as_slice() {
+            Field::parse(second, "seconde", 0, 59)?;
+        }
+
+        Ok(Self {
+            weekdays,
+            year,
+            month,
+            day,
+            hour: Field::parse(hour, "heure", 0, 23)?,
+            minute: Field::parse(minute, "minute", 0, 59)?,
+        })
+    }
+
+    /// La minute de `at` est-elle décrite par l'expression ?
+    #[must_use]
+    pub fn matches(&self, at: NaiveDateTime) -> bool {
+        self.weekdays & (1 << at.weekday().num_days_from_monday()) != 0
+            && u32::try_from(at.year()).is_ok_and(|year| self.year.matches(year))
+            && self.month.matches(at.month())
+            && self.day.matches(at.day())
+            && self.hour.matches(at.hour())
+            && self.minute.matches(at.minute())
+    }
+}
+
+/// Heures de démarrage des jobs (`[schedule]`)
+///
+/// Hors des minutes décrites, les jobs restent en attente ; les jobs en cours continuent
+/// et les extraits de test démarrent toujours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    exprs: Vec<CalendarExpr>,
+}
+
+impl Schedule {
+    /// Lire la section de configuration (None = démarrage à toute heure)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur citant la première expression invalide.
+    pub fn from_settings(settings: &ScheduleSettings) -> Result<Option<Self>> {
+        let exprs = settings
+            .calendar
+            .iter()
+            .map(|text| {
+                CalendarExpr::parse(text)
+                    .map_err(|e| anyhow::anyhow!("schedule: calendar \"{text}\" : {e}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((!exprs.is_empty()).then_some(Self { exprs }))
+    }
+
+    /// Un job peut-il démarrer à `at` (heure locale) ?
+    #[must_use]
+    pub fn allows(&self, at: NaiveDateTime) -> bool {
+        self.exprs.iter().any(|expr| expr.matches(at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 30)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_calendar_expressions() {
+        // 2026-10-12 est un lundi
+        let nights = CalendarExpr::parse("Mon..Fri *-*-* 22,23,00..05:*").unwrap();
+        assert!(nights.matches(at(2026, 10, 12, 22, 15)));
+        assert!(nights.matches(at(2026, 10, 16, 5, 59)));
+        assert!(!nights.matches(at(2026, 10, 16, 6, 0)));
+        assert!(!nights.matches(at(2026, 10, 17, 23, 0)));
+
+        let weekend = CalendarExpr::parse("Sat,sunday *:*").unwrap();
+        assert!(weekend.matches(at(2026, 10, 18, 14, 3)));
+        assert!(!weekend.matches(at(2026, 10, 19, 14, 3)));
+
+        let steps = CalendarExpr::parse("2026-10..12-01/7 08:*/15:00").unwrap();
+        assert!(steps.matches(at(2026, 10, 8, 8, 45)));
+        assert!(!steps.matches(at(2026, 10, 8, 8, 46)));
+        assert!(!steps.matches(at(2026, 10, 9, 8, 45)));
+        assert!(!steps.matches(at(2027, 10, 8, 8, 45)));
+
+        // Heure omise : minuit ; date sans année
+        let daily = CalendarExpr::parse("daily").unwrap();
+        assert!(daily.matches(at(2026, 10, 14, 0, 0)));
+        assert!(!daily.matches(at(2026, 10, 14, 0, 1)));
+        assert_eq!(
+            CalendarExpr::parse("Mon").unwrap(),
+            CalendarExpr::parse("mon *-*-* 00:00:00").unwrap()
+        );
+        assert!(CalendarExpr::parse("12-24 *:*")
+            .unwrap()
+            .matches(at(2030, 12, 24, 18, 0)));
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for (text, message) in [
+            ("", "vide"),
+            ("Mon..Fry 22:*", "Fry"),
+            ("Fri..Mon *:*", "désordre"),
+            ("*-*-* 24:*", "heure"),
+            ("*-13-* *:*", "mois"),
+            ("*-*-* *:*/0", "pas nul"),
+            ("*-*-* 10..08:*", "hors plage"),
+            ("*-*-* 8h", "ni une date"),
+            ("1-2-3-4 *:*", "date"),
+            ("*-*-* 10", "ni une date"),
+        ] {
+            let err = CalendarExpr::parse(text).unwrap_err();
+            assert!(err.contains(message), "{text}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_schedule_from_settings() {
+        let mut settings = ScheduleSettings::default();
+        assert_eq!(Schedule::from_settings(&settings).unwrap(), None);
+
+        settings.calendar = vec!["Sat,Sun *:*".to_string(), "*-*-* 01..05:*".to_string()];
+        let schedule = Schedule::from_settings(&settings).unwrap().unwrap();
+        assert!(schedule.allows(at(2026, 10, 14, 3, 0)));
+        assert!(schedule.allows(at(2026, 10, 17, 12, 0)));
+        assert!(!schedule.allows(at(2026, 10, 14, 12, 0)));
+
+        settings.calendar.push("Lun *:*".to_string());
+        let err = Schedule::from_settings(&settings).unwrap_err().to_string();
+        assert!(err.contains("\"Lun *:*\""), "{err}");
+    }
+}
@@ -0,0 +1,237 @@
+use crate::config::SidecarReportSettings;
+use crate::encoder::{EncodeOutcome, SourceProbe};
+use anyhow::{Context, Result};
+use encodetalker_common::{EncodingJob, PathsConfig};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Version du format du rapport, incrémentée à chaque changement incompatible
+const REPORT_VERSION: u32 = 1;
+
+/// Rapport JSON écrit après un encodage réussi (`[sidecar_report]`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SidecarReport {
+    /// Modèle du nom (`{name}`, `{stem}`)
+    file_name: String,
+    /// Répertoire des rapports (None = celui de la sortie)
+    dir: Option<PathBuf>,
+}
+
+/// Fichier produit, décrit dans son rapport
+#[derive(Debug, Serialize)]
+struct OutputEntry<'a> {
+    path: &'a Path,
+    size_bytes: Option<u64>,
+    /// Durée attendue (partie encodée de la source)
+    duration_secs: Option<f64>,
+}
+
+/// Résultat de l'encodage
+#[derive(Debug, Serialize)]
+struct EncodeEntry {
+    width: u32,
+    height: u32,
+    total_frames: Option<u64>,
+    video_encode_secs: f64,
+    /// Nombre de fichiers produits (plusieurs pour un job découpé)
+    outputs: usize,
+}
+
+/// Contenu du rapport
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    version: u32,
+    output: OutputEntry<'a>,
+    encode: EncodeEntry,
+    source: &'a SourceProbe,
+    job: &'a EncodingJob,
+}
+
+impl SidecarReport {
+    /// Lire la section de configuration (None = aucun rapport)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le nom ne désigne pas un fichier propre à chaque sortie ou si
+    /// le répertoire ne peut pas être expansé.
+    pub fn from_settings(settings: &SidecarReportSettings) -> Result<Option<Self>> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let file_name = settings.file_name.trim();
+        if file_name.contains(['/', '\\']) {
+            anyhow::bail!(
+                "sidecar_report: file_name \"{file_name}\" ne doit pas contenir de répertoire (voir dir)"
+            );
+        }
+        if !file_name.contains("{name}") && !file_name.contains("{stem}") {
+            anyhow::bail!(
+                "sidecar_report: file_name \"{file_name}\" doit contenir {{name}} ou {{stem}}"
+            );
+        }
+        let dir = settings
+            .dir
+            .as_deref()
+            .map(PathsConfig::expand_path)
+            .transpose()?;
+        Ok(Some(Self {
+            file_name: file_name.to_string(),
+            dir,
+        }))
+    }
+
+    /// Chemin du rapport d'une sortie
+    #[must_use]
+    pub fn report_path(&self, output: &Path) -> PathBuf {
+        let name = output
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let stem = output
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let file_name = self
+            .file_name
+            .replace("{name}", &name)
+            .replace("{stem}", &stem);
+        match &self.dir {
+            Some(dir) => dir.join(file_name),
+            None => output.with_file_name(file_name),
+        }
+    }
+
+    /// Écrire un rapport par fichier produit (chaque segment d'un job découpé)
+    ///
+    /// Un échec est seulement signalé : le job reste réussi.
+    pub async fn write(&self, job: &EncodingJob, outcome: &EncodeOutcome) {
+        for (output, duration) in &outcome.outputs {
+            let path = self.report_path(output);
+            if path == *output {
+                warn!(
+                    "Rapport de {} non écrit : il remplacerait la sortie",
+                    output.display()
+                );
+                continue;
+            }
+            let report = Report {
+                version: REPORT_VERSION,
+                output: OutputEntry {
+                    path: output,
+                    size_bytes: tokio::fs::metadata(output).await.ok().map(|m| m.len()),
+                    duration_secs: duration.map(|d| d.as_secs_f64()),
+                },
+                encode: EncodeEntry {
+                    width: outcome.width,
+                    height: outcome.height,
+                    total_frames: outcome.total_frames,
+                    video_encode_secs: outcome.video_encode_time.as_secs_f64(),
+                    outputs: outcome.outputs.len(),
+                },
+                source: &outcome.source,
+                job,
+            };
+            match write_report(&path, &report).await {
+                Ok(()) => info!("Rapport écrit : {}", path.display()),
+                Err(e) => warn!("Rapport de {} non écrit: {e:#}", output.display()),
+            }
+        }
+    }
+}
+
+async fn write_report(path: &Path, report: &Report<'_>) -> Result<()> {
+    let json = serde_json::to_vec_pretty(report).context("Échec de la sérialisation")?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Impossible de créer {}", parent.display()))?;
+    }
+    tokio::fs::write(path, json)
+        .await
+        .with_context(|| format!("Échec d'écriture de {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encodetalker_common::EncodingConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn test_settings_and_report_path() {
+        let mut settings = SidecarReportSettings::default();
+        assert_eq!(SidecarReport::from_settings(&settings).unwrap(), None);
+
+        settings.enabled = true;
+        let report = SidecarReport::from_settings(&settings).unwrap().unwrap();
+        assert_eq!(
+            report.report_path(Path::new("/videos/film.av1.mkv")),
+            PathBuf::from("/videos/film.av1.json")
+        );
+
+        settings.file_name = "{name}.report.json".to_string();
+        settings.dir = Some("/rapports".to_string());
+        let report = SidecarReport::from_settings(&settings).unwrap().unwrap();
+        assert_eq!(
+            report.report_path(Path::new("/videos/film.av1.mkv")),
+            PathBuf::from("/rapports/film.av1.mkv.report.json")
+        );
+
+        settings.file_name = "rapport.json".to_string();
+        assert!(SidecarReport::from_settings(&settings).is_err());
+        settings.file_name = "json/{stem}.json".to_string();
+        assert!(SidecarReport::from_settings(&settings).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_write_report() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let output = dir.join("film.av1.mkv");
+        tokio::fs::write(&output, b"sortie").await.unwrap();
+
+        let mut job = EncodingJob::new(
+            dir.join("film.mkv"),
+            output.clone(),
+            EncodingConfig::default(),
+        );
+        job.mark_completed();
+        let outcome = EncodeOutcome {
+            width: 1920,
+            height: 1080,
+            outputs: vec![(output.clone(), Some(Duration::from_secs(90)))],
+            source: SourceProbe {
+                video_codec: "h264".to_string(),
+                ..SourceProbe::default()
+            },
+            ..EncodeOutcome::default()
+        };
+        let report = SidecarReport {
+            file_name: "{stem}.json".to_string(),
+            dir: None,
+        };
+        report.write(&job, &outcome).await;
+
+        let json = tokio::fs::read_to_string(dir.join("film.av1.json"))
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], REPORT_VERSION);
+        assert_eq!(value["output"]["size_bytes"], 6);
+        assert_eq!(value["output"]["duration_secs"], 90.0);
+        assert_eq!(value["encode"]["width"], 1920);
+        assert_eq!(value["source"]["video_codec"], "h264");
+        let parsed: EncodingJob = serde_json::from_value(value["job"].clone()).unwrap();
+        assert_eq!(parsed.id, job.id);
+
+        // Un nom qui désigne la sortie elle-même ne l'écrase pas
+        let clobber = SidecarReport {
+            file_name: "{name}".to_string(),
+            dir: None,
+        };
+        clobber.write(&job, &outcome).await;
+        assert_eq!(tokio::fs::read(&output).await.unwrap(), b"sortie");
+    }
+}
@@ -0,0 +1,299 @@
+use crate::config::{SourceAction, SourceCleanupSettings};
+use crate::encoder::{EncodeOutcome, EncodingPipeline};
+use anyhow::{Context, Result};
+use encodetalker_common::{is_concat_list, parse_concat_list, EncodingJob, PathsConfig};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// La corbeille freedesktop (`~/.local/share/Trash`) n'existe que sur Linux : ailleurs,
+/// un fichier y serait caché sans que le gestionnaire de fichiers ne le montre
+const TRASH_SUPPORTED: bool = cfg!(target_os = "linux");
+
+/// Sort des sources après un encodage réussi (`[source_cleanup]`)
+#[derive(Debug, Clone)]
+pub struct SourceCleanup {
+    action: SourceAction,
+    /// Destination de l'action `move`
+    move_dir: Option<PathBuf>,
+    verify_output: bool,
+}
+
+impl SourceCleanup {
+    /// Lire la section de configuration (None = sources conservées)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si `move` n'a pas de `move_dir`, si le chemin ne peut pas être
+    /// expansé ou si `trash` est demandé hors de Linux.
+    pub fn from_settings(settings: &SourceCleanupSettings) -> Result<Option<Self>> {
+        let move_dir = match (settings.action, settings.move_dir.as_deref()) {
+            (SourceAction::Keep, _) => return Ok(None),
+            (SourceAction::Trash, _) if !TRASH_SUPPORTED => {
+                anyhow::bail!("source_cleanup: l'action \"trash\" n'est disponible que sur Linux")
+            }
+            (SourceAction::Move, None) => {
+                anyhow::bail!("source_cleanup: l'action \"move\" nécessite move_dir")
+            }
+            (SourceAction::Move, Some(dir)) => Some(PathsConfig::expand_path(dir)?),
+            _ => None,
+        };
+        Ok(Some(Self {
+            action: settings.action,
+            move_dir,
+            verify_output: settings.verify_output,
+        }))
+    }
+
+    /// Appliquer l'action aux sources d'un job terminé
+    ///
+    /// Les sources sont conservées si la vérification de la sortie échoue, si seul un
+    /// extrait a été encodé ou si l'action échoue ; le job reste réussi dans tous les cas.
+    pub async fn apply(
+        &self,
+        job: &EncodingJob,
+        outcome: &EncodeOutcome,
+        pipeline: &EncodingPipeline,
+    ) {
+        if !job.config.trim.is_full() {
+            info!(
+                "Job {} : extrait seulement, source conservée ({})",
+                job.id,
+                job.input_path.display()
+            );
+            return;
+        }
+        if self.verify_output {
+            if let Err(e) = pipeline.verify_outputs(outcome, &job.config.env).await {
+                warn!(
+                    "Job {} : vérification de la sortie échouée, source conservée: {e}",
+                    job.id
+                );
+                return;
+            }
+        }
+
+        for source in job_sources(job).await {
+            if outcome.outputs.iter().any(|(output, _)| *output == source) {
+                continue;
+            }
+            if let Err(e) = self.apply_to(&source).await {
+                warn!(
+                    "Job {} : source {} conservée: {e:#}",
+                    job.id,
+                    source.display()
+                );
+            }
+        }
+    }
+
+    async fn apply_to(&self, source: &Path) -> Result<()> {
+        match self.action {
+            SourceAction::Keep => {}
+            SourceAction::Move => {
+                let dir = self.move_dir.as_deref().context("move_dir manquant")?;
+                tokio::fs::create_dir_all(dir)
+                    .await
+                    .with_context(|| format!("Impossible de créer {}", dir.display()))?;
+                let dest = dir.join(source.file_name().context("Source sans nom de fichier")?);
+                if tokio::fs::try_exists(&dest).await.unwrap_or(true) {
+                    anyhow::bail!("{} existe déjà", dest.display());
+                }
+                move_file(source, &dest).await?;
+                info!(
+                    "Source déplacée: {} -> {}",
+                    source.display(),
+                    dest.display()
+                );
+            }
+            SourceAction::Delete => {
+                warn!("SUPPRESSION DÉFINITIVE de la source {}", source.display());
+                tokio::fs::remove_file(source)
+                    .await
+                    .context("Échec de la suppression")?;
+            }
+            SourceAction::Trash => {
+                let trashed = move_to_trash(source).await?;
+                warn!(
+                    "Source envoyée à la corbeille: {} -> {}",
+                    source.display(),
+                    trashed.display()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fichiers sources d'un job : la source, ou chaque partie d'une liste de concaténation
+/// suivie de la liste elle-même
+async fn job_sources(job: &EncodingJob) -> Vec<PathBuf> {
+    if !is_concat_list(&job.input_path) {
+        return vec![job.input_path.clone()];
+    }
+    let base = job.input_path.parent().unwrap_or(Path::new("."));
+    let mut sources = match tokio::fs::read_to_string(&job.input_path).await {
+        Ok(content) => parse_concat_list(&content, base),
+        Err(e) => {
+            warn!("Liste {} illisible: {e}", job.input_path.display());
+            Vec::new()
+        }
+    };
+    sources.push(job.input_path.clone());
+    sources
+}
+
+/// Déplacer un fichier, par copie puis suppression s'il change de système de fichiers
+async fn move_file(source: &Path, dest: &Path) -> Result<()> {
+    match tokio::fs::rename(source, dest).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            tokio::fs::copy(source, dest)
+                .await
+                .with_context(|| format!("Échec de la copie vers {}", dest.display()))?;
+            tokio::fs::remove_file(source)
+                .await
+                .context("Copie faite, mais échec de la suppression de la source")
+        }
+        Err(e) => Err(e).with_context(|| format!("Échec du déplacement vers {}", dest.display())),
+    }
+}
+
+/// Envoyer un fichier dans la corbeille de l'utilisateur (`$XDG_DATA_HOME/Trash`)
+///
+/// Suit la spécification freedesktop : le fichier va dans `files/`, accompagné d'un
+/// `info/<nom>.trashinfo` donnant son chemin d'origine et la date, pour que les
+/// gestionnaires de fichiers puissent le restaurer.
+async fn move_to_trash(source: &Path) -> Result<PathBuf> {
+    let trash = dirs::data_local_dir()
+        .context("Répertoire de données introuvable")?
+        .join("Trash");
+    let (files, info) = (trash.join("files"), trash.join("info"));
+    tokio::fs::create_dir_all(&files).await?;
+    tokio::fs::create_dir_all(&info).await?;
+
+    let source = std::path::absolute(source)?;
+    let name = source
+        .file_name()
+        .context("Source sans nom de fichier")?
+        .to_string_lossy()
+        .into_owned();
+    // Nom libre dans la corbeille : réservé en créant le .trashinfo (create_new)
+    for n in 1.. {
+        let trashed_name = if n == 1 {
+            name.clone()
+        } else {
+            format!("{name}.{n}")
+        };
+        let info_path = info.join(format!("{trashed_name}.trashinfo"));
+        let dest = files.join(&trashed_name);
+        if tokio::fs::try_exists(&dest).await.unwrap_or(true) {
+            continue;
+        }
+        let created = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+            .await;
+        let mut file = match created {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).context("Échec de l'écriture du .trashinfo"),
+        };
+        tokio::io::AsyncWriteExt::write_all(
+            &mut file,
+            trash_info(&source, chrono::Local::now().naive_local()).as_bytes(),
+        )
+        .await?;
+        if let Err(e) = move_file(&source, &dest).await {
+            let _ = tokio::fs::remove_file(&info_path).await;
+            return Err(e);
+        }
+        return Ok(dest);
+    }
+    unreachable!("noms de corbeille épuisés")
+}
+
+/// Contenu d'un `.trashinfo` (chemin encodé comme une URL, date locale sans fuseau)
+fn trash_info(source: &Path, deleted_at: chrono::NaiveDateTime) -> String {
+    let mut path = String::new();
+    for &byte in source.as_os_str().as_encoded_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            path.push(char::from(byte));
+        } else {
+            let _ = write!(path, "%{byte:02X}");
+        }
+    }
+    format!(
+        "[Trash Info]\nPath={path}\nDeletionDate={}\n",
+        deleted_at.format("%Y-%m-%dT%H:%M:%S")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_require_move_dir() {
+        let mut settings = SourceCleanupSettings::default();
+        assert!(SourceCleanup::from_settings(&settings).unwrap().is_none());
+
+        settings.action = SourceAction::Move;
+        assert!(SourceCleanup::from_settings(&settings).is_err());
+        settings.move_dir = Some("/archive/films".to_string());
+        let cleanup = SourceCleanup::from_settings(&settings).unwrap().unwrap();
+        assert_eq!(cleanup.move_dir, Some(PathBuf::from("/archive/films")));
+        assert!(cleanup.verify_output);
+
+        // Corbeille freedesktop : Linux seulement
+        settings.action = SourceAction::Trash;
+        assert_eq!(
+            SourceCleanup::from_settings(&settings).is_ok(),
+            cfg!(target_os = "linux")
+        );
+    }
+
+    #[test]
+    fn test_trash_info_escapes_path() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_opt(14, 5, 9)
+            .unwrap();
+        assert_eq!(
+            trash_info(Path::new("/vidéos/mon film.mkv"), date),
+            "[Trash Info]\nPath=/vid%C3%A9os/mon%20film.mkv\nDeletionDate=2026-03-01T14:05:09\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_move_keeps_existing_destination() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let archive = dir.join("archive");
+        tokio::fs::create_dir_all(&archive).await.unwrap();
+        let source = dir.join("film.mkv");
+        tokio::fs::write(&source, b"source").await.unwrap();
+
+        let cleanup = SourceCleanup {
+            action: SourceAction::Move,
+            move_dir: Some(archive.clone()),
+            verify_output: false,
+        };
+        tokio::fs::write(archive.join("film.mkv"), b"autre")
+            .await
+            .unwrap();
+        assert!(cleanup.apply_to(&source).await.is_err());
+        assert!(source.exists());
+
+        tokio::fs::remove_file(archive.join("film.mkv"))
+            .await
+            .unwrap();
+        cleanup.apply_to(&source).await.unwrap();
+        assert!(!source.exists());
+        assert_eq!(
+            tokio::fs::read(archive.join("film.mkv")).await.unwrap(),
+            b"source"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Budget global de threads réparti entre les jobs actifs (`daemon.total_threads`)
+///
+/// Un encodeur ne peut pas changer de nombre de threads en cours de route : le budget est
+/// donc découpé en parts égales, une par emplacement (`max_concurrent_jobs`). Un job seul
+/// ne prend que sa part, de sorte qu'un job démarré ensuite trouve toujours la sienne.
+#[derive(Debug)]
+pub struct ThreadBudget {
+    total: u32,
+    /// Nombre de jobs pouvant tourner en même temps
+    slots: u32,
+    /// Threads réservés par job actif
+    allocations: HashMap<Uuid, u32>,
+}
+
+impl ThreadBudget {
+    #[must_use]
+    pub fn new(total: u32, slots: usize) -> Self {
+        Self {
+            total: total.max(1),
+            slots: u32::try_from(slots.max(1)).unwrap_or(u32::MAX),
+            allocations: HashMap::new(),
+        }
+    }
+
+    /// Threads non réservés
+    fn available(&self) -> u32 {
+        self.total
+            .saturating_sub(self.allocations.values().sum::<u32>())
+    }
+
+    /// Réserver les threads d'un job qui démarre
+    ///
+    /// La part d'un emplacement vaut `total / slots`, limitée aux threads encore libres
+    /// (au moins 1). Un nombre de threads imposé par le job est réservé tel quel, même
+    /// s'il dépasse le budget restant.
+    pub fn allocate(&mut self, job_id: Uuid, job_threads: Option<u32>) -> u32 {
+        self.release(job_id);
+        let threads =
+            job_threads.unwrap_or_else(|| (self.total / self.slots).min(self.available()).max(1));
+        self.allocations.insert(job_id, threads);
+        threads
+    }
+
+    /// Rendre les threads d'un job terminé
+    pub fn release(&mut self, job_id: Uuid) {
+        self.allocations.remove(&job_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_is_split_fairly_between_slots() {
+        let mut budget = ThreadBudget::new(16, 2);
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        // Un job seul ne prend que sa part : le suivant trouve la sienne
+        assert_eq!(budget.allocate(a, None), 8);
+        assert_eq!(budget.allocate(b, None), 8);
+        // Budget épuisé : un job en plus garde au moins un thread
+        assert_eq!(budget.allocate(c, None), 1);
+        budget.release(c);
+
+        // Les threads libérés reviennent au job suivant
+        budget.release(a);
+        assert_eq!(budget.allocate(c, None), 8);
+        budget.release(b);
+        budget.release(c);
+
+        // Threads imposés par le job : réservés tels quels et déduits du budget
+        assert_eq!(budget.allocate(a, Some(12)), 12);
+        assert_eq!(budget.allocate(b, None), 4);
+        budget.release(a);
+        budget.release(b);
+
+        // Trois emplacements : parts égales, arrondies à l'inférieur
+        let mut budget = ThreadBudget::new(16, 3);
+        let shares: Vec<u32> = (0..3)
+            .map(|_| budget.allocate(Uuid::new_v4(), None))
+            .collect();
+        assert_eq!(shares, [5, 5, 5]);
+    }
+}
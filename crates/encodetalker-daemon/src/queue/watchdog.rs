@@ -0,0 +1,136 @@
+use encodetalker_common::{EncodingPhase, EncodingStats};
+use std::time::{Duration, Instant};
+
+/// Position d'un job dans son encodage : segment, passe, phase VMAF et frame
+type ProgressKey = (Option<u32>, u32, bool, u64);
+
+/// Détection d'un job bloqué (aucune frame encodée pendant `timeout`)
+///
+/// Surveille les stats envoyées par le pipeline. L'audio et le muxage ne rapportant pas
+/// de progression, la surveillance est suspendue une fois la vidéo (ou le VMAF) terminée
+/// et reprend à la mise à jour suivante (nouvelle passe, nouveau segment).
+#[derive(Debug, Clone)]
+pub struct StallWatchdog {
+    timeout: Duration,
+    last_progress: Option<ProgressKey>,
+    last_advance: Instant,
+    armed: bool,
+}
+
+impl StallWatchdog {
+    #[must_use]
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_progress: None,
+            last_advance: now,
+            armed: true,
+        }
+    }
+
+    /// Prendre en compte une mise à jour des stats
+    pub fn observe(&mut self, stats: &EncodingStats, now: Instant) {
+        let key = (
+            stats.segment_index,
+            stats.current_pass,
+            stats.is_calculating_vmaf,
+            stats.frame,
+        );
+        if self.last_progress != Some(key) {
+            self.last_progress = Some(key);
+            self.last_advance = now;
+        }
+        self.armed = !phase_finished(stats);
+    }
+
+    /// Instant au-delà duquel le job est considéré bloqué (None = surveillance suspendue)
+    #[must_use]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.armed.then(|| self.last_advance + self.timeout)
+    }
+
+    /// Repartir de zéro (après un déclenchement, pour un nouvel essai)
+    pub fn reset(&mut self, now: Instant) {
+        self.last_progress = None;
+        self.last_advance = now;
+        self.armed = true;
+    }
+}
+
+/// Vidéo encodée jusqu'à la dernière frame, VMAF calculé, ou étape sans progression
+/// mesurée (attente de l'audio, muxage)
+fn phase_finished(stats: &EncodingStats) -> bool {
+    if matches!(
+        stats.phase,
+        Some(EncodingPhase::Audio | EncodingPhase::Muxing)
+    ) {
+        return true;
+    }
+    !stats.is_calculating_vmaf
+        && (stats.vmaf_score.is_some()
+            || stats
+                .total_frames
+                .is_some_and(|total| total > 0 && stats.frame >= total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(frame: u64) -> EncodingStats {
+        EncodingStats {
+            frame,
+            total_frames: Some(1000),
+            current_pass: 1,
+            total_passes: 1,
+            ..EncodingStats::default()
+        }
+    }
+
+    #[test]
+    fn test_watchdog_tracks_frame_advance() {
+        let start = Instant::now();
+        let timeout = Duration::from_secs(60);
+        let mut watchdog = StallWatchdog::new(timeout, start);
+        assert_eq!(watchdog.deadline(), Some(start + timeout));
+
+        // Même frame (encodeur à 0 fps) : l'échéance ne bouge pas
+        watchdog.observe(&stats(10), start + Duration::from_secs(5));
+        watchdog.observe(&stats(10), start + Duration::from_secs(30));
+        assert_eq!(
+            watchdog.deadline(),
+            Some(start + Duration::from_secs(5) + timeout)
+        );
+
+        watchdog.observe(&stats(11), start + Duration::from_secs(40));
+        assert_eq!(
+            watchdog.deadline(),
+            Some(start + Duration::from_secs(40) + timeout)
+        );
+
+        // Vidéo terminée : audio et muxage ne sont pas surveillés
+        watchdog.observe(&stats(1000), start + Duration::from_secs(50));
+        assert_eq!(watchdog.deadline(), None);
+
+        // Passe suivante : la surveillance reprend
+        let pass2 = EncodingStats {
+            current_pass: 2,
+            ..stats(0)
+        };
+        watchdog.observe(&pass2, start + Duration::from_secs(70));
+        assert_eq!(
+            watchdog.deadline(),
+            Some(start + Duration::from_secs(70) + timeout)
+        );
+
+        // Attente de l'audio puis muxage, annoncés sans frames : toujours suspendue
+        for phase in [EncodingPhase::Audio, EncodingPhase::Muxing] {
+            let waiting = EncodingStats {
+                phase: Some(phase),
+                ..stats(0)
+            };
+            watchdog.observe(&waiting, start + Duration::from_secs(80));
+            assert_eq!(watchdog.deadline(), None);
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use anyhow::{Context, Result};
 use encodetalker_common::{
-    AudioMode, EncoderParams, EncoderType, EncodingConfig, EncodingJob, EncodingStats, JobStatus,
-    VideoContentType,
+    AomParams, AudioMode, CoverArtConfig, DeinterlaceConfig, EncoderParams, EncoderType,
+    EncodingConfig, EncodingJob, EncodingMode, EncodingStats, JobKind, JobStatus, KeyframeConfig,
+    SplitMode, SubtitlePolicy, TonemapConfig, TrimRange, Tune, VfrMode, VideoContentType,
+    VideoMode,
 };
 use encodetalker_daemon::encoder::EncodingPipeline;
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 /// Chemin vers les vidéos de test
@@ -53,6 +56,7 @@ async fn test_encode_test1_mkv_with_svt_av1() -> Result<()> {
         deps_bin_dir().join("SvtAv1EncApp"),
         deps_bin_dir().join("aomenc"),
         false, // precise_frame_count désactivé pour vitesse
+        Duration::from_millis(250),
     );
 
     // Créer le job d'encodage
@@ -62,29 +66,58 @@ async fn test_encode_test1_mkv_with_svt_av1() -> Result<()> {
         output_path: output_path.clone(),
         config: EncodingConfig {
             encoder: EncoderType::SvtAv1,
+            video_mode: VideoMode::Encode,
             encoder_params: EncoderParams {
                 crf: 63,       // CRF maximum (encodage le plus rapide)
                 preset: 13,    // Preset le plus rapide pour SVT-AV1
                 threads: None, // Auto
                 extra_params: vec![],
                 content_type: VideoContentType::default(),
+                tune: Tune::default(),
+                aom: AomParams::default(),
+                encoding_mode: EncodingMode::default(),
             },
             audio_mode: AudioMode::Opus { bitrate: 128 },
             audio_streams: None,
             subtitle_streams: None,
+            audio_languages: Vec::new(),
+            subtitle_languages: Vec::new(),
             enable_vmaf: false,
+            quality_metrics: false,
+            trim: TrimRange::default(),
+            split: SplitMode::default(),
+            deinterlace: DeinterlaceConfig::default(),
+            crop_to_even: false,
+            vfr_mode: VfrMode::default(),
+            tonemap: TonemapConfig::default(),
+            incompatible_subtitles: SubtitlePolicy::default(),
+            keyframes: KeyframeConfig::default(),
+            cover_art: CoverArtConfig::default(),
+            input_params: vec![],
+            audio_input_params: vec![],
+            precise_frame_count: None,
+            env: Default::default(),
         },
         created_at: chrono::Utc::now(),
+        kind: JobKind::Encode,
+        batch_id: None,
         status: JobStatus::Queued,
         stats: None,
         error_message: None,
+        failure_category: None,
+        fallback_level: None,
+        retries: 0,
+        nice: None,
+        allocated_threads: None,
+        verify_report: None,
+        outputs: Vec::new(),
         started_at: None,
         finished_at: None,
     };
 
     // Channels pour stats et cancel
     let (stats_tx, mut stats_rx) = mpsc::unbounded_channel::<EncodingStats>();
-    let (_cancel_tx, cancel_rx) = mpsc::unbounded_channel::<()>();
+    let (_cancel_tx, mut cancel_rx) = mpsc::unbounded_channel::<()>();
 
     // Spawn task pour logger les stats
     let stats_task = tokio::spawn(async move {
@@ -98,7 +131,7 @@ async fn test_encode_test1_mkv_with_svt_av1() -> Result<()> {
 
     // Lancer l'encodage
     println!("Démarrage encodage de test1.mkv...");
-    let result = pipeline.encode_job(&job, stats_tx, cancel_rx).await;
+    let result = pipeline.encode_job(&job, stats_tx, &mut cancel_rx).await;
 
     // Attendre le stats task
     let _ = stats_task.await;
@@ -200,7 +233,9 @@ async fn test_probe_video() -> Result<()> {
         &deps_bin_dir().join("ffprobe"),
         &deps_bin_dir().join("ffmpeg"),
         &input_path,
-        false, // precise_frame_count
+        None, // pas de comptage précis
+        true,
+        &std::collections::HashMap::new(),
     )
     .await?;
 
@@ -225,3 +260,68 @@ async fn test_probe_video() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[ignore = "Test lent, nécessite vidéo"]
+async fn test_encode_video_only_without_audio() -> Result<()> {
+    use tokio::process::Command;
+
+    let input_path = test_video_dir().join("test1.mkv");
+    let output_path = test_video_dir().join("test1.video-only.av1.mkv");
+    assert!(
+        input_path.exists(),
+        "Vidéo de test manquante: {}",
+        input_path.display()
+    );
+    if output_path.exists() {
+        std::fs::remove_file(&output_path)?;
+    }
+
+    let pipeline = EncodingPipeline::new(
+        deps_bin_dir().join("ffmpeg"),
+        deps_bin_dir().join("ffprobe"),
+        deps_bin_dir().join("SvtAv1EncApp"),
+        deps_bin_dir().join("aomenc"),
+        false,
+        Duration::from_millis(250),
+    );
+    let config = EncodingConfig {
+        encoder_params: EncoderParams {
+            crf: 63,
+            preset: 13,
+            ..EncoderParams::default()
+        },
+        audio_mode: AudioMode::None,
+        enable_vmaf: false,
+        trim: TrimRange {
+            start: None,
+            end: Some(5.0),
+        },
+        ..EncodingConfig::default()
+    };
+    let job = EncodingJob::new(input_path, output_path.clone(), config);
+
+    let (stats_tx, _stats_rx) = mpsc::unbounded_channel::<EncodingStats>();
+    let (_cancel_tx, mut cancel_rx) = mpsc::unbounded_channel::<()>();
+    pipeline.encode_job(&job, stats_tx, &mut cancel_rx).await?;
+
+    // Vidéo AV1 et sous-titres, aucune piste audio
+    let output = Command::new(deps_bin_dir().join("ffprobe"))
+        .args(["-v", "quiet", "-print_format", "json", "-show_streams"])
+        .arg(&output_path)
+        .output()
+        .await
+        .context("Échec ffprobe")?;
+    let data: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let streams = data["streams"]
+        .as_array()
+        .context("Pas de streams dans output")?;
+    assert!(streams
+        .iter()
+        .any(|s| s["codec_type"] == "video" && s["codec_name"] == "av1"));
+    assert!(!streams.iter().any(|s| s["codec_type"] == "audio"));
+    assert!(streams.iter().any(|s| s["codec_type"] == "subtitle"));
+
+    std::fs::remove_file(&output_path)?;
+    Ok(())
+}
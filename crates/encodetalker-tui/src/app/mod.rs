@@ -1,3 +1,5 @@
+pub mod pinned_dirs;
 pub mod state;
 
+pub use pinned_dirs::*;
 pub use state::*;
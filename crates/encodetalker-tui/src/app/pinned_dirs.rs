@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use encodetalker_daemon::DaemonConfig;
+use std::path::{Path, PathBuf};
+
+/// Nombre maximal de répertoires épinglés (raccourcis `1` à `9`)
+pub const MAX_PINNED_DIRS: usize = 9;
+
+/// Répertoires épinglés du navigateur de fichiers, dans l'ordre des raccourcis
+///
+/// Ils sont lus depuis `[ui] pinned_dirs` du fichier de configuration, et y sont réécrits
+/// à chaque modification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PinnedDirs {
+    dirs: Vec<PathBuf>,
+}
+
+impl PinnedDirs {
+    /// Épingles de la configuration : doublons ignorés, [`MAX_PINNED_DIRS`] au plus
+    #[must_use]
+    pub fn new(configured: &[PathBuf]) -> Self {
+        let mut pinned = Self::default();
+        for dir in configured {
+            if pinned.dirs.len() < MAX_PINNED_DIRS && !pinned.dirs.contains(dir) {
+                pinned.dirs.push(dir.clone());
+            }
+        }
+        pinned
+    }
+
+    /// Répertoires épinglés, le premier correspondant au raccourci `1`
+    #[must_use]
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Répertoire du raccourci `number` (1 à 9)
+    #[must_use]
+    pub fn get(&self, number: usize) -> Option<&Path> {
+        number
+            .checked_sub(1)
+            .and_then(|index| self.dirs.get(index))
+            .map(PathBuf::as_path)
+    }
+
+    /// Épingler le répertoire, ou le retirer s'il l'est déjà (les suivants remontent)
+    ///
+    /// Retourne `true` si le répertoire vient d'être épinglé.
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si les [`MAX_PINNED_DIRS`] emplacements sont déjà pris.
+    pub fn toggle(&mut self, dir: &Path) -> Result<bool, String> {
+        if let Some(index) = self.dirs.iter().position(|d| d == dir) {
+            self.dirs.remove(index);
+            return Ok(false);
+        }
+        if self.dirs.len() >= MAX_PINNED_DIRS {
+            return Err(format!(
+                "{MAX_PINNED_DIRS} répertoires déjà épinglés, retirez-en un d'abord"
+            ));
+        }
+        self.dirs.push(dir.to_path_buf());
+        Ok(true)
+    }
+
+    /// Écrire les épingles dans `[ui] pinned_dirs` du fichier de configuration
+    ///
+    /// Le reste du fichier, commentaires compris, est conservé. Un fichier absent est créé
+    /// avec la configuration par défaut.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être lu, analysé ou écrit.
+    pub async fn save(&self, config_file: &Path) -> Result<()> {
+        let content = match tokio::fs::read_to_string(config_file).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                toml::to_string_pretty(&DaemonConfig::default())
+                    .context("Configuration par défaut non sérialisable")?
+            }
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Impossible de lire {}", config_file.display()))
+            }
+        };
+        let updated = self.write_to_config(&content)?;
+
+        if let Some(parent) = config_file.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(config_file, updated)
+            .await
+            .with_context(|| format!("Impossible d'écrire {}", config_file.display()))
+    }
+
+    /// Contenu de la configuration avec `[ui] pinned_dirs` remplacé par ces épingles
+    fn write_to_config(&self, content: &str) -> Result<String> {
+        let mut doc: toml_edit::DocumentMut = content
+            .parse()
+            .context("Fichier de configuration TOML invalide")?;
+        let ui = doc
+            .entry("ui")
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .context("[ui] n'est pas une table")?;
+        let dirs: toml_edit::Array = self
+            .dirs
+            .iter()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .collect();
+        ui.insert("pinned_dirs", toml_edit::value(dirs));
+        Ok(doc.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_dirs_new_and_toggle() {
+        let mut pinned = PinnedDirs::new(&[
+            PathBuf::from("/media/films"),
+            PathBuf::from("/media/séries"),
+            PathBuf::from("/media/films"),
+        ]);
+        assert_eq!(
+            pinned.dirs(),
+            [Path::new("/media/films"), Path::new("/media/séries")]
+        );
+        assert_eq!(pinned.get(2), Some(Path::new("/media/séries")));
+        assert_eq!(pinned.get(0), None);
+        assert_eq!(pinned.get(3), None);
+
+        assert_eq!(pinned.toggle(Path::new("/media/anime")), Ok(true));
+        assert_eq!(pinned.toggle(Path::new("/media/films")), Ok(false));
+        assert_eq!(pinned.get(1), Some(Path::new("/media/séries")));
+
+        for n in pinned.dirs().len()..MAX_PINNED_DIRS {
+            pinned.toggle(Path::new(&format!("/media/{n}"))).unwrap();
+        }
+        assert!(pinned.toggle(Path::new("/media/trop")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_keeps_the_rest_of_the_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_file = tmp.path().join("config.toml");
+        let pinned = PinnedDirs::new(&[PathBuf::from("/media/films")]);
+
+        // Fichier absent : configuration par défaut, relue avec les épingles
+        pinned.save(&config_file).await.unwrap();
+        let config = DaemonConfig::load_from_file(&config_file).unwrap();
+        assert_eq!(config.ui.pinned_dirs, [PathBuf::from("/media/films")]);
+
+        // Fichier existant : commentaires et réglages conservés, épingles remplacées
+        let original = std::fs::read_to_string(&config_file)
+            .unwrap()
+            .replace("[ui]", "# Mes réglages\n[ui]");
+        std::fs::write(&config_file, &original).unwrap();
+        let pinned = PinnedDirs::new(&[PathBuf::from("/media/séries"), PathBuf::from("/a")]);
+        pinned.save(&config_file).await.unwrap();
+        let content = std::fs::read_to_string(&config_file).unwrap();
+        assert!(content.contains("# Mes réglages"));
+        let config = DaemonConfig::load_from_file(&config_file).unwrap();
+        assert_eq!(PinnedDirs::new(&config.ui.pinned_dirs), pinned, "{content}");
+    }
+}
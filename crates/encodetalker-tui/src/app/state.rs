@@ -1,8 +1,19 @@
-use encodetalker_common::protocol::messages::{DepsCompilationStep, DepsStatusInfo};
-use encodetalker_common::{EncodingConfig, EncodingJob};
+use super::PinnedDirs;
+use crate::ui::GraphicsProtocol;
+use encodetalker_common::protocol::messages::{
+    BuildEnvReport, DepsCompilationStep, DepsStatusInfo,
+};
+use encodetalker_common::{
+    available_space, crf_for_height, dimensions_warning, format_language_selection,
+    parse_language_selection, parse_timestamp, validate_language_codes, AudioBitratePolicy,
+    AudioMode, BenchmarkReport, CleanupResult, CleanupTarget, EncoderCapabilities, EncoderParams,
+    EncoderType, EncodingConfig, EncodingJob, OutputNamer, PresetBenchmark, ResolutionCrf,
+    SpeedSummary, TrimRange, Tune, UnitPreferences, VideoMode, DEFAULT_VAAPI_DEVICE,
+};
 use ratatui::prelude::Rect;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Rectangles de layout calculés pendant le rendu (pour le hit-testing souris)
@@ -12,6 +23,8 @@ pub struct LayoutRects {
     pub content: Rect,
     pub content_inner: Rect,
     pub dialog_area: Option<Rect>,
+    /// Zone de la miniature dans l'explorateur (None = non affichée)
+    pub preview: Option<Rect>,
 }
 
 /// Dernier clic (pour détecter le double-clic)
@@ -105,6 +118,7 @@ pub enum View {
     Queue,
     Active,
     History,
+    Stats,
 }
 
 impl View {
@@ -115,7 +129,8 @@ impl View {
             View::FileBrowser => View::Queue,
             View::Queue => View::Active,
             View::Active => View::History,
-            View::History => View::FileBrowser,
+            View::History => View::Stats,
+            View::Stats => View::FileBrowser,
         }
     }
 
@@ -123,10 +138,11 @@ impl View {
     pub fn prev(&self) -> Self {
         match self {
             View::Loading => View::Loading, // Bloquer navigation depuis Loading
-            View::FileBrowser => View::History,
+            View::FileBrowser => View::Stats,
             View::Queue => View::FileBrowser,
             View::Active => View::Queue,
             View::History => View::Active,
+            View::Stats => View::History,
         }
     }
 
@@ -138,6 +154,7 @@ impl View {
             View::Queue => "Queue",
             View::Active => "Encodage en cours",
             View::History => "Historique",
+            View::Stats => "Statistiques",
         }
     }
 }
@@ -155,6 +172,16 @@ pub struct LoadingState {
     pub current_step: Option<DepsCompilationStep>,
     /// Erreur de compilation
     pub error: Option<String>,
+    /// Diagnostic de l'environnement de compilation (avant de lancer la compilation)
+    pub build_env: Option<BuildEnvReport>,
+    /// Afficher la sortie des compilateurs (abonnement auprès du daemon)
+    pub show_logs: bool,
+    /// Dernières lignes de sortie des compilateurs
+    pub log_lines: VecDeque<String>,
+    /// Reprise d'une compilation interrompue
+    pub resumed: bool,
+    /// Dépendances compilées, dans l'ordre (vide = toutes)
+    pub deps: Vec<String>,
 }
 
 impl LoadingState {
@@ -167,6 +194,11 @@ impl LoadingState {
             current_dep: None,
             current_step: None,
             error: None,
+            build_env: None,
+            show_logs: false,
+            log_lines: VecDeque::new(),
+            resumed: false,
+            deps: Vec::new(),
         }
     }
 
@@ -178,8 +210,25 @@ impl LoadingState {
             completed_deps: status.completed_count,
             current_dep: status.current_dep,
             current_step: status.current_step,
-            error: None,
+            resumed: status.resumed,
+            deps: status.deps,
+            error: status.error,
+            ..Self::new()
+        }
+    }
+
+    /// Lignes de sortie gardées pour le panneau de logs
+    pub const MAX_LOG_LINES: usize = 500;
+
+    /// Ajouter un lot de lignes de sortie des compilateurs
+    pub fn push_log_lines(&mut self, lines: Vec<String>, skipped: usize) {
+        if skipped > 0 {
+            self.log_lines
+                .push_back(format!("… {skipped} lignes non affichées"));
         }
+        self.log_lines.extend(lines);
+        let excess = self.log_lines.len().saturating_sub(Self::MAX_LOG_LINES);
+        self.log_lines.drain(..excess);
     }
 
     /// Calculer le pourcentage de progression
@@ -246,6 +295,106 @@ pub struct AppState {
     pub layout: LayoutRects,
     /// Dernier clic (pour détection double-clic)
     pub last_click: Option<LastClick>,
+    /// Capacités des encodeurs (None tant que non récupérées)
+    pub encoder_capabilities: Option<Arc<EncoderCapabilities>>,
+    /// Fichier d'analytique écrit par le daemon (JSONL)
+    pub analytics_file: Option<PathBuf>,
+    /// Fichier de log du daemon
+    pub log_file: Option<PathBuf>,
+    /// Vitesse moyenne par encodeur/preset (vue Statistiques)
+    pub speed_summary: Vec<SpeedSummary>,
+    /// Relecture de l'analytique demandée, faite en arrière-plan par la boucle principale
+    pub analytics_reload: bool,
+    /// Nommage des sorties (`output_template`, `output_dir`, `on_collision`)
+    pub output_namer: OutputNamer,
+    /// Configuration initiale des nouveaux jobs (sections [encoding] et [encoder])
+    pub default_config: EncodingConfig,
+    /// Paramètres par défaut de chaque encodeur, appliqués quand on change d'encodeur
+    pub encoder_defaults: HashMap<EncoderType, EncoderParams>,
+    /// Périphérique VA-API configuré (`encoder.hardware.vaapi_device`), pour la preview
+    pub vaapi_device: PathBuf,
+    /// CRF initial selon la hauteur de la source (`[[encoding.crf_by_resolution]]`)
+    pub crf_policy: Vec<ResolutionCrf>,
+    /// Débit Opus initial selon les canaux de la source (None = débit de la configuration)
+    pub audio_bitrate_policy: Option<AudioBitratePolicy>,
+    /// Protocole graphique du terminal (None = pas de miniatures)
+    pub graphics: Option<GraphicsProtocol>,
+    /// Nombre de jobs encodés en parallèle (`daemon.max_concurrent_jobs`)
+    pub max_concurrent_jobs: usize,
+    /// Miniatures des vidéos de l'explorateur, récupérées en arrière-plan
+    pub thumbnails: ThumbnailCache,
+    /// Répertoires épinglés de l'explorateur (raccourcis `1` à `9`, `[ui] pinned_dirs`)
+    pub pinned_dirs: PinnedDirs,
+    /// Le daemon accepte-t-il de nouveaux jobs (faux pendant son arrêt)
+    pub daemon_accepting_jobs: bool,
+    /// Durée des extraits de test lancés depuis le dialogue (`encoding.preview_duration_secs`)
+    pub preview_duration_secs: u32,
+    /// Unités des tailles et débits affichés (`[ui.units]`)
+    pub units: UnitPreferences,
+    /// Pistes listées dans le dialogue d'encodage avant défilement (`ui.track_list_rows`)
+    pub track_list_rows: usize,
+    /// Job de la queue saisi pour être déplacé (None = pas de déplacement en cours)
+    pub grabbed_job: Option<GrabbedJob>,
+}
+
+/// Job de la queue en cours de déplacement
+///
+/// La liste locale montre le job à sa position visée ; le daemon n'est prévenu qu'au
+/// dépôt du job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrabbedJob {
+    pub job_id: uuid::Uuid,
+    /// Position avant la saisie, rétablie si le déplacement est annulé
+    pub origin: usize,
+    /// Position visée dans la queue
+    pub target: usize,
+}
+
+/// Nombre de miniatures gardées en mémoire (les plus anciennes sont oubliées)
+const THUMBNAIL_CACHE_SIZE: usize = 32;
+
+/// Miniatures des vidéos de l'explorateur
+///
+/// Les demandes au daemon tournent en arrière-plan : une vidéo demandée n'est pas
+/// redemandée tant que sa réponse n'est pas arrivée.
+#[derive(Debug, Clone, Default)]
+pub struct ThumbnailCache {
+    /// PNG par vidéo (None = extraction impossible), de la plus ancienne à la plus récente
+    entries: VecDeque<(PathBuf, Option<Vec<u8>>)>,
+    /// Vidéos dont la miniature est en cours de récupération
+    requested: HashSet<PathBuf>,
+}
+
+impl ThumbnailCache {
+    /// La miniature est-elle connue ou déjà demandée ?
+    #[must_use]
+    pub fn is_known(&self, path: &Path) -> bool {
+        self.requested.contains(path) || self.entries.iter().any(|(p, _)| p == path)
+    }
+
+    /// PNG de la vidéo (None = inconnu ou extraction impossible)
+    #[must_use]
+    pub fn png(&self, path: &Path) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|(p, _)| p == path)
+            .and_then(|(_, png)| png.as_deref())
+    }
+
+    /// Noter qu'une demande est partie pour cette vidéo
+    pub fn mark_requested(&mut self, path: PathBuf) {
+        self.requested.insert(path);
+    }
+
+    /// Ranger la réponse du daemon, en oubliant la plus ancienne si le cache est plein
+    pub fn insert(&mut self, path: PathBuf, png: Option<Vec<u8>>) {
+        self.requested.remove(&path);
+        self.entries.retain(|(p, _)| *p != path);
+        if self.entries.len() >= THUMBNAIL_CACHE_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((path, png));
+    }
 }
 
 impl AppState {
@@ -264,13 +413,194 @@ impl AppState {
             status_message: None,
             layout: LayoutRects::default(),
             last_click: None,
+            encoder_capabilities: None,
+            analytics_file: None,
+            log_file: None,
+            speed_summary: Vec::new(),
+            analytics_reload: false,
+            output_namer: OutputNamer::default(),
+            default_config: EncodingConfig::default(),
+            encoder_defaults: HashMap::new(),
+            vaapi_device: PathBuf::from(DEFAULT_VAAPI_DEVICE),
+            crf_policy: Vec::new(),
+            audio_bitrate_policy: None,
+            max_concurrent_jobs: 1,
+            graphics: None,
+            thumbnails: ThumbnailCache::default(),
+            pinned_dirs: PinnedDirs::default(),
+            daemon_accepting_jobs: true,
+            preview_duration_secs: 30,
+            units: UnitPreferences::default(),
+            track_list_rows: 5,
+            grabbed_job: None,
+        }
+    }
+
+    /// Vidéo sélectionnée dont la miniature reste à demander, avec la position voulue
+    ///
+    /// La frame est prise à 10 % de la durée pour éviter un écran noir d'ouverture ;
+    /// tant que la durée n'est pas probée, la demande attend.
+    #[must_use]
+    pub fn pending_thumbnail(&self) -> Option<(PathBuf, f64)> {
+        if self.graphics.is_none() || self.current_view != View::FileBrowser {
+            return None;
+        }
+        let entry = self.file_browser.get_selected(self.selected_index)?;
+        if !entry.is_video
+            || self.thumbnails.is_known(&entry.path)
+            || self.file_browser.pending_probes.contains(&entry.path)
+        {
+            return None;
+        }
+        let timestamp = entry.duration_secs.map_or(0.0, |duration| duration * 0.1);
+        Some((entry.path.clone(), timestamp))
+    }
+
+    /// Miniature à afficher : celle de la vidéo sélectionnée, hors dialogue
+    #[must_use]
+    pub fn visible_thumbnail(&self) -> Option<(Rect, &Path, &[u8])> {
+        if self.dialog.is_some() || self.current_view != View::FileBrowser {
+            return None;
+        }
+        let area = self.layout.preview?;
+        let entry = self.file_browser.get_selected(self.selected_index)?;
+        Some((area, &entry.path, self.thumbnails.png(&entry.path)?))
+    }
+
+    /// Ouvrir le dialogue de configuration pour un ou plusieurs fichiers
+    #[must_use]
+    pub fn new_encode_dialog(&self, input_paths: Vec<PathBuf>) -> Dialog {
+        let mut dialog = EncodeConfigDialog::new_batch(input_paths);
+        dialog.config = self.default_config.clone();
+        dialog.encoder_defaults = self.encoder_defaults.clone();
+        dialog.vaapi_device.clone_from(&self.vaapi_device);
+        // Après la configuration par défaut : l'encodeur configuré peut être absent
+        let mut dialog = dialog.with_capabilities(self.encoder_capabilities.clone());
+        dialog.tracks.visible_rows = self.track_list_rows;
+        let heights = dialog
+            .input_paths
+            .iter()
+            .filter_map(|path| Some((path.clone(), self.file_browser.video_height(path)?)))
+            .collect();
+        dialog.apply_crf_policy(self.crf_policy.clone(), heights);
+        // Débit Opus selon les canaux de la première source (modifiable ensuite)
+        let channels = dialog
+            .input_paths
+            .first()
+            .and_then(|path| self.file_browser.audio_channels(path));
+        if let (Some(policy), Some(channels), AudioMode::Opus { bitrate }) = (
+            self.audio_bitrate_policy,
+            channels,
+            &mut dialog.config.audio_mode,
+        ) {
+            *bitrate = policy.bitrate_for(channels);
+        }
+        // Après la configuration : le nom peut dépendre de l'encodeur et du CRF
+        let dialog = dialog.with_output_namer(self.output_namer.clone());
+        Dialog::EncodeConfig(Box::new(dialog))
+    }
+
+    /// Jobs dont le nom de fichier (source ou sortie) contient `query`, sans tenir compte
+    /// de la casse : queue, puis jobs actifs, puis historique
+    #[must_use]
+    pub fn search_jobs(&self, query: &str) -> Vec<SearchHit> {
+        let query = query.trim();
+        let lists = [
+            (View::Queue, &self.queue_jobs),
+            (View::Active, &self.active_jobs),
+            (View::History, &self.history_jobs),
+        ];
+        let mut hits = Vec::new();
+        for (view, jobs) in lists {
+            for (index, job) in jobs.iter().enumerate() {
+                let hit = [&job.input_path, &job.output_path]
+                    .into_iter()
+                    .filter_map(|path| path.file_name())
+                    .find_map(|name| {
+                        let name = name.to_string_lossy();
+                        let matched = find_ignore_case(&name, query)?;
+                        Some((name.into_owned(), matched))
+                    });
+                if let Some((file_name, matched)) = hit {
+                    hits.push(SearchHit {
+                        view,
+                        index,
+                        file_name,
+                        matched,
+                    });
+                }
+            }
         }
+        hits
+    }
+
+    /// Aller au job trouvé dans sa vue
+    pub fn jump_to_search_hit(&mut self, hit: &SearchHit) {
+        self.dialog = None;
+        self.switch_view(hit.view);
+        self.selected_index = hit.index;
     }
 
     /// Changer de vue
     pub fn switch_view(&mut self, view: View) {
+        self.cancel_grab();
         self.current_view = view;
         self.selected_index = 0;
+        if view == View::Stats {
+            self.reload_analytics();
+        }
+    }
+
+    /// Demander la relecture du fichier d'analytique et le recalcul des moyennes
+    ///
+    /// Le fichier peut être gros : il est lu hors du thread de l'interface, voir
+    /// [`Self::take_analytics_reload`].
+    pub fn reload_analytics(&mut self) {
+        self.analytics_reload = true;
+    }
+
+    /// Fichier d'analytique à relire, si une relecture a été demandée depuis le dernier appel
+    pub fn take_analytics_reload(&mut self) -> Option<PathBuf> {
+        if std::mem::take(&mut self.analytics_reload) {
+            self.analytics_file.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Épingler le répertoire courant de l'explorateur, ou le retirer s'il l'est déjà
+    ///
+    /// Retourne `true` si les épingles ont changé (à sauvegarder).
+    pub fn toggle_pin_current_dir(&mut self) -> bool {
+        let dir = self.file_browser.current_dir.clone();
+        let message = match self.pinned_dirs.toggle(&dir) {
+            Ok(true) => format!(
+                "{} épinglé (touche {})",
+                dir.display(),
+                self.pinned_dirs.dirs().len()
+            ),
+            Ok(false) => format!("{} retiré des épingles", dir.display()),
+            Err(e) => {
+                self.set_status(e);
+                return false;
+            }
+        };
+        self.set_status(message);
+        true
+    }
+
+    /// Aller au répertoire épinglé `number` (1 à 9)
+    pub fn jump_to_pinned(&mut self, number: usize) {
+        let Some(dir) = self.pinned_dirs.get(number).map(Path::to_path_buf) else {
+            self.set_status(format!("Aucun répertoire épinglé sur la touche {number}"));
+            return;
+        };
+        if dir.is_dir() {
+            self.file_browser.navigate_to(dir);
+            self.selected_index = 0;
+        } else {
+            self.set_status(format!("{} introuvable", dir.display()));
+        }
     }
 
     /// Naviguer vers le haut dans la liste
@@ -288,6 +618,88 @@ impl AppState {
         }
     }
 
+    /// Saisir le job sélectionné de la queue pour le déplacer
+    pub fn grab_selected_job(&mut self) {
+        if let Some(job) = self.queue_jobs.get(self.selected_index) {
+            self.grabbed_job = Some(GrabbedJob {
+                job_id: job.id,
+                origin: self.selected_index,
+                target: self.selected_index,
+            });
+        }
+    }
+
+    /// Déplacer le job saisi d'un cran vers le haut (`up`) ou vers le bas
+    pub fn move_grabbed_job(&mut self, up: bool) {
+        let last = self.queue_jobs.len().saturating_sub(1);
+        if let Some(grab) = &mut self.grabbed_job {
+            grab.target = if up {
+                grab.target.saturating_sub(1)
+            } else {
+                (grab.target + 1).min(last)
+            };
+        }
+        self.sync_grab_preview();
+    }
+
+    /// Déposer le job saisi : job et position à envoyer au daemon (None s'il n'a pas bougé)
+    pub fn drop_grabbed_job(&mut self) -> Option<(uuid::Uuid, usize)> {
+        self.sync_grab_preview();
+        let grab = self.grabbed_job.take()?;
+        (grab.target != grab.origin).then_some((grab.job_id, grab.target))
+    }
+
+    /// Annuler le déplacement en cours et remettre le job à sa position d'origine
+    pub fn cancel_grab(&mut self) {
+        if let Some(grab) = &mut self.grabbed_job {
+            grab.target = grab.origin;
+        }
+        self.sync_grab_preview();
+        self.grabbed_job = None;
+    }
+
+    /// Montrer le job saisi à sa position visée
+    ///
+    /// À rappeler après chaque rechargement de la queue, qui la remet dans l'ordre du
+    /// daemon. Le déplacement s'arrête si le job a quitté la queue (démarré, annulé).
+    pub fn sync_grab_preview(&mut self) {
+        let Some(grab) = &mut self.grabbed_job else {
+            return;
+        };
+        let Some(from) = self.queue_jobs.iter().position(|job| job.id == grab.job_id) else {
+            self.grabbed_job = None;
+            self.set_status("Le job déplacé a quitté la queue");
+            return;
+        };
+        let job = self.queue_jobs.remove(from);
+        grab.target = grab.target.min(self.queue_jobs.len());
+        self.queue_jobs.insert(grab.target, job);
+        if self.current_view == View::Queue {
+            self.selected_index = grab.target;
+        }
+    }
+
+    /// Retirer un job de l'historique local (supprimé côté daemon)
+    pub fn remove_history_job(&mut self, job_id: uuid::Uuid) {
+        self.history_jobs.retain(|job| job.id != job_id);
+        self.clamp_history_selection();
+    }
+
+    /// Vider l'historique local (vidé côté daemon)
+    pub fn clear_history_jobs(&mut self) {
+        self.history_jobs.clear();
+        self.clamp_history_selection();
+    }
+
+    /// Garder la sélection de la vue Historique dans la liste
+    fn clamp_history_selection(&mut self) {
+        if self.current_view == View::History {
+            self.selected_index = self
+                .selected_index
+                .min(self.history_jobs.len().saturating_sub(1));
+        }
+    }
+
     /// Obtenir la longueur de la liste active
     #[must_use]
     pub fn get_current_list_len(&self) -> usize {
@@ -297,9 +709,52 @@ impl AppState {
             View::Queue => self.queue_jobs.len(),
             View::Active => self.active_jobs.len(),
             View::History => self.history_jobs.len(),
+            View::Stats => self.speed_summary.len(),
         }
     }
 
+    /// Ouvrir le fichier de log du daemon avec l'application par défaut
+    ///
+    /// Le chemin est affiché dans la barre de status, même si l'ouverture échoue.
+    pub fn open_log_file(&mut self) {
+        let Some(path) = self.log_file.clone() else {
+            self.set_status("Fichier de log inconnu");
+            return;
+        };
+
+        #[cfg(target_os = "macos")]
+        let opener = "open";
+        #[cfg(windows)]
+        let opener = "explorer";
+        #[cfg(not(any(target_os = "macos", windows)))]
+        let opener = "xdg-open";
+
+        let spawned = std::process::Command::new(opener)
+            .arg(&path)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(_) => self.set_status(format!("Log du daemon: {}", path.display())),
+            Err(e) => self.set_status(format!(
+                "Log du daemon: {} (ouverture impossible: {e})",
+                path.display()
+            )),
+        }
+    }
+
+    /// Refuser un ajout de job pendant l'arrêt du daemon, avec un message (vrai = refusé)
+    pub fn refuse_new_jobs(&mut self) -> bool {
+        if self.daemon_accepting_jobs {
+            return false;
+        }
+        self.dialog = Some(Dialog::Error {
+            message: "Le daemon est en cours d'arrêt : il termine ses jobs actifs et n'accepte plus de nouveaux jobs".to_string(),
+        });
+        true
+    }
+
     /// Définir un message de status
     pub fn set_status(&mut self, message: impl Into<String>) {
         self.status_message = Some(message.into());
@@ -354,6 +809,8 @@ impl FileBrowserState {
                 is_video: false,
                 size_bytes: None,
                 duration_secs: None,
+                height: None,
+                audio_channels: None,
             });
         }
 
@@ -388,6 +845,8 @@ impl FileBrowserState {
                         is_video,
                         size_bytes,
                         duration_secs: None, // Sera rempli via IPC
+                        height: None,
+                        audio_channels: None,
                     })
                 })
                 .collect();
@@ -460,13 +919,39 @@ impl FileBrowserState {
     }
 
     /// Mettre à jour les informations d'une vidéo (appelé quand le daemon répond)
-    pub fn update_video_info(&mut self, path: &Path, duration: Option<f64>) {
+    pub fn update_video_info(
+        &mut self,
+        path: &Path,
+        duration: Option<f64>,
+        height: Option<u32>,
+        audio_channels: Option<u32>,
+    ) {
         if let Some(entry) = self.entries.iter_mut().find(|e| e.path == *path) {
             entry.duration_secs = duration;
+            entry.height = height;
+            entry.audio_channels = audio_channels;
             self.pending_probes.remove(path);
         }
     }
 
+    /// Hauteur d'une vidéo du répertoire courant (None = pas encore probée)
+    #[must_use]
+    pub fn video_height(&self, path: &Path) -> Option<u32> {
+        self.entries
+            .iter()
+            .find(|e| e.path == *path)
+            .and_then(|e| e.height)
+    }
+
+    /// Canaux audio d'une vidéo du répertoire courant (None = inconnus)
+    #[must_use]
+    pub fn audio_channels(&self, path: &Path) -> Option<u32> {
+        self.entries
+            .iter()
+            .find(|e| e.path == *path)
+            .and_then(|e| e.audio_channels)
+    }
+
     /// Obtenir la liste des fichiers vidéo en attente de probe
     #[must_use]
     pub fn get_pending_probes(&self) -> Vec<PathBuf> {
@@ -483,12 +968,25 @@ pub struct DirEntry {
     pub is_video: bool,
     pub size_bytes: Option<u64>,
     pub duration_secs: Option<f64>,
+    /// Hauteur du stream vidéo (remplie via IPC)
+    pub height: Option<u32>,
+    /// Canaux de la première piste audio (remplis via IPC)
+    pub audio_channels: Option<u32>,
 }
 
 /// Vérifier si un fichier est une vidéo
 fn is_video_file(path: &Path) -> bool {
     const VIDEO_EXTENSIONS: &[&str] = &[
-        ".mp4", ".mkv", ".avi", ".mov", ".webm", ".flv", ".wmv", ".m4v", ".m2ts",
+        ".mp4",
+        ".mkv",
+        ".avi",
+        ".mov",
+        ".webm",
+        ".flv",
+        ".wmv",
+        ".m4v",
+        ".m2ts",
+        ".ffconcat",
     ];
 
     if let Some(ext) = path.extension() {
@@ -519,7 +1017,7 @@ pub fn format_duration(seconds: f64) -> String {
 #[derive(Debug, Clone)]
 pub enum Dialog {
     /// Dialogue de configuration d'encodage
-    EncodeConfig(EncodeConfigDialog),
+    EncodeConfig(Box<EncodeConfigDialog>),
     /// Dialogue de confirmation
     Confirm {
         message: String,
@@ -535,15 +1033,158 @@ pub enum Dialog {
         output: String,
         scroll_offset: usize,
     },
+    /// Benchmark des presets (progression puis recommandation)
+    Benchmark(BenchmarkDialog),
+    /// Détail d'un job de la queue ou de l'historique (lecture seule)
+    JobDetail {
+        job: Box<EncodingJob>,
+        scroll_offset: usize,
+    },
+    /// Maintenance : nettoyage des sources, fichiers temporaires, logs et miniatures
+    Maintenance(MaintenanceDialog),
+    /// Recherche d'un job par nom de fichier dans la queue, les jobs actifs et l'historique
+    Search(SearchDialog),
+}
+
+/// Saisie de la recherche globale
+#[derive(Debug, Clone, Default)]
+pub struct SearchDialog {
+    pub query: String,
+    pub cursor: usize,
+    /// Résultat sélectionné (index dans [`AppState::search_jobs`])
+    pub selected: usize,
+}
+
+impl SearchDialog {
+    pub fn move_selection(&mut self, down: bool, hit_count: usize) {
+        self.selected = if down {
+            (self.selected + 1).min(hit_count.saturating_sub(1))
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+}
+
+/// Job trouvé par la recherche globale
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    /// Vue listant le job (Queue, Active ou History)
+    pub view: View,
+    /// Position du job dans la liste de cette vue
+    pub index: usize,
+    /// Nom de fichier correspondant (source, sinon sortie)
+    pub file_name: String,
+    /// Partie correspondante de `file_name` (octets), surlignée à l'affichage
+    pub matched: std::ops::Range<usize>,
+}
+
+/// Position de `needle` dans `haystack` sans tenir compte de la casse (None = absent ou vide)
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<std::ops::Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut rest = haystack[start..].chars();
+        let mut end = start;
+        for wanted in needle.chars() {
+            let c = rest.next()?;
+            if !c.to_lowercase().eq(wanted.to_lowercase()) {
+                return None;
+            }
+            end += c.len_utf8();
+        }
+        Some(start..end)
+    })
+}
+
+/// Suivi d'un benchmark des presets lancé sur le daemon
+#[derive(Debug, Clone)]
+pub struct BenchmarkDialog {
+    pub input_path: PathBuf,
+    pub encoder: EncoderType,
+    /// Presets déjà mesurés, du plus rapide au plus lent
+    pub results: Vec<PresetBenchmark>,
+    /// Rapport final (None = benchmark en cours)
+    pub report: Option<BenchmarkReport>,
+    pub error: Option<String>,
+}
+
+impl BenchmarkDialog {
+    #[must_use]
+    pub fn new(input_path: PathBuf, encoder: EncoderType) -> Self {
+        Self {
+            input_path,
+            encoder,
+            results: Vec::new(),
+            report: None,
+            error: None,
+        }
+    }
+}
+
+/// Cibles de nettoyage cochées, avec l'espace libérable de chacune
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceDialog {
+    /// Ligne sélectionnée (index dans `CleanupTarget::ALL`)
+    pub selected: usize,
+    pub checked: HashSet<CleanupTarget>,
+    /// Estimation du daemon, une entrée par cible (None = en cours)
+    pub estimates: Option<Vec<CleanupResult>>,
+    /// Confirmation demandée avant de supprimer les cibles cochées
+    pub confirming: bool,
+    /// Résultat du dernier nettoyage
+    pub report: Option<Vec<CleanupResult>>,
+    pub error: Option<String>,
+}
+
+impl MaintenanceDialog {
+    pub fn move_selection(&mut self, down: bool) {
+        let last = CleanupTarget::ALL.len() - 1;
+        self.selected = if down {
+            (self.selected + 1).min(last)
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+
+    /// Cocher ou décocher la cible sélectionnée
+    pub fn toggle_selected(&mut self) {
+        let target = CleanupTarget::ALL[self.selected];
+        if !self.checked.remove(&target) {
+            self.checked.insert(target);
+        }
+    }
+
+    /// Cibles cochées, dans l'ordre d'affichage
+    #[must_use]
+    pub fn targets(&self) -> Vec<CleanupTarget> {
+        CleanupTarget::ALL
+            .into_iter()
+            .filter(|target| self.checked.contains(target))
+            .collect()
+    }
+
+    /// Estimation d'une cible (None tant que le daemon n'a pas répondu)
+    #[must_use]
+    pub fn estimate(&self, target: CleanupTarget) -> Option<&CleanupResult> {
+        self.estimates
+            .as_ref()?
+            .iter()
+            .find(|result| result.target == target)
+    }
 }
 
 /// Actions de confirmation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfirmAction {
     CancelJob,
+    /// Annuler tous les jobs du lot du job sélectionné
+    CancelBatch,
     RemoveFromHistory,
     ClearHistory,
     Quit,
+    /// Redémarrer le daemon malgré les jobs actifs
+    RestartDaemon,
 }
 
 /// Dialogue de configuration d'encodage
@@ -552,23 +1193,140 @@ pub struct EncodeConfigDialog {
     /// Chemins d'entrée (1 si single, N si batch)
     pub input_paths: Vec<PathBuf>,
     pub output_path: PathBuf,
+    /// Chemin saisi par l'utilisateur (sinon le chemin par défaut suit la configuration)
+    pub output_path_custom: bool,
+    /// Nommage des sorties (modèle, répertoire par défaut)
+    pub output_namer: OutputNamer,
     pub output_path_string: String,
     pub output_path_cursor: usize,
     pub is_editing_output: bool,
     pub config: EncodingConfig,
     pub selected_field: usize,
+    /// Saisie du début de l'extrait (vide = début du fichier)
+    pub trim_start_string: String,
+    /// Saisie de la fin de l'extrait (vide = fin du fichier)
+    pub trim_end_string: String,
     /// Résultat de la détection d'interlacing (None = pas encore détecté)
     pub is_interlaced: Option<bool>,
+    /// Dimensions de la première source (None = inconnues)
+    pub source_dimensions: Option<(u32, u32)>,
+    /// Avertissement de dimensions déjà affiché : Entrée ajoute quand même le job
+    pub dimensions_acknowledged: bool,
+    /// Capacités des encodeurs (None = inconnues, tout est autorisé)
+    pub capabilities: Option<Arc<EncoderCapabilities>>,
+    /// Paramètres par défaut de chaque encodeur (vide = conserver les réglages courants)
+    pub encoder_defaults: HashMap<EncoderType, EncoderParams>,
+    /// Périphérique VA-API affiché dans la preview des commandes
+    pub vaapi_device: PathBuf,
+    /// CRF selon la hauteur de la source (vide = CRF de l'encodeur)
+    pub crf_policy: Vec<ResolutionCrf>,
+    /// Hauteur connue de chaque source
+    pub source_heights: HashMap<PathBuf, u32>,
+    /// CRF de l'encodeur hors politique, pour les sources sans règle ni hauteur connue
+    pub base_crf: u32,
+    /// CRF modifié à la main : il s'applique alors à toutes les sources
+    pub crf_edited: bool,
+    /// Saisie des paramètres extra de l'encodeur (séparés par des espaces)
+    pub extra_params_string: String,
+    pub extra_params_cursor: usize,
+    pub is_editing_extra: bool,
+    /// Paramètres extra avant l'édition (restaurés par Esc)
+    pub extra_params_saved: Vec<String>,
+    /// Saisie des langues gardées : `audio / sous-titres` (ex. `eng,jpn / eng`)
+    pub languages_string: String,
+    pub languages_cursor: usize,
+    pub is_editing_languages: bool,
+    /// Langues audio et sous-titres avant l'édition (restaurées par Esc)
+    pub languages_saved: (Vec<String>, Vec<String>),
+    /// Pistes de la première source (vides si le probe a échoué)
+    pub tracks: TrackLists,
+    /// Espace libre sur la destination (None = inconnu)
+    pub free_space: Option<u64>,
+    /// Répertoire de destination pour lequel `free_space` a été mesuré
+    pub free_space_dir: Option<PathBuf>,
+}
+
+/// Piste audio ou de sous-titres de la première source
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceTrack {
+    pub language: Option<String>,
+    pub codec: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Pistes de la première source listées sous le champ des langues, avec leur défilement
+#[derive(Debug, Clone, Default)]
+pub struct TrackLists {
+    pub audio: Vec<SourceTrack>,
+    pub subtitles: Vec<SourceTrack>,
+    /// Pistes affichées par liste avant défilement (`ui.track_list_rows`)
+    pub visible_rows: usize,
+    /// Première piste affichée, commune aux deux listes
+    pub scroll_offset: usize,
 }
 
-/// Détection synchrone de l'interlacing
-fn detect_interlacing_sync(video_path: &Path) -> bool {
+impl TrackLists {
+    /// Aucune piste audio ni de sous-titres connue
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.audio.is_empty() && self.subtitles.is_empty()
+    }
+
+    /// Pistes affichées d'une liste (au moins une ligne)
+    #[must_use]
+    pub fn visible<'a>(&self, tracks: &'a [SourceTrack]) -> &'a [SourceTrack] {
+        let start = self.scroll_offset.min(tracks.len());
+        let end = (start + self.visible_rows.max(1)).min(tracks.len());
+        &tracks[start..end]
+    }
+
+    /// Défiler d'une page, sans dépasser la fin de la plus longue liste
+    pub fn scroll_page(&mut self, down: bool) {
+        let page = self.visible_rows.max(1);
+        if down {
+            let longest = self.audio.len().max(self.subtitles.len());
+            let max_scroll = longest.saturating_sub(page);
+            self.scroll_offset = (self.scroll_offset + page).min(max_scroll);
+        } else {
+            self.scroll_offset = self.scroll_offset.saturating_sub(page);
+        }
+    }
+
+    /// Langue de chaque piste, pour résoudre les langues gardées
+    #[must_use]
+    pub fn languages(tracks: &[SourceTrack]) -> Vec<Option<String>> {
+        tracks.iter().map(|track| track.language.clone()).collect()
+    }
+}
+
+/// Résultat du probe synchrone de la première source
+#[derive(Debug, Default)]
+struct SourceProbe {
+    interlaced: bool,
+    dimensions: Option<(u32, u32)>,
+    audio: Vec<SourceTrack>,
+    subtitles: Vec<SourceTrack>,
+}
+
+/// Détection synchrone de l'interlacing, des dimensions du premier stream vidéo et des
+/// pistes audio / sous-titres
+fn probe_source_sync(video_path: &Path) -> SourceProbe {
     use serde::Deserialize;
 
+    #[derive(Deserialize)]
+    struct Tags {
+        language: Option<String>,
+        title: Option<String>,
+    }
+
     #[derive(Deserialize)]
     struct Stream {
         field_order: Option<String>,
         codec_type: Option<String>,
+        codec_name: Option<String>,
+        width: Option<u32>,
+        height: Option<u32>,
+        tags: Option<Tags>,
     }
 
     #[derive(Deserialize)]
@@ -583,7 +1341,7 @@ fn detect_interlacing_sync(video_path: &Path) -> bool {
 
     // Si ffprobe n'existe pas, on assume non-interlacé
     if !ffprobe_bin.exists() {
-        return false;
+        return SourceProbe::default();
     }
 
     let output = std::process::Command::new(&ffprobe_bin)
@@ -592,26 +1350,51 @@ fn detect_interlacing_sync(video_path: &Path) -> bool {
         .arg("-print_format")
         .arg("json")
         .arg("-show_streams")
-        .arg("-select_streams")
-        .arg("v:0")
         .arg(video_path)
         .output();
 
-    let Ok(output) = output else { return false };
+    let Ok(output) = output else {
+        return SourceProbe::default();
+    };
 
     let Ok(probe): Result<Probe, _> = serde_json::from_slice(&output.stdout) else {
-        return false;
+        return SourceProbe::default();
     };
 
-    probe
-        .streams
-        .iter()
-        .find(|s| s.codec_type.as_deref() == Some("video"))
-        .and_then(|s| s.field_order.as_ref())
-        .is_some_and(|fo| matches!(fo.as_str(), "tt" | "bb" | "tb" | "bt"))
+    let mut result = SourceProbe::default();
+    let mut video_seen = false;
+    for stream in probe.streams {
+        let track = || SourceTrack {
+            language: stream.tags.as_ref().and_then(|t| t.language.clone()),
+            codec: stream.codec_name.clone(),
+            title: stream.tags.as_ref().and_then(|t| t.title.clone()),
+        };
+        match stream.codec_type.as_deref() {
+            Some("audio") => result.audio.push(track()),
+            Some("subtitle") => result.subtitles.push(track()),
+            // Premier stream vidéo seulement
+            Some("video") if !video_seen => {
+                video_seen = true;
+                result.interlaced = stream
+                    .field_order
+                    .as_ref()
+                    .is_some_and(|fo| matches!(fo.as_str(), "tt" | "bb" | "tb" | "bt"));
+                result.dimensions = stream.width.zip(stream.height);
+            }
+            _ => {}
+        }
+    }
+    result
 }
 
 impl EncodeConfigDialog {
+    /// Index du champ des paramètres extra de l'encodeur
+    pub const EXTRA_FIELD: usize = 12;
+    /// Index du champ des langues des pistes gardées
+    pub const LANGUAGES_FIELD: usize = 13;
+    /// Index du champ chemin de sortie (dernier champ)
+    pub const OUTPUT_FIELD: usize = 14;
+
     /// Créer dialogue pour un fichier unique
     #[must_use]
     pub fn new(input_path: PathBuf) -> Self {
@@ -621,32 +1404,357 @@ impl EncodeConfigDialog {
     /// Créer dialogue pour plusieurs fichiers
     #[must_use]
     pub fn new_batch(input_paths: Vec<PathBuf>) -> Self {
-        let output_path = if input_paths.len() == 1 {
-            let mut out = input_paths[0].clone();
-            out.set_extension("");
-            PathBuf::from(format!("{}.av1.mkv", out.display()))
-        } else {
-            PathBuf::from("<auto-generated>")
-        };
-
-        let output_path_string = output_path.display().to_string();
-
-        // Détection synchrone de l'interlacing sur le premier fichier
-        let is_interlaced = if input_paths.is_empty() {
-            None
-        } else {
-            Some(detect_interlacing_sync(&input_paths[0]))
+        // Détection synchrone de l'interlacing, des dimensions et des pistes sur le premier
+        // fichier
+        let (is_interlaced, source_dimensions, tracks) = match input_paths.first() {
+            Some(first) => {
+                let probe = probe_source_sync(first);
+                let tracks = TrackLists {
+                    audio: probe.audio,
+                    subtitles: probe.subtitles,
+                    visible_rows: 5,
+                    scroll_offset: 0,
+                };
+                (Some(probe.interlaced), probe.dimensions, tracks)
+            }
+            None => (None, None, TrackLists::default()),
         };
 
         Self {
             input_paths,
-            output_path,
-            output_path_string,
+            output_path: PathBuf::new(),
+            output_path_custom: false,
+            output_namer: OutputNamer::default(),
+            output_path_string: String::new(),
             output_path_cursor: 0,
             is_editing_output: false,
             config: EncodingConfig::default(),
             selected_field: 0,
+            trim_start_string: String::new(),
+            trim_end_string: String::new(),
             is_interlaced,
+            source_dimensions,
+            dimensions_acknowledged: false,
+            capabilities: None,
+            encoder_defaults: HashMap::new(),
+            vaapi_device: PathBuf::from(DEFAULT_VAAPI_DEVICE),
+            crf_policy: Vec::new(),
+            source_heights: HashMap::new(),
+            base_crf: EncoderParams::default().crf,
+            crf_edited: false,
+            extra_params_string: String::new(),
+            extra_params_cursor: 0,
+            is_editing_extra: false,
+            extra_params_saved: Vec::new(),
+            languages_string: String::new(),
+            languages_cursor: 0,
+            is_editing_languages: false,
+            languages_saved: (Vec::new(), Vec::new()),
+            tracks,
+            free_space: None,
+            free_space_dir: None,
+        }
+        .with_output_namer(OutputNamer::default())
+    }
+
+    /// Proposer le CRF selon la résolution des sources
+    ///
+    /// Le CRF affiché est celui de la première source ; en batch, chaque source reçoit
+    /// le sien tant que le CRF n'est pas modifié à la main.
+    pub fn apply_crf_policy(&mut self, policy: Vec<ResolutionCrf>, heights: HashMap<PathBuf, u32>) {
+        self.crf_policy = policy;
+        self.source_heights = heights;
+        self.base_crf = self.config.encoder_params.crf;
+        if let Some(crf) = self
+            .input_paths
+            .first()
+            .and_then(|input| self.policy_crf(input))
+        {
+            self.config.encoder_params.crf = crf;
+        }
+    }
+
+    /// CRF proposé par la politique pour une source (None = pas de règle ou hauteur inconnue)
+    #[must_use]
+    pub fn policy_crf(&self, input: &Path) -> Option<u32> {
+        if self.crf_edited {
+            return None;
+        }
+        let height = *self.source_heights.get(input)?;
+        crf_for_height(&self.crf_policy, height)
+    }
+
+    /// Le CRF d'au moins une source vient-il de la politique par résolution ?
+    #[must_use]
+    pub fn crf_policy_applies(&self) -> bool {
+        self.input_paths
+            .iter()
+            .any(|input| self.policy_crf(input).is_some())
+    }
+
+    /// CRF d'une source du batch
+    ///
+    /// Sans règle pour sa hauteur (ou hauteur inconnue), une source reçoit le CRF de
+    /// l'encodeur, et non celui affiché qui peut venir de la règle de la première source.
+    #[must_use]
+    pub fn crf_for_input(&self, input: &Path) -> u32 {
+        if self.crf_edited {
+            return self.config.encoder_params.crf;
+        }
+        self.policy_crf(input).unwrap_or(self.base_crf)
+    }
+
+    /// Nommer les sorties avec `namer` (modèle, répertoire de sortie par défaut)
+    #[must_use]
+    pub fn with_output_namer(mut self, namer: OutputNamer) -> Self {
+        self.output_namer = namer;
+        self.output_path_custom = false;
+        self.output_path = self.displayed_default_output_path();
+        self.output_path_string = self.output_path.display().to_string();
+        self.refresh_free_space();
+        self
+    }
+
+    /// Sortie de la première source : saisie résolue, ou chemin par défaut en batch ou
+    /// sans saisie
+    #[must_use]
+    pub fn output_destination(&self) -> Option<PathBuf> {
+        let input = self.input_paths.first()?;
+        if self.is_batch() || self.output_path_string.trim().is_empty() {
+            Some(self.default_output_path(input))
+        } else {
+            Some(
+                self.output_namer
+                    .resolve_entered(&self.output_path_string, input),
+            )
+        }
+    }
+
+    /// Mesurer l'espace libre quand le répertoire de destination change
+    ///
+    /// Le rendu affiche `free_space` sans interroger le système de fichiers à chaque frame.
+    pub fn refresh_free_space(&mut self) {
+        let dir = self
+            .output_destination()
+            .and_then(|path| path.parent().map(Path::to_path_buf));
+        if dir != self.free_space_dir {
+            self.free_space = dir.as_deref().and_then(available_space);
+            self.free_space_dir = dir;
+        }
+    }
+
+    /// Configuration que recevra une source (CRF propre à sa résolution en mode batch)
+    fn config_for_input(&self, input: &Path) -> EncodingConfig {
+        let mut config = self.config.clone();
+        config.encoder_params.crf = self.crf_for_input(input);
+        config
+    }
+
+    /// Chemin de sortie par défaut d'un fichier d'entrée
+    #[must_use]
+    pub fn default_output_path(&self, input: &Path) -> PathBuf {
+        self.output_namer
+            .default_path(input, &self.config_for_input(input))
+    }
+
+    /// Chemin affiché dans le dialogue (placeholder en mode batch)
+    fn displayed_default_output_path(&self) -> PathBuf {
+        match self.input_paths.as_slice() {
+            [input] => self.default_output_path(input),
+            _ => self.output_namer.output_dir().map_or_else(
+                || PathBuf::from("<auto-generated>"),
+                |dir| dir.join("<auto-generated>"),
+            ),
+        }
+    }
+
+    /// Sorties des fichiers d'un batch, avec les doublons renommés (ancien, nouveau chemin)
+    #[must_use]
+    pub fn batch_output_paths(&self) -> (Vec<PathBuf>, Vec<(PathBuf, PathBuf)>) {
+        let configs: Vec<EncodingConfig> = self
+            .input_paths
+            .iter()
+            .map(|input| self.config_for_input(input))
+            .collect();
+        self.output_namer
+            .batch_paths(self.input_paths.iter().map(PathBuf::as_path).zip(&configs))
+    }
+
+    /// Suivre la configuration dans le chemin par défaut (`{encoder}`, `{crf}`...), tant
+    /// que l'utilisateur n'a pas saisi le sien
+    pub fn refresh_default_output_path(&mut self) {
+        if self.output_path_custom || self.is_editing_output || self.is_batch() {
+            return;
+        }
+        let path = self.displayed_default_output_path();
+        if path != self.output_path {
+            self.output_path_string = path.display().to_string();
+            self.output_path = path;
+        }
+    }
+
+    /// Problème des dimensions encodées de la première source (None = aucun ou inconnu)
+    #[must_use]
+    pub fn dimensions_warning(&self) -> Option<String> {
+        if self.config.video_mode == VideoMode::Copy {
+            return None;
+        }
+        let (width, height) = self.source_dimensions?;
+        dimensions_warning(width, height, self.config.crop_to_even)
+    }
+
+    /// Activer ou non le recadrage aux dimensions paires
+    pub fn toggle_crop_to_even(&mut self) {
+        self.config.crop_to_even = !self.config.crop_to_even;
+        self.dimensions_acknowledged = false;
+    }
+
+    /// Associer les capacités des encodeurs pour griser les champs non supportés
+    ///
+    /// Si l'encodeur par défaut n'est pas disponible, bascule sur SVT-AV1 ou libaom ;
+    /// sans libopus, l'audio passe en copie.
+    #[must_use]
+    pub fn with_capabilities(mut self, capabilities: Option<Arc<EncoderCapabilities>>) -> Self {
+        if let Some(caps) = &capabilities {
+            if !caps.has_encoder(self.config.encoder) {
+                if let Some(encoder) = [EncoderType::SvtAv1, EncoderType::Aom]
+                    .into_iter()
+                    .find(|encoder| caps.has_encoder(*encoder))
+                {
+                    self.config.encoder = encoder;
+                    if let Some(defaults) = self.encoder_defaults.get(&encoder) {
+                        self.config.encoder_params = defaults.clone();
+                    }
+                    self.config.encoder_params.tune = Tune::default_for(encoder);
+                }
+            }
+            if !caps.ffmpeg_has_encoder("libopus") {
+                self.config.audio_mode = AudioMode::Copy;
+            }
+        }
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Le champ peut-il être modifié avec les encodeurs installés ?
+    #[must_use]
+    pub fn is_field_supported(&self, field: usize) -> bool {
+        // Vidéo copiée : les réglages d'encodage et VMAF sont sans objet
+        if self.config.video_mode == VideoMode::Copy
+            && matches!(field, 2..=7 | 11 | Self::EXTRA_FIELD)
+        {
+            return false;
+        }
+        // Encodeur matériel : ni threads, ni type de contenu, ni tune
+        if self.config.encoder.is_hardware() && matches!(field, 4 | 6 | 7) {
+            return false;
+        }
+
+        let Some(caps) = &self.capabilities else {
+            return true;
+        };
+        let encoder = self.config.encoder;
+
+        match field {
+            // Encodeur : la copie vidéo est toujours disponible
+            0 => true,
+            // Threads
+            4 => match encoder {
+                EncoderType::SvtAv1 => caps.encoder_supports(encoder, "--lp"),
+                EncoderType::Aom => caps.encoder_supports(encoder, "--threads"),
+                EncoderType::Nvenc | EncoderType::Qsv | EncoderType::Vaapi => false,
+            },
+            // Type de contenu : réglages psychovisuels propres à SVT-AV1-PSY
+            6 => {
+                encoder == EncoderType::SvtAv1
+                    && caps.encoder_supports(encoder, "--noise-norm-strength")
+            }
+            // Métrique d'optimisation
+            7 => caps.encoder_supports(encoder, "--tune"),
+            _ => true,
+        }
+    }
+
+    /// Passer au mode audio suivant : Opus → copie → sans audio (ou l'inverse)
+    ///
+    /// Opus est sauté si ffmpeg n'a pas libopus.
+    pub fn cycle_audio_mode(&mut self, forward: bool) {
+        let opus = self
+            .capabilities
+            .as_ref()
+            .is_none_or(|caps| caps.ffmpeg_has_encoder("libopus"));
+        let mut choices = vec![AudioMode::Copy, AudioMode::None];
+        if opus {
+            choices.insert(0, AudioMode::Opus { bitrate: 128 });
+        }
+        let current = choices
+            .iter()
+            .position(|mode| {
+                std::mem::discriminant(mode) == std::mem::discriminant(&self.config.audio_mode)
+            })
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % choices.len()
+        } else {
+            (current + choices.len() - 1) % choices.len()
+        };
+        self.config.audio_mode = choices.swap_remove(next);
+    }
+
+    /// Passer au choix vidéo suivant : SVT-AV1 → libaom → encodeurs matériels → copie
+    /// (ou l'inverse)
+    ///
+    /// Les encodeurs absents des capacités connues sont sautés ; les encodeurs matériels
+    /// ne sont proposés qu'une fois détectés dans ffmpeg.
+    pub fn cycle_video_choice(&mut self, forward: bool) {
+        let available = |encoder: EncoderType| match &self.capabilities {
+            Some(caps) => caps.has_encoder(encoder),
+            None => !encoder.is_hardware(),
+        };
+        let choices: Vec<(VideoMode, EncoderType)> = [EncoderType::SvtAv1, EncoderType::Aom]
+            .into_iter()
+            .chain(EncoderType::HARDWARE)
+            .map(|encoder| (VideoMode::Encode, encoder))
+            .filter(|(_, encoder)| available(*encoder))
+            .chain(std::iter::once((VideoMode::Copy, self.config.encoder)))
+            .collect();
+
+        let current = choices
+            .iter()
+            .position(|(mode, encoder)| {
+                *mode == self.config.video_mode
+                    && (*mode == VideoMode::Copy || *encoder == self.config.encoder)
+            })
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % choices.len()
+        } else {
+            (current + choices.len() - 1) % choices.len()
+        };
+
+        let (mode, encoder) = choices[next];
+        let encoder_changed = encoder != self.config.encoder;
+        self.config.video_mode = mode;
+        self.config.encoder = encoder;
+
+        // Preset, CRF et paramètres extra configurés pour le nouvel encodeur
+        if encoder_changed {
+            if let Some(defaults) = self.encoder_defaults.get(&encoder) {
+                let policy_crf = self
+                    .input_paths
+                    .first()
+                    .and_then(|input| self.policy_crf(input));
+                self.base_crf = defaults.crf;
+                let params = &mut self.config.encoder_params;
+                params.crf = policy_crf.unwrap_or(defaults.crf);
+                params.preset = defaults.preset;
+                params.extra_params.clone_from(&defaults.extra_params);
+            }
+        }
+
+        // Tune propre à l'ancien encodeur : revenir au défaut du nouveau
+        if !self.config.encoder_params.tune.is_supported_by(encoder) {
+            self.config.encoder_params.tune = Tune::default_for(encoder);
         }
     }
 
@@ -663,12 +1771,66 @@ impl EncodeConfigDialog {
     }
 
     pub fn move_field_down(&mut self) {
-        // 8 champs : encodeur, audio mode, CRF, preset, threads, VMAF, content type, output path
-        if self.selected_field < 7 {
+        // 15 champs : encodeur, audio mode, CRF, preset, threads, VMAF, content type,
+        // tune, début, fin, découpage, débit, paramètres extra, langues, output path
+        if self.selected_field < Self::OUTPUT_FIELD {
             self.selected_field += 1;
         }
     }
 
+    /// Saisie de découpe du champ sélectionné (début = 8, fin = 9)
+    fn trim_input_mut(&mut self) -> Option<&mut String> {
+        match self.selected_field {
+            8 => Some(&mut self.trim_start_string),
+            9 => Some(&mut self.trim_end_string),
+            _ => None,
+        }
+    }
+
+    /// Ajouter un caractère à la saisie de découpe (chiffres, `:` et `.`)
+    pub fn push_trim_char(&mut self, c: char) {
+        if !(c.is_ascii_digit() || c == ':' || c == '.') {
+            return;
+        }
+        if let Some(input) = self.trim_input_mut() {
+            input.push(c);
+            // Aperçu à jour dès que la saisie est valide (revalidée à la soumission)
+            let _ = self.sync_trim();
+        }
+    }
+
+    /// Effacer le dernier caractère de la saisie de découpe
+    pub fn pop_trim_char(&mut self) {
+        if let Some(input) = self.trim_input_mut() {
+            input.pop();
+            let _ = self.sync_trim();
+        }
+    }
+
+    /// Convertir les saisies de découpe dans la configuration
+    ///
+    /// # Errors
+    ///
+    /// Retourne un message si un timestamp est invalide ou si la fin précède le début.
+    pub fn sync_trim(&mut self) -> Result<(), String> {
+        let parse = |text: &str, label: &str| {
+            if text.trim().is_empty() {
+                Ok(None)
+            } else {
+                parse_timestamp(text)
+                    .map(Some)
+                    .ok_or_else(|| format!("{label} invalide: '{text}' (format HH:MM:SS)"))
+            }
+        };
+        let trim = TrimRange {
+            start: parse(&self.trim_start_string, "Début")?,
+            end: parse(&self.trim_end_string, "Fin")?,
+        };
+        trim.validate()?;
+        self.config.trim = trim;
+        Ok(())
+    }
+
     pub fn start_editing_output(&mut self) {
         // Désactiver l'édition si batch
         if self.is_batch() {
@@ -683,7 +1845,431 @@ impl EncodeConfigDialog {
         self.sync_output_path();
     }
 
+    /// Appliquer la saisie du chemin de sortie, puis afficher le chemin absolu retenu
+    ///
+    /// Un chemin relatif est résolu par rapport à `output_dir` ou au répertoire de la
+    /// source ; une saisie vide rétablit le chemin par défaut.
     pub fn sync_output_path(&mut self) {
-        self.output_path = PathBuf::from(&self.output_path_string);
+        let Some(input) = self.input_paths.first() else {
+            return;
+        };
+        let default = self.default_output_path(input);
+        self.output_path = if self.output_path_string.trim().is_empty() {
+            default.clone()
+        } else {
+            self.output_namer
+                .resolve_entered(&self.output_path_string, input)
+        };
+        self.output_path_custom = self.output_path != default;
+        self.output_path_string = self.output_path.display().to_string();
+    }
+
+    pub fn start_editing_extra(&mut self) {
+        let params = &self.config.encoder_params.extra_params;
+        self.extra_params_saved.clone_from(params);
+        self.extra_params_string = params.join(" ");
+        self.extra_params_cursor = self.extra_params_string.chars().count();
+        self.is_editing_extra = true;
+    }
+
+    /// Découper la saisie en paramètres (l'aperçu des commandes suit la saisie)
+    ///
+    /// Les espaces séparent les paramètres : une valeur ne peut pas en contenir.
+    pub fn sync_extra_params(&mut self) {
+        self.config.encoder_params.extra_params = self
+            .extra_params_string
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+    }
+
+    /// Valider la saisie (refusée si une option est réservée au pipeline)
+    ///
+    /// # Errors
+    ///
+    /// Retourne le message de validation ; l'édition continue alors.
+    pub fn stop_editing_extra(&mut self) -> Result<(), String> {
+        self.sync_extra_params();
+        self.config.validate_extra_params()?;
+        self.is_editing_extra = false;
+        Ok(())
+    }
+
+    /// Abandonner l'édition et restaurer les paramètres précédents
+    pub fn cancel_editing_extra(&mut self) {
+        self.config.encoder_params.extra_params = std::mem::take(&mut self.extra_params_saved);
+        self.is_editing_extra = false;
+    }
+
+    pub fn start_editing_languages(&mut self) {
+        let (audio, subtitles) = (
+            &self.config.audio_languages,
+            &self.config.subtitle_languages,
+        );
+        self.languages_string = format_language_selection(audio, subtitles);
+        self.languages_saved = (audio.clone(), subtitles.clone());
+        self.languages_cursor = self.languages_string.chars().count();
+        self.is_editing_languages = true;
+    }
+
+    /// Lire la saisie des langues dans la configuration (l'aperçu suit la saisie)
+    pub fn sync_languages(&mut self) {
+        (self.config.audio_languages, self.config.subtitle_languages) =
+            parse_language_selection(&self.languages_string);
+    }
+
+    /// Valider la saisie (refusée si un code de langue est invalide)
+    ///
+    /// # Errors
+    ///
+    /// Retourne le message de validation ; l'édition continue alors.
+    pub fn stop_editing_languages(&mut self) -> Result<(), String> {
+        self.sync_languages();
+        validate_language_codes(&self.config.audio_languages)?;
+        validate_language_codes(&self.config.subtitle_languages)?;
+        self.is_editing_languages = false;
+        Ok(())
+    }
+
+    /// Abandonner l'édition et restaurer les langues précédentes
+    pub fn cancel_editing_languages(&mut self) {
+        (self.config.audio_languages, self.config.subtitle_languages) =
+            std::mem::take(&mut self.languages_saved);
+        self.is_editing_languages = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dialogue de batch sans probe des sources (chemins fictifs)
+    fn batch_dialog(inputs: &[&str]) -> EncodeConfigDialog {
+        let mut dialog = EncodeConfigDialog::new_batch(Vec::new());
+        dialog.input_paths = inputs.iter().map(PathBuf::from).collect();
+        dialog
+    }
+
+    fn crf_rules() -> Vec<ResolutionCrf> {
+        vec![
+            ResolutionCrf {
+                max_height: 720,
+                crf: 28,
+            },
+            ResolutionCrf {
+                max_height: 1080,
+                crf: 32,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_crf_policy_mixed_heights() {
+        let mut dialog = batch_dialog(&["a.mkv", "b.mkv", "c.mkv"]);
+        dialog.config.encoder_params.crf = 30;
+        let heights = HashMap::from([
+            (PathBuf::from("a.mkv"), 720),
+            (PathBuf::from("b.mkv"), 1080),
+            (PathBuf::from("c.mkv"), 2160),
+        ]);
+        dialog.apply_crf_policy(crf_rules(), heights);
+
+        // CRF affiché : celui de la première source
+        assert_eq!(dialog.config.encoder_params.crf, 28);
+        assert_eq!(dialog.crf_for_input(Path::new("a.mkv")), 28);
+        assert_eq!(dialog.crf_for_input(Path::new("b.mkv")), 32);
+        // Aucune règle au-delà de 1080p : CRF de l'encodeur, pas celui de la première
+        assert_eq!(dialog.crf_for_input(Path::new("c.mkv")), 30);
+
+        // CRF modifié à la main : appliqué à toutes les sources
+        dialog.crf_edited = true;
+        dialog.config.encoder_params.crf = 24;
+        assert!(!dialog.crf_policy_applies());
+        assert_eq!(dialog.crf_for_input(Path::new("b.mkv")), 24);
+        assert_eq!(dialog.crf_for_input(Path::new("c.mkv")), 24);
+    }
+
+    #[test]
+    fn test_crf_policy_unknown_heights() {
+        let mut dialog = batch_dialog(&["a.mkv", "b.mkv"]);
+        dialog.config.encoder_params.crf = 30;
+        let heights = HashMap::from([(PathBuf::from("a.mkv"), 1080)]);
+        dialog.apply_crf_policy(crf_rules(), heights);
+        assert_eq!(dialog.config.encoder_params.crf, 32);
+        assert_eq!(dialog.crf_for_input(Path::new("b.mkv")), 30);
+
+        // Aucune hauteur connue : la politique ne s'applique pas
+        let mut dialog = batch_dialog(&["a.mkv"]);
+        dialog.config.encoder_params.crf = 30;
+        dialog.apply_crf_policy(crf_rules(), HashMap::new());
+        assert!(!dialog.crf_policy_applies());
+        assert_eq!(dialog.config.encoder_params.crf, 30);
+        assert_eq!(dialog.crf_for_input(Path::new("a.mkv")), 30);
+    }
+
+    #[test]
+    fn test_free_space_follows_destination_dir() {
+        let mut dialog = batch_dialog(&["/videos/film.mkv"]);
+        dialog.refresh_free_space();
+        assert_eq!(dialog.free_space_dir, Some(PathBuf::from("/videos")));
+
+        // Même répertoire : la mesure est gardée
+        dialog.free_space = Some(42);
+        dialog.output_path_string = "/videos/autre.mkv".to_string();
+        dialog.refresh_free_space();
+        assert_eq!(dialog.free_space, Some(42));
+
+        // Nouveau répertoire (premier ancêtre existant : /) : nouvelle mesure
+        dialog.output_path_string = "/ailleurs/film.av1.mkv".to_string();
+        dialog.refresh_free_space();
+        assert_eq!(dialog.free_space_dir, Some(PathBuf::from("/ailleurs")));
+        assert_eq!(dialog.free_space, available_space(Path::new("/")));
+    }
+
+    /// État avec une queue de `count` jobs, affichée dans la vue Queue
+    fn queue_state(count: usize) -> (AppState, Vec<uuid::Uuid>) {
+        let mut state = AppState::new(std::env::temp_dir());
+        state.current_view = View::Queue;
+        state.queue_jobs = (0..count)
+            .map(|i| {
+                EncodingJob::new(
+                    PathBuf::from(format!("/videos/{i}.mkv")),
+                    PathBuf::from(format!("/videos/{i}.av1.mkv")),
+                    EncodingConfig::default(),
+                )
+            })
+            .collect();
+        let ids = state.queue_jobs.iter().map(|job| job.id).collect();
+        (state, ids)
+    }
+
+    fn queue_order(state: &AppState) -> Vec<uuid::Uuid> {
+        state.queue_jobs.iter().map(|job| job.id).collect()
+    }
+
+    #[test]
+    fn test_grab_move_and_drop() {
+        let (mut state, ids) = queue_state(3);
+        state.selected_index = 2;
+        state.grab_selected_job();
+
+        // Le job suit les flèches, borné au début de la queue
+        state.move_grabbed_job(true);
+        state.move_grabbed_job(true);
+        state.move_grabbed_job(true);
+        assert_eq!(queue_order(&state), [ids[2], ids[0], ids[1]]);
+        assert_eq!(state.selected_index, 0);
+
+        // Queue rechargée dans l'ordre du daemon : l'aperçu est rétabli
+        state
+            .queue_jobs
+            .sort_by_key(|job| ids.iter().position(|id| *id == job.id));
+        state.sync_grab_preview();
+        assert_eq!(queue_order(&state), [ids[2], ids[0], ids[1]]);
+
+        assert_eq!(state.drop_grabbed_job(), Some((ids[2], 0)));
+        assert!(state.grabbed_job.is_none());
+
+        // Déposé à sa place : rien à envoyer
+        state.grab_selected_job();
+        state.move_grabbed_job(false);
+        state.move_grabbed_job(true);
+        assert_eq!(state.drop_grabbed_job(), None);
+    }
+
+    #[test]
+    fn test_grab_cancel_and_job_leaving_queue() {
+        let (mut state, ids) = queue_state(3);
+        state.selected_index = 0;
+        state.grab_selected_job();
+        state.move_grabbed_job(false);
+        state.move_grabbed_job(false);
+        state.move_grabbed_job(false);
+        assert_eq!(queue_order(&state), [ids[1], ids[2], ids[0]]);
+
+        // Annulation : retour à la position d'origine
+        state.cancel_grab();
+        assert!(state.grabbed_job.is_none());
+        assert_eq!(queue_order(&state), ids);
+        assert_eq!(state.selected_index, 0);
+
+        // Job démarré pendant le déplacement : la saisie s'arrête
+        state.grab_selected_job();
+        state.queue_jobs.remove(0);
+        state.sync_grab_preview();
+        assert!(state.grabbed_job.is_none());
+        assert_eq!(state.drop_grabbed_job(), None);
+
+        // Queue vide : rien à saisir
+        let (mut empty, _) = queue_state(0);
+        empty.grab_selected_job();
+        assert!(empty.grabbed_job.is_none());
+    }
+
+    #[test]
+    fn test_thumbnail_cache() {
+        let mut cache = ThumbnailCache::default();
+        let film = PathBuf::from("/videos/film.mkv");
+        assert!(!cache.is_known(&film));
+
+        // Demande en cours : pas redemandée, rien à afficher
+        cache.mark_requested(film.clone());
+        assert!(cache.is_known(&film));
+        assert_eq!(cache.png(&film), None);
+        cache.insert(film.clone(), Some(vec![1, 2, 3]));
+        assert_eq!(cache.png(&film), Some(&[1, 2, 3][..]));
+
+        // Extraction impossible : connue, mais rien à afficher
+        let broken = PathBuf::from("/videos/broken.mkv");
+        cache.insert(broken.clone(), None);
+        assert!(cache.is_known(&broken));
+        assert_eq!(cache.png(&broken), None);
+
+        // Cache plein : la plus ancienne miniature est oubliée
+        for i in 0..THUMBNAIL_CACHE_SIZE - 1 {
+            cache.insert(PathBuf::from(format!("/videos/{i}.mkv")), Some(vec![0]));
+        }
+        assert!(!cache.is_known(&film));
+        assert!(cache.is_known(&broken));
+    }
+
+    #[test]
+    fn test_find_ignore_case() {
+        assert_eq!(find_ignore_case("Film.MKV", "mkv"), Some(5..8));
+        assert_eq!(find_ignore_case("film.mkv", "FILM"), Some(0..4));
+        assert_eq!(find_ignore_case("film.mkv", "avi"), None);
+        assert_eq!(find_ignore_case("film.mkv", ""), None);
+        assert_eq!(find_ignore_case("", "film"), None);
+
+        // Plages en octets : les caractères accentués en occupent deux
+        let name = "Le Bel Été.mkv";
+        let matched = find_ignore_case(name, "été").unwrap();
+        assert_eq!(matched, 7..12);
+        assert_eq!(&name[matched], "Été");
+        assert_eq!(find_ignore_case("ÉTÉ", "té"), Some(2..5));
+        // Aiguille plus longue que la fin de la chaîne
+        assert_eq!(find_ignore_case("été", "étés"), None);
+    }
+
+    #[test]
+    fn test_search_jobs_order_and_matches() {
+        let job = |input: &str, output: &str| {
+            EncodingJob::new(
+                PathBuf::from(input),
+                PathBuf::from(output),
+                EncodingConfig::default(),
+            )
+        };
+        let mut state = AppState::new(std::env::temp_dir());
+        state.history_jobs = vec![job("/v/Été.mkv", "/out/ete.av1.mkv")];
+        state.active_jobs = vec![job("/v/autre.mkv", "/out/autre.av1.mkv")];
+        state.queue_jobs = vec![
+            job("/v/hiver.mkv", "/out/hiver.av1.mkv"),
+            job("/été/ÉTÉ 2.mkv", "/out/x.mkv"),
+        ];
+
+        // Queue, puis actifs, puis historique ; seul le nom de fichier compte
+        let hits = state.search_jobs("  été ");
+        assert_eq!(
+            hits.iter()
+                .map(|hit| (hit.view, hit.index))
+                .collect::<Vec<_>>(),
+            [(View::Queue, 1), (View::History, 0)]
+        );
+        assert_eq!(hits[0].file_name, "ÉTÉ 2.mkv");
+        assert_eq!(&hits[0].file_name[hits[0].matched.clone()], "ÉTÉ");
+
+        // Nom de sortie quand la source ne correspond pas
+        let hits = state.search_jobs("AV1");
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].file_name, "hiver.av1.mkv");
+        assert_eq!(hits[1].view, View::Active);
+        assert_eq!(hits[2].file_name, "ete.av1.mkv");
+
+        // Requête vide : aucun résultat
+        assert!(state.search_jobs("").is_empty());
+        assert!(state.search_jobs("   ").is_empty());
+    }
+
+    /// Pistes numérotées par leur langue ("0", "1", …)
+    fn tracks(count: usize) -> Vec<SourceTrack> {
+        (0..count)
+            .map(|i| SourceTrack {
+                language: Some(i.to_string()),
+                ..SourceTrack::default()
+            })
+            .collect()
+    }
+
+    fn languages(tracks: &[SourceTrack]) -> Vec<&str> {
+        tracks
+            .iter()
+            .filter_map(|track| track.language.as_deref())
+            .collect()
+    }
+
+    #[test]
+    fn test_track_lists_visible_clamps() {
+        let mut lists = TrackLists {
+            audio: tracks(5),
+            subtitles: tracks(2),
+            visible_rows: 3,
+            scroll_offset: 0,
+        };
+        assert_eq!(languages(lists.visible(&lists.audio)), ["0", "1", "2"]);
+        assert_eq!(languages(lists.visible(&lists.subtitles)), ["0", "1"]);
+
+        // Défilement commun : la liste la plus courte peut être entièrement passée
+        lists.scroll_offset = 3;
+        assert_eq!(languages(lists.visible(&lists.audio)), ["3", "4"]);
+        assert!(lists.visible(&lists.subtitles).is_empty());
+        lists.scroll_offset = 10;
+        assert!(lists.visible(&lists.audio).is_empty());
+
+        // Aucune ligne configurée : une piste reste affichée
+        lists.visible_rows = 0;
+        lists.scroll_offset = 1;
+        assert_eq!(languages(lists.visible(&lists.audio)), ["1"]);
+        assert!(lists.visible(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_track_lists_scroll_page() {
+        let mut lists = TrackLists {
+            audio: tracks(2),
+            subtitles: tracks(7),
+            visible_rows: 3,
+            scroll_offset: 0,
+        };
+        // Vers le bas, bornée par la plus longue liste (7 - 3)
+        lists.scroll_page(true);
+        assert_eq!(lists.scroll_offset, 3);
+        lists.scroll_page(true);
+        assert_eq!(lists.scroll_offset, 4);
+        assert_eq!(languages(lists.visible(&lists.subtitles)), ["4", "5", "6"]);
+        lists.scroll_page(true);
+        assert_eq!(lists.scroll_offset, 4);
+
+        // Vers le haut, jusqu'à la première piste
+        lists.scroll_page(false);
+        assert_eq!(lists.scroll_offset, 1);
+        lists.scroll_page(false);
+        assert_eq!(lists.scroll_offset, 0);
+
+        // Tout tient dans la page : pas de défilement
+        let mut short = TrackLists {
+            audio: tracks(2),
+            visible_rows: 3,
+            ..TrackLists::default()
+        };
+        short.scroll_page(true);
+        assert_eq!(short.scroll_offset, 0);
+
+        // Aucune ligne configurée : défilement piste par piste
+        short.visible_rows = 0;
+        short.scroll_page(true);
+        assert_eq!(short.scroll_offset, 1);
+        short.scroll_page(true);
+        assert_eq!(short.scroll_offset, 1);
     }
 }
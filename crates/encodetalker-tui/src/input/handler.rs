@@ -1,8 +1,12 @@
 use crate::app::{
-    AppState, ConfirmAction, Dialog, EncodeConfigDialog, LastClick, View, VmafGraphData,
+    AppState, ConfirmAction, Dialog, EncodeConfigDialog, LastClick, MaintenanceDialog,
+    SearchDialog, View, VmafGraphData,
 };
+use crate::ui::job_detail_lines;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
-use encodetalker_common::{AudioMode, EncoderType, VideoContentType};
+use encodetalker_common::{
+    EncoderType, EncodingJob, EncodingMode, SplitMode, Tune, VideoContentType,
+};
 use std::time::{Duration, Instant};
 
 /// Obtenir le nombre max de threads disponibles sur la machine
@@ -16,13 +20,23 @@ fn get_max_threads() -> u32 {
 
 /// Gérer un événement clavier
 pub fn handle_key_event(state: &mut AppState, key: KeyEvent) -> InputAction {
-    // Si on est en Loading, bloquer toutes les touches sauf 'q'
+    // Si on est en Loading, bloquer toutes les touches sauf 'q' et 'l'
     if state.current_view == View::Loading {
-        if matches!(key.code, KeyCode::Char('q' | 'Q')) {
-            state.dialog = Some(Dialog::Confirm {
-                message: "Voulez-vous quitter l'application ?\n(La compilation continuera en arrière-plan)".to_string(),
-                on_confirm: ConfirmAction::Quit,
-            });
+        match key.code {
+            KeyCode::Char('q' | 'Q') => {
+                state.dialog = Some(Dialog::Confirm {
+                    message: "Voulez-vous quitter l'application ?\n(La compilation continuera en arrière-plan)".to_string(),
+                    on_confirm: ConfirmAction::Quit,
+                });
+            }
+            // Afficher/masquer la sortie des compilateurs
+            KeyCode::Char('l' | 'L') => {
+                if let Some(loading) = &mut state.loading_state {
+                    loading.show_logs = !loading.show_logs;
+                    return InputAction::SetCompilationLogStreaming(loading.show_logs);
+                }
+            }
+            _ => {}
         }
         return InputAction::None;
     }
@@ -56,6 +70,32 @@ pub fn handle_key_event(state: &mut AppState, key: KeyEvent) -> InputAction {
             state.switch_view(state.current_view.prev());
             return InputAction::None;
         }
+        KeyCode::Char('L') => {
+            state.open_log_file();
+            return InputAction::None;
+        }
+        KeyCode::Char('M') => {
+            state.dialog = Some(Dialog::Maintenance(MaintenanceDialog::default()));
+            return InputAction::EstimateCleanup;
+        }
+        KeyCode::Char('/') => {
+            state.dialog = Some(Dialog::Search(SearchDialog::default()));
+            return InputAction::None;
+        }
+        KeyCode::Char('R') => {
+            // Sans job actif, rien à perdre : redémarrer sans confirmation
+            if state.active_jobs.is_empty() {
+                return InputAction::RestartDaemon;
+            }
+            state.dialog = Some(Dialog::Confirm {
+                message: format!(
+                    "Redémarrer le daemon ?\n{} job(s) en cours seront arrêtés selon daemon.shutdown_mode",
+                    state.active_jobs.len()
+                ),
+                on_confirm: ConfirmAction::RestartDaemon,
+            });
+            return InputAction::None;
+        }
         _ => {}
     }
 
@@ -66,6 +106,7 @@ pub fn handle_key_event(state: &mut AppState, key: KeyEvent) -> InputAction {
         View::Queue => handle_queue_key(state, key),
         View::Active => handle_active_key(state, key),
         View::History => handle_history_key(state, key),
+        View::Stats => handle_stats_key(state, key),
     }
 }
 
@@ -99,7 +140,7 @@ pub fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent) -> InputActio
                     return InputAction::None;
                 }
                 MouseEventKind::ScrollDown => {
-                    if config.selected_field < 7 {
+                    if config.selected_field < EncodeConfigDialog::OUTPUT_FIELD {
                         config.selected_field += 1;
                     }
                     return InputAction::None;
@@ -131,6 +172,13 @@ pub fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent) -> InputActio
                     _ => {}
                 }
             }
+            Some(Dialog::JobDetail { .. }) => match kind {
+                MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                    scroll_job_detail(state, kind == MouseEventKind::ScrollDown);
+                    return InputAction::None;
+                }
+                _ => {}
+            },
             _ => {}
         }
 
@@ -187,26 +235,78 @@ pub fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent) -> InputActio
 pub enum InputAction {
     None,
     RefreshLists,
+    /// Arrêter puis relancer le daemon (configuration modifiée)
+    RestartDaemon,
     AddJob {
         input_path: std::path::PathBuf,
         output_path: std::path::PathBuf,
         config: encodetalker_common::EncodingConfig,
     },
-    /// Ajouter plusieurs jobs avec la même config
+    /// Ajouter un extrait de test de la première source, le dialogue restant ouvert
+    AddPreviewJob {
+        input_path: std::path::PathBuf,
+        output_path: std::path::PathBuf,
+        config: encodetalker_common::EncodingConfig,
+    },
+    /// Ajouter plusieurs jobs avec la même config (entrée, sortie, CRF de la source)
     AddBatchJobs {
-        jobs: Vec<(std::path::PathBuf, std::path::PathBuf)>,
+        jobs: Vec<(std::path::PathBuf, std::path::PathBuf, u32)>,
         config: encodetalker_common::EncodingConfig,
+        /// Sorties en double renommées avant l'ajout (ancien chemin, nouveau chemin)
+        renamed: Vec<(std::path::PathBuf, std::path::PathBuf)>,
     },
     CancelJob {
         job_id: uuid::Uuid,
     },
+    /// Annuler les jobs d'un lot en attente ou en cours
+    CancelBatch {
+        batch_id: uuid::Uuid,
+    },
     RetryJob {
         job_id: uuid::Uuid,
     },
+    /// Déplacer un job en attente dans la queue
+    MoveJob {
+        job_id: uuid::Uuid,
+        position: usize,
+    },
+    /// Changer la niceness d'un job actif
+    SetJobPriority {
+        job_id: uuid::Uuid,
+        nice: i32,
+    },
     RemoveFromHistory {
         job_id: uuid::Uuid,
+        /// Supprimer aussi les fichiers de sortie du job
+        delete_outputs: bool,
     },
-    ClearHistory,
+    ClearHistory {
+        /// Supprimer aussi les fichiers de sortie des jobs
+        delete_outputs: bool,
+    },
+    /// Lancer un benchmark des presets sur une vidéo
+    StartBenchmark {
+        input_path: std::path::PathBuf,
+        encoder: EncoderType,
+    },
+    /// S'abonner ou se désabonner de la sortie des compilateurs
+    SetCompilationLogStreaming(bool),
+    /// Joindre les fichiers sélectionnés (dans l'ordre) en une seule source
+    JoinFiles {
+        files: Vec<std::path::PathBuf>,
+    },
+    /// Vérifier des fichiers déjà encodés (un job de vérification chacun)
+    VerifyFiles {
+        files: Vec<std::path::PathBuf>,
+    },
+    /// Demander au daemon l'espace libérable par chaque cible de maintenance
+    EstimateCleanup,
+    /// Nettoyer les cibles de maintenance confirmées
+    Cleanup {
+        targets: Vec<encodetalker_common::CleanupTarget>,
+    },
+    /// Écrire les répertoires épinglés dans la configuration
+    SavePinnedDirs,
 }
 
 /// Gérer un clic sur le contenu (détection double-clic et sélection)
@@ -267,21 +367,23 @@ fn handle_content_click(state: &mut AppState, row: u16, column: u16) -> InputAct
 fn handle_file_browser_enter(state: &mut AppState) -> InputAction {
     let selected_files = state.file_browser.get_selected_files();
 
-    if let Some(entry) = state.file_browser.get_selected(state.selected_index) {
+    if let Some(entry) = state
+        .file_browser
+        .get_selected(state.selected_index)
+        .cloned()
+    {
         if entry.is_dir {
             // Toujours naviguer dans les dossiers (priorité)
             state.file_browser.navigate_to(entry.path.clone());
             state.selected_index = 0;
+        } else if (!selected_files.is_empty() || entry.is_video) && state.refuse_new_jobs() {
+            // Daemon en cours d'arrêt : pas de dialogue d'encodage
         } else if !selected_files.is_empty() {
             // Batch avec fichiers sélectionnés
-            state.dialog = Some(Dialog::EncodeConfig(EncodeConfigDialog::new_batch(
-                selected_files,
-            )));
+            state.dialog = Some(state.new_encode_dialog(selected_files));
         } else if entry.is_video {
             // Single file: comportement actuel
-            state.dialog = Some(Dialog::EncodeConfig(EncodeConfigDialog::new(
-                entry.path.clone(),
-            )));
+            state.dialog = Some(state.new_encode_dialog(vec![entry.path.clone()]));
         }
     }
     InputAction::None
@@ -322,16 +424,61 @@ fn handle_file_browser_key(state: &mut AppState, key: KeyEvent) -> InputAction {
 
         // 'a' : Shortcut pour single file (ignore les sélections, compatibilité)
         KeyCode::Char('a') => {
+            let video = state
+                .file_browser
+                .get_selected(state.selected_index)
+                .filter(|entry| entry.is_video)
+                .map(|entry| entry.path.clone());
+            if let Some(path) = video {
+                if !state.refuse_new_jobs() {
+                    state.dialog = Some(state.new_encode_dialog(vec![path]));
+                }
+            }
+            InputAction::None
+        }
+
+        // 'J' : joindre les fichiers sélectionnés en un seul encodage
+        KeyCode::Char('J') => {
+            let files = state.file_browser.get_selected_files();
+            if files.len() < 2 {
+                state.set_status("Sélectionnez au moins deux fichiers à joindre");
+                return InputAction::None;
+            }
+            if state.refuse_new_jobs() {
+                return InputAction::None;
+            }
+            InputAction::JoinFiles { files }
+        }
+
+        // 'b' : benchmark des presets de l'encodeur par défaut sur la vidéo sélectionnée
+        KeyCode::Char('b') => {
             if let Some(entry) = state.file_browser.get_selected(state.selected_index) {
                 if entry.is_video {
-                    state.dialog = Some(Dialog::EncodeConfig(EncodeConfigDialog::new(
-                        entry.path.clone(),
-                    )));
+                    return InputAction::StartBenchmark {
+                        input_path: entry.path.clone(),
+                        encoder: state.default_config.encoder,
+                    };
                 }
             }
             InputAction::None
         }
 
+        // 'v' : vérifier les fichiers sélectionnés (ou celui sous le curseur) sans réencoder
+        KeyCode::Char('v') => {
+            let mut files = state.file_browser.get_selected_files();
+            if files.is_empty() {
+                if let Some(entry) = state.file_browser.get_selected(state.selected_index) {
+                    if entry.is_video {
+                        files.push(entry.path.clone());
+                    }
+                }
+            }
+            if files.is_empty() || state.refuse_new_jobs() {
+                return InputAction::None;
+            }
+            InputAction::VerifyFiles { files }
+        }
+
         KeyCode::Char('r') => {
             // Rafraîchir
             state.file_browser.refresh();
@@ -339,6 +486,21 @@ fn handle_file_browser_key(state: &mut AppState, key: KeyEvent) -> InputAction {
             InputAction::None
         }
 
+        // 'p' : épingler (ou retirer) le répertoire courant
+        KeyCode::Char('p') => {
+            if state.toggle_pin_current_dir() {
+                InputAction::SavePinnedDirs
+            } else {
+                InputAction::None
+            }
+        }
+
+        // '1' à '9' : aller au répertoire épinglé correspondant
+        KeyCode::Char(c @ '1'..='9') => {
+            state.jump_to_pinned(c as usize - '0' as usize);
+            InputAction::None
+        }
+
         KeyCode::Char('i') => {
             // Afficher les informations vidéo (ffmpeg -i)
             if let Some(entry) = state.file_browser.get_selected(state.selected_index) {
@@ -398,8 +560,27 @@ fn handle_file_browser_key(state: &mut AppState, key: KeyEvent) -> InputAction {
     }
 }
 
+/// Gérer les touches pendant le déplacement d'un job de la queue
+fn handle_grab_key(state: &mut AppState, key: KeyEvent) -> InputAction {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => state.move_grabbed_job(true),
+        KeyCode::Down | KeyCode::Char('j') => state.move_grabbed_job(false),
+        KeyCode::Enter | KeyCode::Char('m') => {
+            if let Some((job_id, position)) = state.drop_grabbed_job() {
+                return InputAction::MoveJob { job_id, position };
+            }
+        }
+        KeyCode::Esc => state.cancel_grab(),
+        _ => {}
+    }
+    InputAction::None
+}
+
 /// Gérer les touches dans la queue
 fn handle_queue_key(state: &mut AppState, key: KeyEvent) -> InputAction {
+    if state.grabbed_job.is_some() {
+        return handle_grab_key(state, key);
+    }
     match key.code {
         KeyCode::Up | KeyCode::Char('k') => {
             state.move_up();
@@ -419,6 +600,43 @@ fn handle_queue_key(state: &mut AppState, key: KeyEvent) -> InputAction {
             }
             InputAction::None
         }
+        KeyCode::Char('m') => {
+            // Saisir le job sélectionné : ↑↓ le déplacent, Entrée le dépose
+            state.grab_selected_job();
+            InputAction::None
+        }
+        KeyCode::Char('C') => {
+            // Annuler tout le lot du job sélectionné
+            let batch = state
+                .queue_jobs
+                .get(state.selected_index)
+                .and_then(|job| job.batch_id);
+            match batch {
+                Some(batch_id) => {
+                    let count = state
+                        .queue_jobs
+                        .iter()
+                        .chain(&state.active_jobs)
+                        .filter(|job| job.batch_id == Some(batch_id))
+                        .count();
+                    state.dialog = Some(Dialog::Confirm {
+                        message: format!(
+                            "Annuler les {count} jobs du lot {} ?",
+                            short_batch_id(batch_id)
+                        ),
+                        on_confirm: ConfirmAction::CancelBatch,
+                    });
+                }
+                None => state.set_status("Ce job ne fait pas partie d'un lot"),
+            }
+            InputAction::None
+        }
+        KeyCode::Enter => {
+            if let Some(job) = state.queue_jobs.get(state.selected_index) {
+                open_job_detail(state, job.clone());
+            }
+            InputAction::None
+        }
         KeyCode::Char('r') => InputAction::RefreshLists,
         _ => InputAction::None,
     }
@@ -445,11 +663,41 @@ fn handle_active_key(state: &mut AppState, key: KeyEvent) -> InputAction {
             }
             InputAction::None
         }
+        // + : baisser la priorité (nice +1), - : l'augmenter (nice -1)
+        KeyCode::Char(c @ ('+' | '-')) => {
+            if let Some(job) = state.active_jobs.get(state.selected_index) {
+                let delta = if c == '+' { 1 } else { -1 };
+                let nice = (job.nice.unwrap_or(0) + delta).clamp(-20, 19);
+                InputAction::SetJobPriority {
+                    job_id: job.id,
+                    nice,
+                }
+            } else {
+                InputAction::None
+            }
+        }
+        KeyCode::Enter => {
+            if let Some(job) = state.active_jobs.get(state.selected_index) {
+                open_job_detail(state, job.clone());
+            }
+            InputAction::None
+        }
         KeyCode::Char('r') => InputAction::RefreshLists,
         _ => InputAction::None,
     }
 }
 
+/// Gérer les touches dans la vue Statistiques
+fn handle_stats_key(state: &mut AppState, key: KeyEvent) -> InputAction {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => state.move_up(),
+        KeyCode::Down | KeyCode::Char('j') => state.move_down(),
+        KeyCode::Char('r') => state.reload_analytics(),
+        _ => {}
+    }
+    InputAction::None
+}
+
 /// Gérer les touches dans l'historique
 fn handle_history_key(state: &mut AppState, key: KeyEvent) -> InputAction {
     match key.code {
@@ -463,40 +711,29 @@ fn handle_history_key(state: &mut AppState, key: KeyEvent) -> InputAction {
         }
         KeyCode::Enter => {
             if let Some(job) = state.history_jobs.get(state.selected_index) {
-                if let Some(vmaf_path) = job.stats.as_ref().and_then(|s| s.vmaf_json_path.as_ref())
-                {
-                    if vmaf_path.exists() {
-                        let filename = job
-                            .input_path
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("unknown")
-                            .to_string();
-                        match VmafGraphData::from_json_file(vmaf_path, filename) {
-                            Ok(data) => {
-                                state.dialog = Some(Dialog::VmafGraph(data));
-                            }
-                            Err(e) => {
-                                state.dialog = Some(Dialog::Error {
-                                    message: format!("Erreur de lecture VMAF: {e}"),
-                                });
-                            }
-                        }
-                    } else {
-                        state.dialog = Some(Dialog::Error {
-                            message: "Fichier VMAF introuvable".to_string(),
-                        });
-                    }
-                }
+                open_job_detail(state, job.clone());
+            }
+            InputAction::None
+        }
+        KeyCode::Char('v') => {
+            if let Some(job) = state.history_jobs.get(state.selected_index) {
+                let job = job.clone();
+                open_vmaf_graph(state, &job);
             }
             InputAction::None
         }
         KeyCode::Char('r') => {
             // Retry un job failed
-            if let Some(job) = state.history_jobs.get(state.selected_index) {
-                if matches!(job.status, encodetalker_common::JobStatus::Failed) {
-                    return InputAction::RetryJob { job_id: job.id };
+            let failed = state
+                .history_jobs
+                .get(state.selected_index)
+                .filter(|job| matches!(job.status, encodetalker_common::JobStatus::Failed))
+                .map(|job| job.id);
+            if let Some(job_id) = failed {
+                if state.refuse_new_jobs() {
+                    return InputAction::None;
                 }
+                return InputAction::RetryJob { job_id };
             }
             InputAction::RefreshLists
         }
@@ -522,11 +759,133 @@ fn handle_history_key(state: &mut AppState, key: KeyEvent) -> InputAction {
     }
 }
 
+/// Ouvrir le détail d'un job
+fn open_job_detail(state: &mut AppState, job: EncodingJob) {
+    state.dialog = Some(Dialog::JobDetail {
+        job: Box::new(job),
+        scroll_offset: 0,
+    });
+}
+
+/// Faire défiler le détail d'un job d'une ligne
+fn scroll_job_detail(state: &mut AppState, down: bool) {
+    // Hauteur visible : zone du dialogue moins les bordures
+    let visible_lines = state
+        .layout
+        .dialog_area
+        .map_or(40, |area| area.height.saturating_sub(2) as usize);
+    let units = state.units;
+    if let Some(Dialog::JobDetail { job, scroll_offset }) = &mut state.dialog {
+        if down {
+            let max_scroll = job_detail_lines(job, units)
+                .len()
+                .saturating_sub(visible_lines);
+            *scroll_offset = (*scroll_offset + 1).min(max_scroll);
+        } else {
+            *scroll_offset = scroll_offset.saturating_sub(1);
+        }
+    }
+}
+
+/// Ouvrir le graphe VMAF par frame d'un job terminé (rien si VMAF n'a pas été calculé)
+fn open_vmaf_graph(state: &mut AppState, job: &EncodingJob) {
+    let Some(vmaf_path) = job.stats.as_ref().and_then(|s| s.vmaf_json_path.as_ref()) else {
+        state.set_status("Pas de scores VMAF pour ce job");
+        return;
+    };
+    if !vmaf_path.exists() {
+        state.dialog = Some(Dialog::Error {
+            message: "Fichier VMAF introuvable".to_string(),
+        });
+        return;
+    }
+    let filename = job
+        .input_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    state.dialog = Some(match VmafGraphData::from_json_file(vmaf_path, filename) {
+        Ok(data) => Dialog::VmafGraph(data),
+        Err(e) => Dialog::Error {
+            message: format!("Erreur de lecture VMAF: {e}"),
+        },
+    });
+}
+
+/// Gérer les touches de la maintenance : cocher les cibles, puis confirmer le nettoyage
+fn handle_maintenance_key(state: &mut AppState, key: KeyEvent) -> InputAction {
+    let Some(Dialog::Maintenance(maintenance)) = &mut state.dialog else {
+        return InputAction::None;
+    };
+    if maintenance.confirming {
+        maintenance.confirming = false;
+        return match key.code {
+            KeyCode::Char('y' | 'Y' | 'o' | 'O') | KeyCode::Enter => InputAction::Cleanup {
+                targets: maintenance.targets(),
+            },
+            _ => InputAction::None,
+        };
+    }
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => state.dialog = None,
+        KeyCode::Up | KeyCode::Char('k') => maintenance.move_selection(false),
+        KeyCode::Down | KeyCode::Char('j') => maintenance.move_selection(true),
+        KeyCode::Char(' ') => maintenance.toggle_selected(),
+        KeyCode::Char('r') => return InputAction::EstimateCleanup,
+        KeyCode::Enter => {
+            if maintenance.checked.is_empty() {
+                state.set_status("Aucune cible cochée (Espace pour cocher)");
+            } else {
+                maintenance.confirming = true;
+            }
+        }
+        _ => {}
+    }
+    InputAction::None
+}
+
+/// Gérer les touches de la recherche globale
+fn handle_search_key(state: &mut AppState, key: KeyEvent) -> InputAction {
+    let Some(Dialog::Search(search)) = &state.dialog else {
+        return InputAction::None;
+    };
+    let hits = state.search_jobs(&search.query);
+    let Some(Dialog::Search(search)) = &mut state.dialog else {
+        return InputAction::None;
+    };
+    match key.code {
+        KeyCode::Esc => state.dialog = None,
+        KeyCode::Up => search.move_selection(false, hits.len()),
+        KeyCode::Down => search.move_selection(true, hits.len()),
+        KeyCode::Enter => {
+            if let Some(hit) = hits.get(search.selected) {
+                state.jump_to_search_hit(hit);
+            }
+        }
+        _ => {
+            if edit_text_input(&mut search.query, &mut search.cursor, key) {
+                search.selected = 0;
+            }
+        }
+    }
+    InputAction::None
+}
+
 /// Gérer les touches dans un dialogue
 fn handle_dialog_key(state: &mut AppState, key: KeyEvent) -> InputAction {
     let dialog = state.dialog.clone();
     match dialog {
-        Some(Dialog::EncodeConfig(_)) => handle_encode_config_dialog_key(state, key),
+        Some(Dialog::EncodeConfig(_)) => {
+            let action = handle_encode_config_dialog_key(state, key);
+            // Le nom par défaut suit l'encodeur et le CRF choisis, l'espace libre suit
+            // la destination
+            if let Some(Dialog::EncodeConfig(ref mut config)) = state.dialog {
+                config.refresh_default_output_path();
+                config.refresh_free_space();
+            }
+            action
+        }
         Some(Dialog::Confirm { on_confirm, .. }) => {
             handle_confirm_dialog_key(state, key, on_confirm)
         }
@@ -535,6 +894,25 @@ fn handle_dialog_key(state: &mut AppState, key: KeyEvent) -> InputAction {
             state.dialog = None;
             InputAction::None
         }
+        Some(Dialog::Benchmark(_)) => {
+            // Fermer n'annule pas le benchmark : le résultat s'affichera dans la barre de status
+            if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                state.dialog = None;
+            }
+            InputAction::None
+        }
+        Some(Dialog::JobDetail { job, .. }) => {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => state.dialog = None,
+                KeyCode::Up | KeyCode::Char('k') => scroll_job_detail(state, false),
+                KeyCode::Down | KeyCode::Char('j') => scroll_job_detail(state, true),
+                KeyCode::Char('v') => open_vmaf_graph(state, &job),
+                _ => {}
+            }
+            InputAction::None
+        }
+        Some(Dialog::Maintenance(_)) => handle_maintenance_key(state, key),
+        Some(Dialog::Search(_)) => handle_search_key(state, key),
         Some(Dialog::VmafGraph(_)) => {
             if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
                 state.dialog = None;
@@ -579,68 +957,132 @@ fn handle_dialog_key(state: &mut AppState, key: KeyEvent) -> InputAction {
     }
 }
 
-/// Gérer l'édition du chemin de sortie
-fn handle_output_path_editing(config: &mut EncodeConfigDialog, key: KeyEvent) -> InputAction {
+/// Édition d'une saisie texte avec curseur (chemin de sortie, paramètres extra)
+///
+/// Retourne true si la saisie a été modifiée.
+fn edit_text_input(text: &mut String, cursor: &mut usize, key: KeyEvent) -> bool {
     match key.code {
-        KeyCode::Esc => {
-            // Annuler et restaurer
-            config.output_path_string = config.output_path.display().to_string();
-            config.is_editing_output = false;
-            InputAction::None
-        }
-        KeyCode::Enter => {
-            config.stop_editing_output();
-            InputAction::None
-        }
         KeyCode::Left => {
-            if config.output_path_cursor > 0 {
-                config.output_path_cursor -= 1;
+            if *cursor > 0 {
+                *cursor -= 1;
             }
-            InputAction::None
+            false
         }
         KeyCode::Right => {
-            let char_count = config.output_path_string.chars().count();
-            if config.output_path_cursor < char_count {
-                config.output_path_cursor += 1;
+            let char_count = text.chars().count();
+            if *cursor < char_count {
+                *cursor += 1;
             }
-            InputAction::None
+            false
         }
         KeyCode::Home => {
-            config.output_path_cursor = 0;
-            InputAction::None
+            *cursor = 0;
+            false
         }
         KeyCode::End => {
-            config.output_path_cursor = config.output_path_string.chars().count();
-            InputAction::None
+            *cursor = text.chars().count();
+            false
         }
         KeyCode::Backspace => {
-            if config.output_path_cursor > 0 {
-                let mut chars: Vec<char> = config.output_path_string.chars().collect();
-                if config.output_path_cursor <= chars.len() {
-                    chars.remove(config.output_path_cursor - 1);
-                    config.output_path_string = chars.into_iter().collect();
-                    config.output_path_cursor -= 1;
+            if *cursor > 0 {
+                let mut chars: Vec<char> = text.chars().collect();
+                if *cursor <= chars.len() {
+                    chars.remove(*cursor - 1);
+                    *text = chars.into_iter().collect();
+                    *cursor -= 1;
+                    return true;
                 }
             }
-            InputAction::None
+            false
         }
         KeyCode::Delete => {
-            let mut chars: Vec<char> = config.output_path_string.chars().collect();
-            if config.output_path_cursor < chars.len() {
-                chars.remove(config.output_path_cursor);
-                config.output_path_string = chars.into_iter().collect();
+            let mut chars: Vec<char> = text.chars().collect();
+            if *cursor < chars.len() {
+                chars.remove(*cursor);
+                *text = chars.into_iter().collect();
+                return true;
             }
-            InputAction::None
+            false
         }
         KeyCode::Char(c) => {
-            let mut chars: Vec<char> = config.output_path_string.chars().collect();
-            chars.insert(config.output_path_cursor, c);
-            config.output_path_string = chars.into_iter().collect();
-            config.output_path_cursor += 1;
-            InputAction::None
+            let mut chars: Vec<char> = text.chars().collect();
+            chars.insert(*cursor, c);
+            *text = chars.into_iter().collect();
+            *cursor += 1;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Gérer l'édition du chemin de sortie
+fn handle_output_path_editing(config: &mut EncodeConfigDialog, key: KeyEvent) -> InputAction {
+    match key.code {
+        KeyCode::Esc => {
+            // Annuler et restaurer
+            config.output_path_string = config.output_path.display().to_string();
+            config.is_editing_output = false;
+        }
+        KeyCode::Enter => config.stop_editing_output(),
+        _ => {
+            edit_text_input(
+                &mut config.output_path_string,
+                &mut config.output_path_cursor,
+                key,
+            );
+        }
+    }
+    InputAction::None
+}
+
+/// Gérer l'édition des paramètres extra de l'encodeur
+fn handle_extra_params_editing(state: &mut AppState, key: KeyEvent) -> InputAction {
+    let Some(Dialog::EncodeConfig(ref mut config)) = state.dialog else {
+        return InputAction::None;
+    };
+    match key.code {
+        KeyCode::Esc => config.cancel_editing_extra(),
+        KeyCode::Enter => {
+            if let Err(msg) = config.stop_editing_extra() {
+                state.set_status(msg);
+            }
+        }
+        _ => {
+            if edit_text_input(
+                &mut config.extra_params_string,
+                &mut config.extra_params_cursor,
+                key,
+            ) {
+                config.sync_extra_params();
+            }
+        }
+    }
+    InputAction::None
+}
+
+/// Gérer l'édition des langues des pistes gardées
+fn handle_languages_editing(state: &mut AppState, key: KeyEvent) -> InputAction {
+    let Some(Dialog::EncodeConfig(ref mut config)) = state.dialog else {
+        return InputAction::None;
+    };
+    match key.code {
+        KeyCode::Esc => config.cancel_editing_languages(),
+        KeyCode::Enter => {
+            if let Err(msg) = config.stop_editing_languages() {
+                state.set_status(msg);
+            }
+        }
+        _ => {
+            if edit_text_input(
+                &mut config.languages_string,
+                &mut config.languages_cursor,
+                key,
+            ) {
+                config.sync_languages();
+            }
         }
-        _ => InputAction::None,
     }
+    InputAction::None
 }
 
 /// Gérer les touches dans le dialogue de config d'encodage
@@ -650,6 +1092,19 @@ fn handle_encode_config_dialog_key(state: &mut AppState, key: KeyEvent) -> Input
         if config.is_editing_output && !config.is_batch() {
             return handle_output_path_editing(config, key);
         }
+        if config.is_editing_extra {
+            return handle_extra_params_editing(state, key);
+        }
+        // Défilement des pistes de la source, pendant l'édition des langues comprise
+        if config.selected_field == EncodeConfigDialog::LANGUAGES_FIELD
+            && matches!(key.code, KeyCode::PageUp | KeyCode::PageDown)
+        {
+            config.tracks.scroll_page(key.code == KeyCode::PageDown);
+            return InputAction::None;
+        }
+        if config.is_editing_languages {
+            return handle_languages_editing(state, key);
+        }
 
         match key.code {
             KeyCode::Esc => {
@@ -665,11 +1120,23 @@ fn handle_encode_config_dialog_key(state: &mut AppState, key: KeyEvent) -> Input
                 return InputAction::None;
             }
             KeyCode::Left | KeyCode::Right => {
-                // Si sur field 7 (output path) et batch, ne rien faire
-                if config.selected_field == 7 && key.code == KeyCode::Right {
+                // Si sur le champ output path et batch, ne rien faire
+                if config.selected_field == EncodeConfigDialog::OUTPUT_FIELD
+                    && key.code == KeyCode::Right
+                {
                     if !config.is_batch() {
                         config.start_editing_output();
                     }
+                } else if config.selected_field == EncodeConfigDialog::EXTRA_FIELD
+                    && key.code == KeyCode::Right
+                {
+                    if config.is_field_supported(EncodeConfigDialog::EXTRA_FIELD) {
+                        config.start_editing_extra();
+                    }
+                } else if config.selected_field == EncodeConfigDialog::LANGUAGES_FIELD
+                    && key.code == KeyCode::Right
+                {
+                    config.start_editing_languages();
                 } else {
                     toggle_field_value(config, key.code == KeyCode::Right);
                 }
@@ -678,11 +1145,42 @@ fn handle_encode_config_dialog_key(state: &mut AppState, key: KeyEvent) -> Input
 
             // Validation avec logique batch
             KeyCode::Enter => {
-                // Si sur field 7 et pas batch, activer l'édition
-                if config.selected_field == 7 && !config.is_batch() {
+                // Si sur le champ output path et pas batch, activer l'édition
+                if config.selected_field == EncodeConfigDialog::OUTPUT_FIELD && !config.is_batch() {
                     config.start_editing_output();
                     return InputAction::None;
                 }
+                if config.selected_field == EncodeConfigDialog::EXTRA_FIELD
+                    && config.is_field_supported(EncodeConfigDialog::EXTRA_FIELD)
+                {
+                    config.start_editing_extra();
+                    return InputAction::None;
+                }
+                if config.selected_field == EncodeConfigDialog::LANGUAGES_FIELD {
+                    config.start_editing_languages();
+                    return InputAction::None;
+                }
+
+                // Découpe invalide : rester dans le dialogue
+                if let Err(msg) = config.sync_trim() {
+                    state.set_status(msg);
+                    return InputAction::None;
+                }
+                // Configuration refusée par le daemon : le signaler tout de suite
+                if let Err(msg) = config.config.validate() {
+                    state.set_status(msg);
+                    return InputAction::None;
+                }
+                // Dimensions impaires ou hors limites : avertir une fois avant d'ajouter
+                if let Some(warning) = config.dimensions_warning() {
+                    if !config.dimensions_acknowledged {
+                        config.dimensions_acknowledged = true;
+                        state.set_status(format!(
+                            "{warning} | Ctrl+E: Recadrer au pair | Entrée: Ajouter quand même"
+                        ));
+                        return InputAction::None;
+                    }
+                }
 
                 // Early return pour clarté
                 if !config.is_batch() {
@@ -704,14 +1202,14 @@ fn handle_encode_config_dialog_key(state: &mut AppState, key: KeyEvent) -> Input
                 // Batch jobs
                 {
                     let encoding_config = config.config.clone();
-                    // Créer plusieurs jobs
-                    let jobs: Vec<(std::path::PathBuf, std::path::PathBuf)> = config
+                    // Créer plusieurs jobs ; deux sources de même nom ne doivent pas
+                    // s'écraser l'une l'autre
+                    let (outputs, renamed) = config.batch_output_paths();
+                    let jobs: Vec<(std::path::PathBuf, std::path::PathBuf, u32)> = config
                         .input_paths
                         .iter()
-                        .map(|input| {
-                            let output = generate_output_path(input);
-                            (input.clone(), output)
-                        })
+                        .zip(outputs)
+                        .map(|(input, output)| (input.clone(), output, config.crf_for_input(input)))
                         .collect();
 
                     state.dialog = None;
@@ -723,9 +1221,48 @@ fn handle_encode_config_dialog_key(state: &mut AppState, key: KeyEvent) -> Input
                     return InputAction::AddBatchJobs {
                         jobs,
                         config: encoding_config,
+                        renamed,
                     };
                 }
             }
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                config.toggle_crop_to_even();
+                return InputAction::None;
+            }
+            // Ctrl+T : extrait de test de la première source, pour juger qualité et taille
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Err(msg) = config.sync_trim() {
+                    state.set_status(msg);
+                    return InputAction::None;
+                }
+                if let Err(msg) = config.config.validate() {
+                    state.set_status(msg);
+                    return InputAction::None;
+                }
+                let input_path = config.input_paths[0].clone();
+                let mut encoding_config = config.config.clone();
+                let output_path = if config.is_batch() {
+                    // CRF que recevra cette source dans le batch
+                    encoding_config.encoder_params.crf = config.crf_for_input(&input_path);
+                    config.default_output_path(&input_path)
+                } else {
+                    config.output_path.clone()
+                };
+                return InputAction::AddPreviewJob {
+                    input_path,
+                    output_path,
+                    config: encoding_config,
+                };
+            }
+            // Saisie des timestamps de découpe (champs 8 et 9)
+            KeyCode::Char(c) => {
+                config.push_trim_char(c);
+                return InputAction::None;
+            }
+            KeyCode::Backspace => {
+                config.pop_trim_char();
+                return InputAction::None;
+            }
             _ => {}
         }
     }
@@ -733,45 +1270,33 @@ fn handle_encode_config_dialog_key(state: &mut AppState, key: KeyEvent) -> Input
     InputAction::None
 }
 
-/// Générer le chemin de sortie pour un fichier d'entrée
-fn generate_output_path(input: &std::path::Path) -> std::path::PathBuf {
-    let mut output = input.to_path_buf();
-    output.set_extension("");
-    std::path::PathBuf::from(format!("{}.av1.mkv", output.display()))
-}
-
 /// Changer la valeur d'un champ dans le dialogue de config
 #[allow(clippy::match_same_arms)]
 fn toggle_field_value(config: &mut EncodeConfigDialog, increment: bool) {
+    // Champ non supporté par les encodeurs installés : ne rien modifier
+    if !config.is_field_supported(config.selected_field) {
+        return;
+    }
+
     match config.selected_field {
         0 => {
-            // Encodeur
-            config.config.encoder = match config.config.encoder {
-                EncoderType::SvtAv1 => EncoderType::Aom,
-                EncoderType::Aom => EncoderType::SvtAv1,
-            };
+            // Encodeur (ou copie de la vidéo source)
+            config.cycle_video_choice(increment);
         }
         1 => {
             // Audio mode
-            config.config.audio_mode = match config.config.audio_mode {
-                AudioMode::Opus { .. } => AudioMode::Copy,
-                AudioMode::Copy | AudioMode::Custom { .. } => AudioMode::Opus { bitrate: 128 },
-            };
+            config.cycle_audio_mode(increment);
         }
         2 => {
-            // CRF
-            if increment && config.config.encoder_params.crf < 51 {
-                config.config.encoder_params.crf += 1;
-            } else if !increment && config.config.encoder_params.crf > 0 {
-                config.config.encoder_params.crf -= 1;
-            }
+            // CRF, dans la plage de l'encodeur choisi
+            let encoder = config.config.encoder;
+            config.config.encoder_params.step_crf(encoder, increment);
+            // CRF choisi à la main : plus de CRF par résolution
+            config.crf_edited = true;
         }
         3 => {
             // Preset
-            let max_preset = match config.config.encoder {
-                EncoderType::SvtAv1 => 13,
-                EncoderType::Aom => 8,
-            };
+            let max_preset = config.config.encoder.max_preset();
             if increment && config.config.encoder_params.preset < max_preset {
                 config.config.encoder_params.preset += 1;
             } else if !increment && config.config.encoder_params.preset > 0 {
@@ -809,8 +1334,20 @@ fn toggle_field_value(config: &mut EncodeConfigDialog, increment: bool) {
             }
         }
         5 => {
-            // VMAF toggle
-            config.config.enable_vmaf = !config.config.enable_vmaf;
+            // Qualité mesurée : aucune → VMAF → VMAF + PSNR/SSIM
+            let levels = [(false, false), (true, false), (true, true)];
+            let current = levels
+                .iter()
+                .position(|&level| {
+                    level == (config.config.enable_vmaf, config.config.quality_metrics)
+                })
+                .unwrap_or(0);
+            let next = if increment {
+                (current + 1) % levels.len()
+            } else {
+                (current + levels.len() - 1) % levels.len()
+            };
+            (config.config.enable_vmaf, config.config.quality_metrics) = levels[next];
         }
         6 => {
             // Content Type: cycle Default → Anime → LiveAction → GrainedFilm → Default
@@ -845,9 +1382,76 @@ fn toggle_field_value(config: &mut EncodeConfigDialog, increment: bool) {
                         }
                     }
                 };
+            // Film granuleux : le tune subjectif préserve mieux le grain
+            if config.config.encoder_params.content_type == VideoContentType::GrainedFilm {
+                config.config.encoder_params.tune = Tune::Subjective;
+            }
         }
         7 => {
-            // Output path: géré par le mode édition, ne rien faire ici
+            // Tune : cycle parmi les valeurs supportées par l'encodeur
+            let options = Tune::options_for(config.config.encoder);
+            let current = options
+                .iter()
+                .position(|t| *t == config.config.encoder_params.tune)
+                .unwrap_or(0);
+            let next = if increment {
+                (current + 1) % options.len()
+            } else {
+                (current + options.len() - 1) % options.len()
+            };
+            config.config.encoder_params.tune = options[next];
+        }
+        10 => {
+            // Découpage : Aucun → Chapitres → 5/10/15/30/60 min → Aucun
+            const INTERVALS: [u32; 5] = [300, 600, 900, 1800, 3600];
+            let mut choices = vec![SplitMode::None, SplitMode::Chapters];
+            choices.extend(INTERVALS.iter().map(|&secs| SplitMode::Interval { secs }));
+            let current = choices
+                .iter()
+                .position(|c| *c == config.config.split)
+                .unwrap_or(0);
+            let next = if increment {
+                (current + 1) % choices.len()
+            } else {
+                (current + choices.len() - 1) % choices.len()
+            };
+            config.config.split = choices[next];
+        }
+        11 => {
+            // Débit : CRF seul → CRF plafonné (2 à 20 Mbps) → débit moyen (1 à 8 Mbps)
+            const CAPS: [u32; 6] = [2000, 4000, 6000, 8000, 12000, 20000];
+            const AVERAGES: [u32; 5] = [1000, 2000, 4000, 6000, 8000];
+            let mut choices = vec![EncodingMode::Crf];
+            choices.extend(
+                CAPS.iter()
+                    .map(|&max_bitrate| EncodingMode::ConstrainedQuality { max_bitrate }),
+            );
+            choices.extend(
+                AVERAGES
+                    .iter()
+                    .map(|&bitrate| EncodingMode::AverageBitrate {
+                        bitrate,
+                        max_bitrate: None,
+                    }),
+            );
+            let params = &mut config.config.encoder_params;
+            let current = choices
+                .iter()
+                .position(|c| *c == params.encoding_mode)
+                .unwrap_or(0);
+            let next = if increment {
+                (current + 1) % choices.len()
+            } else {
+                (current + choices.len() - 1) % choices.len()
+            };
+            params.encoding_mode = choices[next];
+        }
+        8
+        | 9
+        | EncodeConfigDialog::EXTRA_FIELD
+        | EncodeConfigDialog::LANGUAGES_FIELD
+        | EncodeConfigDialog::OUTPUT_FIELD => {
+            // Découpe, paramètres extra, langues et output path: gérés par la saisie texte
         }
         _ => {
             // Autres cas: ne rien faire
@@ -855,14 +1459,31 @@ fn toggle_field_value(config: &mut EncodeConfigDialog, increment: bool) {
     }
 }
 
+/// Identifiant de lot abrégé pour l'affichage (8 premiers caractères)
+#[must_use]
+pub fn short_batch_id(batch_id: uuid::Uuid) -> String {
+    batch_id.simple().to_string()[..8].to_string()
+}
+
 /// Gérer les touches dans le dialogue de confirmation
 fn handle_confirm_dialog_key(
     state: &mut AppState,
     key: KeyEvent,
     on_confirm: ConfirmAction,
 ) -> InputAction {
+    // 's' : confirmer en supprimant aussi les fichiers de sortie (historique uniquement)
+    let delete_outputs = matches!(key.code, KeyCode::Char('s' | 'S'));
+    if delete_outputs
+        && !matches!(
+            on_confirm,
+            ConfirmAction::RemoveFromHistory | ConfirmAction::ClearHistory
+        )
+    {
+        return InputAction::None;
+    }
+
     match key.code {
-        KeyCode::Char('y' | 'Y' | 'o' | 'O') | KeyCode::Enter => {
+        KeyCode::Char('y' | 'Y' | 'o' | 'O' | 's' | 'S') | KeyCode::Enter => {
             // Confirmer
             state.dialog = None;
 
@@ -879,18 +1500,31 @@ fn handle_confirm_dialog_key(
                         return InputAction::CancelJob { job_id };
                     }
                 }
+                ConfirmAction::CancelBatch => {
+                    let batch = state
+                        .queue_jobs
+                        .get(state.selected_index)
+                        .and_then(|job| job.batch_id);
+                    if let Some(batch_id) = batch {
+                        return InputAction::CancelBatch { batch_id };
+                    }
+                }
                 ConfirmAction::RemoveFromHistory => {
                     if let Some(job) = state.history_jobs.get(state.selected_index) {
-                        return InputAction::RemoveFromHistory { job_id: job.id };
+                        return InputAction::RemoveFromHistory {
+                            job_id: job.id,
+                            delete_outputs,
+                        };
                     }
                 }
                 ConfirmAction::ClearHistory => {
-                    return InputAction::ClearHistory;
+                    return InputAction::ClearHistory { delete_outputs };
                 }
                 ConfirmAction::Quit => {
                     state.should_quit = true;
                     return InputAction::None;
                 }
+                ConfirmAction::RestartDaemon => return InputAction::RestartDaemon,
             }
 
             InputAction::None
@@ -2,28 +2,73 @@ use anyhow::{Context, Result};
 use encodetalker_common::ipc::{IpcListener, IpcStream};
 use futures::{SinkExt, StreamExt};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
 use tokio::sync::Mutex;
+use tokio::sync::{mpsc, watch};
 use tokio_serde::{formats::Bincode, Framed as SerdeFramed};
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use encodetalker_common::{
-    protocol::messages::DepsStatusInfo, EncodingConfig, EncodingJob, Event, IpcMessage, Request,
-    RequestPayload, Response, ResponsePayload,
+    protocol::messages::{
+        BatchJobSpec, BuildEnvReport, DaemonStatusInfo, DepsStatusInfo, ImportedPreset,
+        QueueSnapshot,
+    },
+    CleanupResult, CleanupTarget, EncoderCapabilities, EncoderType, EncodingConfig, EncodingJob,
+    Event, IpcMessage, Request, RequestPayload, Response, ResponsePayload,
 };
 
+type PendingResponses = Arc<Mutex<HashMap<Uuid, tokio::sync::oneshot::Sender<Response>>>>;
+type IpcFramed = SerdeFramed<
+    Framed<IpcStream, LengthDelimitedCodec>,
+    IpcMessage,
+    IpcMessage,
+    Bincode<IpcMessage, IpcMessage>,
+>;
+
+/// Premier délai avant une tentative de reconnexion
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(250);
+/// Délai maximal entre deux tentatives de reconnexion
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// État de la connexion au daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Connecté ; `generation` augmente à chaque reconnexion réussie
+    Connected { generation: u64 },
+    /// Connexion perdue, tentative de reconnexion en cours
+    Reconnecting { attempt: u32 },
+}
+
+/// Métadonnées d'une vidéo probée par le daemon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoProbe {
+    pub duration_secs: Option<f64>,
+    pub size_bytes: u64,
+    /// Hauteur du stream vidéo (None = inconnue)
+    pub height: Option<u32>,
+    /// Canaux de la première piste audio (None = pas d'audio ou inconnu)
+    pub audio_channels: Option<u32>,
+}
+
 /// Client IPC pour communiquer avec le daemon
+///
+/// Si le daemon redémarre, le client se reconnecte au socket avec un délai croissant ;
+/// les requêtes échouent immédiatement tant que la connexion n'est pas rétablie.
+/// Les clones partagent la même connexion (requêtes lancées en arrière-plan).
+#[derive(Clone)]
 pub struct IpcClient {
     /// Sender pour envoyer des requêtes
     request_tx: mpsc::UnboundedSender<Request>,
     /// Receiver pour recevoir des événements
     event_rx: Arc<Mutex<mpsc::UnboundedReceiver<Event>>>,
     /// Map des pending responses (par `request_id`)
-    pending_responses: Arc<Mutex<HashMap<Uuid, tokio::sync::oneshot::Sender<Response>>>>,
+    pending_responses: PendingResponses,
+    /// État de la connexion, mis à jour par la tâche de connexion
+    state_rx: watch::Receiver<ConnectionState>,
 }
 
 impl IpcClient {
@@ -33,81 +78,48 @@ impl IpcClient {
     ///
     /// Retourne une erreur si la connexion au socket échoue.
     pub async fn connect(socket_path: impl AsRef<Path>) -> Result<Self> {
-        let stream = IpcStream::connect(socket_path.as_ref())
+        let socket_path = socket_path.as_ref().to_path_buf();
+        let framed = open_framed(&socket_path)
             .await
             .context("Échec de connexion au daemon")?;
 
         info!("Connecté au daemon");
 
-        // Setup framing
-        let length_framed = Framed::new(stream, LengthDelimitedCodec::new());
-        let framed = SerdeFramed::new(length_framed, Bincode::<IpcMessage, IpcMessage>::default());
-
-        // Split pour lecture et écriture
-        let (mut writer, mut reader) = framed.split();
-
         // Channels pour communication interne
-        let (request_tx, mut request_rx) = mpsc::unbounded_channel::<Request>();
+        let (request_tx, request_rx) = mpsc::unbounded_channel::<Request>();
         let (event_tx, event_rx) = mpsc::unbounded_channel::<Event>();
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected { generation: 0 });
 
-        let pending_responses: Arc<Mutex<HashMap<Uuid, tokio::sync::oneshot::Sender<Response>>>> =
-            Arc::new(Mutex::new(HashMap::new()));
+        let pending_responses: PendingResponses = Arc::new(Mutex::new(HashMap::new()));
 
-        // Tâche d'écriture (envoyer les requêtes)
-        tokio::spawn(async move {
-            while let Some(request) = request_rx.recv().await {
-                debug!("Envoi requête: {:?}", request.payload);
-                if let Err(e) = writer.send(IpcMessage::Request(request)).await {
-                    error!("Échec d'envoi de requête: {}", e);
-                    break;
-                }
-            }
-        });
-
-        // Tâche de lecture (recevoir réponses et événements)
-        let pending_responses_clone = pending_responses.clone();
-        tokio::spawn(async move {
-            while let Some(msg) = reader.next().await {
-                match msg {
-                    Ok(IpcMessage::Response(response)) => {
-                        debug!("Réponse reçue pour request_id: {}", response.request_id);
-
-                        // Vérifier si c'est une réponse attendue
-                        let mut pending = pending_responses_clone.lock().await;
-                        if let Some(tx) = pending.remove(&response.request_id) {
-                            let _ = tx.send(response);
-                        } else {
-                            // Réponse non attendue, on l'ignore
-                            debug!(
-                                "Réponse non attendue pour request_id: {}",
-                                response.request_id
-                            );
-                        }
-                    }
-                    Ok(IpcMessage::Event(event)) => {
-                        debug!("Événement reçu: {:?}", event.payload);
-                        let _ = event_tx.send(event);
-                    }
-                    Ok(IpcMessage::Request(_)) => {
-                        error!("Requête reçue côté client (inattendu)");
-                    }
-                    Err(e) => {
-                        error!("Erreur de lecture: {}", e);
-                        break;
-                    }
-                }
-            }
-        });
+        tokio::spawn(supervise_connection(
+            socket_path,
+            framed,
+            request_rx,
+            event_tx,
+            pending_responses.clone(),
+            state_tx,
+        ));
 
         Ok(Self {
             request_tx,
             event_rx: Arc::new(Mutex::new(event_rx)),
             pending_responses,
+            state_rx,
         })
     }
 
+    /// État actuel de la connexion au daemon
+    #[must_use]
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.state_rx.borrow()
+    }
+
     /// Envoyer une requête et attendre la réponse
     async fn send_request(&self, payload: RequestPayload) -> Result<Response> {
+        if let ConnectionState::Reconnecting { .. } = self.connection_state() {
+            anyhow::bail!("Daemon déconnecté, reconnexion en cours");
+        }
         let request = Request::new(payload);
         let request_id = request.id;
 
@@ -146,7 +158,7 @@ impl IpcClient {
             .send_request(RequestPayload::AddJob {
                 input_path,
                 output_path,
-                config,
+                config: Box::new(config),
             })
             .await?;
 
@@ -157,6 +169,159 @@ impl IpcClient {
         }
     }
 
+    /// Ajouter un extrait de test des `duration_secs` premières secondes, écrit dans
+    /// `<sortie>.preview.<ext>`
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon refuse le job.
+    pub async fn add_preview_job(
+        &self,
+        input_path: std::path::PathBuf,
+        output_path: std::path::PathBuf,
+        config: EncodingConfig,
+        duration_secs: u32,
+    ) -> Result<Uuid> {
+        let response = self
+            .send_request(RequestPayload::AddPreviewJob {
+                input_path,
+                output_path,
+                config: Box::new(config),
+                duration_secs,
+            })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::JobId { job_id } => Ok(job_id),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Ajouter un job dont la configuration est lue par le daemon dans un fichier
+    ///
+    /// Sans `output_path`, la sortie est nommée par le daemon.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le fichier est invalide.
+    pub async fn add_job_from_file(
+        &self,
+        input_path: std::path::PathBuf,
+        output_path: Option<std::path::PathBuf>,
+        config_path: std::path::PathBuf,
+    ) -> Result<Uuid> {
+        let response = self
+            .send_request(RequestPayload::AddJobFromFile {
+                input_path,
+                output_path,
+                config_path,
+            })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::JobId { job_id } => Ok(job_id),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Nettoyer des cibles de maintenance, ou seulement estimer l'espace libérable
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue.
+    pub async fn cleanup(
+        &self,
+        targets: Vec<CleanupTarget>,
+        dry_run: bool,
+    ) -> Result<Vec<CleanupResult>> {
+        let response = self
+            .send_request(RequestPayload::Cleanup { targets, dry_run })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::CleanupReport { results } => Ok(results),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Convertir les presets d'un export JSON de Handbrake, lu par le daemon
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le fichier n'est pas un export valide.
+    pub async fn import_handbrake_presets(&self, path: PathBuf) -> Result<Vec<ImportedPreset>> {
+        let response = self
+            .send_request(RequestPayload::ImportHandbrakePresets { path })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::ImportedPresets { presets } => Ok(presets),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Ajouter un job de vérification d'un fichier existant
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon refuse le job.
+    pub async fn verify_file(&self, path: std::path::PathBuf) -> Result<Uuid> {
+        let response = self
+            .send_request(RequestPayload::VerifyFile { path })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::JobId { job_id } => Ok(job_id),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Ajouter des jobs sous un même identifiant de lot
+    ///
+    /// Retourne l'identifiant du lot et les fichiers refusés avec la raison.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue.
+    pub async fn add_batch_jobs(
+        &self,
+        jobs: Vec<BatchJobSpec>,
+    ) -> Result<(Uuid, Vec<(std::path::PathBuf, String)>)> {
+        let response = self
+            .send_request(RequestPayload::AddBatchJobs { jobs })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::BatchAdded {
+                batch_id, errors, ..
+            } => Ok((batch_id, errors)),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Annuler les jobs d'un lot encore en attente ou en cours
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si aucun job du lot n'est annulable.
+    pub async fn cancel_batch(&self, batch_id: Uuid) -> Result<usize> {
+        let response = self
+            .send_request(RequestPayload::CancelBatch { batch_id })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::BatchCancelled { cancelled } => Ok(cancelled),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
     /// Annuler un job
     ///
     /// # Errors
@@ -174,6 +339,40 @@ impl IpcClient {
         }
     }
 
+    /// Déplacer un job en attente à `position` dans la queue
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
+    pub async fn move_job(&self, job_id: Uuid, position: usize) -> Result<()> {
+        let response = self
+            .send_request(RequestPayload::MoveJob { job_id, position })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::Ok => Ok(()),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Changer la niceness d'un job en cours
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
+    pub async fn set_job_priority(&self, job_id: Uuid, nice: i32) -> Result<()> {
+        let response = self
+            .send_request(RequestPayload::SetJobPriority { job_id, nice })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::Ok => Ok(()),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
     /// Retry un job failed
     ///
     /// # Errors
@@ -236,14 +435,32 @@ impl IpcClient {
         }
     }
 
+    /// Obtenir un job (en attente, en cours ou terminé)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le job est inconnu.
+    pub async fn get_job(&self, job_id: Uuid) -> Result<EncodingJob> {
+        let response = self.send_request(RequestPayload::GetJob { job_id }).await?;
+
+        match response.payload {
+            ResponsePayload::Job { job } => Ok(*job),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
     /// Supprimer un job spécifique de l'historique
     ///
     /// # Errors
     ///
     /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
-    pub async fn remove_from_history(&self, job_id: Uuid) -> Result<()> {
+    pub async fn remove_from_history(&self, job_id: Uuid, delete_outputs: bool) -> Result<()> {
         let response = self
-            .send_request(RequestPayload::RemoveFromHistory { job_id })
+            .send_request(RequestPayload::RemoveFromHistory {
+                job_id,
+                delete_outputs,
+            })
             .await?;
         match response.payload {
             ResponsePayload::Ok => Ok(()),
@@ -257,8 +474,10 @@ impl IpcClient {
     /// # Errors
     ///
     /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
-    pub async fn clear_history(&self) -> Result<()> {
-        let response = self.send_request(RequestPayload::ClearHistory).await?;
+    pub async fn clear_history(&self, delete_outputs: bool) -> Result<()> {
+        let response = self
+            .send_request(RequestPayload::ClearHistory { delete_outputs })
+            .await?;
 
         match response.payload {
             ResponsePayload::Ok => Ok(()),
@@ -281,6 +500,21 @@ impl IpcClient {
         }
     }
 
+    /// Demander l'arrêt du daemon (les jobs actifs suivent `daemon.shutdown_mode`)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon refuse.
+    pub async fn shutdown(&self) -> Result<()> {
+        let response = self.send_request(RequestPayload::Shutdown).await?;
+
+        match response.payload {
+            ResponsePayload::Ok => Ok(()),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
     /// Obtenir l'état de compilation des dépendances
     ///
     /// # Errors
@@ -296,12 +530,120 @@ impl IpcClient {
         }
     }
 
-    /// Prober une vidéo pour récupérer ses métadonnées (durée, taille)
+    /// Récupérer l'état général du daemon (jobs acceptés ou non)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
+    pub async fn get_daemon_status(&self) -> Result<DaemonStatusInfo> {
+        let response = self.send_request(RequestPayload::GetDaemonStatus).await?;
+
+        match response.payload {
+            ResponsePayload::DaemonStatus { status } => Ok(status),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Récupérer les capacités des encodeurs installés
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
+    pub async fn get_encoder_capabilities(&self) -> Result<EncoderCapabilities> {
+        let response = self
+            .send_request(RequestPayload::GetEncoderCapabilities)
+            .await?;
+
+        match response.payload {
+            ResponsePayload::EncoderCapabilities { capabilities } => Ok(capabilities),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Vérifier l'environnement de compilation des dépendances
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la communication IPC échoue ou si le daemon renvoie une erreur.
+    pub async fn check_build_env(&self) -> Result<BuildEnvReport> {
+        let response = self.send_request(RequestPayload::CheckBuildEnv).await?;
+
+        match response.payload {
+            ResponsePayload::BuildEnvReport { report } => Ok(report),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Lancer un benchmark des presets (résultats transmis par événements)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon refuse le benchmark.
+    pub async fn start_benchmark(
+        &self,
+        encoder: EncoderType,
+        input_path: std::path::PathBuf,
+        target_speed: f64,
+    ) -> Result<()> {
+        let response = self
+            .send_request(RequestPayload::Benchmark {
+                encoder,
+                input_path,
+                target_speed,
+            })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::Ok => Ok(()),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// S'abonner (ou se désabonner) à la sortie des compilateurs
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue.
+    pub async fn set_compilation_log_streaming(&self, enabled: bool) -> Result<()> {
+        let response = self
+            .send_request(RequestPayload::SetCompilationLogStreaming { enabled })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::Ok => Ok(()),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Suivre l'état complet de la file : retourne l'état actuel, les changements
+    /// arrivent ensuite en événements `StateChanged`
+    ///
+    /// L'abonnement est propre à la connexion : à renouveler après une reconnexion.
     ///
     /// # Errors
     ///
     /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
-    pub async fn probe_video(&self, path: std::path::PathBuf) -> Result<(Option<f64>, u64)> {
+    pub async fn watch_state(&self) -> Result<QueueSnapshot> {
+        let response = self.send_request(RequestPayload::WatchState).await?;
+
+        match response.payload {
+            ResponsePayload::State { snapshot } => Ok(snapshot),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Prober une vidéo pour récupérer ses métadonnées (durée, taille, hauteur, canaux audio)
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
+    pub async fn probe_video(&self, path: std::path::PathBuf) -> Result<VideoProbe> {
         let response = self
             .send_request(RequestPayload::ProbeVideo { path })
             .await?;
@@ -310,8 +652,65 @@ impl IpcClient {
             ResponsePayload::VideoInfo {
                 duration_secs,
                 size_bytes,
+                height,
+                audio_channels,
                 ..
-            } => Ok((duration_secs, size_bytes)),
+            } => Ok(VideoProbe {
+                duration_secs,
+                size_bytes,
+                height,
+                audio_channels,
+            }),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Prober plusieurs vidéos en une requête (le daemon en probe quelques-unes à la fois)
+    ///
+    /// Les résultats suivent l'ordre de `paths`, avec l'erreur de chaque fichier en échec.
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
+    pub async fn probe_videos(
+        &self,
+        paths: Vec<std::path::PathBuf>,
+    ) -> Result<Vec<(std::path::PathBuf, Result<VideoProbe, String>)>> {
+        let response = self
+            .send_request(RequestPayload::ProbeVideos { paths })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::VideoInfos { results } => Ok(results
+                .into_iter()
+                .map(|(path, result)| {
+                    let probe = result.map(|summary| VideoProbe {
+                        duration_secs: summary.duration_secs,
+                        size_bytes: summary.size_bytes,
+                        height: summary.height,
+                        audio_channels: summary.audio_channels,
+                    });
+                    (path, probe)
+                })
+                .collect()),
+            ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
+            _ => anyhow::bail!("Réponse inattendue"),
+        }
+    }
+
+    /// Obtenir la miniature PNG d'une vidéo à `timestamp` secondes
+    ///
+    /// # Errors
+    ///
+    /// Retourne une erreur si la requête échoue ou si le daemon retourne une erreur.
+    pub async fn get_thumbnail(&self, path: std::path::PathBuf, timestamp: f64) -> Result<Vec<u8>> {
+        let response = self
+            .send_request(RequestPayload::GetThumbnail { path, timestamp })
+            .await?;
+
+        match response.payload {
+            ResponsePayload::Thumbnail { png, .. } => Ok(png),
             ResponsePayload::Error { message } => anyhow::bail!("Erreur: {message}"),
             _ => anyhow::bail!("Réponse inattendue"),
         }
@@ -322,6 +721,11 @@ impl IpcClient {
         self.event_rx.lock().await.try_recv().ok()
     }
 
+    /// Attendre le prochain événement (None si le client est fermé)
+    pub async fn next_event(&self) -> Option<Event> {
+        self.event_rx.lock().await.recv().await
+    }
+
     /// Rafraîchir toutes les listes
     ///
     /// # Errors
@@ -337,12 +741,150 @@ impl IpcClient {
     }
 }
 
+/// Ouvrir le socket du daemon avec le framing du protocole
+async fn open_framed(socket_path: &Path) -> Result<IpcFramed> {
+    let stream = IpcStream::connect(socket_path).await?;
+    let length_framed = Framed::new(stream, LengthDelimitedCodec::new());
+    Ok(SerdeFramed::new(
+        length_framed,
+        Bincode::<IpcMessage, IpcMessage>::default(),
+    ))
+}
+
+/// Fin d'une connexion
+enum ConnectionEnd {
+    /// Le daemon a fermé le flux ou une erreur d'I/O est survenue
+    Lost,
+    /// Le client a été libéré : plus rien à faire
+    ClientDropped,
+}
+
+/// Faire vivre la connexion au daemon et la rétablir quand elle se ferme
+async fn supervise_connection(
+    socket_path: PathBuf,
+    mut framed: IpcFramed,
+    mut request_rx: mpsc::UnboundedReceiver<Request>,
+    event_tx: mpsc::UnboundedSender<Event>,
+    pending: PendingResponses,
+    state_tx: watch::Sender<ConnectionState>,
+) {
+    let mut generation = 0;
+    loop {
+        match run_connection(framed, &mut request_rx, &event_tx, &pending).await {
+            ConnectionEnd::ClientDropped => return,
+            ConnectionEnd::Lost => warn!("Connexion au daemon perdue"),
+        }
+
+        // Les requêtes en attente n'auront jamais de réponse : les faire échouer
+        pending.lock().await.clear();
+
+        let mut attempt = 0;
+        let mut delay = RECONNECT_INITIAL_DELAY;
+        framed = loop {
+            attempt += 1;
+            let _ = state_tx.send(ConnectionState::Reconnecting { attempt });
+            tokio::time::sleep(delay).await;
+
+            // Requêtes émises pendant la coupure : refusées par send_request, à ignorer
+            loop {
+                match request_rx.try_recv() {
+                    Ok(_) => {}
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => return,
+                }
+            }
+
+            match open_framed(&socket_path).await {
+                Ok(framed) => break framed,
+                Err(e) => {
+                    debug!("Reconnexion au daemon impossible (tentative {attempt}): {e}");
+                    delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+                }
+            }
+        };
+
+        generation += 1;
+        info!("Reconnecté au daemon après {attempt} tentative(s)");
+        let _ = state_tx.send(ConnectionState::Connected { generation });
+    }
+}
+
+/// Relayer requêtes, réponses et événements jusqu'à la fermeture de la connexion
+async fn run_connection(
+    framed: IpcFramed,
+    request_rx: &mut mpsc::UnboundedReceiver<Request>,
+    event_tx: &mpsc::UnboundedSender<Event>,
+    pending: &PendingResponses,
+) -> ConnectionEnd {
+    let (mut writer, mut reader) = framed.split();
+    loop {
+        tokio::select! {
+            request = request_rx.recv() => {
+                let Some(request) = request else {
+                    return ConnectionEnd::ClientDropped;
+                };
+                debug!("Envoi requête: {:?}", request.payload);
+                if let Err(e) = writer.send(IpcMessage::Request(request)).await {
+                    error!("Échec d'envoi de requête: {}", e);
+                    return ConnectionEnd::Lost;
+                }
+            }
+            msg = reader.next() => match msg {
+                Some(Ok(IpcMessage::Response(response))) => {
+                    debug!("Réponse reçue pour request_id: {}", response.request_id);
+
+                    // Vérifier si c'est une réponse attendue
+                    if let Some(tx) = pending.lock().await.remove(&response.request_id) {
+                        let _ = tx.send(response);
+                    } else {
+                        // Réponse non attendue, on l'ignore
+                        debug!(
+                            "Réponse non attendue pour request_id: {}",
+                            response.request_id
+                        );
+                    }
+                }
+                Some(Ok(IpcMessage::Event(event))) => {
+                    debug!("Événement reçu: {:?}", event.payload);
+                    let _ = event_tx.send(event);
+                }
+                Some(Ok(IpcMessage::Request(_))) => {
+                    error!("Requête reçue côté client (inattendu)");
+                }
+                Some(Err(e)) => {
+                    error!("Erreur de lecture: {}", e);
+                    return ConnectionEnd::Lost;
+                }
+                None => return ConnectionEnd::Lost,
+            },
+        }
+    }
+}
+
+/// Délai de réponse du daemon à une sonde de disponibilité
+const READY_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Attente maximale de l'arrêt du daemon lors d'un redémarrage (30 s de jobs en mode `wait`,
+/// puis annulation et sauvegarde)
+const DAEMON_STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Démarrer le daemon s'il n'est pas déjà en cours d'exécution
 ///
+/// Le daemon est prêt quand il répond à `GetDepsStatus` (une compilation des dépendances
+/// en cours est alors affichée par le TUI). L'attente s'arrête tôt si le daemon quitte
+/// pendant son démarrage. `locate_daemon` n'est appelé que s'il faut lancer le daemon :
+/// un daemon déjà lancé n'a pas besoin de binaire.
+///
 /// # Errors
 ///
-/// Retourne une erreur si le daemon ne peut pas être démarré ou si la connexion échoue.
-pub async fn ensure_daemon_running(daemon_bin: &Path, socket_path: &Path) -> Result<()> {
+/// Retourne une erreur si le binaire du daemon est introuvable ou ne peut pas être lancé,
+/// ou si le daemon s'arrête pendant son démarrage ou ne répond pas dans `startup_timeout`.
+pub async fn ensure_daemon_running(
+    locate_daemon: impl FnOnce() -> Result<PathBuf>,
+    socket_path: &Path,
+    startup_timeout: Duration,
+    log_file: &Path,
+) -> Result<()> {
     // Vérifier si un serveur écoute déjà sur ce socket/pipe
     if IpcStream::server_exists(socket_path) {
         // Essayer de se connecter
@@ -354,10 +896,11 @@ pub async fn ensure_daemon_running(daemon_bin: &Path, socket_path: &Path) -> Res
         IpcListener::cleanup(socket_path);
     }
 
-    info!("Démarrage du daemon...");
+    let daemon_bin = locate_daemon()?;
+    info!("Démarrage du daemon ({})...", daemon_bin.display());
 
     // Lancer le daemon en arrière-plan
-    let mut cmd = tokio::process::Command::new(daemon_bin);
+    let mut cmd = tokio::process::Command::new(&daemon_bin);
     cmd.stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::null())
         .stderr(std::process::Stdio::null());
@@ -384,31 +927,102 @@ pub async fn ensure_daemon_running(daemon_bin: &Path, socket_path: &Path) -> Res
         cmd.creation_flags(CREATE_NO_WINDOW | DETACHED_PROCESS);
     }
 
-    cmd.spawn().context("Échec du démarrage du daemon")?;
+    let mut child = cmd.spawn().context("Échec du démarrage du daemon")?;
 
     info!("Attente du démarrage du daemon...");
 
-    // Attendre que le socket soit créé (max 3 minutes)
-    // La première fois, le daemon compile les dépendances avant de créer le socket
-    for i in 0..1800 {
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let started = std::time::Instant::now();
+    let mut socket_seen = false;
+    let mut tick: u64 = 0;
+    loop {
+        tick += 1;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Daemon arrêté (dépendances manquantes, config invalide...) : inutile d'attendre
+        if let Some(status) = child.try_wait()? {
+            anyhow::bail!(
+                "Le daemon s'est arrêté pendant son démarrage ({status}). Consultez le log : {}",
+                log_file.display()
+            );
+        }
+
         if IpcStream::server_exists(socket_path) {
-            // Attendre un peu plus pour que le daemon soit prêt
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-            info!("Daemon démarré et prêt");
-            return Ok(());
+            socket_seen = true;
+            if let Some(status) = probe_daemon_ready(socket_path).await {
+                if status.compiling {
+                    info!("Daemon prêt, compilation des dépendances en cours");
+                } else {
+                    info!("Daemon démarré et prêt");
+                }
+                return Ok(());
+            }
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= startup_timeout {
+            let state = if socket_seen {
+                "a ouvert son socket mais ne répond pas"
+            } else {
+                "n'a pas ouvert son socket"
+            };
+            anyhow::bail!(
+                "Le daemon {state} après {} s (ui.daemon_start_timeout_secs). Consultez le log : {}",
+                startup_timeout.as_secs(),
+                log_file.display()
+            );
         }
 
         // Afficher un message toutes les 10 secondes
-        if i % 100 == 0 && i > 0 {
-            let seconds = i / 10;
-            info!("Attente du daemon... ({} secondes écoulées)", seconds);
+        if tick.is_multiple_of(100) {
+            info!(
+                "Attente du daemon... ({} secondes écoulées)",
+                elapsed.as_secs()
+            );
         }
     }
+}
 
-    #[cfg(unix)]
-    anyhow::bail!("Timeout en attente du démarrage du daemon (3 minutes). Vérifiez les logs dans ~/.local/share/encodetalker/daemon.log");
+/// Arrêter le daemon puis le relancer (pour appliquer une configuration modifiée)
+///
+/// Attend que le daemon ferme son socket, ce qui peut prendre jusqu'à 30 s avec des jobs
+/// actifs en mode `wait`, puis le relance comme au démarrage du TUI. Le client se
+/// reconnecte ensuite de lui-même au nouveau daemon.
+///
+/// # Errors
+///
+/// Retourne une erreur si le daemon ne s'arrête pas à temps ou ne redémarre pas.
+pub async fn restart_daemon(
+    client: &IpcClient,
+    locate_daemon: impl FnOnce() -> Result<PathBuf>,
+    socket_path: &Path,
+    startup_timeout: Duration,
+    log_file: &Path,
+) -> Result<()> {
+    // La connexion peut se fermer avant la réponse : seul compte l'arrêt du socket
+    if let Err(e) = client.shutdown().await {
+        warn!("Demande d'arrêt du daemon: {e}");
+    }
 
-    #[cfg(windows)]
-    anyhow::bail!("Timeout en attente du démarrage du daemon (3 minutes). Vérifiez les logs dans %LOCALAPPDATA%\\encodetalker\\daemon.log");
+    let started = std::time::Instant::now();
+    while IpcStream::server_exists(socket_path) {
+        if started.elapsed() >= DAEMON_STOP_TIMEOUT {
+            anyhow::bail!(
+                "Le daemon ne s'est pas arrêté après {} s",
+                DAEMON_STOP_TIMEOUT.as_secs()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    info!("Daemon arrêté, redémarrage");
+
+    ensure_daemon_running(locate_daemon, socket_path, startup_timeout, log_file).await
+}
+
+/// Interroger l'état des dépendances : `Some` si le daemon répond, `None` sinon
+async fn probe_daemon_ready(socket_path: &Path) -> Option<DepsStatusInfo> {
+    let client = IpcClient::connect(socket_path).await.ok()?;
+    tokio::time::timeout(READY_PROBE_TIMEOUT, client.get_deps_status())
+        .await
+        .ok()?
+        .ok()
 }
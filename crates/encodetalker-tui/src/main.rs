@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
@@ -6,17 +6,200 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use tracing_subscriber::{fmt, EnvFilter};
 
-use encodetalker_common::AppPaths;
+use encodetalker_common::{
+    concat_list_path, format_concat_list, parse_analytics_jsonl, summarize_speed, AppPaths,
+    EncoderType, EventPayload, PathsConfig,
+};
 use encodetalker_daemon::DaemonConfig;
 use encodetalker_tui::{
-    ensure_daemon_running, handle_key_event, handle_mouse_event, render_ui, AppState, InputAction,
-    IpcClient,
+    ensure_daemon_running, handle_key_event, handle_mouse_event, render_ui, restart_daemon,
+    AppState, ConnectionState, GraphicsProtocol, InputAction, IpcClient, PinnedDirs,
 };
 
+/// Valeur d'une option `--nom valeur` ou `--nom=valeur`
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Soumettre `--enqueue <vidéo> --config <fichier> [--output <sortie>]`
+///
+/// Les chemins sont rendus absolus : le daemon ne partage pas le répertoire courant. Sans
+/// `--output`, le daemon nomme la sortie d'après la configuration lue dans le fichier.
+async fn enqueue_from_file(client: &IpcClient, input: &str) -> Result<()> {
+    let config_path = arg_value("--config")
+        .ok_or_else(|| anyhow::anyhow!("--enqueue nécessite --config <fichier.toml|json>"))?;
+    let input_path = std::path::absolute(input)?;
+    let output_path = arg_value("--output").map(std::path::absolute).transpose()?;
+
+    let job_id = client
+        .add_job_from_file(
+            input_path.clone(),
+            output_path.clone(),
+            std::path::absolute(config_path)?,
+        )
+        .await?;
+    // Chemin retenu par le daemon (nommage, collision)
+    let output_path = match client.get_job(job_id).await {
+        Ok(job) => Some(job.output_path),
+        Err(_) => output_path,
+    };
+    match output_path {
+        Some(output_path) => println!(
+            "Job {job_id} ajouté : {} -> {}",
+            input_path.display(),
+            output_path.display()
+        ),
+        None => println!("Job {job_id} ajouté : {}", input_path.display()),
+    }
+    Ok(())
+}
+
+/// Importer `--import-handbrake <presets.json> [--output <dossier>]` : un profil TOML par
+/// preset, utilisable avec `--enqueue <vidéo> --config <profil>`
+///
+/// Les profils existants ne sont pas écrasés.
+async fn import_handbrake(client: &IpcClient, input: &str) -> Result<()> {
+    let dir = match arg_value("--output") {
+        Some(dir) => std::path::absolute(dir)?,
+        None => std::env::current_dir()?,
+    };
+    let presets = client
+        .import_handbrake_presets(std::path::absolute(input)?)
+        .await?;
+
+    for preset in presets {
+        let path = dir.join(encodetalker_daemon::profile_file_name(&preset.name));
+        if path.exists() {
+            println!(
+                "Preset « {} » ignoré : {} existe déjà",
+                preset.name,
+                path.display()
+            );
+            continue;
+        }
+        let content = encodetalker_daemon::job_config_toml(&preset.config)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Impossible d'écrire {}", path.display()))?;
+        println!("Preset « {} » -> {}", preset.name, path.display());
+        for warning in &preset.warnings {
+            println!("  ⚠ {warning}");
+        }
+    }
+    println!("Utilisation : encodetalker-tui --enqueue <vidéo> --config <profil.toml>");
+    Ok(())
+}
+
+/// Suivre l'état de la file (`--watch-json`) : une ligne JSON sur stdout à chaque changement
+///
+/// L'abonnement est renouvelé après une reconnexion au daemon, qui renvoie l'état complet.
+async fn watch_state_json(client: &IpcClient) -> Result<()> {
+    let mut watched_generation = None;
+    loop {
+        if let ConnectionState::Connected { generation } = client.connection_state() {
+            if watched_generation != Some(generation) {
+                match client.watch_state().await {
+                    Ok(snapshot) => {
+                        println!("{}", serde_json::to_string(&snapshot)?);
+                        watched_generation = Some(generation);
+                    }
+                    Err(e) => warn!("Abonnement à l'état de la file impossible: {e}"),
+                }
+            }
+        }
+
+        // Délai borné pour revérifier la connexion même sans événement
+        match tokio::time::timeout(Duration::from_millis(500), client.next_event()).await {
+            Ok(Some(event)) => {
+                if let EventPayload::StateChanged { snapshot } = event.payload {
+                    println!("{}", serde_json::to_string(&snapshot)?);
+                }
+            }
+            Ok(None) => return Ok(()),
+            Err(_) => {}
+        }
+    }
+}
+
+/// Dessiner (ou effacer) la miniature quand la sélection, la zone ou le dialogue change
+fn draw_thumbnail(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app_state: &mut AppState,
+    protocol: GraphicsProtocol,
+    shown: &mut Option<(std::path::PathBuf, ratatui::layout::Rect)>,
+) -> Result<()> {
+    use std::io::Write as _;
+
+    let wanted = app_state
+        .visible_thumbnail()
+        .map(|(area, path, _)| (path.to_path_buf(), area));
+    if wanted == *shown {
+        return Ok(());
+    }
+
+    if shown.is_some() {
+        if let Some(clear) = protocol.clear() {
+            terminal.backend_mut().write_all(clear.as_bytes())?;
+        } else {
+            // Sixel : seul un redessin complet recouvre l'ancienne image
+            terminal.clear()?;
+            terminal.draw(|f| render_ui(f, app_state))?;
+        }
+    }
+    if let Some((area, _, png)) = app_state.visible_thumbnail() {
+        if let Some(sequence) = protocol.draw(png, area) {
+            terminal.backend_mut().write_all(sequence.as_bytes())?;
+        }
+    }
+    terminal.backend_mut().flush()?;
+    *shown = wanted;
+    Ok(())
+}
+
+/// Écrire la liste de concaténation des fichiers à joindre et la faire valider par le daemon
+///
+/// La liste est placée dans le répertoire de sortie par défaut, sinon à côté du premier
+/// fichier. Le probe vérifie que les fichiers sont compatibles (codec, résolution) ;
+/// en cas d'échec, la liste est supprimée. Retourne la liste et son probe.
+async fn join_files(
+    client: &IpcClient,
+    app_state: &AppState,
+    files: &[std::path::PathBuf],
+) -> Result<(std::path::PathBuf, encodetalker_tui::VideoProbe)> {
+    let first = files.first().context("Aucun fichier à joindre")?;
+    let dir = app_state
+        .output_namer
+        .output_dir()
+        .or_else(|| first.parent())
+        .unwrap_or_else(|| std::path::Path::new("."));
+    let list = concat_list_path(dir, first);
+    let content = format_concat_list(files).map_err(anyhow::Error::msg)?;
+    std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&list, content))
+        .with_context(|| format!("Écriture de {} impossible", list.display()))?;
+
+    match client.probe_video(list.clone()).await {
+        Ok(probe) => Ok((list, probe)),
+        Err(e) => {
+            let _ = std::fs::remove_file(&list);
+            Err(e)
+        }
+    }
+}
+
 #[tokio::main]
 #[allow(clippy::too_many_lines)] // Setup complet : logging, daemon, terminal, event loop
 async fn main() -> Result<()> {
@@ -50,18 +233,24 @@ async fn main() -> Result<()> {
 
     info!("Connexion au socket: {:?}", paths.socket_path);
 
-    // ÉTAPE 4: Démarrer daemon si nécessaire
-    let daemon_bin = std::env::current_exe()?
+    // ÉTAPE 4: Démarrer daemon si nécessaire (binaire à côté du TUI, dans le PATH ou
+    // ui.daemon_bin)
+    let tui_dir = std::env::current_exe()?
         .parent()
-        .unwrap()
-        .join("encodetalker-daemon");
+        .map(std::path::Path::to_path_buf);
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let locate_daemon = || config.ui.find_daemon_binary(tui_dir.as_deref(), &path_var);
 
     info!("Vérification du daemon...");
-    if let Err(e) = ensure_daemon_running(&daemon_bin, &paths.socket_path).await {
+    if let Err(e) = ensure_daemon_running(
+        locate_daemon,
+        &paths.socket_path,
+        config.ui.daemon_start_timeout(),
+        &paths.log_file,
+    )
+    .await
+    {
         eprintln!("Échec du démarrage du daemon: {e}");
-        eprintln!(
-            "Assurez-vous que le binaire encodetalker-daemon est présent dans le même répertoire."
-        );
         return Err(e);
     }
 
@@ -80,6 +269,23 @@ async fn main() -> Result<()> {
         deps_status.all_present, deps_status.compiling
     );
 
+    // Mode non interactif : soumettre un job configuré par fichier, sans ouvrir le TUI
+    if let Some(input) = arg_value("--enqueue") {
+        return enqueue_from_file(&client, &input).await;
+    }
+    if let Some(input) = arg_value("--import-handbrake") {
+        return import_handbrake(&client, &input).await;
+    }
+    if let Some(path) = arg_value("--verify") {
+        let path = std::path::absolute(path)?;
+        let job_id = client.verify_file(path.clone()).await?;
+        println!("Vérification {job_id} ajoutée : {}", path.display());
+        return Ok(());
+    }
+    if std::env::args().any(|arg| arg == "--watch-json") {
+        return watch_state_json(&client).await;
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -90,17 +296,67 @@ async fn main() -> Result<()> {
     // Créer l'état de l'application
     let start_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("/"));
     let mut app_state = AppState::new(start_dir);
+    app_state.analytics_file = Some(paths.analytics_file.clone());
+    app_state.pinned_dirs = PinnedDirs::new(&config.ui.pinned_dirs);
+    app_state.log_file = Some(paths.log_file.clone());
+    app_state.default_config = config.default_encoding_config();
+    app_state.encoder_defaults = [EncoderType::SvtAv1, EncoderType::Aom]
+        .into_iter()
+        .chain(EncoderType::HARDWARE)
+        .map(|encoder| (encoder, config.encoder.params_for(encoder)))
+        .collect();
+    app_state.vaapi_device = match PathsConfig::expand_path(&config.encoder.hardware.vaapi_device) {
+        Ok(device) => device,
+        Err(e) => {
+            warn!("encoder.hardware.vaapi_device: {e:#}");
+            config.encoder.hardware.vaapi_device.clone().into()
+        }
+    };
+    app_state.output_namer = match config.output_namer() {
+        Ok(namer) => namer,
+        Err(e) => {
+            warn!("Nommage des sorties par défaut: {e:#}");
+            encodetalker_common::OutputNamer::default()
+        }
+    };
+    app_state
+        .crf_policy
+        .clone_from(&config.encoding.crf_by_resolution);
+    app_state.audio_bitrate_policy = config.encoding.audio_bitrate_policy();
+    app_state.graphics = GraphicsProtocol::detect();
+    app_state.max_concurrent_jobs = config.daemon.max_concurrent_jobs.max(1);
+    app_state.preview_duration_secs = config.encoding.preview_duration_secs;
+    app_state.units = config.ui.units;
+    app_state.track_list_rows = config.ui.track_list_rows;
 
     // Ajuster la vue initiale selon l'état des dépendances
+    let deps_ready = deps_status.all_present;
     if deps_status.all_present {
         // Dépendances prêtes, passer directement à FileBrowser
         app_state.current_view = encodetalker_tui::View::FileBrowser;
         app_state.loading_state = None;
-    } else if deps_status.compiling {
-        // Compilation en cours, rester en Loading et afficher l'état actuel
+    } else if deps_status.compiling || deps_status.error.is_some() {
+        // Compilation en cours ou impossible : rester en Loading et afficher l'état actuel
         app_state.loading_state = Some(encodetalker_tui::LoadingState::from_status(deps_status));
+    } else {
+        // En attente du démarrage de la compilation : vérifier l'environnement au préalable
+        match client.check_build_env().await {
+            Ok(report) => {
+                if let Some(loading) = &mut app_state.loading_state {
+                    loading.build_env = Some(report);
+                }
+            }
+            Err(e) => warn!("Diagnostic de l'environnement de compilation impossible: {e}"),
+        }
+    }
+
+    // Récupérer les capacités des encodeurs (pour griser les options non supportées)
+    if deps_ready {
+        match client.get_encoder_capabilities().await {
+            Ok(caps) => app_state.encoder_capabilities = Some(Arc::new(caps)),
+            Err(e) => error!("Échec de récupération des capacités: {}", e),
+        }
     }
-    // Sinon, rester en Loading avec état vide (en attente du démarrage de la compilation)
 
     // Charger les listes initiales
     match client.refresh_all().await {
@@ -113,15 +369,74 @@ async fn main() -> Result<()> {
             error!("Échec du chargement initial: {}", e);
         }
     }
+    // Daemon déjà en cours d'arrêt (drain) : le signaler avant toute tentative d'ajout
+    if let Ok(status) = client.get_daemon_status().await {
+        app_state.daemon_accepting_jobs = status.accepting_jobs;
+    }
 
     // Boucle principale
-    let tick_rate = Duration::from_millis(500); // Rafraîchir toutes les 500ms
+    // Intervalle de rafraîchissement configuré ([ui] refresh_interval_ms, 500ms par défaut)
+    let tick_rate = config.ui.tick_rate();
     let mut last_tick = std::time::Instant::now();
+    let mut last_connection = client.connection_state();
+
+    // Miniature actuellement dessinée (chemin et zone)
+    let mut shown_thumbnail: Option<(std::path::PathBuf, ratatui::layout::Rect)> = None;
+    // Miniatures récupérées en arrière-plan : la boucle n'attend jamais le daemon
+    let (thumbnail_tx, mut thumbnail_rx) = tokio::sync::mpsc::unbounded_channel();
+    // Moyennes de l'analytique, calculées en arrière-plan
+    let (analytics_tx, mut analytics_rx) = tokio::sync::mpsc::unbounded_channel();
 
     loop {
-        // Rendre l'interface
+        while let Ok(summary) = analytics_rx.try_recv() {
+            app_state.speed_summary = summary;
+        }
+        if let Some(path) = app_state.take_analytics_reload() {
+            let analytics_tx = analytics_tx.clone();
+            tokio::spawn(async move {
+                let records = match tokio::fs::read_to_string(&path).await {
+                    Ok(content) => parse_analytics_jsonl(&content),
+                    Err(e) => {
+                        tracing::debug!("Analytique {} illisible: {e}", path.display());
+                        Vec::new()
+                    }
+                };
+                let _ = analytics_tx.send(summarize_speed(&records));
+            });
+        }
+
+        // Rendre l'interface (la queue rechargée garde l'aperçu du déplacement en cours)
+        app_state.sync_grab_preview();
         terminal.draw(|f| render_ui(f, &mut app_state))?;
 
+        // Miniature de la vidéo sélectionnée, dessinée par-dessus l'interface
+        if let Some(protocol) = app_state.graphics {
+            while let Ok((path, png)) = thumbnail_rx.try_recv() {
+                app_state.thumbnails.insert(path, png);
+            }
+            if let Some((path, timestamp)) = app_state.pending_thumbnail() {
+                app_state.thumbnails.mark_requested(path.clone());
+                let client = client.clone();
+                let thumbnail_tx = thumbnail_tx.clone();
+                tokio::spawn(async move {
+                    let png = match client.get_thumbnail(path.clone(), timestamp).await {
+                        Ok(png) => Some(png),
+                        Err(e) => {
+                            tracing::debug!("Miniature de {} impossible: {e}", path.display());
+                            None
+                        }
+                    };
+                    let _ = thumbnail_tx.send((path, png));
+                });
+            }
+            draw_thumbnail(
+                &mut terminal,
+                &mut app_state,
+                protocol,
+                &mut shown_thumbnail,
+            )?;
+        }
+
         // Gérer les événements
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
@@ -169,41 +484,108 @@ async fn main() -> Result<()> {
                         }
                     }
                 }
-                InputAction::AddBatchJobs { jobs, config } => {
-                    let total = jobs.len();
-                    let mut success_count = 0;
-                    let mut errors = Vec::new();
-
-                    for (input_path, output_path) in jobs {
-                        match client
-                            .add_job(input_path.clone(), output_path, config.clone())
-                            .await
-                        {
-                            Ok(_job_id) => {
-                                success_count += 1;
-                            }
-                            Err(e) => {
-                                let filename = input_path
-                                    .file_name()
-                                    .unwrap_or_default()
-                                    .to_string_lossy()
-                                    .to_string();
-                                errors.push(format!("{filename}: {e}"));
+                InputAction::AddPreviewJob {
+                    input_path,
+                    output_path,
+                    config,
+                } => {
+                    match client
+                        .add_preview_job(
+                            input_path,
+                            output_path,
+                            config,
+                            app_state.preview_duration_secs,
+                        )
+                        .await
+                    {
+                        Ok(job_id) => {
+                            app_state.set_status(format!("Extrait de test {job_id} ajouté"));
+                            if let Ok((queue, active, history)) = client.refresh_all().await {
+                                app_state.queue_jobs = queue;
+                                app_state.active_jobs = active;
+                                app_state.history_jobs = history;
                             }
                         }
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Échec de l'ajout de l'extrait de test: {e}"),
+                            });
+                        }
                     }
+                }
+                InputAction::AddBatchJobs {
+                    jobs,
+                    config,
+                    renamed,
+                } => {
+                    let total = jobs.len();
+                    let specs = jobs
+                        .into_iter()
+                        .map(|(input_path, output_path, crf)| {
+                            let mut config = config.clone();
+                            config.encoder_params.crf = crf;
+                            encodetalker_common::protocol::messages::BatchJobSpec {
+                                input_path,
+                                output_path,
+                                config,
+                            }
+                        })
+                        .collect();
 
-                    if success_count == total {
-                        app_state.set_status(format!("{total} jobs ajoutés avec succès"));
+                    let file_name = |path: &std::path::Path| {
+                        path.file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string()
+                    };
+                    let (batch, errors) = match client.add_batch_jobs(specs).await {
+                        Ok((batch_id, failed)) => (
+                            Some(batch_id),
+                            failed
+                                .iter()
+                                .map(|(input_path, e)| format!("{}: {e}", file_name(input_path)))
+                                .collect(),
+                        ),
+                        Err(e) => (None, vec![e.to_string()]),
+                    };
+                    let success_count = if batch.is_some() {
+                        total - errors.len()
                     } else {
-                        app_state.dialog = Some(encodetalker_tui::Dialog::Error {
-                            message: format!(
-                                "{}/{} jobs ajoutés. Échecs:\n{}",
-                                success_count,
-                                total,
-                                errors.join("\n")
-                            ),
-                        });
+                        0
+                    };
+                    let batch_label = batch
+                        .map(|batch_id| {
+                            format!(" (lot {})", encodetalker_tui::short_batch_id(batch_id))
+                        })
+                        .unwrap_or_default();
+                    let renamed_list: Vec<String> = renamed
+                        .iter()
+                        .map(|(from, to)| format!("{} → {}", file_name(from), file_name(to)))
+                        .collect();
+
+                    if success_count == total && renamed.is_empty() {
+                        app_state
+                            .set_status(format!("{total} jobs ajoutés avec succès{batch_label}"));
+                    } else if success_count == total {
+                        app_state.set_status(format!(
+                            "{total} jobs ajoutés{batch_label}, sorties en double renommées : {}",
+                            renamed_list.join(", ")
+                        ));
+                    } else {
+                        let mut message = format!(
+                            "{}/{} jobs ajoutés{}. Échecs:\n{}",
+                            success_count,
+                            total,
+                            batch_label,
+                            errors.join("\n")
+                        );
+                        if !renamed.is_empty() {
+                            message.push_str(&format!(
+                                "\n\nSorties en double renommées:\n{}",
+                                renamed_list.join("\n")
+                            ));
+                        }
+                        app_state.dialog = Some(encodetalker_tui::Dialog::Error { message });
                     }
 
                     // Rafraîchir les listes
@@ -231,6 +613,47 @@ async fn main() -> Result<()> {
                         }
                     }
                 }
+                InputAction::RestartDaemon => {
+                    app_state.set_status("Redémarrage du daemon…");
+                    terminal.draw(|f| render_ui(f, &mut app_state))?;
+                    match restart_daemon(
+                        &client,
+                        locate_daemon,
+                        &paths.socket_path,
+                        config.ui.daemon_start_timeout(),
+                        &paths.log_file,
+                    )
+                    .await
+                    {
+                        // Le rechargement des listes suit la reconnexion du client
+                        Ok(()) => app_state.set_status("Daemon redémarré"),
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Échec du redémarrage du daemon: {e}"),
+                            });
+                        }
+                    }
+                }
+                InputAction::CancelBatch { batch_id } => {
+                    match client.cancel_batch(batch_id).await {
+                        Ok(cancelled) => {
+                            app_state.set_status(format!(
+                                "Lot {} : {cancelled} jobs annulés",
+                                encodetalker_tui::short_batch_id(batch_id)
+                            ));
+                            if let Ok((queue, active, history)) = client.refresh_all().await {
+                                app_state.queue_jobs = queue;
+                                app_state.active_jobs = active;
+                                app_state.history_jobs = history;
+                            }
+                        }
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Échec de l'annulation du lot: {e}"),
+                            });
+                        }
+                    }
+                }
                 InputAction::RetryJob { job_id } => {
                     match client.retry_job(job_id).await {
                         Ok(()) => {
@@ -249,10 +672,53 @@ async fn main() -> Result<()> {
                         }
                     }
                 }
-                InputAction::RemoveFromHistory { job_id } => {
-                    match client.remove_from_history(job_id).await {
+                InputAction::MoveJob { job_id, position } => {
+                    match client.move_job(job_id, position).await {
                         Ok(()) => {
-                            app_state.set_status("Tâche supprimée de l'historique");
+                            app_state
+                                .set_status(format!("Job déplacé en position {}", position + 1));
+                        }
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Échec du déplacement: {e}"),
+                            });
+                        }
+                    }
+                    // Ordre du daemon, y compris après un échec
+                    if let Ok((queue, active, history)) = client.refresh_all().await {
+                        app_state.queue_jobs = queue;
+                        app_state.active_jobs = active;
+                        app_state.history_jobs = history;
+                    }
+                }
+                InputAction::SetJobPriority { job_id, nice } => {
+                    match client.set_job_priority(job_id, nice).await {
+                        Ok(()) => {
+                            app_state.set_status(format!("Niceness du job fixée à {nice}"));
+                            if let Some(job) =
+                                app_state.active_jobs.iter_mut().find(|j| j.id == job_id)
+                            {
+                                job.nice = Some(nice);
+                            }
+                        }
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Échec du changement de priorité: {e}"),
+                            });
+                        }
+                    }
+                }
+                InputAction::RemoveFromHistory {
+                    job_id,
+                    delete_outputs,
+                } => {
+                    match client.remove_from_history(job_id, delete_outputs).await {
+                        Ok(()) => {
+                            app_state.set_status(if delete_outputs {
+                                "Tâche et fichiers de sortie supprimés"
+                            } else {
+                                "Tâche supprimée de l'historique"
+                            });
                             app_state.history_jobs.retain(|j| j.id != job_id);
                             // Ajuster l'index si nécessaire
                             if app_state.selected_index >= app_state.history_jobs.len()
@@ -268,17 +734,140 @@ async fn main() -> Result<()> {
                         }
                     }
                 }
-                InputAction::ClearHistory => match client.clear_history().await {
-                    Ok(()) => {
-                        app_state.set_status("Historique effacé");
-                        app_state.history_jobs.clear();
+                InputAction::ClearHistory { delete_outputs } => {
+                    match client.clear_history(delete_outputs).await {
+                        Ok(()) => {
+                            app_state.set_status(if delete_outputs {
+                                "Historique et fichiers de sortie effacés"
+                            } else {
+                                "Historique effacé"
+                            });
+                            app_state.history_jobs.clear();
+                        }
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Échec du clear: {e}"),
+                            });
+                        }
                     }
-                    Err(e) => {
+                }
+                InputAction::StartBenchmark {
+                    input_path,
+                    encoder,
+                } => {
+                    // Cible : tenir le temps réel
+                    match client
+                        .start_benchmark(encoder, input_path.clone(), 1.0)
+                        .await
+                    {
+                        Ok(()) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Benchmark(
+                                encodetalker_tui::BenchmarkDialog::new(input_path, encoder),
+                            ));
+                        }
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Échec du benchmark: {e}"),
+                            });
+                        }
+                    }
+                }
+                InputAction::SetCompilationLogStreaming(enabled) => {
+                    if let Err(e) = client.set_compilation_log_streaming(enabled).await {
+                        warn!("Abonnement aux logs de compilation impossible: {e}");
+                    }
+                }
+                InputAction::EstimateCleanup => {
+                    let result = client
+                        .cleanup(encodetalker_common::CleanupTarget::ALL.to_vec(), true)
+                        .await;
+                    if let Some(encodetalker_tui::Dialog::Maintenance(maintenance)) =
+                        &mut app_state.dialog
+                    {
+                        match result {
+                            Ok(estimates) => maintenance.estimates = Some(estimates),
+                            Err(e) => maintenance.error = Some(e.to_string()),
+                        }
+                    }
+                }
+                InputAction::SavePinnedDirs => {
+                    if let Err(e) = app_state.pinned_dirs.save(&paths.config_file).await {
+                        app_state.set_status(format!("Échec de la sauvegarde des épingles: {e:#}"));
+                    }
+                }
+                InputAction::Cleanup { targets } => {
+                    let result = client.cleanup(targets, false).await;
+                    let estimates = client
+                        .cleanup(encodetalker_common::CleanupTarget::ALL.to_vec(), true)
+                        .await
+                        .ok();
+                    if let Ok(report) = &result {
+                        let bytes: u64 = report.iter().map(|r| r.bytes).sum();
+                        app_state.set_status(format!(
+                            "Nettoyage : {} libérés",
+                            app_state.units.size(bytes)
+                        ));
+                    }
+                    if let Some(encodetalker_tui::Dialog::Maintenance(maintenance)) =
+                        &mut app_state.dialog
+                    {
+                        match result {
+                            Ok(report) => {
+                                maintenance.report = Some(report);
+                                maintenance.checked.clear();
+                            }
+                            Err(e) => maintenance.error = Some(e.to_string()),
+                        }
+                        maintenance.estimates = estimates;
+                    }
+                }
+                InputAction::VerifyFiles { files } => {
+                    let mut added = 0;
+                    let mut errors = Vec::new();
+                    for file in files {
+                        match client.verify_file(file.clone()).await {
+                            Ok(_) => added += 1,
+                            Err(e) => errors.push(format!("{}: {e}", file.display())),
+                        }
+                    }
+                    if errors.is_empty() {
+                        app_state.file_browser.clear_selection();
+                        app_state.set_status(format!("{added} vérification(s) ajoutée(s)"));
+                    } else {
                         app_state.dialog = Some(encodetalker_tui::Dialog::Error {
-                            message: format!("Échec du clear: {e}"),
+                            message: format!(
+                                "{added} vérification(s) ajoutée(s), {} échec(s):\n{}",
+                                errors.len(),
+                                errors.join("\n")
+                            ),
                         });
                     }
-                },
+                    if let Ok((queue, active, history)) = client.refresh_all().await {
+                        app_state.queue_jobs = queue;
+                        app_state.active_jobs = active;
+                        app_state.history_jobs = history;
+                    }
+                }
+                InputAction::JoinFiles { files } => {
+                    match join_files(&client, &app_state, &files).await {
+                        Ok((list, probe)) => {
+                            app_state.file_browser.clear_selection();
+                            app_state.file_browser.refresh();
+                            app_state.file_browser.update_video_info(
+                                &list,
+                                probe.duration_secs,
+                                probe.height,
+                                probe.audio_channels,
+                            );
+                            app_state.dialog = Some(app_state.new_encode_dialog(vec![list]));
+                        }
+                        Err(e) => {
+                            app_state.dialog = Some(encodetalker_tui::Dialog::Error {
+                                message: format!("Impossible de joindre les fichiers: {e}"),
+                            });
+                        }
+                    }
+                }
             }
         }
 
@@ -286,6 +875,35 @@ async fn main() -> Result<()> {
         if last_tick.elapsed() >= tick_rate {
             last_tick = std::time::Instant::now();
 
+            // Daemon redémarré ou planté : signaler la reconnexion, puis tout recharger
+            let connection = client.connection_state();
+            if connection != last_connection {
+                last_connection = connection;
+                match connection {
+                    ConnectionState::Reconnecting { attempt } => {
+                        app_state.set_status(format!(
+                            "⚠ Daemon injoignable, reconnexion… (tentative {attempt})"
+                        ));
+                    }
+                    ConnectionState::Connected { .. } => {
+                        app_state.set_status("Reconnecté au daemon");
+                        if let Ok((queue, active, history)) = client.refresh_all().await {
+                            app_state.queue_jobs = queue;
+                            app_state.active_jobs = active;
+                            app_state.history_jobs = history;
+                        }
+                        if let Ok(caps) = client.get_encoder_capabilities().await {
+                            app_state.encoder_capabilities = Some(Arc::new(caps));
+                        }
+                        // Nouveau daemon : il accepte de nouveau les jobs
+                        app_state.daemon_accepting_jobs = client
+                            .get_daemon_status()
+                            .await
+                            .map_or(true, |status| status.accepting_jobs);
+                    }
+                }
+            }
+
             // Recevoir les événements du daemon
             while let Some(event) = client.poll_event().await {
                 match event.payload {
@@ -293,7 +911,8 @@ async fn main() -> Result<()> {
                     | encodetalker_common::EventPayload::JobStarted { .. }
                     | encodetalker_common::EventPayload::JobCompleted { .. }
                     | encodetalker_common::EventPayload::JobFailed { .. }
-                    | encodetalker_common::EventPayload::JobCancelled { .. } => {
+                    | encodetalker_common::EventPayload::JobCancelled { .. }
+                    | encodetalker_common::EventPayload::JobMoved { .. } => {
                         // Rafraîchir les listes
                         if let Ok((queue, active, history)) = client.refresh_all().await {
                             app_state.queue_jobs = queue;
@@ -301,26 +920,48 @@ async fn main() -> Result<()> {
                             app_state.history_jobs = history;
                         }
                     }
+                    encodetalker_common::EventPayload::StateChanged { snapshot } => {
+                        app_state.queue_jobs = snapshot.queue;
+                        app_state.active_jobs = snapshot.active;
+                        app_state.history_jobs = snapshot.history;
+                    }
                     encodetalker_common::EventPayload::JobProgress { job_id, stats } => {
                         // Mettre à jour les stats du job
                         if let Some(job) = app_state.active_jobs.iter_mut().find(|j| j.id == job_id)
                         {
-                            job.stats = Some(stats);
+                            job.stats = Some(*stats);
                         }
                     }
+                    // Historique modifié par un autre client : appliqué sans tout recharger
+                    encodetalker_common::EventPayload::HistoryRemoved { job_id } => {
+                        app_state.remove_history_job(job_id);
+                    }
+                    encodetalker_common::EventPayload::HistoryCleared => {
+                        app_state.clear_history_jobs();
+                    }
+                    encodetalker_common::EventPayload::StoppedAcceptingJobs => {
+                        app_state.daemon_accepting_jobs = false;
+                    }
                     encodetalker_common::EventPayload::DaemonShutdown => {
                         app_state.dialog = Some(encodetalker_tui::Dialog::Error {
                             message: "Le daemon s'est arrêté".to_string(),
                         });
                     }
                     // Événements de compilation des dépendances
-                    encodetalker_common::EventPayload::DepsCompilationStarted { total_deps } => {
+                    encodetalker_common::EventPayload::DepsCompilationStarted {
+                        total_deps,
+                        resumed,
+                        deps,
+                    } => {
                         info!(
-                            "Compilation des dépendances démarrée ({} dépendances)",
-                            total_deps
+                            "Compilation des dépendances démarrée ({} dépendances{})",
+                            total_deps,
+                            if resumed { ", reprise" } else { "" }
                         );
                         let mut loading = encodetalker_tui::LoadingState::new();
                         loading.total_deps = total_deps;
+                        loading.resumed = resumed;
+                        loading.deps = deps;
                         app_state.loading_state = Some(loading);
                         app_state.current_view = encodetalker_tui::View::Loading;
                     }
@@ -347,6 +988,9 @@ async fn main() -> Result<()> {
                         app_state.current_view = encodetalker_tui::View::FileBrowser;
                         app_state.loading_state = None;
                         app_state.set_status("✅ Dépendances compilées avec succès");
+                        if let Ok(caps) = client.get_encoder_capabilities().await {
+                            app_state.encoder_capabilities = Some(Arc::new(caps));
+                        }
                     }
                     encodetalker_common::EventPayload::DepsCompilationFailed {
                         dep_name,
@@ -357,22 +1001,82 @@ async fn main() -> Result<()> {
                             loading.error = Some(format!("{dep_name}: {error}"));
                         }
                     }
+                    encodetalker_common::EventPayload::DepsCompilationLog { lines, skipped } => {
+                        if let Some(loading) = &mut app_state.loading_state {
+                            loading.push_log_lines(lines, skipped);
+                        }
+                    }
+                    // Événements du benchmark (le dialogue a pu être fermé entre-temps)
+                    encodetalker_common::EventPayload::BenchmarkProgress { result } => {
+                        if let Some(encodetalker_tui::Dialog::Benchmark(benchmark)) =
+                            &mut app_state.dialog
+                        {
+                            benchmark.results.push(result);
+                        }
+                    }
+                    encodetalker_common::EventPayload::BenchmarkCompleted { report } => {
+                        app_state.set_status(match report.recommended() {
+                            Some(preset) => {
+                                format!("Benchmark {} : preset {preset} recommandé", report.encoder)
+                            }
+                            None => format!(
+                                "Benchmark {} : aucun preset n'atteint le temps réel",
+                                report.encoder
+                            ),
+                        });
+                        if let Some(encodetalker_tui::Dialog::Benchmark(benchmark)) =
+                            &mut app_state.dialog
+                        {
+                            benchmark.report = Some(report);
+                        }
+                    }
+                    encodetalker_common::EventPayload::BenchmarkFailed { error } => {
+                        app_state.set_status(format!("Benchmark échoué: {error}"));
+                        if let Some(encodetalker_tui::Dialog::Benchmark(benchmark)) =
+                            &mut app_state.dialog
+                        {
+                            benchmark.error = Some(error);
+                        }
+                    }
                 }
             }
 
-            // Envoyer des requêtes ProbeVideo pour les vidéos en attente (1 par tick)
+            // Prober les vidéos en attente, autant par tick que le daemon en probe à la
+            // fois : l'interface n'attend pas plus longtemps qu'un seul probe
             if app_state.current_view == encodetalker_tui::View::FileBrowser {
-                let pending_probes = app_state.file_browser.get_pending_probes();
-                // Prendre seulement la première vidéo en attente pour ne pas bloquer
-                if let Some(path) = pending_probes.first() {
-                    match client.probe_video(path.clone()).await {
-                        Ok((duration, _size)) => {
-                            app_state.file_browser.update_video_info(path, duration);
+                let mut pending_probes = app_state.file_browser.get_pending_probes();
+                pending_probes.truncate(encodetalker_daemon::MAX_PARALLEL_PROBES);
+                if !pending_probes.is_empty() {
+                    match client.probe_videos(pending_probes.clone()).await {
+                        Ok(results) => {
+                            for (path, result) in results {
+                                match result {
+                                    Ok(probe) => app_state.file_browser.update_video_info(
+                                        &path,
+                                        probe.duration_secs,
+                                        probe.height,
+                                        probe.audio_channels,
+                                    ),
+                                    Err(e) => {
+                                        // En cas d'erreur, marquer comme "-" en mettant None
+                                        app_state
+                                            .file_browser
+                                            .update_video_info(&path, None, None, None);
+                                        tracing::debug!(
+                                            "Erreur probe vidéo {}: {e}",
+                                            path.display()
+                                        );
+                                    }
+                                }
+                            }
                         }
                         Err(e) => {
-                            // En cas d'erreur, marquer comme "-" en mettant None
-                            app_state.file_browser.update_video_info(path, None);
-                            tracing::debug!("Erreur probe vidéo {}: {}", path.display(), e);
+                            for path in &pending_probes {
+                                app_state
+                                    .file_browser
+                                    .update_video_info(path, None, None, None);
+                            }
+                            tracing::debug!("Erreur probe des vidéos: {e}");
                         }
                     }
                 }
@@ -0,0 +1,57 @@
+use crate::app::AppState;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+/// Rendre la vue des statistiques de vitesse (par encodeur et preset)
+pub fn render_analytics_view(frame: &mut Frame, area: Rect, state: &AppState) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            " 📊 Statistiques ({} combinaisons) ",
+            state.speed_summary.len()
+        ))
+        .border_style(Style::default().fg(Color::Magenta));
+
+    if state.speed_summary.is_empty() {
+        let text = Paragraph::new(
+            "Aucune donnée d'encodage\n\nLes statistiques apparaissent après le premier job terminé.",
+        )
+        .block(block)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(text, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = state
+        .speed_summary
+        .iter()
+        .map(|summary| {
+            let text = format!(
+                "{} preset {}\n  Vitesse moyenne: {:.2} fps | Durée moyenne: {:.0}s | Jobs: {}",
+                summary.encoder,
+                summary.preset,
+                summary.avg_fps,
+                summary.avg_encode_secs,
+                summary.jobs
+            );
+            ListItem::new(text).style(Style::default().fg(Color::White))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selected_index));
+
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
@@ -1,4 +1,8 @@
-use crate::app::Dialog;
+use crate::app::{
+    BenchmarkDialog, Dialog, EncodeConfigDialog, MaintenanceDialog, SearchDialog, SearchHit,
+    TrackLists, View,
+};
+use encodetalker_common::{CleanupResult, CleanupTarget, UnitPreferences};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
@@ -6,17 +10,21 @@ use ratatui::{
 
 /// Rendre un dialogue par-dessus l'interface
 pub fn render_dialog(frame: &mut Frame, area: Rect, state: &mut crate::app::AppState) {
+    let units = state.units;
     if let Some(dialog) = &state.dialog {
         match dialog {
             Dialog::EncodeConfig(config) => {
                 let dialog_area = centered_rect(80, 80, area);
                 state.layout.dialog_area = Some(dialog_area);
-                render_encode_config_dialog(frame, area, config);
+                render_encode_config_dialog(frame, area, config, units);
             }
-            Dialog::Confirm { message, .. } => {
+            Dialog::Confirm {
+                message,
+                on_confirm,
+            } => {
                 let dialog_area = centered_rect(50, 30, area);
                 state.layout.dialog_area = Some(dialog_area);
-                render_confirm_dialog(frame, area, message);
+                render_confirm_dialog(frame, area, message, *on_confirm);
             }
             Dialog::Error { message } => {
                 let dialog_area = centered_rect(60, 30, area);
@@ -37,18 +45,63 @@ pub fn render_dialog(frame: &mut Frame, area: Rect, state: &mut crate::app::AppS
                 state.layout.dialog_area = Some(dialog_area);
                 render_video_info_dialog(frame, area, path, output, *scroll_offset);
             }
+            Dialog::Benchmark(benchmark) => {
+                let dialog_area = centered_rect(60, 50, area);
+                state.layout.dialog_area = Some(dialog_area);
+                render_benchmark_dialog(frame, area, benchmark);
+            }
+            Dialog::Maintenance(maintenance) => {
+                let dialog_area = centered_rect(60, 50, area);
+                state.layout.dialog_area = Some(dialog_area);
+                render_maintenance_dialog(frame, dialog_area, maintenance, units);
+            }
+            Dialog::Search(search) => {
+                let dialog_area = centered_rect(70, 60, area);
+                state.layout.dialog_area = Some(dialog_area);
+                let hits = state.search_jobs(&search.query);
+                render_search_dialog(frame, dialog_area, search, &hits);
+            }
+            Dialog::JobDetail { job, scroll_offset } => {
+                let dialog_area = centered_rect(80, 80, area);
+                state.layout.dialog_area = Some(dialog_area);
+                crate::ui::job_detail::render_job_detail(
+                    frame,
+                    dialog_area,
+                    job,
+                    *scroll_offset,
+                    units,
+                );
+            }
         }
     } else {
         state.layout.dialog_area = None;
     }
 }
 
+/// Style d'un champ du dialogue : sélectionné, normal ou grisé si non supporté
+fn field_style(config: &crate::app::EncodeConfigDialog, field: usize) -> Style {
+    let supported = config.is_field_supported(field);
+    if config.selected_field == field {
+        let color = if supported {
+            Color::Yellow
+        } else {
+            Color::DarkGray
+        };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    } else if supported {
+        Style::default().fg(Color::White)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    }
+}
+
 /// Rendre le dialogue de configuration d'encodage
 #[allow(clippy::too_many_lines)] // UI avec 7 champs configurables, difficile à décomposer
 fn render_encode_config_dialog(
     frame: &mut Frame,
     area: Rect,
     config: &crate::app::EncodeConfigDialog,
+    units: UnitPreferences,
 ) {
     // Centrer le dialogue (plus large et plus haut pour la preview)
     let dialog_area = centered_rect(80, 80, area);
@@ -87,7 +140,12 @@ fn render_encode_config_dialog(
             Constraint::Length(3), // Preset
             Constraint::Length(3), // Threads
             Constraint::Length(3), // VMAF
-            Constraint::Length(3), // Content Type
+            Constraint::Length(3), // Content Type / Tune aom
+            Constraint::Length(3), // Découpe (début / fin)
+            Constraint::Length(3), // Découpage de la sortie
+            Constraint::Length(3), // Contrôle de débit
+            Constraint::Length(3), // Paramètres extra de l'encodeur
+            Constraint::Length(3), // Langues des pistes gardées
             Constraint::Min(5),    // Command Preview (extensible)
             Constraint::Length(2), // Instructions
         ])
@@ -99,53 +157,100 @@ fn render_encode_config_dialog(
     } else {
         format!("Input:  {}", config.input_paths[0].display())
     };
-    let input = Paragraph::new(input_text).style(Style::default().fg(Color::White));
+    let mut input_lines = vec![Line::from(input_text)];
+    // Source entrelacée : signaler le désentrelacement (ou son absence)
+    if config.is_interlaced == Some(true) {
+        let warning = if config.config.deinterlace.applies(true) {
+            format!(
+                "⚠ Source entrelacée : désentrelacement {}",
+                config.config.deinterlace
+            )
+        } else {
+            "⚠ Source entrelacée : désentrelacement désactivé".to_string()
+        };
+        input_lines.push(Line::styled(warning, Style::default().fg(Color::Yellow)));
+    }
+    // Dimensions refusées par l'AV1 : proposer le recadrage au pair
+    if let Some(warning) = config.dimensions_warning() {
+        input_lines.push(Line::styled(
+            format!("⚠ {warning} (Ctrl+E)"),
+            Style::default().fg(Color::Yellow),
+        ));
+    } else if config.config.crop_to_even {
+        input_lines.push(Line::styled(
+            "Recadrage aux dimensions paires activé (Ctrl+E)",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    let input = Paragraph::new(input_lines).style(Style::default().fg(Color::White));
     frame.render_widget(input, chunks[0]);
 
     // Output file (éditable) - Style grisé si batch
-    let output_style = if config.selected_field == 7 && !config.is_batch() {
-        if config.is_editing_output {
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD)
+    let output_style =
+        if config.selected_field == EncodeConfigDialog::OUTPUT_FIELD && !config.is_batch() {
+            if config.is_editing_output {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            }
+        } else if config.is_batch() {
+            Style::default().fg(Color::DarkGray) // Grisé si batch
         } else {
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD)
-        }
-    } else if config.is_batch() {
-        Style::default().fg(Color::DarkGray) // Grisé si batch
-    } else {
-        Style::default().fg(Color::White)
-    };
+            Style::default().fg(Color::White)
+        };
 
     // Texte output adapté
     let output_text = if config.is_batch() {
-        "Output: <auto-généré: {nom}.av1.mkv>".to_string()
+        format!("Output: <auto-généré: {}>", config.output_namer.template())
     } else if config.is_editing_output {
         // Mode édition : afficher avec curseur (utiliser chars() pour gérer UTF-8)
         let chars: Vec<char> = config.output_path_string.chars().collect();
         let before: String = chars[..config.output_path_cursor].iter().collect();
         let after: String = chars[config.output_path_cursor..].iter().collect();
-        format!("Output: {before}█{after}")
-    } else if config.selected_field == 7 {
+        // Chemin relatif : montrer où le fichier sera réellement créé
+        let resolved = config
+            .output_namer
+            .resolve_entered(&config.output_path_string, &config.input_paths[0]);
+        if config.output_path_string.trim().is_empty()
+            || resolved == std::path::Path::new(config.output_path_string.trim())
+        {
+            format!("Output: {before}█{after}")
+        } else {
+            format!("Output: {before}█{after}  → {}", resolved.display())
+        }
+    } else if config.selected_field == EncodeConfigDialog::OUTPUT_FIELD {
         format!("Output: {} [→ to edit]", config.output_path_string)
     } else {
         format!("Output: {}", config.output_path_string)
     };
 
-    let output = Paragraph::new(output_text).style(output_style);
+    // Espace libre sur la destination (mesuré au changement de répertoire), en rouge
+    // sous le seuil
+    let mut output_spans = vec![Span::styled(output_text, output_style)];
+    if let Some(free) = config.free_space {
+        let free_style = if free < encodetalker_common::LOW_FREE_SPACE_BYTES {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        output_spans.push(Span::styled(
+            format!("  ({} libres)", units.size(free)),
+            free_style,
+        ));
+    }
+    let output = Paragraph::new(Line::from(output_spans));
     frame.render_widget(output, chunks[1]);
 
     // Encoder
-    let encoder_text = format!("Encoder: {}", config.config.encoder);
-    let encoder_style = if config.selected_field == 0 {
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
+    let encoder_text = match config.config.video_mode {
+        encodetalker_common::VideoMode::Encode => format!("Encoder: {}", config.config.encoder),
+        encodetalker_common::VideoMode::Copy => "Encoder: Copie vidéo (audio seul)".to_string(),
     };
+    let encoder_style = field_style(config, 0);
     let encoder = Paragraph::new(encoder_text).style(encoder_style);
     frame.render_widget(encoder, chunks[2]);
 
@@ -158,21 +263,26 @@ fn render_encode_config_dialog(
         encodetalker_common::AudioMode::Custom { codec, bitrate } => {
             format!("Audio:   {codec} {bitrate} kbps")
         }
+        encodetalker_common::AudioMode::None => "Audio:   Aucun (vidéo seule)".to_string(),
     };
-    let audio_style = if config.selected_field == 1 {
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
+    let audio_style = field_style(config, 1);
     let audio = Paragraph::new(audio_text).style(audio_style);
     frame.render_widget(audio, chunks[3]);
 
     // CRF
+    // CRF proposé selon la résolution de la source (en batch, chaque fichier a le sien)
+    let by_resolution = config.crf_policy_applies();
+    let crf_range = config.config.encoder.crf_range();
     let crf_text = format!(
-        "CRF:     {} (0-51, lower = better quality)",
-        config.config.encoder_params.crf
+        "CRF:     {} ({}-{}, lower = better quality){}",
+        config.config.encoder_params.crf,
+        crf_range.start(),
+        crf_range.end(),
+        if by_resolution {
+            " [by resolution]"
+        } else {
+            ""
+        }
     );
     let crf_style = if config.selected_field == 2 {
         Style::default()
@@ -185,10 +295,7 @@ fn render_encode_config_dialog(
     frame.render_widget(crf, chunks[4]);
 
     // Preset
-    let max_preset = match config.config.encoder {
-        encodetalker_common::EncoderType::SvtAv1 => 13,
-        encodetalker_common::EncoderType::Aom => 8,
-    };
+    let max_preset = config.config.encoder.max_preset();
     let preset_text = format!(
         "Preset:  {} (0-{}, higher = faster)",
         config.config.encoder_params.preset, max_preset
@@ -214,18 +321,14 @@ fn render_encode_config_dialog(
         format!("Threads: Auto (1-{max_threads})")
     };
 
-    let threads_style = if config.selected_field == 4 {
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
+    let threads_style = field_style(config, 4);
     let threads = Paragraph::new(threads_text).style(threads_style);
     frame.render_widget(threads, chunks[6]);
 
     // VMAF
-    let vmaf_text = if config.config.enable_vmaf {
+    let vmaf_text = if config.config.quality_metrics {
+        "VMAF:    [x] Calculer VMAF, PSNR et SSIM après encodage"
+    } else if config.config.enable_vmaf {
         "VMAF:    [x] Calculer le score VMAF après encodage"
     } else {
         "VMAF:    [ ] Calculer le score VMAF après encodage"
@@ -240,27 +343,201 @@ fn render_encode_config_dialog(
     let vmaf = Paragraph::new(vmaf_text).style(vmaf_style);
     frame.render_widget(vmaf, chunks[7]);
 
-    // Content Type
-    let content_type_text = format!("Type:    {}", config.config.encoder_params.content_type);
-    let content_type_style = if config.selected_field == 6 {
+    // Content Type (SVT-AV1) et métrique libaom côte à côte
+    let type_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[8]);
+    let content_type_text = if config.is_field_supported(6) {
+        format!("Type:    {}", config.config.encoder_params.content_type)
+    } else {
+        format!(
+            "Type:    {} (non supporté)",
+            config.config.encoder_params.content_type
+        )
+    };
+    let content_type_style = field_style(config, 6);
+    let content_type = Paragraph::new(content_type_text).style(content_type_style);
+    frame.render_widget(content_type, type_chunks[0]);
+
+    let tune_text = format!("Tune:   {}", config.config.encoder_params.tune);
+    let tune = Paragraph::new(tune_text).style(field_style(config, 7));
+    frame.render_widget(tune, type_chunks[1]);
+
+    // Découpe (début / fin côte à côte)
+    let trim_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[9]);
+    for (field, label, input, placeholder, area) in [
+        (
+            8,
+            "Début:  ",
+            &config.trim_start_string,
+            "<début du fichier>",
+            trim_chunks[0],
+        ),
+        (
+            9,
+            "Fin:    ",
+            &config.trim_end_string,
+            "<fin du fichier>",
+            trim_chunks[1],
+        ),
+    ] {
+        let value = if input.is_empty() {
+            placeholder.to_string()
+        } else {
+            input.clone()
+        };
+        let cursor = if config.selected_field == field {
+            "█"
+        } else {
+            ""
+        };
+        let text =
+            Paragraph::new(format!("{label}{value}{cursor}")).style(field_style(config, field));
+        frame.render_widget(text, area);
+    }
+
+    // Découpage de la sortie
+    let split_text = match config.config.split {
+        encodetalker_common::SplitMode::None => "Sortie:  Un seul fichier".to_string(),
+        split => format!("Sortie:  {split} (jetons {{index}} / {{chapter}} dans le nom)"),
+    };
+    let split = Paragraph::new(split_text).style(field_style(config, 10));
+    frame.render_widget(split, chunks[10]);
+
+    // Contrôle de débit (plafond pour le streaming)
+    let rate = Paragraph::new(format!(
+        "Débit:   {}",
+        config.config.encoder_params.encoding_mode
+    ))
+    .style(field_style(config, 11));
+    frame.render_widget(rate, chunks[11]);
+
+    // Paramètres extra de l'encodeur (éditables, séparés par des espaces)
+    let extra_field = EncodeConfigDialog::EXTRA_FIELD;
+    let extra_text = if config.is_editing_extra {
+        let chars: Vec<char> = config.extra_params_string.chars().collect();
+        let before: String = chars[..config.extra_params_cursor].iter().collect();
+        let after: String = chars[config.extra_params_cursor..].iter().collect();
+        format!("Extra:   {before}█{after}")
+    } else {
+        let params = &config.config.encoder_params.extra_params;
+        let value = if params.is_empty() {
+            "<aucun>".to_string()
+        } else {
+            params.join(" ")
+        };
+        if config.selected_field == extra_field && config.is_field_supported(extra_field) {
+            format!("Extra:   {value} [→ to edit]")
+        } else {
+            format!("Extra:   {value}")
+        }
+    };
+    let extra_style = if config.is_editing_extra {
         Style::default()
-            .fg(Color::Yellow)
+            .fg(Color::Green)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::White)
+        field_style(config, extra_field)
     };
-    let content_type = Paragraph::new(content_type_text).style(content_type_style);
-    frame.render_widget(content_type, chunks[8]);
+    let mut extra_lines = vec![Line::styled(extra_text, extra_style)];
+    // Option réservée au pipeline : signalée pendant la saisie
+    if let Err(msg) = config.config.validate_extra_params() {
+        extra_lines.push(Line::styled(
+            format!("⚠ {msg}"),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    frame.render_widget(Paragraph::new(extra_lines), chunks[12]);
 
-    // Command Preview
-    let preview_text = if config.is_batch() {
+    // Langues des pistes gardées (audio / sous-titres), résolues sur chaque source
+    let languages_field = EncodeConfigDialog::LANGUAGES_FIELD;
+    let languages_text = if config.is_editing_languages {
+        let chars: Vec<char> = config.languages_string.chars().collect();
+        let before: String = chars[..config.languages_cursor].iter().collect();
+        let after: String = chars[config.languages_cursor..].iter().collect();
+        format!("Langues: {before}█{after}")
+    } else {
+        let list = |codes: &[String]| {
+            if codes.is_empty() {
+                "toutes".to_string()
+            } else {
+                codes.join(",")
+            }
+        };
+        let value = format!(
+            "audio {} | sous-titres {}",
+            list(&config.config.audio_languages),
+            list(&config.config.subtitle_languages)
+        );
+        if config.selected_field == languages_field {
+            format!("Langues: {value} [→ to edit]")
+        } else {
+            format!("Langues: {value}")
+        }
+    };
+    let languages_style = if config.is_editing_languages {
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        field_style(config, languages_field)
+    };
+    frame.render_widget(
+        Paragraph::new(languages_text).style(languages_style),
+        chunks[13],
+    );
+
+    // Champ des langues : les pistes de la source remplacent l'aperçu des commandes
+    if config.selected_field == languages_field && !config.tracks.is_empty() {
+        render_track_lists(frame, chunks[14], config);
+    } else {
+        render_command_preview(frame, chunks[14], config);
+    }
+
+    // Instructions - Adaptées au batch
+    let instructions_text = if config.is_editing_output {
+        "←→: Déplacer curseur | Caractère: Insérer | Backspace/Suppr: Effacer | Entrée: Valider | ESC: Annuler"
+    } else if config.is_editing_extra {
+        "Options séparées par des espaces (ex: --enable-qm 1) | Entrée: Valider | ESC: Annuler"
+    } else if config.is_editing_languages {
+        "Audio / sous-titres (ex: eng,jpn / eng), vide = toutes les pistes | PgUp/PgDn: Pistes | Entrée: Valider | ESC: Annuler"
+    } else if config.selected_field == languages_field {
+        "↑↓: Naviguer | →/Entrée: Éditer | PgUp/PgDn: Défiler les pistes | ESC: Annuler"
+    } else if matches!(config.selected_field, 8 | 9) {
+        "↑↓: Naviguer | Saisir HH:MM:SS (vide = sans découpe) | Backspace: Effacer | Entrée: Ajouter | ESC: Annuler"
+    } else if config.is_batch() {
+        "↑↓: Naviguer | ←→: Changer valeur | Entrée: Ajouter tous les jobs | Ctrl+T: Extrait de test | ESC: Annuler"
+    } else {
+        "↑↓: Naviguer | ←→: Changer valeur | Entrée: Ajouter à la queue | Ctrl+T: Extrait de test | ESC: Annuler"
+    };
+    let instructions = Paragraph::new(instructions_text)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(instructions, chunks[15]);
+}
+
+/// Rendre l'aperçu des commandes du dialogue d'encodage
+fn render_command_preview(frame: &mut Frame, area: Rect, config: &EncodeConfigDialog) {
+    let preview_text = if config.is_batch()
+        && config.config.video_mode == encodetalker_common::VideoMode::Copy
+    {
         format!(
-            "Batch: {} fichiers avec config identique\nEncoder: {} | CRF: {} | Preset: {} | Type: {}",
+            "Batch: {} fichiers avec config identique\nVidéo copiée, audio seul traité",
+            config.input_paths.len()
+        )
+    } else if config.is_batch() {
+        format!(
+            "Batch: {} fichiers avec config identique\nEncoder: {} | CRF: {} | Preset: {} | Type: {} | Tune: {}",
             config.input_paths.len(),
             config.config.encoder,
             config.config.encoder_params.crf,
             config.config.encoder_params.preset,
             config.config.encoder_params.content_type,
+            config.config.encoder_params.tune,
         )
     } else {
         let preview_lines = encodetalker_common::build_full_pipeline_preview(
@@ -268,6 +545,7 @@ fn render_encode_config_dialog(
             &config.output_path,
             &config.config,
             config.is_interlaced,
+            &config.vaapi_device,
         );
         preview_lines.join("\n")
     };
@@ -281,24 +559,183 @@ fn render_encode_config_dialog(
                 .title(" Aperçu des commandes ")
                 .border_style(Style::default().fg(Color::DarkGray)),
         );
-    frame.render_widget(preview, chunks[9]);
+    frame.render_widget(preview, area);
+}
 
-    // Instructions - Adaptées au batch
-    let instructions_text = if config.is_editing_output {
-        "←→: Déplacer curseur | Caractère: Insérer | Backspace/Suppr: Effacer | Entrée: Valider | ESC: Annuler"
-    } else if config.is_batch() {
-        "↑↓: Naviguer | ←→: Changer valeur | Entrée: Ajouter tous les jobs | ESC: Annuler"
+/// Rendre les pistes audio et sous-titres de la première source, gardées en vert
+///
+/// Chaque liste affiche `ui.track_list_rows` pistes à partir du défilement commun.
+fn render_track_lists(frame: &mut Frame, area: Rect, config: &EncodeConfigDialog) {
+    let tracks = &config.tracks;
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .title(" Pistes de la source ")
+        .border_style(Style::default().fg(Color::DarkGray));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+
+    let audio_kept = config
+        .config
+        .resolve_audio_streams(&TrackLists::languages(&tracks.audio));
+    let subtitles_kept = config
+        .config
+        .resolve_subtitle_streams(&TrackLists::languages(&tracks.subtitles));
+    let lists = [
+        ("audio", &tracks.audio, audio_kept),
+        ("sous-titres", &tracks.subtitles, subtitles_kept),
+    ];
+    for ((label, list, kept), column) in lists.into_iter().zip(columns.iter()) {
+        let is_kept = |index: usize| kept.as_ref().is_none_or(|kept| kept.contains(&index));
+        let kept_count = (0..list.len()).filter(|&index| is_kept(index)).count();
+        let mut lines = vec![Line::styled(
+            format!("{kept_count}/{} pistes {label} gardées", list.len()),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )];
+        let start = tracks.scroll_offset.min(list.len());
+        if start > 0 {
+            lines.push(Line::styled(
+                format!("↑ {start} de plus (PgUp)"),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        let visible = tracks.visible(list);
+        for (offset, track) in visible.iter().enumerate() {
+            let index = start + offset;
+            let mut text = format!(
+                "#{index} {} {}",
+                track.language.as_deref().unwrap_or("und"),
+                track.codec.as_deref().unwrap_or("?")
+            );
+            if let Some(title) = &track.title {
+                text.push_str(&format!(" « {title} »"));
+            }
+            let color = if is_kept(index) {
+                Color::Green
+            } else {
+                Color::DarkGray
+            };
+            lines.push(Line::styled(text, Style::default().fg(color)));
+        }
+        let remaining = list.len() - start - visible.len();
+        if remaining > 0 {
+            lines.push(Line::styled(
+                format!("↓ {remaining} de plus (PgDn)"),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        frame.render_widget(Paragraph::new(lines), *column);
+    }
+}
+
+/// Rendre la recherche globale : saisie, puis un résultat par ligne avec la vue du job et
+/// la partie correspondante du nom surlignée
+fn render_search_dialog(
+    frame: &mut Frame,
+    dialog_area: Rect,
+    search: &SearchDialog,
+    hits: &[SearchHit],
+) {
+    frame.render_widget(Clear, dialog_area);
+    let title = if search.query.trim().is_empty() {
+        " Recherche ".to_string()
     } else {
-        "↑↓: Naviguer | ←→: Changer valeur | Entrée: Ajouter à la queue | ESC: Annuler"
+        format!(" Recherche ({} résultats) ", hits.len())
     };
-    let instructions = Paragraph::new(instructions_text)
-        .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::DarkGray));
-    frame.render_widget(instructions, chunks[10]);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let chars: Vec<char> = search.query.chars().collect();
+    let before: String = chars[..search.cursor].iter().collect();
+    let after: String = chars[search.cursor..].iter().collect();
+    frame.render_widget(
+        Paragraph::new(format!("Fichier: {before}█{after}")).style(
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        chunks[0],
+    );
+
+    let lines: Vec<Line> = if search.query.trim().is_empty() {
+        vec![Line::styled(
+            "Nom de fichier d'un job de la queue, en cours ou de l'historique",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else if hits.is_empty() {
+        vec![Line::styled(
+            "Aucun job trouvé",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        // Garder le résultat sélectionné visible
+        let visible = usize::from(chunks[1].height).max(1);
+        let skip = (search.selected + 1).saturating_sub(visible);
+        hits.iter()
+            .enumerate()
+            .skip(skip)
+            .take(visible)
+            .map(|(index, hit)| {
+                let base = if index == search.selected {
+                    Style::default().bg(Color::DarkGray).fg(Color::White)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let view = match hit.view {
+                    View::Queue => "Queue",
+                    View::Active => "En cours",
+                    _ => "Historique",
+                };
+                let name = &hit.file_name;
+                Line::from(vec![
+                    Span::styled(format!("{view:<11}"), base.fg(Color::Cyan)),
+                    Span::styled(name[..hit.matched.start].to_string(), base),
+                    Span::styled(
+                        name[hit.matched.clone()].to_string(),
+                        base.fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(name[hit.matched.end..].to_string(), base),
+                ])
+            })
+            .collect()
+    };
+    frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+    frame.render_widget(
+        Paragraph::new("↑↓: Naviguer | Entrée: Aller au job | ESC: Fermer")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
 }
 
 /// Rendre le dialogue de confirmation
-fn render_confirm_dialog(frame: &mut Frame, area: Rect, message: &str) {
+fn render_confirm_dialog(
+    frame: &mut Frame,
+    area: Rect,
+    message: &str,
+    on_confirm: crate::app::ConfirmAction,
+) {
     let dialog_area = centered_rect(50, 30, area);
 
     let clear = Clear;
@@ -324,7 +761,14 @@ fn render_confirm_dialog(frame: &mut Frame, area: Rect, message: &str) {
         .style(Style::default().fg(Color::White));
     frame.render_widget(text, chunks[0]);
 
-    let instructions = Paragraph::new("O/Entrée: Confirmer | N/ESC: Annuler")
+    // Par défaut les fichiers de sortie sont conservés ; 's' les supprime aussi
+    let instructions_text = match on_confirm {
+        crate::app::ConfirmAction::RemoveFromHistory | crate::app::ConfirmAction::ClearHistory => {
+            "O/Entrée: Confirmer | S: Confirmer + supprimer les sorties | N/ESC: Annuler"
+        }
+        _ => "O/Entrée: Confirmer | N/ESC: Annuler",
+    };
+    let instructions = Paragraph::new(instructions_text)
         .alignment(Alignment::Center)
         .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(instructions, chunks[1]);
@@ -363,6 +807,190 @@ fn render_error_dialog(frame: &mut Frame, area: Rect, message: &str) {
     frame.render_widget(instructions, chunks[1]);
 }
 
+/// Rendre le dialogue de benchmark des presets
+fn render_benchmark_dialog(frame: &mut Frame, area: Rect, benchmark: &BenchmarkDialog) {
+    let dialog_area = centered_rect(60, 50, area);
+    frame.render_widget(Clear, dialog_area);
+
+    let filename = benchmark
+        .input_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("fichier");
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Benchmark {} : {filename} ", benchmark.encoder))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let mut lines = Vec::new();
+    let results = benchmark
+        .report
+        .as_ref()
+        .map_or(&benchmark.results, |r| &r.results);
+    for result in results {
+        let speed = benchmark
+            .report
+            .as_ref()
+            .map(|r| format!("  ({:.2}x temps réel)", r.speed(result)))
+            .unwrap_or_default();
+        lines.push(Line::from(format!(
+            "Preset {:>2} : {:>7.1} fps{speed}",
+            result.preset, result.fps
+        )));
+    }
+    lines.push(Line::from(""));
+
+    if let Some(error) = &benchmark.error {
+        lines.push(Line::styled(
+            format!("Échec : {error}"),
+            Style::default().fg(Color::Red),
+        ));
+    } else if let Some(report) = &benchmark.report {
+        let recommendation = match report.recommended() {
+            Some(preset) => format!(
+                "Preset recommandé : {preset} (le plus lent à ≥ {:.1}x temps réel)",
+                report.target_speed
+            ),
+            None => format!(
+                "Aucun preset testé n'atteint {:.1}x temps réel",
+                report.target_speed
+            ),
+        };
+        lines.push(Line::styled(
+            recommendation,
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else {
+        lines.push(Line::styled(
+            "Mesure en cours (extrait de 10 s par preset)...",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), chunks[0]);
+
+    let instructions = Paragraph::new("Esc/Entrée : fermer (le benchmark continue)")
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(instructions, chunks[1]);
+}
+
+/// Taille et nombre de fichiers d'un nettoyage, ou la raison pour laquelle il n'a pas eu lieu
+fn cleanup_summary(result: &CleanupResult, units: UnitPreferences) -> String {
+    let summary = format!("{} ({} fichier(s))", units.size(result.bytes), result.files);
+    match &result.skipped {
+        Some(reason) if result.files == 0 => format!("ignoré : {reason}"),
+        Some(reason) => format!("{summary}, {reason}"),
+        None => summary,
+    }
+}
+
+/// Rendre la maintenance : cibles cochées avec leur espace libérable, puis confirmation
+fn render_maintenance_dialog(
+    frame: &mut Frame,
+    dialog_area: Rect,
+    maintenance: &MaintenanceDialog,
+    units: UnitPreferences,
+) {
+    frame.render_widget(Clear, dialog_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Maintenance ")
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let mut lines = Vec::new();
+    for (index, target) in CleanupTarget::ALL.into_iter().enumerate() {
+        let checkbox = if maintenance.checked.contains(&target) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
+        let estimate = maintenance.estimate(target).map_or_else(
+            || "estimation...".to_string(),
+            |result| cleanup_summary(result, units),
+        );
+        let style = if index == maintenance.selected {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::styled(
+            format!("{checkbox} {:<24} {estimate}", target.to_string()),
+            style,
+        ));
+    }
+    lines.push(Line::from(""));
+
+    if let Some(error) = &maintenance.error {
+        lines.push(Line::styled(
+            format!("Échec : {error}"),
+            Style::default().fg(Color::Red),
+        ));
+    } else if maintenance.confirming {
+        let targets: Vec<String> = maintenance
+            .targets()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        lines.push(Line::styled(
+            format!("Supprimer : {} ?", targets.join(", ")),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else if let Some(report) = &maintenance.report {
+        let bytes: u64 = report.iter().map(|result| result.bytes).sum();
+        lines.push(Line::styled(
+            format!("Nettoyage terminé : {} libérés", units.size(bytes)),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ));
+        for result in report {
+            lines.push(Line::from(format!(
+                "  {} : {}",
+                result.target,
+                cleanup_summary(result, units)
+            )));
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), chunks[0]);
+
+    let instructions = if maintenance.confirming {
+        "Entrée/o : confirmer | autre touche : annuler"
+    } else {
+        "Espace : cocher | Entrée : nettoyer | r : réestimer | Esc : fermer"
+    };
+    frame.render_widget(
+        Paragraph::new(instructions)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
 /// Créer un rectangle centré
 pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -2,11 +2,36 @@ use crate::app::state::format_duration;
 use crate::app::AppState;
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, List, ListItem, ListState,
+    },
 };
 
+/// Largeur du panneau de miniature (colonnes, bordures comprises)
+const PREVIEW_WIDTH: u16 = 42;
+
 /// Rendre le navigateur de fichiers
-pub fn render_file_browser(frame: &mut Frame, area: Rect, state: &AppState) {
+///
+/// Retourne la zone libre du panneau de miniature, affiché si le terminal sait dessiner
+/// des images et s'il reste assez de place pour la liste.
+pub fn render_file_browser(frame: &mut Frame, area: Rect, state: &AppState) -> Option<Rect> {
+    let (area, preview) = if state.graphics.is_some() && area.width >= PREVIEW_WIDTH * 2 + 20 {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(PREVIEW_WIDTH)])
+            .split(area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Aperçu ")
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(chunks[1]);
+        frame.render_widget(block, chunks[1]);
+        (chunks[0], Some(inner))
+    } else {
+        (area, None)
+    };
+
     // Compteur de sélections dans le titre
     let selection_count = state.file_browser.selected_files.len();
     let title = if selection_count > 0 {
@@ -20,11 +45,17 @@ pub fn render_file_browser(frame: &mut Frame, area: Rect, state: &AppState) {
         format!(" 📁 {} ", state.file_browser.current_dir.display())
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .title(title)
         .border_style(Style::default().fg(Color::Cyan));
 
+    // Répertoires épinglés en bas du cadre : ` 1:Films  2:Séries `, le courant en gras
+    let pinned = pinned_spans(state);
+    if !pinned.is_empty() {
+        block = block.title(Title::from(Line::from(pinned)).position(Position::Bottom));
+    }
+
     // Créer les items de la liste
     let items: Vec<ListItem> = state
         .file_browser
@@ -56,17 +87,17 @@ pub fn render_file_browser(frame: &mut Frame, area: Rect, state: &AppState) {
                 2 +   // icône emoji (compte pour 2)
                 1 +   // espace après icône
                 3 +   // " | " avant taille
-                12 +  // colonne taille ("   123456 Mo")
+                12 +  // colonne taille ("    12,4 Go")
                 3 +   // " | " avant durée
                 11 +  // colonne durée ("00:00:05:30")
                 4, // bordures du bloc + marge pour emojis
             );
 
-            // Formatter la taille (uniquement pour vidéos, arrondi au Mo)
+            // Formatter la taille (uniquement pour vidéos, dans l'unité choisie)
             let size_str = if entry.is_video {
                 entry.size_bytes.map_or_else(
-                    || "        - Mo".to_string(),
-                    |b| format!("{:>9} Mo", b / 1_000_000),
+                    || format!("{:>12}", "-"),
+                    |b| format!("{:>12}", state.units.size(b)),
                 )
             } else {
                 "            ".to_string() // vide pour les non-vidéos
@@ -113,6 +144,31 @@ pub fn render_file_browser(frame: &mut Frame, area: Rect, state: &AppState) {
     list_state.select(Some(state.selected_index));
 
     frame.render_stateful_widget(list, area, &mut list_state);
+    preview
+}
+
+/// Étiquettes des répertoires épinglés (`1:Films`), celle du répertoire courant en gras
+fn pinned_spans(state: &AppState) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for (index, dir) in state.pinned_dirs.dirs().iter().enumerate() {
+        let name = dir.file_name().map_or_else(
+            || dir.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let style = if *dir == state.file_browser.current_dir {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Yellow)
+        };
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(format!("{}:{name}", index + 1), style));
+    }
+    if !spans.is_empty() {
+        spans.push(Span::raw(" "));
+    }
+    spans
 }
 
 fn entry_style(entry: &crate::app::state::DirEntry, is_selected: bool) -> Style {
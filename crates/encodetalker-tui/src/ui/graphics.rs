@@ -0,0 +1,469 @@
+use ratatui::layout::Rect;
+use std::fmt::Write as _;
+
+/// Protocole graphique du terminal utilisé pour les miniatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// Protocole graphique de kitty (aussi WezTerm, Ghostty)
+    Kitty,
+    /// Sixel (foot, mlterm, xterm -ti vt340...)
+    Sixel,
+}
+
+/// Taille d'une cellule supposée quand le terminal ne donne pas sa taille en pixels
+const DEFAULT_CELL_PIXELS: (u32, u32) = (8, 16);
+
+/// Taille des morceaux base64 envoyés au protocole kitty
+const KITTY_CHUNK: usize = 4096;
+
+impl GraphicsProtocol {
+    /// Détecter le protocole du terminal (None = pas d'images)
+    ///
+    /// `ENCODETALKER_GRAPHICS` (`kitty`, `sixel` ou `none`) force le choix ; sinon le
+    /// terminal est reconnu par `TERM`, `TERM_PROGRAM` et `KITTY_WINDOW_ID`.
+    #[must_use]
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).unwrap_or_default().to_lowercase();
+        match var("ENCODETALKER_GRAPHICS").as_str() {
+            "kitty" => return Some(Self::Kitty),
+            "sixel" => return Some(Self::Sixel),
+            "none" | "off" => return None,
+            _ => {}
+        }
+
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+        if std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || matches!(program.as_str(), "wezterm" | "ghostty")
+        {
+            Some(Self::Kitty)
+        } else if term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || term.contains("sixel")
+            || matches!(program.as_str(), "iterm.app" | "contour")
+        {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+
+    /// Séquence affichant `png` dans `area` (None si l'image est illisible)
+    #[must_use]
+    pub fn draw(self, png: &[u8], area: Rect) -> Option<String> {
+        let (width, height) = png_dimensions(png)?;
+        let (cols, rows) = fit_cells(width, height, area);
+        let mut out = format!("\x1b7\x1b[{};{}H", area.y + 1, area.x + 1);
+        match self {
+            Self::Kitty => out.push_str(&kitty_image(png, cols, rows)),
+            Self::Sixel => {
+                let (cell_w, cell_h) = cell_pixels();
+                let image = decode_stored_png(png)?;
+                let scaled = image.resize(u32::from(cols) * cell_w, u32::from(rows) * cell_h);
+                out.push_str(&sixel_image(&scaled));
+            }
+        }
+        out.push_str("\x1b8");
+        Some(out)
+    }
+
+    /// Séquence effaçant les images affichées (None = redessiner l'écran)
+    #[must_use]
+    pub fn clear(self) -> Option<&'static str> {
+        match self {
+            Self::Kitty => Some("\x1b_Ga=d,q=2\x1b\\"),
+            // Les pixels sixel restent jusqu'à ce que les cellules soient réécrites
+            Self::Sixel => None,
+        }
+    }
+}
+
+/// Taille d'une cellule en pixels
+fn cell_pixels() -> (u32, u32) {
+    crossterm::terminal::window_size()
+        .ok()
+        .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+        .map_or(DEFAULT_CELL_PIXELS, |size| {
+            (
+                u32::from(size.width / size.columns).max(1),
+                u32::from(size.height / size.rows).max(1),
+            )
+        })
+}
+
+/// Nombre de cellules couvertes par l'image, ratio conservé, sans dépasser `area`
+fn fit_cells(width: u32, height: u32, area: Rect) -> (u16, u16) {
+    let (cell_w, cell_h) = cell_pixels();
+    let max_w = u32::from(area.width) * cell_w;
+    let max_h = u32::from(area.height) * cell_h;
+    // Facteur d'échelle en fraction entière : min(max_w / width, max_h / height)
+    let (px_w, px_h) =
+        if u64::from(max_w) * u64::from(height) <= u64::from(max_h) * u64::from(width) {
+            (max_w, height * max_w / width.max(1))
+        } else {
+            (width * max_h / height.max(1), max_h)
+        };
+    let cols = (px_w / cell_w).clamp(1, u32::from(area.width));
+    let rows = (px_h / cell_h).clamp(1, u32::from(area.height));
+    (
+        u16::try_from(cols).unwrap_or(area.width),
+        u16::try_from(rows).unwrap_or(area.height),
+    )
+}
+
+/// Protocole kitty : PNG transmis en base64, par morceaux, étiré sur `cols` × `rows` cellules
+fn kitty_image(png: &[u8], cols: u16, rows: u16) -> String {
+    let encoded = base64(png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut out = String::with_capacity(encoded.len() + chunks.len() * 16);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            let _ = write!(out, "\x1b_Ga=T,f=100,q=2,C=1,c={cols},r={rows},m={more};");
+        } else {
+            let _ = write!(out, "\x1b_Gm={more};");
+        }
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\x1b\\");
+    }
+    out
+}
+
+/// Encodage base64 standard (avec remplissage)
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 63]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Image RGB 8 bits
+struct RgbImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl RgbImage {
+    fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let i = ((y * self.width + x) * 3) as usize;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    /// Réduire (plus proche voisin) pour tenir dans `max_w` × `max_h`, ratio conservé
+    fn resize(&self, max_w: u32, max_h: u32) -> Self {
+        let scale_w = f64::from(max_w) / f64::from(self.width);
+        let scale_h = f64::from(max_h) / f64::from(self.height);
+        let scale = scale_w.min(scale_h).min(1.0);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Safe: <= taille source
+        let (width, height) = (
+            ((f64::from(self.width) * scale) as u32).max(1),
+            ((f64::from(self.height) * scale) as u32).max(1),
+        );
+        let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+        for y in 0..height {
+            let sy = y * self.height / height;
+            for x in 0..width {
+                pixels.extend(self.pixel(x * self.width / width, sy));
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Largeur et hauteur lues dans l'en-tête IHDR
+fn png_dimensions(png: &[u8]) -> Option<(u32, u32)> {
+    if png.len() < 24 || &png[..8] != b"\x89PNG\r\n\x1a\n" || &png[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(png[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(png[20..24].try_into().ok()?);
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+/// Décoder un PNG RGB 8 bits non compressé et non filtré (miniatures du daemon)
+///
+/// Les autres PNG (compressés, filtrés, avec palette...) ne sont pas supportés.
+fn decode_stored_png(png: &[u8]) -> Option<RgbImage> {
+    let (width, height) = png_dimensions(png)?;
+    // Profondeur 8, RGB, sans entrelacement
+    if png.get(24..29)? != [8, 2, 0, 0, 0] {
+        return None;
+    }
+
+    let mut zlib = Vec::new();
+    let mut pos = 8;
+    while pos + 8 <= png.len() {
+        let len = u32::from_be_bytes(png[pos..pos + 4].try_into().ok()?) as usize;
+        let kind = &png[pos + 4..pos + 8];
+        let data = png.get(pos + 8..pos + 8 + len)?;
+        match kind {
+            b"IDAT" => zlib.extend_from_slice(data),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+
+    // Flux zlib : en-tête de 2 octets puis blocs deflate « stockés » uniquement
+    let mut raw = Vec::new();
+    let mut pos = 2;
+    loop {
+        let header = *zlib.get(pos)?;
+        if header & 0b110 != 0 {
+            return None; // Bloc compressé
+        }
+        let len = usize::from(u16::from_le_bytes(
+            zlib.get(pos + 1..pos + 3)?.try_into().ok()?,
+        ));
+        raw.extend_from_slice(zlib.get(pos + 5..pos + 5 + len)?);
+        pos += 5 + len;
+        if header & 1 == 1 {
+            break;
+        }
+    }
+
+    // Chaque ligne : octet de filtre (0 = aucun) puis les pixels
+    let stride = width as usize * 3;
+    let mut pixels = Vec::with_capacity(stride * height as usize);
+    for row in raw.chunks(stride + 1).take(height as usize) {
+        if row.len() != stride + 1 || row[0] != 0 {
+            return None;
+        }
+        pixels.extend_from_slice(&row[1..]);
+    }
+    (pixels.len() == stride * height as usize).then_some(RgbImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Index d'un pixel dans la palette 6×6×6
+fn palette_index([r, g, b]: [u8; 3]) -> usize {
+    let level = |c: u8| usize::from(c) * 5 / 255;
+    level(r) * 36 + level(g) * 6 + level(b)
+}
+
+/// Encoder une image en sixel avec une palette fixe de 216 couleurs
+fn sixel_image(image: &RgbImage) -> String {
+    let mut out = format!("\x1bPq\"1;1;{};{}", image.width, image.height);
+    for index in 0..216 {
+        let percent = |level: usize| level * 100 / 5;
+        let _ = write!(
+            out,
+            "#{index};2;{};{};{}",
+            percent(index / 36),
+            percent(index / 6 % 6),
+            percent(index % 6)
+        );
+    }
+
+    let width = image.width as usize;
+    let mut bands = vec![0u8; 216 * width];
+    for band_top in (0..image.height).step_by(6) {
+        bands.fill(0);
+        let mut used = [false; 216];
+        for dy in 0..6.min(image.height - band_top) {
+            for x in 0..image.width {
+                let index = palette_index(image.pixel(x, band_top + dy));
+                used[index] = true;
+                bands[index * width + x as usize] |= 1 << dy;
+            }
+        }
+
+        for index in (0..216).filter(|&i| used[i]) {
+            let _ = write!(out, "#{index}");
+            let row = &bands[index * width..(index + 1) * width];
+            // Répétitions codées `!<n><caractère>`
+            let mut x = 0;
+            while x < width {
+                let bits = row[x];
+                let run = row[x..].iter().take_while(|&&b| b == bits).count();
+                let c = char::from(63 + bits);
+                if run > 3 {
+                    let _ = write!(out, "!{run}{c}");
+                } else {
+                    out.extend(std::iter::repeat_n(c, run));
+                }
+                x += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PNG RGB 8 bits non compressé, comme ceux du daemon (CRC non vérifiés : nuls)
+    fn stored_png(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+        let chunk = |png: &mut Vec<u8>, kind: &[u8], data: &[u8]| {
+            png.extend_from_slice(&u32::try_from(data.len()).unwrap().to_be_bytes());
+            png.extend_from_slice(kind);
+            png.extend_from_slice(data);
+            png.extend_from_slice(&[0; 4]);
+        };
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+        chunk(&mut png, b"IHDR", &ihdr);
+
+        let stride = width as usize * 3;
+        let raw: Vec<u8> = pixels
+            .chunks(stride)
+            .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+            .collect();
+        let mut zlib = vec![0x78, 0x01];
+        let blocks: Vec<&[u8]> = raw.chunks(u16::MAX as usize).collect();
+        for (i, block) in blocks.iter().enumerate() {
+            let len = u16::try_from(block.len()).unwrap();
+            zlib.push(u8::from(i + 1 == blocks.len()));
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&[0; 4]); // Adler-32 non vérifié
+        chunk(&mut png, b"IDAT", &zlib);
+        chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn test_base64_rfc4648_vectors() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+        assert_eq!(base64(b"fooba"), "Zm9vYmE=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xff, 0xfe, 0x00]), "//4A");
+    }
+
+    #[test]
+    fn test_kitty_image_is_chunked() {
+        let data = vec![0u8; KITTY_CHUNK]; // 4096 octets → 5464 caractères base64
+        let sequence = kitty_image(&data, 10, 5);
+        assert!(sequence.starts_with("\x1b_Ga=T,f=100,q=2,C=1,c=10,r=5,m=1;"));
+        assert!(sequence.contains("\x1b_Gm=0;"));
+        assert_eq!(sequence.matches("\x1b\\").count(), 2);
+    }
+
+    #[test]
+    fn test_decode_stored_png_round_trip() {
+        let pixels: Vec<u8> = (0..3 * 2 * 3).map(|i| i * 10).collect();
+        let png = stored_png(3, 2, &pixels);
+        assert_eq!(png_dimensions(&png), Some((3, 2)));
+        let image = decode_stored_png(&png).unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(image.pixels, pixels);
+        assert_eq!(image.pixel(2, 1), [150, 160, 170]);
+
+        // Image plus grande qu'un bloc deflate stocké (64 Kio)
+        let pixels = vec![7u8; 200 * 120 * 3];
+        let image = decode_stored_png(&stored_png(200, 120, &pixels)).unwrap();
+        assert_eq!(image.pixels, pixels);
+    }
+
+    #[test]
+    fn test_decode_stored_png_rejects_malformed_input() {
+        let png = stored_png(2, 2, &[1; 12]);
+        assert!(decode_stored_png(&[]).is_none());
+        assert!(decode_stored_png(&png[..20]).is_none());
+        assert!(decode_stored_png(&png[..png.len() - 20]).is_none());
+
+        let mut bad_signature = png.clone();
+        bad_signature[1] = b'J';
+        assert!(png_dimensions(&bad_signature).is_none());
+
+        // Palette au lieu de RGB
+        let mut palette = png.clone();
+        palette[25] = 3;
+        assert!(decode_stored_png(&palette).is_none());
+
+        // Bloc deflate compressé (IDAT à l'octet 33, flux zlib après 8 octets d'en-tête)
+        let mut compressed = png.clone();
+        compressed[33 + 8 + 2] |= 0b010;
+        assert!(decode_stored_png(&compressed).is_none());
+
+        // Ligne filtrée (octet de filtre non nul)
+        let mut filtered = png.clone();
+        filtered[33 + 8 + 2 + 5] = 1;
+        assert!(decode_stored_png(&filtered).is_none());
+
+        // Largeur nulle
+        let mut empty = png;
+        empty[16..20].fill(0);
+        assert!(png_dimensions(&empty).is_none());
+    }
+
+    #[test]
+    fn test_resize_keeps_ratio_and_never_upscales() {
+        let image = RgbImage {
+            width: 40,
+            height: 20,
+            pixels: vec![0; 40 * 20 * 3],
+        };
+        let small = image.resize(10, 10);
+        assert_eq!((small.width, small.height), (10, 5));
+        assert_eq!(small.pixels.len(), 10 * 5 * 3);
+        let same = image.resize(400, 400);
+        assert_eq!((same.width, same.height), (40, 20));
+    }
+
+    #[test]
+    fn test_sixel_image() {
+        // 4 × 7 : deux bandes de 6 lignes, une seule couleur par bande
+        let mut pixels = vec![255u8; 4 * 6 * 3];
+        pixels.extend([0u8; 4 * 3]);
+        let image = RgbImage {
+            width: 4,
+            height: 7,
+            pixels,
+        };
+        let sixel = sixel_image(&image);
+        assert!(sixel.starts_with("\x1bPq\"1;1;4;7#0;2;0;0;0#1;2;0;0;20"));
+        assert!(sixel.ends_with("\x1b\\"));
+        // Blanc (index 215), 6 lignes pleines répétées 4 fois ; puis noir sur 1 ligne
+        assert!(sixel.contains("#215!4~$-"));
+        assert!(sixel.contains("#0!4@$-"));
+        assert_eq!(sixel.matches('-').count(), 2);
+
+        // Répétitions courtes écrites telles quelles
+        let image = RgbImage {
+            width: 2,
+            height: 1,
+            pixels: vec![0; 6],
+        };
+        assert!(sixel_image(&image).contains("#0@@$-"));
+        assert_eq!(palette_index([255, 0, 0]), 180);
+        assert_eq!(palette_index([0, 0, 255]), 5);
+    }
+}
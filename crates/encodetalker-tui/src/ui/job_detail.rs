@@ -0,0 +1,201 @@
+use crate::input::short_batch_id;
+use chrono::{DateTime, Local, Utc};
+use encodetalker_common::{EncodingJob, JobKind, UnitPreferences};
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Clear, Paragraph,
+    },
+};
+
+/// Date locale d'un horodatage (`--` si absent)
+fn format_date(date: Option<DateTime<Utc>>) -> String {
+    date.map_or_else(
+        || "--".to_string(),
+        |dt| {
+            dt.with_timezone(&Local)
+                .format("%d/%m/%Y %H:%M:%S")
+                .to_string()
+        },
+    )
+}
+
+/// Durée entre deux horodatages au format `H:MM:SS`
+fn format_elapsed(from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    let secs = (to - from).num_seconds().max(0);
+    format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+}
+
+/// Lignes du détail d'un job : identité, horodatages, statistiques, erreur, configuration
+#[must_use]
+pub fn job_detail_lines(job: &EncodingJob, units: UnitPreferences) -> Vec<String> {
+    let mut lines = vec![format!("Job:     {}", job.id)];
+    let status = match job.failure_category {
+        Some(category) => format!("{} [{category}]", job.status),
+        None => job.status.to_string(),
+    };
+    lines.push(format!("Statut:  {status}"));
+    let kind = match job.kind {
+        JobKind::Encode => format!(
+            "Encodage ({} + {})",
+            job.config.video_label(),
+            job.config.audio_label()
+        ),
+        JobKind::Preview => format!(
+            "Extrait de test ({} + {})",
+            job.config.video_label(),
+            job.config.audio_label()
+        ),
+        JobKind::Verify => "Vérification".to_string(),
+    };
+    lines.push(format!("Type:    {kind}"));
+    if let Some(batch_id) = job.batch_id {
+        lines.push(format!("Lot:     {}", short_batch_id(batch_id)));
+    }
+    lines.push(format!("Entrée:  {}", job.input_path.display()));
+    lines.push(format!("Sortie:  {}", job.output_path.display()));
+
+    lines.push(String::new());
+    lines.push(format!("Créé:    {}", format_date(Some(job.created_at))));
+    lines.push(format!("Démarré: {}", format_date(job.started_at)));
+    lines.push(format!("Terminé: {}", format_date(job.finished_at)));
+    if let Some(started) = job.started_at {
+        lines.push(format!(
+            "Attente: {}",
+            format_elapsed(job.created_at, started)
+        ));
+        lines.push(format!(
+            "Durée:   {}",
+            format_elapsed(started, job.finished_at.unwrap_or_else(Utc::now))
+        ));
+    }
+
+    let mut process = Vec::new();
+    if let Some(threads) = job.allocated_threads {
+        process.push(format!("{threads} threads"));
+    }
+    if let Some(nice) = job.nice {
+        process.push(format!("nice {nice}"));
+    }
+    if let Some(level) = job.fallback_level {
+        process.push(format!(
+            "repli {level} (preset {})",
+            job.config.encoder_params.preset
+        ));
+    }
+    if job.retries > 0 {
+        process.push(format!("{} nouvel(s) essai(s)", job.retries));
+    }
+    if !process.is_empty() {
+        lines.push(format!("Exécution: {}", process.join(", ")));
+    }
+
+    if let Some(stats) = &job.stats {
+        lines.push(String::new());
+        lines.push("Statistiques:".to_string());
+        let frames = match stats.total_frames {
+            Some(total) => format!("{}/{total}", stats.frame),
+            None => stats.frame.to_string(),
+        };
+        lines.push(format!(
+            "  Frames: {frames} ({:.1}%), {:.2} fps, {}",
+            stats.progress_percent,
+            stats.fps,
+            units.bitrate(stats.bitrate)
+        ));
+        if let Some(summary) = stats.progress_summary() {
+            lines.push(format!("  Étape:  {summary}"));
+        }
+        if stats.total_passes > 1 {
+            lines.push(format!(
+                "  Passe:  {}/{}",
+                stats.current_pass, stats.total_passes
+            ));
+        }
+        if let Some(vmaf) = stats.vmaf_score {
+            let range = match (stats.vmaf_min, stats.vmaf_max) {
+                (Some(min), Some(max)) => format!(" (min {min:.2}, max {max:.2})"),
+                _ => String::new(),
+            };
+            lines.push(format!("  VMAF:   {vmaf:.2}{range}"));
+        }
+        if let Some(psnr) = stats.psnr {
+            lines.push(format!("  PSNR:   {psnr:.2} dB (luma)"));
+        }
+        if let Some(ssim) = stats.ssim {
+            lines.push(format!("  SSIM:   {ssim:.4}"));
+        }
+        if let Some(path) = &stats.vmaf_json_path {
+            lines.push(format!("  Scores VMAF: {} (v: graphe)", path.display()));
+        }
+    }
+
+    if let Some(report) = &job.verify_report {
+        lines.push(String::new());
+        lines.push(format!("Vérification: {}", report.summary()));
+        for problem in report.problems() {
+            lines.push(format!("  ⚠ {problem}"));
+        }
+    }
+
+    if let Some(error) = &job.error_message {
+        lines.push(String::new());
+        lines.push("Erreur:".to_string());
+        lines.extend(error.lines().map(|line| format!("  {line}")));
+    }
+
+    lines.push(String::new());
+    lines.push("Configuration:".to_string());
+    match serde_json::to_string_pretty(&job.config) {
+        Ok(config) => lines.extend(config.lines().map(|line| format!("  {line}"))),
+        Err(e) => lines.push(format!("  (illisible: {e})")),
+    }
+    lines
+}
+
+/// Rendre le détail d'un job (lecture seule, défilable)
+pub fn render_job_detail(
+    frame: &mut Frame,
+    dialog_area: Rect,
+    job: &EncodingJob,
+    scroll: usize,
+    units: UnitPreferences,
+) {
+    frame.render_widget(Clear, dialog_area);
+
+    let filename = job
+        .input_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("job");
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Détail : {filename} "))
+        .title(
+            Title::from(" ↑↓: Défiler | v: Graphe VMAF | ESC: Fermer ")
+                .position(Position::Bottom)
+                .alignment(Alignment::Center),
+        )
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let lines: Vec<Line> = job_detail_lines(job, units)
+        .into_iter()
+        .skip(scroll)
+        .take(inner.height as usize)
+        .map(|line| {
+            // Titres de section en évidence
+            if line.ends_with(':') && !line.starts_with(' ') {
+                Line::styled(line, Style::default().fg(Color::Cyan).bold())
+            } else {
+                Line::from(line)
+            }
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).style(Style::default().fg(Color::White)),
+        inner,
+    );
+}
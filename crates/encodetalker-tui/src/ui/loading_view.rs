@@ -1,4 +1,5 @@
 use crate::app::state::LoadingState;
+use encodetalker_common::protocol::messages::{BuildEnvCheckKind, BuildEnvReport};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -20,7 +21,10 @@ pub fn render_loading_view(frame: &mut Frame, area: Rect, state: &LoadingState)
 
     // Si total_deps = 0, on est en train de vérifier
     if state.total_deps == 0 {
-        render_checking_deps(frame, area);
+        match &state.build_env {
+            Some(report) => render_build_env(frame, area, report),
+            None => render_checking_deps(frame, area),
+        }
         return;
     }
 
@@ -33,20 +37,24 @@ pub fn render_loading_view(frame: &mut Frame, area: Rect, state: &LoadingState)
             Constraint::Length(3), // Barre de progression
             Constraint::Length(8), // Liste des dépendances
             Constraint::Length(3), // Étape actuelle
-            Constraint::Min(0),    // Espace
+            Constraint::Min(0),    // Sortie des compilateurs (si affichée)
             Constraint::Length(3), // Aide
         ])
         .split(area);
 
     // Titre
-    let title = Paragraph::new("Compilation des dépendances")
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+    let title = Paragraph::new(if state.resumed {
+        "Compilation interrompue : reprise des dépendances manquantes"
+    } else {
+        "Compilation des dépendances"
+    })
+    .style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
     frame.render_widget(title, chunks[0]);
 
     // Barre de progression
@@ -82,20 +90,72 @@ pub fn render_loading_view(frame: &mut Frame, area: Rect, state: &LoadingState)
         );
     frame.render_widget(current_step, chunks[3]);
 
+    if state.show_logs {
+        render_compilation_logs(frame, chunks[4], state);
+    }
+
     // Aide
-    let help = Paragraph::new("q: Quitter | Première compilation: 30-60 minutes")
-        .style(Style::default().fg(Color::DarkGray))
-        .alignment(Alignment::Center);
+    let help = Paragraph::new(if state.show_logs {
+        "q: Quitter | l: Masquer les logs | Première compilation: 30-60 minutes"
+    } else {
+        "q: Quitter | l: Afficher les logs | Première compilation: 30-60 minutes"
+    })
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center);
     frame.render_widget(help, chunks[5]);
 }
 
+/// Afficher les dernières lignes de sortie des compilateurs (défilement automatique)
+fn render_compilation_logs(frame: &mut Frame, area: Rect, state: &LoadingState) {
+    let visible = usize::from(area.height.saturating_sub(2));
+    let skip = state.log_lines.len().saturating_sub(visible);
+    let lines: Vec<Line> = state
+        .log_lines
+        .iter()
+        .skip(skip)
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    let text = if lines.is_empty() {
+        vec![Line::styled(
+            "En attente de sortie des compilateurs...",
+            Style::default().fg(Color::DarkGray),
+        )]
+    } else {
+        lines
+    };
+    let logs = Paragraph::new(text)
+        .style(Style::default().fg(Color::Gray))
+        .block(
+            Block::default()
+                .title("Sortie de compilation")
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(logs, area);
+}
+
 /// Afficher la liste des dépendances avec leur statut
 fn render_deps_list(frame: &mut Frame, area: Rect, state: &LoadingState) {
-    let deps = [
+    let all_deps = [
         ("FFmpeg", "15-20 min"),
-        ("SVT-AV1-PSY", "10-15 min"),
+        ("SVT-AV1", "10-15 min"),
         ("libaom", "15-20 min"),
     ];
+    // Reprise : seules les dépendances encore manquantes sont compilées
+    let deps: Vec<(&str, &str)> = if state.deps.is_empty() {
+        all_deps.to_vec()
+    } else {
+        state
+            .deps
+            .iter()
+            .map(|name| {
+                let duration = all_deps
+                    .iter()
+                    .find(|(known, _)| known == name)
+                    .map_or("?", |(_, duration)| *duration);
+                (name.as_str(), duration)
+            })
+            .collect()
+    };
 
     let items: Vec<ListItem> = deps
         .iter()
@@ -156,6 +216,81 @@ fn render_checking_deps(frame: &mut Frame, area: Rect) {
     frame.render_widget(message, chunks[1]);
 }
 
+/// Afficher le diagnostic de l'environnement de compilation (checklist)
+fn render_build_env(frame: &mut Frame, area: Rect, report: &BuildEnvReport) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3), // Titre
+            Constraint::Min(5),    // Checklist
+            Constraint::Length(3), // Verdict
+            Constraint::Length(3), // Aide
+        ])
+        .split(area);
+
+    let title = Paragraph::new("Environnement de compilation")
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let items: Vec<ListItem> = report
+        .checks
+        .iter()
+        .map(|check| {
+            let (icon, style) = match (check.found, check.required) {
+                (true, _) => ("✅", Style::default().fg(Color::Green)),
+                (false, true) => ("❌", Style::default().fg(Color::Red)),
+                // Bibliothèque absente mais compilée par le script
+                (false, false) => ("⚠", Style::default().fg(Color::Yellow)),
+            };
+            let kind = match check.kind {
+                BuildEnvCheckKind::Tool => "outil",
+                BuildEnvCheckKind::Library => "bibliothèque",
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(icon, style),
+                Span::raw(" "),
+                Span::styled(format!("{} ({kind})", check.name), style),
+                Span::styled(
+                    format!(" - {}", check.detail),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items).block(Block::default().title("Prérequis").borders(Borders::ALL));
+    frame.render_widget(list, chunks[1]);
+
+    let (verdict, color) = if report.is_ready() {
+        (
+            "✅ Environnement prêt : lancer ./INSTALL_DEPENDENCIES.sh",
+            Color::Green,
+        )
+    } else {
+        (
+            "❌ Outils manquants : les installer avant de lancer la compilation",
+            Color::Red,
+        )
+    };
+    let verdict = Paragraph::new(verdict)
+        .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(verdict, chunks[2]);
+
+    let help = Paragraph::new("q: Quitter")
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[3]);
+}
+
 /// Afficher l'état d'erreur
 fn render_error_state(frame: &mut Frame, area: Rect, error: &str) {
     let chunks = Layout::default()
@@ -1,13 +1,19 @@
+pub mod analytics_view;
 pub mod dialog;
 pub mod file_browser;
+pub mod graphics;
+pub mod job_detail;
 pub mod loading_view;
 pub mod queue_view;
 pub mod render;
 pub mod stats_view;
 pub mod vmaf_graph;
 
+pub use analytics_view::*;
 pub use dialog::*;
 pub use file_browser::*;
+pub use graphics::*;
+pub use job_detail::*;
 pub use loading_view::*;
 pub use queue_view::*;
 pub use render::*;
@@ -6,9 +6,18 @@ use ratatui::{
 
 /// Rendre la vue de la queue
 pub fn render_queue_view(frame: &mut Frame, area: Rect, state: &AppState) {
+    let grabbed = state.grabbed_job.map(|grab| grab.job_id);
+    let title = if grabbed.is_some() {
+        format!(
+            " 📋 Queue ({} jobs) — déplacement : ↑↓ puis Entrée ",
+            state.queue_jobs.len()
+        )
+    } else {
+        format!(" 📋 Queue ({} jobs) ", state.queue_jobs.len())
+    };
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(format!(" 📋 Queue ({} jobs) ", state.queue_jobs.len()))
+        .title(title)
         .border_style(Style::default().fg(Color::Yellow));
 
     if state.queue_jobs.is_empty() {
@@ -26,50 +35,90 @@ pub fn render_queue_view(frame: &mut Frame, area: Rect, state: &AppState) {
     let items: Vec<ListItem> = state
         .queue_jobs
         .iter()
-        .map(|job| {
+        .enumerate()
+        .map(|(position, job)| {
             let filename = job
                 .input_path
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("unknown");
 
-            let encoder = match job.config.encoder {
-                encodetalker_common::EncoderType::SvtAv1 => "SVT-AV1",
-                encodetalker_common::EncoderType::Aom => "libaom",
+            let encoder = job.config.video_label();
+            let audio = job.config.audio_label();
+
+            let vmaf = match (job.config.enable_vmaf, job.config.quality_metrics) {
+                (_, true) => "oui + PSNR/SSIM",
+                (true, false) => "oui",
+                (false, false) => "non",
+            };
+
+            let ahead = match position {
+                0 => "prochain".to_string(),
+                n => format!("{n} devant"),
             };
 
-            let audio = match &job.config.audio_mode {
-                encodetalker_common::AudioMode::Opus { bitrate } => format!("Opus {bitrate}k"),
-                encodetalker_common::AudioMode::Copy => "Copie".to_string(),
-                encodetalker_common::AudioMode::Custom { codec, bitrate } => {
-                    format!("{codec} {bitrate}k")
-                }
+            let batch = job
+                .batch_id
+                .map(|batch_id| format!(" [lot {}]", crate::input::short_batch_id(batch_id)))
+                .unwrap_or_default();
+            let batch = if job.kind == encodetalker_common::JobKind::Preview {
+                format!("{batch} [extrait de test]")
+            } else {
+                batch
             };
 
-            let vmaf = if job.config.enable_vmaf { "oui" } else { "non" };
+            let mut text = if job.kind == encodetalker_common::JobKind::Verify {
+                format!(
+                    "#{} {}{} ({})\n  Vérification (probe et décodage complet)",
+                    position + 1,
+                    filename,
+                    batch,
+                    ahead
+                )
+            } else {
+                format!(
+                    "#{} {}{} ({})\n  Encoder: {} | Audio: {} | CRF: {} | Preset: {} | VMAF: {}",
+                    position + 1,
+                    filename,
+                    batch,
+                    ahead,
+                    encoder,
+                    audio,
+                    job.config.encoder_params.crf,
+                    job.config.encoder_params.preset,
+                    vmaf
+                )
+            };
 
-            let text = format!(
-                "{}\n  Encoder: {} | Audio: {} | CRF: {} | Preset: {} | VMAF: {}",
-                filename,
-                encoder,
-                audio,
-                job.config.encoder_params.crf,
-                job.config.encoder_params.preset,
-                vmaf
-            );
+            let trim = job.config.trim;
+            if !trim.is_full() {
+                let bound = |t: Option<f64>, default: &str| {
+                    t.map_or_else(
+                        || default.to_string(),
+                        encodetalker_common::format_timestamp,
+                    )
+                };
+                text.push_str(&format!(
+                    " | Extrait: {} → {}",
+                    bound(trim.start, "début"),
+                    bound(trim.end, "fin")
+                ));
+            }
 
             ListItem::new(text).style(Style::default().fg(Color::White))
         })
         .collect();
 
+    // Job saisi : surligné en jaune, avec un symbole de déplacement
+    let (highlight, symbol) = if grabbed.is_some() {
+        (Style::default().bg(Color::Yellow).fg(Color::Black), "⇅ ")
+    } else {
+        (Style::default().bg(Color::DarkGray), "▶ ")
+    };
     let list = List::new(items)
         .block(block)
-        .highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol("▶ ");
+        .highlight_style(highlight.add_modifier(Modifier::BOLD))
+        .highlight_symbol(symbol);
 
     let mut list_state = ListState::default();
     list_state.select(Some(state.selected_index));
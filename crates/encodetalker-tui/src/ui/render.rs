@@ -1,7 +1,7 @@
 use crate::app::{AppState, View};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Paragraph, Tabs},
+    widgets::{block::Title, Block, Borders, Paragraph, Tabs},
 };
 
 /// Rendre l'interface complète
@@ -22,6 +22,7 @@ pub fn render_ui(frame: &mut Frame, state: &mut AppState) {
     state.layout.header = chunks[0];
     state.layout.content = chunks[1];
     state.layout.content_inner = chunks[1].inner(&layout::Margin::new(1, 1));
+    state.layout.preview = None;
 
     // Rendre le header
     render_header(frame, chunks[0], &*state);
@@ -34,10 +35,13 @@ pub fn render_ui(frame: &mut Frame, state: &mut AppState) {
             }
             return; // Ne pas afficher header/footer pour Loading
         }
-        View::FileBrowser => crate::ui::render_file_browser(frame, chunks[1], &*state),
+        View::FileBrowser => {
+            state.layout.preview = crate::ui::render_file_browser(frame, chunks[1], &*state);
+        }
         View::Queue => crate::ui::render_queue_view(frame, chunks[1], &*state),
         View::Active => crate::ui::render_active_view(frame, chunks[1], &*state),
         View::History => crate::ui::render_history_view(frame, chunks[1], &*state),
+        View::Stats => crate::ui::render_analytics_view(frame, chunks[1], &*state),
     }
 
     // Rendre le footer
@@ -56,16 +60,31 @@ fn render_header(frame: &mut Frame, area: Rect, state: &AppState) {
         "Queue",
         "Encodage en cours...",
         "Historique",
+        "Statistiques",
     ];
     let selected = match state.current_view {
         View::Loading | View::FileBrowser => 0,
         View::Queue => 1,
         View::Active => 2,
         View::History => 3,
+        View::Stats => 4,
     };
 
+    let mut block = Block::default().borders(Borders::ALL).title("EncodeTalker");
+    // Bandeau visible dans toutes les vues tant que le daemon termine ses jobs
+    if !state.daemon_accepting_jobs {
+        block = block
+            .title(
+                Title::from(Line::styled(
+                    " ⚠ Daemon en cours d'arrêt : nouveaux jobs refusés ",
+                    Style::default().fg(Color::Red).bold(),
+                ))
+                .alignment(Alignment::Right),
+            )
+            .border_style(Style::default().fg(Color::Red));
+    }
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL).title("EncodeTalker"))
+        .block(block)
         .select(selected)
         .style(Style::default().fg(Color::White))
         .highlight_style(
@@ -84,9 +103,12 @@ fn render_footer(frame: &mut Frame, area: Rect, state: &AppState) {
     } else {
         match state.current_view {
             View::Loading => " q: Quitter ", // Ne devrait pas arriver (Loading affiche son propre footer)
-            View::FileBrowser => " Tab: Vue suivante | ↑↓: Naviguer | Entrée: Ouvrir | ESPACE: Sélectionner | Ctrl+A: Tout | a: Ajouter | i: Info | r: Rafraîchir | q: Quitter ",
-            View::Queue | View::Active => " Tab: Vue suivante | ↑↓: Naviguer | c: Annuler | r: Rafraîchir | q: Quitter ",
-            View::History => " Tab: Vue suivante | ↑↓: Naviguer | Entrée: Graphe VMAF | r: Réessayer | c: Effacer | C: Tout effacer | q: Quitter ",
+            View::FileBrowser => " Tab: Vue suivante | ↑↓: Naviguer | Entrée: Ouvrir | ESPACE: Sélectionner | Ctrl+A: Tout | a: Ajouter | J: Joindre | v: Vérifier | i: Info | b: Benchmark | p: Épingler | 1-9: Épingle | r: Rafraîchir | L: Logs | q: Quitter ",
+            View::Queue if state.grabbed_job.is_some() => " ↑↓: Déplacer le job | Entrée/m: Déposer | ESC: Annuler le déplacement ",
+            View::Queue => " Tab: Vue suivante | ↑↓: Naviguer | Entrée: Détail | m: Déplacer | c: Annuler | C: Annuler le lot | r: Rafraîchir | L: Logs | q: Quitter ",
+            View::Active => " Tab: Vue suivante | ↑↓: Naviguer | Entrée: Détail | c: Annuler | +/-: Priorité | r: Rafraîchir | L: Logs | q: Quitter ",
+            View::History => " Tab: Vue suivante | ↑↓: Naviguer | Entrée: Détail | v: Graphe VMAF | r: Réessayer | c: Effacer | C: Tout effacer | L: Logs | q: Quitter ",
+            View::Stats => " Tab: Vue suivante | ↑↓: Naviguer | r: Rafraîchir | M: Maintenance | L: Logs | q: Quitter ",
         }
     };
 
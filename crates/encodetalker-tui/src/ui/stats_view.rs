@@ -1,6 +1,6 @@
 use crate::app::AppState;
 use chrono::Local;
-use encodetalker_common::JobStatus;
+use encodetalker_common::{EncodingPhase, EncodingStats, JobKind, JobStatus, UnitPreferences};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph},
@@ -20,8 +20,9 @@ pub fn render_active_view(frame: &mut Frame, area: Rect, state: &AppState) {
     let block = Block::default()
         .borders(Borders::ALL)
         .title(format!(
-            " ⚙️  Encodage en cours ({}) ",
-            state.active_jobs.len()
+            " ⚙️  Encodage en cours ({}/{} slots) ",
+            state.active_jobs.len(),
+            state.max_concurrent_jobs.max(state.active_jobs.len())
         ))
         .border_style(Style::default().fg(Color::Green));
 
@@ -46,9 +47,30 @@ pub fn render_active_view(frame: &mut Frame, area: Rect, state: &AppState) {
         )
         .split(area);
 
+    // Rang de démarrage parmi les jobs actifs : le daemon n'attribue pas de slot numéroté
+    let mut by_start: Vec<_> = state
+        .active_jobs
+        .iter()
+        .map(|j| (j.started_at, j.id))
+        .collect();
+    by_start.sort();
+    let slots = state.max_concurrent_jobs.max(state.active_jobs.len());
+
     for (i, job) in state.active_jobs.iter().enumerate() {
         if i < chunks.len() {
-            render_active_job(frame, chunks[i], job, i == state.selected_index);
+            let rank = by_start
+                .iter()
+                .position(|&(_, id)| id == job.id)
+                .unwrap_or(i)
+                + 1;
+            render_active_job(
+                frame,
+                chunks[i],
+                job,
+                (rank, slots),
+                i == state.selected_index,
+                state.units,
+            );
         }
     }
 }
@@ -58,13 +80,20 @@ fn render_active_job(
     frame: &mut Frame,
     area: Rect,
     job: &encodetalker_common::EncodingJob,
+    (rank, slots): (usize, usize),
     selected: bool,
+    units: UnitPreferences,
 ) {
     let filename = job
         .input_path
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown");
+    let filename = match job.kind {
+        encodetalker_common::JobKind::Verify => format!("{filename} | Vérification"),
+        encodetalker_common::JobKind::Preview => format!("{filename} | Extrait de test"),
+        encodetalker_common::JobKind::Encode => filename.to_string(),
+    };
 
     let border_style = if selected {
         Style::default().fg(Color::Yellow)
@@ -74,17 +103,34 @@ fn render_active_job(
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(format!(" {filename} "))
+        .title(
+            match job
+                .stats
+                .as_ref()
+                .and_then(|s| s.segment_index.zip(s.segment_count))
+            {
+                Some((index, count)) => format!(
+                    " Démarré {rank}/{slots} | {filename} | Segment {index}/{count} | nice {} ",
+                    job.nice.unwrap_or(0)
+                ),
+                None => format!(
+                    " Démarré {rank}/{slots} | {filename} | nice {} ",
+                    job.nice.unwrap_or(0)
+                ),
+            },
+        )
         .border_style(border_style);
 
     if let Some(stats) = &job.stats {
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
-        if stats.is_calculating_vmaf {
-            render_vmaf_progress(frame, inner, stats);
-        } else {
-            render_encoding_progress(frame, inner, stats);
+        match stats.phase {
+            Some(phase @ (EncodingPhase::Audio | EncodingPhase::Muxing)) => {
+                render_waiting_phase(frame, inner, stats, phase);
+            }
+            _ if stats.is_calculating_vmaf => render_vmaf_progress(frame, inner, stats),
+            _ => render_encoding_progress(frame, inner, stats, units),
         }
     } else {
         let text = Paragraph::new("Démarrage...")
@@ -95,6 +141,51 @@ fn render_active_job(
     }
 }
 
+/// Afficher le résumé `Vidéo 80% • global 60%` (rien avec un daemon antérieur)
+fn render_progress_summary(frame: &mut Frame, area: Rect, stats: &EncodingStats) {
+    if let Some(summary) = stats.progress_summary() {
+        frame.render_widget(
+            Paragraph::new(summary).style(Style::default().fg(Color::Yellow)),
+            area,
+        );
+    }
+}
+
+/// Rendre une étape sans progression mesurée (attente de l'audio, muxage) :
+/// seul l'avancement global est affiché
+fn render_waiting_phase(
+    frame: &mut Frame,
+    inner: Rect,
+    stats: &EncodingStats,
+    phase: EncodingPhase,
+) {
+    let info_text = match phase {
+        EncodingPhase::Audio => "Vidéo terminée, fin de l'encodage audio...",
+        _ => "Muxage final...",
+    };
+
+    let info_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(3),
+        ])
+        .split(inner);
+
+    let info = Paragraph::new(info_text).style(Style::default().fg(Color::Yellow));
+    frame.render_widget(info, info_chunks[1]);
+
+    if let Some(overall) = stats.overall_percent {
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::NONE))
+            .gauge_style(Style::default().fg(Color::Yellow).bg(Color::DarkGray))
+            .percent(percent_to_u16(overall))
+            .label(format!("Global: {overall:.1}%"));
+        frame.render_widget(gauge, info_chunks[2]);
+    }
+}
+
 /// Rendre la progression du calcul VMAF
 fn render_vmaf_progress(
     frame: &mut Frame,
@@ -118,6 +209,7 @@ fn render_vmaf_progress(
         ])
         .split(inner);
 
+    render_progress_summary(frame, info_chunks[0], stats);
     let info = Paragraph::new(vmaf_info).style(Style::default().fg(Color::Cyan));
     frame.render_widget(info, info_chunks[1]);
 
@@ -135,6 +227,7 @@ fn render_encoding_progress(
     frame: &mut Frame,
     inner: Rect,
     stats: &encodetalker_common::EncodingStats,
+    units: UnitPreferences,
 ) {
     let progress = stats.progress_percent;
 
@@ -149,8 +242,11 @@ fn render_encoding_progress(
     };
 
     let info_text = format!(
-        "Frame: {} | FPS: {:.1} | Bitrate: {:.1} kbps | {}",
-        stats.frame, stats.fps, stats.bitrate, eta_text
+        "Frame: {} | FPS: {:.1} | Bitrate: {} | {}",
+        stats.frame,
+        stats.fps,
+        units.bitrate(stats.bitrate),
+        eta_text
     );
 
     let info_chunks = Layout::default()
@@ -162,6 +258,7 @@ fn render_encoding_progress(
         ])
         .split(inner);
 
+    render_progress_summary(frame, info_chunks[0], stats);
     let info = Paragraph::new(info_text).style(Style::default().fg(Color::White));
     frame.render_widget(info, info_chunks[1]);
 
@@ -256,11 +353,43 @@ pub fn render_history_view(frame: &mut Frame, area: Rect, state: &AppState) {
                 "--:--:--".to_string()
             };
 
-            let vmaf_text = if let Some(vmaf) = job.stats.as_ref().and_then(|s| s.vmaf_score) {
+            // Ce qui a été produit (sans objet pour une vérification)
+            let output_text = match job.kind {
+                JobKind::Encode => format!(
+                    " | {} + {}",
+                    job.config.video_label(),
+                    job.config.audio_label()
+                ),
+                JobKind::Preview => format!(
+                    " | Extrait de test {} + {}",
+                    job.config.video_label(),
+                    job.config.audio_label()
+                ),
+                JobKind::Verify => String::new(),
+            };
+
+            let mut vmaf_text = if let Some(vmaf) = job.stats.as_ref().and_then(|s| s.vmaf_score) {
                 format!(" | VMAF: {vmaf:.2}")
             } else {
                 String::new()
             };
+            if let Some(stats) = &job.stats {
+                if let Some(psnr) = stats.psnr {
+                    vmaf_text.push_str(&format!(" | PSNR: {psnr:.2} dB"));
+                }
+                if let Some(ssim) = stats.ssim {
+                    vmaf_text.push_str(&format!(" | SSIM: {ssim:.4}"));
+                }
+            }
+
+            let fallback_text = if let Some(level) = job.fallback_level {
+                format!(
+                    " | Repli {level} (preset {})",
+                    job.config.encoder_params.preset
+                )
+            } else {
+                String::new()
+            };
 
             let started_text = match job.started_at {
                 Some(dt) => dt
@@ -278,14 +407,23 @@ pub fn render_history_view(frame: &mut Frame, area: Rect, state: &AppState) {
                 None => "--".to_string(),
             };
 
+            let verify_text = job
+                .verify_report
+                .as_ref()
+                .map(|report| format!("\n  Vérification: {}", report.summary()))
+                .unwrap_or_default();
+
             let error_text = if let Some(error) = &job.error_message {
-                format!("\n  Erreur: {error}")
+                match job.failure_category {
+                    Some(category) => format!("\n  Erreur [{category}]: {error}"),
+                    None => format!("\n  Erreur: {error}"),
+                }
             } else {
                 String::new()
             };
 
             let text = format!(
-                "{status_icon} {filename} | Durée: {duration_text}{vmaf_text}\n  Début: {started_text}\n  Fin:   {finished_text}{error_text}"
+                "{status_icon} {filename}{output_text} | Durée: {duration_text}{vmaf_text}{fallback_text}\n  Début: {started_text}\n  Fin:   {finished_text}{verify_text}{error_text}"
             );
 
             ListItem::new(text).style(Style::default().fg(status_color))
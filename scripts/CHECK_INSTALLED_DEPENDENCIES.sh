@@ -131,7 +131,7 @@ check_vmaf() {
 check_svt_av1() {
     echo "Checking SVT-AV1-PSY..."
 
-    local binary_path="$DEPS_BIN/SvtAv1EncApp"
+    local binary_path="${SVT_AV1_BIN:-$DEPS_BIN/SvtAv1EncApp}"
     local status="✗"
 
     if [[ -x "$binary_path" ]]; then
@@ -154,7 +154,7 @@ check_svt_av1() {
 check_aomenc() {
     echo "Checking libaom (aomenc)..."
 
-    local binary_path="$DEPS_BIN/aomenc"
+    local binary_path="${AOMENC_BIN:-$DEPS_BIN/aomenc}"
     local status="✗"
 
     if [[ -x "$binary_path" ]]; then
@@ -31,6 +31,26 @@ if [[ -f "$CONFIG_FILE" ]]; then
     fi
 fi
 
+# Variante de SVT-AV1 ([deps] svt_av1_variant = "psy" | "mainline") et tag/commit optionnel
+SVT_AV1_VARIANT="psy"
+SVT_AV1_REF=""
+if [[ -f "$CONFIG_FILE" ]]; then
+    custom_variant=$(grep '^\s*svt_av1_variant\s*=' "$CONFIG_FILE" 2>/dev/null | sed 's/.*=\s*"\(.*\)"/\1/' | head -1)
+    if [[ -n "$custom_variant" ]]; then
+        SVT_AV1_VARIANT="$custom_variant"
+    fi
+    SVT_AV1_REF=$(grep '^\s*svt_av1_ref\s*=' "$CONFIG_FILE" 2>/dev/null | sed 's/.*=\s*"\(.*\)"/\1/' | head -1)
+fi
+
+# Profondeur des clones Git ([deps] git_clone_depth, 0 = historique complet)
+GIT_CLONE_DEPTH=1
+if [[ -f "$CONFIG_FILE" ]]; then
+    custom_depth=$(grep '^\s*git_clone_depth\s*=' "$CONFIG_FILE" 2>/dev/null | sed 's/.*=\s*\([0-9]*\).*/\1/' | head -1)
+    if [[ -n "$custom_depth" ]]; then
+        GIT_CLONE_DEPTH="$custom_depth"
+    fi
+fi
+
 # URLs sources (même que dans downloader.rs)
 OPUS_VERSION="1.6.1"
 OPUS_URL="https://downloads.xiph.org/releases/opus/opus-${OPUS_VERSION}.tar.gz"
@@ -39,7 +59,8 @@ LIBVPX_URL="https://github.com/webmproject/libvpx/archive/refs/tags/v${LIBVPX_VE
 FFMPEG_VERSION="8.0.1"
 FFMPEG_URL="https://ffmpeg.org/releases/ffmpeg-${FFMPEG_VERSION}.tar.xz"
 FFMPEG_WINDOWS_URL="https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip"
-SVT_AV1_GIT="https://github.com/BlueSwordM/svt-av1-psy.git"
+SVT_AV1_PSY_GIT="https://github.com/BlueSwordM/svt-av1-psy.git"
+SVT_AV1_MAINLINE_GIT="https://gitlab.com/AOMediaCodec/SVT-AV1.git"
 LIBAOM_GIT="https://aomedia.googlesource.com/aom"
 VMAF_GIT="https://github.com/Netflix/vmaf.git"
 DAV1D_VERSION="1.5.3"
@@ -256,18 +277,35 @@ download_tarball() {
 #######################################
 # Helper : Cloner repo Git
 #######################################
+# Options de profondeur pour clone/fetch (vide = historique complet)
+git_depth_args() {
+    if [[ "$GIT_CLONE_DEPTH" -gt 0 ]]; then
+        echo "--depth $GIT_CLONE_DEPTH"
+    fi
+}
+
 clone_git_repo() {
     local url="$1"
     local dest_dir="$2"
-    local depth="${3:-1}"  # Shallow clone par défaut
+    local partial_dir="$dest_dir.partial"
 
     echo "  Cloning $(basename "$url" .git)..."
 
+    # Un clone interrompu laisse un dépôt sans HEAD : le supprimer plutôt que le réutiliser
+    if [[ -d "$dest_dir" ]] && ! git -C "$dest_dir" rev-parse --verify --quiet HEAD >/dev/null 2>&1; then
+        echo -e "  ${YELLOW}Incomplete clone in $dest_dir, cloning again${NC}"
+        rm -rf "$dest_dir"
+    fi
+
     if [[ -d "$dest_dir" ]]; then
         echo "  Directory already exists, pulling latest..."
-        git -C "$dest_dir" pull
+        # Échec possible hors branche (révision épinglée) : les sources existantes suffisent
+        git -C "$dest_dir" pull --ff-only || echo -e "  ${YELLOW}Pull failed, using existing sources${NC}"
     else
-        git clone --depth "$depth" "$url" "$dest_dir"
+        # Cloner à côté puis renommer : une interruption ne laisse jamais $dest_dir à moitié écrit
+        rm -rf "$partial_dir"
+        git clone $(git_depth_args) "$url" "$partial_dir"
+        mv "$partial_dir" "$dest_dir"
     fi
 }
 
@@ -415,7 +453,7 @@ install_vmaf() {
     fi
 
     # Cloner repo Git
-    clone_git_repo "$VMAF_GIT" "$vmaf_src" 1
+    clone_git_repo "$VMAF_GIT" "$vmaf_src"
 
     # Compiler avec Meson/Ninja (dans le sous-répertoire libvmaf)
     echo "  Configuring libvmaf..."
@@ -486,6 +524,14 @@ install_ffmpeg_linux() {
     # Rendre les libs compilées localement visibles par pkg-config et le linker
     export PKG_CONFIG_PATH="$DEPS_DIR/lib/pkgconfig:${PKG_CONFIG_PATH:-}"
 
+    # zscale (tone mapping HDR → SDR) si libzimg est installée sur le système
+    local optional_flags=()
+    if pkg-config --exists zimg 2>/dev/null; then
+        optional_flags+=(--enable-libzimg)
+    else
+        echo -e "${YELLOW}  ⚠ libzimg not found: zscale (HDR tone mapping) disabled${NC}"
+    fi
+
     # Ajouter -lstdc++ pour linker libvmaf qui contient du C++
     ./configure \
         --prefix="$DEPS_DIR" \
@@ -498,6 +544,7 @@ install_ffmpeg_linux() {
         --enable-libvpx \
         --enable-libdav1d \
         --enable-libvmaf \
+        "${optional_flags[@]}" \
         --disable-doc \
         --disable-htmlpages \
         --disable-manpages \
@@ -564,40 +611,80 @@ install_ffmpeg_windows() {
 }
 
 #######################################
-# Compilation SVT-AV1-PSY
+# Helper : Lire une clé du manifeste de build
+#######################################
+manifest_value() {
+    local key="$1"
+    local manifest="$DEPS_DIR/build-manifest.txt"
+    [[ -f "$manifest" ]] || return 0
+    grep "^${key}=" "$manifest" 2>/dev/null | cut -d= -f2- | head -1
+}
+
+#######################################
+# Helper : Enregistrer une clé dans le manifeste de build
+#######################################
+record_manifest() {
+    local key="$1"
+    local value="$2"
+    local manifest="$DEPS_DIR/build-manifest.txt"
+    touch "$manifest"
+    sed -i "/^${key}=/d" "$manifest"
+    echo "${key}=${value}" >> "$manifest"
+}
+
+#######################################
+# Compilation SVT-AV1 (fork PSY ou version officielle)
 #######################################
 install_svt_av1() {
-    echo -e "${YELLOW}=== Installing SVT-AV1-PSY ===${NC}"
+    local svt_git svt_label
+    case "$SVT_AV1_VARIANT" in
+        psy)      svt_git="$SVT_AV1_PSY_GIT"; svt_label="SVT-AV1-PSY";;
+        mainline) svt_git="$SVT_AV1_MAINLINE_GIT"; svt_label="SVT-AV1";;
+        *)
+            echo -e "${RED}✗ Unknown svt_av1_variant '$SVT_AV1_VARIANT' (expected psy or mainline)${NC}"
+            exit 1
+            ;;
+    esac
+
+    echo -e "${YELLOW}=== Installing $svt_label ===${NC}"
 
-    local svt_src="$DEPS_SRC/svt-av1-psy"
+    local svt_src="$DEPS_SRC/svt-av1-$SVT_AV1_VARIANT"
     local svt_build="$svt_src/Build"
 
-    # Vérifier si déjà installé
-    if [[ -x "$DEPS_BIN/SvtAv1EncApp" ]]; then
-        echo -e "${GREEN}✓ SVT-AV1-PSY already installed${NC}"
+    # Vérifier si déjà installé (recompiler si la variante ou la révision a changé)
+    if [[ -x "$DEPS_BIN/SvtAv1EncApp" ]] && \
+       [[ "$(manifest_value svt_av1_variant)" == "$SVT_AV1_VARIANT" ]] && \
+       [[ "$(manifest_value svt_av1_ref)" == "$SVT_AV1_REF" ]]; then
+        echo -e "${GREEN}✓ $svt_label already installed${NC}"
         return 0
     fi
 
-    # Cloner repo Git
-    clone_git_repo "$SVT_AV1_GIT" "$svt_src" 1
+    # Cloner repo Git (puis se placer sur le tag/commit demandé)
+    clone_git_repo "$svt_git" "$svt_src"
+    if [[ -n "$SVT_AV1_REF" ]]; then
+        echo "  Checking out $SVT_AV1_REF..."
+        git -C "$svt_src" fetch $(git_depth_args) origin "$SVT_AV1_REF"
+        git -C "$svt_src" checkout --detach FETCH_HEAD
+    fi
+    rm -rf "$svt_build"
 
     # Créer dossier build
     mkdir -p "$svt_build"
     cd "$svt_build"
 
     # Configurer avec CMake
-    echo "  Configuring SVT-AV1-PSY..."
+    echo "  Configuring $svt_label..."
     cmake .. \
         -DCMAKE_BUILD_TYPE=Release \
         -DCMAKE_INSTALL_PREFIX="$DEPS_DIR" \
         -DBUILD_SHARED_LIBS=OFF
 
     # Compiler
-    echo "  Building SVT-AV1-PSY with $NCPUS cores... (~15-30 minutes)"
+    echo "  Building $svt_label with $NCPUS cores... (~15-30 minutes)"
     make -j"$NCPUS"
 
     # Installer
-    echo "  Installing SVT-AV1-PSY..."
+    echo "  Installing $svt_label..."
     make install
 
     # Copier binaire si pas dans bin/ (parfois dans local/bin/)
@@ -607,10 +694,16 @@ install_svt_av1() {
 
     # Vérifier installation
     if [[ -x "$DEPS_BIN/SvtAv1EncApp" ]]; then
-        echo -e "${GREEN}✓ SVT-AV1-PSY compiled successfully${NC}"
+        echo -e "${GREEN}✓ $svt_label compiled successfully${NC}"
         "$DEPS_BIN/SvtAv1EncApp" --version 2>&1 | head -1 || true
+
+        # Manifeste de build : variante, révision demandée, commit et version compilés
+        record_manifest svt_av1_variant "$SVT_AV1_VARIANT"
+        record_manifest svt_av1_ref "$SVT_AV1_REF"
+        record_manifest svt_av1_commit "$(git -C "$svt_src" rev-parse HEAD)"
+        record_manifest svt_av1_version "$("$DEPS_BIN/SvtAv1EncApp" --version 2>&1 | head -1)"
     else
-        echo -e "${RED}✗ SVT-AV1-PSY compilation failed${NC}"
+        echo -e "${RED}✗ $svt_label compilation failed${NC}"
         exit 1
     fi
 }
@@ -631,7 +724,7 @@ install_aomenc() {
     fi
 
     # Cloner repo Git
-    clone_git_repo "$LIBAOM_GIT" "$aom_src" 1
+    clone_git_repo "$LIBAOM_GIT" "$aom_src"
 
     # Créer dossier build
     mkdir -p "$aom_build"
@@ -682,7 +775,7 @@ usage() {
     echo "  --opus            Install only libopus"
     echo "  --vpx             Install only libvpx"
     echo "  --ffmpeg          Install only FFmpeg (compile libopus/libvpx first si absents)"
-    echo "  --svt-av1         Install only SVT-AV1-PSY"
+    echo "  --svt-av1         Install only SVT-AV1 (variant from [deps] svt_av1_variant)"
     echo "  --dav1d            Install only libdav1d"
     echo "  --vmaf            Install only libvmaf"
     echo "  --aomenc          Install only libaom (aomenc)"
@@ -695,6 +788,11 @@ usage() {
     echo "  $0 --ffmpeg          # Install FFmpeg (+ libopus, libvpx)"
     echo "  $0 -j 4             # Install all with 4 threads"
     echo "  $0 --svt-av1 --aomenc # Install SVT-AV1 and libaom"
+    echo ""
+    echo "SVT-AV1 VARIANT (config.toml):"
+    echo "  [deps]"
+    echo "  svt_av1_variant = \"mainline\"   # \"psy\" (default) or \"mainline\""
+    echo "  svt_av1_ref = \"v2.3.0\"          # Optional tag or commit"
 }
 
 #######################################
@@ -797,6 +895,11 @@ main() {
     # Créer dossiers
     mkdir -p "$DEPS_BIN" "$DEPS_SRC"
 
+    # Marqueur de compilation en cours : s'il reste (script tué ou en échec), le daemon
+    # reprend la compilation des binaires manquants à son démarrage
+    local build_marker="$DEPS_DIR/.build-in-progress"
+    echo "$*" > "$build_marker"
+
     # Vérifier dépendances système
     if [[ "$skip_check" == false ]]; then
         check_system_dependencies
@@ -867,7 +970,7 @@ main() {
         if [[ "$PLATFORM" == "linux" ]]; then
             install_svt_av1
         else
-            echo -e "${YELLOW}⚠ SVT-AV1-PSY compilation not yet supported on $PLATFORM${NC}"
+            echo -e "${YELLOW}⚠ SVT-AV1 compilation not yet supported on $PLATFORM${NC}"
         fi
         echo ""
     fi
@@ -881,6 +984,8 @@ main() {
         echo ""
     fi
 
+    rm -f "$build_marker"
+
     local end_time=$(date +%s)
     local elapsed=$((end_time - start_time))
     local minutes=$((elapsed / 60))